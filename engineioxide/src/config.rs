@@ -30,7 +30,7 @@
 //! let svc = EngineIoService::with_config(Arc::new(MyHandler), config);
 //! ```
 
-use std::{borrow::Cow, time::Duration};
+use std::{borrow::Cow, sync::Arc, time::Duration};
 
 use crate::service::TransportType;
 
@@ -49,6 +49,17 @@ pub struct EngineIoConfig {
     /// Defaults to 20 seconds.
     pub ping_timeout: Duration,
 
+    /// The amount of time the server will wait for a websocket upgrade to complete.
+    /// If the upgrade is not completed in time, the websocket connection is dropped
+    /// and the client keeps using the polling transport.
+    /// Defaults to 10 seconds.
+    pub upgrade_timeout: Duration,
+
+    /// The maximum amount of time a polling request is held open while no packet is pending.
+    /// Once it is elapsed, the request is answered with a noop packet and the client sends a new one.
+    /// Defaults to `None`: the request is held until a packet is sent, at least the next ping.
+    pub polling_duration: Option<Duration>,
+
     /// The maximum number of packets that can be buffered per connection before being emitted to the client.
     ///
     /// If the buffer if full the `emit()` method will return an error
@@ -60,9 +71,203 @@ pub struct EngineIoConfig {
     /// Defaults to 100kb.
     pub max_payload: u64,
 
+    /// The maximum size in bytes of a single websocket frame received from a client.
+    /// The size is checked from the frame header, so an oversized frame is rejected before being buffered,
+    /// and the connection is closed with a protocol error close code.
+    /// Defaults to 16mb.
+    pub max_frame_size: usize,
+
+    /// The maximum number of connections the server accepts at the same time.
+    /// Once it is reached, the new handshakes are rejected with a `503 Service Unavailable` response.
+    /// Defaults to `None` (no limit).
+    pub max_connections: Option<usize>,
+
     /// Allowed transports on this server
     /// It is represented as a bitfield to allow to combine any number of transports easily
     pub transports: u8,
+
+    /// Coalescing of the packets written to the websocket transport, see [`WriteBatch`].
+    /// Defaults to `None`: the packets are written as soon as possible.
+    pub write_batch: Option<WriteBatch>,
+
+    /// A policy adapting the ping interval of each connection to its health, see [`HeartbeatPolicy`].
+    /// Defaults to `None`: the ping interval is fixed and a missed pong closes the connection.
+    pub heartbeat_policy: Option<HeartbeatPolicy>,
+
+    /// The cookie set on the polling handshake responses, see [`CookieConfig`].
+    /// Defaults to `None`: no cookie is set.
+    pub cookie: Option<CookieConfig>,
+
+    /// A callback called with the raw bytes of every frame sent or received, see the [`frames`](crate::frames) module.
+    /// Defaults to no callback.
+    #[cfg(feature = "debug-frames")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "debug-frames")))]
+    pub raw_packet_hook: crate::frames::RawPacketHook,
+}
+
+/// When set, the packets emitted to a websocket connection within a small time window
+/// are written and flushed together rather than one by one.
+///
+/// After the first packet of a batch is received, the transport keeps collecting the packets
+/// as long as they keep coming, until `window` is elapsed or until `max_packets` packets are collected,
+/// and then flushes them all at once. As soon as no other packet is ready the batch is flushed,
+/// so a packet emitted to an idle socket is not delayed.
+/// The packets are always written in the order they were emitted.
+///
+/// The polling transport already sends every buffered packet in a single payload
+/// so it is not affected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WriteBatch {
+    /// The maximum amount of time a batch keeps collecting packets before being flushed.
+    pub window: Duration,
+    /// The maximum number of packets in a batch. When reached, the batch is flushed immediately.
+    pub max_packets: usize,
+}
+
+/// A cookie set on the polling handshake responses with the session id as value,
+/// so that the load balancers doing sticky sessions route the following requests of a session to the same server.
+///
+/// Only the polling transport needs session affinity: a websocket connection stays on the server it was opened on.
+///
+/// #### Example
+/// ```
+/// # use engineioxide::config::{CookieConfig, EngineIoConfig, SameSite};
+/// let config = EngineIoConfig::builder()
+///     .cookie(CookieConfig {
+///         name: "affinity".into(),
+///         same_site: SameSite::Strict,
+///         ..Default::default()
+///     })
+///     .build();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CookieConfig {
+    /// The name of the cookie. Defaults to "io".
+    pub name: Cow<'static, str>,
+    /// The `Path` attribute of the cookie. Defaults to "/".
+    pub path: Cow<'static, str>,
+    /// The `SameSite` attribute of the cookie. Defaults to [`SameSite::Lax`].
+    pub same_site: SameSite,
+    /// Whether the cookie is hidden from the javascript of the page with the `HttpOnly` attribute.
+    /// Defaults to true.
+    pub http_only: bool,
+}
+
+/// The `SameSite` attribute of a [`CookieConfig`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SameSite {
+    /// The cookie is only sent to the site that set it.
+    Strict,
+    /// The cookie is also sent when navigating to the site from another one.
+    Lax,
+    /// The cookie is sent with the cross-site requests.
+    /// Browsers require the `Secure` attribute with it, so it is added to the cookie.
+    None,
+}
+
+impl Default for CookieConfig {
+    fn default() -> Self {
+        Self {
+            name: "io".into(),
+            path: "/".into(),
+            same_site: SameSite::Lax,
+            http_only: true,
+        }
+    }
+}
+
+impl CookieConfig {
+    /// The name must be a non empty token: no control characters, spaces or separators.
+    fn is_valid_name(&self) -> bool {
+        const SEPARATORS: &[u8] = b"()<>@,;:\\\"/[]?={} \t";
+        !self.name.is_empty()
+            && self
+                .name
+                .bytes()
+                .all(|b| b.is_ascii_graphic() && !SEPARATORS.contains(&b))
+    }
+
+    /// The path can be any printable ascii characters except `;`.
+    fn is_valid_path(&self) -> bool {
+        self.path
+            .bytes()
+            .all(|b| (b' '..=b'~').contains(&b) && b != b';')
+    }
+
+    /// Formats the `Set-Cookie` header value for the given session id.
+    pub(crate) fn header_value(&self, sid: impl std::fmt::Display) -> String {
+        let same_site = match self.same_site {
+            SameSite::Strict => "Strict",
+            SameSite::Lax => "Lax",
+            SameSite::None => "None; Secure",
+        };
+        let http_only = if self.http_only { "; HttpOnly" } else { "" };
+        format!(
+            "{}={}; Path={}; SameSite={}{}",
+            self.name, sid, self.path, same_site, http_only
+        )
+    }
+}
+
+/// The health of the heartbeat of a connection, given to the [`HeartbeatPolicy`] after each ping.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HeartbeatStatus {
+    /// The round-trip time of the last ping,
+    /// or `None` if its pong was not received within the ping timeout.
+    pub rtt: Option<Duration>,
+    /// The number of consecutive pings without a pong, 0 if the last pong was received.
+    pub missed_pongs: u32,
+    /// The current ping interval of the connection.
+    pub interval: Duration,
+}
+
+/// A callback called after each ping of the server with the [`HeartbeatStatus`] of the connection,
+/// returning the interval before the next ping, or `None` to close the connection.
+///
+/// With a policy, a missed pong doesn't close the connection by itself: it is reported to the policy
+/// which can, for example, ping an unstable connection more often and close it after a few missed pongs,
+/// and ping a stable connection less often to reduce the overhead.
+///
+/// Clients only accept pings within the `pingInterval + pingTimeout` sent in the handshake,
+/// so the intervals returned should not be longer than the configured [`EngineIoConfig::ping_interval`].
+/// The intervals shorter than 1 millisecond are raised to 1 millisecond.
+/// Only the engine.io v4 protocol is supported, with v3 the clients send the pings.
+///
+/// #### Example
+/// ```
+/// # use engineioxide::config::{EngineIoConfig, HeartbeatPolicy};
+/// # use std::time::Duration;
+/// let policy = HeartbeatPolicy::new(|status| match status.missed_pongs {
+///     0 => Some(Duration::from_secs(25)),
+///     1 | 2 => Some(Duration::from_secs(5)),
+///     _ => None,
+/// });
+/// let config = EngineIoConfig::builder().heartbeat_policy(policy).build();
+/// ```
+#[derive(Clone)]
+pub struct HeartbeatPolicy(Arc<dyn Fn(HeartbeatStatus) -> Option<Duration> + Send + Sync>);
+
+impl HeartbeatPolicy {
+    /// Creates a policy from a callback returning the next ping interval, or `None` to close the connection.
+    pub fn new<F>(policy: F) -> Self
+    where
+        F: Fn(HeartbeatStatus) -> Option<Duration> + Send + Sync + 'static,
+    {
+        Self(Arc::new(policy))
+    }
+
+    /// The shortest interval between two pings
+    const MIN_INTERVAL: Duration = Duration::from_millis(1);
+
+    pub(crate) fn next_interval(&self, status: HeartbeatStatus) -> Option<Duration> {
+        (self.0)(status).map(|interval| interval.max(Self::MIN_INTERVAL))
+    }
+}
+
+impl std::fmt::Debug for HeartbeatPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("HeartbeatPolicy").finish()
+    }
 }
 
 impl Default for EngineIoConfig {
@@ -71,9 +276,18 @@ impl Default for EngineIoConfig {
             req_path: "/engine.io".into(),
             ping_interval: Duration::from_millis(25000),
             ping_timeout: Duration::from_millis(20000),
+            upgrade_timeout: Duration::from_millis(10000),
+            polling_duration: None,
             max_buffer_size: 128,
-            max_payload: 1e5 as u64, // 100kb
+            max_payload: 1e5 as u64,  // 100kb
+            max_frame_size: 16 << 20, // 16mb
+            max_connections: None,
             transports: TransportType::Polling as u8 | TransportType::Websocket as u8,
+            write_batch: None,
+            heartbeat_policy: None,
+            cookie: None,
+            #[cfg(feature = "debug-frames")]
+            raw_packet_hook: Default::default(),
         }
     }
 }
@@ -125,6 +339,43 @@ impl EngineIoConfigBuilder {
         self
     }
 
+    /// The amount of time the server will wait for a websocket upgrade to complete.
+    /// If the upgrade is not completed in time, the websocket connection is dropped
+    /// and the client keeps using the polling transport.
+    /// Defaults to 10 seconds.
+    pub fn upgrade_timeout(mut self, upgrade_timeout: Duration) -> Self {
+        self.config.upgrade_timeout = upgrade_timeout;
+        self
+    }
+
+    /// The maximum amount of time a polling request is held open while no packet is pending,
+    /// before being answered with a noop packet.
+    ///
+    /// A shorter duration lets the proxies closing idle requests keep the long-polling connections alive,
+    /// at the cost of more requests. The reuse of the underlying tcp connections between the requests
+    /// is handled by the http server.
+    /// Defaults to no limit: the request is held until a packet is sent, at least the next ping.
+    pub fn polling_duration(mut self, polling_duration: Duration) -> Self {
+        self.config.polling_duration = Some(polling_duration);
+        self
+    }
+
+    /// Coalesces the packets written to the websocket transport:
+    /// the packets emitted in a burst are flushed together, for at most `window` and up to `max_packets` packets per batch.
+    ///
+    /// See [`WriteBatch`] for more details.
+    ///
+    /// # Panics
+    /// If `max_packets` is 0.
+    pub fn write_batch(mut self, window: Duration, max_packets: usize) -> Self {
+        assert!(max_packets > 0, "max_packets must be greater than 0");
+        self.config.write_batch = Some(WriteBatch {
+            window,
+            max_packets,
+        });
+        self
+    }
+
     /// The maximum number of packets that can be buffered per connection before being emitted to the client.
     ///
     /// If the buffer if full the `emit()` method will return an error
@@ -173,6 +424,59 @@ impl EngineIoConfigBuilder {
         self
     }
 
+    /// The maximum size in bytes of a single websocket frame received from a client.
+    ///
+    /// The size is checked as soon as the frame header is read, so an oversized frame is rejected
+    /// before being buffered and the connection is closed with a protocol error close code (1002).
+    /// It complements the [`max_payload`](#method.max_payload) limit of the polling requests.
+    /// Defaults to 16mb.
+    pub fn max_frame_size(mut self, max_frame_size: usize) -> Self {
+        self.config.max_frame_size = max_frame_size;
+        self
+    }
+
+    /// Adapts the ping interval of each connection to its health with the given [`HeartbeatPolicy`],
+    /// called after each ping. A missed pong is reported to the policy instead of closing the connection.
+    /// Defaults to a fixed ping interval.
+    pub fn heartbeat_policy(mut self, policy: HeartbeatPolicy) -> Self {
+        self.config.heartbeat_policy = Some(policy);
+        self
+    }
+
+    /// Sets a cookie with the session id on the polling handshake responses, for the load balancers
+    /// doing sticky sessions. See [`CookieConfig`] for more details.
+    /// Defaults to no cookie.
+    ///
+    /// # Panics
+    /// If the cookie name is not a valid [RFC 6265](https://datatracker.ietf.org/doc/html/rfc6265#section-4.1.1) token,
+    /// or if the path contains control characters or a `;`.
+    pub fn cookie(mut self, cookie: CookieConfig) -> Self {
+        assert!(
+            cookie.is_valid_name(),
+            "invalid cookie name: {:?}",
+            cookie.name
+        );
+        assert!(
+            cookie.is_valid_path(),
+            "invalid cookie path: {:?}",
+            cookie.path
+        );
+        self.config.cookie = Some(cookie);
+        self
+    }
+
+    /// The maximum number of connections the server accepts at the same time.
+    ///
+    /// Once it is reached, the new handshakes are rejected with a `503 Service Unavailable` response
+    /// with a `Retry-After` header, so that load balancers can route the clients to other servers.
+    /// The websocket connections going over the limit during their upgrade are closed.
+    /// The connections upgraded from polling to websocket are not counted twice.
+    /// Defaults to no limit.
+    pub fn max_connections(mut self, max_connections: usize) -> Self {
+        self.config.max_connections = Some(max_connections);
+        self
+    }
+
     /// Allowed transports on this server
     ///
     /// The `transports` array should have a size of 1 or 2
@@ -222,4 +526,42 @@ mod tests {
         assert!(conf.allowed_transport(TransportType::Polling));
         assert!(conf.allowed_transport(TransportType::Websocket));
     }
+
+    #[test]
+    pub fn config_cookie() {
+        let cookie = CookieConfig {
+            name: "my-affinity_1".into(),
+            path: "/socket.io/".into(),
+            ..Default::default()
+        };
+        let conf = EngineIoConfig::builder().cookie(cookie.clone()).build();
+        assert_eq!(conf.cookie, Some(cookie));
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid cookie name")]
+    pub fn config_cookie_invalid_name() {
+        EngineIoConfig::builder().cookie(CookieConfig {
+            name: "io; Path=/admin".into(),
+            ..Default::default()
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid cookie name")]
+    pub fn config_cookie_empty_name() {
+        EngineIoConfig::builder().cookie(CookieConfig {
+            name: "".into(),
+            ..Default::default()
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid cookie path")]
+    pub fn config_cookie_invalid_path() {
+        EngineIoConfig::builder().cookie(CookieConfig {
+            path: "/\r\nSet-Cookie: a=b".into(),
+            ..Default::default()
+        });
+    }
 }
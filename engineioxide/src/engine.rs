@@ -40,13 +40,15 @@ impl<H: EngineIoHandler> EngineIo<H> {
 
 impl<H: EngineIoHandler> EngineIo<H> {
     /// Create a new engine.io session and a new socket and add it to the socket map
+    ///
+    /// Returns `None` if the [`EngineIoConfig::max_connections`] limit is reached
     pub(crate) fn create_session(
         self: &Arc<Self>,
         protocol: ProtocolVersion,
         transport: TransportType,
         req: Parts,
         #[cfg(feature = "v3")] supports_binary: bool,
-    ) -> Arc<Socket<H::Data>> {
+    ) -> Option<Arc<Socket<H::Data>>> {
         let engine = self.clone();
         let close_fn = Box::new(move |sid, reason| engine.close_session(sid, reason));
 
@@ -60,12 +62,30 @@ impl<H: EngineIoHandler> EngineIo<H> {
             supports_binary,
         );
         let socket = Arc::new(socket);
-        self.sockets
-            .write()
-            .unwrap()
-            .insert(socket.id, socket.clone());
+        {
+            // The limit is checked under the same lock as the insertion
+            // so that concurrent handshakes cannot go over it
+            let mut sockets = self.sockets.write().unwrap();
+            if self
+                .config
+                .max_connections
+                .is_some_and(|max| sockets.len() >= max)
+            {
+                return None;
+            }
+            sockets.insert(socket.id, socket.clone());
+        }
         self.handler.clone().on_connect(socket.clone());
-        socket
+        Some(socket)
+    }
+
+    /// Returns true if the [`EngineIoConfig::max_connections`] limit is reached
+    ///
+    /// It is only used to reject the handshakes early, the limit is enforced by [`EngineIo::create_session`]
+    pub(crate) fn is_full(&self) -> bool {
+        self.config
+            .max_connections
+            .is_some_and(|max| self.sockets.read().unwrap().len() >= max)
     }
 
     /// Get a socket by its sid
@@ -134,13 +154,15 @@ mod tests {
     #[tokio::test]
     async fn create_session() {
         let engine = create_engine();
-        let socket = engine.create_session(
-            ProtocolVersion::V4,
-            TransportType::Polling,
-            Request::<()>::default().into_parts().0,
-            #[cfg(feature = "v3")]
-            true,
-        );
+        let socket = engine
+            .create_session(
+                ProtocolVersion::V4,
+                TransportType::Polling,
+                Request::<()>::default().into_parts().0,
+                #[cfg(feature = "v3")]
+                true,
+            )
+            .unwrap();
         assert_eq!(engine.sockets.read().unwrap().len(), 1);
         assert_eq!(socket.protocol, ProtocolVersion::V4);
         assert!(socket.is_http());
@@ -149,13 +171,15 @@ mod tests {
     #[tokio::test]
     async fn close_session() {
         let engine = create_engine();
-        let socket = engine.create_session(
-            ProtocolVersion::V4,
-            TransportType::Polling,
-            Request::<()>::default().into_parts().0,
-            #[cfg(feature = "v3")]
-            true,
-        );
+        let socket = engine
+            .create_session(
+                ProtocolVersion::V4,
+                TransportType::Polling,
+                Request::<()>::default().into_parts().0,
+                #[cfg(feature = "v3")]
+                true,
+            )
+            .unwrap();
         assert_eq!(engine.sockets.read().unwrap().len(), 1);
         engine.close_session(socket.id, DisconnectReason::TransportClose);
         assert_eq!(engine.sockets.read().unwrap().len(), 0);
@@ -164,16 +188,39 @@ mod tests {
     #[tokio::test]
     async fn get_socket() {
         let engine = create_engine();
-        let socket = engine.create_session(
-            ProtocolVersion::V4,
-            TransportType::Polling,
-            Request::<()>::default().into_parts().0,
-            #[cfg(feature = "v3")]
-            true,
-        );
+        let socket = engine
+            .create_session(
+                ProtocolVersion::V4,
+                TransportType::Polling,
+                Request::<()>::default().into_parts().0,
+                #[cfg(feature = "v3")]
+                true,
+            )
+            .unwrap();
         assert_eq!(engine.sockets.read().unwrap().len(), 1);
         let socket = engine.get_socket(socket.id).unwrap();
         assert_eq!(socket.protocol, ProtocolVersion::V4);
         assert!(socket.is_http());
     }
+
+    #[tokio::test]
+    async fn create_session_max_connections() {
+        let config = EngineIoConfig::builder().max_connections(1).build();
+        let engine = Arc::new(EngineIo::new(Arc::new(MockHandler), config));
+        let create = || {
+            engine.create_session(
+                ProtocolVersion::V4,
+                TransportType::Polling,
+                Request::<()>::default().into_parts().0,
+                #[cfg(feature = "v3")]
+                true,
+            )
+        };
+        let socket = create().unwrap();
+        assert!(create().is_none());
+        assert_eq!(engine.sockets.read().unwrap().len(), 1);
+
+        engine.close_session(socket.id, DisconnectReason::TransportClose);
+        assert!(create().is_some());
+    }
 }
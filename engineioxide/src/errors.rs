@@ -42,6 +42,8 @@ pub enum Error {
     TransportMismatch,
     #[error("payload too large")]
     PayloadTooLarge,
+    #[error("too many connections")]
+    TooManyConnections,
 
     #[error("Invalid packet length")]
     InvalidPacketLength,
@@ -76,6 +78,11 @@ impl<B> From<Error> for Response<ResponseBody<B>> {
                 .status(413)
                 .body(ResponseBody::empty_response())
                 .unwrap(),
+            Error::TooManyConnections => Response::builder()
+                .status(StatusCode::SERVICE_UNAVAILABLE)
+                .header("Retry-After", "1")
+                .body(ResponseBody::empty_response())
+                .unwrap(),
 
             Error::UnknownSessionID(_) => {
                 conn_err_resp("{\"code\":\"1\",\"message\":\"Session ID unknown\"}")
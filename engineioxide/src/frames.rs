@@ -0,0 +1,143 @@
+//! ## Inspection of the raw engine.io frames, enabled with the `debug-frames` feature
+//!
+//! A [`RawPacketHook`] is called with the exact bytes of every frame sent or received
+//! by the websocket and polling transports, which is useful to debug interop issues with other clients:
+//! * For the websocket transport, each websocket message is a frame.
+//! * For the polling transport, each response payload is a frame
+//!   and the request bodies are reported chunk by chunk, as they are read.
+//!
+//! The custom [`Transport`](crate::Transport)s exchange [`Packet`](crate::Packet)s rather than bytes
+//! so their packets are not reported.
+//!
+//! The hook is shared by all the clones of the [`EngineIoConfig`](crate::config::EngineIoConfig)
+//! it belongs to, so it can be set after the service is created.
+//!
+//! #### Example :
+//! ```rust
+//! # use engineioxide::config::EngineIoConfig;
+//! let config = EngineIoConfig::default();
+//! config.raw_packet_hook.set(|sid, direction, data| {
+//!     println!("[{sid}] {direction:?}: {:?}", String::from_utf8_lossy(data));
+//! });
+//! ```
+use std::{
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, RwLock,
+    },
+    task::{Context, Poll},
+};
+
+use bytes::{Buf, Bytes};
+use http_body::{Body, Frame, SizeHint};
+use pin_project_lite::pin_project;
+
+use crate::sid::Sid;
+
+/// The direction of a raw frame reported to a [`RawPacketHook`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// The frame was received from the client.
+    Incoming,
+    /// The frame was sent to the client.
+    Outgoing,
+}
+
+type BoxedRawPacketHook = Box<dyn Fn(Sid, Direction, &[u8]) + Send + Sync + 'static>;
+
+#[derive(Default)]
+struct Inner {
+    enabled: AtomicBool,
+    hook: RwLock<Option<BoxedRawPacketHook>>,
+}
+
+/// A shared slot for a callback called with the raw bytes of every engine.io frame.
+/// See the [`frames`](self) module doc for more details.
+#[derive(Clone, Default)]
+pub struct RawPacketHook(Arc<Inner>);
+
+impl RawPacketHook {
+    /// Sets the callback called for every frame with the session id, the frame direction and its raw bytes.
+    /// It replaces the previous callback, if any.
+    ///
+    /// The callback is called on the transport tasks, so it should not block.
+    pub fn set<F>(&self, hook: F)
+    where
+        F: Fn(Sid, Direction, &[u8]) + Send + Sync + 'static,
+    {
+        self.0.hook.write().unwrap().replace(Box::new(hook));
+        self.0.enabled.store(true, Ordering::Release);
+    }
+
+    /// Removes the callback.
+    pub fn clear(&self) {
+        self.0.enabled.store(false, Ordering::Release);
+        self.0.hook.write().unwrap().take();
+    }
+
+    /// Calls the callback, if any.
+    #[inline]
+    pub(crate) fn call(&self, sid: Sid, direction: Direction, data: &[u8]) {
+        if !self.0.enabled.load(Ordering::Acquire) {
+            return;
+        }
+        if let Some(hook) = self.0.hook.read().unwrap().as_ref() {
+            hook(sid, direction, data);
+        }
+    }
+}
+
+impl std::fmt::Debug for RawPacketHook {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RawPacketHook")
+            .field("enabled", &self.0.enabled.load(Ordering::Relaxed))
+            .finish()
+    }
+}
+
+pin_project! {
+    /// A request body wrapper reporting its data chunks to a [`RawPacketHook`] as they are read.
+    pub(crate) struct InspectBody<B> {
+        #[pin]
+        body: B,
+        sid: Sid,
+        hook: RawPacketHook,
+    }
+}
+impl<B> InspectBody<B> {
+    pub fn new(body: B, sid: Sid, hook: RawPacketHook) -> Self {
+        Self { body, sid, hook }
+    }
+}
+
+impl<B: Body> Body for InspectBody<B> {
+    type Data = Bytes;
+    type Error = B::Error;
+
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        let this = self.project();
+        let frame = match this.body.poll_frame(cx) {
+            Poll::Ready(Some(Ok(frame))) => frame,
+            Poll::Ready(Some(Err(e))) => return Poll::Ready(Some(Err(e))),
+            Poll::Ready(None) => return Poll::Ready(None),
+            Poll::Pending => return Poll::Pending,
+        };
+        let frame = frame.map_data(|mut data| data.copy_to_bytes(data.remaining()));
+        if let Some(data) = frame.data_ref() {
+            this.hook.call(*this.sid, Direction::Incoming, data);
+        }
+        Poll::Ready(Some(Ok(frame)))
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.body.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.body.size_hint()
+    }
+}
@@ -35,11 +35,14 @@
 pub use crate::str::Str;
 pub use service::{ProtocolVersion, TransportType};
 pub use socket::{DisconnectReason, Socket};
+pub use transport::{memory, Transport};
 
-#[cfg(any(test, socketioxide_test))]
-pub use packet::*;
+pub use packet::{OpenPacket, Packet};
 
 pub mod config;
+#[cfg(feature = "debug-frames")]
+#[cfg_attr(docsrs, doc(cfg(feature = "debug-frames")))]
+pub mod frames;
 pub mod handler;
 pub mod layer;
 pub mod service;
@@ -1,18 +1,37 @@
-use tokio::sync::mpsc::{error::TryRecvError, Receiver};
+use std::sync::Arc;
+
+use tokio::sync::{
+    mpsc::{error::TryRecvError, Receiver},
+    watch,
+};
 
 /// Peekable receiver for polling transport
 /// It is a thin wrapper around a [`Receiver`](tokio::sync::mpsc::Receiver) that allows to peek the next packet without consuming it
 ///
 /// Its main goal is to be able to peek the next packet without consuming it to calculate the
 /// packet length when using polling transport to check if it fits according to the max_payload setting
+///
+/// It also counts the received items so that the transport can notify with [`mark_flushed`](Self::mark_flushed)
+/// how many of them were flushed.
 #[derive(Debug)]
 pub struct PeekableReceiver<T> {
     rx: Receiver<T>,
     next: Option<T>,
+    received: u64,
+    flushed: Arc<watch::Sender<u64>>,
 }
 impl<T> PeekableReceiver<T> {
+    #[cfg(test)]
     pub fn new(rx: Receiver<T>) -> Self {
-        Self { rx, next: None }
+        Self::with_flushed(rx, Arc::new(watch::channel(0).0))
+    }
+    pub fn with_flushed(rx: Receiver<T>, flushed: Arc<watch::Sender<u64>>) -> Self {
+        Self {
+            rx,
+            next: None,
+            received: 0,
+            flushed,
+        }
     }
     pub fn peek(&mut self) -> Option<&T> {
         if self.next.is_none() {
@@ -21,18 +40,34 @@ impl<T> PeekableReceiver<T> {
         self.next.as_ref()
     }
     pub async fn recv(&mut self) -> Option<T> {
-        if self.next.is_none() {
+        let item = if self.next.is_none() {
             self.rx.recv().await
         } else {
             self.next.take()
+        };
+        self.received += item.is_some() as u64;
+        item
+    }
+    /// Waits for the next item without consuming it.
+    /// It is cancel safe: the item stays available for the next [`recv`](Self::recv) or [`peek`](Self::peek).
+    pub async fn wait(&mut self) {
+        if self.next.is_none() {
+            self.next = self.rx.recv().await;
         }
     }
     pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
-        if self.next.is_none() {
+        let item = if self.next.is_none() {
             self.rx.try_recv()
         } else {
             Ok(self.next.take().unwrap())
-        }
+        };
+        self.received += item.is_ok() as u64;
+        item
+    }
+
+    /// Notifies that all the received items were flushed by the transport.
+    pub fn mark_flushed(&self) {
+        self.flushed.send_replace(self.received);
     }
 
     pub fn close(&mut self) {
@@ -76,4 +111,29 @@ mod tests {
         assert_eq!(rx.recv().await, Some(Packet::Close));
         assert!(rx.peek().is_none());
     }
+
+    #[tokio::test]
+    async fn mark_flushed() {
+        use super::PeekableReceiver;
+        use crate::packet::Packet;
+        use std::sync::Arc;
+        use tokio::sync::{mpsc::channel, watch};
+
+        let (tx, rx) = channel(2);
+        let flushed = Arc::new(watch::channel(0).0);
+        let mut rx = PeekableReceiver::with_flushed(rx, flushed.clone());
+
+        tx.send(Packet::Ping).await.unwrap();
+        tx.send(Packet::Pong).await.unwrap();
+        // Peeked items are not received yet
+        assert_eq!(rx.peek(), Some(&Packet::Ping));
+        rx.mark_flushed();
+        assert_eq!(*flushed.borrow(), 0);
+
+        assert_eq!(rx.recv().await, Some(Packet::Ping));
+        assert_eq!(rx.try_recv(), Ok(Packet::Pong));
+        assert!(rx.try_recv().is_err());
+        rx.mark_flushed();
+        assert_eq!(*flushed.borrow(), 2);
+    }
 }
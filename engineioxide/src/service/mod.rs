@@ -42,6 +42,7 @@ use tower::Service as TowerSvc;
 
 use crate::{
     body::ResponseBody, config::EngineIoConfig, engine::EngineIo, handler::EngineIoHandler,
+    sid::Sid,
 };
 
 mod futures;
@@ -86,6 +87,16 @@ impl<S: Clone, H: EngineIoHandler> EngineIoService<H, S> {
         }
     }
 
+    /// Connects a client through a custom [`Transport`](crate::Transport),
+    /// e.g. an in-memory [`MemoryTransport`](crate::memory::MemoryTransport), and returns the id of its session.
+    /// The request is used as the request of the session (headers, uri, extensions...).
+    ///
+    /// Returns `None` if the [`EngineIoConfig::max_connections`] limit is reached.
+    pub fn connect<T: crate::Transport, B>(&self, transport: T, req: Request<B>) -> Option<Sid> {
+        let (parts, _) = req.into_parts();
+        crate::transport::connect(self.engine.clone(), transport, parts)
+    }
+
     /// Convert this [`EngineIoService`] into a [`MakeEngineIoService`].
     /// This is useful when using [`EngineIoService`] without layers.
     pub fn into_make_service(self) -> MakeEngineIoService<H, S> {
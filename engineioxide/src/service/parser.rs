@@ -29,6 +29,12 @@ where
     F: Future,
 {
     match RequestInfo::parse(&req, &engine.config) {
+        // New handshakes are rejected when the server is at capacity
+        Ok(RequestInfo { sid: None, .. }) if engine.is_full() => {
+            #[cfg(feature = "tracing")]
+            tracing::debug!("max connections reached, rejecting handshake");
+            ResponseFuture::ready(Err(crate::errors::Error::TooManyConnections))
+        }
         Ok(RequestInfo {
             protocol,
             sid: None,
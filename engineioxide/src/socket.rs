@@ -56,7 +56,7 @@
 //! ```
 use std::{
     sync::{
-        atomic::{AtomicU8, Ordering},
+        atomic::{AtomicU64, AtomicU8, Ordering},
         Arc,
     },
     time::Duration,
@@ -68,16 +68,23 @@ use smallvec::{smallvec, SmallVec};
 use tokio::{
     sync::{
         mpsc::{self},
-        mpsc::{error::TrySendError, Receiver},
-        Mutex,
+        mpsc::{
+            error::{SendError, TrySendError},
+            Receiver,
+        },
+        watch, Mutex,
     },
     task::JoinHandle,
 };
 use tokio_tungstenite::tungstenite;
 
 use crate::{
-    config::EngineIoConfig, errors::Error, packet::Packet, peekable::PeekableReceiver,
-    service::ProtocolVersion, Str,
+    config::{EngineIoConfig, HeartbeatPolicy, HeartbeatStatus},
+    errors::Error,
+    packet::Packet,
+    peekable::PeekableReceiver,
+    service::ProtocolVersion,
+    Str,
 };
 use crate::{service::TransportType, sid::Sid};
 
@@ -122,17 +129,18 @@ impl From<&Error> for Option<DisconnectReason> {
 /// A permit holds a place in the internal channel to send one packet to the client.
 pub struct Permit<'a> {
     inner: mpsc::Permit<'a, PacketBuf>,
+    queued: &'a AtomicU64,
 }
 impl Permit<'_> {
     /// Consume the permit and emit a message to the client.
     #[inline]
     pub fn emit(self, msg: String) {
-        self.inner.send(smallvec![Packet::Message(msg.into())]);
+        self.send(smallvec![Packet::Message(msg.into())]);
     }
     /// Consume the permit and emit a binary message to the client.
     #[inline]
     pub fn emit_binary(self, data: Bytes) {
-        self.inner.send(smallvec![Packet::Binary(data)]);
+        self.send(smallvec![Packet::Binary(data)]);
     }
 
     /// Consume the permit and emit a message with multiple binary data to the client.
@@ -144,6 +152,26 @@ impl Permit<'_> {
         for d in data {
             packets.push(Packet::Binary(d));
         }
+        self.send(packets);
+    }
+
+    /// Consume the permit and emit several messages, each one followed by its binary data, to the client.
+    ///
+    /// The messages are queued as a single item of the internal chan, so no other message
+    /// can be interleaved between them.
+    pub fn emit_batch(self, msgs: Vec<(String, Vec<Bytes>)>) {
+        let len = msgs.iter().map(|(_, data)| data.len() + 1).sum();
+        let mut packets = SmallVec::with_capacity(len);
+        for (msg, data) in msgs {
+            packets.push(Packet::Message(msg.into()));
+            packets.extend(data.into_iter().map(Packet::Binary));
+        }
+        self.send(packets);
+    }
+
+    #[inline]
+    fn send(self, packets: PacketBuf) {
+        self.queued.fetch_add(1, Ordering::SeqCst);
         self.inner.send(packets);
     }
 }
@@ -192,6 +220,11 @@ where
 
     /// Channel to send [PacketBuf] to the internal connection
     internal_tx: mpsc::Sender<PacketBuf>,
+    /// The number of [`PacketBuf`] queued in the internal channel since the creation of the socket
+    queued: AtomicU64,
+    /// The number of [`PacketBuf`] flushed by the transport since the creation of the socket,
+    /// updated by the [`PeekableReceiver`] of the internal channel
+    flushed: Arc<watch::Sender<u64>>,
 
     /// Internal channel to receive Pong [`Packets`](Packet) (v4 protocol) or Ping (v3 protocol) in the heartbeat job
     /// which is running in a separate task
@@ -229,14 +262,17 @@ where
     ) -> Self {
         let (internal_tx, internal_rx) = mpsc::channel(config.max_buffer_size);
         let (heartbeat_tx, heartbeat_rx) = mpsc::channel(1);
+        let flushed = Arc::new(watch::channel(0).0);
 
         Self {
             id: Sid::new(),
             protocol,
             transport: AtomicU8::new(transport as u8),
 
-            internal_rx: Mutex::new(PeekableReceiver::new(internal_rx)),
+            internal_rx: Mutex::new(PeekableReceiver::with_flushed(internal_rx, flushed.clone())),
             internal_tx,
+            queued: AtomicU64::new(0),
+            flushed,
 
             heartbeat_rx: Mutex::new(heartbeat_rx),
             heartbeat_tx,
@@ -262,23 +298,28 @@ where
     pub(crate) fn send(&self, packet: Packet) -> Result<(), TrySendError<Packet>> {
         #[cfg(feature = "tracing")]
         tracing::debug!("[sid={}] sending packet: {:?}", self.id, packet);
-        self.internal_tx
-            .try_send(smallvec![packet])
-            .map_err(|p| match p {
-                TrySendError::Full(mut p) => TrySendError::Full(p.pop().unwrap()),
-                TrySendError::Closed(mut p) => TrySendError::Closed(p.pop().unwrap()),
-            })?;
+        let permit = match self.reserve() {
+            Ok(permit) => permit,
+            Err(TrySendError::Full(())) => return Err(TrySendError::Full(packet)),
+            Err(TrySendError::Closed(())) => return Err(TrySendError::Closed(packet)),
+        };
+        permit.send(smallvec![packet]);
         Ok(())
     }
 
     /// Spawn the heartbeat job
     ///
     /// Keep a handle to the job so that it can be aborted when the socket is closed
-    pub(crate) fn spawn_heartbeat(self: Arc<Self>, interval: Duration, timeout: Duration) {
+    pub(crate) fn spawn_heartbeat(
+        self: Arc<Self>,
+        interval: Duration,
+        timeout: Duration,
+        policy: Option<HeartbeatPolicy>,
+    ) {
         let socket = self.clone();
 
         let handle = tokio::spawn(async move {
-            if let Err(_e) = socket.heartbeat_job(interval, timeout).await {
+            if let Err(_e) = socket.heartbeat_job(interval, timeout, policy).await {
                 socket.close(DisconnectReason::HeartbeatTimeout);
                 #[cfg(feature = "tracing")]
                 tracing::debug!("[sid={}] heartbeat error: {:?}", socket.id, _e);
@@ -294,10 +335,15 @@ where
     ///
     /// If the client or server does not respond within the timeout, the connection is closed.
     #[cfg(feature = "v3")]
-    async fn heartbeat_job(&self, interval: Duration, timeout: Duration) -> Result<(), Error> {
+    async fn heartbeat_job(
+        &self,
+        interval: Duration,
+        timeout: Duration,
+        policy: Option<HeartbeatPolicy>,
+    ) -> Result<(), Error> {
         match self.protocol {
             ProtocolVersion::V3 => self.heartbeat_job_v3(interval, timeout).await,
-            ProtocolVersion::V4 => self.heartbeat_job_v4(interval, timeout).await,
+            ProtocolVersion::V4 => self.heartbeat_job_v4(interval, timeout, policy).await,
         }
     }
 
@@ -305,14 +351,25 @@ where
     ///
     /// If the client does not respond within the timeout, the connection is closed.
     #[cfg(not(feature = "v3"))]
-    async fn heartbeat_job(&self, interval: Duration, timeout: Duration) -> Result<(), Error> {
-        self.heartbeat_job_v4(interval, timeout).await
+    async fn heartbeat_job(
+        &self,
+        interval: Duration,
+        timeout: Duration,
+        policy: Option<HeartbeatPolicy>,
+    ) -> Result<(), Error> {
+        self.heartbeat_job_v4(interval, timeout, policy).await
     }
 
     /// Heartbeat is sent every `interval` milliseconds and the client is expected to respond within `timeout` milliseconds.
     ///
     /// If the client does not respond within the timeout, the connection is closed.
-    async fn heartbeat_job_v4(&self, interval: Duration, timeout: Duration) -> Result<(), Error> {
+    /// With a [`HeartbeatPolicy`], the policy decides the next interval or to close the connection after each ping.
+    async fn heartbeat_job_v4(
+        &self,
+        interval: Duration,
+        timeout: Duration,
+        policy: Option<HeartbeatPolicy>,
+    ) -> Result<(), Error> {
         let mut heartbeat_rx = self
             .heartbeat_rx
             .try_lock()
@@ -330,17 +387,41 @@ where
         #[cfg(feature = "tracing")]
         tracing::debug!("[sid={}] heartbeat sender routine started", self.id);
 
+        let mut interval = interval;
+        let mut missed_pongs = 0;
         loop {
             // Some clients send the pong packet in first. If that happens, we should consume it.
             heartbeat_rx.try_recv().ok();
 
-            self.internal_tx
-                .try_send(smallvec![Packet::Ping])
-                .map_err(|_| Error::HeartbeatTimeout)?;
-            tokio::time::timeout(timeout, heartbeat_rx.recv())
-                .await
+            self.reserve()
                 .map_err(|_| Error::HeartbeatTimeout)?
-                .ok_or(Error::HeartbeatTimeout)?;
+                .send(smallvec![Packet::Ping]);
+            let ping_at = tokio::time::Instant::now();
+            let pong = tokio::time::timeout(timeout, heartbeat_rx.recv()).await;
+            let rtt = match pong {
+                Ok(Some(())) => Some(ping_at.elapsed()),
+                Ok(None) => return Err(Error::HeartbeatTimeout),
+                Err(_) if policy.is_none() => return Err(Error::HeartbeatTimeout),
+                Err(_) => None,
+            };
+
+            if let Some(policy) = &policy {
+                missed_pongs = if rtt.is_some() { 0 } else { missed_pongs + 1 };
+                let status = HeartbeatStatus {
+                    rtt,
+                    missed_pongs,
+                    interval,
+                };
+                let next = policy
+                    .next_interval(status)
+                    .ok_or(Error::HeartbeatTimeout)?;
+                if next != interval {
+                    #[cfg(feature = "tracing")]
+                    tracing::debug!("[sid={}] ping interval set to {:?}", self.id, next);
+                    interval = next;
+                    interval_tick = tokio::time::interval_at(ping_at + next, next);
+                }
+            }
             interval_tick.tick().await;
         }
     }
@@ -363,9 +444,9 @@ where
 
             #[cfg(feature = "tracing")]
             tracing::debug!("[sid={}] ping received, sending pong", self.id);
-            self.internal_tx
-                .try_send(smallvec![Packet::Pong])
-                .map_err(|_| Error::HeartbeatTimeout)?;
+            self.reserve()
+                .map_err(|_| Error::HeartbeatTimeout)?
+                .send(smallvec![Packet::Pong]);
         }
     }
 
@@ -398,7 +479,24 @@ where
     #[inline]
     pub fn reserve(&self) -> Result<Permit<'_>, TrySendError<()>> {
         let permit = self.internal_tx.try_reserve()?;
-        Ok(Permit { inner: permit })
+        Ok(Permit {
+            inner: permit,
+            queued: &self.queued,
+        })
+    }
+
+    /// Waits for a place in the internal chan to emit a message, applying backpressure to the caller.
+    ///
+    /// The internal chan is drained by the transport task and never by the caller,
+    /// so waiting for a permit can't block the transport.
+    /// When the socket is closed the internal chan is closed and the function returns a [`SendError`] error.
+    #[inline]
+    pub async fn reserve_wait(&self) -> Result<Permit<'_>, SendError<()>> {
+        let permit = self.internal_tx.reserve().await?;
+        Ok(Permit {
+            inner: permit,
+            queued: &self.queued,
+        })
     }
 
     /// Emits a message to the client.
@@ -433,6 +531,26 @@ where
         self.internal_tx.closed().await
     }
 
+    /// Waits for the transport to flush all the packets queued before this call.
+    ///
+    /// With the websocket transport, the packets are flushed once they are written to the websocket stream.
+    /// With the polling transport, they are flushed once they are encoded in a polling response.
+    /// It does not mean that the client received them.
+    ///
+    /// Returns `false` if the socket is closed before the packets are flushed.
+    pub async fn flushed(&self) -> bool {
+        use futures_util::future::{self, Either};
+        let target = self.queued.load(Ordering::SeqCst);
+        let mut rx = self.flushed.subscribe();
+        let flushed = std::pin::pin!(rx.wait_for(|flushed| *flushed >= target));
+        let closed = std::pin::pin!(self.closed());
+        let res = match future::select(flushed, closed).await {
+            Either::Left((res, _)) => res.is_ok(),
+            Either::Right(_) => *self.flushed.borrow() >= target,
+        };
+        res
+    }
+
     /// Emits a binary message to the client.
     ///
     /// If the transport is in websocket mode, the message is directly sent as a binary frame.
@@ -502,14 +620,17 @@ where
     ) -> (Arc<Socket<D>>, tokio::sync::mpsc::Receiver<Packet>) {
         let (internal_tx, internal_rx) = mpsc::channel(buffer_size);
         let (heartbeat_tx, heartbeat_rx) = mpsc::channel(1);
+        let flushed = Arc::new(watch::channel(0).0);
 
         let sock = Self {
             id: sid,
             protocol: ProtocolVersion::V4,
             transport: AtomicU8::new(TransportType::Websocket as u8),
 
-            internal_rx: Mutex::new(PeekableReceiver::new(internal_rx)),
+            internal_rx: Mutex::new(PeekableReceiver::with_flushed(internal_rx, flushed.clone())),
             internal_tx,
+            queued: AtomicU64::new(0),
+            flushed,
 
             heartbeat_rx: Mutex::new(heartbeat_rx),
             heartbeat_tx,
@@ -532,6 +653,7 @@ where
                 for packet in packets {
                     tx.send(packet).await.unwrap();
                 }
+                internal_rx.mark_flushed();
             }
         });
 
@@ -0,0 +1,191 @@
+//! The memory transport module exchanges engine.io packets through in-process channels,
+//! without any network connection nor packet encoding.
+//!
+//! It can be used to embed an engine.io server in the same process as its clients
+//! or to write fast and deterministic integration tests.
+//!
+//! #### Example
+//! ```
+//! # use engineioxide::{handler::EngineIoHandler, service::EngineIoService, Packet, Socket, Str};
+//! # use engineioxide::{socket::DisconnectReason, memory::MemoryTransport};
+//! # use std::sync::Arc;
+//! # #[derive(Debug)]
+//! # struct MyHandler;
+//! # impl EngineIoHandler for MyHandler {
+//! #     type Data = ();
+//! #     fn on_connect(self: Arc<Self>, socket: Arc<Socket<()>>) { }
+//! #     fn on_disconnect(&self, socket: Arc<Socket<()>>, reason: DisconnectReason) { }
+//! #     fn on_message(&self, msg: Str, socket: Arc<Socket<()>>) {
+//! #         socket.emit(msg).ok();
+//! #     }
+//! #     fn on_binary(&self, data: bytes::Bytes, socket: Arc<Socket<()>>) { }
+//! # }
+//! # tokio_test(async {
+//! let svc = EngineIoService::new(Arc::new(MyHandler));
+//! let (transport, mut client) = MemoryTransport::pair(16);
+//! svc.connect(transport, http::Request::new(()));
+//!
+//! assert!(matches!(client.recv().await, Some(Packet::Open(_))));
+//! client.send(Packet::Message("hello".into())).await.unwrap();
+//! assert_eq!(client.recv().await, Some(Packet::Message("hello".into())));
+//! # });
+//! # fn tokio_test(f: impl std::future::Future<Output = ()>) {
+//! #     tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(f)
+//! # }
+//! ```
+
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{ready, Context, Poll},
+};
+
+use futures_util::{Sink, Stream};
+use tokio::sync::mpsc::{self, error::SendError, OwnedPermit};
+
+use crate::{packet::Packet, transport::Transport};
+
+/// The error returned when the other half of a memory transport is dropped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("memory transport closed")]
+pub struct MemoryClosed;
+
+/// The server half of an in-memory transport, created with [`MemoryTransport::pair`].
+#[derive(Debug)]
+pub struct MemoryTransport {
+    tx: mpsc::Sender<Packet>,
+    rx: mpsc::Receiver<Packet>,
+}
+
+/// The client half of an in-memory transport, created with [`MemoryTransport::pair`].
+#[derive(Debug)]
+pub struct MemoryClient {
+    tx: mpsc::Sender<Packet>,
+    rx: mpsc::Receiver<Packet>,
+}
+
+impl MemoryTransport {
+    /// Creates a connected pair of a server transport and its client.
+    ///
+    /// Each side can send up to `buffer_size` packets before waiting for the other side to receive them.
+    /// When the client doesn't receive its packets, the server socket buffer fills up and
+    /// [`Socket::emit`](crate::socket::Socket::emit) fails as it would with a slow network connection.
+    ///
+    /// # Panics
+    /// If `buffer_size` is 0.
+    pub fn pair(buffer_size: usize) -> (MemoryTransport, MemoryClient) {
+        let (client_tx, server_rx) = mpsc::channel(buffer_size);
+        let (server_tx, client_rx) = mpsc::channel(buffer_size);
+        let transport = MemoryTransport {
+            tx: server_tx,
+            rx: server_rx,
+        };
+        let client = MemoryClient {
+            tx: client_tx,
+            rx: client_rx,
+        };
+        (transport, client)
+    }
+}
+
+impl MemoryClient {
+    /// Sends a packet to the server.
+    pub async fn send(&self, packet: Packet) -> Result<(), MemoryClosed> {
+        self.tx.send(packet).await.map_err(|_| MemoryClosed)
+    }
+
+    /// Receives the next packet sent by the server.
+    /// Returns `None` once the server closed the transport.
+    pub async fn recv(&mut self) -> Option<Packet> {
+        self.rx.recv().await
+    }
+}
+
+impl Transport for MemoryTransport {
+    type Error = MemoryClosed;
+    type Sink = MemorySink;
+    type Stream = MemoryStream;
+
+    fn split(self) -> (MemorySink, MemoryStream) {
+        let sink = MemorySink {
+            tx: self.tx,
+            reserve: None,
+            permit: None,
+        };
+        (sink, MemoryStream(self.rx))
+    }
+}
+
+type ReserveFuture =
+    Pin<Box<dyn Future<Output = Result<OwnedPermit<Packet>, SendError<()>>> + Send + Sync>>;
+
+/// The half of a [`MemoryTransport`] sending packets to the client.
+///
+/// It is ready to send a packet once a slot of the channel is reserved for it,
+/// so a client that doesn't receive its packets applies backpressure to the server.
+pub struct MemorySink {
+    tx: mpsc::Sender<Packet>,
+    /// The pending reservation of a slot in the channel
+    reserve: Option<ReserveFuture>,
+    /// The reserved slot, consumed by the next packet sent
+    permit: Option<OwnedPermit<Packet>>,
+}
+
+impl std::fmt::Debug for MemorySink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MemorySink")
+            .field("tx", &self.tx)
+            .field("reserved", &self.permit.is_some())
+            .finish()
+    }
+}
+
+impl Sink<Packet> for MemorySink {
+    type Error = MemoryClosed;
+
+    fn poll_ready(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), MemoryClosed>> {
+        if self.permit.is_some() {
+            return Poll::Ready(Ok(()));
+        }
+        let tx = self.tx.clone();
+        let reserve = self
+            .reserve
+            .get_or_insert_with(|| Box::pin(tx.reserve_owned()));
+        let res = ready!(reserve.as_mut().poll(cx));
+        self.reserve = None;
+        self.permit = Some(res.map_err(|_| MemoryClosed)?);
+        Poll::Ready(Ok(()))
+    }
+
+    fn start_send(mut self: Pin<&mut Self>, packet: Packet) -> Result<(), MemoryClosed> {
+        let permit = self
+            .permit
+            .take()
+            .expect("poll_ready must be called before start_send");
+        permit.send(packet);
+        Ok(())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), MemoryClosed>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), MemoryClosed>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// The half of a [`MemoryTransport`] receiving packets from the client.
+#[derive(Debug)]
+pub struct MemoryStream(mpsc::Receiver<Packet>);
+
+impl Stream for MemoryStream {
+    type Item = Packet;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Packet>> {
+        self.0.poll_recv(cx)
+    }
+}
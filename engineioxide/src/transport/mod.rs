@@ -1,4 +1,202 @@
 //! All transports modules available in engineioxide
+//!
+//! Custom persistent transports (e.g. the in-memory [`MemoryTransport`](memory::MemoryTransport))
+//! can be implemented with the [`Transport`] trait and connected with
+//! [`EngineIoService::connect`](crate::service::EngineIoService::connect).
+//! The built-in polling and websocket transports do not use this trait.
 
+use std::sync::Arc;
+
+use futures_util::{
+    future::{self, Either},
+    Sink, SinkExt, Stream, StreamExt,
+};
+use http::request::Parts;
+
+use crate::{
+    engine::EngineIo,
+    errors::Error,
+    handler::EngineIoHandler,
+    packet::{OpenPacket, Packet},
+    service::{ProtocolVersion, TransportType},
+    sid::Sid,
+    DisconnectReason, Socket,
+};
+
+pub mod memory;
 pub mod polling;
 pub mod ws;
+
+/// A custom persistent and bidirectional transport exchanging engine.io [`Packet`]s with a client.
+///
+/// The session of a custom transport follows the rules of a websocket session:
+/// * The [`Packet::Open`] packet is the first packet sent to the client.
+/// * The client must answer the [`Packet::Ping`] packets sent by the server with [`Packet::Pong`] packets.
+/// * The session is closed when the client sends a [`Packet::Close`] packet or when the stream ends.
+/// * The session is closed with a [`DisconnectReason::TransportError`] on the first error of the sink.
+///
+/// The sockets connected with a custom transport are reported with the [`TransportType::Websocket`] type
+/// and the [`ProtocolVersion::V4`] protocol.
+pub trait Transport: Send + 'static {
+    /// The error returned by the [`Transport::Sink`].
+    type Error: std::fmt::Debug + Send;
+    /// The half sending packets to the client.
+    type Sink: Sink<Packet, Error = Self::Error> + Send + Unpin + 'static;
+    /// The half receiving packets from the client.
+    type Stream: Stream<Item = Packet> + Send + Unpin + 'static;
+
+    /// Splits the transport into its sending and receiving halves.
+    fn split(self) -> (Self::Sink, Self::Stream);
+}
+
+/// Creates a new session on a custom [`Transport`] and spawns the tasks handling it.
+///
+/// Returns `None` if the [`EngineIoConfig::max_connections`](crate::config::EngineIoConfig::max_connections)
+/// limit is reached.
+pub(crate) fn connect<H: EngineIoHandler, T: Transport>(
+    engine: Arc<EngineIo<H>>,
+    transport: T,
+    req_data: Parts,
+) -> Option<Sid> {
+    let socket = engine.create_session(
+        ProtocolVersion::V4,
+        TransportType::Websocket,
+        req_data,
+        #[cfg(feature = "v3")]
+        true,
+    )?;
+    let sid = socket.id;
+    #[cfg(feature = "tracing")]
+    tracing::debug!("[sid={sid}] new custom transport connection");
+
+    tokio::spawn(async move {
+        let (mut tx, rx) = transport.split();
+        let packet = Packet::Open(OpenPacket::new(
+            TransportType::Websocket,
+            socket.id,
+            &engine.config,
+        ));
+        if let Err(_e) = tx.send(packet).await {
+            #[cfg(feature = "tracing")]
+            tracing::debug!("[sid={}] error sending open packet: {:?}", socket.id, _e);
+            engine.close_session(socket.id, DisconnectReason::TransportError);
+            return;
+        }
+        socket.clone().spawn_heartbeat(
+            engine.config.ping_interval,
+            engine.config.ping_timeout,
+            engine.config.heartbeat_policy.clone(),
+        );
+
+        let mut tx_handle = tokio::spawn(forward_to_transport::<H, T>(socket.clone(), tx));
+        let rx_fut = std::pin::pin!(forward_to_handler::<H, T>(&engine, rx, &socket));
+        let res = match future::select(rx_fut, &mut tx_handle).await {
+            Either::Left((res, _)) => res,
+            // The session is already closed when the sending half stops
+            Either::Right(_) => return,
+        };
+        if let Err(ref e) = res {
+            #[cfg(feature = "tracing")]
+            tracing::debug!("[sid={}] error when handling packet: {:?}", socket.id, e);
+            if let Some(reason) = e.into() {
+                engine.close_session(socket.id, reason);
+            }
+        } else {
+            engine.close_session(socket.id, DisconnectReason::TransportClose);
+        }
+        tx_handle.abort();
+    });
+    Some(sid)
+}
+
+/// Forwards all packets received from a custom transport to a EngineIo [`Socket`]
+async fn forward_to_handler<H: EngineIoHandler, T: Transport>(
+    engine: &Arc<EngineIo<H>>,
+    mut rx: T::Stream,
+    socket: &Arc<Socket<H::Data>>,
+) -> Result<(), Error> {
+    while let Some(packet) = rx.next().await {
+        match packet {
+            Packet::Binary(data) | Packet::BinaryV3(data) => {
+                engine.handler.on_binary(data, socket.clone())
+            }
+            packet => {
+                if !handle_packet(engine, socket, packet).await? {
+                    break;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Handles a non binary packet received from a persistent transport, the websocket one or a custom one.
+/// Returns `Ok(false)` when the client closed the session with a [`Packet::Close`] packet.
+pub(crate) async fn handle_packet<H: EngineIoHandler>(
+    engine: &EngineIo<H>,
+    socket: &Arc<Socket<H::Data>>,
+    packet: Packet,
+) -> Result<bool, Error> {
+    match packet {
+        Packet::Close => {
+            #[cfg(feature = "tracing")]
+            tracing::debug!("[sid={}] closing session", socket.id);
+            Ok(false)
+        }
+        Packet::Pong | Packet::Ping => socket
+            .heartbeat_tx
+            .try_send(())
+            .map(|_| true)
+            .map_err(|_| Error::HeartbeatTimeout),
+        Packet::Message(msg) => {
+            engine.handler.on_message(msg, socket.clone());
+            Ok(true)
+        }
+        p => Err(Error::BadPacket(p)),
+    }
+}
+
+/// Forwards all packets waiting to be sent to a custom transport.
+/// The transport is flushed each time the internal channel is drained.
+///
+/// The session is closed on the first error of the transport.
+async fn forward_to_transport<H: EngineIoHandler, T: Transport>(
+    socket: Arc<Socket<H::Data>>,
+    mut tx: T::Sink,
+) {
+    let mut internal_rx = socket.internal_rx.try_lock().unwrap();
+    while let Some(items) = internal_rx.recv().await {
+        // For every available packet we continue to send until the channel is drained
+        let mut next = Some(items);
+        while let Some(items) = next.take() {
+            for item in items {
+                let res = match item {
+                    Packet::Close => {
+                        tx.send(Packet::Close).await.ok();
+                        internal_rx.close();
+                        return;
+                    }
+                    // Noop packets are only used to close pending polling requests
+                    Packet::Noop => Ok(()),
+                    item => tx.feed(item).await,
+                };
+                if let Err(_e) = res {
+                    #[cfg(feature = "tracing")]
+                    tracing::debug!("[sid={}] error sending packet: {:?}", socket.id, _e);
+                    internal_rx.close();
+                    socket.close(DisconnectReason::TransportError);
+                    return;
+                }
+            }
+            next = internal_rx.try_recv().ok();
+        }
+        if let Err(_e) = tx.flush().await {
+            #[cfg(feature = "tracing")]
+            tracing::debug!("[sid={}] error flushing transport: {:?}", socket.id, _e);
+            internal_rx.close();
+            socket.close(DisconnectReason::TransportError);
+            return;
+        }
+        internal_rx.mark_flushed();
+    }
+}
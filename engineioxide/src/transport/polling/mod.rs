@@ -53,18 +53,25 @@ where
     H: EngineIoHandler,
     B: Send + 'static,
 {
-    let socket = engine.create_session(
-        protocol,
-        TransportType::Polling,
-        req.into_parts().0,
-        #[cfg(feature = "v3")]
-        supports_binary,
+    let socket = engine
+        .create_session(
+            protocol,
+            TransportType::Polling,
+            req.into_parts().0,
+            #[cfg(feature = "v3")]
+            supports_binary,
+        )
+        .ok_or(Error::TooManyConnections)?;
+
+    let sid = socket.id;
+    let packet = OpenPacket::new(TransportType::Polling, sid, &engine.config);
+
+    socket.spawn_heartbeat(
+        engine.config.ping_interval,
+        engine.config.ping_timeout,
+        engine.config.heartbeat_policy.clone(),
     );
 
-    let packet = OpenPacket::new(TransportType::Polling, socket.id, &engine.config);
-
-    socket.spawn_heartbeat(engine.config.ping_interval, engine.config.ping_timeout);
-
     let packet: String = Packet::Open(packet).try_into().unwrap();
     let packet = {
         #[cfg(feature = "v3")]
@@ -80,7 +87,18 @@ where
         #[cfg(not(feature = "v3"))]
         packet
     };
-    http_response(StatusCode::OK, packet, false).map_err(Error::Http)
+    #[cfg(feature = "debug-frames")]
+    engine
+        .config
+        .raw_packet_hook
+        .call(sid, crate::frames::Direction::Outgoing, packet.as_bytes());
+    let mut res = http_response(StatusCode::OK, packet, false).map_err(Error::Http)?;
+    if let Some(cookie) = &engine.config.cookie {
+        let cookie =
+            http::HeaderValue::try_from(cookie.header_value(sid)).map_err(http::Error::from)?;
+        res.headers_mut().insert(http::header::SET_COOKIE, cookie);
+    }
+    Ok(res)
 }
 
 /// Handle http polling request
@@ -103,7 +121,7 @@ where
 
     // If the socket is already locked, it means that the socket is being used by another request
     // In case of multiple http polling, session should be closed
-    let rx = match socket.internal_rx.try_lock() {
+    let mut rx = match socket.internal_rx.try_lock() {
         Ok(s) => s,
         Err(_) => {
             socket.close(DisconnectReason::MultipleHttpPollingError);
@@ -114,6 +132,15 @@ where
     #[cfg(feature = "tracing")]
     tracing::debug!("[sid={sid}] polling request");
 
+    // Answer with a noop packet if nothing is sent before the polling duration
+    if let Some(duration) = engine.config.polling_duration {
+        if tokio::time::timeout(duration, rx.wait()).await.is_err() {
+            #[cfg(feature = "tracing")]
+            tracing::debug!("[sid={sid}] polling duration elapsed, sending noop");
+            socket.send(Packet::Noop).ok();
+        }
+    }
+
     let max_payload = engine.config.max_payload;
 
     #[cfg(feature = "v3")]
@@ -124,6 +151,11 @@ where
 
     #[cfg(feature = "tracing")]
     tracing::debug!("[sid={sid}] sending data: {:?}", data);
+    #[cfg(feature = "debug-frames")]
+    engine
+        .config
+        .raw_packet_hook
+        .call(sid, crate::frames::Direction::Outgoing, &data);
     Ok(http_response(StatusCode::OK, data, has_binary)?)
 }
 
@@ -148,6 +180,11 @@ where
         return Err(Error::TransportMismatch);
     }
 
+    #[cfg(feature = "debug-frames")]
+    let body = body.map(|body| {
+        let hook = engine.config.raw_packet_hook.clone();
+        crate::frames::InspectBody::new(body, sid, hook)
+    });
     let packets = payload::decoder(body, protocol, engine.config.max_payload);
     futures_util::pin_mut!(packets);
 
@@ -106,6 +106,7 @@ pub async fn v4_encoder(
         }
     }
 
+    rx.mark_flushed();
     Ok(Payload::new(data.into(), false))
 }
 
@@ -228,6 +229,7 @@ pub async fn v3_binary_encoder(
 
     #[cfg(feature = "tracing")]
     tracing::debug!("sending packet: {:?}", &data);
+    rx.mark_flushed();
     Ok(Payload::new(data.freeze(), has_binary))
 }
 
@@ -262,6 +264,7 @@ pub async fn v3_string_encoder(
         }
     }
 
+    rx.mark_flushed();
     Ok(Payload::new(data.freeze(), false))
 }
 
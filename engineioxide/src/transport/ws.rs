@@ -7,22 +7,31 @@
 use std::sync::Arc;
 
 use futures_util::{
+    future::{self, Either},
     stream::{SplitSink, SplitStream},
     SinkExt, StreamExt, TryStreamExt,
 };
 use http::{request::Parts, HeaderValue, Request, Response, StatusCode};
 use tokio::{
     io::{AsyncRead, AsyncWrite},
+    sync::oneshot,
     task::JoinHandle,
+    time::Instant,
 };
 use tokio_tungstenite::{
-    tungstenite::{handshake::derive_accept_key, protocol::Role, Message},
+    tungstenite::{
+        self,
+        error::CapacityError,
+        handshake::derive_accept_key,
+        protocol::{frame::coding::CloseCode, CloseFrame, Role, WebSocketConfig},
+        Message,
+    },
     WebSocketStream,
 };
 
 use crate::{
     body::ResponseBody,
-    config::EngineIoConfig,
+    config::{EngineIoConfig, WriteBatch},
     engine::EngineIo,
     errors::Error,
     handler::EngineIoHandler,
@@ -30,9 +39,13 @@ use crate::{
     service::ProtocolVersion,
     service::TransportType,
     sid::Sid,
+    transport::handle_packet,
     DisconnectReason, Socket,
 };
 
+#[cfg(feature = "debug-frames")]
+use crate::frames::{Direction, RawPacketHook};
+
 /// Create a response for websocket upgrade
 fn ws_response<B>(ws_key: &HeaderValue) -> Result<Response<ResponseBody<B>>, http::Error> {
     let derived = derive_accept_key(ws_key.as_bytes());
@@ -110,40 +123,85 @@ async fn on_init<H: EngineIoHandler, S>(
 where
     S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
 {
-    let ws_init = move || WebSocketStream::from_raw_socket(conn, Role::Server, None);
+    let ws_config = WebSocketConfig {
+        max_frame_size: Some(engine.config.max_frame_size),
+        ..Default::default()
+    };
+    let ws_init = move || WebSocketStream::from_raw_socket(conn, Role::Server, Some(ws_config));
     let (socket, ws) = if let Some(sid) = sid {
         match engine.get_socket(sid) {
             None => return Err(Error::UnknownSessionID(sid)),
             Some(socket) if socket.is_ws() => return Err(Error::Upgrade),
             Some(socket) => {
                 let mut ws = ws_init().await;
-                upgrade_handshake::<H, S>(&socket, &mut ws).await?;
+                let upgrade = upgrade_handshake::<H, S>(
+                    &socket,
+                    &mut ws,
+                    #[cfg(feature = "debug-frames")]
+                    &engine.config.raw_packet_hook,
+                );
+                // If the upgrade takes too long the ws connection is dropped
+                // and the socket stays on the polling transport
+                match tokio::time::timeout(engine.config.upgrade_timeout, upgrade).await {
+                    Ok(res) => res?,
+                    Err(_) => {
+                        #[cfg(feature = "tracing")]
+                        tracing::debug!("[sid={sid}] websocket upgrade timed out");
+                        return Err(Error::Upgrade);
+                    }
+                }
                 (socket, ws)
             }
         }
     } else {
-        let socket = engine.create_session(
-            protocol,
-            TransportType::Websocket,
-            req_data,
-            #[cfg(feature = "v3")]
-            false,
-        );
+        let socket = engine
+            .create_session(
+                protocol,
+                TransportType::Websocket,
+                req_data,
+                #[cfg(feature = "v3")]
+                false,
+            )
+            .ok_or(Error::TooManyConnections)?;
         #[cfg(feature = "tracing")]
         tracing::debug!("[sid={}] new websocket connection", socket.id);
         let mut ws = ws_init().await;
         init_handshake(socket.id, &mut ws, &engine.config).await?;
-        socket
-            .clone()
-            .spawn_heartbeat(engine.config.ping_interval, engine.config.ping_timeout);
+        socket.clone().spawn_heartbeat(
+            engine.config.ping_interval,
+            engine.config.ping_timeout,
+            engine.config.heartbeat_policy.clone(),
+        );
         (socket, ws)
     };
     let (tx, rx) = ws.split();
-    let rx_handle = forward_to_socket::<H, S>(socket.clone(), tx);
+    let (close_tx, close_rx) = oneshot::channel();
+    let mut rx_handle = forward_to_socket::<H, S>(
+        socket.clone(),
+        tx,
+        engine.config.write_batch,
+        close_rx,
+        #[cfg(feature = "debug-frames")]
+        engine.config.raw_packet_hook.clone(),
+    );
 
     if let Err(ref e) = forward_to_handler(&engine, rx, &socket).await {
         #[cfg(feature = "tracing")]
         tracing::debug!("[sid={}] error when handling packet: {:?}", socket.id, e);
+        if let Error::WsTransport(tungstenite::Error::Capacity(CapacityError::MessageTooLong {
+            ..
+        })) = e
+        {
+            // The oversized frame is not read, so the connection is closed with a protocol error
+            let frame = CloseFrame {
+                code: CloseCode::Protocol,
+                reason: "frame too large".into(),
+            };
+            if close_tx.send(frame).is_ok() {
+                let timeout = engine.config.ping_timeout;
+                tokio::time::timeout(timeout, &mut rx_handle).await.ok();
+            }
+        }
         if let Some(reason) = e.into() {
             engine.close_session(socket.id, reason);
         }
@@ -164,23 +222,27 @@ where
     S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
 {
     while let Some(msg) = rx.try_next().await? {
+        #[cfg(feature = "debug-frames")]
+        match &msg {
+            Message::Text(data) => {
+                let hook = &engine.config.raw_packet_hook;
+                hook.call(socket.id, Direction::Incoming, data.as_bytes());
+            }
+            Message::Binary(data) => {
+                let hook = &engine.config.raw_packet_hook;
+                hook.call(socket.id, Direction::Incoming, data);
+            }
+            _ => (),
+        }
         match msg {
-            Message::Text(msg) => match Packet::try_from(msg)? {
-                Packet::Close => {
-                    #[cfg(feature = "tracing")]
-                    tracing::debug!("[sid={}] closing session", socket.id);
+            Message::Text(msg) => match handle_packet(engine, socket, Packet::try_from(msg)?).await
+            {
+                Ok(true) => Ok(()),
+                Ok(false) => {
                     engine.close_session(socket.id, DisconnectReason::TransportClose);
                     break;
                 }
-                Packet::Pong | Packet::Ping => socket
-                    .heartbeat_tx
-                    .try_send(())
-                    .map_err(|_| Error::HeartbeatTimeout),
-                Packet::Message(msg) => {
-                    engine.handler.on_message(msg, socket.clone());
-                    Ok(())
-                }
-                p => return Err(Error::BadPacket(p)),
+                Err(e) => Err(e),
             },
             Message::Binary(mut data) => {
                 if socket.protocol == ProtocolVersion::V3 && !data.is_empty() {
@@ -203,10 +265,16 @@ where
 
 /// Forwards all packets waiting to be sent to the websocket
 ///
-/// The websocket stream is flushed only when the internal channel is drained
+/// The websocket stream is flushed when the internal channel is drained,
+/// or if a [`WriteBatch`] is set, when the batch window is elapsed or the batch is full.
+///
+/// If a close frame is sent through `close_rx`, it is written to the websocket and the forwarding stops.
 fn forward_to_socket<H: EngineIoHandler, S>(
     socket: Arc<Socket<H::Data>>,
     mut tx: SplitSink<WebSocketStream<S>, Message>,
+    write_batch: Option<WriteBatch>,
+    mut close_rx: oneshot::Receiver<CloseFrame<'static>>,
+    #[cfg(feature = "debug-frames")] hook: RawPacketHook,
 ) -> JoinHandle<()>
 where
     S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
@@ -221,11 +289,19 @@ where
             ($item:ident) => {
                 let res = match $item {
                     Packet::Binary(bin) | Packet::BinaryV3(bin) => {
-                        let mut bin: Vec<u8> = bin.into();
-                        if socket.protocol == ProtocolVersion::V3 {
+                        // The binary buffer may be shared with other sockets (e.g. when broadcasting),
+                        // so it is copied only once here into the frame owned by the websocket writer.
+                        let bin: Vec<u8> = if socket.protocol == ProtocolVersion::V3 {
                             // v3 protocol requires packet type as the first byte
-                            bin.insert(0, 0x04);
-                        }
+                            let mut buf = Vec::with_capacity(bin.len() + 1);
+                            buf.push(0x04);
+                            buf.extend_from_slice(&bin);
+                            buf
+                        } else {
+                            bin.into()
+                        };
+                        #[cfg(feature = "debug-frames")]
+                        hook.call(socket.id, Direction::Outgoing, &bin);
                         tx.feed(Message::Binary(bin)).await
                     }
                     Packet::Close => {
@@ -239,6 +315,8 @@ where
                     Packet::Noop => Ok(()),
                     _ => {
                         let packet: String = $item.try_into().unwrap();
+                        #[cfg(feature = "debug-frames")]
+                        hook.call(socket.id, Direction::Outgoing, packet.as_bytes());
                         tx.feed(Message::Text(packet)).await
                     }
                 };
@@ -249,18 +327,70 @@ where
             };
         }
 
-        while let Some(items) = internal_rx.recv().await {
+        // Set once the close channel is dropped without any close frame
+        let mut close_dropped = false;
+        loop {
+            let items = {
+                let recv = std::pin::pin!(internal_rx.recv());
+                if close_dropped {
+                    recv.await
+                } else {
+                    match future::select(recv, &mut close_rx).await {
+                        Either::Left((items, _)) => items,
+                        Either::Right((Ok(frame), _)) => {
+                            tx.send(Message::Close(Some(frame))).await.ok();
+                            break;
+                        }
+                        Either::Right((Err(_), recv)) => {
+                            close_dropped = true;
+                            recv.await
+                        }
+                    }
+                }
+            };
+            let Some(items) = items else {
+                break;
+            };
+            let mut count = items.len();
             for item in items {
                 map_fn!(item);
             }
-            // For every available packet we continue to send until the channel is drained
-            while let Ok(items) = internal_rx.try_recv() {
-                for item in items {
-                    map_fn!(item);
+            if let Some(WriteBatch {
+                window,
+                max_packets,
+            }) = write_batch
+            {
+                // We collect the packets while they keep coming, until the window is elapsed
+                // or the batch is full. The batch is flushed as soon as no packet is ready.
+                let deadline = Instant::now() + window;
+                while count < max_packets && Instant::now() < deadline {
+                    let items = match internal_rx.try_recv() {
+                        Ok(items) => items,
+                        Err(_) => {
+                            // Let the emitting tasks run before considering the socket idle
+                            tokio::task::yield_now().await;
+                            match internal_rx.try_recv() {
+                                Ok(items) => items,
+                                Err(_) => break,
+                            }
+                        }
+                    };
+                    count += items.len();
+                    for item in items {
+                        map_fn!(item);
+                    }
+                }
+            } else {
+                // For every available packet we continue to send until the channel is drained
+                while let Ok(items) = internal_rx.try_recv() {
+                    for item in items {
+                        map_fn!(item);
+                    }
                 }
             }
 
             tx.flush().await.ok();
+            internal_rx.mark_flushed();
         }
     })
 }
@@ -273,8 +403,13 @@ async fn init_handshake<S>(
 where
     S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
 {
-    let packet = Packet::Open(OpenPacket::new(TransportType::Websocket, sid, config));
-    ws.send(Message::Text(packet.try_into()?)).await?;
+    let packet: String =
+        Packet::Open(OpenPacket::new(TransportType::Websocket, sid, config)).try_into()?;
+    #[cfg(feature = "debug-frames")]
+    config
+        .raw_packet_hook
+        .call(sid, Direction::Outgoing, packet.as_bytes());
+    ws.send(Message::Text(packet)).await?;
     Ok(())
 }
 
@@ -301,10 +436,11 @@ where
 ///│                                                      │
 ///│            -----  WebSocket frames -----             │
 /// ```
-#[cfg_attr(feature = "tracing", tracing::instrument(skip(socket, ws), fields(sid = socket.id.to_string())))]
+#[cfg_attr(feature = "tracing", tracing::instrument(skip(socket, ws, hook), fields(sid = socket.id.to_string())))]
 async fn upgrade_handshake<H: EngineIoHandler, S>(
     socket: &Arc<Socket<H::Data>>,
     ws: &mut WebSocketStream<S>,
+    #[cfg(feature = "debug-frames")] hook: &RawPacketHook,
 ) -> Result<(), Error>
 where
     S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
@@ -317,11 +453,15 @@ where
         Some(Ok(Message::Text(d))) => d,
         _ => Err(Error::Upgrade)?,
     };
+    #[cfg(feature = "debug-frames")]
+    hook.call(socket.id, Direction::Incoming, msg.as_bytes());
     match Packet::try_from(msg)? {
         Packet::PingUpgrade => {
             // Respond with a PongUpgrade packet
-            ws.send(Message::Text(Packet::PongUpgrade.try_into()?))
-                .await?;
+            let packet: String = Packet::PongUpgrade.try_into()?;
+            #[cfg(feature = "debug-frames")]
+            hook.call(socket.id, Direction::Outgoing, packet.as_bytes());
+            ws.send(Message::Text(packet)).await?;
         }
         p => Err(Error::BadPacket(p))?,
     };
@@ -343,6 +483,8 @@ where
             Err(Error::Upgrade)?
         }
     };
+    #[cfg(feature = "debug-frames")]
+    hook.call(socket.id, Direction::Incoming, msg.as_bytes());
     match Packet::try_from(msg)? {
         Packet::Upgrade => {
             #[cfg(feature = "tracing")]
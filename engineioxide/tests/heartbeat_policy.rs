@@ -0,0 +1,142 @@
+//! Tests for the heartbeat policy adapting the ping interval of a connection
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use bytes::Bytes;
+use engineioxide::{
+    config::{EngineIoConfig, HeartbeatPolicy, HeartbeatStatus},
+    handler::EngineIoHandler,
+    service::EngineIoService,
+    socket::{DisconnectReason, Socket},
+    Str,
+};
+use futures_util::{SinkExt, StreamExt};
+use hyper::server::conn::http1;
+use hyper_util::rt::TokioIo;
+use tokio::net::{TcpListener, TcpStream};
+use tokio_tungstenite::{tungstenite::Message, MaybeTlsStream, WebSocketStream};
+
+#[derive(Debug)]
+struct MyHandler;
+
+impl EngineIoHandler for MyHandler {
+    type Data = ();
+
+    fn on_connect(self: Arc<Self>, _: Arc<Socket<()>>) {}
+    fn on_disconnect(&self, _: Arc<Socket<()>>, _: DisconnectReason) {}
+    fn on_message(&self, _: Str, _: Arc<Socket<()>>) {}
+    fn on_binary(&self, _: Bytes, _: Arc<Socket<()>>) {}
+}
+
+async fn create_server(config: EngineIoConfig, port: u16) {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), port);
+    let svc = EngineIoService::with_config(Arc::new(MyHandler), config);
+    let listener = TcpListener::bind(&addr).await.unwrap();
+    tokio::spawn(async move {
+        loop {
+            let (stream, _) = listener.accept().await.unwrap();
+            let svc = svc.clone();
+            tokio::task::spawn(async move {
+                http1::Builder::new()
+                    .serve_connection(TokioIo::new(stream), svc)
+                    .with_upgrades()
+                    .await
+                    .ok();
+            });
+        }
+    });
+}
+
+async fn next_msg(ws: &mut WebSocketStream<MaybeTlsStream<TcpStream>>) -> Message {
+    tokio::time::timeout(Duration::from_millis(500), ws.next())
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap()
+}
+
+#[tokio::test]
+pub async fn missed_pong_shortens_ping_interval() {
+    const PORT: u16 = 12380;
+    const INTERVAL: Duration = Duration::from_millis(300);
+    const SHORT_INTERVAL: Duration = Duration::from_millis(60);
+
+    let statuses = Arc::new(Mutex::new(Vec::<HeartbeatStatus>::new()));
+    let policy = {
+        let statuses = statuses.clone();
+        HeartbeatPolicy::new(move |status| {
+            statuses.lock().unwrap().push(status);
+            match status.missed_pongs {
+                0 => Some(INTERVAL),
+                _ => Some(SHORT_INTERVAL),
+            }
+        })
+    };
+    let config = EngineIoConfig::builder()
+        .ping_interval(INTERVAL)
+        .ping_timeout(Duration::from_millis(30))
+        .heartbeat_policy(policy)
+        .build();
+    create_server(config, PORT).await;
+
+    let (mut ws, _) = tokio_tungstenite::connect_async(format!(
+        "ws://127.0.0.1:{PORT}/engine.io/?EIO=4&transport=websocket"
+    ))
+    .await
+    .unwrap();
+
+    // Open packet
+    next_msg(&mut ws).await;
+    // The first ping is not answered
+    assert_eq!(next_msg(&mut ws).await, Message::Text("2".into()));
+    let missed_at = tokio::time::Instant::now();
+
+    // The next ping is sent after the shorter interval rather than the configured one
+    assert_eq!(next_msg(&mut ws).await, Message::Text("2".into()));
+    assert!(missed_at.elapsed() < Duration::from_millis(200));
+    ws.send(Message::Text("3".into())).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(20)).await;
+
+    let statuses = statuses.lock().unwrap().clone();
+    assert_eq!(statuses.len(), 2);
+    assert_eq!(
+        statuses[0],
+        HeartbeatStatus {
+            rtt: None,
+            missed_pongs: 1,
+            interval: INTERVAL
+        }
+    );
+    assert!(statuses[1].rtt.is_some());
+    assert_eq!(statuses[1].missed_pongs, 0);
+    assert_eq!(statuses[1].interval, SHORT_INTERVAL);
+}
+
+#[tokio::test]
+pub async fn zero_interval_is_clamped() {
+    const PORT: u16 = 12383;
+    let policy = HeartbeatPolicy::new(|_| Some(Duration::ZERO));
+    let config = EngineIoConfig::builder()
+        .ping_interval(Duration::from_millis(50))
+        .ping_timeout(Duration::from_millis(100))
+        .heartbeat_policy(policy)
+        .build();
+    create_server(config, PORT).await;
+
+    let (mut ws, _) = tokio_tungstenite::connect_async(format!(
+        "ws://127.0.0.1:{PORT}/engine.io/?EIO=4&transport=websocket"
+    ))
+    .await
+    .unwrap();
+
+    // Open packet
+    next_msg(&mut ws).await;
+    // The heartbeat keeps pinging after a zero interval is returned
+    for _ in 0..3 {
+        assert_eq!(next_msg(&mut ws).await, Message::Text("2".into()));
+        ws.send(Message::Text("3".into())).await.unwrap();
+    }
+}
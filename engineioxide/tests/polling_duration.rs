@@ -0,0 +1,92 @@
+//! Tests for the polling requests answered once the polling duration is elapsed
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use bytes::Bytes;
+use engineioxide::{
+    config::EngineIoConfig,
+    handler::EngineIoHandler,
+    service::EngineIoService,
+    socket::{DisconnectReason, Socket},
+    Str,
+};
+use http_body_util::{BodyExt, Empty};
+use hyper::server::conn::http1;
+use hyper_util::{
+    client::legacy::Client,
+    rt::{TokioExecutor, TokioIo},
+};
+use tokio::net::TcpListener;
+
+#[derive(Debug)]
+struct MyHandler;
+
+impl EngineIoHandler for MyHandler {
+    type Data = ();
+
+    fn on_connect(self: Arc<Self>, _: Arc<Socket<()>>) {}
+    fn on_disconnect(&self, _: Arc<Socket<()>>, _: DisconnectReason) {}
+    fn on_message(&self, _: Str, _: Arc<Socket<()>>) {}
+    fn on_binary(&self, _: Bytes, _: Arc<Socket<()>>) {}
+}
+
+async fn create_server(config: EngineIoConfig, port: u16) {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), port);
+    let svc = EngineIoService::with_config(Arc::new(MyHandler), config);
+    let listener = TcpListener::bind(&addr).await.unwrap();
+    tokio::spawn(async move {
+        loop {
+            let (stream, _) = listener.accept().await.unwrap();
+            let svc = svc.clone();
+            tokio::task::spawn(async move {
+                http1::Builder::new()
+                    .serve_connection(TokioIo::new(stream), svc)
+                    .await
+                    .ok();
+            });
+        }
+    });
+}
+
+async fn get(port: u16, params: &str) -> String {
+    let req = http::Request::builder()
+        .uri(format!("http://127.0.0.1:{port}/engine.io/?EIO=4&{params}"))
+        .body(Empty::<Bytes>::new())
+        .unwrap();
+    let mut res = Client::builder(TokioExecutor::new())
+        .build_http()
+        .request(req)
+        .await
+        .unwrap();
+    let body = res.body_mut().collect().await.unwrap().to_bytes();
+    String::from_utf8(body.to_vec()).unwrap()
+}
+
+#[tokio::test]
+pub async fn polling_request_answered_after_polling_duration() {
+    const PORT: u16 = 12381;
+    const DURATION: Duration = Duration::from_millis(100);
+    let config = EngineIoConfig::builder()
+        .ping_interval(Duration::from_secs(10))
+        .polling_duration(DURATION)
+        .build();
+    create_server(config, PORT).await;
+
+    let open = get(PORT, "transport=polling").await;
+    let open: serde_json::Value = serde_json::from_str(&open[1..]).unwrap();
+    let sid = open["sid"].as_str().unwrap();
+
+    // No packet is pending, the request is answered with a noop once the duration is elapsed
+    let start = Instant::now();
+    let body = get(PORT, &format!("transport=polling&sid={sid}")).await;
+    let elapsed = start.elapsed();
+    assert_eq!(body, "6");
+    assert!(elapsed >= DURATION, "answered too early: {elapsed:?}");
+    assert!(
+        elapsed < Duration::from_secs(1),
+        "answered too late: {elapsed:?}"
+    );
+}
@@ -0,0 +1,41 @@
+use std::{net::SocketAddr, time::Duration};
+
+use socketioxide::{extract::SocketRef, socket::DisconnectReason, SocketIo};
+use tokio::net::TcpListener;
+use tracing::{info, Level};
+use tracing_subscriber::FmtSubscriber;
+
+fn on_connect(socket: SocketRef) {
+    info!("Socket.IO connected: {:?} {:?}", socket.ns(), socket.id);
+
+    socket.on_disconnect(|socket: SocketRef, reason: DisconnectReason| {
+        info!("Socket {} disconnected: {}", socket.id, reason);
+    });
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let subscriber = FmtSubscriber::builder()
+        .with_line_number(true)
+        .with_max_level(Level::DEBUG)
+        .finish();
+    tracing::subscriber::set_global_default(subscriber)?;
+
+    let (_, io) = SocketIo::builder()
+        .drain_timeout(Duration::from_secs(5))
+        .build_svc();
+
+    io.ns("/", on_connect);
+
+    let addr = SocketAddr::from(([127, 0, 0, 1], 3000));
+    let listener = TcpListener::bind(addr).await?;
+
+    info!("Starting server, press ctrl-c to stop it");
+    io.serve_with_graceful_shutdown(listener, async {
+        tokio::signal::ctrl_c().await.ok();
+        info!("Shutting down the server");
+    })
+    .await;
+
+    Ok(())
+}
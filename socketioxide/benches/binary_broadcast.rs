@@ -0,0 +1,215 @@
+//! Compares the allocations made by the binary emit path when broadcasting a large payload,
+//! between sharing the same `Bytes` buffer across all the recipients and copying it for each of them.
+//!
+//! Only the allocations of the thread calling `emit` are counted,
+//! the websocket writers run on the runtime workers and are not measured.
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+};
+
+use bytes::Bytes;
+use criterion::{
+    criterion_group, criterion_main,
+    measurement::{Measurement, ValueFormatter},
+    BatchSize, Criterion, Throughput,
+};
+use futures_util::{SinkExt, StreamExt};
+use hyper::server::conn::http1;
+use hyper_util::rt::TokioIo;
+use serde_json::Value;
+use socketioxide::SocketIo;
+use tokio::{net::TcpListener, runtime::Runtime};
+use tokio_tungstenite::{tungstenite::Message, MaybeTlsStream, WebSocketStream};
+
+const CLIENTS: usize = 16;
+const PAYLOAD_SIZE: usize = 256 * 1024;
+
+type WsStream = WebSocketStream<MaybeTlsStream<tokio::net::TcpStream>>;
+
+thread_local! {
+    static ALLOC_COUNT: Cell<u64> = const { Cell::new(0) };
+    static ALLOC_BYTES: Cell<u64> = const { Cell::new(0) };
+}
+
+/// A global allocator counting the allocations made by each thread.
+struct CountingAlloc;
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        record(layout.size());
+        System.alloc(layout)
+    }
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        record(layout.size());
+        System.alloc_zeroed(layout)
+    }
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        record(new_size);
+        System.realloc(ptr, layout, new_size)
+    }
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+fn record(size: usize) {
+    // The thread locals may already be destroyed when a thread is exiting.
+    ALLOC_COUNT.try_with(|c| c.set(c.get() + 1)).ok();
+    ALLOC_BYTES.try_with(|c| c.set(c.get() + size as u64)).ok();
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+/// A criterion [`Measurement`] of the allocations made by the current thread.
+enum Allocations {
+    Count,
+    Bytes,
+}
+
+impl Allocations {
+    fn current(&self) -> u64 {
+        match self {
+            Allocations::Count => ALLOC_COUNT.with(Cell::get),
+            Allocations::Bytes => ALLOC_BYTES.with(Cell::get),
+        }
+    }
+}
+
+impl Measurement for Allocations {
+    type Intermediate = u64;
+    type Value = u64;
+
+    fn start(&self) -> Self::Intermediate {
+        self.current()
+    }
+    fn end(&self, start: Self::Intermediate) -> Self::Value {
+        self.current() - start
+    }
+    fn add(&self, v1: &Self::Value, v2: &Self::Value) -> Self::Value {
+        v1 + v2
+    }
+    fn zero(&self) -> Self::Value {
+        0
+    }
+    fn to_f64(&self, value: &Self::Value) -> f64 {
+        *value as f64
+    }
+    fn formatter(&self) -> &dyn ValueFormatter {
+        self
+    }
+}
+
+impl ValueFormatter for Allocations {
+    fn scale_values(&self, _typical_value: f64, _values: &mut [f64]) -> &'static str {
+        match self {
+            Allocations::Count => "allocs",
+            Allocations::Bytes => "B",
+        }
+    }
+    fn scale_throughputs(
+        &self,
+        _typical_value: f64,
+        _throughput: &Throughput,
+        _values: &mut [f64],
+    ) -> &'static str {
+        self.scale_values(0.0, &mut [])
+    }
+    fn scale_for_machines(&self, values: &mut [f64]) -> &'static str {
+        self.scale_values(0.0, values)
+    }
+}
+
+/// Spawns a server and connects [`CLIENTS`] websocket clients to it.
+async fn setup() -> (SocketIo, Vec<WsStream>) {
+    let (svc, io) = SocketIo::new_svc();
+    io.ns("/", || {});
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        loop {
+            let (stream, _) = listener.accept().await.unwrap();
+            let svc = svc.clone();
+            tokio::spawn(
+                http1::Builder::new()
+                    .serve_connection(TokioIo::new(stream), svc)
+                    .with_upgrades(),
+            );
+        }
+    });
+
+    let mut clients = Vec::with_capacity(CLIENTS);
+    for _ in 0..CLIENTS {
+        let (mut ws, _) = tokio_tungstenite::connect_async(format!(
+            "ws://{addr}/socket.io/?EIO=4&transport=websocket"
+        ))
+        .await
+        .unwrap();
+        ws.send(Message::Text("40{}".to_string())).await.unwrap();
+        ws.next().await.unwrap().unwrap(); // engine.io open packet
+        ws.next().await.unwrap().unwrap(); // socket.io connect packet
+        clients.push(ws);
+    }
+    (io, clients)
+}
+
+/// Waits for every client to receive the text and binary frames of the previous broadcast.
+async fn drain(clients: &mut [WsStream]) {
+    for ws in clients {
+        ws.next().await.unwrap().unwrap(); // event packet
+        ws.next().await.unwrap().unwrap(); // binary attachment
+    }
+}
+
+fn bench_binary_broadcast(c: &mut Criterion<Allocations>, name: &str) {
+    let rt = Runtime::new().unwrap();
+    let (io, mut clients) = rt.block_on(setup());
+    let payload = Bytes::from(vec![0xab; PAYLOAD_SIZE]);
+    // Each iteration setup drains the broadcast of the previous one.
+    io.bin([payload.clone()]).emit("bin", Value::Null).unwrap();
+
+    let mut group = c.benchmark_group(format!("binary_broadcast/{name}"));
+    group.sample_size(10);
+    group.bench_function("shared_bytes", |b| {
+        b.iter_batched(
+            || rt.block_on(drain(&mut clients)),
+            |_| io.bin([payload.clone()]).emit("bin", Value::Null).unwrap(),
+            BatchSize::PerIteration,
+        )
+    });
+    group.bench_function("copy_per_recipient", |b| {
+        b.iter_batched(
+            || rt.block_on(drain(&mut clients)),
+            |_| {
+                for socket in io.sockets().unwrap() {
+                    socket
+                        .bin([payload.to_vec()])
+                        .emit("bin", Value::Null)
+                        .unwrap();
+                }
+            },
+            BatchSize::PerIteration,
+        )
+    });
+    group.finish();
+}
+
+fn bench_alloc_count(c: &mut Criterion<Allocations>) {
+    bench_binary_broadcast(c, "alloc_count")
+}
+fn bench_alloc_bytes(c: &mut Criterion<Allocations>) {
+    bench_binary_broadcast(c, "alloc_bytes")
+}
+
+criterion_group! {
+    name = count;
+    config = Criterion::default().with_measurement(Allocations::Count);
+    targets = bench_alloc_count
+}
+criterion_group! {
+    name = bytes;
+    config = Criterion::default().with_measurement(Allocations::Bytes);
+    targets = bench_alloc_bytes
+}
+criterion_main!(count, bytes);
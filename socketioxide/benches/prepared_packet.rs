@@ -0,0 +1,66 @@
+//! Compares broadcasting the same event to many rooms with `emit`, which serializes the data
+//! for each room, and with `emit_prepared`, which serializes it once.
+use bytes::Bytes;
+use criterion::{criterion_group, criterion_main, Criterion};
+use serde::Serialize;
+use socketioxide::SocketIo;
+
+const ROOMS: usize = 64;
+
+#[derive(Serialize)]
+struct Player {
+    id: u32,
+    name: String,
+    position: [f64; 3],
+    tags: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct Snapshot {
+    tick: u64,
+    players: Vec<Player>,
+    map: Bytes,
+}
+
+fn snapshot() -> Snapshot {
+    Snapshot {
+        tick: 42,
+        players: (0..100)
+            .map(|id| Player {
+                id,
+                name: format!("player{id}"),
+                position: [id as f64, id as f64 * 2.0, 0.5],
+                tags: vec!["red".into(), "online".into()],
+            })
+            .collect(),
+        map: Bytes::from(vec![0xab; 16 * 1024]),
+    }
+}
+
+fn bench_prepared_packet(c: &mut Criterion) {
+    let (_, io) = SocketIo::new_svc();
+    io.ns("/", || {});
+    let rooms: Vec<String> = (0..ROOMS).map(|i| format!("room{i}")).collect();
+    let data = snapshot();
+
+    let mut group = c.benchmark_group("prepared_packet");
+    group.bench_function("emit", |b| {
+        b.iter(|| {
+            for room in &rooms {
+                io.to(room.clone()).emit("snapshot", &data).unwrap();
+            }
+        })
+    });
+    group.bench_function("emit_prepared", |b| {
+        b.iter(|| {
+            let packet = io.prepare("snapshot", &data).unwrap();
+            for room in &rooms {
+                io.to(room.clone()).emit_prepared(&packet).unwrap();
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_prepared_packet);
+criterion_main!(benches);
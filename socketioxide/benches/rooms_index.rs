@@ -0,0 +1,61 @@
+//! Benchmarks the room membership lookups and updates of the [`LocalAdapter`]
+//! with many sockets spread across many rooms.
+use std::sync::Weak;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use socketioxide::{
+    adapter::{Adapter, LocalAdapter},
+    socket::Sid,
+};
+
+const SOCKETS: usize = 10_000;
+const ROOMS: usize = 1_000;
+const ROOMS_PER_SOCKET: usize = 5;
+
+fn socket_rooms(i: usize) -> Vec<String> {
+    (0..ROOMS_PER_SOCKET)
+        .map(|j| format!("room{}", (i * 7 + j * 131) % ROOMS))
+        .collect()
+}
+
+fn setup() -> (LocalAdapter, Vec<Sid>) {
+    let adapter = LocalAdapter::new(Weak::new());
+    let sids: Vec<Sid> = (0..SOCKETS).map(|_| Sid::new()).collect();
+    for (i, sid) in sids.iter().enumerate() {
+        adapter.add_all(*sid, socket_rooms(i)).unwrap();
+    }
+    (adapter, sids)
+}
+
+fn bench_rooms_index(c: &mut Criterion) {
+    let (adapter, sids) = setup();
+    let mut group = c.benchmark_group("rooms_index");
+    group.bench_function("socket_rooms", |b| {
+        let mut i = 0;
+        b.iter(|| {
+            i = (i + 1) % SOCKETS;
+            black_box(adapter.socket_rooms(sids[i]).unwrap());
+        })
+    });
+    group.bench_function("leave_one_and_rejoin", |b| {
+        let mut i = 0;
+        b.iter(|| {
+            i = (i + 1) % SOCKETS;
+            let room = socket_rooms(i).swap_remove(0);
+            adapter.del(sids[i], room.clone()).unwrap();
+            adapter.add_all(sids[i], room).unwrap();
+        })
+    });
+    group.bench_function("leave_all_and_rejoin", |b| {
+        let mut i = 0;
+        b.iter(|| {
+            i = (i + 1) % SOCKETS;
+            adapter.del_all(sids[i]).unwrap();
+            adapter.add_all(sids[i], socket_rooms(i)).unwrap();
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_rooms_index);
+criterion_main!(benches);
@@ -0,0 +1,77 @@
+use std::time::Duration;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use futures_util::{SinkExt, StreamExt};
+use hyper::server::conn::http1;
+use hyper_util::rt::TokioIo;
+use socketioxide::{SocketIo, SocketIoBuilder};
+use tokio::{net::TcpListener, runtime::Runtime};
+use tokio_tungstenite::tungstenite::Message;
+
+const PACKETS: usize = 500;
+
+/// Spawns a server with the given config and connects a websocket client to it.
+async fn setup(
+    builder: SocketIoBuilder,
+) -> (
+    SocketIo,
+    tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>,
+) {
+    let (svc, io) = builder.max_buffer_size(PACKETS * 2).build_svc();
+    io.ns("/", || {});
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        loop {
+            let (stream, _) = listener.accept().await.unwrap();
+            let svc = svc.clone();
+            tokio::spawn(
+                http1::Builder::new()
+                    .serve_connection(TokioIo::new(stream), svc)
+                    .with_upgrades(),
+            );
+        }
+    });
+
+    let (mut ws, _) = tokio_tungstenite::connect_async(format!(
+        "ws://{addr}/socket.io/?EIO=4&transport=websocket"
+    ))
+    .await
+    .unwrap();
+    ws.send(Message::Text("40{}".to_string())).await.unwrap();
+    ws.next().await.unwrap().unwrap(); // engine.io open packet
+    ws.next().await.unwrap().unwrap(); // socket.io connect packet
+    (io, ws)
+}
+
+fn bench_write_batch(c: &mut Criterion) {
+    let rt = Runtime::new().unwrap();
+    let mut group = c.benchmark_group("write_batch");
+    let configs = [
+        ("emit_burst_no_batch", SocketIo::builder()),
+        (
+            "emit_burst_batch_64",
+            SocketIo::builder().write_batch(Duration::from_micros(100), 64),
+        ),
+    ];
+    for (name, builder) in configs {
+        let (io, mut ws) = rt.block_on(setup(builder));
+        let socket = io.sockets().unwrap().pop().unwrap();
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                rt.block_on(async {
+                    for i in 0..PACKETS {
+                        socket.emit("msg", i).unwrap();
+                    }
+                    for _ in 0..PACKETS {
+                        ws.next().await.unwrap().unwrap();
+                    }
+                })
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_write_batch);
+criterion_main!(benches);
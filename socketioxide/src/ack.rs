@@ -5,8 +5,13 @@
 //! - [`AckStream`]: A [`Stream`]/[`Future`] of [`AckResponse`] received from the client.
 //! - [`AckResponse`]: An acknowledgement sent by the client.
 use std::{
+    collections::HashMap,
     fmt::Debug,
     pin::Pin,
+    sync::{
+        atomic::{AtomicBool, AtomicI64, Ordering},
+        Arc, Mutex, Weak,
+    },
     task::{Context, Poll},
     time::Duration,
 };
@@ -17,7 +22,10 @@ use futures_core::{FusedFuture, FusedStream, Future, Stream};
 use futures_util::stream::FuturesUnordered;
 use serde::de::DeserializeOwned;
 use serde_json::Value;
-use tokio::{sync::oneshot::Receiver, time::Timeout};
+use tokio::{
+    sync::oneshot::{Receiver, Sender},
+    time::Timeout,
+};
 
 use crate::{adapter::Adapter, errors::AckError, extract::SocketRef, packet::Packet, SocketError};
 
@@ -34,6 +42,81 @@ pub struct AckResponse<T> {
 
 pub(crate) type AckResult<T = Value> = Result<AckResponse<T>, AckError<()>>;
 
+/// The acknowledgements awaited from the client of a socket, by ack id.
+pub(crate) type AckMap = Mutex<HashMap<i64, Sender<AckResult<Value>>>>;
+
+/// A handle to an acknowledgement awaited from a single socket, obtained with [`AckStream::handle`].
+///
+/// It can inspect the pending acknowledgement and cancel it, e.g. when the request is abandoned,
+/// so that its [`AckStream`] resolves with an [`AckError::Cancelled`] and the pending entry of the socket is freed.
+/// A late acknowledgement from the client is then ignored.
+#[derive(Debug, Clone)]
+pub struct AckHandle {
+    acks: Weak<AckMap>,
+    state: Arc<AckHandleState>,
+}
+
+#[derive(Debug)]
+struct AckHandleState {
+    /// The ack id of the last attempt, it changes when the message is re-emitted with retries
+    id: AtomicI64,
+    cancelled: AtomicBool,
+}
+
+impl AckHandle {
+    pub(crate) fn new(acks: &Arc<AckMap>, id: i64) -> Self {
+        Self {
+            acks: Arc::downgrade(acks),
+            state: Arc::new(AckHandleState {
+                id: AtomicI64::new(id),
+                cancelled: AtomicBool::new(false),
+            }),
+        }
+    }
+
+    /// Sets the ack id of a new attempt.
+    pub(crate) fn set_id(&self, id: i64) {
+        self.state.id.store(id, Ordering::SeqCst);
+    }
+
+    /// Returns true if the acknowledgement was cancelled.
+    pub(crate) fn cancelled(&self) -> bool {
+        self.state.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// The ack id of the message sent to the client, of the last attempt if it was retried.
+    pub fn id(&self) -> i64 {
+        self.state.id.load(Ordering::SeqCst)
+    }
+
+    /// Returns true if the acknowledgement is still awaited from the client:
+    /// it is neither received, timed out, cancelled, nor dropped.
+    pub fn is_pending(&self) -> bool {
+        let Some(acks) = self.acks.upgrade() else {
+            return false;
+        };
+        let acks = acks.lock().unwrap();
+        !self.cancelled() && acks.get(&self.id()).is_some_and(|tx| !tx.is_closed())
+    }
+
+    /// Cancels the acknowledgement: its [`AckStream`] resolves with an [`AckError::Cancelled`]
+    /// and no more attempt is made if it was emitted with retries.
+    ///
+    /// Returns false if the acknowledgement was not pending anymore.
+    pub fn cancel(&self) -> bool {
+        let pending = self.is_pending();
+        self.state.cancelled.store(true, Ordering::SeqCst);
+        let tx = self
+            .acks
+            .upgrade()
+            .and_then(|acks| acks.lock().unwrap().remove(&self.id()));
+        if let Some(tx) = tx {
+            tx.send(Err(AckError::Cancelled)).ok();
+        }
+        pending
+    }
+}
+
 pin_project_lite::pin_project! {
     /// A [`Future`] of [`AckResponse`] received from the client with its corresponding [`Sid`].
     /// It is used internally by [`AckStream`] and **should not** be used directly.
@@ -77,8 +160,8 @@ pin_project_lite::pin_project! {
     /// If the packet encoding failed an [`serde_json::Error`] is **immediately** returned.
     ///
     /// If the client didn't respond before the timeout, the [`AckStream`] will yield
-    /// an [`AckError::Timeout`]. If the data sent by the client is not deserializable as `T`,
-    /// an [`AckError::Serde`] will be yielded.
+    /// an [`AckError::Timeout`], or the value set with [`AckStream::on_timeout_default`].
+    /// If the data sent by the client is not deserializable as `T`, an [`AckError::Serde`] will be yielded.
     ///
     /// An [`AckStream`] can be created from:
     /// * The [`SocketRef::emit_with_ack`] method, in this case there will be only one [`AckResponse`].
@@ -113,10 +196,14 @@ pin_project_lite::pin_project! {
     pub struct AckStream<T> {
         #[pin]
         inner: AckInnerStream,
+        timeout_default: Option<TimeoutDefault<T>>,
+        handle: Option<AckHandle>,
         _marker: std::marker::PhantomData<T>,
     }
 }
 
+type TimeoutDefault<T> = Box<dyn Fn() -> T + Send + Sync + 'static>;
+
 pin_project_lite::pin_project! {
     #[allow(missing_docs)]
     #[project = InnerProj]
@@ -143,7 +230,7 @@ impl AckInnerStream {
     /// The [`Packet`] is sent to all the sockets and the [`AckInnerStream`] will wait
     /// for an acknowledgement from each socket.
     ///
-    /// The [`AckInnerStream`] will wait for the default timeout specified in the config
+    /// The [`AckInnerStream`] will wait for the default ack timeout of each socket
     /// (5s by default) if no custom timeout is specified.
     pub fn broadcast<A: Adapter>(
         packet: Packet<'static>,
@@ -156,9 +243,8 @@ impl AckInnerStream {
             return AckInnerStream::Stream { rxs };
         }
 
-        let duration =
-            duration.unwrap_or_else(|| sockets.first().unwrap().get_io().config().ack_timeout);
         for socket in sockets {
+            let duration = duration.unwrap_or_else(|| socket.ack_timeout());
             let rx = socket.send_with_ack(packet.clone());
             rxs.push(AckResultWithId {
                 result: tokio::time::timeout(duration, rx),
@@ -245,15 +331,88 @@ impl FusedFuture for AckInnerStream {
 
 // ==== impl AckStream ====
 
+impl<T> AckStream<T> {
+    /// Resolves the acknowledgements that timed out with the given value instead of an [`AckError::Timeout`].
+    /// When broadcasting, each socket that didn't respond in time yields a clone of the value.
+    ///
+    /// It applies to the default ack timeout as well as to the one set with the `timeout()` operator.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use socketioxide::{SocketIo, extract::SocketRef};
+    /// # use std::time::Duration;
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.ns("/", |socket: SocketRef| async move {
+    ///     let ack = socket
+    ///         .timeout(Duration::from_secs(1))
+    ///         .emit_with_ack::<_, bool>("ready?", ())
+    ///         .unwrap()
+    ///         .on_timeout_default(false)
+    ///         .await;
+    ///     let ready = ack.map(|ack| ack.data).unwrap_or(false);
+    /// });
+    /// ```
+    pub fn on_timeout_default(mut self, value: T) -> Self
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        self.timeout_default = Some(Box::new(move || value.clone()));
+        self
+    }
+
+    /// Returns an [`AckHandle`] to inspect and cancel the acknowledgement.
+    ///
+    /// It is only available for the messages emitted to a single socket with `emit_with_ack`,
+    /// it is `None` for the broadcasts.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use socketioxide::{SocketIo, extract::SocketRef};
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.ns("/", |socket: SocketRef| async move {
+    ///     let ack = socket.emit_with_ack::<_, String>("question", "ready?").unwrap();
+    ///     let handle = ack.handle().unwrap();
+    ///     socket.on("abort", move || {
+    ///         handle.cancel();
+    ///     });
+    ///     // Yields an `AckError::Cancelled` if the client emitted "abort" before answering
+    ///     let answer = ack.await;
+    /// });
+    /// ```
+    pub fn handle(&self) -> Option<AckHandle> {
+        self.handle.clone()
+    }
+
+    pub(crate) fn with_handle(mut self, handle: AckHandle) -> Self {
+        self.handle = Some(handle);
+        self
+    }
+}
+
+/// Replaces a timeout error with the default value, if there is one.
+fn apply_timeout_default<T>(
+    ack: AckResult<T>,
+    default: &Option<TimeoutDefault<T>>,
+) -> AckResult<T> {
+    match (ack, default) {
+        (Err(AckError::Timeout), Some(default)) => Ok(AckResponse {
+            data: default(),
+            binary: Vec::new(),
+        }),
+        (ack, _) => ack,
+    }
+}
+
 impl<T: DeserializeOwned> Stream for AckStream<T> {
     type Item = (Sid, AckResult<T>);
 
     #[inline]
     fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        self.project()
-            .inner
+        let proj = self.project();
+        let default = &*proj.timeout_default;
+        proj.inner
             .poll_next(cx)
-            .map(|v| v.map(|(s, v)| (s, map_ack_response(v))))
+            .map(|v| v.map(|(s, v)| (s, apply_timeout_default(map_ack_response(v), default))))
     }
 
     #[inline(always)]
@@ -274,7 +433,11 @@ impl<T: DeserializeOwned> Future for AckStream<T> {
 
     #[inline]
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
-        self.project().inner.poll(cx).map(map_ack_response)
+        let proj = self.project();
+        let default = &*proj.timeout_default;
+        proj.inner
+            .poll(cx)
+            .map(|v| apply_timeout_default(map_ack_response(v), default))
     }
 }
 
@@ -289,6 +452,8 @@ impl<T> From<AckInnerStream> for AckStream<T> {
     fn from(inner: AckInnerStream) -> Self {
         Self {
             inner,
+            timeout_default: None,
+            handle: None,
             _marker: std::marker::PhantomData,
         }
     }
@@ -546,4 +711,42 @@ mod test {
 
         assert!(matches!(stream.await.unwrap_err(), AckError::Timeout));
     }
+
+    #[tokio::test]
+    async fn ack_fut_with_timeout_default() {
+        let (_tx, rx) = tokio::sync::oneshot::channel();
+        let sid = Sid::new();
+        let stream: AckStream<String> =
+            AckInnerStream::send(rx, Duration::from_millis(10), sid).into();
+
+        let ack = stream.on_timeout_default("default".into()).await.unwrap();
+        assert_eq!(ack.data, "default");
+        assert!(ack.binary.is_empty());
+    }
+
+    #[tokio::test]
+    async fn broadcast_ack_with_timeout_default() {
+        let socket = create_socket();
+        let socket2 = create_socket();
+        let mut packet = Packet::event("/", "test", "test".into());
+        packet.inner.set_ack_id(1);
+        let (sid1, sid2) = (socket.id, socket2.id);
+        let socks = vec![socket.clone().into(), socket2.clone().into()];
+        let stream: AckStream<String> =
+            AckInnerStream::broadcast(packet, socks, Some(Duration::from_millis(10))).into();
+        let stream = stream.on_timeout_default("default".into());
+
+        socket
+            .recv(Packet::ack("test", "test".into(), 1).inner)
+            .unwrap();
+
+        futures_util::pin_mut!(stream);
+
+        // Only the sockets that didn't respond in time yield the default value
+        let (id, ack) = stream.next().await.unwrap();
+        assert_eq!((id, ack.unwrap().data.as_str()), (sid1, "test"));
+        let (id, ack) = stream.next().await.unwrap();
+        assert_eq!((id, ack.unwrap().data.as_str()), (sid2, "default"));
+        assert!(stream.next().await.is_none());
+    }
 }
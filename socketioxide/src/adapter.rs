@@ -8,10 +8,19 @@ use std::{
     collections::{HashMap, HashSet},
     convert::Infallible,
     sync::{RwLock, Weak},
-    time::Duration,
+    time::{Duration, Instant},
 };
+#[cfg(feature = "extensions")]
+use std::{fmt, sync::Arc};
 
 use engineioxide::sid::Sid;
+use futures_util::{
+    future::{self, BoxFuture},
+    stream::{self, BoxStream, StreamExt},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::sync::broadcast;
 
 use crate::{
     ack::AckInnerStream,
@@ -20,9 +29,13 @@ use crate::{
     ns::Namespace,
     operators::RoomParam,
     packet::Packet,
+    remote::{SocketRequest, SocketResponse},
     DisconnectError,
 };
 
+#[cfg(feature = "extensions")]
+use crate::extensions::Extensions;
+
 /// A room identifier
 pub type Room = Cow<'static, str>;
 
@@ -41,12 +54,117 @@ pub struct BroadcastOptions {
     /// The flags to apply to the broadcast.
     pub flags: HashSet<BroadcastFlags>,
     /// The rooms to broadcast to.
+    /// The selected sockets are the union of the sockets of these rooms.
     pub rooms: HashSet<Room>,
+    /// The rooms the sockets must **all** be in to be selected (intersection).
+    ///
+    /// If `rooms` is not empty, the sockets selected with `rooms` are narrowed down to the ones
+    /// that are also in all of these rooms.
+    pub rooms_all: HashSet<Room>,
+    /// The socket ids to broadcast to, in addition to the sockets of `rooms`.
+    ///
+    /// The ids may belong to sockets connected to other servers,
+    /// adapters sharing the state between multiple servers should forward them.
+    pub sids: HashSet<Sid>,
     /// The rooms to exclude from the broadcast.
     pub except: HashSet<Room>,
     /// The socket id of the sender.
     pub sid: Option<Sid>,
+    /// The metadata the sockets must **all** match to be selected, set with the `where_meta()` operator.
+    ///
+    /// The sockets selected with the other options are narrowed down to the ones
+    /// with all of these metadata values, see [`Adapter::set_meta`].
+    pub meta: HashMap<String, String>,
+    /// The instant the sockets must have joined the rooms after to be selected,
+    /// set with the `joined_after()` operator.
+    ///
+    /// The sockets selected with the other options are narrowed down to the ones that joined
+    /// one of `rooms` after this instant, or any room if `rooms` is empty.
+    pub joined_after: Option<Instant>,
+    /// A predicate over the [`Extensions`] of the sockets that must be matched to be selected.
+    ///
+    /// The predicate can only be evaluated on the sockets connected to this node.
+    /// Adapters sharing the state between multiple servers should **skip the remote sockets**
+    /// when a filter is set, unless they replicate the socket extensions.
+    #[cfg_attr(docsrs, doc(cfg(feature = "extensions")))]
+    #[cfg(feature = "extensions")]
+    pub filter: Option<SocketFilter>,
+    /// A predicate over the [`Extensions`] of the selected sockets expected to acknowledge a broadcast,
+    /// set with the `ack_filter()` operator.
+    ///
+    /// With [`Adapter::broadcast_with_ack`], the packet is still sent to all the selected sockets,
+    /// but only the ones matching the predicate are asked for an acknowledgement.
+    /// As for `filter`, it can only be evaluated on the sockets connected to this node.
+    #[cfg_attr(docsrs, doc(cfg(feature = "extensions")))]
+    #[cfg(feature = "extensions")]
+    pub ack_filter: Option<SocketFilter>,
+}
+
+/// A predicate over the [`Extensions`] of a socket, set with the `filter()` operator.
+#[cfg_attr(docsrs, doc(cfg(feature = "extensions")))]
+#[cfg(feature = "extensions")]
+#[derive(Clone)]
+pub struct SocketFilter(Arc<dyn Fn(&Extensions) -> bool + Send + Sync + 'static>);
+
+#[cfg(feature = "extensions")]
+impl SocketFilter {
+    /// Creates a new filter from a predicate.
+    pub fn new(predicate: impl Fn(&Extensions) -> bool + Send + Sync + 'static) -> Self {
+        Self(Arc::new(predicate))
+    }
+
+    /// Returns a filter that matches the sockets matching both `self` and `other`.
+    pub fn and(self, other: SocketFilter) -> Self {
+        Self::new(move |ext| (self.0)(ext) && (other.0)(ext))
+    }
+
+    /// Returns true if the socket extensions match the predicate.
+    pub fn matches(&self, extensions: &Extensions) -> bool {
+        (self.0)(extensions)
+    }
+}
+
+#[cfg(feature = "extensions")]
+impl fmt::Debug for SocketFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SocketFilter").finish_non_exhaustive()
+    }
+}
+/// The kind of a [`MembershipEvent`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MembershipKind {
+    /// The socket joined the room.
+    Join,
+    /// The socket left the room.
+    Leave,
+}
+
+/// A change of the room membership of a socket, yielded by [`Adapter::subscribe_changes`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MembershipEvent {
+    /// Whether the socket joined or left the room.
+    pub kind: MembershipKind,
+    /// The socket id.
+    pub sid: Sid,
+    /// The room joined or left.
+    pub room: Room,
 }
+
+/// A serializable snapshot of the room memberships and the metadata of a namespace,
+/// returned by [`Adapter::export_state`] and given to [`Adapter::import_state`].
+///
+/// The sockets themselves are not part of the snapshot, only their ids.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AdapterState {
+    /// The members of each room.
+    pub rooms: HashMap<Room, HashSet<Sid>>,
+    /// The metadata of each socket, see [`Adapter::set_meta`].
+    pub meta: HashMap<Sid, HashMap<String, String>>,
+    /// The metadata of each room, see [`Adapter::set_room_meta`].
+    #[serde(default)]
+    pub room_meta: HashMap<Room, HashMap<String, Value>>,
+}
+
 //TODO: Make an AsyncAdapter trait
 /// An adapter is responsible for managing the state of the server.
 /// This adapter can be implemented to share the state between multiple servers.
@@ -69,8 +187,31 @@ pub trait Adapter: std::fmt::Debug + Send + Sync + 'static {
     fn server_count(&self) -> Result<u16, Self::Error>;
 
     /// Adds the socket to all the rooms.
+    ///
+    /// The adapter should call `notify_room_created` on its namespace for each room the socket is the first member of.
     fn add_all(&self, sid: Sid, rooms: impl RoomParam) -> Result<(), Self::Error>;
+    /// Adds the socket to the room only if it has less than `max` members, and returns whether it is a member.
+    ///
+    /// The size check and the insertion should be atomic, so that concurrent joins never exceed `max`.
+    /// A socket already in the room stays in it and `true` is returned.
+    ///
+    /// The default implementation checks the size with [`Adapter::sockets`] and then calls [`Adapter::add_all`],
+    /// it is **not atomic** and should be overridden.
+    fn try_add(&self, sid: Sid, room: Room, max: usize) -> Result<bool, Self::Error> {
+        let members = self.sockets(room.clone())?;
+        if members.contains(&sid) {
+            return Ok(true);
+        }
+        if members.len() >= max {
+            return Ok(false);
+        }
+        self.add_all(sid, room)?;
+        Ok(true)
+    }
     /// Removes the socket from the rooms.
+    ///
+    /// The adapter should call `notify_room_empty` on its namespace for each room the socket was the last member of,
+    /// here and in [`Adapter::del_all`].
     fn del(&self, sid: Sid, rooms: impl RoomParam) -> Result<(), Self::Error>;
     /// Removes the socket from all the rooms.
     fn del_all(&self, sid: Sid) -> Result<(), Self::Error>;
@@ -89,9 +230,93 @@ pub trait Adapter: std::fmt::Debug + Send + Sync + 'static {
     /// Returns the sockets ids that match the [`BroadcastOptions`].
     fn sockets(&self, rooms: impl RoomParam) -> Result<Vec<Sid>, Self::Error>;
 
+    /// Returns a stream yielding all the socket ids of the namespace.
+    ///
+    /// The default implementation collects the ids with [`Adapter::sockets`].
+    fn sids_stream(&self) -> Result<BoxStream<'static, Sid>, Self::Error> {
+        let sids = self.sockets(Vec::<Room>::new())?;
+        Ok(stream::iter(sids).boxed())
+    }
+
+    /// Returns a stream of the room membership changes of the namespace.
+    /// A [`MembershipEvent`] is yielded every time a socket joins or leaves a room.
+    ///
+    /// Adapters sharing the state between multiple servers should also yield the changes of the other servers.
+    /// If the subscriber is too slow, the oldest changes may be skipped.
+    ///
+    /// The default implementation returns a stream that ends immediately.
+    fn subscribe_changes(&self) -> Result<BoxStream<'static, MembershipEvent>, Self::Error> {
+        Ok(stream::empty().boxed())
+    }
+
     /// Returns the rooms of the socket.
     fn socket_rooms(&self, sid: Sid) -> Result<Vec<Room>, Self::Error>;
 
+    /// Sets the metadata `key` of the socket to `value`, replacing the previous value.
+    ///
+    /// The adapter should index the metadata so that the sockets matching the
+    /// [`BroadcastOptions::meta`] can be selected without iterating over all the sockets.
+    ///
+    /// The default implementation does nothing, the metadata are still stored on the socket.
+    fn set_meta(&self, sid: Sid, key: &str, value: &str) -> Result<(), Self::Error> {
+        let _ = (sid, key, value);
+        Ok(())
+    }
+
+    /// Removes all the metadata of the socket, it is called when the socket disconnects.
+    ///
+    /// The default implementation does nothing.
+    fn del_meta(&self, sid: Sid) -> Result<(), Self::Error> {
+        let _ = sid;
+        Ok(())
+    }
+
+    /// Sets the metadata `key` of the room to `value`, replacing the previous value.
+    ///
+    /// The metadata of a room do not depend on its members, they are kept until they are removed
+    /// with [`Adapter::del_room_meta`]. Adapters sharing the state between multiple servers
+    /// should replicate them to the other servers.
+    ///
+    /// The default implementation does not store the metadata.
+    fn set_room_meta(&self, room: Room, key: &str, value: Value) -> Result<(), Self::Error> {
+        let _ = (room, key, value);
+        Ok(())
+    }
+
+    /// Returns the metadata of the room.
+    ///
+    /// The default implementation returns no metadata.
+    fn room_meta(&self, room: &str) -> Result<HashMap<String, Value>, Self::Error> {
+        let _ = room;
+        Ok(HashMap::new())
+    }
+
+    /// Removes the metadata `key` of the room, or all of its metadata if `key` is `None`.
+    ///
+    /// The default implementation does nothing.
+    fn del_room_meta(&self, room: &str, key: Option<&str>) -> Result<(), Self::Error> {
+        let _ = (room, key);
+        Ok(())
+    }
+
+    /// Fetches the rooms or the data of a socket from the node owning it, see [`RemoteSocket`].
+    ///
+    /// Adapters sharing the state between multiple servers should send the [`SocketRequest`] to the
+    /// other servers. The server owning the socket answers it with `recv_socket_request` on its namespace.
+    /// Returns `None` if no server owns the socket.
+    ///
+    /// The sockets of the current server are answered by its namespace without calling this method.
+    /// The default implementation returns `None`, as for the [`LocalAdapter`] which has no other servers.
+    ///
+    /// [`RemoteSocket`]: crate::remote::RemoteSocket
+    fn fetch_socket(
+        &self,
+        req: SocketRequest,
+    ) -> BoxFuture<'static, Result<Option<SocketResponse>, Self::Error>> {
+        let _ = req;
+        Box::pin(future::ready(Ok(None)))
+    }
+
     /// Returns the sockets that match the [`BroadcastOptions`].
     fn fetch_sockets(&self, opts: BroadcastOptions) -> Result<Vec<SocketRef<Self>>, Self::Error>
     where
@@ -110,17 +335,65 @@ pub trait Adapter: std::fmt::Debug + Send + Sync + 'static {
     /// Returns all the rooms for this adapter.
     fn rooms(&self) -> Result<Vec<Room>, Self::Error>;
 
+    /// Emits an event to the other servers of the cluster (not to the clients).
+    /// When receiving it, the other servers should call `recv_server_event` on their namespace.
+    ///
+    /// The default implementation does nothing, as for the [`LocalAdapter`] which has no other servers.
+    fn server_side_emit(&self, event: &str, data: Value) -> Result<(), Self::Error> {
+        let _ = (event, data);
+        Ok(())
+    }
+
+    /// Returns a snapshot of the rooms and the metadata of the namespace.
+    ///
+    /// The default implementation collects the rooms with [`Adapter::rooms`] and [`Adapter::sockets`],
+    /// without the metadata.
+    fn export_state(&self) -> Result<AdapterState, Self::Error> {
+        let mut state = AdapterState::default();
+        for room in self.rooms()? {
+            let members = self.sockets(room.clone())?;
+            state.rooms.insert(room, members.into_iter().collect());
+        }
+        Ok(state)
+    }
+
+    /// Replaces the rooms and the metadata of the namespace with the snapshot, e.g. to seed a fresh adapter
+    /// with the state exported from another instance.
+    ///
+    /// No membership change or room hook is notified.
+    ///
+    /// The default implementation only adds the members of the rooms with [`Adapter::add_all`],
+    /// the previous rooms are kept and the metadata are not imported.
+    fn import_state(&self, state: AdapterState) -> Result<(), Self::Error> {
+        for (room, members) in state.rooms {
+            for sid in members {
+                self.add_all(sid, room.clone())?;
+            }
+        }
+        Ok(())
+    }
+
     //TODO: implement
-    // fn server_side_emit(&self, packet: Packet, opts: BroadcastOptions) -> Result<u64, Error>;
     // fn persist_session(&self, sid: i64);
     // fn restore_session(&self, sid: i64) -> Session;
 }
 
+/// The number of membership changes buffered for each [`LocalAdapter::subscribe_changes`] subscriber.
+const CHANGES_BUFFER_SIZE: usize = 1024;
+
 /// The default adapter. Store the state in memory.
 #[derive(Debug)]
 pub struct LocalAdapter {
     rooms: RwLock<HashMap<Room, HashSet<Sid>>>,
+    /// The reverse index of `rooms`, with the rooms of each socket and the instant it joined them.
+    /// It is only updated while holding the `rooms` write lock so that both indexes stay consistent.
+    sockets: RwLock<HashMap<Sid, HashMap<Room, Instant>>>,
+    /// The sockets indexed by metadata key and value
+    meta: RwLock<HashMap<String, HashMap<String, HashSet<Sid>>>>,
+    /// The metadata of the rooms, indexed by room and key
+    room_meta: RwLock<HashMap<Room, HashMap<String, Value>>>,
     ns: Weak<Namespace<Self>>,
+    changes: broadcast::Sender<MembershipEvent>,
 }
 
 impl From<Infallible> for AdapterError {
@@ -135,7 +408,11 @@ impl Adapter for LocalAdapter {
     fn new(ns: Weak<Namespace<Self>>) -> Self {
         Self {
             rooms: HashMap::new().into(),
+            sockets: HashMap::new().into(),
+            meta: HashMap::new().into(),
+            room_meta: HashMap::new().into(),
             ns,
+            changes: broadcast::channel(CHANGES_BUFFER_SIZE).0,
         }
     }
 
@@ -149,6 +426,9 @@ impl Adapter for LocalAdapter {
         let mut rooms = self.rooms.write().unwrap();
         rooms.clear();
         rooms.shrink_to_fit();
+        let mut sockets = self.sockets.write().unwrap();
+        sockets.clear();
+        sockets.shrink_to_fit();
         Ok(())
     }
 
@@ -157,28 +437,102 @@ impl Adapter for LocalAdapter {
     }
 
     fn add_all(&self, sid: Sid, rooms: impl RoomParam) -> Result<(), Infallible> {
+        let mut created = Vec::new();
         let mut rooms_map = self.rooms.write().unwrap();
+        let mut sockets_map = self.sockets.write().unwrap();
+        let now = Instant::now();
         for room in rooms.into_room_iter() {
-            rooms_map.entry(room).or_default().insert(sid);
+            let sids = rooms_map.entry(room.clone()).or_default();
+            let was_empty = sids.is_empty();
+            if sids.insert(sid) {
+                if was_empty {
+                    created.push(room.clone());
+                }
+                sockets_map
+                    .entry(sid)
+                    .or_default()
+                    .insert(room.clone(), now);
+                self.notify_change(MembershipKind::Join, sid, room);
+            }
         }
+        drop(sockets_map);
+        drop(rooms_map);
+        self.notify_rooms(&created, Namespace::notify_room_created);
         Ok(())
     }
 
+    fn try_add(&self, sid: Sid, room: Room, max: usize) -> Result<bool, Infallible> {
+        let mut rooms_map = self.rooms.write().unwrap();
+        let sids = rooms_map.entry(room.clone()).or_default();
+        if sids.contains(&sid) {
+            return Ok(true);
+        }
+        if sids.len() >= max {
+            if sids.is_empty() {
+                rooms_map.remove(&room);
+            }
+            return Ok(false);
+        }
+        let created = sids.is_empty();
+        sids.insert(sid);
+        self.sockets
+            .write()
+            .unwrap()
+            .entry(sid)
+            .or_default()
+            .insert(room.clone(), Instant::now());
+        self.notify_change(MembershipKind::Join, sid, room.clone());
+        drop(rooms_map);
+        if created {
+            self.notify_rooms(&[room], Namespace::notify_room_created);
+        }
+        Ok(true)
+    }
+
     fn del(&self, sid: Sid, rooms: impl RoomParam) -> Result<(), Infallible> {
+        let mut emptied = Vec::new();
         let mut rooms_map = self.rooms.write().unwrap();
+        let mut sockets_map = self.sockets.write().unwrap();
         for room in rooms.into_room_iter() {
-            if let Some(room) = rooms_map.get_mut(&room) {
-                room.remove(&sid);
+            let Some(sids) = rooms_map.get_mut(&room) else {
+                continue;
+            };
+            if sids.remove(&sid) {
+                if sids.is_empty() {
+                    emptied.push(room.clone());
+                }
+                if let Some(socket_rooms) = sockets_map.get_mut(&sid) {
+                    socket_rooms.remove(&room);
+                    if socket_rooms.is_empty() {
+                        sockets_map.remove(&sid);
+                    }
+                }
+                self.notify_change(MembershipKind::Leave, sid, room);
             }
         }
+        drop(sockets_map);
+        drop(rooms_map);
+        self.notify_rooms(&emptied, Namespace::notify_room_empty);
         Ok(())
     }
 
     fn del_all(&self, sid: Sid) -> Result<(), Infallible> {
+        let mut emptied = Vec::new();
         let mut rooms_map = self.rooms.write().unwrap();
-        for room in rooms_map.values_mut() {
-            room.remove(&sid);
+        let socket_rooms = self.sockets.write().unwrap().remove(&sid);
+        for room in socket_rooms.into_iter().flat_map(HashMap::into_keys) {
+            let Some(sids) = rooms_map.get_mut(&room) else {
+                continue;
+            };
+            if sids.remove(&sid) {
+                if sids.is_empty() {
+                    emptied.push(room.clone());
+                }
+                self.notify_change(MembershipKind::Leave, sid, room);
+            }
         }
+        drop(rooms_map);
+        self.notify_rooms(&emptied, Namespace::notify_room_empty);
         Ok(())
     }
 
@@ -189,7 +543,7 @@ impl Adapter for LocalAdapter {
         tracing::debug!("broadcasting packet to {} sockets", sockets.len());
         let errors: Vec<_> = sockets
             .into_iter()
-            .filter_map(|socket| socket.send(packet.clone()).err())
+            .filter_map(|socket| socket.send_broadcast(packet.clone()).err())
             .collect();
         if errors.is_empty() {
             Ok(())
@@ -204,7 +558,26 @@ impl Adapter for LocalAdapter {
         opts: BroadcastOptions,
         timeout: Option<Duration>,
     ) -> AckInnerStream {
+        #[cfg(feature = "extensions")]
+        let ack_filter = opts.ack_filter.clone();
         let sockets = self.apply_opts(opts);
+        #[cfg(feature = "extensions")]
+        let sockets = match ack_filter {
+            Some(filter) => {
+                // The other sockets receive the packet without being asked for an acknowledgement
+                let (acked, others): (Vec<_>, Vec<_>) = sockets
+                    .into_iter()
+                    .partition(|socket| filter.matches(&socket.extensions));
+                for socket in others {
+                    if let Err(_e) = socket.send(packet.clone()) {
+                        #[cfg(feature = "tracing")]
+                        tracing::debug!(?socket.id, "error while broadcasting packet: {_e:?}");
+                    }
+                }
+                acked
+            }
+            None => sockets,
+        };
         #[cfg(feature = "tracing")]
         tracing::debug!(
             "broadcasting packet to {} sockets: {:?}",
@@ -224,14 +597,91 @@ impl Adapter for LocalAdapter {
             .collect())
     }
 
-    //TODO: make this operation O(1)
+    /// Only the socket ids are copied when the stream is created, the sockets are not retained.
+    fn sids_stream(&self) -> Result<BoxStream<'static, Sid>, Infallible> {
+        let sids = self
+            .ns
+            .upgrade()
+            .map(|ns| ns.get_sids())
+            .unwrap_or_default();
+        Ok(stream::iter(sids).boxed())
+    }
+
+    /// Skips the changes missed when the subscriber lags behind by more than [`CHANGES_BUFFER_SIZE`] changes.
+    fn subscribe_changes(&self) -> Result<BoxStream<'static, MembershipEvent>, Infallible> {
+        let stream = stream::unfold(self.changes.subscribe(), |mut rx| async move {
+            loop {
+                match rx.recv().await {
+                    Ok(event) => return Some((event, rx)),
+                    Err(broadcast::error::RecvError::Lagged(_)) => (),
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                }
+            }
+        });
+        Ok(stream.boxed())
+    }
+
     fn socket_rooms(&self, sid: Sid) -> Result<Vec<Cow<'static, str>>, Infallible> {
-        let rooms_map = self.rooms.read().unwrap();
-        Ok(rooms_map
-            .iter()
-            .filter(|(_, sockets)| sockets.contains(&sid))
-            .map(|(room, _)| room.clone())
-            .collect())
+        let sockets_map = self.sockets.read().unwrap();
+        Ok(sockets_map
+            .get(&sid)
+            .map(|rooms| rooms.keys().cloned().collect())
+            .unwrap_or_default())
+    }
+
+    fn set_meta(&self, sid: Sid, key: &str, value: &str) -> Result<(), Infallible> {
+        let mut meta = self.meta.write().unwrap();
+        let values = meta.entry(key.to_string()).or_default();
+        values.retain(|_, sids| {
+            sids.remove(&sid);
+            !sids.is_empty()
+        });
+        values.entry(value.to_string()).or_default().insert(sid);
+        Ok(())
+    }
+
+    fn del_meta(&self, sid: Sid) -> Result<(), Infallible> {
+        let mut meta = self.meta.write().unwrap();
+        meta.retain(|_, values| {
+            values.retain(|_, sids| {
+                sids.remove(&sid);
+                !sids.is_empty()
+            });
+            !values.is_empty()
+        });
+        Ok(())
+    }
+
+    fn set_room_meta(&self, room: Room, key: &str, value: Value) -> Result<(), Infallible> {
+        let mut room_meta = self.room_meta.write().unwrap();
+        room_meta
+            .entry(room)
+            .or_default()
+            .insert(key.to_string(), value);
+        Ok(())
+    }
+
+    fn room_meta(&self, room: &str) -> Result<HashMap<String, Value>, Infallible> {
+        let room_meta = self.room_meta.read().unwrap();
+        Ok(room_meta.get(room).cloned().unwrap_or_default())
+    }
+
+    fn del_room_meta(&self, room: &str, key: Option<&str>) -> Result<(), Infallible> {
+        let mut room_meta = self.room_meta.write().unwrap();
+        match key {
+            Some(key) => {
+                if let Some(values) = room_meta.get_mut(room) {
+                    values.remove(key);
+                    if values.is_empty() {
+                        room_meta.remove(room);
+                    }
+                }
+            }
+            None => {
+                room_meta.remove(room);
+            }
+        }
+        Ok(())
     }
 
     fn fetch_sockets(&self, opts: BroadcastOptions) -> Result<Vec<SocketRef<Self>>, Infallible> {
@@ -273,43 +723,197 @@ impl Adapter for LocalAdapter {
     fn rooms(&self) -> Result<Vec<Room>, Self::Error> {
         Ok(self.rooms.read().unwrap().keys().cloned().collect())
     }
+
+    fn export_state(&self) -> Result<AdapterState, Infallible> {
+        let rooms = self.rooms.read().unwrap();
+        let rooms = rooms
+            .iter()
+            .filter(|(_, sids)| !sids.is_empty())
+            .map(|(room, sids)| (room.clone(), sids.clone()))
+            .collect();
+        let mut meta: HashMap<Sid, HashMap<String, String>> = HashMap::new();
+        for (key, values) in self.meta.read().unwrap().iter() {
+            for (value, sids) in values {
+                for sid in sids {
+                    meta.entry(*sid)
+                        .or_default()
+                        .insert(key.clone(), value.clone());
+                }
+            }
+        }
+        let room_meta = self.room_meta.read().unwrap().clone();
+        Ok(AdapterState {
+            rooms,
+            meta,
+            room_meta,
+        })
+    }
+
+    fn import_state(&self, state: AdapterState) -> Result<(), Infallible> {
+        let mut rooms_map = self.rooms.write().unwrap();
+        let mut sockets_map = self.sockets.write().unwrap();
+        sockets_map.clear();
+        // The join instants are not part of the snapshot, the imported memberships start now
+        let now = Instant::now();
+        for (room, sids) in &state.rooms {
+            for sid in sids {
+                sockets_map
+                    .entry(*sid)
+                    .or_default()
+                    .insert(room.clone(), now);
+            }
+        }
+        *rooms_map = state.rooms;
+
+        let mut meta = self.meta.write().unwrap();
+        meta.clear();
+        for (sid, values) in state.meta {
+            for (key, value) in values {
+                meta.entry(key)
+                    .or_default()
+                    .entry(value)
+                    .or_default()
+                    .insert(sid);
+            }
+        }
+        *self.room_meta.write().unwrap() = state.room_meta;
+        Ok(())
+    }
 }
 
 impl LocalAdapter {
+    /// Sends a membership change to the [`LocalAdapter::subscribe_changes`] subscribers, if any.
+    fn notify_change(&self, kind: MembershipKind, sid: Sid, room: Room) {
+        if self.changes.receiver_count() > 0 {
+            self.changes.send(MembershipEvent { kind, sid, room }).ok();
+        }
+    }
+
+    /// Calls the given room hook of the namespace for each room, once the rooms lock is released
+    /// so that the hook can use the adapter.
+    fn notify_rooms(&self, rooms: &[Room], notify: fn(&Namespace<Self>, &Room)) {
+        if rooms.is_empty() {
+            return;
+        }
+        if let Some(ns) = self.ns.upgrade() {
+            rooms.iter().for_each(|room| notify(&ns, room));
+        }
+    }
+
     /// Applies the given `opts` and return the sockets that match.
     fn apply_opts(&self, opts: BroadcastOptions) -> Vec<SocketRef<Self>> {
+        #[cfg(feature = "extensions")]
+        let filter = opts.filter;
         let rooms = opts.rooms;
 
         let except = self.get_except_sids(&opts.except);
+        let meta = self.get_meta_sids(&opts.meta);
         let ns = self.ns.upgrade().unwrap();
-        if !rooms.is_empty() {
-            let rooms_map = self.rooms.read().unwrap();
-            rooms
-                .iter()
-                .filter_map(|room| rooms_map.get(room))
-                .flatten()
-                .filter(|sid| {
-                    !except.contains(*sid)
-                        && (!opts.flags.contains(&BroadcastFlags::Broadcast)
-                            || opts.sid.map(|s| s != **sid).unwrap_or(true))
-                })
-                .filter_map(|sid| ns.get_socket(*sid).ok())
-                .map(SocketRef::from)
-                .collect()
-        } else if opts.flags.contains(&BroadcastFlags::Broadcast) {
-            let sockets = ns.get_sockets();
-            sockets
-                .into_iter()
-                .filter(|socket| {
-                    !except.contains(&socket.id) && opts.sid.map(|s| s != socket.id).unwrap_or(true)
-                })
-                .map(SocketRef::from)
-                .collect()
-        } else if let Some(sock) = opts.sid.and_then(|sid| ns.get_socket(sid).ok()) {
-            vec![sock.into()]
-        } else {
-            vec![]
+        let mut sockets: Vec<SocketRef<Self>> =
+            if !rooms.is_empty() || !opts.rooms_all.is_empty() || !opts.sids.is_empty() {
+                let rooms_map = self.rooms.read().unwrap();
+                let mut sids: HashSet<Sid> = if !rooms.is_empty() || !opts.sids.is_empty() {
+                    rooms
+                        .iter()
+                        .filter_map(|room| rooms_map.get(room))
+                        .flatten()
+                        .chain(opts.sids.iter())
+                        .copied()
+                        .collect()
+                } else {
+                    // Start from the smallest room of the intersection
+                    opts.rooms_all
+                        .iter()
+                        .map(|room| rooms_map.get(room))
+                        .min_by_key(|sids| sids.map(HashSet::len).unwrap_or(0))
+                        .flatten()
+                        .cloned()
+                        .unwrap_or_default()
+                };
+                sids.retain(|sid| {
+                    opts.rooms_all
+                        .iter()
+                        .all(|room| rooms_map.get(room).is_some_and(|s| s.contains(sid)))
+                });
+                sids.into_iter()
+                    .filter(|sid| {
+                        !except.contains(sid)
+                            && (!opts.flags.contains(&BroadcastFlags::Broadcast)
+                                || opts.sid.map(|s| s != *sid).unwrap_or(true))
+                    })
+                    .filter_map(|sid| ns.get_socket(sid).ok())
+                    .map(SocketRef::from)
+                    .collect()
+            } else if opts.flags.contains(&BroadcastFlags::Broadcast) {
+                // Only the sockets matching the metadata are candidates
+                let sockets = match &meta {
+                    Some(sids) => sids
+                        .iter()
+                        .filter_map(|sid| ns.get_socket(*sid).ok())
+                        .collect(),
+                    None => ns.get_sockets(),
+                };
+                sockets
+                    .into_iter()
+                    .filter(|socket| {
+                        !except.contains(&socket.id)
+                            && opts.sid.map(|s| s != socket.id).unwrap_or(true)
+                    })
+                    .map(SocketRef::from)
+                    .collect()
+            } else if let Some(sock) = opts.sid.and_then(|sid| ns.get_socket(sid).ok()) {
+                vec![sock.into()]
+            } else {
+                vec![]
+            };
+
+        if let Some(meta) = meta {
+            sockets.retain(|socket| meta.contains(&socket.id));
+        }
+        if let Some(after) = opts.joined_after {
+            let sockets_map = self.sockets.read().unwrap();
+            sockets.retain(|socket| {
+                let Some(joined) = sockets_map.get(&socket.id) else {
+                    return false;
+                };
+                if rooms.is_empty() {
+                    joined.values().any(|at| *at > after)
+                } else {
+                    rooms
+                        .iter()
+                        .any(|room| joined.get(room).is_some_and(|at| *at > after))
+                }
+            });
         }
+        #[cfg(feature = "extensions")]
+        if let Some(filter) = filter {
+            sockets.retain(|socket| filter.matches(&socket.extensions));
+        }
+        sockets
+    }
+
+    /// Returns the sockets matching all the given metadata, or `None` if there is no metadata to match.
+    fn get_meta_sids(&self, meta: &HashMap<String, String>) -> Option<HashSet<Sid>> {
+        if meta.is_empty() {
+            return None;
+        }
+        let index = self.meta.read().unwrap();
+        let mut sets = Vec::with_capacity(meta.len());
+        for (key, value) in meta {
+            match index.get(key).and_then(|values| values.get(value)) {
+                Some(sids) => sets.push(sids),
+                None => return Some(HashSet::new()),
+            }
+        }
+        // Start from the smallest set of the intersection
+        sets.sort_by_key(|sids| sids.len());
+        let (first, rest) = sets.split_first()?;
+        let sids = first
+            .iter()
+            .filter(|sid| rest.iter().all(|sids| sids.contains(sid)))
+            .copied()
+            .collect();
+        Some(sids)
     }
 
     fn get_except_sids(&self, except: &HashSet<Room>) -> HashSet<Sid> {
@@ -335,6 +939,46 @@ mod test {
         };
     }
 
+    #[tokio::test]
+    async fn test_state_round_trip() {
+        let sid1 = Sid::new();
+        let sid2 = Sid::new();
+        let ns = Namespace::new_dummy([sid1, sid2]);
+        let adapter = LocalAdapter::new(Arc::downgrade(&ns));
+        adapter.add_all(sid1, ["room1", "room2"]).unwrap();
+        adapter.add_all(sid2, ["room2", "room3"]).unwrap();
+        adapter.del(sid2, "room3").unwrap();
+        adapter.set_meta(sid1, "role", "admin").unwrap();
+        adapter.set_meta(sid2, "role", "user").unwrap();
+        adapter
+            .set_room_meta("room3".into(), "capacity", 4.into())
+            .unwrap();
+
+        let state = adapter.export_state().unwrap();
+        // The empty rooms are not exported
+        assert_eq!(state.rooms.len(), 2);
+        assert_eq!(state.rooms["room2"], hash_set![sid1, sid2]);
+        assert_eq!(state.meta[&sid2]["role"], "user");
+        // The metadata of the empty rooms are exported
+        assert_eq!(state.room_meta["room3"]["capacity"], 4);
+
+        let json = serde_json::to_string(&state).unwrap();
+        let state: AdapterState = serde_json::from_str(&json).unwrap();
+
+        let ns = Namespace::new_dummy([sid1, sid2]);
+        let imported = LocalAdapter::new(Arc::downgrade(&ns));
+        imported.import_state(state.clone()).unwrap();
+        assert_eq!(imported.export_state().unwrap(), state);
+
+        let mut rooms = imported.socket_rooms(sid1).unwrap();
+        rooms.sort();
+        assert_eq!(rooms, ["room1", "room2"]);
+        assert_eq!(imported.socket_rooms(sid2).unwrap(), ["room2"]);
+        let meta = HashMap::from([("role".to_string(), "admin".to_string())]);
+        assert_eq!(imported.get_meta_sids(&meta), Some(hash_set![sid1]));
+        assert_eq!(imported.room_meta("room3").unwrap()["capacity"], 4);
+    }
+
     #[tokio::test]
     async fn test_server_count() {
         let ns = Namespace::new_dummy([]);
@@ -354,6 +998,20 @@ mod test {
         assert_eq!(rooms_map.get("room2").unwrap().len(), 1);
     }
 
+    #[tokio::test]
+    async fn test_try_add() {
+        let (socket1, socket2) = (Sid::new(), Sid::new());
+        let ns = Namespace::new_dummy([socket1, socket2]);
+        let adapter = LocalAdapter::new(Arc::downgrade(&ns));
+        assert!(!adapter.try_add(socket1, "room1".into(), 0).unwrap());
+        assert!(!adapter.rooms.read().unwrap().contains_key("room1"));
+        assert!(adapter.try_add(socket1, "room1".into(), 1).unwrap());
+        assert!(adapter.try_add(socket1, "room1".into(), 1).unwrap());
+        assert!(!adapter.try_add(socket2, "room1".into(), 1).unwrap());
+        assert_eq!(adapter.sockets("room1").unwrap(), [socket1]);
+        assert_eq!(adapter.socket_rooms(socket1).unwrap(), ["room1"]);
+    }
+
     #[tokio::test]
     async fn test_del() {
         let socket = Sid::new();
@@ -380,6 +1038,33 @@ mod test {
         assert_eq!(rooms_map.get("room2").unwrap().len(), 0);
     }
 
+    #[tokio::test]
+    async fn test_reverse_index() {
+        let sid1 = Sid::new();
+        let sid2 = Sid::new();
+        let ns = Namespace::new_dummy([sid1, sid2]);
+        let adapter = LocalAdapter::new(Arc::downgrade(&ns));
+        adapter.add_all(sid1, ["room1", "room2"]).unwrap();
+        adapter.add_all(sid2, ["room2", "room3"]).unwrap();
+        adapter.del(sid1, ["room1", "room3"]).unwrap();
+        {
+            let sockets_map = adapter.sockets.read().unwrap();
+            let socket_rooms =
+                |sid| -> HashSet<Room> { sockets_map.get(&sid).unwrap().keys().cloned().collect() };
+            assert_eq!(socket_rooms(sid1), hash_set!["room2".into()]);
+            assert_eq!(
+                socket_rooms(sid2),
+                hash_set!["room2".into(), "room3".into()]
+            );
+        }
+
+        // The sockets without any room are removed from the index
+        adapter.del(sid1, "room2").unwrap();
+        adapter.del_all(sid2).unwrap();
+        assert!(adapter.sockets.read().unwrap().is_empty());
+        assert!(adapter.socket_rooms(sid2).unwrap().is_empty());
+    }
+
     #[tokio::test]
     async fn test_socket_room() {
         let sid1 = Sid::new();
@@ -402,6 +1087,61 @@ mod test {
         assert_eq!(adapter.socket_rooms(sid3).unwrap(), ["room2"]);
     }
 
+    #[tokio::test]
+    async fn test_sids_stream() {
+        let sids: [Sid; 100] = std::array::from_fn(|_| Sid::new());
+        let ns = Namespace::new_dummy(sids);
+        let adapter = LocalAdapter::new(Arc::downgrade(&ns));
+        let stream = adapter.sids_stream().unwrap();
+        let res: Vec<Sid> = stream.collect().await;
+        assert_eq!(res.len(), sids.len());
+        assert_eq!(HashSet::<Sid>::from_iter(res), HashSet::from(sids));
+
+        let ns = Namespace::new_dummy([]);
+        let adapter = LocalAdapter::new(Arc::downgrade(&ns));
+        let res: Vec<Sid> = adapter.sids_stream().unwrap().collect().await;
+        assert!(res.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_changes() {
+        let sid1 = Sid::new();
+        let sid2 = Sid::new();
+        let ns = Namespace::new_dummy([sid1, sid2]);
+        let adapter = LocalAdapter::new(Arc::downgrade(&ns));
+        // Changes without any subscriber are not buffered
+        adapter.add_all(sid1, ["room0"]).unwrap();
+
+        let mut changes = adapter.subscribe_changes().unwrap();
+        adapter.add_all(sid1, ["room1", "room2"]).unwrap();
+        // Already joined, no change
+        adapter.add_all(sid1, ["room1"]).unwrap();
+        adapter.add_all(sid2, ["room1"]).unwrap();
+        adapter.del(sid1, ["room1", "room3"]).unwrap();
+        adapter.del_all(sid2).unwrap();
+
+        let event = |kind, sid, room: &'static str| MembershipEvent {
+            kind,
+            sid,
+            room: room.into(),
+        };
+        use MembershipKind::*;
+        let expected = [
+            event(Join, sid1, "room1"),
+            event(Join, sid1, "room2"),
+            event(Join, sid2, "room1"),
+            event(Leave, sid1, "room1"),
+            event(Leave, sid2, "room1"),
+        ];
+        for expected in expected {
+            assert_eq!(changes.next().await.unwrap(), expected);
+        }
+        assert!(futures_util::FutureExt::now_or_never(changes.next()).is_none());
+
+        drop(adapter);
+        assert!(changes.next().await.is_none());
+    }
+
     #[tokio::test]
     async fn test_add_socket() {
         let socket = Sid::new();
@@ -578,4 +1318,142 @@ mod test {
         let sockets = adapter.fetch_sockets(opts).unwrap();
         assert_eq!(sockets.len(), 0);
     }
+
+    #[tokio::test]
+    async fn test_apply_opts_rooms_all() {
+        let socket0 = Sid::new();
+        let socket1 = Sid::new();
+        let socket2 = Sid::new();
+        let ns = Namespace::new_dummy([socket0, socket1, socket2]);
+        let adapter = LocalAdapter::new(Arc::downgrade(&ns));
+        adapter.add_all(socket0, ["room1", "room2"]).unwrap();
+        adapter.add_all(socket1, ["room1"]).unwrap();
+        adapter.add_all(socket2, ["room2"]).unwrap();
+
+        // Union: each socket is only selected once
+        let opts = BroadcastOptions {
+            rooms: hash_set!["room1".into(), "room2".into()],
+            ..Default::default()
+        };
+        let sockets = adapter.fetch_sockets(opts).unwrap();
+        assert_eq!(sockets.len(), 3);
+
+        // Intersection
+        let opts = BroadcastOptions {
+            rooms_all: hash_set!["room1".into(), "room2".into()],
+            ..Default::default()
+        };
+        let sockets = adapter.fetch_sockets(opts).unwrap();
+        assert_eq!(sockets.len(), 1);
+        assert_eq!(sockets[0].id, socket0);
+
+        // Intersection with an unknown room
+        let opts = BroadcastOptions {
+            rooms_all: hash_set!["room1".into(), "room3".into()],
+            ..Default::default()
+        };
+        assert!(adapter.fetch_sockets(opts).unwrap().is_empty());
+
+        // Union narrowed down by an intersection
+        let opts = BroadcastOptions {
+            rooms: hash_set!["room1".into()],
+            rooms_all: hash_set!["room2".into()],
+            ..Default::default()
+        };
+        let sockets = adapter.fetch_sockets(opts).unwrap();
+        assert_eq!(sockets.len(), 1);
+        assert_eq!(sockets[0].id, socket0);
+    }
+
+    #[tokio::test]
+    async fn test_apply_opts_sids() {
+        let socket0 = Sid::new();
+        let socket1 = Sid::new();
+        let socket2 = Sid::new();
+        let ns = Namespace::new_dummy([socket0, socket1, socket2]);
+        let adapter = LocalAdapter::new(Arc::downgrade(&ns));
+        adapter.add_all(socket0, ["room1"]).unwrap();
+        adapter.add_all(socket1, ["room2"]).unwrap();
+
+        // Unknown sids are ignored
+        let opts = BroadcastOptions {
+            sids: hash_set![socket1, Sid::new()],
+            ..Default::default()
+        };
+        let sockets = adapter.fetch_sockets(opts).unwrap();
+        assert_eq!(sockets.len(), 1);
+        assert_eq!(sockets[0].id, socket1);
+
+        // Union with the rooms, without the excluded rooms
+        let opts = BroadcastOptions {
+            rooms: hash_set!["room1".into()],
+            sids: hash_set![socket0, socket1, socket2],
+            except: hash_set!["room2".into()],
+            ..Default::default()
+        };
+        let mut sockets: Vec<Sid> = adapter
+            .fetch_sockets(opts)
+            .unwrap()
+            .into_iter()
+            .map(|s| s.id)
+            .collect();
+        sockets.sort();
+        let mut expected = vec![socket0, socket2];
+        expected.sort();
+        assert_eq!(sockets, expected);
+    }
+
+    #[tokio::test]
+    async fn test_apply_opts_meta() {
+        let socket0 = Sid::new();
+        let socket1 = Sid::new();
+        let socket2 = Sid::new();
+        let ns = Namespace::new_dummy([socket0, socket1, socket2]);
+        let adapter = LocalAdapter::new(Arc::downgrade(&ns));
+        adapter.add_all(socket0, ["room1"]).unwrap();
+        adapter.set_meta(socket0, "region", "eu").unwrap();
+        adapter.set_meta(socket0, "shard", "1").unwrap();
+        adapter.set_meta(socket1, "region", "us").unwrap();
+        adapter.set_meta(socket1, "region", "eu").unwrap();
+        adapter.set_meta(socket2, "region", "us").unwrap();
+
+        let meta_sids = |meta: &[(&str, &str)], rooms: HashSet<Room>| {
+            let opts = BroadcastOptions {
+                flags: hash_set![BroadcastFlags::Broadcast],
+                rooms,
+                meta: meta
+                    .iter()
+                    .map(|&(k, v)| (k.to_string(), v.to_string()))
+                    .collect(),
+                ..Default::default()
+            };
+            let mut sids: Vec<Sid> = adapter
+                .fetch_sockets(opts)
+                .unwrap()
+                .into_iter()
+                .map(|s| s.id)
+                .collect();
+            sids.sort();
+            sids
+        };
+        let mut expected = vec![socket0, socket1];
+        expected.sort();
+        // The previous value of a key is replaced
+        assert_eq!(meta_sids(&[("region", "eu")], hash_set![]), expected);
+        assert_eq!(meta_sids(&[("region", "us")], hash_set![]), vec![socket2]);
+        assert_eq!(
+            meta_sids(&[("region", "eu"), ("shard", "1")], hash_set![]),
+            vec![socket0]
+        );
+        assert!(meta_sids(&[("region", "asia")], hash_set![]).is_empty());
+        // Narrowed down selection of the rooms
+        assert_eq!(
+            meta_sids(&[("region", "eu")], hash_set!["room1".into()]),
+            vec![socket0]
+        );
+
+        adapter.del_meta(socket0).unwrap();
+        assert_eq!(meta_sids(&[("region", "eu")], hash_set![]), vec![socket1]);
+        assert!(meta_sids(&[("shard", "1")], hash_set![]).is_empty());
+    }
 }
@@ -0,0 +1,109 @@
+//! Client-initiated binary streams, reassembled from chunked events.
+//!
+//! To upload a large payload, the client splits it into chunks sent as the binary attachments
+//! of successive events with the same name. [`Socket::binary_stream`] registers a handler for
+//! this event and exposes the chunks, in the order they were received, as a [`BinaryStream`].
+//! It implements both [`Stream<Item = Bytes>`](futures_core::Stream) and [`AsyncRead`].
+//!
+//! An event of the stream without any binary attachment is the end marker: the stream completes
+//! once all the previous chunks are consumed, and the following events are ignored.
+//! The stream also completes when the socket is dropped after its disconnection,
+//! or when another handler is registered for the same event.
+//!
+//! #### Example
+//! ```
+//! # use socketioxide::{SocketIo, extract::*};
+//! # use futures_util::StreamExt;
+//! let (_, io) = SocketIo::new_svc();
+//! io.ns("/", |socket: SocketRef| {
+//!     // client: socket.emit("upload", chunk1); socket.emit("upload", chunk2); socket.emit("upload");
+//!     let mut upload = socket.binary_stream("upload");
+//!     tokio::spawn(async move {
+//!         let mut file = Vec::new();
+//!         while let Some(chunk) = upload.next().await {
+//!             file.extend_from_slice(&chunk);
+//!         }
+//!         println!("received {} bytes", file.len());
+//!     });
+//! });
+//! ```
+//!
+//! [`Socket::binary_stream`]: crate::socket::Socket#method.binary_stream
+use std::{
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{ready, Context, Poll},
+};
+
+use bytes::{Buf, Bytes};
+use futures_core::Stream;
+use tokio::{
+    io::{AsyncRead, ReadBuf},
+    sync::mpsc,
+};
+
+use crate::extract::Bin;
+
+/// The chunks of a binary stream sent by the client, see the [module doc](self) for more details.
+///
+/// The chunks are buffered until they are consumed.
+#[derive(Debug)]
+pub struct BinaryStream {
+    rx: mpsc::UnboundedReceiver<Bytes>,
+    /// The rest of the chunk partially consumed by [`AsyncRead::poll_read`]
+    chunk: Bytes,
+}
+
+impl BinaryStream {
+    /// Creates a stream and the message handler feeding it with the received chunks.
+    pub(crate) fn new() -> (Self, impl Fn(Bin) + Clone + Send + Sync + 'static) {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let tx = Arc::new(Mutex::new(Some(tx)));
+        let handler = move |Bin(bin): Bin| {
+            let mut tx = tx.lock().unwrap();
+            if bin.is_empty() {
+                // End marker, the stream completes once the sender is dropped
+                tx.take();
+            } else if let Some(tx) = tx.as_ref() {
+                for chunk in bin {
+                    tx.send(chunk).ok();
+                }
+            }
+        };
+        let stream = Self {
+            rx,
+            chunk: Bytes::new(),
+        };
+        (stream, handler)
+    }
+}
+
+impl Stream for BinaryStream {
+    type Item = Bytes;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if !self.chunk.is_empty() {
+            return Poll::Ready(Some(std::mem::take(&mut self.chunk)));
+        }
+        self.rx.poll_recv(cx)
+    }
+}
+
+impl AsyncRead for BinaryStream {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        while self.chunk.is_empty() {
+            match ready!(self.rx.poll_recv(cx)) {
+                Some(chunk) => self.chunk = chunk,
+                None => return Poll::Ready(Ok(())),
+            }
+        }
+        let n = self.chunk.len().min(buf.remaining());
+        buf.put_slice(&self.chunk[..n]);
+        self.chunk.advance(n);
+        Poll::Ready(Ok(()))
+    }
+}
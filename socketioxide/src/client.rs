@@ -1,5 +1,6 @@
 use std::borrow::Cow;
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex, OnceLock, RwLock};
 
 use bytes::Bytes;
@@ -14,20 +15,50 @@ use tokio::sync::oneshot;
 
 use crate::adapter::Adapter;
 use crate::handler::ConnectHandler;
+use crate::lifecycle::{
+    BoxedConnectedHook, BoxedDropSink, BoxedEventRewriter, BoxedEventSink, BoxedJoinHook,
+    BoxedLeaveHook, BoxedRoomHook, DropEvent, LifecycleEvent, RoomHooks,
+};
 use crate::ns::NamespaceCtr;
 use crate::socket::DisconnectReason;
 use crate::{
     errors::Error,
     ns::Namespace,
-    packet::{Packet, PacketData},
-    SocketIoConfig,
+    packet::{DecodeLimits, IsRawEvent, Packet, PacketData},
+    EarlyPacketPolicy, EventLimitsPolicy, SocketIoConfig,
 };
 use crate::{ProtocolVersion, SocketIo};
 
+/// The maximum number of packets buffered for each namespace with [`EarlyPacketPolicy::Buffer`].
+const EARLY_PACKETS_BUFFER_SIZE: usize = 32;
+
+/// The maximum length in bytes of a namespace path sent in a connect packet.
+const MAX_NS_PATH_LEN: usize = 256;
+
+/// Checks that a namespace path received in a connect packet is well-formed before looking it up:
+/// it must start with `/`, be at most [`MAX_NS_PATH_LEN`] bytes long and must not contain
+/// whitespaces, control characters or any of `?`, `#` and `\`.
+fn is_valid_ns_path(path: &str) -> bool {
+    path.starts_with('/')
+        && path.len() <= MAX_NS_PATH_LEN
+        && !path
+            .chars()
+            .any(|c| c.is_whitespace() || c.is_control() || matches!(c, '?' | '#' | '\\'))
+}
+
 pub struct Client<A: Adapter> {
     pub(crate) config: SocketIoConfig,
     ns: RwLock<HashMap<Cow<'static, str>, Arc<Namespace<A>>>>,
     router: RwLock<Router<NamespaceCtr<A>>>,
+    event_sink: RwLock<Option<BoxedEventSink>>,
+    join_hook: RwLock<Option<BoxedJoinHook>>,
+    leave_hook: RwLock<Option<BoxedLeaveHook>>,
+    connected_hook: RwLock<Option<BoxedConnectedHook<A>>>,
+    event_rewriter: RwLock<Option<BoxedEventRewriter>>,
+    drop_sink: RwLock<Option<BoxedDropSink>>,
+    room_hooks: Arc<RoomHooks>,
+    #[cfg(feature = "schema")]
+    pub(crate) schemas: Arc<crate::schema::SchemaRegistry>,
 
     #[cfg(feature = "state")]
     pub(crate) state: state::TypeMap![Send + Sync],
@@ -44,16 +75,25 @@ impl<A: Adapter> Client<A> {
         state.freeze();
 
         Self {
+            #[cfg(feature = "schema")]
+            schemas: Arc::new(crate::schema::SchemaRegistry::new(config.schema_policy)),
             config,
             ns: RwLock::new(HashMap::new()),
             router: RwLock::new(Router::new()),
+            event_sink: RwLock::new(None),
+            join_hook: RwLock::new(None),
+            leave_hook: RwLock::new(None),
+            connected_hook: RwLock::new(None),
+            event_rewriter: RwLock::new(None),
+            drop_sink: RwLock::new(None),
+            room_hooks: Arc::default(),
             #[cfg(feature = "state")]
             state,
         }
     }
 
     /// Called when a socket connects to a new namespace
-    fn sock_connect(
+    pub(crate) fn sock_connect(
         &self,
         auth: Option<String>,
         ns_path: Str,
@@ -62,22 +102,76 @@ impl<A: Adapter> Client<A> {
         #[cfg(feature = "tracing")]
         tracing::debug!("auth: {:?}", auth);
         let protocol: ProtocolVersion = esocket.protocol.into();
+        // Legacy clients identify their namespace sockets with the engine.io session id,
+        // otherwise each namespace socket gets its own id.
+        let sid = match protocol {
+            ProtocolVersion::V4 => esocket.id,
+            ProtocolVersion::V5 => Sid::new(),
+        };
+        let wait_timeout = self.config.connect_timeout;
         let connect =
             move |ns: Arc<Namespace<A>>, esocket: Arc<engineioxide::Socket<SocketData<A>>>| async move {
-                if ns.connect(esocket.id, esocket.clone(), auth).await.is_ok() {
+                // If the previous socket of this session on the namespace is still disconnecting,
+                // the new one is only connected once it is fully removed, so that its disconnection
+                // doesn't remove the new socket state nor reach the client after the new connect packet.
+                let previous = esocket
+                    .data
+                    .ns_sids
+                    .lock()
+                    .unwrap()
+                    .get(ns.path.as_str())
+                    .copied();
+                let previous = previous
+                    .and_then(|sid| ns.get_socket(sid).ok())
+                    .filter(|socket| socket.disconnecting());
+                if let Some(previous) = previous {
+                    #[cfg(feature = "tracing")]
+                    tracing::debug!(?previous.id, "waiting for the previous socket to disconnect");
+                    if tokio::time::timeout(wait_timeout, previous.closed())
+                        .await
+                        .is_err()
+                    {
+                        let packet: String =
+                            Packet::connect_error(ns.path.clone(), "Disconnect in progress").into();
+                        esocket.emit(packet).ok();
+                        return;
+                    }
+                }
+                let connected = ns.clone().connect(sid, esocket.clone(), auth).await.is_ok();
+                if connected {
                     // cancel the connect timeout task for v5
                     if let Some(tx) = esocket.data.connect_recv_tx.lock().unwrap().take() {
                         tx.send(()).ok();
                     }
                 }
+                let early = esocket
+                    .data
+                    .early_packets
+                    .lock()
+                    .unwrap()
+                    .remove(ns.path.as_str());
+                for (packet, size) in early.into_iter().flatten().filter(|_| connected) {
+                    if let Err(_e) = ns.recv(sid, packet.inner, size) {
+                        #[cfg(feature = "tracing")]
+                        tracing::debug!("error while handling an early packet: {}", _e);
+                    }
+                }
             };
 
-        if let Some(ns) = self.get_ns(&ns_path) {
+        if !is_valid_ns_path(&ns_path) {
+            #[cfg(feature = "tracing")]
+            tracing::debug!(len = ns_path.len(), "rejecting malformed namespace path");
+            let packet: String = Packet::connect_error(ns_path, "Invalid namespace").into();
+            if let Err(_e) = esocket.emit(packet) {
+                #[cfg(feature = "tracing")]
+                tracing::error!("error while sending invalid namespace packet: {}", _e);
+            }
+        } else if let Some(ns) = self.get_ns(&ns_path) {
             tokio::spawn(connect(ns, esocket.clone()));
         } else if let Ok(Match { value: ns_ctr, .. }) = self.router.read().unwrap().at(&ns_path) {
             let path: Cow<'static, str> = Cow::Owned(ns_path.clone().into());
             let ns = ns_ctr.get_new_ns(ns_path); //TODO: check memory leak here
-            self.ns.write().unwrap().insert(path, ns.clone());
+            self.insert_ns(path, ns.clone());
             tokio::spawn(connect(ns, esocket.clone()));
         } else if protocol == ProtocolVersion::V4 && ns_path == "/" {
             #[cfg(feature = "tracing")]
@@ -95,9 +189,24 @@ impl<A: Adapter> Client<A> {
     }
 
     /// Propagate a packet to a its target namespace
-    fn sock_propagate_packet(&self, packet: Packet<'_>, sid: Sid) -> Result<(), Error> {
+    fn sock_propagate_packet(
+        &self,
+        packet: Packet<'static>,
+        size: usize,
+        esocket: &EIoSocket<SocketData<A>>,
+    ) -> Result<(), Error> {
         if let Some(ns) = self.get_ns(&packet.ns) {
-            ns.recv(sid, packet.inner)
+            let sid = esocket
+                .data
+                .ns_sids
+                .lock()
+                .unwrap()
+                .get(ns.path.as_str())
+                .copied();
+            match sid {
+                Some(sid) => ns.recv(sid, packet.inner, size),
+                None => self.recv_early_packet(packet, size, esocket),
+            }
         } else {
             #[cfg(feature = "tracing")]
             tracing::debug!("invalid namespace requested: {}", packet.ns);
@@ -105,6 +214,50 @@ impl<A: Adapter> Client<A> {
         }
     }
 
+    /// Returns `true` if the socket of the session connected to the namespace has a raw handler for the event
+    fn is_raw_event(&self, esocket: &EIoSocket<SocketData<A>>, ns: &str, event: &str) -> bool {
+        let Some(ns) = self.get_ns(ns) else {
+            return false;
+        };
+        let sid = esocket
+            .data
+            .ns_sids
+            .lock()
+            .unwrap()
+            .get(ns.path.as_str())
+            .copied();
+        sid.and_then(|sid| ns.get_socket(sid).ok())
+            .is_some_and(|socket| socket.is_raw_event(event))
+    }
+
+    /// Applies the [`EarlyPacketPolicy`] to a packet sent to a namespace the socket is not connected to yet
+    fn recv_early_packet(
+        &self,
+        packet: Packet<'static>,
+        size: usize,
+        esocket: &EIoSocket<SocketData<A>>,
+    ) -> Result<(), Error> {
+        match self.config.early_packet_policy {
+            EarlyPacketPolicy::Drop => {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(?esocket.id, "dropping a packet sent before connecting to {}", packet.ns);
+                Ok(())
+            }
+            EarlyPacketPolicy::Buffer => {
+                let mut early = esocket.data.early_packets.lock().unwrap();
+                let packets = early.entry(packet.ns.to_string().into()).or_default();
+                if packets.len() < EARLY_PACKETS_BUFFER_SIZE {
+                    packets.push((packet, size));
+                } else {
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!(?esocket.id, "early packets buffer full for {}", packet.ns);
+                }
+                Ok(())
+            }
+            EarlyPacketPolicy::Disconnect => Err(Error::SocketGone(esocket.id)),
+        }
+    }
+
     /// Spawn a task that will close the socket if it is not connected to a namespace
     /// after the [`SocketIoConfig::connect_timeout`] duration
     fn spawn_connect_timeout_task(&self, socket: Arc<EIoSocket<SocketData<A>>>) {
@@ -131,6 +284,24 @@ impl<A: Adapter> Client<A> {
         #[cfg(feature = "tracing")]
         tracing::debug!("adding namespace {}", path);
         let ns = Namespace::new(Str::from(&path), callback);
+        self.insert_ns(path, ns);
+    }
+
+    pub fn add_ordered_ns<C, T>(&self, path: Cow<'static, str>, callback: C)
+    where
+        C: ConnectHandler<A, T>,
+        T: Send + Sync + 'static,
+    {
+        #[cfg(feature = "tracing")]
+        tracing::debug!("adding ordered namespace {}", path);
+        let ns = Namespace::new_ordered(Str::from(&path), callback);
+        self.insert_ns(path, ns);
+    }
+
+    fn insert_ns(&self, path: Cow<'static, str>, ns: Arc<Namespace<A>>) {
+        ns.room_hooks.set(self.room_hooks.clone()).ok();
+        #[cfg(feature = "schema")]
+        ns.schemas.set(self.schemas.clone()).ok();
         self.ns.write().unwrap().insert(path, ns);
     }
 
@@ -161,37 +332,160 @@ impl<A: Adapter> Client<A> {
         }
     }
 
+    /// Returns true if a socket can connect to this namespace path,
+    /// either because it is already registered or because it matches a dynamic namespace.
+    pub(crate) fn has_ns(&self, path: &str) -> bool {
+        self.get_ns(path).is_some() || self.router.read().unwrap().at(path).is_ok()
+    }
+
     pub fn get_ns(&self, path: &str) -> Option<Arc<Namespace<A>>> {
         self.ns.read().unwrap().get(path).cloned()
     }
 
-    /// Closes all engine.io connections and all clients
+    /// Returns the sorted paths of the registered namespaces
+    pub fn ns_paths(&self) -> Vec<String> {
+        let mut paths: Vec<String> = self
+            .ns
+            .read()
+            .unwrap()
+            .keys()
+            .map(|p| p.to_string())
+            .collect();
+        paths.sort_unstable();
+        paths
+    }
+
+    /// Sets the lifecycle event sink, replacing the previous one
+    pub fn set_event_sink(&self, sink: BoxedEventSink) {
+        self.event_sink.write().unwrap().replace(sink);
+    }
+
+    /// Sends a lifecycle event to the event sink.
+    /// The event is only built if a sink is registered.
+    pub(crate) fn notify(&self, event: impl FnOnce() -> LifecycleEvent) {
+        let sink = self.event_sink.read().unwrap().clone();
+        if let Some(sink) = sink {
+            sink(event());
+        }
+    }
+
+    /// Returns true if a lifecycle event sink is registered.
+    pub(crate) fn has_event_sink(&self) -> bool {
+        self.event_sink.read().unwrap().is_some()
+    }
+
+    /// Sets the dropped packets sink, replacing the previous one
+    pub fn set_drop_sink(&self, sink: BoxedDropSink) {
+        self.drop_sink.write().unwrap().replace(sink);
+    }
+
+    /// Sends a drop event to the drop sink.
+    /// The event is only built if a sink is registered.
+    pub(crate) fn notify_drop(&self, event: impl FnOnce() -> DropEvent) {
+        let sink = self.drop_sink.read().unwrap().clone();
+        if let Some(sink) = sink {
+            sink(event());
+        }
+    }
+
+    /// Sets the hook called before a socket joins a room, replacing the previous one
+    pub fn set_join_hook(&self, hook: BoxedJoinHook) {
+        self.join_hook.write().unwrap().replace(hook);
+    }
+
+    /// Sets the hook called after a socket left a room, replacing the previous one
+    pub fn set_leave_hook(&self, hook: BoxedLeaveHook) {
+        self.leave_hook.write().unwrap().replace(hook);
+    }
+
+    /// Sets the hook called once a socket is fully connected, replacing the previous one
+    pub fn set_connected_hook(&self, hook: BoxedConnectedHook<A>) {
+        self.connected_hook.write().unwrap().replace(hook);
+    }
+
+    /// Sets the rewriter of the incoming event names, replacing the previous one
+    pub fn set_event_rewriter(&self, rewriter: BoxedEventRewriter) {
+        self.event_rewriter.write().unwrap().replace(rewriter);
+    }
+
+    /// Sets the hook called when a room gets its first member, replacing the previous one
+    pub fn set_room_created_hook(&self, hook: BoxedRoomHook) {
+        self.room_hooks.created.write().unwrap().replace(hook);
+    }
+
+    /// Sets the hook called when the last member of a room leaves it, replacing the previous one
+    pub fn set_room_empty_hook(&self, hook: BoxedRoomHook) {
+        self.room_hooks.empty.write().unwrap().replace(hook);
+    }
+
+    pub(crate) fn join_hook(&self) -> Option<BoxedJoinHook> {
+        self.join_hook.read().unwrap().clone()
+    }
+
+    pub(crate) fn leave_hook(&self) -> Option<BoxedLeaveHook> {
+        self.leave_hook.read().unwrap().clone()
+    }
+
+    pub(crate) fn connected_hook(&self) -> Option<BoxedConnectedHook<A>> {
+        self.connected_hook.read().unwrap().clone()
+    }
+
+    pub(crate) fn event_rewriter(&self) -> Option<BoxedEventRewriter> {
+        self.event_rewriter.read().unwrap().clone()
+    }
+
+    /// Closes all engine.io connections and all clients with the given reason
     #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
-    pub(crate) async fn close(&self) {
+    pub(crate) async fn close(&self, reason: DisconnectReason) {
         #[cfg(feature = "tracing")]
         tracing::debug!("closing all namespaces");
         let ns = { std::mem::take(&mut *self.ns.write().unwrap()) };
-        futures_util::future::join_all(
-            ns.values()
-                .map(|ns| ns.close(DisconnectReason::ClosingServer)),
-        )
-        .await;
+        futures_util::future::join_all(ns.values().map(|ns| ns.close(reason))).await;
         #[cfg(feature = "tracing")]
         tracing::debug!("all namespaces closed");
     }
 }
 
+/// A received packet with its encoded size, binary attachments included.
+type SizedPacket = (Packet<'static>, usize);
+
 #[derive(Debug)]
 pub struct SocketData<A: Adapter> {
-    /// Partial binary packet that is being received
+    /// Partial binary packet that is being received, with the size of the data received so far
     /// Stored here until all the binary payloads are received
-    pub partial_bin_packet: Mutex<Option<Packet<'static>>>,
+    pub partial_bin_packet: Mutex<Option<SizedPacket>>,
 
     /// Channel used to notify the socket that it has been connected to a namespace for v5
     pub connect_recv_tx: Mutex<Option<oneshot::Sender<()>>>,
 
     /// Used to store the [`SocketIo`] instance so it can be accessed by any sockets
     pub io: OnceLock<SocketIo<A>>,
+
+    /// The ids of the namespace sockets backed by this engine.io session, indexed by namespace path
+    pub ns_sids: Mutex<HashMap<Cow<'static, str>, Sid>>,
+
+    /// The auth results validated on this session, indexed by their key (e.g. a token),
+    /// so that they are reused by the connections to the other namespaces
+    pub auth_cache: Mutex<HashMap<String, serde_json::Value>>,
+
+    /// The packets received before connecting to their namespace, with [`EarlyPacketPolicy::Buffer`]
+    pub early_packets: Mutex<HashMap<Cow<'static, str>, Vec<SizedPacket>>>,
+
+    /// Whether a socket of this session registered a raw event handler with [`Socket::on_raw`](crate::socket::Socket::on_raw)
+    pub raw_events: AtomicBool,
+}
+
+impl<A: Adapter> SocketData<A> {
+    /// Gets the id of the socket connected to the given namespace through this session.
+    /// Falls back to the session id if the session is not connected to the namespace.
+    pub(crate) fn ns_sid(&self, ns: &str, session_id: Sid) -> Sid {
+        self.ns_sids
+            .lock()
+            .unwrap()
+            .get(ns)
+            .copied()
+            .unwrap_or(session_id)
+    }
 }
 impl<A: Adapter> Default for SocketData<A> {
     fn default() -> Self {
@@ -199,6 +493,10 @@ impl<A: Adapter> Default for SocketData<A> {
             partial_bin_packet: Default::default(),
             connect_recv_tx: Default::default(),
             io: OnceLock::new(),
+            ns_sids: Default::default(),
+            auth_cache: Default::default(),
+            early_packets: Default::default(),
+            raw_events: AtomicBool::new(false),
         }
     }
 }
@@ -236,13 +534,29 @@ impl<A: Adapter> EngineIoHandler for Client<A> {
             .read()
             .unwrap()
             .values()
-            .filter_map(|ns| ns.get_socket(socket.id).ok())
+            .filter_map(|ns| ns.get_socket(socket.data.ns_sid(&ns.path, socket.id)).ok())
             .collect();
 
         let _res: Result<Vec<_>, _> = socks
             .into_iter()
             .map(|s| s.close(reason.clone().into()))
             .collect();
+        socket.data.auth_cache.lock().unwrap().clear();
+        socket.data.early_packets.lock().unwrap().clear();
+
+        // Drop the binary packet that was still waiting for its attachments
+        let _partial = socket.data.partial_bin_packet.lock().unwrap().take();
+        #[cfg(feature = "tracing")]
+        if let Some((packet, _)) = _partial {
+            if let PacketData::BinaryEvent(_, bin, _) | PacketData::BinaryAck(bin, _) = packet.inner
+            {
+                tracing::debug!(
+                    "dropping partial binary packet with {}/{} attachments",
+                    bin.bin.len(),
+                    bin.payload_count()
+                );
+            }
+        }
 
         #[cfg(feature = "tracing")]
         match _res {
@@ -258,18 +572,40 @@ impl<A: Adapter> EngineIoHandler for Client<A> {
     fn on_message(&self, msg: Str, socket: Arc<EIoSocket<SocketData<A>>>) {
         #[cfg(feature = "tracing")]
         tracing::debug!("Received message: {:?}", msg);
-        let packet = match Packet::try_from(msg) {
+        let limits = DecodeLimits {
+            max_event_name_len: self.config.max_event_name_len,
+            max_event_args: self.config.max_event_args,
+            max_attachments: self.config.max_attachments,
+        };
+        let is_raw = |ns: &str, event: &str| self.is_raw_event(&socket, ns, event);
+        let is_raw = socket
+            .data
+            .raw_events
+            .load(Ordering::Relaxed)
+            .then_some(&is_raw as IsRawEvent<'_>);
+        let size = msg.len();
+        let packet = match Packet::decode_with(msg, limits, is_raw) {
             Ok(packet) => packet,
-            Err(_e) => {
+            Err(e) => {
                 #[cfg(feature = "tracing")]
-                tracing::debug!("socket serialization error: {}", _e);
-                socket.close(EIoDisconnectReason::PacketParsingError);
+                tracing::debug!("socket serialization error: {}", e);
+                self.notify(|| LifecycleEvent::Error {
+                    ns: None,
+                    sid: socket.id,
+                    message: e.to_string(),
+                });
+                let limit_exceeded =
+                    matches!(e, Error::EventNameTooLong(_) | Error::TooManyEventArgs(_));
+                if !limit_exceeded || self.config.event_limits_policy == EventLimitsPolicy::Close {
+                    socket.close(EIoDisconnectReason::PacketParsingError);
+                }
                 return;
             }
         };
         #[cfg(feature = "tracing")]
         tracing::debug!("Packet: {:?}", packet);
 
+        let ns = packet.ns.clone();
         let res: Result<(), Error> = match packet.inner {
             PacketData::Connect(auth) => {
                 self.sock_connect(auth, packet.ns, &socket);
@@ -282,10 +618,10 @@ impl<A: Adapter> EngineIoHandler for Client<A> {
                     .partial_bin_packet
                     .lock()
                     .unwrap()
-                    .replace(packet);
+                    .replace((packet, size));
                 Ok(())
             }
-            _ => self.sock_propagate_packet(packet, socket.id),
+            _ => self.sock_propagate_packet(packet, size, &socket),
         };
         if let Err(ref err) = res {
             #[cfg(feature = "tracing")]
@@ -294,6 +630,11 @@ impl<A: Adapter> EngineIoHandler for Client<A> {
                 socket.id,
                 err
             );
+            self.notify(|| LifecycleEvent::Error {
+                sid: socket.data.ns_sid(&ns, socket.id),
+                ns: Some(ns),
+                message: err.to_string(),
+            });
             if let Some(reason) = err.into() {
                 socket.close(reason);
             }
@@ -305,14 +646,20 @@ impl<A: Adapter> EngineIoHandler for Client<A> {
     /// If the packet is complete, it is propagated to the namespace
     fn on_binary(&self, data: Bytes, socket: Arc<EIoSocket<SocketData<A>>>) {
         if apply_payload_on_packet(data, &socket) {
-            if let Some(packet) = socket.data.partial_bin_packet.lock().unwrap().take() {
-                if let Err(ref err) = self.sock_propagate_packet(packet, socket.id) {
+            if let Some((packet, size)) = socket.data.partial_bin_packet.lock().unwrap().take() {
+                let ns = packet.ns.clone();
+                if let Err(ref err) = self.sock_propagate_packet(packet, size, &socket) {
                     #[cfg(feature = "tracing")]
                     tracing::debug!(
                         "error while propagating packet to socket {}: {}",
                         socket.id,
                         err
                     );
+                    self.notify(|| LifecycleEvent::Error {
+                        sid: socket.data.ns_sid(&ns, socket.id),
+                        ns: Some(ns),
+                        message: err.to_string(),
+                    });
                     if let Some(reason) = err.into() {
                         socket.close(reason);
                     }
@@ -338,7 +685,8 @@ impl<A: Adapter> std::fmt::Debug for Client<A> {
 fn apply_payload_on_packet<A: Adapter>(data: Bytes, socket: &EIoSocket<SocketData<A>>) -> bool {
     #[cfg(feature = "tracing")]
     tracing::debug!("[sid={}] applying payload on packet", socket.id);
-    if let Some(ref mut packet) = *socket.data.partial_bin_packet.lock().unwrap() {
+    if let Some((ref mut packet, ref mut size)) = *socket.data.partial_bin_packet.lock().unwrap() {
+        *size += data.len();
         match packet.inner {
             PacketData::BinaryEvent(_, ref mut bin, _) | PacketData::BinaryAck(ref mut bin, _) => {
                 bin.add_payload(data);
@@ -459,4 +807,24 @@ mod test {
             .await
             .unwrap_err();
     }
+
+    #[tokio::test]
+    async fn partial_bin_packet_dropped_on_disconnect() {
+        let client = create_client();
+        let sock = EIoSocket::new_dummy(Sid::new(), Box::new(|_, _| {}));
+        client.clone().on_connect(sock.clone());
+        client.on_message("0".into(), sock.clone());
+
+        let header = r#"52-["test",{"_placeholder":true,"num":0},{"_placeholder":true,"num":1}]"#;
+        client.on_message(header.into(), sock.clone());
+        client.on_binary(Bytes::from_static(&[1, 2, 3]), sock.clone());
+        assert!(sock.data.partial_bin_packet.lock().unwrap().is_some());
+
+        client.on_disconnect(sock.clone(), EIoDisconnectReason::TransportClose);
+        assert!(sock.data.partial_bin_packet.lock().unwrap().is_none());
+
+        // A late attachment is ignored rather than completing the dropped packet
+        client.on_binary(Bytes::from_static(&[4, 5, 6]), sock.clone());
+        assert!(sock.data.partial_bin_packet.lock().unwrap().is_none());
+    }
 }
@@ -0,0 +1,65 @@
+//! Server time emission for the synchronization of the client clocks.
+//!
+//! When enabled with [`SocketIoBuilder::time_sync`] for all the sockets, or with [`Socket::sync_time`]
+//! for some of them, a [`TIME_EVENT`] is periodically emitted to the sockets with the current [`ServerTime`].
+//!
+//! [`Socket::measure_rtt`] measures the round-trip time to the client with the acknowledgement
+//! of the same event, so the client should acknowledge it when requested:
+//! ```js
+//! socket.on("server:time", (time, ack) => {
+//!     offset = time.unix - Date.now();
+//!     ack?.();
+//! });
+//! ```
+//!
+//! #### Example
+//! ```
+//! # use socketioxide::{SocketIo, extract::SocketRef};
+//! # use std::time::Duration;
+//! let (_, io) = SocketIo::builder()
+//!     .time_sync(Duration::from_secs(30))
+//!     .build_svc();
+//! io.ns("/", |socket: SocketRef| async move {
+//!     if let Ok(rtt) = socket.measure_rtt().await {
+//!         println!("rtt to {}: {:?}", socket.id, rtt);
+//!     }
+//! });
+//! ```
+//!
+//! [`SocketIoBuilder::time_sync`]: crate::SocketIoBuilder#method.time_sync
+//! [`Socket::sync_time`]: crate::socket::Socket#method.sync_time
+//! [`Socket::measure_rtt`]: crate::socket::Socket#method.measure_rtt
+use std::{
+    sync::OnceLock,
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+
+/// The event periodically emitted to the sockets with the [`ServerTime`].
+pub const TIME_EVENT: &str = "server:time";
+
+/// The payload of the [`TIME_EVENT`], in milliseconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ServerTime {
+    /// The time on a monotonic clock, since the first timestamp taken by the server.
+    /// Contrary to the wall-clock time it never goes backward, so it can be used to measure durations.
+    pub monotonic: u64,
+    /// The wall-clock time, since the unix epoch.
+    pub unix: u64,
+}
+
+impl ServerTime {
+    /// Takes the current timestamps of the server.
+    pub fn now() -> Self {
+        static START: OnceLock<Instant> = OnceLock::new();
+        let start = START.get_or_init(Instant::now);
+        let unix = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        Self {
+            monotonic: start.elapsed().as_millis() as u64,
+            unix: unix.as_millis() as u64,
+        }
+    }
+}
@@ -0,0 +1,80 @@
+//! Serialization of the emitted payloads with a per-call context.
+//!
+//! A type implementing [`SerializeWithContext`] can be serialized differently depending on a context
+//! given at each emit call, e.g. to redact the admin-only fields for the regular users, without
+//! maintaining a separate payload type for each view.
+//!
+//! The payload and its context are combined with [`WithContext`], which implements [`Serialize`]
+//! so it can be given to any emit method, or with the [`Socket::emit_with_context`] and
+//! [`BroadcastOperators::emit_with_context`] shortcuts.
+//! When broadcasting, the payload is serialized once per call, so the recipients are expected to be
+//! grouped by context, e.g. with a room per class of recipients.
+//!
+//! #### Example
+//! ```
+//! # use socketioxide::{SocketIo, extract::*, context::SerializeWithContext};
+//! # use serde::{Serialize, Serializer, ser::SerializeStruct};
+//! struct User {
+//!     name: String,
+//!     email: String,
+//! }
+//! enum View {
+//!     Public,
+//!     Admin,
+//! }
+//! impl SerializeWithContext<View> for User {
+//!     fn serialize_with_context<S: Serializer>(&self, view: &View, serializer: S) -> Result<S::Ok, S::Error> {
+//!         let mut s = serializer.serialize_struct("User", 2)?;
+//!         s.serialize_field("name", &self.name)?;
+//!         match view {
+//!             View::Admin => s.serialize_field("email", &self.email)?,
+//!             View::Public => s.skip_field("email")?,
+//!         }
+//!         s.end()
+//!     }
+//! }
+//!
+//! let (_, io) = SocketIo::new_svc();
+//! io.ns("/", |socket: SocketRef| {
+//!     let user = User { name: "bob".into(), email: "bob@example.com".into() };
+//!     socket.to("admins").emit_with_context("user", &user, &View::Admin).ok();
+//!     socket.to("users").emit_with_context("user", &user, &View::Public).ok();
+//! });
+//! ```
+//!
+//! [`Socket::emit_with_context`]: crate::socket::Socket#method.emit_with_context
+//! [`BroadcastOperators::emit_with_context`]: crate::operators::BroadcastOperators#method.emit_with_context
+use serde::{Serialize, Serializer};
+
+/// A type that can be serialized differently depending on a context `C`,
+/// see the [module doc](self) for more details.
+pub trait SerializeWithContext<C> {
+    /// Serializes the value with the given serializer, according to the context.
+    fn serialize_with_context<S: Serializer>(
+        &self,
+        ctx: &C,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>;
+}
+
+/// A value serialized with [`SerializeWithContext::serialize_with_context`] and the given context.
+pub struct WithContext<'a, T: ?Sized, C> {
+    value: &'a T,
+    ctx: &'a C,
+}
+
+impl<'a, T: ?Sized, C> WithContext<'a, T, C> {
+    /// Combines a value with the context to serialize it with.
+    pub fn new(value: &'a T, ctx: &'a C) -> Self {
+        Self { value, ctx }
+    }
+}
+
+impl<'a, T, C> Serialize for WithContext<'a, T, C>
+where
+    T: SerializeWithContext<C> + ?Sized,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.value.serialize_with_context(self.ctx, serializer)
+    }
+}
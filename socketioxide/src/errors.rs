@@ -1,6 +1,10 @@
+use crate::adapter::Room;
 use engineioxide::{sid::Sid, socket::DisconnectReason as EIoDisconnectReason};
 use std::fmt::{Debug, Display};
-use tokio::{sync::mpsc::error::TrySendError, time::error::Elapsed};
+use tokio::{
+    sync::mpsc::error::{SendError as MpscSendError, TrySendError},
+    time::error::Elapsed,
+};
 
 pub use matchit::InsertError as NsInsertError;
 
@@ -16,6 +20,15 @@ pub enum Error {
     #[error("invalid event name")]
     InvalidEventName,
 
+    #[error("event name too long: {0} bytes")]
+    EventNameTooLong(usize),
+
+    #[error("too many event arguments: {0}")]
+    TooManyEventArgs(usize),
+
+    #[error("too many binary attachments: {0}")]
+    TooManyAttachments(usize),
+
     #[error("invalid namespace")]
     InvalidNamespace,
 
@@ -46,6 +59,21 @@ pub enum AckError<T> {
     /// Error sending/receiving data through the engine.io socket
     #[error("Error sending data through the engine.io socket: {0:?}")]
     Socket(#[from] SocketError<T>),
+
+    /// The socket already has the maximum number of acknowledgements pending,
+    /// set with [`SocketIoBuilder::max_pending_acks`](crate::SocketIoBuilder#method.max_pending_acks).
+    /// The packet was not sent.
+    #[error("too many pending acknowledgements")]
+    TooManyPending,
+
+    /// The acknowledgement was cancelled with [`AckHandle::cancel`](crate::ack::AckHandle::cancel).
+    #[error("ack cancelled")]
+    Cancelled,
+
+    /// The payload of the emitted event does not match the schema registered for it.
+    /// The packet was not sent.
+    #[error("{0}")]
+    Schema(#[from] SchemaError),
 }
 
 /// Error type for broadcast operations.
@@ -62,6 +90,22 @@ pub enum BroadcastError {
     /// An error occured while broadcasting to other nodes.
     #[error("Adapter error: {0}")]
     Adapter(#[from] AdapterError),
+
+    /// The payload does not match the schema registered for its event.
+    #[error("{0}")]
+    Schema(#[from] SchemaError),
+
+    /// The operators are contradictory: all the selected rooms are also excluded,
+    /// therefore the message would not be sent to anyone.
+    #[error("Contradictory broadcast operators: rooms {0:?} are both selected and excluded")]
+    ContradictoryOperators(Vec<Room>),
+
+    /// The broadcast selects a room frozen with [`SocketIo::freeze_room`] and cannot be held
+    /// until it is unfrozen, therefore it was not sent.
+    ///
+    /// [`SocketIo::freeze_room`]: crate::SocketIo#method.freeze_room
+    #[error("Broadcast to the frozen room {0:?}")]
+    FrozenRoom(Room),
 }
 /// Error type for sending operations.
 #[derive(thiserror::Error, Debug)]
@@ -70,6 +114,10 @@ pub enum SendError<T> {
     #[error("Error serializing JSON packet: {0:?}")]
     Serialize(#[from] serde_json::Error),
 
+    /// The payload does not match the schema registered for its event.
+    #[error("{0}")]
+    Schema(#[from] SchemaError),
+
     /// Error sending/receiving data through the engine.io socket
     #[error("Error sending data through the engine.io socket: {0:?}")]
     Socket(#[from] SocketError<T>),
@@ -98,6 +146,46 @@ impl<T> Debug for SocketError<T> {
     }
 }
 
+/// Error type for the raw sending operations
+/// [`Socket::send_raw`](crate::socket::Socket#method.send_raw),
+/// [`Socket::send_raw_bytes`](crate::socket::Socket#method.send_raw_bytes)
+/// and [`Socket::emit_to_ns`](crate::socket::Socket#method.emit_to_ns).
+#[derive(thiserror::Error, Debug)]
+pub enum RawSendError {
+    /// The raw packet is not a valid UTF-8 string.
+    #[error("raw packet is not valid utf-8: {0}")]
+    InvalidUtf8(#[from] std::str::Utf8Error),
+
+    /// The raw packet is not a valid socket.io packet.
+    #[error("invalid raw packet: {0}")]
+    InvalidPacket(String),
+
+    /// The packet targets a namespace that does not exist on the server.
+    #[error("unknown namespace: {0}")]
+    UnknownNamespace(String),
+
+    /// An error occurred while serializing the JSON packet.
+    #[error("Error serializing JSON packet: {0:?}")]
+    Serialize(#[from] serde_json::Error),
+
+    /// The payload does not match the schema registered for its event.
+    #[error("{0}")]
+    Schema(#[from] SchemaError),
+
+    /// The packet declares more binary attachments than it contains.
+    #[error("binary packet is missing attachments: expected {expected}, got {got}")]
+    MissingAttachments {
+        /// The number of attachments declared by the packet
+        expected: usize,
+        /// The number of attachments of the packet
+        got: usize,
+    },
+
+    /// Error sending data through the engine.io socket
+    #[error("Error sending data through the engine.io socket: {0:?}")]
+    Socket(#[from] SocketError<()>),
+}
+
 /// Error type for sending operations.
 #[derive(thiserror::Error, Debug)]
 pub enum DisconnectError {
@@ -113,6 +201,108 @@ pub enum DisconnectError {
     Adapter(#[from] AdapterError),
 }
 
+/// Error type for the [`Socket::switch_namespace`](crate::socket::Socket#method.switch_namespace) method.
+#[derive(thiserror::Error, Debug)]
+pub enum SwitchNamespaceError {
+    /// The target namespace does not exist. The socket is still connected to its current namespace.
+    #[error("invalid namespace: {0}")]
+    InvalidNamespace(String),
+
+    /// The socket could not be disconnected from its current namespace.
+    #[error("error disconnecting from the current namespace: {0}")]
+    Disconnect(#[from] DisconnectError),
+}
+
+/// Error type for the [`Socket::join`](crate::socket::Socket#method.join) method.
+#[derive(thiserror::Error, Debug)]
+pub enum JoinError<E> {
+    /// Joining the room was vetoed by the hook registered with
+    /// [`SocketIo::on_join`](crate::SocketIo#method.on_join). No room was joined.
+    #[error("joining room {0:?} was vetoed")]
+    Vetoed(Room),
+
+    /// Joining the rooms would exceed the limit set with
+    /// [`SocketIoBuilder::max_rooms_per_socket`](crate::SocketIoBuilder#method.max_rooms_per_socket).
+    /// No room was joined.
+    #[error("too many rooms joined, the limit is {0}")]
+    TooManyRooms(usize),
+
+    /// The room reached the capacity set with
+    /// [`RoomMeta::set_capacity`](crate::room_meta::RoomMeta#method.set_capacity).
+    /// No room was joined.
+    #[error("room {0:?} is full")]
+    RoomFull(Room),
+
+    /// An error occured in the adapter.
+    #[error("adapter error: {0}")]
+    Adapter(#[from] E),
+}
+
+/// Error type for the [`RoomMeta`](crate::room_meta::RoomMeta) methods.
+#[derive(thiserror::Error, Debug)]
+pub enum RoomMetaError<E> {
+    /// The metadata value could not be serialized.
+    #[error("error serializing the room metadata: {0}")]
+    Serialize(serde_json::Error),
+
+    /// The key starts with the [`RESERVED_PREFIX`](crate::room_meta::RESERVED_PREFIX)
+    /// of the metadata managed by socketioxide, such as the capacity of the room.
+    #[error("the room metadata key {0:?} is reserved")]
+    ReservedKey(String),
+
+    /// An error occured in the adapter.
+    #[error("adapter error: {0}")]
+    Adapter(#[from] E),
+}
+
+/// Error type for the [`Socket::resync`](crate::socket::Socket#method.resync) method.
+#[derive(thiserror::Error, Debug)]
+pub enum ResyncError {
+    /// The replay buffer is not enabled, see [`SocketIoBuilder::replay_buffer`](crate::SocketIoBuilder#method.replay_buffer).
+    #[error("replay buffer disabled")]
+    Disabled,
+
+    /// Some of the packets sent after the offset were already dropped from the replay buffer.
+    /// It contains the oldest offset that can still be replayed.
+    #[error("offset expired, the oldest buffered offset is {0}")]
+    OffsetExpired(u64),
+
+    /// The packets could not be sent to the socket.
+    #[error("error sending the replayed packets: {0}")]
+    Socket(#[from] SocketError<()>),
+}
+
+/// Error type for the [`RemoteSocket`](crate::remote::RemoteSocket) methods.
+#[derive(thiserror::Error, Debug)]
+pub enum RemoteSocketError<E> {
+    /// The socket is not connected to any node of the cluster.
+    #[error("remote socket {0} not found")]
+    SocketGone(Sid),
+
+    /// The node owning the socket answered with a response of another kind than the request.
+    #[error("unexpected remote socket response")]
+    UnexpectedResponse,
+
+    /// The data of the socket cannot be deserialized to the requested type.
+    #[error("cannot deserialize remote socket data: {0}")]
+    Decode(serde_json::Error),
+
+    /// An error occured in the adapter.
+    #[error("adapter error: {0}")]
+    Adapter(#[from] E),
+}
+
+/// Error type when an outgoing payload does not match the schema registered for its event.
+/// It is only returned with the `schema` feature and the `SchemaPolicy::Reject` policy.
+#[derive(thiserror::Error, Debug)]
+#[error("payload of event {event:?} does not match its schema: {reason}")]
+pub struct SchemaError {
+    /// The name of the event.
+    pub event: String,
+    /// The path of the first mismatching value and the reason of the mismatch.
+    pub reason: String,
+}
+
 /// Error type for the [`Adapter`](crate::adapter::Adapter) trait.
 #[derive(Debug, thiserror::Error)]
 pub struct AdapterError(#[from] pub Box<dyn std::error::Error + Send + Sync>);
@@ -122,6 +312,14 @@ impl Display for AdapterError {
     }
 }
 
+impl<T> SocketError<T> {
+    pub(crate) fn without_value(self) -> SocketError<()> {
+        match self {
+            Self::InternalChannelFull(_) => SocketError::InternalChannelFull(()),
+            Self::Closed(_) => SocketError::Closed(()),
+        }
+    }
+}
 impl SocketError<()> {
     pub(crate) fn with_value<T>(self, value: T) -> SocketError<T> {
         match self {
@@ -138,6 +336,11 @@ impl<T> From<TrySendError<T>> for SocketError<()> {
         }
     }
 }
+impl<T> From<MpscSendError<T>> for SocketError<()> {
+    fn from(_: MpscSendError<T>) -> Self {
+        Self::Closed(())
+    }
+}
 
 impl From<Vec<SocketError<()>>> for BroadcastError {
     /// Converts a vector of `SendError` into a `BroadcastError`.
@@ -169,9 +372,12 @@ impl From<&Error> for Option<EIoDisconnectReason> {
         use EIoDisconnectReason::*;
         match value {
             Error::SocketGone(_) => Some(TransportClose),
-            Error::Serialize(_) | Error::InvalidPacketType | Error::InvalidEventName => {
-                Some(PacketParsingError)
-            }
+            Error::Serialize(_)
+            | Error::InvalidPacketType
+            | Error::InvalidEventName
+            | Error::EventNameTooLong(_)
+            | Error::TooManyEventArgs(_)
+            | Error::TooManyAttachments(_) => Some(PacketParsingError),
             Error::Adapter(_) | Error::InvalidNamespace => None,
         }
     }
@@ -4,7 +4,7 @@ use std::sync::Arc;
 use crate::handler::{FromConnectParts, FromMessage, FromMessageParts};
 use crate::{adapter::Adapter, socket::Socket};
 use bytes::Bytes;
-use serde::de::DeserializeOwned;
+use serde::de::{self, DeserializeOwned};
 use serde_json::Value;
 
 /// Utility function to unwrap an array with a single element
@@ -17,6 +17,37 @@ fn upwrap_array(v: &mut Value) {
     }
 }
 
+/// Deserializes a value with the given deserializer.
+/// If `strict` is set, it fails if any field of the payload was ignored by the target type.
+pub(crate) fn deserialize<'de, T, D>(de: D, strict: bool) -> Result<T, serde_json::Error>
+where
+    T: de::Deserialize<'de>,
+    D: de::Deserializer<'de, Error = serde_json::Error>,
+{
+    if !strict {
+        return T::deserialize(de);
+    }
+    let mut unknown = None;
+    let data = serde_ignored::deserialize(de, |path| {
+        unknown.get_or_insert_with(|| path.to_string());
+    })?;
+    match unknown {
+        Some(path) => Err(de::Error::custom(format_args!("unknown field `{path}`"))),
+        None => Ok(data),
+    }
+}
+
+/// Deserializes the auth payload of a connect packet, or an empty object if there is none.
+fn from_auth<T: DeserializeOwned>(
+    auth: Option<&str>,
+    strict: bool,
+) -> Result<T, serde_json::Error> {
+    let mut de = serde_json::Deserializer::from_str(auth.unwrap_or("{}"));
+    let data = deserialize(&mut de, strict)?;
+    de.end()?;
+    Ok(data)
+}
+
 /// An Extractor that returns the deserialized data without checking errors.
 /// If a deserialization error occurs, the handler won't be called
 /// and an error log will be print if the `tracing` feature is enabled.
@@ -27,11 +58,8 @@ where
     A: Adapter,
 {
     type Error = serde_json::Error;
-    fn from_connect_parts(_: &Arc<Socket<A>>, auth: &Option<String>) -> Result<Self, Self::Error> {
-        auth.as_ref()
-            .map(|a| serde_json::from_str::<T>(a))
-            .unwrap_or(serde_json::from_str::<T>("{}"))
-            .map(Data)
+    fn from_connect_parts(s: &Arc<Socket<A>>, auth: &Option<String>) -> Result<Self, Self::Error> {
+        from_auth(auth.as_deref(), s.deny_unknown_fields()).map(Data)
     }
 }
 impl<T, A> FromMessageParts<A> for Data<T>
@@ -41,13 +69,13 @@ where
 {
     type Error = serde_json::Error;
     fn from_message_parts(
-        _: &Arc<Socket<A>>,
+        s: &Arc<Socket<A>>,
         v: &mut serde_json::Value,
         _: &mut Vec<Bytes>,
         _: &Option<i64>,
     ) -> Result<Self, Self::Error> {
         upwrap_array(v);
-        serde_json::from_value(v.clone()).map(Data)
+        deserialize(v.clone(), s.deny_unknown_fields()).map(Data)
     }
 }
 
@@ -60,12 +88,8 @@ where
     A: Adapter,
 {
     type Error = Infallible;
-    fn from_connect_parts(_: &Arc<Socket<A>>, auth: &Option<String>) -> Result<Self, Infallible> {
-        let v: Result<T, serde_json::Error> = auth
-            .as_ref()
-            .map(|a| serde_json::from_str(a))
-            .unwrap_or(serde_json::from_str("{}"));
-        Ok(TryData(v))
+    fn from_connect_parts(s: &Arc<Socket<A>>, auth: &Option<String>) -> Result<Self, Infallible> {
+        Ok(TryData(from_auth(auth.as_deref(), s.deny_unknown_fields())))
     }
 }
 impl<T, A> FromMessageParts<A> for TryData<T>
@@ -75,16 +99,118 @@ where
 {
     type Error = Infallible;
     fn from_message_parts(
-        _: &Arc<Socket<A>>,
+        s: &Arc<Socket<A>>,
+        v: &mut serde_json::Value,
+        _: &mut Vec<Bytes>,
+        _: &Option<i64>,
+    ) -> Result<Self, Infallible> {
+        upwrap_array(v);
+        Ok(TryData(deserialize(v.clone(), s.deny_unknown_fields())))
+    }
+}
+
+/// An Extractor that returns the deserialized data related to the event
+/// and distinguishes absent data from malformed data:
+/// * `Ok(None)`: no data was sent
+/// * `Ok(Some(data))`: the data was sent and deserialized
+/// * `Err(e)`: the data was sent but could not be deserialized
+///
+/// For the connect handlers, the auth data is considered absent if it is not set or `null`.
+/// For the message handlers, the data is considered absent if the event has no arguments or a single `null` argument.
+pub struct MaybeData<T>(pub Result<Option<T>, serde_json::Error>);
+
+impl<T, A> FromConnectParts<A> for MaybeData<T>
+where
+    T: DeserializeOwned,
+    A: Adapter,
+{
+    type Error = Infallible;
+    fn from_connect_parts(s: &Arc<Socket<A>>, auth: &Option<String>) -> Result<Self, Infallible> {
+        let v = match auth.as_deref().map(str::trim) {
+            None | Some("null") => Ok(None),
+            Some(a) => from_auth(Some(a), s.deny_unknown_fields()).map(Some),
+        };
+        Ok(MaybeData(v))
+    }
+}
+impl<T, A> FromMessageParts<A> for MaybeData<T>
+where
+    T: DeserializeOwned,
+    A: Adapter,
+{
+    type Error = Infallible;
+    fn from_message_parts(
+        s: &Arc<Socket<A>>,
         v: &mut serde_json::Value,
         _: &mut Vec<Bytes>,
         _: &Option<i64>,
     ) -> Result<Self, Infallible> {
         upwrap_array(v);
-        Ok(TryData(serde_json::from_value(v.clone())))
+        let v = match v {
+            Value::Null => Ok(None),
+            Value::Array(vec) if vec.is_empty() => Ok(None),
+            v => deserialize(v.clone(), s.deny_unknown_fields()).map(Some),
+        };
+        Ok(MaybeData(v))
     }
 }
 
+/// Deserializes the argument at `index` of an event.
+/// If the event has a single argument and it was already unwrapped by another extractor, it is considered at index 0.
+fn from_arg<T: DeserializeOwned>(
+    v: &Value,
+    index: usize,
+    strict: bool,
+) -> Result<T, serde_json::Error> {
+    let arg = match v {
+        Value::Array(args) => args.get(index),
+        v if index == 0 => Some(v),
+        _ => None,
+    };
+    let len = match v {
+        Value::Array(args) => args.len(),
+        _ => 1,
+    };
+    let arg = arg.ok_or_else(|| {
+        de::Error::invalid_length(len, &format!("at least {} arguments", index + 1).as_str())
+    })?;
+    deserialize(arg.clone(), strict)
+}
+
+macro_rules! impl_arg {
+    ($($ident:ident => $index:literal),+) => {
+        $(
+            #[doc = concat!("An Extractor that returns the deserialized argument at index ", $index, " of the event.")]
+            /// Each argument of a multi-argument event can be bound to a distinct extractor:
+            /// `["hi", 5]` is extracted with `Arg0<String>` and `Arg1<i32>`.
+            ///
+            /// If the event has too few arguments or if a deserialization error occurs, the handler won't be called
+            /// and an error log will be print if the `tracing` feature is enabled.
+            ///
+            /// It should not be used with the [`Data`], [`TryData`] or [`MaybeData`] extractors
+            /// as they unwrap the arguments of single-argument events.
+            pub struct $ident<T>(pub T);
+            impl<T, A> FromMessageParts<A> for $ident<T>
+            where
+                T: DeserializeOwned,
+                A: Adapter,
+            {
+                type Error = serde_json::Error;
+                fn from_message_parts(
+                    s: &Arc<Socket<A>>,
+                    v: &mut serde_json::Value,
+                    _: &mut Vec<Bytes>,
+                    _: &Option<i64>,
+                ) -> Result<Self, Self::Error> {
+                    from_arg(v, $index, s.deny_unknown_fields()).map($ident)
+                }
+            }
+            super::__impl_deref!($ident);
+        )+
+    };
+}
+impl_arg!(Arg0 => 0, Arg1 => 1, Arg2 => 2, Arg3 => 3, Arg4 => 4, Arg5 => 5, Arg6 => 6, Arg7 => 7);
+
 /// An Extractor that returns the binary data of the message.
 /// If there is no binary data, it will contain an empty vec.
 pub struct Bin(pub Vec<Bytes>);
@@ -100,6 +226,29 @@ impl<A: Adapter> FromMessage<A> for Bin {
     }
 }
 
+/// An Extractor that returns the arguments of the message as a JSON array, e.g. `[1,{"foo":"bar"}]`.
+///
+/// With a handler registered with [`Socket::on_raw`] the arguments are given as they were received,
+/// without being deserialized. Otherwise they are serialized back from the decoded data.
+/// Because it consumes the event it should be the last argument.
+pub struct RawPacket(pub Bytes);
+impl<A: Adapter> FromMessage<A> for RawPacket {
+    type Error = serde_json::Error;
+    fn from_message(
+        _: Arc<Socket<A>>,
+        v: serde_json::Value,
+        _: Vec<Bytes>,
+        _: Option<i64>,
+    ) -> Result<Self, serde_json::Error> {
+        match v {
+            Value::String(raw) => Ok(RawPacket(Bytes::from(raw))),
+            v => serde_json::to_vec(&v).map(|v| RawPacket(Bytes::from(v))),
+        }
+    }
+}
+
 super::__impl_deref!(Bin: Vec<Bytes>);
+super::__impl_deref!(RawPacket: Bytes);
 super::__impl_deref!(TryData<T>: Result<T, serde_json::Error>);
+super::__impl_deref!(MaybeData<T>: Result<Option<T>, serde_json::Error>);
 super::__impl_deref!(Data);
@@ -0,0 +1,145 @@
+use std::sync::Arc;
+
+use jsonwebtoken::{errors::ErrorKind, DecodingKey, Validation};
+use serde::de::DeserializeOwned;
+
+use crate::adapter::Adapter;
+use crate::handler::FromConnectParts;
+use crate::socket::Socket;
+
+/// The key and the validation rules used by the [`Jwt`] extractor to verify the tokens.
+///
+/// It should be set with [`SocketIoBuilder::with_state`](crate::io::SocketIoBuilder).
+#[derive(Clone)]
+pub struct JwtKey {
+    key: DecodingKey,
+    validation: Validation,
+    session_cache: bool,
+}
+
+impl JwtKey {
+    /// Creates a new [`JwtKey`] from a decoding key and validation rules.
+    pub fn new(key: DecodingKey, validation: Validation) -> Self {
+        Self {
+            key,
+            validation,
+            session_cache: false,
+        }
+    }
+
+    /// Caches the claims of the verified tokens on the engine.io session of the client,
+    /// see [`Socket::cached_auth`](crate::socket::Socket#method.cached_auth).
+    /// A client connecting to several namespaces with the same token is then only verified once.
+    ///
+    /// The expiry of the cached claims is still checked on each connection.
+    pub fn with_session_cache(mut self) -> Self {
+        self.session_cache = true;
+        self
+    }
+}
+
+/// An Extractor that verifies a JWT token sent by the client and returns its decoded claims.
+///
+/// The token is read from the `token` field of the auth payload,
+/// or from the `Authorization: Bearer <token>` header of the request if there is none.
+/// Its signature and expiry are verified with the [`JwtKey`] set with
+/// [`SocketIoBuilder::with_state`](crate::io::SocketIoBuilder).
+///
+/// If the verification fails, the handler won't be called and a [`JwtError`] is returned.
+/// When used in a connect middleware, a `connect_error` packet is sent to the client.
+///
+/// ### Example
+/// ```
+/// # use socketioxide::{SocketIo, extract::{SocketRef, Jwt, JwtKey}};
+/// # use serde::Deserialize;
+/// # use jsonwebtoken::{DecodingKey, Validation};
+/// #[derive(Debug, Deserialize)]
+/// struct Claims {
+///     sub: String,
+///     exp: u64,
+/// }
+/// let key = JwtKey::new(DecodingKey::from_secret(b"secret"), Validation::default());
+/// let (_, io) = SocketIo::builder().with_state(key).build_svc();
+/// io.ns("/", |socket: SocketRef, Jwt(claims): Jwt<Claims>| {
+///     println!("User {} connected", claims.sub);
+/// });
+/// ```
+pub struct Jwt<T>(pub T);
+
+/// An error returned by the [`Jwt`] extractor.
+#[derive(Debug, thiserror::Error)]
+pub enum JwtError {
+    /// No [`JwtKey`] was set with [`SocketIoBuilder::with_state`](crate::io::SocketIoBuilder).
+    #[error("jwt key not found, maybe you forgot to insert it in the state map?")]
+    KeyNotFound,
+
+    /// The client did not send any token.
+    #[error("missing jwt token")]
+    Missing,
+
+    /// The token is expired.
+    #[error("expired jwt token")]
+    Expired,
+
+    /// The signature of the token is invalid.
+    #[error("invalid jwt signature")]
+    InvalidSignature,
+
+    /// The token is invalid for another reason (malformed token, invalid claims, etc...).
+    #[error("invalid jwt token: {0}")]
+    Invalid(jsonwebtoken::errors::Error),
+}
+
+impl From<jsonwebtoken::errors::Error> for JwtError {
+    fn from(err: jsonwebtoken::errors::Error) -> Self {
+        match err.kind() {
+            ErrorKind::ExpiredSignature => JwtError::Expired,
+            ErrorKind::InvalidSignature => JwtError::InvalidSignature,
+            _ => JwtError::Invalid(err),
+        }
+    }
+}
+
+/// Reads the token from the auth payload or from the `Authorization` header.
+fn extract_token<A: Adapter>(s: &Arc<Socket<A>>, auth: &Option<String>) -> Option<String> {
+    let from_auth = auth
+        .as_deref()
+        .and_then(|auth| serde_json::from_str::<serde_json::Value>(auth).ok())
+        .and_then(|auth| Some(auth.get("token")?.as_str()?.to_string()));
+    from_auth.or_else(|| {
+        let header = s.req_parts().headers.get(http::header::AUTHORIZATION)?;
+        let token = header.to_str().ok()?.strip_prefix("Bearer ")?;
+        Some(token.trim().to_string())
+    })
+}
+
+impl<A: Adapter, T: DeserializeOwned> FromConnectParts<A> for Jwt<T> {
+    type Error = JwtError;
+    fn from_connect_parts(s: &Arc<Socket<A>>, auth: &Option<String>) -> Result<Self, JwtError> {
+        let key = s
+            .get_io()
+            .get_state::<JwtKey>()
+            .ok_or(JwtError::KeyNotFound)?;
+        let token = extract_token(s, auth).ok_or(JwtError::Missing)?;
+        if !key.session_cache {
+            let data = jsonwebtoken::decode::<T>(&token, &key.key, &key.validation)?;
+            return Ok(Jwt(data.claims));
+        }
+
+        let claims: serde_json::Value = s.cached_auth(&format!("jwt:{token}"), || {
+            jsonwebtoken::decode(&token, &key.key, &key.validation).map(|data| data.claims)
+        })?;
+        let exp = claims.get("exp").and_then(serde_json::Value::as_u64);
+        if key.validation.validate_exp
+            && exp.is_some_and(|exp| {
+                exp.saturating_add(key.validation.leeway) < jsonwebtoken::get_current_timestamp()
+            })
+        {
+            return Err(JwtError::Expired);
+        }
+        let claims = serde_json::from_value(claims).map_err(jsonwebtoken::errors::Error::from)?;
+        Ok(Jwt(claims))
+    }
+}
+
+super::__impl_deref!(Jwt);
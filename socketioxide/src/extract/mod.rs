@@ -10,12 +10,29 @@
 //!     - for [`ConnectHandler`] and [`ConnectMiddleware`]:
 //! extracts and deserialize to json the auth data
 //!     - for [`MessageHandler`]: extracts and deserialize to json the message data
+//! * [`MaybeData`]: extracts and deserialize to json any data with a `Result<Option<T>>` type
+//! to distinguish absent data (`Ok(None)`) from malformed data (`Err(e)`):
+//!     - for [`ConnectHandler`] and [`ConnectMiddleware`]:
+//! extracts and deserialize to json the auth data
+//!     - for [`MessageHandler`]: extracts and deserialize to json the message data
+//! * [`Arg0`], [`Arg1`], …, [`Arg7`]: extracts and deserialize to json the event argument at the given position
+//! (only for [`MessageHandler`]). If the event has too few arguments the handler won't be called.
 //! * [`SocketRef`]: extracts a reference to the [`Socket`](crate::socket::Socket)
+//! * [`EngineSessionId`]: extracts the id of the engine.io session shared by the sockets of a client
+//! * [`Secure`]: extracts whether the client connected over a secure transport (`https` or `wss`)
 //! * [`Bin`]: extract a binary payload for a given message. Because it consumes the event it should be the last argument
+//! * [`RawPacket`]: extracts the arguments of the message as JSON bytes, undecoded with a handler registered with
+//!   [`Socket::on_raw`](crate::socket::Socket::on_raw). Because it consumes the event it should be the last argument
 //! * [`AckSender`]: Can be used to send an ack response to the current message event
 //! * [`ProtocolVersion`](crate::ProtocolVersion): extracts the protocol version
+//! * [`EIoProtocolVersion`](crate::EIoProtocolVersion): extracts the engine.io protocol version
 //! * [`TransportType`](crate::TransportType): extracts the transport type
 //! * [`DisconnectReason`](crate::socket::DisconnectReason): extracts the reason of the disconnection
+//! * [`SocketStats`](crate::socket::SocketStats): extracts the throughput counters of the socket
+//! * [`Jwt`]: verifies a JWT token sent in the auth payload or in the `Authorization` header and extracts its claims
+//! (only for [`ConnectHandler`] and [`ConnectMiddleware`]).
+//! * [`SocketIo`](crate::SocketIo): extracts a [`Clone`] of the [`SocketIo`](crate::SocketIo) handle,
+//! e.g. to broadcast to other namespaces without capturing it in every handler.
 //! * [`State`]: extracts a [`Clone`] of a state previously set with [`SocketIoBuilder::with_state`](crate::io::SocketIoBuilder).
 //! * [`Extension`]: extracts an extension of the given type stored on the called socket by cloning it.
 //! * [`MaybeExtension`]: extracts an extension of the given type if it exists or [`None`] otherwise
@@ -103,12 +120,18 @@ mod data;
 mod extensions;
 mod socket;
 
+#[cfg(feature = "jwt")]
+#[cfg_attr(docsrs, doc(cfg(feature = "jwt")))]
+mod jwt;
 #[cfg(feature = "state")]
 #[cfg_attr(docsrs, doc(cfg(feature = "state")))]
 mod state;
 
 pub use data::*;
 pub use extensions::*;
+#[cfg(feature = "jwt")]
+#[cfg_attr(docsrs, doc(cfg(feature = "jwt")))]
+pub use jwt::*;
 pub use socket::*;
 #[cfg(feature = "state")]
 #[cfg_attr(docsrs, doc(cfg(feature = "state")))]
@@ -1,16 +1,19 @@
 use std::convert::Infallible;
+use std::future::Future;
 use std::sync::Arc;
+use std::time::Duration;
 
 use crate::handler::{FromConnectParts, FromDisconnectParts, FromMessageParts};
 use crate::{
     adapter::{Adapter, LocalAdapter},
-    errors::{DisconnectError, SendError},
+    errors::{AckError, DisconnectError, SendError, SwitchNamespaceError},
     packet::Packet,
-    socket::{DisconnectReason, Socket},
+    socket::{DisconnectReason, Sid, Socket, SocketStats},
     SocketIo,
 };
 use bytes::Bytes;
 use serde::Serialize;
+use serde_json::Value;
 
 /// An Extractor that returns a reference to a [`Socket`].
 #[derive(Debug)]
@@ -74,6 +77,56 @@ impl<A: Adapter> SocketRef<A> {
     pub fn disconnect(self) -> Result<(), DisconnectError> {
         self.0.disconnect()
     }
+
+    /// Schedules the disconnection of the socket from the current namespace after the given delay.
+    ///
+    /// See [`Socket::schedule_disconnect`] for more details.
+    #[inline(always)]
+    pub fn schedule_disconnect<T: Serialize>(
+        &self,
+        delay: Duration,
+        reason: T,
+    ) -> Result<(), SendError<Value>> {
+        self.0.clone().schedule_disconnect(delay, reason)
+    }
+
+    /// Gracefully disconnects the socket once its running handlers complete, or after the timeout.
+    ///
+    /// See [`Socket::disconnect_graceful`] for more details.
+    #[inline(always)]
+    pub fn disconnect_graceful(
+        self,
+        timeout: Duration,
+    ) -> impl Future<Output = Result<(), DisconnectError>> + Send + 'static {
+        self.0.disconnect_graceful(timeout)
+    }
+
+    /// Requests the client to reconnect to another node, and disconnects the socket once it acknowledges it.
+    ///
+    /// See [`Socket::request_reconnect`] for more details.
+    #[inline(always)]
+    pub fn request_reconnect(
+        self,
+        target_hint: Option<String>,
+    ) -> impl Future<Output = Result<(), AckError<()>>> + Send + 'static {
+        self.0.request_reconnect(target_hint)
+    }
+
+    /// Emits the server time to the client now and then every `interval`.
+    ///
+    /// See [`Socket::sync_time`] for more details.
+    #[inline(always)]
+    pub fn sync_time(&self, interval: Duration) {
+        self.0.sync_time(interval)
+    }
+
+    /// Moves the socket to another namespace without closing the underlying connection.
+    ///
+    /// See [`Socket::switch_namespace`] for more details.
+    #[inline(always)]
+    pub fn switch_namespace(&self, path: impl Into<String>) -> Result<(), SwitchNamespaceError> {
+        self.0.clone().switch_namespace(path)
+    }
 }
 
 /// An Extractor to send an ack response corresponding to the current event.
@@ -111,6 +164,9 @@ impl<A: Adapter> AckSender<A> {
     }
 
     /// Send the ack response to the client.
+    ///
+    /// A payload serializing to `null`, like `()` or `None`, is sent with the shape
+    /// set with [`SocketIoBuilder::empty_ack_shape`](crate::SocketIoBuilder#method.empty_ack_shape).
     pub fn send<T: Serialize>(self, data: T) -> Result<(), SendError<T>> {
         use crate::socket::PermitExt;
         if let Some(ack_id) = self.ack_id {
@@ -123,13 +179,18 @@ impl<A: Adapter> AckSender<A> {
                 }
             };
             let ns = self.socket.ns.path.clone();
-            let data = serde_json::to_value(data)?;
+            let data = match serde_json::to_value(data)? {
+                Value::Null if self.socket.empty_ack_shape() == crate::EmptyAckShape::Null => {
+                    Value::Array(vec![Value::Null])
+                }
+                data => data,
+            };
             let packet = if self.binary.is_empty() {
                 Packet::ack(ns, data, ack_id)
             } else {
                 Packet::bin_ack(ns, data, self.binary, ack_id)
             };
-            permit.send(packet);
+            self.socket.record_bytes_out(permit.send(packet));
             Ok(())
         } else {
             Ok(())
@@ -161,6 +222,30 @@ impl<A: Adapter> FromDisconnectParts<A> for crate::ProtocolVersion {
     }
 }
 
+impl<A: Adapter> FromConnectParts<A> for crate::EIoProtocolVersion {
+    type Error = Infallible;
+    fn from_connect_parts(s: &Arc<Socket<A>>, _: &Option<String>) -> Result<Self, Infallible> {
+        Ok(s.protocol_version())
+    }
+}
+impl<A: Adapter> FromMessageParts<A> for crate::EIoProtocolVersion {
+    type Error = Infallible;
+    fn from_message_parts(
+        s: &Arc<Socket<A>>,
+        _: &mut serde_json::Value,
+        _: &mut Vec<Bytes>,
+        _: &Option<i64>,
+    ) -> Result<Self, Infallible> {
+        Ok(s.protocol_version())
+    }
+}
+impl<A: Adapter> FromDisconnectParts<A> for crate::EIoProtocolVersion {
+    type Error = Infallible;
+    fn from_disconnect_parts(s: &Arc<Socket<A>>, _: DisconnectReason) -> Result<Self, Infallible> {
+        Ok(s.protocol_version())
+    }
+}
+
 impl<A: Adapter> FromConnectParts<A> for crate::TransportType {
     type Error = Infallible;
     fn from_connect_parts(s: &Arc<Socket<A>>, _: &Option<String>) -> Result<Self, Infallible> {
@@ -185,6 +270,93 @@ impl<A: Adapter> FromDisconnectParts<A> for crate::TransportType {
     }
 }
 
+/// An Extractor that returns the id of the engine.io session backing the socket.
+///
+/// Unlike the [`Socket::id`], it is shared by all the sockets of a client connected to different namespaces.
+/// See [`Socket::session_id`] for more details.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EngineSessionId(pub Sid);
+
+impl<A: Adapter> FromConnectParts<A> for EngineSessionId {
+    type Error = Infallible;
+    fn from_connect_parts(s: &Arc<Socket<A>>, _: &Option<String>) -> Result<Self, Infallible> {
+        Ok(EngineSessionId(s.session_id()))
+    }
+}
+impl<A: Adapter> FromMessageParts<A> for EngineSessionId {
+    type Error = Infallible;
+    fn from_message_parts(
+        s: &Arc<Socket<A>>,
+        _: &mut serde_json::Value,
+        _: &mut Vec<Bytes>,
+        _: &Option<i64>,
+    ) -> Result<Self, Infallible> {
+        Ok(EngineSessionId(s.session_id()))
+    }
+}
+impl<A: Adapter> FromDisconnectParts<A> for EngineSessionId {
+    type Error = Infallible;
+    fn from_disconnect_parts(s: &Arc<Socket<A>>, _: DisconnectReason) -> Result<Self, Infallible> {
+        Ok(EngineSessionId(s.session_id()))
+    }
+}
+super::__impl_deref!(EngineSessionId: Sid);
+
+/// An Extractor that returns whether the client connected over a secure transport (`https` or `wss`).
+///
+/// See [`Socket::is_secure`] for more details.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Secure(pub bool);
+
+impl<A: Adapter> FromConnectParts<A> for Secure {
+    type Error = Infallible;
+    fn from_connect_parts(s: &Arc<Socket<A>>, _: &Option<String>) -> Result<Self, Infallible> {
+        Ok(Secure(s.is_secure()))
+    }
+}
+impl<A: Adapter> FromMessageParts<A> for Secure {
+    type Error = Infallible;
+    fn from_message_parts(
+        s: &Arc<Socket<A>>,
+        _: &mut serde_json::Value,
+        _: &mut Vec<Bytes>,
+        _: &Option<i64>,
+    ) -> Result<Self, Infallible> {
+        Ok(Secure(s.is_secure()))
+    }
+}
+impl<A: Adapter> FromDisconnectParts<A> for Secure {
+    type Error = Infallible;
+    fn from_disconnect_parts(s: &Arc<Socket<A>>, _: DisconnectReason) -> Result<Self, Infallible> {
+        Ok(Secure(s.is_secure()))
+    }
+}
+super::__impl_deref!(Secure: bool);
+
+impl<A: Adapter> FromConnectParts<A> for SocketStats {
+    type Error = Infallible;
+    fn from_connect_parts(s: &Arc<Socket<A>>, _: &Option<String>) -> Result<Self, Infallible> {
+        Ok(s.stats())
+    }
+}
+impl<A: Adapter> FromMessageParts<A> for SocketStats {
+    type Error = Infallible;
+    fn from_message_parts(
+        s: &Arc<Socket<A>>,
+        _: &mut serde_json::Value,
+        _: &mut Vec<Bytes>,
+        _: &Option<i64>,
+    ) -> Result<Self, Infallible> {
+        Ok(s.stats())
+    }
+}
+impl<A: Adapter> FromDisconnectParts<A> for SocketStats {
+    type Error = Infallible;
+    fn from_disconnect_parts(s: &Arc<Socket<A>>, _: DisconnectReason) -> Result<Self, Infallible> {
+        Ok(s.stats())
+    }
+}
+
 impl<A: Adapter> FromDisconnectParts<A> for DisconnectReason {
     type Error = Infallible;
     fn from_disconnect_parts(
@@ -0,0 +1,182 @@
+//! In-memory groups of sockets, for transient sets of sockets that don't warrant an adapter room.
+//!
+//! A [`SocketGroup`] is built by collecting [`SocketRef`]s, and can then emit to or disconnect all
+//! its members at once. Unlike a room, it is not shared with the adapter: it only lives in the memory
+//! of this server and can contain sockets of different namespaces.
+//!
+//! The disconnected members are automatically removed from the group the next time it is used,
+//! so they are never reached by a group operation.
+//!
+//! #### Example
+//! ```
+//! # use socketioxide::{SocketIo, extract::*, group::SocketGroup};
+//! let (_, io) = SocketIo::new_svc();
+//! let players = SocketGroup::new();
+//! io.ns("/", move |socket: SocketRef| {
+//!     players.insert(socket);
+//!     if players.len() == 2 {
+//!         players.emit("start", "game").ok();
+//!     }
+//! });
+//! ```
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use engineioxide::sid::Sid;
+use serde::Serialize;
+
+use crate::{
+    adapter::{Adapter, LocalAdapter},
+    errors::{BroadcastError, DisconnectError},
+    extract::SocketRef,
+    packet::Packet,
+};
+
+/// A cheaply cloneable set of sockets, see the [module doc](self) for more details.
+///
+/// The clones of a group share the same members.
+pub struct SocketGroup<A: Adapter = LocalAdapter> {
+    members: Arc<Mutex<HashMap<Sid, SocketRef<A>>>>,
+}
+
+impl<A: Adapter> SocketGroup<A> {
+    /// Creates an empty group.
+    pub fn new() -> Self {
+        Self {
+            members: Default::default(),
+        }
+    }
+
+    /// Adds a socket to the group. Returns `false` if it was already a member.
+    ///
+    /// A socket that is not connected is not added.
+    pub fn insert(&self, socket: SocketRef<A>) -> bool {
+        if !socket.connected() {
+            return false;
+        }
+        self.members
+            .lock()
+            .unwrap()
+            .insert(socket.id, socket)
+            .is_none()
+    }
+
+    /// Removes a socket from the group. Returns `false` if it was not a member.
+    pub fn remove(&self, sid: Sid) -> bool {
+        self.members.lock().unwrap().remove(&sid).is_some()
+    }
+
+    /// Returns `true` if the socket is a connected member of the group.
+    pub fn contains(&self, sid: Sid) -> bool {
+        self.sockets().iter().any(|s| s.id == sid)
+    }
+
+    /// Returns the number of connected members.
+    pub fn len(&self) -> usize {
+        self.sockets().len()
+    }
+
+    /// Returns `true` if the group has no connected members.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the connected members, after removing the disconnected ones from the group.
+    pub fn sockets(&self) -> Vec<SocketRef<A>> {
+        let mut members = self.members.lock().unwrap();
+        members.retain(|_, socket| socket.connected());
+        members.values().cloned().collect()
+    }
+
+    /// Emits a message to all the connected members of the group.
+    ///
+    /// The data is serialized once, and the packet is sent to each member on its own namespace.
+    ///
+    /// ## Errors
+    /// * When encoding the data into JSON a [`BroadcastError::Serialize`] is returned.
+    /// * If the packet could not be sent to some members a [`BroadcastError::Socket`] is returned
+    ///   with their errors, the message is still sent to the other members.
+    pub fn emit<T: Serialize>(
+        &self,
+        event: impl Into<Cow<'static, str>>,
+        data: T,
+    ) -> Result<(), BroadcastError> {
+        let (data, bins) = crate::to_value(data)?;
+        let event = event.into();
+        let sockets = self.sockets();
+        if let Some(socket) = sockets.first() {
+            socket.ns.check_schema(&event, &data)?;
+        }
+        let errors: Vec<_> = sockets
+            .into_iter()
+            .filter_map(|socket| {
+                let ns = socket.ns.path.clone();
+                let packet = match bins.is_empty() {
+                    true => Packet::event(ns, event.clone(), data.clone()),
+                    false => Packet::bin_event(ns, event.clone(), data.clone(), bins.clone()),
+                };
+                socket.send(packet).err()
+            })
+            .collect();
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors.into())
+        }
+    }
+
+    /// Disconnects all the connected members of the group, which is then empty.
+    ///
+    /// ## Errors
+    /// The errors of the members that could not be disconnected are returned,
+    /// the other members are still disconnected.
+    pub fn disconnect(&self) -> Result<(), Vec<DisconnectError>> {
+        let sockets: Vec<_> = self.members.lock().unwrap().drain().collect();
+        let errors: Vec<_> = sockets
+            .into_iter()
+            .filter(|(_, socket)| socket.connected())
+            .filter_map(|(_, socket)| socket.disconnect().err())
+            .collect();
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+impl<A: Adapter> Default for SocketGroup<A> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<A: Adapter> Clone for SocketGroup<A> {
+    fn clone(&self) -> Self {
+        Self {
+            members: self.members.clone(),
+        }
+    }
+}
+
+impl<A: Adapter> FromIterator<SocketRef<A>> for SocketGroup<A> {
+    fn from_iter<I: IntoIterator<Item = SocketRef<A>>>(iter: I) -> Self {
+        let group = Self::new();
+        for socket in iter {
+            group.insert(socket);
+        }
+        group
+    }
+}
+
+impl<A: Adapter> std::fmt::Debug for SocketGroup<A> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let members: Vec<_> = self.members.lock().unwrap().keys().copied().collect();
+        f.debug_struct("SocketGroup")
+            .field("members", &members)
+            .finish()
+    }
+}
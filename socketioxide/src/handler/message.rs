@@ -8,6 +8,8 @@
 //! See the [`extract`](crate::extract) module doc for more details on available extractors.
 //!
 //! Handlers can be _optionally_ async.
+//! Sync handlers are called inline when the packet is received, CPU-heavy ones can be moved
+//! to the blocking thread pool with [`MessageHandler::blocking`].
 //!
 //! ## Example with sync closures
 //! ```rust
@@ -75,6 +77,7 @@ use std::sync::Arc;
 
 use bytes::Bytes;
 use futures_core::Future;
+use futures_util::future::BoxFuture;
 use serde_json::Value;
 
 use crate::adapter::Adapter;
@@ -86,7 +89,20 @@ use super::MakeErasedHandler;
 pub(crate) type BoxedMessageHandler<A> = Box<dyn ErasedMessageHandler<A>>;
 
 pub(crate) trait ErasedMessageHandler<A: Adapter>: Send + Sync + 'static {
-    fn call(&self, s: Arc<Socket<A>>, v: Value, p: Vec<Bytes>, ack_id: Option<i64>);
+    fn call_deferred(
+        &self,
+        s: Arc<Socket<A>>,
+        v: Value,
+        p: Vec<Bytes>,
+        ack_id: Option<i64>,
+    ) -> Option<BoxFuture<'static, ()>>;
+    fn call_unspawned(
+        &self,
+        s: Arc<Socket<A>>,
+        v: Value,
+        p: Vec<Bytes>,
+        ack_id: Option<i64>,
+    ) -> Option<BoxFuture<'static, ()>>;
 }
 
 /// Define a handler for the connect event.
@@ -104,12 +120,129 @@ pub trait MessageHandler<A: Adapter, T>: Send + Sync + 'static {
     /// Call the handler with the given arguments
     fn call(&self, s: Arc<Socket<A>>, v: Value, p: Vec<Bytes>, ack_id: Option<i64>);
 
+    /// Runs the handler on the tokio blocking thread pool with [`tokio::task::spawn_blocking`]
+    /// instead of running it inline on the task dispatching the socket packets.
+    ///
+    /// It is useful for CPU-heavy sync handlers that would otherwise stall the runtime
+    /// and the processing of the other packets. The extractors are also run on the blocking thread.
+    /// There is no point in using it with async handlers, they are already spawned on the runtime.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use socketioxide::{SocketIo, handler::MessageHandler, extract::*};
+    /// fn hash(s: SocketRef, Data(data): Data<String>) {
+    ///     let hash = data.bytes().fold(0u64, |h, b| h.wrapping_mul(31).wrapping_add(b as u64));
+    ///     s.emit("hash", hash).ok();
+    /// }
+    ///
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.ns("/", |s: SocketRef| {
+    ///     s.on("hash", hash.blocking());
+    /// });
+    /// ```
+    fn blocking(self) -> impl MessageHandler<A, T>
+    where
+        Self: Sized,
+        T: Send + Sync + 'static,
+    {
+        BlockingMessageHandler {
+            handler: Arc::new(self),
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// Returns a future calling the handler instead of calling it or spawning it right away.
+    /// It is used to process the events of [ordered namespaces](crate::SocketIo#method.ordered_ns) sequentially.
+    /// Returns `None` if the handler should not be called.
+    #[doc(hidden)]
+    fn call_deferred(
+        &self,
+        s: Arc<Socket<A>>,
+        v: Value,
+        p: Vec<Bytes>,
+        ack_id: Option<i64>,
+    ) -> Option<BoxFuture<'static, ()>> {
+        self.call(s, v, p, ack_id);
+        None
+    }
+
+    /// Calls the handler as [`MessageHandler::call`] does, but returns the future of an async handler
+    /// instead of spawning it, so that the caller can catch its panics.
+    /// Returns `None` if the handler already ran or should not be called.
+    #[doc(hidden)]
+    fn call_unspawned(
+        &self,
+        s: Arc<Socket<A>>,
+        v: Value,
+        p: Vec<Bytes>,
+        ack_id: Option<i64>,
+    ) -> Option<BoxFuture<'static, ()>> {
+        self.call(s, v, p, ack_id);
+        None
+    }
+
     #[doc(hidden)]
     fn phantom(&self) -> std::marker::PhantomData<T> {
         std::marker::PhantomData
     }
 }
 
+struct BlockingMessageHandler<A, H, T> {
+    handler: Arc<H>,
+    phantom: std::marker::PhantomData<(A, T)>,
+}
+impl<A, H, T> MessageHandler<A, T> for BlockingMessageHandler<A, H, T>
+where
+    A: Adapter,
+    H: MessageHandler<A, T>,
+    T: Send + Sync + 'static,
+{
+    fn call(&self, s: Arc<Socket<A>>, v: Value, p: Vec<Bytes>, ack_id: Option<i64>) {
+        let handler = self.handler.clone();
+        tokio::task::spawn_blocking(move || handler.call(s, v, p, ack_id));
+    }
+
+    fn call_deferred(
+        &self,
+        s: Arc<Socket<A>>,
+        v: Value,
+        p: Vec<Bytes>,
+        ack_id: Option<i64>,
+    ) -> Option<BoxFuture<'static, ()>> {
+        let handler = self.handler.clone();
+        let rt = tokio::runtime::Handle::current();
+        Some(Box::pin(async move {
+            let handle = tokio::task::spawn_blocking(move || {
+                if let Some(fut) = handler.call_deferred(s, v, p, ack_id) {
+                    rt.block_on(fut);
+                }
+            });
+            resume_panic(handle.await);
+        }))
+    }
+
+    fn call_unspawned(
+        &self,
+        s: Arc<Socket<A>>,
+        v: Value,
+        p: Vec<Bytes>,
+        ack_id: Option<i64>,
+    ) -> Option<BoxFuture<'static, ()>> {
+        let handler = self.handler.clone();
+        let handle = tokio::task::spawn_blocking(move || handler.call(s, v, p, ack_id));
+        Some(Box::pin(async move { resume_panic(handle.await) }))
+    }
+}
+
+/// Forwards the panic of a blocking handler to the task awaiting it.
+fn resume_panic(res: Result<(), tokio::task::JoinError>) {
+    if let Err(e) = res {
+        if e.is_panic() {
+            std::panic::resume_unwind(e.into_panic());
+        }
+    }
+}
+
 impl<A, T, H> MakeErasedHandler<H, A, T>
 where
     T: Send + Sync + 'static,
@@ -128,8 +261,25 @@ where
     A: Adapter,
 {
     #[inline(always)]
-    fn call(&self, s: Arc<Socket<A>>, v: Value, p: Vec<Bytes>, ack_id: Option<i64>) {
-        self.handler.call(s, v, p, ack_id);
+    fn call_deferred(
+        &self,
+        s: Arc<Socket<A>>,
+        v: Value,
+        p: Vec<Bytes>,
+        ack_id: Option<i64>,
+    ) -> Option<BoxFuture<'static, ()>> {
+        self.handler.call_deferred(s, v, p, ack_id)
+    }
+
+    #[inline(always)]
+    fn call_unspawned(
+        &self,
+        s: Arc<Socket<A>>,
+        v: Value,
+        p: Vec<Bytes>,
+        ack_id: Option<i64>,
+    ) -> Option<BoxFuture<'static, ()>> {
+        self.handler.call_unspawned(s, v, p, ack_id)
     }
 }
 
@@ -224,6 +374,27 @@ where
         let fut = (self.clone())();
         tokio::spawn(fut);
     }
+
+    fn call_deferred(
+        &self,
+        _: Arc<Socket<A>>,
+        _: Value,
+        _: Vec<Bytes>,
+        _: Option<i64>,
+    ) -> Option<BoxFuture<'static, ()>> {
+        let f = self.clone();
+        Some(Box::pin(async move { f().await }))
+    }
+
+    fn call_unspawned(
+        &self,
+        s: Arc<Socket<A>>,
+        v: Value,
+        p: Vec<Bytes>,
+        ack_id: Option<i64>,
+    ) -> Option<BoxFuture<'static, ()>> {
+        MessageHandler::<A, (private::Async,)>::call_deferred(self, s, v, p, ack_id)
+    }
 }
 
 /// Empty Sync handler
@@ -235,6 +406,17 @@ where
     fn call(&self, _: Arc<Socket<A>>, _: Value, _: Vec<Bytes>, _: Option<i64>) {
         (self.clone())();
     }
+
+    fn call_deferred(
+        &self,
+        _: Arc<Socket<A>>,
+        _: Value,
+        _: Vec<Bytes>,
+        _: Option<i64>,
+    ) -> Option<BoxFuture<'static, ()>> {
+        let f = self.clone();
+        Some(Box::pin(async move { f() }))
+    }
 }
 
 macro_rules! impl_async_handler {
@@ -254,18 +436,16 @@ macro_rules! impl_async_handler {
                 $(
                     let $ty = match $ty::from_message_parts(&s, &mut v, &mut p, &ack_id) {
                         Ok(v) => v,
-                        Err(_e) => {
-                            #[cfg(feature = "tracing")]
-                            tracing::error!("Error while extracting data: {}", _e);
+                        Err(e) => {
+                            s.extract_error(&e, ack_id);
                             return;
                         },
                     };
                 )*
-                let last = match $last::from_message(s, v, p, ack_id) {
+                let last = match $last::from_message(s.clone(), v, p, ack_id) {
                     Ok(v) => v,
-                    Err(_e) => {
-                        #[cfg(feature = "tracing")]
-                        tracing::error!("Error while extracting data: {}", _e);
+                    Err(e) => {
+                        s.extract_error(&e, ack_id);
                         return;
                     },
                 };
@@ -273,6 +453,43 @@ macro_rules! impl_async_handler {
                 let fut = (self.clone())($($ty,)* last);
                 tokio::spawn(fut);
             }
+
+            fn call_deferred(
+                &self,
+                s: Arc<Socket<A>>,
+                mut v: Value,
+                mut p: Vec<Bytes>,
+                ack_id: Option<i64>,
+            ) -> Option<BoxFuture<'static, ()>> {
+                $(
+                    let $ty = match $ty::from_message_parts(&s, &mut v, &mut p, &ack_id) {
+                        Ok(v) => v,
+                        Err(e) => {
+                            s.extract_error(&e, ack_id);
+                            return None;
+                        },
+                    };
+                )*
+                let last = match $last::from_message(s.clone(), v, p, ack_id) {
+                    Ok(v) => v,
+                    Err(e) => {
+                        s.extract_error(&e, ack_id);
+                        return None;
+                    },
+                };
+
+                Some(Box::pin((self.clone())($($ty,)* last)))
+            }
+
+            fn call_unspawned(
+                &self,
+                s: Arc<Socket<A>>,
+                v: Value,
+                p: Vec<Bytes>,
+                ack_id: Option<i64>,
+            ) -> Option<BoxFuture<'static, ()>> {
+                MessageHandler::<A, (private::Async, M, $($ty,)* $last,)>::call_deferred(self, s, v, p, ack_id)
+            }
         }
     };
 }
@@ -292,16 +509,35 @@ macro_rules! impl_handler {
                 $(
                     let $ty = match $ty::from_message_parts(&s, &mut v, &mut p, &ack_id) {
                         Ok(v) => v,
-                        Err(_) => return,
+                        Err(e) => {
+                            s.extract_error(&e, ack_id);
+                            return;
+                        },
                     };
                 )*
-                let last = match $last::from_message(s, v, p, ack_id) {
+                let last = match $last::from_message(s.clone(), v, p, ack_id) {
                     Ok(v) => v,
-                    Err(_) => return,
+                    Err(e) => {
+                        s.extract_error(&e, ack_id);
+                        return;
+                    },
                 };
 
                 (self.clone())($($ty,)* last);
             }
+
+            fn call_deferred(
+                &self,
+                s: Arc<Socket<A>>,
+                v: Value,
+                p: Vec<Bytes>,
+                ack_id: Option<i64>,
+            ) -> Option<BoxFuture<'static, ()>> {
+                let f = self.clone();
+                Some(Box::pin(async move {
+                    MessageHandler::<A, (private::Sync, M, $($ty,)* $last,)>::call(&f, s, v, p, ack_id)
+                }))
+            }
         }
     };
 }
@@ -7,20 +7,33 @@ use engineioxide::{
     sid::Sid,
     TransportType,
 };
+use futures_util::stream::BoxStream;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
 
 use crate::{
     ack::AckStream,
-    adapter::{Adapter, LocalAdapter, Room},
+    adapter::{Adapter, AdapterState, LocalAdapter, MembershipEvent, Room},
     client::Client,
-    extract::SocketRef,
+    extract::{Data, SocketRef},
     handler::ConnectHandler,
     layer::SocketIoLayer,
+    lifecycle::{
+        BoxedConnectedHook, BoxedEventRewriter, BoxedJoinHook, BoxedLeaveHook, DropEvent,
+        LifecycleEvent,
+    },
     operators::{
         holding::{WithBinary, WithoutBinary},
-        BroadcastOperators, RoomParam,
+        BroadcastOperators, PreparedPacket, RoomParam,
     },
+    packet::EncodedPacket,
+    presence::Presence,
+    readiness::ReadinessGate,
+    remote::RemoteSocket,
+    room_meta::RoomMeta,
     service::SocketIoService,
-    BroadcastError, DisconnectError,
+    socket::DisconnectReason,
+    BroadcastError, DisconnectError, JoinError,
 };
 
 /// Configuration for Socket.IO & Engine.IO
@@ -38,6 +51,397 @@ pub struct SocketIoConfig {
     ///
     /// Defaults to 45 seconds.
     pub connect_timeout: Duration,
+
+    /// The amount of time without any incoming application activity before disconnecting a socket
+    /// from its namespace. See [`SocketIoBuilder::idle_timeout`].
+    ///
+    /// Defaults to `None`.
+    pub idle_timeout: Option<Duration>,
+
+    /// The interval at which the server time is emitted to every socket. See [`SocketIoBuilder::time_sync`].
+    ///
+    /// Defaults to `None`.
+    pub time_sync: Option<Duration>,
+
+    /// The maximum number of bytes a socket can receive during each [`SocketIoConfig::byte_quota_window`]
+    /// before being disconnected. See [`SocketIoBuilder::byte_quota`].
+    ///
+    /// Defaults to `None`.
+    pub byte_quota: Option<u64>,
+
+    /// The window after which the [`SocketIoConfig::byte_quota`] of each socket is reset.
+    ///
+    /// Defaults to 1 second.
+    pub byte_quota_window: Duration,
+
+    /// The number of broadcast packets kept by each socket to be replayed after a gap,
+    /// see [`SocketIoBuilder::replay_buffer`].
+    ///
+    /// Defaults to `None`.
+    pub replay_buffer_size: Option<usize>,
+
+    /// The rooms every socket joins right after connecting to a namespace,
+    /// before the connect handler is called.
+    ///
+    /// Defaults to no rooms.
+    pub default_rooms: Vec<Room>,
+
+    /// Whether every socket joins a room named after its own id right after connecting to a namespace.
+    /// See [`SocketIoBuilder::auto_sid_room`].
+    ///
+    /// Defaults to `false`.
+    pub auto_sid_room: bool,
+
+    /// An optional gate holding the namespace connections until it is open.
+    /// See [`SocketIoBuilder::readiness_gate`].
+    ///
+    /// Defaults to `None`.
+    pub readiness_gate: Option<ReadinessGate>,
+
+    /// The maximum amount of time a namespace connection is held by a closed [`SocketIoConfig::readiness_gate`]
+    /// before being rejected.
+    ///
+    /// Defaults to 5 seconds.
+    pub readiness_timeout: Duration,
+
+    /// The maximum amount of time to wait for the connections to be closed
+    /// when the server is gracefully shut down with [`SocketIo::serve_with_graceful_shutdown`].
+    ///
+    /// Defaults to 10 seconds.
+    pub drain_timeout: Duration,
+
+    /// An optional callback to extract the ack timeout of each socket from its handshake auth payload.
+    /// See [`SocketIoBuilder::ack_timeout_from_auth`].
+    ///
+    /// Defaults to `None`.
+    pub ack_timeout_extractor: Option<AckTimeoutExtractor>,
+
+    /// An optional callback to extract the reconnection attempt count reported by each client in its handshake auth payload.
+    /// See [`SocketIoBuilder::reconnect_attempts_from_auth`].
+    ///
+    /// Defaults to `None`.
+    pub reconnect_attempts_extractor: Option<ReconnectAttemptsExtractor>,
+
+    /// A callback extracting the user id of a socket from its handshake auth payload.
+    /// See [`SocketIoBuilder::user_id_from_auth`].
+    ///
+    /// Defaults to `None`.
+    pub user_id_extractor: Option<UserIdExtractor>,
+
+    /// The behavior when a socket connects with the user id of a socket already connected to the namespace.
+    /// See [`SocketIoBuilder::duplicate_policy`].
+    ///
+    /// Defaults to [`DuplicatePolicy::AllowMultiple`].
+    pub duplicate_policy: DuplicatePolicy,
+
+    /// The behavior of the broadcasts to a room frozen with [`SocketIo::freeze_room`].
+    /// See [`SocketIoBuilder::freeze_policy`].
+    ///
+    /// Defaults to [`FreezePolicy::Queue`].
+    pub freeze_policy: FreezePolicy,
+
+    /// The behavior when an outgoing payload doesn't match the schema of its event.
+    /// See [`SocketIoBuilder::schema_policy`].
+    ///
+    /// Defaults to [`SchemaPolicy::Reject`](crate::schema::SchemaPolicy::Reject).
+    #[cfg(feature = "schema")]
+    pub schema_policy: crate::schema::SchemaPolicy,
+
+    /// The behavior when a client emits an event without any registered handler.
+    ///
+    /// Defaults to [`UnknownEventPolicy::Ignore`].
+    pub unknown_event_policy: UnknownEventPolicy,
+
+    /// The behavior when an event handler panics.
+    ///
+    /// Defaults to [`HandlerPanicPolicy::Propagate`].
+    pub handler_panic_policy: HandlerPanicPolicy,
+
+    /// The maximum number of event handlers that can be registered on a single socket.
+    /// See [`SocketIoBuilder::max_listeners`].
+    ///
+    /// Defaults to `None` (no limit).
+    pub max_listeners: Option<usize>,
+
+    /// The behavior when more than [`SocketIoConfig::max_listeners`] event handlers are registered on a socket.
+    ///
+    /// Defaults to [`MaxListenersPolicy::Log`].
+    pub max_listeners_policy: MaxListenersPolicy,
+
+    /// The maximum number of rooms a single socket can be in.
+    /// See [`SocketIoBuilder::max_rooms_per_socket`].
+    ///
+    /// Defaults to `None` (no limit).
+    pub max_rooms_per_socket: Option<usize>,
+
+    /// The maximum number of acknowledgements requested by the server that can be pending on a single socket.
+    /// See [`SocketIoBuilder::max_pending_acks`].
+    ///
+    /// Defaults to `None` (no limit).
+    pub max_pending_acks: Option<usize>,
+
+    /// The maximum length in bytes of the event names received from the clients.
+    /// See [`SocketIoBuilder::max_event_name_len`].
+    ///
+    /// Defaults to `None` (no limit).
+    pub max_event_name_len: Option<usize>,
+
+    /// The maximum number of arguments of the events received from the clients.
+    /// See [`SocketIoBuilder::max_event_args`].
+    ///
+    /// Defaults to `None` (no limit).
+    pub max_event_args: Option<usize>,
+
+    /// The maximum number of binary attachments of the packets received from the clients.
+    /// See [`SocketIoBuilder::max_attachments`].
+    ///
+    /// Defaults to `None` (no limit).
+    pub max_attachments: Option<usize>,
+
+    /// The behavior when a received event exceeds the [`SocketIoConfig::max_event_name_len`]
+    /// or the [`SocketIoConfig::max_event_args`] limits.
+    ///
+    /// Defaults to [`EventLimitsPolicy::Close`].
+    pub event_limits_policy: EventLimitsPolicy,
+
+    /// Whether the [`Data`](crate::extract::Data) like extractors reject the payloads
+    /// containing fields that are not part of the deserialized type.
+    /// See [`SocketIoBuilder::deny_unknown_fields`].
+    ///
+    /// Defaults to `false`.
+    pub deny_unknown_fields: bool,
+
+    /// Whether the emitted events are tagged with a per-socket monotonic packet id.
+    /// See [`SocketIoBuilder::packet_ids`].
+    ///
+    /// Defaults to `false`.
+    pub packet_ids: bool,
+
+    /// Whether the error acks sent when an extractor fails contain the detailed error message.
+    /// See [`SocketIoBuilder::expose_deser_errors`].
+    ///
+    /// Defaults to `false`.
+    pub expose_deser_errors: bool,
+
+    /// The behavior when a client sends a packet to a namespace before being connected to it.
+    /// See [`SocketIoBuilder::early_packet_policy`].
+    ///
+    /// Defaults to [`EarlyPacketPolicy::Drop`].
+    pub early_packet_policy: EarlyPacketPolicy,
+
+    /// The wire shape of the acknowledgements sent with a `()` or `None` payload.
+    /// See [`SocketIoBuilder::empty_ack_shape`].
+    ///
+    /// Defaults to [`EmptyAckShape::NoArgs`].
+    pub empty_ack_shape: EmptyAckShape,
+
+    /// Whether the `X-Forwarded-Proto` header set by a reverse proxy is trusted
+    /// to tell if a connection is secure. See [`SocketIoBuilder::trust_forwarded_proto`].
+    ///
+    /// Defaults to `false`.
+    pub trust_forwarded_proto: bool,
+}
+
+/// The behavior when a client emits an event without any registered handler.
+/// It is set with [`SocketIoBuilder::unknown_event_policy`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum UnknownEventPolicy {
+    /// The event is silently ignored.
+    #[default]
+    Ignore,
+    /// The event is logged at the `warn` level. It requires the `tracing` feature.
+    Log,
+    /// If the event expects an acknowledgement, an error ack `{ "code": "unknown_event" }` is sent back.
+    /// Otherwise the event is ignored.
+    ErrorAck,
+}
+
+/// The behavior when an event handler panics.
+/// It is set with [`SocketIoBuilder::handler_panic_policy`].
+///
+/// When the panic is caught, it is logged at the `error` level with the socket id and the event name
+/// (it requires the `tracing` feature) and a [`LifecycleEvent::Error`] is emitted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HandlerPanicPolicy {
+    /// The panic is not caught: it unwinds the task running the handler, as without this option.
+    #[default]
+    Propagate,
+    /// The panic is caught and the socket stays connected.
+    Log,
+    /// The panic is caught and the socket is disconnected from its namespace
+    /// with the [`DisconnectReason::HandlerPanic`] reason.
+    ///
+    /// [`DisconnectReason::HandlerPanic`]: crate::socket::DisconnectReason::HandlerPanic
+    Disconnect,
+}
+
+/// The behavior when a client sends a packet to a namespace it is not connected to yet,
+/// e.g. an event sent before its connect packet was answered, which is a protocol violation.
+/// It is set with [`SocketIoBuilder::early_packet_policy`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EarlyPacketPolicy {
+    /// The packet is dropped and a warning is logged. Logging requires the `tracing` feature.
+    #[default]
+    Drop,
+    /// The packet is buffered and handled once the socket is connected to the namespace.
+    /// The buffered packets are dropped if the connection is rejected.
+    ///
+    /// At most 32 packets are buffered for each namespace, the next ones are dropped.
+    Buffer,
+    /// The connection is closed.
+    Disconnect,
+}
+
+/// The behavior when a socket receives an event once its disconnection has started, e.g. an event
+/// still in flight on the wire while it is [draining](crate::socket::Socket#method.disconnect_graceful)
+/// or being disconnected. It is set per namespace with [`SocketIo::disconnecting_event_policy`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DisconnectingEventPolicy {
+    /// The event is dispatched to its handler as if the socket was still connected.
+    /// The emits of the handler to the socket then fail.
+    Dispatch,
+    /// The event is dropped.
+    #[default]
+    Drop,
+    /// The event is dropped and a warning is logged. Logging requires the `tracing` feature.
+    DropWithLog,
+}
+
+/// The behavior when a received event exceeds the limits set with [`SocketIoBuilder::max_event_name_len`]
+/// or [`SocketIoBuilder::max_event_args`].
+/// It is set with [`SocketIoBuilder::event_limits_policy`].
+///
+/// In both cases a [`LifecycleEvent::Error`] is emitted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EventLimitsPolicy {
+    /// The connection is closed, as for any invalid packet.
+    #[default]
+    Close,
+    /// The event is dropped and the connection is kept open.
+    Drop,
+}
+
+/// The wire shape of an acknowledgement sent with a `()` or `None` payload,
+/// which serialize to `null`.
+/// It is set with [`SocketIoBuilder::empty_ack_shape`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EmptyAckShape {
+    /// The ack is sent without any argument (`[]`): the client callback is called with no argument.
+    #[default]
+    NoArgs,
+    /// The ack is sent with a single `null` argument (`[null]`).
+    Null,
+}
+
+/// The behavior when more event handlers than the configured limit are registered on a socket.
+/// It is set with [`SocketIoBuilder::max_listeners`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MaxListenersPolicy {
+    /// The handler is registered and a warning is logged. It requires the `tracing` feature.
+    #[default]
+    Log,
+    /// The handler is not registered and a [`LifecycleEvent::Error`] is emitted.
+    Reject,
+}
+
+/// A callback extracting the ack timeout of a socket from its handshake auth payload.
+/// It is set with [`SocketIoBuilder::ack_timeout_from_auth`].
+#[derive(Clone)]
+pub struct AckTimeoutExtractor(Arc<AckTimeoutFn>);
+type AckTimeoutFn = dyn Fn(&Value) -> Option<Duration> + Send + Sync + 'static;
+
+impl AckTimeoutExtractor {
+    /// Creates a new extractor from a callback.
+    pub fn new(f: impl Fn(&Value) -> Option<Duration> + Send + Sync + 'static) -> Self {
+        Self(Arc::new(f))
+    }
+
+    /// Extracts the ack timeout from the given auth payload.
+    pub fn extract(&self, auth: &Value) -> Option<Duration> {
+        (self.0)(auth)
+    }
+}
+
+impl std::fmt::Debug for AckTimeoutExtractor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AckTimeoutExtractor")
+            .finish_non_exhaustive()
+    }
+}
+
+/// A callback extracting the reconnection attempt count reported by a client in its handshake auth payload.
+/// It is set with [`SocketIoBuilder::reconnect_attempts_from_auth`].
+#[derive(Clone)]
+pub struct ReconnectAttemptsExtractor(Arc<ReconnectAttemptsFn>);
+type ReconnectAttemptsFn = dyn Fn(&Value) -> Option<u32> + Send + Sync + 'static;
+
+impl ReconnectAttemptsExtractor {
+    /// Creates a new extractor from a callback.
+    pub fn new(f: impl Fn(&Value) -> Option<u32> + Send + Sync + 'static) -> Self {
+        Self(Arc::new(f))
+    }
+
+    /// Extracts the reconnection attempt count from the given auth payload.
+    pub fn extract(&self, auth: &Value) -> Option<u32> {
+        (self.0)(auth)
+    }
+}
+
+impl std::fmt::Debug for ReconnectAttemptsExtractor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ReconnectAttemptsExtractor")
+            .finish_non_exhaustive()
+    }
+}
+
+/// A callback extracting the user id of a socket from its handshake auth payload.
+/// It is set with [`SocketIoBuilder::user_id_from_auth`].
+#[derive(Clone)]
+pub struct UserIdExtractor(Arc<UserIdFn>);
+type UserIdFn = dyn Fn(&Value) -> Option<String> + Send + Sync + 'static;
+
+impl UserIdExtractor {
+    /// Creates a new extractor from a callback.
+    pub fn new(f: impl Fn(&Value) -> Option<String> + Send + Sync + 'static) -> Self {
+        Self(Arc::new(f))
+    }
+
+    /// Extracts the user id from the given auth payload.
+    pub fn extract(&self, auth: &Value) -> Option<String> {
+        (self.0)(auth)
+    }
+}
+
+impl std::fmt::Debug for UserIdExtractor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UserIdExtractor").finish_non_exhaustive()
+    }
+}
+
+/// The behavior when a socket connects to a namespace with the user id of an already connected socket.
+/// The user ids are read with [`SocketIoBuilder::user_id_from_auth`].
+/// It is set with [`SocketIoBuilder::duplicate_policy`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DuplicatePolicy {
+    /// Every connection is accepted, e.g. for users connected from multiple devices.
+    #[default]
+    AllowMultiple,
+    /// The new connection is accepted and the previous sockets of the user are disconnected
+    /// from the namespace, to keep a single session per user.
+    KickOld,
+    /// The new connection is rejected with a `connect_error` packet.
+    RejectNew,
+}
+
+/// The behavior of the broadcasts to a room frozen with [`SocketIo::freeze_room`].
+/// It is set with [`SocketIoBuilder::freeze_policy`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FreezePolicy {
+    /// The broadcasts are queued and sent in order when the room is unfrozen.
+    #[default]
+    Queue,
+    /// The broadcasts are dropped.
+    Drop,
 }
 
 impl Default for SocketIoConfig {
@@ -49,6 +453,39 @@ impl Default for SocketIoConfig {
             },
             ack_timeout: Duration::from_secs(5),
             connect_timeout: Duration::from_secs(45),
+            idle_timeout: None,
+            time_sync: None,
+            byte_quota: None,
+            byte_quota_window: Duration::from_secs(1),
+            replay_buffer_size: None,
+            default_rooms: Vec::new(),
+            auto_sid_room: false,
+            readiness_gate: None,
+            readiness_timeout: Duration::from_secs(5),
+            drain_timeout: Duration::from_secs(10),
+            ack_timeout_extractor: None,
+            reconnect_attempts_extractor: None,
+            user_id_extractor: None,
+            duplicate_policy: DuplicatePolicy::AllowMultiple,
+            freeze_policy: FreezePolicy::Queue,
+            #[cfg(feature = "schema")]
+            schema_policy: crate::schema::SchemaPolicy::Reject,
+            unknown_event_policy: UnknownEventPolicy::Ignore,
+            handler_panic_policy: HandlerPanicPolicy::Propagate,
+            max_listeners: None,
+            max_listeners_policy: MaxListenersPolicy::Log,
+            max_rooms_per_socket: None,
+            max_pending_acks: None,
+            max_event_name_len: None,
+            max_event_args: None,
+            max_attachments: None,
+            event_limits_policy: EventLimitsPolicy::Close,
+            deny_unknown_fields: false,
+            packet_ids: false,
+            expose_deser_errors: false,
+            early_packet_policy: EarlyPacketPolicy::Drop,
+            empty_ack_shape: EmptyAckShape::NoArgs,
+            trust_forwarded_proto: false,
         }
     }
 }
@@ -77,6 +514,8 @@ impl<A: Adapter> SocketIoBuilder<A> {
     }
 
     /// The path to listen for socket.io requests on.
+    /// It must include the prefix of the service if it is mounted under a sub-path by a router that doesn't strip it,
+    /// see [mounting the service under a sub-path](crate#mounting-the-service-under-a-sub-path).
     ///
     /// Defaults to "/socket.io".
     #[inline]
@@ -103,8 +542,86 @@ impl<A: Adapter> SocketIoBuilder<A> {
         self
     }
 
+    /// The maximum amount of time a polling request is held open while no packet is pending,
+    /// before being answered with a noop packet. It trades some latency on idle connections
+    /// for fewer requests, or keeps them alive behind the proxies closing idle requests.
+    ///
+    /// Defaults to None, the request is held until a packet is sent, at least the next ping.
+    ///
+    /// # Example
+    /// ```
+    /// # use socketioxide::SocketIo;
+    /// # use std::time::Duration;
+    /// let (_, io) = SocketIo::builder()
+    ///     .polling_duration(Duration::from_secs(10))
+    ///     .build_svc();
+    /// ```
+    #[inline]
+    pub fn polling_duration(mut self, polling_duration: Duration) -> Self {
+        self.engine_config_builder = self
+            .engine_config_builder
+            .polling_duration(polling_duration);
+        self
+    }
+
+    /// A policy adjusting the ping interval to the health of each connection,
+    /// called after each ping with the round-trip time and the number of consecutive missed pongs.
+    /// See [`HeartbeatPolicy`](engineioxide::config::HeartbeatPolicy) for more details.
+    ///
+    /// Defaults to None, the ping interval is fixed and a missed pong closes the connection.
+    #[inline]
+    pub fn heartbeat_policy(mut self, policy: engineioxide::config::HeartbeatPolicy) -> Self {
+        self.engine_config_builder = self.engine_config_builder.heartbeat_policy(policy);
+        self
+    }
+
+    /// Sets a session affinity cookie on the polling handshake responses, with the engine.io session id as value,
+    /// so that the load balancers doing sticky sessions route all the requests of a session to the same server.
+    /// See [`CookieConfig`](engineioxide::config::CookieConfig) for more details.
+    ///
+    /// Defaults to no cookie.
+    ///
+    /// # Panics
+    /// If the cookie name is not a valid token or if the cookie path contains control characters or a `;`.
+    #[inline]
+    pub fn with_cookie(mut self, cookie: engineioxide::config::CookieConfig) -> Self {
+        self.engine_config_builder = self.engine_config_builder.cookie(cookie);
+        self
+    }
+
+    /// The amount of time the server will wait for a websocket upgrade to complete.
+    /// If the upgrade is not completed in time, the websocket connection is dropped
+    /// and the client keeps using the polling transport.
+    ///
+    /// Defaults to 10 seconds.
+    #[inline]
+    pub fn upgrade_timeout(mut self, upgrade_timeout: Duration) -> Self {
+        self.engine_config_builder = self.engine_config_builder.upgrade_timeout(upgrade_timeout);
+        self
+    }
+
+    /// Coalesces the packets written to the websocket connections:
+    /// the packets emitted to a socket in a burst are flushed together, for at most `window`
+    /// and up to `max_packets` packets per batch. The ordering of the packets is preserved.
+    ///
+    /// It reduces the number of writes when many small packets are emitted in a tight loop.
+    /// A batch is flushed as soon as no other packet is ready, so a packet emitted to an idle socket is not delayed.
+    ///
+    /// See [`WriteBatch`](engineioxide::config::WriteBatch) for more details.
+    ///
+    /// Defaults to no batching.
+    ///
+    /// # Panics
+    /// If `max_packets` is 0.
+    #[inline]
+    pub fn write_batch(mut self, window: Duration, max_packets: usize) -> Self {
+        self.engine_config_builder = self.engine_config_builder.write_batch(window, max_packets);
+        self
+    }
+
     /// The maximum number of packets that can be buffered per connection before being emitted to the client.
     /// If the buffer if full the `emit()` method will return an error
+    /// and the `emit_wait()` method will wait for space in the buffer.
     ///
     /// Defaults to 128 packets.
     #[inline]
@@ -123,6 +640,28 @@ impl<A: Adapter> SocketIoBuilder<A> {
         self
     }
 
+    /// The maximum size in bytes of a single websocket frame received from a client.
+    /// An oversized frame is rejected as soon as its header is read, before being buffered,
+    /// and the connection is closed with a protocol error close code.
+    ///
+    /// Defaults to 16 mb.
+    #[inline]
+    pub fn max_frame_size(mut self, max_frame_size: usize) -> Self {
+        self.engine_config_builder = self.engine_config_builder.max_frame_size(max_frame_size);
+        self
+    }
+
+    /// The maximum number of engine.io connections the server accepts at the same time, to shed load under surge.
+    /// Once it is reached, the new handshakes are rejected with a `503 Service Unavailable` response
+    /// with a `Retry-After` header, until some clients disconnect.
+    ///
+    /// Defaults to no limit.
+    #[inline]
+    pub fn max_connections(mut self, max_connections: usize) -> Self {
+        self.engine_config_builder = self.engine_config_builder.max_connections(max_connections);
+        self
+    }
+
     /// Allowed transports on this server
     ///
     /// The `transports` array should have a size of 1 or 2
@@ -144,293 +683,1713 @@ impl<A: Adapter> SocketIoBuilder<A> {
         self
     }
 
-    /// The amount of time before disconnecting a client that has not successfully joined a namespace.
+    /// Reads the ack timeout of each socket from its handshake auth payload with the given callback.
     ///
-    /// Defaults to 45 seconds.
+    /// If the callback returns `Some`, the duration is used as the default timeout of the acknowledgements
+    /// requested by the server to this socket, instead of the [`ack_timeout`](Self::ack_timeout) value.
+    /// It lets clients with slow networks extend their timeouts.
+    /// A custom timeout set with the `timeout()` operator still takes precedence.
+    ///
+    /// # Example
+    /// ```
+    /// # use socketioxide::SocketIo;
+    /// # use std::time::Duration;
+    /// // The client can send `{ "ackTimeout": 10000 }` in its auth payload
+    /// let (_, io) = SocketIo::builder()
+    ///     .ack_timeout_from_auth(|auth| {
+    ///         let ms = auth.get("ackTimeout")?.as_u64()?;
+    ///         Some(Duration::from_millis(ms))
+    ///     })
+    ///     .build_svc();
+    /// ```
     #[inline]
-    pub fn connect_timeout(mut self, connect_timeout: Duration) -> Self {
-        self.config.connect_timeout = connect_timeout;
+    pub fn ack_timeout_from_auth<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&Value) -> Option<Duration> + Send + Sync + 'static,
+    {
+        self.config.ack_timeout_extractor = Some(AckTimeoutExtractor::new(f));
         self
     }
 
-    /// Sets a custom [`SocketIoConfig`] created previously for this [`SocketIoBuilder`]
+    /// Reads the reconnection attempt count reported by each client in its handshake auth payload
+    /// with the given callback.
+    ///
+    /// The socket.io clients count their reconnection attempts but don't send them by default,
+    /// the client should put the count in its auth payload. It is then available with
+    /// [`Socket::reconnect_attempts`], for example to degrade the features of flapping clients.
+    ///
+    /// [`Socket::reconnect_attempts`]: crate::socket::Socket#method.reconnect_attempts
+    ///
+    /// # Example
+    /// ```
+    /// # use socketioxide::SocketIo;
+    /// // The client can send `{ "reconnectAttempts": 3 }` in its auth payload
+    /// let (_, io) = SocketIo::builder()
+    ///     .reconnect_attempts_from_auth(|auth| {
+    ///         auth.get("reconnectAttempts")?.as_u64()?.try_into().ok()
+    ///     })
+    ///     .build_svc();
+    /// ```
     #[inline]
-    pub fn with_config(mut self, config: SocketIoConfig) -> Self {
-        self.config = config;
+    pub fn reconnect_attempts_from_auth<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&Value) -> Option<u32> + Send + Sync + 'static,
+    {
+        self.config.reconnect_attempts_extractor = Some(ReconnectAttemptsExtractor::new(f));
         self
     }
 
-    /// Sets a custom [`Adapter`] for this [`SocketIoBuilder`]
-    pub fn with_adapter<B: Adapter>(self) -> SocketIoBuilder<B> {
-        SocketIoBuilder {
-            config: self.config,
-            engine_config_builder: self.engine_config_builder,
-            adapter: std::marker::PhantomData,
-            #[cfg(feature = "state")]
-            state: self.state,
-        }
+    /// Reads the user id of each client from its handshake auth payload with the given callback.
+    ///
+    /// It is then available with [`Socket::user_id`], and the connections sharing a user id
+    /// on a namespace are handled with the [`DuplicatePolicy`] set with [`duplicate_policy`](#method.duplicate_policy).
+    ///
+    /// [`Socket::user_id`]: crate::socket::Socket#method.user_id
+    ///
+    /// # Example
+    /// ```
+    /// # use socketioxide::SocketIo;
+    /// // The client can send `{ "userId": "alice" }` in its auth payload
+    /// let (_, io) = SocketIo::builder()
+    ///     .user_id_from_auth(|auth| Some(auth.get("userId")?.as_str()?.to_string()))
+    ///     .build_svc();
+    /// ```
+    #[inline]
+    pub fn user_id_from_auth<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&Value) -> Option<String> + Send + Sync + 'static,
+    {
+        self.config.user_id_extractor = Some(UserIdExtractor::new(f));
+        self
     }
 
-    /// Add a custom global state for the [`SocketIo`] instance.
-    /// This state will be accessible from every handler with the [`State`](crate::extract::State) extractor.
-    /// You can set any number of states as long as they have different types.
-    /// The state must be cloneable, therefore it is recommended to wrap it in an `Arc` if you want shared state.
+    /// The behavior when a socket connects to a namespace with the user id of an already connected socket,
+    /// e.g. to keep a single session per user. The user ids are read with [`user_id_from_auth`](#method.user_id_from_auth).
+    ///
+    /// The policy is applied once the connect middlewares succeeded, so that the user id can be checked by them.
+    /// The sockets without a user id are not affected.
+    ///
+    /// Defaults to [`DuplicatePolicy::AllowMultiple`].
+    ///
+    /// # Example
+    /// ```
+    /// # use socketioxide::{SocketIo, DuplicatePolicy};
+    /// let (_, io) = SocketIo::builder()
+    ///     .user_id_from_auth(|auth| Some(auth.get("userId")?.as_str()?.to_string()))
+    ///     .duplicate_policy(DuplicatePolicy::KickOld)
+    ///     .build_svc();
+    /// ```
     #[inline]
-    #[cfg_attr(docsrs, doc(cfg(feature = "state")))]
-    #[cfg(feature = "state")]
-    pub fn with_state<S: Clone + Send + Sync + 'static>(self, state: S) -> Self {
-        self.state.set(state);
+    pub fn duplicate_policy(mut self, policy: DuplicatePolicy) -> Self {
+        self.config.duplicate_policy = policy;
         self
     }
 
-    /// Builds a [`SocketIoLayer`] and a [`SocketIo`] instance
+    /// The behavior of the broadcasts to a room frozen with [`SocketIo::freeze_room`],
+    /// until it is unfrozen with [`SocketIo::unfreeze_room`].
     ///
-    /// The layer can be used as a tower layer
-    pub fn build_layer(mut self) -> (SocketIoLayer<A>, SocketIo<A>) {
-        self.config.engine_config = self.engine_config_builder.build();
-
-        let (layer, client) = SocketIoLayer::from_config(
-            self.config,
-            #[cfg(feature = "state")]
-            self.state,
-        );
-        (layer, SocketIo(client))
+    /// Defaults to [`FreezePolicy::Queue`].
+    ///
+    /// # Example
+    /// ```
+    /// # use socketioxide::{SocketIo, FreezePolicy};
+    /// let (_, io) = SocketIo::builder()
+    ///     .freeze_policy(FreezePolicy::Drop)
+    ///     .build_svc();
+    /// ```
+    #[inline]
+    pub fn freeze_policy(mut self, policy: FreezePolicy) -> Self {
+        self.config.freeze_policy = policy;
+        self
     }
 
-    /// Builds a [`SocketIoService`] and a [`SocketIo`] instance
+    /// The behavior when an outgoing payload doesn't match the schema registered
+    /// for its event with [`SocketIo::register_schema`].
     ///
-    /// This service will be a _standalone_ service that return a 404 error for every non-socket.io request
-    /// It can be used as a hyper service
-    pub fn build_svc(mut self) -> (SocketIoService<NotFoundService>, SocketIo) {
-        self.config.engine_config = self.engine_config_builder.build();
-
-        let (svc, client) = SocketIoService::with_config_inner(
-            NotFoundService,
-            self.config,
-            #[cfg(feature = "state")]
-            self.state,
-        );
-        (svc, SocketIo(client))
+    /// Defaults to [`SchemaPolicy::Reject`](crate::schema::SchemaPolicy::Reject).
+    ///
+    /// # Example
+    /// ```
+    /// # use socketioxide::{SocketIo, schema::SchemaPolicy};
+    /// let (_, io) = SocketIo::builder()
+    ///     .schema_policy(SchemaPolicy::Log)
+    ///     .build_svc();
+    /// ```
+    #[inline]
+    #[cfg_attr(docsrs, doc(cfg(feature = "schema")))]
+    #[cfg(feature = "schema")]
+    pub fn schema_policy(mut self, policy: crate::schema::SchemaPolicy) -> Self {
+        self.config.schema_policy = policy;
+        self
     }
 
-    /// Builds a [`SocketIoService`] and a [`SocketIo`] instance with an inner service
+    /// The behavior when a client emits an event without any registered handler.
+    /// It can help clients to fail fast on typos in event names.
     ///
-    /// It can be used as a hyper service
-    pub fn build_with_inner_svc<S: Clone>(mut self, svc: S) -> (SocketIoService<S>, SocketIo) {
-        self.config.engine_config = self.engine_config_builder.build();
-
-        let (svc, client) = SocketIoService::with_config_inner(
-            svc,
-            self.config,
+    /// See [`UnknownEventPolicy`] for the available policies.
+    ///
+    /// Defaults to [`UnknownEventPolicy::Ignore`].
+    ///
+    /// # Example
+    /// ```
+    /// # use socketioxide::{SocketIo, UnknownEventPolicy};
+    /// let (_, io) = SocketIo::builder()
+    ///     .unknown_event_policy(UnknownEventPolicy::ErrorAck)
+    ///     .build_svc();
+    /// ```
+    #[inline]
+    pub fn unknown_event_policy(mut self, policy: UnknownEventPolicy) -> Self {
+        self.config.unknown_event_policy = policy;
+        self
+    }
+
+    /// Sets the behavior when an event handler panics, see [`HandlerPanicPolicy`].
+    ///
+    /// The panics of the sync handlers, of the async handlers and of the extractors are caught,
+    /// the connect and disconnect handlers are not concerned.
+    ///
+    /// Defaults to [`HandlerPanicPolicy::Propagate`].
+    ///
+    /// # Example
+    /// ```
+    /// # use socketioxide::{SocketIo, HandlerPanicPolicy};
+    /// let (_, io) = SocketIo::builder()
+    ///     .handler_panic_policy(HandlerPanicPolicy::Disconnect)
+    ///     .build_svc();
+    /// ```
+    #[inline]
+    pub fn handler_panic_policy(mut self, policy: HandlerPanicPolicy) -> Self {
+        self.config.handler_panic_policy = policy;
+        self
+    }
+
+    /// Makes the [`Data`](crate::extract::Data), [`TryData`](crate::extract::TryData)
+    /// and [`MaybeData`](crate::extract::MaybeData) extractors fail when the payload contains fields
+    /// that would be ignored when deserializing the target type,
+    /// as if every type was annotated with `#[serde(deny_unknown_fields)]`.
+    ///
+    /// The error message contains the path of the first unknown field (e.g. ``unknown field `user.age` ``).
+    ///
+    /// Defaults to `false`: unknown fields are silently ignored.
+    ///
+    /// # Example
+    /// ```
+    /// # use socketioxide::SocketIo;
+    /// let (_, io) = SocketIo::builder()
+    ///     .deny_unknown_fields(true)
+    ///     .build_svc();
+    /// ```
+    #[inline]
+    pub fn deny_unknown_fields(mut self, deny: bool) -> Self {
+        self.config.deny_unknown_fields = deny;
+        self
+    }
+
+    /// Limits the length in bytes of the event names received from the clients.
+    /// The offending packets are rejected when they are decoded, before calling any handler,
+    /// and the [`EventLimitsPolicy`] set with [`event_limits_policy`](#method.event_limits_policy) is applied.
+    ///
+    /// Defaults to no limit.
+    ///
+    /// # Example
+    /// ```
+    /// # use socketioxide::{SocketIo, EventLimitsPolicy};
+    /// let (_, io) = SocketIo::builder()
+    ///     .max_event_name_len(64)
+    ///     .max_event_args(8)
+    ///     .event_limits_policy(EventLimitsPolicy::Drop)
+    ///     .build_svc();
+    /// ```
+    #[inline]
+    pub fn max_event_name_len(mut self, max: usize) -> Self {
+        self.config.max_event_name_len = Some(max);
+        self
+    }
+
+    /// Limits the number of arguments (the top-level elements of the JSON array following the event name)
+    /// of the events received from the clients.
+    /// The offending packets are rejected when they are decoded, before calling any handler,
+    /// and the [`EventLimitsPolicy`] set with [`event_limits_policy`](#method.event_limits_policy) is applied.
+    ///
+    /// Defaults to no limit.
+    #[inline]
+    pub fn max_event_args(mut self, max: usize) -> Self {
+        self.config.max_event_args = Some(max);
+        self
+    }
+
+    /// Limits the number of binary attachments of the binary event and ack packets received from the clients.
+    /// The attachment count declared in the packet header is checked when the packet is decoded,
+    /// before waiting for any attachment, and the connection is closed if it exceeds the limit.
+    ///
+    /// It complements the [`max_payload`](#method.max_payload) limit of the engine.io packets,
+    /// which doesn't bound the number of frames the server waits for.
+    ///
+    /// Defaults to no limit.
+    ///
+    /// # Example
+    /// ```
+    /// # use socketioxide::SocketIo;
+    /// let (_, io) = SocketIo::builder()
+    ///     .max_attachments(10)
+    ///     .build_svc();
+    /// ```
+    #[inline]
+    pub fn max_attachments(mut self, max: usize) -> Self {
+        self.config.max_attachments = Some(max);
+        self
+    }
+
+    /// The behavior when a received event exceeds the [`max_event_name_len`](#method.max_event_name_len)
+    /// or the [`max_event_args`](#method.max_event_args) limits.
+    ///
+    /// Defaults to [`EventLimitsPolicy::Close`].
+    #[inline]
+    pub fn event_limits_policy(mut self, policy: EventLimitsPolicy) -> Self {
+        self.config.event_limits_policy = policy;
+        self
+    }
+
+    /// Limits the number of event handlers that can be registered on a single socket
+    /// with [`Socket::on`](crate::socket::Socket::on), similar to the `maxListeners` of Node.js event emitters.
+    /// It helps to catch handlers registered in a loop, for example with dynamic event names.
+    ///
+    /// Replacing the handler of an already registered event does not count against the limit.
+    /// When the limit is exceeded, the given [`MaxListenersPolicy`] is applied.
+    ///
+    /// Defaults to no limit.
+    ///
+    /// # Example
+    /// ```
+    /// # use socketioxide::{SocketIo, MaxListenersPolicy};
+    /// let (_, io) = SocketIo::builder()
+    ///     .max_listeners(50, MaxListenersPolicy::Reject)
+    ///     .build_svc();
+    /// ```
+    #[inline]
+    pub fn max_listeners(mut self, max: usize, policy: MaxListenersPolicy) -> Self {
+        self.config.max_listeners = Some(max);
+        self.config.max_listeners_policy = policy;
+        self
+    }
+
+    /// Sets the maximum number of rooms a single socket can be in, to bound its footprint in the adapter.
+    /// Once the socket is in `max` rooms, [`Socket::join`] returns a [`JoinError::TooManyRooms`] error
+    /// and none of the requested rooms are joined. The [`join`] operator, e.g. `io.within("a").join("b")`,
+    /// applies the same limit to each selected socket and returns the errors of the sockets at the limit.
+    ///
+    /// A room named after the socket id is not counted against the limit.
+    ///
+    /// Defaults to no limit.
+    ///
+    /// [`Socket::join`]: crate::socket::Socket#method.join
+    /// [`join`]: crate::operators::BroadcastOperators#method.join
+    /// [`JoinError::TooManyRooms`]: crate::JoinError::TooManyRooms
+    /// #### Example
+    /// ```
+    /// # use socketioxide::{SocketIo, extract::SocketRef};
+    /// let (_, io) = SocketIo::builder().max_rooms_per_socket(2).build_svc();
+    /// io.ns("/", |socket: SocketRef| {
+    ///     socket.join(["room1", "room2"]).unwrap();
+    ///     assert!(socket.join("room3").is_err());
+    /// });
+    /// ```
+    #[inline]
+    pub fn max_rooms_per_socket(mut self, max: usize) -> Self {
+        self.config.max_rooms_per_socket = Some(max);
+        self
+    }
+
+    /// Limits the number of acknowledgements requested with `emit_with_ack` that can be pending
+    /// on a single socket, to bound the memory used to track them when they are not awaited.
+    ///
+    /// Once `max` acknowledgements are pending, the packets emitted with an ack are not sent
+    /// and their [`AckStream`] yields an [`AckError::TooManyPending`], until the client answers
+    /// or the pending acks time out or stop being awaited.
+    ///
+    /// Defaults to no limit.
+    ///
+    /// [`AckStream`]: crate::ack::AckStream
+    /// [`AckError::TooManyPending`]: crate::AckError::TooManyPending
+    /// #### Example
+    /// ```
+    /// # use socketioxide::SocketIo;
+    /// let (_, io) = SocketIo::builder().max_pending_acks(64).build_svc();
+    /// ```
+    #[inline]
+    pub fn max_pending_acks(mut self, max: usize) -> Self {
+        self.config.max_pending_acks = Some(max);
+        self
+    }
+
+    /// Sets the rooms every socket joins right after connecting to a namespace.
+    /// The rooms are joined before the connect handler is called,
+    /// so they are already visible with [`Socket::rooms`] in the handler.
+    ///
+    /// The [`SocketIo::on_join`] hook also applies to these rooms.
+    ///
+    /// Defaults to no rooms.
+    ///
+    /// [`Socket::rooms`]: crate::socket::Socket#method.rooms
+    /// #### Example
+    /// ```
+    /// # use socketioxide::{SocketIo, extract::SocketRef};
+    /// let (_, io) = SocketIo::builder().default_rooms(["all"]).build_svc();
+    /// io.ns("/", |socket: SocketRef| {
+    ///     assert!(socket.rooms().unwrap().contains(&"all".into()));
+    /// });
+    /// ```
+    #[inline]
+    pub fn default_rooms(mut self, rooms: impl RoomParam) -> Self {
+        self.config.default_rooms = rooms.into_room_iter().collect();
+        self
+    }
+
+    /// Makes every socket join a room named after its own id right after connecting to a namespace,
+    /// like the JS socket.io server, so that it can be targeted with [`SocketIo::to_sid`] or `io.to(sid)`.
+    ///
+    /// It is disabled by default because it adds a room for each socket to the adapter.
+    /// The sockets can be targeted without it with [`SocketIo::to_sockets`].
+    ///
+    /// Defaults to `false`.
+    /// #### Example
+    /// ```
+    /// # use socketioxide::{SocketIo, extract::SocketRef};
+    /// let (_, io) = SocketIo::builder().auto_sid_room(true).build_svc();
+    /// io.ns("/", |socket: SocketRef| {
+    ///     assert!(socket.rooms().unwrap().contains(&socket.id.to_string().into()));
+    /// });
+    /// ```
+    #[inline]
+    pub fn auto_sid_room(mut self, enabled: bool) -> Self {
+        self.config.auto_sid_room = enabled;
+        self
+    }
+
+    /// Holds the namespace connections until the given [`ReadinessGate`] is open.
+    /// It can be used when the server is not ready to accept connections yet, for example while warming a cache.
+    ///
+    /// While the gate is closed, the middlewares and the connect handlers are not called.
+    /// If the gate is still closed after `max_hold`, the connection is rejected
+    /// with a `connect_error` packet with the `server not ready` message.
+    ///
+    /// See the [`readiness`](crate::readiness) module doc for more details.
+    ///
+    /// Defaults to no gate.
+    #[inline]
+    pub fn readiness_gate(mut self, gate: ReadinessGate, max_hold: Duration) -> Self {
+        self.config.readiness_gate = Some(gate);
+        self.config.readiness_timeout = max_hold;
+        self
+    }
+
+    /// The amount of time before disconnecting a client that has not successfully joined a namespace.
+    ///
+    /// Defaults to 45 seconds.
+    #[inline]
+    pub fn connect_timeout(mut self, connect_timeout: Duration) -> Self {
+        self.config.connect_timeout = connect_timeout;
+        self
+    }
+
+    /// The amount of time without any incoming application activity before disconnecting a socket
+    /// from its namespace with the [`DisconnectReason::IdleTimeout`] reason.
+    ///
+    /// Only the events and the acknowledgements received from the client are considered as activity,
+    /// the engine.io heartbeats are not. It is therefore distinct from the [`ping_timeout`](#method.ping_timeout)
+    /// which only detects the dead connections.
+    ///
+    /// Defaults to no idle timeout.
+    ///
+    /// [`DisconnectReason::IdleTimeout`]: crate::socket::DisconnectReason::IdleTimeout
+    #[inline]
+    pub fn idle_timeout(mut self, idle_timeout: Duration) -> Self {
+        self.config.idle_timeout = Some(idle_timeout);
+        self
+    }
+
+    /// Emits a [`TIME_EVENT`] with the current [`ServerTime`] to every socket once connected
+    /// and then every `interval`, so that the clients can synchronize their clock.
+    /// See the [`clock`](crate::clock) module for more details.
+    ///
+    /// Defaults to no emission. It can also be enabled for some sockets only with
+    /// [`Socket::sync_time`](crate::socket::Socket#method.sync_time).
+    ///
+    /// [`TIME_EVENT`]: crate::clock::TIME_EVENT
+    /// [`ServerTime`]: crate::clock::ServerTime
+    #[inline]
+    pub fn time_sync(mut self, interval: Duration) -> Self {
+        self.config.time_sync = Some(interval);
+        self
+    }
+
+    /// Limits the number of bytes each socket can receive from the client to `bytes_per_window`
+    /// during each `window`. A socket exceeding its budget is disconnected from its namespace with the
+    /// [`DisconnectReason::QuotaExceeded`] reason, and the packet going over the budget is not handled.
+    ///
+    /// The budget of each socket is reset at the end of every window, starting from its connection.
+    /// The received bytes are counted like in the [`Socket::stats`]: the socket.io packets
+    /// and their binary attachments, without the engine.io framing.
+    ///
+    /// Unlike a limit on the number of events, it protects against clients sending a few huge payloads.
+    ///
+    /// Defaults to no quota.
+    ///
+    /// #### Example
+    /// ```
+    /// # use socketioxide::SocketIo;
+    /// # use std::time::Duration;
+    /// // At most 1MiB per minute for each socket
+    /// let (_, io) = SocketIo::builder()
+    ///     .byte_quota(1024 * 1024, Duration::from_secs(60))
+    ///     .build_svc();
+    /// ```
+    ///
+    /// [`DisconnectReason::QuotaExceeded`]: crate::socket::DisconnectReason::QuotaExceeded
+    /// [`Socket::stats`]: crate::socket::Socket::stats
+    #[inline]
+    pub fn byte_quota(mut self, bytes_per_window: u64, window: Duration) -> Self {
+        self.config.byte_quota = Some(bytes_per_window);
+        self.config.byte_quota_window = window;
+        self
+    }
+
+    /// Keeps the last `size` event packets broadcast to each socket so that they can be replayed
+    /// with [`Socket::resync`] when the client detects it missed some of them,
+    /// see the [`replay`](crate::replay) module doc for more details.
+    ///
+    /// When enabled, an offset is appended as the last argument of each broadcast event.
+    ///
+    /// Defaults to no replay buffer.
+    ///
+    /// [`Socket::resync`]: crate::socket::Socket#method.resync
+    #[inline]
+    pub fn replay_buffer(mut self, size: usize) -> Self {
+        self.config.replay_buffer_size = Some(size);
+        self
+    }
+
+    /// Includes the detailed error message, e.g. the serde error of a [`Data`] extractor, in the error acks
+    /// sent when an extractor of a message handler fails.
+    ///
+    /// When an extractor fails the handler is not called and, if the event expects an acknowledgement,
+    /// an error ack `{ "code": "bad_request", "message": "bad request" }` is sent back.
+    /// When enabled, the generic message is replaced with the error message. It helps debugging
+    /// but may leak details on the expected payloads, so it should only be enabled in development.
+    ///
+    /// Defaults to `false`.
+    ///
+    /// [`Data`]: crate::extract::Data
+    ///
+    /// # Example
+    /// ```
+    /// # use socketioxide::SocketIo;
+    /// let (_, io) = SocketIo::builder()
+    ///     .expose_deser_errors(cfg!(debug_assertions))
+    ///     .build_svc();
+    /// ```
+    #[inline]
+    pub fn expose_deser_errors(mut self, expose: bool) -> Self {
+        self.config.expose_deser_errors = expose;
+        self
+    }
+
+    /// The behavior when a client sends a packet to a namespace before being connected to it,
+    /// e.g. an event emitted before its connect packet was answered.
+    ///
+    /// Defaults to [`EarlyPacketPolicy::Drop`].
+    ///
+    /// # Example
+    /// ```
+    /// # use socketioxide::{SocketIo, EarlyPacketPolicy};
+    /// let (_, io) = SocketIo::builder()
+    ///     .early_packet_policy(EarlyPacketPolicy::Buffer)
+    ///     .build_svc();
+    /// ```
+    #[inline]
+    pub fn early_packet_policy(mut self, policy: EarlyPacketPolicy) -> Self {
+        self.config.early_packet_policy = policy;
+        self
+    }
+
+    /// The wire shape of the acknowledgements sent with [`AckSender::send`] and a payload
+    /// serializing to `null`, like `()` or `None`.
+    /// Depending on the client, the ack callback may expect no argument or a `null` one.
+    ///
+    /// Defaults to [`EmptyAckShape::NoArgs`].
+    ///
+    /// # Example
+    /// ```
+    /// # use socketioxide::{SocketIo, EmptyAckShape};
+    /// let (_, io) = SocketIo::builder()
+    ///     .empty_ack_shape(EmptyAckShape::Null)
+    ///     .build_svc();
+    /// ```
+    ///
+    /// [`AckSender::send`]: crate::extract::AckSender#method.send
+    #[inline]
+    pub fn empty_ack_shape(mut self, shape: EmptyAckShape) -> Self {
+        self.config.empty_ack_shape = shape;
+        self
+    }
+
+    /// Trusts the `X-Forwarded-Proto` header of the handshake request to tell
+    /// if a connection is secure with [`Socket::is_secure`] and the [`Secure`] extractor,
+    /// when the TLS connection is terminated by a reverse proxy.
+    ///
+    /// It should only be enabled behind a proxy that sets or strips this header,
+    /// otherwise any client could pretend to use a secure connection.
+    ///
+    /// Defaults to `false`: only the scheme of the request uri is used.
+    ///
+    /// # Example
+    /// ```
+    /// # use socketioxide::SocketIo;
+    /// let (_, io) = SocketIo::builder()
+    ///     .trust_forwarded_proto(true)
+    ///     .build_svc();
+    /// ```
+    ///
+    /// [`Socket::is_secure`]: crate::socket::Socket#method.is_secure
+    /// [`Secure`]: crate::extract::Secure
+    #[inline]
+    pub fn trust_forwarded_proto(mut self, trust: bool) -> Self {
+        self.config.trust_forwarded_proto = trust;
+        self
+    }
+
+    /// Tags each event emitted to a socket with a monotonic packet id, starting at 1 and
+    /// incremented for each event sent to this socket, so that the client can discard the duplicates
+    /// it may receive after a reconnection or a replay.
+    ///
+    /// When enabled, an object with the reserved [`PACKET_ID_KEY`] field is appended as the last argument
+    /// of each event, e.g. `["hello","world",{"_pid":1}]`. It comes after the offset
+    /// appended by the [replay buffer](#method.replay_buffer).
+    ///
+    /// Defaults to `false`.
+    ///
+    /// [`PACKET_ID_KEY`]: crate::socket::PACKET_ID_KEY
+    ///
+    /// # Example
+    /// ```
+    /// # use socketioxide::SocketIo;
+    /// let (_, io) = SocketIo::builder()
+    ///     .packet_ids(true)
+    ///     .build_svc();
+    /// ```
+    #[inline]
+    pub fn packet_ids(mut self, enabled: bool) -> Self {
+        self.config.packet_ids = enabled;
+        self
+    }
+
+    /// The maximum amount of time to wait for the connections to be closed
+    /// when the server is gracefully shut down with [`SocketIo::serve_with_graceful_shutdown`].
+    /// The connections still open after this delay are dropped.
+    ///
+    /// Defaults to 10 seconds.
+    #[inline]
+    pub fn drain_timeout(mut self, drain_timeout: Duration) -> Self {
+        self.config.drain_timeout = drain_timeout;
+        self
+    }
+
+    /// Sets a custom [`SocketIoConfig`] created previously for this [`SocketIoBuilder`]
+    #[inline]
+    pub fn with_config(mut self, config: SocketIoConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Sets a custom [`Adapter`] for this [`SocketIoBuilder`]
+    pub fn with_adapter<B: Adapter>(self) -> SocketIoBuilder<B> {
+        SocketIoBuilder {
+            config: self.config,
+            engine_config_builder: self.engine_config_builder,
+            adapter: std::marker::PhantomData,
+            #[cfg(feature = "state")]
+            state: self.state,
+        }
+    }
+
+    /// Add a custom global state for the [`SocketIo`] instance.
+    /// This state will be accessible from every handler with the [`State`](crate::extract::State) extractor.
+    /// You can set any number of states as long as they have different types.
+    /// The state must be cloneable, therefore it is recommended to wrap it in an `Arc` if you want shared state.
+    #[inline]
+    #[cfg_attr(docsrs, doc(cfg(feature = "state")))]
+    #[cfg(feature = "state")]
+    pub fn with_state<S: Clone + Send + Sync + 'static>(self, state: S) -> Self {
+        self.state.set(state);
+        self
+    }
+
+    /// Builds a [`SocketIoLayer`] and a [`SocketIo`] instance
+    ///
+    /// The layer can be used as a tower layer
+    pub fn build_layer(mut self) -> (SocketIoLayer<A>, SocketIo<A>) {
+        self.config.engine_config = self.engine_config_builder.build();
+
+        let (layer, client) = SocketIoLayer::from_config(
+            self.config,
             #[cfg(feature = "state")]
             self.state,
         );
-        (svc, SocketIo(client))
+        (layer, SocketIo(client))
+    }
+
+    /// Builds a [`SocketIoService`] and a [`SocketIo`] instance
+    ///
+    /// This service will be a _standalone_ service that return a 404 error for every non-socket.io request
+    /// It can be used as a hyper service
+    pub fn build_svc(mut self) -> (SocketIoService<NotFoundService>, SocketIo) {
+        self.config.engine_config = self.engine_config_builder.build();
+
+        let (svc, client) = SocketIoService::with_config_inner(
+            NotFoundService,
+            self.config,
+            #[cfg(feature = "state")]
+            self.state,
+        );
+        (svc, SocketIo(client))
+    }
+
+    /// Builds a [`SocketIoService`] and a [`SocketIo`] instance with an inner service
+    ///
+    /// It can be used as a hyper service
+    pub fn build_with_inner_svc<S: Clone>(mut self, svc: S) -> (SocketIoService<S>, SocketIo) {
+        self.config.engine_config = self.engine_config_builder.build();
+
+        let (svc, client) = SocketIoService::with_config_inner(
+            svc,
+            self.config,
+            #[cfg(feature = "state")]
+            self.state,
+        );
+        (svc, SocketIo(client))
+    }
+}
+
+impl Default for SocketIoBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The [`SocketIo`] instance can be cheaply cloned and moved around everywhere in your program.
+/// It can be used as the main handle to access the whole socket.io context.
+#[derive(Debug)]
+pub struct SocketIo<A: Adapter = LocalAdapter>(Arc<Client<A>>);
+
+impl SocketIo<LocalAdapter> {
+    /// Creates a new [`SocketIoBuilder`] with a default config
+    #[inline(always)]
+    pub fn builder() -> SocketIoBuilder {
+        SocketIoBuilder::new()
+    }
+
+    /// Creates a new [`SocketIoService`] and a [`SocketIo`] instance with a default config.
+    /// This service will be a _standalone_ service that return a 404 error for every non-socket.io request.
+    /// It can be used as a [`Service`](tower::Service) (see hyper example)
+    #[inline(always)]
+    pub fn new_svc() -> (SocketIoService<NotFoundService>, SocketIo) {
+        Self::builder().build_svc()
+    }
+
+    /// Creates a new [`SocketIoService`] and a [`SocketIo`] instance with a default config.
+    /// It can be used as a [`Service`](tower::Service) with an inner service
+    #[inline(always)]
+    pub fn new_inner_svc<S: Clone>(svc: S) -> (SocketIoService<S>, SocketIo) {
+        Self::builder().build_with_inner_svc(svc)
+    }
+
+    /// Builds a [`SocketIoLayer`] and a [`SocketIo`] instance with a default config.
+    /// It can be used as a tower [`Layer`](tower::layer::Layer) (see axum example)
+    #[inline(always)]
+    pub fn new_layer() -> (SocketIoLayer, SocketIo) {
+        Self::builder().build_layer()
+    }
+}
+
+impl<A: Adapter> SocketIo<A> {
+    /// Returns a reference to the [`SocketIoConfig`] used by this [`SocketIo`] instance
+    #[inline]
+    pub fn config(&self) -> &SocketIoConfig {
+        &self.0.config
+    }
+
+    /// Registers a [`ConnectHandler`] for the given namespace
+    ///
+    /// * See the [`connect`](crate::handler::connect) module doc for more details on connect handler.
+    /// * See the [`extract`](crate::extract) module doc for more details on available extractors.
+    ///
+    /// # Examples
+    /// #### Simple example with a sync closure:
+    /// ```
+    /// # use socketioxide::{SocketIo, extract::*};
+    /// # use serde::{Serialize, Deserialize};
+    /// #[derive(Debug, Serialize, Deserialize)]
+    /// struct MyData {
+    ///     name: String,
+    ///     age: u8,
+    /// }
+    ///
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.ns("/", |socket: SocketRef| {
+    ///     // Register a handler for the "test" event and extract the data as a `MyData` struct
+    ///     // With the Data extractor, the handler is called only if the data can be deserialized as a `MyData` struct
+    ///     // If you want to manage errors yourself you can use the TryData extractor
+    ///     socket.on("test", |socket: SocketRef, Data::<MyData>(data)| {
+    ///         println!("Received a test message {:?}", data);
+    ///         socket.emit("test-test", MyData { name: "Test".to_string(), age: 8 }).ok(); // Emit a message to the client
+    ///     });
+    /// });
+    ///
+    /// ```
+    ///
+    /// #### Example with a closure and an acknowledgement + binary data:
+    /// ```
+    /// # use socketioxide::{SocketIo, extract::*};
+    /// # use serde_json::Value;
+    /// # use serde::{Serialize, Deserialize};
+    /// #[derive(Debug, Serialize, Deserialize)]
+    /// struct MyData {
+    ///     name: String,
+    ///     age: u8,
+    /// }
+    ///
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.ns("/", |socket: SocketRef| {
+    ///     // Register an async handler for the "test" event and extract the data as a `MyData` struct
+    ///     // Extract the binary payload as a `Vec<Bytes>` with the Bin extractor.
+    ///     // It should be the last extractor because it consumes the request
+    ///     socket.on("test", |socket: SocketRef, Data::<MyData>(data), ack: AckSender, Bin(bin)| async move {
+    ///         println!("Received a test message {:?}", data);
+    ///         tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+    ///         ack.bin(bin).send(data).ok(); // The data received is sent back to the client through the ack
+    ///         socket.emit("test-test", MyData { name: "Test".to_string(), age: 8 }).ok(); // Emit a message to the client
+    ///     });
+    /// });
+    /// ```
+    /// #### Simple example with a closure:
+    /// ```
+    /// # use socketioxide::{SocketIo, extract::{SocketRef, Data}};
+    /// # use serde::{Serialize, Deserialize};
+    /// #[derive(Debug, Deserialize)]
+    /// struct MyAuthData {
+    ///     token: String,
+    /// }
+    /// #[derive(Debug, Serialize, Deserialize)]
+    /// struct MyData {
+    ///     name: String,
+    ///     age: u8,
+    /// }
+    ///
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.ns("/", |socket: SocketRef, Data(auth): Data<MyAuthData>| {
+    ///     if auth.token.is_empty() {
+    ///         println!("Invalid token, disconnecting");
+    ///         socket.disconnect().ok();
+    ///         return;
+    ///     }
+    ///     socket.on("test", |socket: SocketRef, Data::<MyData>(data)| async move {
+    ///         println!("Received a test message {:?}", data);
+    ///         socket.emit("test-test", MyData { name: "Test".to_string(), age: 8 }).ok(); // Emit a message to the client
+    ///     });
+    /// });
+    ///
+    /// ```
+    #[inline]
+    pub fn ns<C, T>(&self, path: impl Into<Cow<'static, str>>, callback: C)
+    where
+        C: ConnectHandler<A, T>,
+        T: Send + Sync + 'static,
+    {
+        self.0.add_ns(path.into(), callback);
+    }
+
+    /// Registers a [`ConnectHandler`] for the given namespace, like [`ns`](#method.ns),
+    /// but the events of each socket are handled one after the other.
+    ///
+    /// By default async message handlers are spawned as soon as their event is received,
+    /// so two events sent by the same client can complete in any order.
+    /// In an ordered namespace, the handler of an event is only called once the handler of the
+    /// previous event of the same socket has completed, sync or async.
+    /// The events of different sockets are still handled concurrently.
+    ///
+    /// Acknowledgements are not queued, so a handler can wait for an ack from the client.
+    /// However a handler must not wait for another event of the same socket, it would never be handled.
+    ///
+    /// # Example
+    /// ```
+    /// # use socketioxide::{SocketIo, extract::*};
+    /// # use std::time::Duration;
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.ordered_ns("/orders", |socket: SocketRef| {
+    ///     socket.on("place", |socket: SocketRef, Data::<String>(order)| async move {
+    ///         tokio::time::sleep(Duration::from_millis(10)).await;
+    ///         // The "cancel" event sent after this one is handled once this handler completes
+    ///         socket.emit("placed", order).ok();
+    ///     });
+    ///     socket.on("cancel", |socket: SocketRef, Data::<String>(order)| {
+    ///         socket.emit("canceled", order).ok();
+    ///     });
+    /// });
+    /// ```
+    #[inline]
+    pub fn ordered_ns<C, T>(&self, path: impl Into<Cow<'static, str>>, callback: C)
+    where
+        C: ConnectHandler<A, T>,
+        T: Send + Sync + 'static,
+    {
+        self.0.add_ordered_ns(path.into(), callback);
+    }
+
+    /// Registers a [`ConnectHandler`] for the given dynamic namespace.
+    /// You can specify dynamic parts in the path by using the `{name}` syntax.
+    /// Note that any static namespace will take precedence over a dynamic one.
+    ///
+    ///
+    /// For more info about namespace routing, see the [matchit] router documentation.
+    ///
+    /// The dynamic namespace will create a child namespace for any path that matches the given pattern with the given handler.
+    ///
+    /// * See the [`connect`](crate::handler::connect) module doc for more details on connect handler.
+    /// * See the [`extract`](crate::extract) module doc for more details on available extractors.
+    ///
+    /// ## Errors
+    /// If the pattern is invalid, a [`NsInsertError`](crate::NsInsertError) will be returned.
+    ///
+    /// ## Example
+    /// ```
+    /// # use socketioxide::{SocketIo, extract::SocketRef};
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.dyn_ns("/client/{client_id}", |socket: SocketRef| {
+    ///     println!("Socket connected on dynamic namespace with namespace path: {}", socket.ns());
+    /// }).unwrap();
+    ///
+    /// ```
+    /// ```
+    /// # use socketioxide::{SocketIo, extract::SocketRef};
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.dyn_ns("/client/{*remaining_path}", |socket: SocketRef| {
+    ///     println!("Socket connected on dynamic namespace with namespace path: {}", socket.ns());
+    /// }).unwrap();
+    ///
+    /// ```
+    #[inline]
+    pub fn dyn_ns<C, T>(
+        &self,
+        path: impl Into<String>,
+        callback: C,
+    ) -> Result<(), crate::NsInsertError>
+    where
+        C: ConnectHandler<A, T>,
+        T: Send + Sync + 'static,
+    {
+        self.0.add_dyn_ns(path.into(), callback)
+    }
+
+    /// Deletes the namespace with the given path.
+    ///
+    /// This will disconnect all sockets connected to this
+    /// namespace in a deferred way.
+    ///
+    /// # Panics
+    /// If the v4 protocol (legacy) is enabled and the namespace to delete is the default namespace "/".
+    /// For v4, the default namespace cannot be deleted. See [official doc](https://socket.io/docs/v3/namespaces/#main-namespace) for more informations.
+    #[inline]
+    pub fn delete_ns<'a>(&self, path: impl Into<&'a str>) {
+        self.0.delete_ns(path.into());
+    }
+
+    /// Returns the paths of all the namespaces registered on this server, sorted alphabetically.
+    ///
+    /// The namespaces created from a [dynamic namespace](SocketIo::dyn_ns) are included once a client
+    /// connected to them, the dynamic path patterns themselves are not.
+    ///
+    /// # Example
+    /// ```
+    /// # use socketioxide::{SocketIo, extract::SocketRef};
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.ns("/", || {});
+    /// io.ns("/admin", || {});
+    ///
+    /// assert_eq!(io.namespaces(), ["/", "/admin"]);
+    /// ```
+    #[inline]
+    pub fn namespaces(&self) -> Vec<String> {
+        self.0.ns_paths()
+    }
+
+    /// Limits the number of connections to the given namespace whose [middlewares](crate::handler::connect#middlewares)
+    /// run at the same time, e.g. to protect a database-backed authentication from a reconnection storm.
+    ///
+    /// The excess connections wait for a running one to complete, and are rejected with a
+    /// `connect_error` packet if they wait longer than `timeout`.
+    /// Returns `false` if the namespace is not registered. The namespaces created from
+    /// a [dynamic namespace](SocketIo::dyn_ns) are not limited.
+    ///
+    /// # Example
+    /// ```
+    /// # use socketioxide::{SocketIo, extract::SocketRef, handler::ConnectHandler};
+    /// # use std::time::Duration;
+    /// async fn auth() -> Result<(), std::convert::Infallible> {
+    ///     // Check the credentials in a database
+    ///     Ok(())
+    /// }
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.ns("/", { || {} }.with(auth));
+    /// io.max_concurrent_connects("/", 10, Duration::from_secs(5));
+    /// ```
+    pub fn max_concurrent_connects(&self, path: &str, max: usize, timeout: Duration) -> bool {
+        match self.0.get_ns(path) {
+            Some(ns) => {
+                ns.set_connect_limit(max, timeout);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Sets the behavior of the sockets of the given namespace when they receive an event once
+    /// their disconnection has started. See [`DisconnectingEventPolicy`] for the available policies.
+    ///
+    /// Defaults to [`DisconnectingEventPolicy::Drop`].
+    /// Returns `false` if the namespace is not registered. The namespaces created from
+    /// a [dynamic namespace](SocketIo::dyn_ns) keep the default policy.
+    ///
+    /// # Example
+    /// ```
+    /// # use socketioxide::{SocketIo, DisconnectingEventPolicy};
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.ns("/", || {});
+    /// io.disconnecting_event_policy("/", DisconnectingEventPolicy::DropWithLog);
+    /// ```
+    pub fn disconnecting_event_policy(&self, path: &str, policy: DisconnectingEventPolicy) -> bool {
+        match self.0.get_ns(path) {
+            Some(ns) => {
+                *ns.disconnecting_event_policy.write().unwrap() = policy;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Gracefully closes all the connections and drops every sockets
+    ///
+    /// Any `on_disconnect` handler will called with [`DisconnectReason::ClosingServer`]
+    #[inline]
+    pub async fn close(&self) {
+        self.0.close(DisconnectReason::ClosingServer).await;
+    }
+
+    /// Serves the socket.io server on the given listener until the `signal` future resolves,
+    /// then gracefully shuts it down:
+    /// * The listener stops accepting new connections.
+    /// * Every socket is disconnected with [`DisconnectReason::ServerShutdown`](crate::socket::DisconnectReason::ServerShutdown)
+    /// and its underlying connection is closed, like with [`SocketIo::close`].
+    /// * The remaining http connections are gracefully shut down.
+    ///
+    /// The shutdown is bounded by the [`SocketIoBuilder::drain_timeout`],
+    /// the connections still open after this delay are dropped.
+    ///
+    /// The requests that do not target the socket.io path are answered with a 404 response.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use socketioxide::{SocketIo, extract::SocketRef};
+    /// # use tokio::net::TcpListener;
+    /// # async fn doc() -> std::io::Result<()> {
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.ns("/", |socket: SocketRef| {});
+    ///
+    /// let listener = TcpListener::bind("127.0.0.1:3000").await?;
+    /// io.serve_with_graceful_shutdown(listener, async {
+    ///     tokio::signal::ctrl_c().await.ok();
+    /// })
+    /// .await;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "serve")))]
+    #[cfg(feature = "serve")]
+    pub async fn serve_with_graceful_shutdown<F>(
+        &self,
+        listener: tokio::net::TcpListener,
+        signal: F,
+    ) where
+        F: std::future::Future<Output = ()>,
+    {
+        use futures_util::future::{self, Either};
+        use hyper::server::conn::http1;
+        use hyper_util::rt::TokioIo;
+
+        let svc = SocketIoService::with_client(NotFoundService, self.0.clone());
+        let (shutdown_tx, shutdown_rx) = tokio::sync::watch::channel(());
+        let mut conns = tokio::task::JoinSet::new();
+        let mut signal = std::pin::pin!(signal);
+
+        loop {
+            tokio::select! {
+                res = listener.accept() => {
+                    let stream = match res {
+                        Ok((stream, _)) => stream,
+                        Err(_e) => {
+                            #[cfg(feature = "tracing")]
+                            tracing::debug!("error accepting connection: {_e}");
+                            continue;
+                        }
+                    };
+                    let svc = svc.clone();
+                    let mut shutdown_rx = shutdown_rx.clone();
+                    conns.spawn(async move {
+                        let conn = http1::Builder::new()
+                            .serve_connection(TokioIo::new(stream), svc)
+                            .with_upgrades();
+                        let mut conn = std::pin::pin!(conn);
+                        let shutdown = std::pin::pin!(shutdown_rx.changed());
+                        let _res = match future::select(conn.as_mut(), shutdown).await {
+                            Either::Left((res, _)) => res,
+                            Either::Right(_) => {
+                                conn.as_mut().graceful_shutdown();
+                                conn.await
+                            }
+                        };
+                        #[cfg(feature = "tracing")]
+                        if let Err(e) = _res {
+                            tracing::debug!("error serving connection: {e}");
+                        }
+                    });
+                }
+                Some(_) = conns.join_next() => (),
+                _ = &mut signal => break,
+            }
+        }
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            "shutdown signal received, draining {} connections",
+            conns.len()
+        );
+        drop(listener);
+        let drain = async {
+            self.0.close(DisconnectReason::ServerShutdown).await;
+            shutdown_tx.send(()).ok();
+            while conns.join_next().await.is_some() {}
+        };
+        if tokio::time::timeout(self.config().drain_timeout, drain)
+            .await
+            .is_err()
+        {
+            #[cfg(feature = "tracing")]
+            tracing::debug!(
+                "drain timeout elapsed, dropping {} connections",
+                conns.len()
+            );
+            conns.shutdown().await;
+        }
+    }
+
+    /// Registers a sink that will be called for every [`LifecycleEvent`] of the server
+    /// (connections, disconnections, emitted events, rooms joined/left and errors).
+    ///
+    /// Only one sink can be registered, calling this method again replaces the previous one.
+    /// The sink is called synchronously so it should not block.
+    ///
+    /// See the [`lifecycle`](crate::lifecycle) module doc for more details.
+    ///
+    /// # Example
+    /// ```
+    /// # use socketioxide::{SocketIo, lifecycle::LifecycleEvent};
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.on_event(|event: LifecycleEvent| {
+    ///     // Forward the event to an audit log
+    ///     println!("{:?}", event);
+    /// });
+    /// ```
+    #[inline]
+    pub fn on_event<F>(&self, sink: F)
+    where
+        F: Fn(LifecycleEvent) + Send + Sync + 'static,
+    {
+        self.0.set_event_sink(Arc::new(sink));
+    }
+
+    /// Registers a callback that will be called for every packet that could not be sent
+    /// to a socket, with the [`DropReason`](crate::lifecycle::DropReason) of the drop.
+    ///
+    /// The packets dropped when broadcasting or when answering an acknowledgement are silently lost,
+    /// this callback gives visibility on them. It can be used to log them or to record a metric.
+    ///
+    /// Only one callback can be registered, calling this method again replaces the previous one.
+    /// The callback is called synchronously so it should not block.
+    ///
+    /// # Example
+    /// ```
+    /// # use socketioxide::{SocketIo, lifecycle::DropEvent};
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.on_drop(|DropEvent { sid, reason, event, .. }| {
+    ///     println!("packet {event:?} dropped for socket {sid}: {reason:?}");
+    /// });
+    /// ```
+    #[inline]
+    pub fn on_drop<F>(&self, callback: F)
+    where
+        F: Fn(DropEvent) + Send + Sync + 'static,
+    {
+        self.0.set_drop_sink(Arc::new(callback));
+    }
+
+    /// Registers a hook that will be called each time a socket tries to join a room
+    /// with [`Socket::join`](crate::socket::Socket#method.join).
+    /// If the hook returns `false` the join is vetoed: none of the rooms are joined
+    /// and a [`JoinError::Vetoed`](crate::JoinError::Vetoed) error is returned.
+    ///
+    /// It can be used to prevent sockets from joining rooms they are not authorized to join.
+    /// Rooms joined with the broadcast operators (e.g. `io.within("room1").join("room2")`)
+    /// are not checked.
+    ///
+    /// Only one hook can be registered, calling this method again replaces the previous one.
+    ///
+    /// # Example
+    /// ```
+    /// # use socketioxide::{SocketIo, extract::SocketRef};
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.on_join(|_sid, room| !room.starts_with("admin:"));
+    /// io.ns("/", |socket: SocketRef| {
+    ///     assert!(socket.join("lobby").is_ok());
+    ///     assert!(socket.join("admin:logs").is_err());
+    /// });
+    /// ```
+    #[inline]
+    pub fn on_join<F>(&self, hook: F)
+    where
+        F: Fn(Sid, &Room) -> bool + Send + Sync + 'static,
+    {
+        self.0.set_join_hook(Arc::new(hook));
+    }
+
+    /// Registers a hook that will be called each time a socket is fully connected to a namespace:
+    /// once its connect handler was called and the `CONNECT` packet was sent to the client by the transport.
+    ///
+    /// Unlike the connect handler, which may run before the client has received the `CONNECT` packet,
+    /// the client is guaranteed to be ready to handle events and acknowledgements,
+    /// so it can be used to run post-connection setup with
+    /// [`Socket::emit_with_ack`](crate::socket::Socket#method.emit_with_ack).
+    /// The hook is not called if the socket disconnects before.
+    ///
+    /// Only one hook can be registered, calling this method again replaces the previous one.
+    ///
+    /// # Example
+    /// ```
+    /// # use socketioxide::{SocketIo, extract::SocketRef};
+    /// # use std::time::Duration;
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.on_connected(|socket: SocketRef| async move {
+    ///     let ack = socket.timeout(Duration::from_secs(5)).emit_with_ack::<_, String>("version", "1.0");
+    ///     match ack.unwrap().await {
+    ///         Ok(ack) => println!("client version: {:?}", ack.data),
+    ///         Err(err) => println!("client did not answer: {err}"),
+    ///     }
+    /// });
+    /// ```
+    #[inline]
+    pub fn on_connected<F, Fut>(&self, hook: F)
+    where
+        F: Fn(SocketRef<A>) -> Fut + Send + Sync + 'static,
+        Fut: std::future::Future<Output = ()> + Send + 'static,
+    {
+        self.0
+            .set_connected_hook(Arc::new(move |socket| Box::pin(hook(socket))));
     }
-}
 
-impl Default for SocketIoBuilder {
-    fn default() -> Self {
-        Self::new()
+    /// Registers a hook that will be called with the raw bytes of every engine.io frame
+    /// sent to or received from the clients, to debug the exchanges at the wire level.
+    /// It receives the engine.io session id, the [`Direction`](crate::Direction) of the frame and its bytes.
+    ///
+    /// With the websocket transport each websocket message is reported. With the polling transport,
+    /// each response payload is reported and the request bodies are reported chunk by chunk.
+    /// See the [`engineioxide::frames`] module for more details.
+    ///
+    /// The hook is called on the transport tasks, so it should not block.
+    /// Only one hook can be registered, calling this method again replaces the previous one.
+    /// If no hook is registered, the frames are not inspected.
+    ///
+    /// # Example
+    /// ```
+    /// # use socketioxide::SocketIo;
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.on_raw_packet(|sid, direction, data| {
+    ///     println!("[{sid}] {direction:?}: {}", String::from_utf8_lossy(data));
+    /// });
+    /// ```
+    #[cfg(feature = "debug-frames")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "debug-frames")))]
+    #[inline]
+    pub fn on_raw_packet<F>(&self, hook: F)
+    where
+        F: Fn(Sid, crate::Direction, &[u8]) + Send + Sync + 'static,
+    {
+        self.0.config.engine_config.raw_packet_hook.set(hook);
     }
-}
 
-/// The [`SocketIo`] instance can be cheaply cloned and moved around everywhere in your program.
-/// It can be used as the main handle to access the whole socket.io context.
-#[derive(Debug)]
-pub struct SocketIo<A: Adapter = LocalAdapter>(Arc<Client<A>>);
+    /// Registers a hook that will be called each time a socket leaves a room,
+    /// either with [`Socket::leave`](crate::socket::Socket#method.leave),
+    /// [`Socket::leave_all`](crate::socket::Socket#method.leave_all) or because it disconnected.
+    ///
+    /// Only one hook can be registered, calling this method again replaces the previous one.
+    ///
+    /// # Example
+    /// ```
+    /// # use socketioxide::SocketIo;
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.on_leave(|sid, room| println!("socket {sid} left {room}"));
+    /// ```
+    #[inline]
+    pub fn on_leave<F>(&self, hook: F)
+    where
+        F: Fn(Sid, &Room) + Send + Sync + 'static,
+    {
+        self.0.set_leave_hook(Arc::new(hook));
+    }
 
-impl SocketIo<LocalAdapter> {
-    /// Creates a new [`SocketIoBuilder`] with a default config
-    #[inline(always)]
-    pub fn builder() -> SocketIoBuilder {
-        SocketIoBuilder::new()
+    /// Registers a rewriter of the names of the events received from the clients of any namespace,
+    /// e.g. to keep supporting the event names used by older clients.
+    ///
+    /// It is called before the lookup of the event handler with the received event name,
+    /// and returns the name of the event to dispatch, or `None` to drop the event.
+    ///
+    /// Only one rewriter can be registered, calling this method again replaces the previous one.
+    ///
+    /// # Example
+    /// ```
+    /// # use socketioxide::{SocketIo, extract::SocketRef};
+    /// # use std::borrow::Cow;
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.rewrite_events(|name| match name.strip_prefix("v1:") {
+    ///     Some("legacy") => None,
+    ///     Some(name) => Some(Cow::Borrowed(name)),
+    ///     None => Some(Cow::Borrowed(name)),
+    /// });
+    /// io.ns("/", |socket: SocketRef| {
+    ///     // Also called for the `v1:message` events
+    ///     socket.on("message", || {});
+    /// });
+    /// ```
+    #[inline]
+    pub fn rewrite_events<F>(&self, rewriter: F)
+    where
+        F: for<'a> Fn(&'a str) -> Option<Cow<'a, str>> + Send + Sync + 'static,
+    {
+        self.0.set_event_rewriter(Arc::new(rewriter));
     }
 
-    /// Creates a new [`SocketIoService`] and a [`SocketIo`] instance with a default config.
-    /// This service will be a _standalone_ service that return a 404 error for every non-socket.io request.
-    /// It can be used as a [`Service`](tower::Service) (see hyper example)
-    #[inline(always)]
-    pub fn new_svc() -> (SocketIoService<NotFoundService>, SocketIo) {
-        Self::builder().build_svc()
+    /// Registers a hook that will be called each time a room of any namespace gets its first member,
+    /// e.g. to set up the resources associated with the room.
+    ///
+    /// The hook is called by the adapter after the socket joined the room.
+    /// Only one hook can be registered, calling this method again replaces the previous one.
+    ///
+    /// # Example
+    /// ```
+    /// # use socketioxide::SocketIo;
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.on_room_created(|room| println!("starting the game instance of {room}"));
+    /// ```
+    #[inline]
+    pub fn on_room_created<F>(&self, hook: F)
+    where
+        F: Fn(&Room) + Send + Sync + 'static,
+    {
+        self.0.set_room_created_hook(Arc::new(hook));
     }
 
-    /// Creates a new [`SocketIoService`] and a [`SocketIo`] instance with a default config.
-    /// It can be used as a [`Service`](tower::Service) with an inner service
-    #[inline(always)]
-    pub fn new_inner_svc<S: Clone>(svc: S) -> (SocketIoService<S>, SocketIo) {
-        Self::builder().build_with_inner_svc(svc)
+    /// Registers a hook that will be called each time the last member of a room of any namespace leaves it,
+    /// either explicitly or because it disconnected, e.g. to tear down the resources associated with the room.
+    ///
+    /// The hook is called by the adapter after the socket left the room.
+    /// Only one hook can be registered, calling this method again replaces the previous one.
+    ///
+    /// # Example
+    /// ```
+    /// # use socketioxide::SocketIo;
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.on_room_empty(|room| println!("tearing down the game instance of {room}"));
+    /// ```
+    #[inline]
+    pub fn on_room_empty<F>(&self, hook: F)
+    where
+        F: Fn(&Room) + Send + Sync + 'static,
+    {
+        self.0.set_room_empty_hook(Arc::new(hook));
     }
 
-    /// Builds a [`SocketIoLayer`] and a [`SocketIo`] instance with a default config.
-    /// It can be used as a tower [`Layer`](tower::layer::Layer) (see axum example)
-    #[inline(always)]
-    pub fn new_layer() -> (SocketIoLayer, SocketIo) {
-        Self::builder().build_layer()
+    /// Sends a lifecycle event to the registered sink if there is one.
+    #[inline]
+    pub(crate) fn notify(&self, event: impl FnOnce() -> LifecycleEvent) {
+        self.0.notify(event);
     }
-}
 
-impl<A: Adapter> SocketIo<A> {
-    /// Returns a reference to the [`SocketIoConfig`] used by this [`SocketIo`] instance
+    /// Returns true if a lifecycle event sink is registered.
     #[inline]
-    pub fn config(&self) -> &SocketIoConfig {
-        &self.0.config
+    pub(crate) fn has_event_sink(&self) -> bool {
+        self.0.has_event_sink()
     }
 
-    /// Registers a [`ConnectHandler`] for the given namespace
+    /// Sends a drop event to the registered callback if there is one.
+    #[inline]
+    pub(crate) fn notify_drop(&self, event: impl FnOnce() -> DropEvent) {
+        self.0.notify_drop(event);
+    }
+
+    #[inline]
+    pub(crate) fn has_ns(&self, path: &str) -> bool {
+        self.0.get_ns(path).is_some()
+    }
+
+    #[inline]
+    pub(crate) fn join_hook(&self) -> Option<BoxedJoinHook> {
+        self.0.join_hook()
+    }
+
+    #[inline]
+    pub(crate) fn leave_hook(&self) -> Option<BoxedLeaveHook> {
+        self.0.leave_hook()
+    }
+
+    #[inline]
+    pub(crate) fn connected_hook(&self) -> Option<BoxedConnectedHook<A>> {
+        self.0.connected_hook()
+    }
+
+    #[inline]
+    pub(crate) fn event_rewriter(&self) -> Option<BoxedEventRewriter> {
+        self.0.event_rewriter()
+    }
+
+    #[inline]
+    pub(crate) fn client(&self) -> &Arc<Client<A>> {
+        &self.0
+    }
+
+    /// Returns a [`Presence`] handle to track the presence of rooms in the given namespace.
+    /// Returns `None` if the namespace does not exist.
+    /// Currently you cannot select a dynamic namespace with this method.
     ///
-    /// * See the [`connect`](crate::handler::connect) module doc for more details on connect handler.
-    /// * See the [`extract`](crate::extract) module doc for more details on available extractors.
+    /// See the [`presence`](crate::presence) module doc for more details.
     ///
-    /// # Examples
-    /// #### Simple example with a sync closure:
+    /// # Example
     /// ```
-    /// # use socketioxide::{SocketIo, extract::*};
-    /// # use serde::{Serialize, Deserialize};
-    /// #[derive(Debug, Serialize, Deserialize)]
-    /// struct MyData {
-    ///     name: String,
-    ///     age: u8,
-    /// }
-    ///
+    /// # use socketioxide::{SocketIo, extract::SocketRef};
     /// let (_, io) = SocketIo::new_svc();
-    /// io.ns("/", |socket: SocketRef| {
-    ///     // Register a handler for the "test" event and extract the data as a `MyData` struct
-    ///     // With the Data extractor, the handler is called only if the data can be deserialized as a `MyData` struct
-    ///     // If you want to manage errors yourself you can use the TryData extractor
-    ///     socket.on("test", |socket: SocketRef, Data::<MyData>(data)| {
-    ///         println!("Received a test message {:?}", data);
-    ///         socket.emit("test-test", MyData { name: "Test".to_string(), age: 8 }).ok(); // Emit a message to the client
-    ///     });
-    /// });
+    /// io.ns("/", |socket: SocketRef| socket.join("lobby").unwrap());
     ///
+    /// io.presence("/").unwrap().track(["lobby", "game"]);
     /// ```
+    #[inline]
+    pub fn presence<'a>(&self, path: impl Into<&'a str>) -> Option<Presence<A>> {
+        self.0.get_ns(path.into()).map(Presence::new)
+    }
+
+    /// Gets a [`Stream`] yielding all the socket ids of the given namespace.
+    /// Returns `None` if the namespace does not exist.
     ///
-    /// #### Example with a closure and an acknowledgement + binary data:
+    /// Contrary to [`sockets()`](#method.sockets), the sockets are not all collected at once.
+    /// It can be used to iterate over a large number of sockets for maintenance tasks.
+    ///
+    /// [`Stream`]: futures_core::stream::Stream
+    ///
+    /// # Example
     /// ```
     /// # use socketioxide::{SocketIo, extract::*};
-    /// # use serde_json::Value;
-    /// # use serde::{Serialize, Deserialize};
-    /// #[derive(Debug, Serialize, Deserialize)]
-    /// struct MyData {
-    ///     name: String,
-    ///     age: u8,
-    /// }
-    ///
+    /// # use futures_util::StreamExt;
     /// let (_, io) = SocketIo::new_svc();
     /// io.ns("/", |socket: SocketRef| {
-    ///     // Register an async handler for the "test" event and extract the data as a `MyData` struct
-    ///     // Extract the binary payload as a `Vec<Bytes>` with the Bin extractor.
-    ///     // It should be the last extractor because it consumes the request
-    ///     socket.on("test", |socket: SocketRef, Data::<MyData>(data), ack: AckSender, Bin(bin)| async move {
-    ///         println!("Received a test message {:?}", data);
-    ///         tokio::time::sleep(std::time::Duration::from_secs(1)).await;
-    ///         ack.bin(bin).send(data).ok(); // The data received is sent back to the client through the ack
-    ///         socket.emit("test-test", MyData { name: "Test".to_string(), age: 8 }).ok(); // Emit a message to the client
-    ///     });
+    ///   socket.on("test", |io: SocketIo| async move {
+    ///     // Iterate over all the socket ids of the namespace "/"
+    ///     let mut sids = io.sids_stream("/").unwrap().unwrap();
+    ///     while let Some(sid) = sids.next().await {
+    ///         println!("socket {sid} is connected");
+    ///     }
+    ///   });
     /// });
     /// ```
-    /// #### Simple example with a closure:
-    /// ```
-    /// # use socketioxide::{SocketIo, extract::{SocketRef, Data}};
-    /// # use serde::{Serialize, Deserialize};
-    /// #[derive(Debug, Deserialize)]
-    /// struct MyAuthData {
-    ///     token: String,
-    /// }
-    /// #[derive(Debug, Serialize, Deserialize)]
-    /// struct MyData {
-    ///     name: String,
-    ///     age: u8,
-    /// }
+    pub fn sids_stream<'a>(
+        &self,
+        path: impl Into<&'a str>,
+    ) -> Option<Result<BoxStream<'static, Sid>, A::Error>> {
+        self.0
+            .get_ns(path.into())
+            .map(|ns| ns.adapter.sids_stream())
+    }
+
+    /// Subscribes to the room membership changes of the given namespace.
+    /// Returns `None` if the namespace does not exist.
+    ///
+    /// The returned [`Stream`] yields a [`MembershipEvent`] every time a socket joins or leaves a room,
+    /// which can be used to react to the room topology changes without polling [`sockets()`](#method.sockets).
+    ///
+    /// [`Stream`]: futures_core::stream::Stream
     ///
+    /// # Example
+    /// ```
+    /// # use socketioxide::{SocketIo, extract::*, adapter::MembershipKind};
+    /// # use futures_util::StreamExt;
     /// let (_, io) = SocketIo::new_svc();
-    /// io.ns("/", |socket: SocketRef, Data(auth): Data<MyAuthData>| {
-    ///     if auth.token.is_empty() {
-    ///         println!("Invalid token, disconnecting");
-    ///         socket.disconnect().ok();
-    ///         return;
+    /// io.ns("/", |socket: SocketRef| {
+    ///   socket.on("watch", |io: SocketIo| async move {
+    ///     let mut changes = io.subscribe_changes("/").unwrap().unwrap();
+    ///     while let Some(change) = changes.next().await {
+    ///         match change.kind {
+    ///             MembershipKind::Join => println!("{} joined {}", change.sid, change.room),
+    ///             MembershipKind::Leave => println!("{} left {}", change.sid, change.room),
+    ///         }
     ///     }
-    ///     socket.on("test", |socket: SocketRef, Data::<MyData>(data)| async move {
-    ///         println!("Received a test message {:?}", data);
-    ///         socket.emit("test-test", MyData { name: "Test".to_string(), age: 8 }).ok(); // Emit a message to the client
-    ///     });
+    ///   });
     /// });
+    /// ```
+    pub fn subscribe_changes<'a>(
+        &self,
+        path: impl Into<&'a str>,
+    ) -> Option<Result<BoxStream<'static, MembershipEvent>, A::Error>> {
+        self.0
+            .get_ns(path.into())
+            .map(|ns| ns.adapter.subscribe_changes())
+    }
+
+    /// Exports a serializable snapshot of the rooms and the metadata of the given namespace,
+    /// which can be given to [`import_state()`](#method.import_state) on another instance.
+    /// Returns `None` if the namespace does not exist.
+    ///
+    /// # Example
+    /// ```
+    /// # use socketioxide::SocketIo;
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.ns("/", || {});
+    /// let state = io.export_state("/").unwrap().unwrap();
+    /// let json = serde_json::to_string(&state).unwrap();
+    ///
+    /// let (_, io2) = SocketIo::new_svc();
+    /// io2.ns("/", || {});
+    /// io2.import_state("/", serde_json::from_str(&json).unwrap()).unwrap().unwrap();
+    /// ```
+    pub fn export_state<'a>(
+        &self,
+        path: impl Into<&'a str>,
+    ) -> Option<Result<AdapterState, A::Error>> {
+        self.0
+            .get_ns(path.into())
+            .map(|ns| ns.adapter.export_state())
+    }
+
+    /// Replaces the rooms and the metadata of the given namespace with a snapshot from
+    /// [`export_state()`](#method.export_state).
+    /// Returns `None` if the namespace does not exist.
+    pub fn import_state<'a>(
+        &self,
+        path: impl Into<&'a str>,
+        state: AdapterState,
+    ) -> Option<Result<(), A::Error>> {
+        self.0
+            .get_ns(path.into())
+            .map(|ns| ns.adapter.import_state(state))
+    }
+
+    /// Returns a [`RoomMeta`] handle to the metadata of a room of the root namespace.
     ///
+    /// See the [`room_meta`](crate::room_meta) module doc for more details.
+    ///
+    /// ## Panics
+    /// If the **default namespace "/" is not found** this fn will panic!
+    ///
+    /// # Example
+    /// ```
+    /// # use socketioxide::SocketIo;
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.ns("/", || {});
+    ///
+    /// let meta = io.room_meta("game");
+    /// meta.set_capacity(2).unwrap();
+    /// assert_eq!(meta.capacity().unwrap(), Some(2));
+    /// ```
+    #[inline]
+    pub fn room_meta(&self, room: impl Into<Room>) -> RoomMeta<A> {
+        let ns = self.0.get_ns("/").expect("default namespace not found");
+        RoomMeta::new(ns, room.into())
+    }
+
+    /// Freezes a room of the root namespace, e.g. during the maintenance of a game instance.
+    ///
+    /// The broadcasts to the room are held according to the [`FreezePolicy`] set with
+    /// [`SocketIoBuilder::freeze_policy`]: they are either queued until [`unfreeze_room`] or dropped.
+    /// A broadcast selecting several rooms is held if any of them is frozen.
+    ///
+    /// Only the broadcasts sent with [`emit()`] are held, the emits to a single socket are sent right away.
+    /// The broadcasts waiting for acknowledgements, for a flush or for an audit cannot be held:
+    /// they fail with a [`BroadcastError::FrozenRoom`].
+    /// Freezing an already frozen room does nothing.
+    ///
+    /// ## Panics
+    /// If the **default namespace "/" is not found** this fn will panic!
+    ///
+    /// [`unfreeze_room`]: #method.unfreeze_room
+    /// [`emit()`]: crate::operators::BroadcastOperators#method.emit
+    /// [`BroadcastError::FrozenRoom`]: crate::BroadcastError::FrozenRoom
+    ///
+    /// # Example
+    /// ```
+    /// # use socketioxide::SocketIo;
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.ns("/", || {});
+    ///
+    /// io.freeze_room("game");
+    /// io.to("game").emit("state", "queued").unwrap();
+    /// // The queued broadcasts are sent in order
+    /// assert_eq!(io.unfreeze_room("game"), 1);
+    /// ```
+    #[inline]
+    pub fn freeze_room(&self, room: impl Into<Room>) {
+        let ns = self.0.get_ns("/").expect("default namespace not found");
+        ns.freeze_room(room.into(), self.0.config.freeze_policy);
+    }
+
+    /// Registers the JSON schema of the payloads of an event, replacing the previous one.
+    /// The events emitted with this name to the clients of any namespace are then validated against it.
+    /// See the [`schema`](crate::schema) module doc for more details.
+    ///
+    /// Returns an [`InvalidSchemaError`](crate::schema::InvalidSchemaError) and keeps the previous schema
+    /// if this one uses an unsupported keyword or a keyword with a value of the wrong type.
+    ///
+    /// # Example
+    /// ```
+    /// # use socketioxide::SocketIo;
+    /// # use serde_json::json;
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.register_schema("message", json!({ "type": "string", "maxLength": 280 }))
+    ///     .unwrap();
     /// ```
     #[inline]
-    pub fn ns<C, T>(&self, path: impl Into<Cow<'static, str>>, callback: C)
-    where
-        C: ConnectHandler<A, T>,
-        T: Send + Sync + 'static,
-    {
-        self.0.add_ns(path.into(), callback);
+    #[cfg_attr(docsrs, doc(cfg(feature = "schema")))]
+    #[cfg(feature = "schema")]
+    pub fn register_schema(
+        &self,
+        event: impl Into<String>,
+        schema: Value,
+    ) -> Result<(), crate::schema::InvalidSchemaError> {
+        self.0.schemas.register(event.into(), schema)
     }
 
-    /// Registers a [`ConnectHandler`] for the given dynamic namespace.
-    /// You can specify dynamic parts in the path by using the `{name}` syntax.
-    /// Note that any static namespace will take precedence over a dynamic one.
+    /// Returns the document of a room of the root namespace, patched with
+    /// [`BroadcastOperators::patch`](crate::operators::BroadcastOperators#method.patch).
+    /// See the [`state_sync`](crate::state_sync) module doc for more details.
     ///
+    /// ## Panics
+    /// If the **default namespace "/" is not found** this fn will panic!
+    #[inline]
+    pub fn room_state(&self, room: impl AsRef<str>) -> Option<Value> {
+        let ns = self.0.get_ns("/").expect("default namespace not found");
+        let states = ns.room_states.lock().unwrap();
+        states.get(room.as_ref()).cloned()
+    }
+
+    /// Removes the document of a room of the root namespace, so the joiners don't receive a snapshot anymore.
+    /// Returns the removed document.
     ///
-    /// For more info about namespace routing, see the [matchit] router documentation.
+    /// ## Panics
+    /// If the **default namespace "/" is not found** this fn will panic!
+    #[inline]
+    pub fn clear_room_state(&self, room: impl AsRef<str>) -> Option<Value> {
+        let ns = self.0.get_ns("/").expect("default namespace not found");
+        let mut states = ns.room_states.lock().unwrap();
+        states.remove(room.as_ref())
+    }
+
+    /// Unfreezes a room frozen with [`freeze_room`](#method.freeze_room), sending its queued broadcasts in order.
+    /// Returns the number of broadcasts that were sent.
     ///
-    /// The dynamic namespace will create a child namespace for any path that matches the given pattern with the given handler.
+    /// A queued broadcast still selecting another frozen room is queued again for that room.
+    /// The broadcasts sent to the room while its queue is sent, e.g. from a lifecycle sink,
+    /// are sent after the queued ones and counted as well.
     ///
-    /// * See the [`connect`](crate::handler::connect) module doc for more details on connect handler.
-    /// * See the [`extract`](crate::extract) module doc for more details on available extractors.
+    /// ## Panics
+    /// If the **default namespace "/" is not found** this fn will panic!
+    #[inline]
+    pub fn unfreeze_room(&self, room: impl AsRef<str>) -> usize {
+        let ns = self.0.get_ns("/").expect("default namespace not found");
+        ns.unfreeze_room(room.as_ref())
+    }
+
+    /// Emits an event to the other servers of the cluster through the adapter, on the root namespace.
+    /// The event is not sent to the clients.
+    /// It will be received by the handlers registered with [`SocketIo::on_server_event`] on the other servers.
+    ///
+    /// With the default [`LocalAdapter`] there is no other server, so this is a no-op.
     ///
     /// ## Errors
-    /// If the pattern is invalid, a [`NsInsertError`](crate::NsInsertError) will be returned.
+    /// * If the data cannot be serialized a [`BroadcastError::Serialize`] is returned.
+    /// * If the adapter fails to send the event a [`BroadcastError::Adapter`] is returned.
     ///
-    /// ## Example
-    /// ```
-    /// # use socketioxide::{SocketIo, extract::SocketRef};
-    /// let (_, io) = SocketIo::new_svc();
-    /// io.dyn_ns("/client/{client_id}", |socket: SocketRef| {
-    ///     println!("Socket connected on dynamic namespace with namespace path: {}", socket.ns());
-    /// }).unwrap();
+    /// ## Panics
+    /// If the **default namespace "/" is not found** this fn will panic!
     ///
+    /// ## Example
     /// ```
-    /// ```
-    /// # use socketioxide::{SocketIo, extract::SocketRef};
+    /// # use socketioxide::SocketIo;
     /// let (_, io) = SocketIo::new_svc();
-    /// io.dyn_ns("/client/{*remaining_path}", |socket: SocketRef| {
-    ///     println!("Socket connected on dynamic namespace with namespace path: {}", socket.ns());
-    /// }).unwrap();
+    /// io.ns("/", || {});
     ///
+    /// io.server_emit("sync", &[1, 2, 3]).unwrap();
     /// ```
-    #[inline]
-    pub fn dyn_ns<C, T>(
+    pub fn server_emit(
         &self,
-        path: impl Into<String>,
-        callback: C,
-    ) -> Result<(), crate::NsInsertError>
-    where
-        C: ConnectHandler<A, T>,
-        T: Send + Sync + 'static,
-    {
-        self.0.add_dyn_ns(path.into(), callback)
+        event: &str,
+        data: impl serde::Serialize,
+    ) -> Result<(), BroadcastError> {
+        let data = serde_json::to_value(data)?;
+        self.0
+            .get_ns("/")
+            .expect("default namespace not found")
+            .adapter
+            .server_side_emit(event, data)
+            .map_err(|e| BroadcastError::Adapter(e.into()))
     }
 
-    /// Deletes the namespace with the given path.
+    /// Registers a handler for an event sent by another server of the cluster with [`SocketIo::server_emit`],
+    /// on the root namespace. Registering a handler for the same event replaces the previous one.
     ///
-    /// This will disconnect all sockets connected to this
-    /// namespace in a deferred way.
+    /// The data is deserialized to the type `T` of the [`Data`](crate::extract::Data) argument.
+    /// If it fails, the handler is not called.
     ///
-    /// # Panics
-    /// If the v4 protocol (legacy) is enabled and the namespace to delete is the default namespace "/".
-    /// For v4, the default namespace cannot be deleted. See [official doc](https://socket.io/docs/v3/namespaces/#main-namespace) for more informations.
-    #[inline]
-    pub fn delete_ns<'a>(&self, path: impl Into<&'a str>) {
-        self.0.delete_ns(path.into());
-    }
-
-    /// Gracefully closes all the connections and drops every sockets
+    /// ## Panics
+    /// If the **default namespace "/" is not found** this fn will panic!
     ///
-    /// Any `on_disconnect` handler will called with [`DisconnectReason::ClosingServer`](crate::socket::DisconnectReason::ClosingServer)
-    #[inline]
-    pub async fn close(&self) {
-        self.0.close().await;
+    /// ## Example
+    /// ```
+    /// # use socketioxide::{SocketIo, extract::Data};
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.ns("/", || {});
+    ///
+    /// io.on_server_event("sync", |Data::<Vec<u32>>(data)| {
+    ///     println!("received sync event from another server: {:?}", data);
+    /// });
+    /// ```
+    pub fn on_server_event<T, F>(&self, event: impl Into<Cow<'static, str>>, handler: F)
+    where
+        T: DeserializeOwned,
+        F: Fn(Data<T>) + Send + Sync + 'static,
+    {
+        let handler = move |data: Value| match serde_json::from_value(data) {
+            Ok(data) => handler(Data(data)),
+            Err(_e) => {
+                #[cfg(feature = "tracing")]
+                tracing::debug!("error deserializing server event data: {_e}");
+            }
+        };
+        self.0
+            .get_ns("/")
+            .expect("default namespace not found")
+            .on_server_event(event.into(), Arc::new(handler));
     }
 
     // Chaining operators fns
@@ -545,6 +2504,112 @@ impl<A: Adapter> SocketIo<A> {
         self.get_default_op().except(rooms)
     }
 
+    /// Selects all sockets that are in **any** of the given rooms (union) on the root namespace.
+    ///
+    /// Alias for `io.of("/").unwrap().to_any_of(rooms)`
+    ///
+    /// ## Panics
+    /// If the **default namespace "/" is not found** this fn will panic!
+    ///
+    /// ## Example
+    /// ```
+    /// # use socketioxide::{SocketIo, extract::SocketRef};
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.ns("/", |socket: SocketRef| {
+    ///     socket.join(["region:eu", "plan:pro"]);
+    /// });
+    ///
+    /// // Later in your code you can select all sockets in the room "region:eu" or in the room "plan:pro"
+    /// let sockets = io.to_any_of(["region:eu", "plan:pro"]).sockets().unwrap();
+    /// for socket in sockets {
+    ///   println!("found socket on / ns in region:eu or plan:pro with id: {}", socket.id);
+    /// }
+    #[inline]
+    pub fn to_any_of(&self, rooms: impl RoomParam) -> BroadcastOperators<WithoutBinary, A> {
+        self.get_default_op().to_any_of(rooms)
+    }
+
+    /// Selects all sockets that are in **all** the given rooms (intersection) on the root namespace.
+    ///
+    /// Alias for `io.of("/").unwrap().to_all_of(rooms)`
+    ///
+    /// See [`BroadcastOperators::to_all_of`] for more details.
+    ///
+    /// ## Panics
+    /// If the **default namespace "/" is not found** this fn will panic!
+    ///
+    /// ## Example
+    /// ```
+    /// # use socketioxide::{SocketIo, extract::SocketRef};
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.ns("/", |socket: SocketRef| {
+    ///     socket.join(["region:eu", "plan:pro"]);
+    /// });
+    ///
+    /// // Later in your code you can emit to the sockets that are both in "region:eu" and "plan:pro"
+    /// io.to_all_of(["region:eu", "plan:pro"]).emit("offer", "pro plan discount");
+    /// ```
+    #[inline]
+    pub fn to_all_of(&self, rooms: impl RoomParam) -> BroadcastOperators<WithoutBinary, A> {
+        self.get_default_op().to_all_of(rooms)
+    }
+
+    /// Selects the socket with the given id on the root namespace.
+    ///
+    /// When [`SocketIoBuilder::auto_sid_room`] is enabled, the socket is selected through its sid room,
+    /// the room named after the socket id, like with `io.to(sid)`. Otherwise the socket is targeted directly,
+    /// like with [`to_sockets`](#method.to_sockets), so it is reached with the default config.
+    /// In both cases it can be combined with the other room operators like any room.
+    ///
+    /// ## Panics
+    /// If the **default namespace "/" is not found** this fn will panic!
+    ///
+    /// ## Example
+    /// ```
+    /// # use socketioxide::{SocketIo, extract::SocketRef};
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.ns("/", |socket: SocketRef| {
+    ///     socket.on("whisper", |socket: SocketRef, io: SocketIo| {
+    ///         io.to_sid(socket.id).emit("whisper", "only for you").unwrap();
+    ///     });
+    /// });
+    /// ```
+    #[inline]
+    pub fn to_sid(&self, sid: Sid) -> BroadcastOperators<WithoutBinary, A> {
+        if self.0.config.auto_sid_room {
+            self.to(sid)
+        } else {
+            self.to_sockets([sid])
+        }
+    }
+
+    /// Selects the sockets with the given ids on the root namespace.
+    ///
+    /// Alias for `io.of("/").unwrap().to_sockets(sids)`
+    ///
+    /// See [`BroadcastOperators::to_sockets`] for more details.
+    ///
+    /// ## Panics
+    /// If the **default namespace "/" is not found** this fn will panic!
+    ///
+    /// ## Example
+    /// ```
+    /// # use socketioxide::{SocketIo, extract::SocketRef, socket::Sid};
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.ns("/", || {});
+    ///
+    /// // Later in your code you can emit to the sockets returned by a database query
+    /// let sids: Vec<Sid> = vec![];
+    /// io.to_sockets(sids).emit("notification", "hello").unwrap();
+    /// ```
+    #[inline]
+    pub fn to_sockets(
+        &self,
+        sids: impl IntoIterator<Item = Sid>,
+    ) -> BroadcastOperators<WithoutBinary, A> {
+        self.get_default_op().to_sockets(sids)
+    }
+
     /// Broadcasts to all sockets only connected on this node (when using multiple nodes).
     /// When using the default in-memory adapter, this operator is a no-op.
     ///
@@ -572,6 +2637,69 @@ impl<A: Adapter> SocketIo<A> {
         self.get_default_op().local()
     }
 
+    /// Selects all sockets matching a predicate over their [`Extensions`](crate::extensions::Extensions)
+    /// on the root namespace.
+    ///
+    /// Alias for `io.of("/").unwrap().filter(predicate)`
+    ///
+    /// See [`BroadcastOperators::filter`] for more details.
+    ///
+    /// ## Panics
+    /// If the **default namespace "/" is not found** this fn will panic!
+    ///
+    /// ## Example
+    /// ```
+    /// # use socketioxide::{SocketIo, extract::SocketRef};
+    /// #[derive(Clone, PartialEq)]
+    /// enum Role { Admin, User }
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.ns("/", |socket: SocketRef| {
+    ///     socket.extensions.insert(Role::User);
+    /// });
+    ///
+    /// // Later in your code you can emit to all the admins
+    /// io.filter(|ext| ext.get::<Role>() == Some(Role::Admin))
+    ///     .emit("hello", "admins");
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "extensions")))]
+    #[cfg(feature = "extensions")]
+    #[inline]
+    pub fn filter<F>(&self, predicate: F) -> BroadcastOperators<WithoutBinary, A>
+    where
+        F: Fn(&crate::extensions::Extensions) -> bool + Send + Sync + 'static,
+    {
+        self.get_default_op().filter(predicate)
+    }
+
+    /// Selects all sockets of the root namespace with the metadata `key` set to `value`.
+    ///
+    /// Alias for `io.of("/").unwrap().where_meta(key, value)`
+    ///
+    /// See [`BroadcastOperators::where_meta`] for more details.
+    ///
+    /// ## Panics
+    /// If the **default namespace "/" is not found** this fn will panic!
+    ///
+    /// ## Example
+    /// ```
+    /// # use socketioxide::{SocketIo, extract::SocketRef};
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.ns("/", |socket: SocketRef| {
+    ///     socket.set_meta("region", "eu").unwrap();
+    /// });
+    ///
+    /// // Later in your code you can emit to all the sockets of the eu region
+    /// io.where_meta("region", "eu").emit("hello", "eu");
+    /// ```
+    #[inline]
+    pub fn where_meta(
+        &self,
+        key: impl Into<String>,
+        value: impl Into<String>,
+    ) -> BroadcastOperators<WithoutBinary, A> {
+        self.get_default_op().where_meta(key, value)
+    }
+
     /// Sets a custom timeout when broadcasting a message with an acknowledgement.
     ///
     /// Alias for `io.of("/").unwrap().timeout(duration)`
@@ -678,6 +2806,69 @@ impl<A: Adapter> SocketIo<A> {
         self.get_default_op().emit(event, data)
     }
 
+    /// Serializes an event once into a [`PreparedPacket`], to emit it with
+    /// [`emit_prepared`](BroadcastOperators#method.emit_prepared) to several rooms or namespaces.
+    ///
+    /// ## Errors
+    /// When encoding the data into JSON a [`serde_json::Error`] is returned.
+    ///
+    /// ## Example
+    /// ```
+    /// # use socketioxide::SocketIo;
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.ns("/", || {});
+    /// let packet = io.prepare("score", [1, 2, 3]).unwrap();
+    /// for room in ["game1", "game2"] {
+    ///     io.to(room).emit_prepared(&packet).unwrap();
+    /// }
+    /// ```
+    #[inline]
+    pub fn prepare<T: serde::Serialize>(
+        &self,
+        event: impl Into<Cow<'static, str>>,
+        data: T,
+    ) -> Result<PreparedPacket, serde_json::Error> {
+        PreparedPacket::new(event, data)
+    }
+
+    /// Serializes and encodes an event for the root namespace into an [`EncodedPacket`],
+    /// with the same bytes as the ones written to the connection when emitting it.
+    ///
+    /// Contrary to [`prepare`](#method.prepare), it is not tied to any operator:
+    /// it can be used to cache the encoded packets, to route them with a custom fan-out strategy
+    /// or to inspect the wire format. A text packet can be sent back with
+    /// [`Socket::send_raw_bytes`](crate::socket::Socket#method.send_raw_bytes).
+    ///
+    /// ## Errors
+    /// When encoding the data into JSON a [`serde_json::Error`] is returned.
+    ///
+    /// ## Example
+    /// ```
+    /// # use socketioxide::SocketIo;
+    /// let (_, io) = SocketIo::new_svc();
+    /// let packet = io.encode_event("score", ("alice", 42)).unwrap();
+    /// assert_eq!(packet.text(), r#"2["score","alice",42]"#);
+    /// ```
+    #[inline]
+    pub fn encode_event<T: serde::Serialize>(
+        &self,
+        event: impl Into<Cow<'static, str>>,
+        data: T,
+    ) -> Result<EncodedPacket, serde_json::Error> {
+        EncodedPacket::new("/", event, data)
+    }
+
+    /// Emits an event prepared with [`prepare`](#method.prepare) to all sockets selected with the previous operators.
+    ///
+    /// Alias for `io.of("/").unwrap().emit_prepared(packet)`
+    ///
+    /// ## Panics
+    /// If the **default namespace "/" is not found** this fn will panic!
+    #[inline]
+    pub fn emit_prepared(&self, packet: &PreparedPacket) -> Result<(), BroadcastError> {
+        self.get_default_op().emit_prepared(packet)
+    }
+
     /// Emits a message to all sockets selected with the previous operators and
     /// waits for the acknowledgement(s).
     ///
@@ -689,7 +2880,7 @@ impl<A: Adapter> SocketIo<A> {
     /// * As a [`Future`]: It will yield the first [`AckResponse`] received from the client.
     /// Useful when expecting only one acknowledgement.
     ///
-    /// If the packet encoding failed a [`serde_json::Error`] is **immediately** returned.
+    /// If the packet encoding failed a [`BroadcastError::Serialize`] is **immediately** returned.
     ///
     /// If the socket is full or if it has been closed before receiving the acknowledgement,
     /// an [`AckError::Socket`] will be yielded.
@@ -741,7 +2932,7 @@ impl<A: Adapter> SocketIo<A> {
         &self,
         event: impl Into<Cow<'static, str>>,
         data: impl serde::Serialize,
-    ) -> Result<AckStream<V>, serde_json::Error> {
+    ) -> Result<AckStream<V>, BroadcastError> {
         self.get_default_op().emit_with_ack(event, data)
     }
 
@@ -800,6 +2991,8 @@ impl<A: Adapter> SocketIo<A> {
     ///
     /// Alias for `io.of("/").unwrap().join(rooms)`
     ///
+    /// See [`BroadcastOperators::join`] for the checks applied to each socket and the errors.
+    ///
     /// ## Panics
     /// If the **default namespace "/" is not found** this fn will panic!
     ///
@@ -814,7 +3007,7 @@ impl<A: Adapter> SocketIo<A> {
     /// // Later in your code you can for example add all sockets on the root namespace to the room1 and room3
     /// io.join(["room1", "room3"]).unwrap();
     #[inline]
-    pub fn join(self, rooms: impl RoomParam) -> Result<(), A::Error> {
+    pub fn join(self, rooms: impl RoomParam) -> Result<(), Vec<JoinError<A::Error>>> {
         self.get_default_op().join(rooms)
     }
 
@@ -840,7 +3033,7 @@ impl<A: Adapter> SocketIo<A> {
 
     /// Makes all sockets selected with the previous operators leave the given room(s).
     ///
-    /// Alias for `io.of("/").unwrap().join(rooms)`
+    /// Alias for `io.of("/").unwrap().leave(rooms)`
     ///
     /// ## Panics
     /// If the **default namespace "/" is not found** this fn will panic!
@@ -866,6 +3059,15 @@ impl<A: Adapter> SocketIo<A> {
         self.get_default_op().get_socket(sid)
     }
 
+    /// Gets a [`RemoteSocket`] handle to the socket with the specified [`Sid`] on the default namespace,
+    /// connected to any node of the cluster.
+    ///
+    /// See the [`remote`](crate::remote) module doc for more details.
+    #[inline]
+    pub fn remote_socket(&self, sid: Sid) -> RemoteSocket<A> {
+        self.get_default_op().remote_socket(sid)
+    }
+
     #[cfg(feature = "state")]
     pub(crate) fn get_state<T: Clone + 'static>(&self) -> Option<T> {
         self.0.state.try_get::<T>().cloned()
@@ -921,6 +3123,7 @@ impl<A: Adapter> SocketIo<A> {
 mod tests {
 
     use super::*;
+    use crate::RemoteSocketError;
 
     #[test]
     fn get_default_op() {
@@ -960,4 +3163,280 @@ mod tests {
         assert!(io.get_socket(sid).is_some());
         assert!(io.get_socket(Sid::new()).is_none());
     }
+
+    /// A mock adapter connecting all the namespaces created with it as if they were on different nodes.
+    mod mock {
+        use std::{
+            convert::Infallible,
+            sync::{Mutex, Weak},
+            time::Duration,
+        };
+
+        use futures_util::future::BoxFuture;
+
+        use super::*;
+        use crate::{
+            ack::AckInnerStream,
+            adapter::BroadcastOptions,
+            ns::Namespace,
+            packet::Packet,
+            remote::{SocketRequest, SocketResponse},
+        };
+
+        static NODES: Mutex<Vec<Weak<Namespace<MockAdapter>>>> = Mutex::new(Vec::new());
+
+        /// The broadcasts to this room fail as if the other nodes were unreachable.
+        pub const FAILING_ROOM: &str = "failing";
+
+        #[derive(Debug)]
+        pub struct MockAdapter(Weak<Namespace<Self>>);
+
+        impl Adapter for MockAdapter {
+            type Error = Infallible;
+
+            fn new(ns: Weak<Namespace<Self>>) -> Self {
+                NODES.lock().unwrap().push(ns.clone());
+                Self(ns)
+            }
+            fn init(&self) -> Result<(), Infallible> {
+                Ok(())
+            }
+            fn close(&self) -> Result<(), Infallible> {
+                Ok(())
+            }
+            fn server_count(&self) -> Result<u16, Infallible> {
+                Ok(NODES.lock().unwrap().len() as u16)
+            }
+            fn add_all(&self, _: Sid, _: impl RoomParam) -> Result<(), Infallible> {
+                Ok(())
+            }
+            fn del(&self, _: Sid, _: impl RoomParam) -> Result<(), Infallible> {
+                Ok(())
+            }
+            fn del_all(&self, _: Sid) -> Result<(), Infallible> {
+                Ok(())
+            }
+            /// Only the `sids` option is supported, the sockets of every node are reached.
+            fn broadcast(
+                &self,
+                packet: Packet<'_>,
+                opts: BroadcastOptions,
+            ) -> Result<(), BroadcastError> {
+                if opts.rooms.contains(FAILING_ROOM) {
+                    let err: Box<dyn std::error::Error + Send + Sync> = "node unreachable".into();
+                    return Err(crate::AdapterError(err).into());
+                }
+                let nodes = NODES.lock().unwrap().clone();
+                for ns in nodes.iter().filter_map(Weak::upgrade) {
+                    for sid in &opts.sids {
+                        if let Ok(socket) = ns.get_socket(*sid) {
+                            socket.send(packet.clone()).unwrap();
+                        }
+                    }
+                }
+                Ok(())
+            }
+            fn broadcast_with_ack(
+                &self,
+                packet: Packet<'static>,
+                _: BroadcastOptions,
+                timeout: Option<Duration>,
+            ) -> AckInnerStream {
+                AckInnerStream::broadcast::<Self>(packet, vec![], timeout)
+            }
+            fn sockets(&self, _: impl RoomParam) -> Result<Vec<Sid>, Infallible> {
+                Ok(vec![])
+            }
+            fn socket_rooms(&self, _: Sid) -> Result<Vec<Room>, Infallible> {
+                Ok(vec![])
+            }
+            /// The request is answered by the first node owning the socket.
+            fn fetch_socket(
+                &self,
+                req: SocketRequest,
+            ) -> BoxFuture<'static, Result<Option<SocketResponse>, Infallible>> {
+                let nodes = NODES.lock().unwrap().clone();
+                let res = nodes
+                    .iter()
+                    .filter_map(Weak::upgrade)
+                    .find_map(|ns| ns.recv_socket_request(&req));
+                Box::pin(async move { Ok(res) })
+            }
+            fn fetch_sockets(
+                &self,
+                _: BroadcastOptions,
+            ) -> Result<Vec<SocketRef<Self>>, Infallible> {
+                Ok(vec![])
+            }
+            fn add_sockets(
+                &self,
+                _: BroadcastOptions,
+                _: impl RoomParam,
+            ) -> Result<(), Infallible> {
+                Ok(())
+            }
+            fn del_sockets(
+                &self,
+                _: BroadcastOptions,
+                _: impl RoomParam,
+            ) -> Result<(), Infallible> {
+                Ok(())
+            }
+            fn disconnect_socket(&self, _: BroadcastOptions) -> Result<(), Vec<DisconnectError>> {
+                Ok(())
+            }
+            fn rooms(&self) -> Result<Vec<Room>, Infallible> {
+                Ok(vec![])
+            }
+            fn server_side_emit(&self, event: &str, data: Value) -> Result<(), Infallible> {
+                let nodes = NODES.lock().unwrap().clone();
+                for ns in nodes.iter().filter(|ns| !ns.ptr_eq(&self.0)) {
+                    if let Some(ns) = ns.upgrade() {
+                        ns.recv_server_event(event, data.clone());
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+
+    #[test]
+    fn server_emit_to_other_nodes() {
+        use std::sync::{Arc, Mutex};
+        let (_, io1) = SocketIo::builder()
+            .with_adapter::<mock::MockAdapter>()
+            .build_layer();
+        let (_, io2) = SocketIo::builder()
+            .with_adapter::<mock::MockAdapter>()
+            .build_layer();
+        io1.ns("/", || {});
+        io2.ns("/", || {});
+
+        let received = Arc::new(Mutex::new(Vec::new()));
+        for (name, io) in [("io1", &io1), ("io2", &io2)] {
+            let received = received.clone();
+            io.on_server_event("sync", move |Data::<Vec<u32>>(data)| {
+                received.lock().unwrap().push((name, data));
+            });
+        }
+        // Events with an invalid payload are ignored
+        io2.on_server_event("other", |Data::<u32>(_)| panic!("should not be called"));
+
+        io1.server_emit("sync", [1, 2, 3]).unwrap();
+        io1.server_emit("other", "invalid").unwrap();
+        io1.server_emit("unknown", ()).unwrap();
+        // The sender does not receive its own event
+        assert_eq!(*received.lock().unwrap(), vec![("io2", vec![1, 2, 3])]);
+
+        io2.server_emit("sync", [4]).unwrap();
+        assert_eq!(received.lock().unwrap()[1], ("io1", vec![4]));
+    }
+
+    #[test]
+    fn broadcast_adapter_error() {
+        let (_, io) = SocketIo::builder()
+            .with_adapter::<mock::MockAdapter>()
+            .build_layer();
+        io.ns("/", || {});
+
+        let err = io.to(mock::FAILING_ROOM).emit("test", "hello").unwrap_err();
+        assert!(matches!(&err, BroadcastError::Adapter(_)));
+        assert_eq!(err.to_string(), "Adapter error: node unreachable");
+        assert!(io.to("other").emit("test", "hello").is_ok());
+    }
+
+    #[tokio::test]
+    async fn to_sockets_local_and_remote() {
+        use engineioxide::Packet::*;
+        let (_, io1) = SocketIo::builder()
+            .with_adapter::<mock::MockAdapter>()
+            .build_layer();
+        let (_, io2) = SocketIo::builder()
+            .with_adapter::<mock::MockAdapter>()
+            .build_layer();
+        io1.ns("/", || {});
+        io2.ns("/", || {});
+
+        let (_stx1, mut srx1) = io1.new_dummy_sock("/", ()).await;
+        srx1.recv().await.unwrap(); // NS connect packet
+        let (_stx2, mut srx2) = io2.new_dummy_sock("/", ()).await;
+        srx2.recv().await.unwrap();
+        let (_stx3, mut srx3) = io1.new_dummy_sock("/", ()).await;
+        srx3.recv().await.unwrap();
+
+        let local = io1.0.get_ns("/").unwrap().get_sockets();
+        let local = local.iter().find(|s| s.id != local[0].id).unwrap().id;
+        let remote = io2.0.get_ns("/").unwrap().get_sockets()[0].id;
+
+        // A single adapter broadcast reaches the local and the remote sockets
+        io1.to_sockets([local, remote, Sid::new()])
+            .emit("test", "hello")
+            .unwrap();
+        let expected = Message("2[\"test\",\"hello\"]".into());
+        let timeout = Duration::from_millis(20);
+        let msg = tokio::time::timeout(timeout, srx2.recv()).await;
+        assert_eq!(msg.unwrap().unwrap(), expected);
+        // Only the selected local socket received the message
+        let mut received = Vec::new();
+        for srx in [&mut srx1, &mut srx3] {
+            if let Ok(Some(msg)) = tokio::time::timeout(timeout, srx.recv()).await {
+                received.push(msg);
+            }
+        }
+        assert_eq!(received, [expected]);
+    }
+
+    #[tokio::test]
+    async fn remote_socket_on_other_node() {
+        let (_, io1) = SocketIo::builder()
+            .with_adapter::<mock::MockAdapter>()
+            .build_layer();
+        let (_, io2) = SocketIo::builder()
+            .with_adapter::<mock::MockAdapter>()
+            .build_layer();
+        io1.ns("/", || {});
+        io2.ns("/", |socket: SocketRef<mock::MockAdapter>| {
+            socket.set_data(("john", 42)).unwrap();
+        });
+
+        let (_stx, mut srx) = io2.new_dummy_sock("/", ()).await;
+        srx.recv().await.unwrap(); // NS connect packet
+        let sid = io2.0.get_ns("/").unwrap().get_sockets()[0].id;
+
+        // The mock adapter does not track the rooms
+        let socket = io1.remote_socket(sid);
+        assert!(socket.rooms().await.unwrap().is_empty());
+        let data: Option<(String, u32)> = socket.data().await.unwrap();
+        assert_eq!(data, Some(("john".to_string(), 42)));
+        let res = socket.data::<u32>().await;
+        assert!(matches!(res, Err(RemoteSocketError::Decode(_))));
+
+        let unknown = Sid::new();
+        let res = io1.remote_socket(unknown).rooms().await;
+        assert!(matches!(res, Err(RemoteSocketError::SocketGone(sid)) if sid == unknown));
+    }
+
+    #[tokio::test]
+    async fn remote_socket_local_adapter() {
+        let (_, io) = SocketIo::new_svc();
+        io.ns("/", |socket: SocketRef| socket.join("room1").unwrap());
+
+        let (_stx, mut srx) = io.new_dummy_sock("/", ()).await;
+        srx.recv().await.unwrap(); // NS connect packet
+        let sid = io.0.get_ns("/").unwrap().get_sockets()[0].id;
+
+        let socket = io.remote_socket(sid);
+        assert_eq!(socket.rooms().await.unwrap(), vec!["room1"]);
+        assert_eq!(socket.data::<String>().await.unwrap(), None);
+        io.get_socket(sid).unwrap().set_data("john").unwrap();
+        assert_eq!(socket.data().await.unwrap(), Some("john".to_string()));
+    }
+
+    #[test]
+    fn server_emit_local_adapter() {
+        let (_, io) = SocketIo::new_svc();
+        io.ns("/", || {});
+        io.on_server_event("sync", |Data::<()>(_)| panic!("should not be called"));
+        io.server_emit("sync", ()).unwrap();
+    }
 }
@@ -124,6 +124,25 @@
 //! let (svc, io) = SocketIo::new_svc();
 //! ```
 //!
+//! #### Mounting the service under a sub-path:
+//! The service only handles the requests whose path starts with the [`req_path`](SocketIoBuilder#method.req_path),
+//! both for the polling requests and the websocket upgrades. When it is nested in an axum router with
+//! [`nest_service`](https://docs.rs/axum/latest/axum/struct.Router.html#method.nest_service), axum strips the prefix
+//! before calling it so the default `req_path` still matches and the clients connect with the `/rt/socket.io` path.
+//! With a router or a proxy that keeps the prefix, the full path must be set with the `req_path` option instead.
+//! ```no_run
+//! use socketioxide::SocketIo;
+//! # async fn doc_main() {
+//! let (svc, io) = SocketIo::new_svc();
+//! // The JS client connects with `io("http://localhost:3000", { path: "/rt/socket.io" })`
+//! let app: axum::Router = axum::Router::new().nest_service("/rt", svc);
+//!
+//! // Without nesting, the same client connects to a service with the full path
+//! let (svc, io) = SocketIo::builder().req_path("/rt/socket.io").build_svc();
+//! let app: axum::Router = axum::Router::new().fallback_service(svc);
+//! # }
+//! ```
+//!
 //! ## Handlers
 //! Handlers are functions or clonable closures that are given to the `io.ns`, the `socket.on` and the `socket.on_disconnect` fns.
 //! They can be async or sync and can take from 0 to 16 arguments that implements the [`FromConnectParts`](handler::FromConnectParts)
@@ -156,6 +175,7 @@
 //! * [`Bin`](extract::Bin): extract a binary payload for a given message. Because it consumes the event it should be the last argument
 //! * [`AckSender`](extract::AckSender): Can be used to send an ack response to the current message event
 //! * [`ProtocolVersion`]: extracts the protocol version of the socket
+//! * [`EIoProtocolVersion`]: extracts the engine.io protocol version of the socket
 //! * [`TransportType`]: extracts the transport type of the socket
 //! * [`DisconnectReason`](crate::socket::DisconnectReason): extracts the reason of the disconnection
 //! * [`State`](extract::State): extracts a [`Clone`] of a state previously set with [`SocketIoBuilder::with_state`](crate::io::SocketIoBuilder).
@@ -281,6 +301,10 @@
 //! * `tracing`: enable logging with [`tracing`] calls
 //! * `extensions`: enable per-socket state with the [`extensions`] module
 //! * `state`: enable global state management
+//! * `jwt`: enable the [`Jwt`](extract::Jwt) extractor to verify JWT tokens
+//! * `serve`: enable the built-in server with [`SocketIo::serve_with_graceful_shutdown`]
+//! * `debug-frames`: enable `SocketIo::on_raw_packet` to inspect the raw engine.io frames
+//! * `schema`: enable the validation of the outgoing payloads with the [`schema`] module
 //!
 pub mod adapter;
 
@@ -289,19 +313,46 @@ pub mod adapter;
 pub mod extensions;
 
 pub mod ack;
+pub mod binary_stream;
+pub mod clock;
+pub mod context;
 pub mod extract;
+pub mod group;
 pub mod handler;
 pub mod layer;
+pub mod lifecycle;
+pub mod migration;
 pub mod operators;
 pub mod packet;
+pub mod presence;
+pub mod readiness;
+pub mod remote;
+pub mod replay;
+pub mod room_meta;
+pub mod rpc;
+#[cfg_attr(docsrs, doc(cfg(feature = "schema")))]
+#[cfg(feature = "schema")]
+pub mod schema;
 pub mod service;
 pub mod socket;
+pub mod state_sync;
+pub mod typed;
 
-pub use engineioxide::TransportType;
+#[cfg(feature = "debug-frames")]
+#[cfg_attr(docsrs, doc(cfg(feature = "debug-frames")))]
+pub use engineioxide::frames::Direction;
+pub use engineioxide::{ProtocolVersion as EIoProtocolVersion, TransportType};
 pub use errors::{
-    AckError, AdapterError, BroadcastError, DisconnectError, NsInsertError, SendError, SocketError,
+    AckError, AdapterError, BroadcastError, DisconnectError, JoinError, NsInsertError,
+    RawSendError, RemoteSocketError, ResyncError, RoomMetaError, SchemaError, SendError,
+    SocketError, SwitchNamespaceError,
+};
+pub use io::{
+    AckTimeoutExtractor, DisconnectingEventPolicy, DuplicatePolicy, EarlyPacketPolicy,
+    EmptyAckShape, EventLimitsPolicy, FreezePolicy, HandlerPanicPolicy, MaxListenersPolicy,
+    ReconnectAttemptsExtractor, SocketIo, SocketIoBuilder, SocketIoConfig, UnknownEventPolicy,
+    UserIdExtractor,
 };
-pub use io::{SocketIo, SocketIoBuilder, SocketIoConfig};
 pub use value::{de::from_value, ser::to_value};
 
 mod client;
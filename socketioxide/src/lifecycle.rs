@@ -0,0 +1,156 @@
+//! Lifecycle events that can be collected with a sink registered with [`SocketIo::on_event`].
+//!
+//! It can be used for audit logging or to forward events to an analytics service
+//! without relying on the `tracing` feature.
+//!
+//! When no sink is registered, no event is built.
+//!
+//! The packets that could not be sent to a socket can also be collected as [`DropEvent`]s
+//! with a callback registered with [`SocketIo::on_drop`].
+//!
+//! #### Example
+//! ```
+//! # use socketioxide::{SocketIo, lifecycle::LifecycleEvent};
+//! let (_, io) = SocketIo::new_svc();
+//! io.on_event(|event: LifecycleEvent| match event {
+//!     LifecycleEvent::Connect { ns, sid } => println!("socket {sid} connected to {ns}"),
+//!     LifecycleEvent::Disconnect { ns, sid, reason } => {
+//!         println!("socket {sid} disconnected from {ns}: {reason:?}")
+//!     }
+//!     event => println!("{event:?}"),
+//! });
+//! ```
+//!
+//! [`SocketIo::on_event`]: crate::SocketIo#method.on_event
+//! [`SocketIo::on_drop`]: crate::SocketIo#method.on_drop
+use std::{
+    borrow::Cow,
+    sync::{Arc, RwLock},
+};
+
+use engineioxide::{sid::Sid, Str};
+
+use futures_util::future::BoxFuture;
+
+use crate::{adapter::Room, extract::SocketRef, socket::DisconnectReason, SocketError};
+
+/// A lifecycle event of the socket.io server.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum LifecycleEvent {
+    /// A socket connected to a namespace.
+    Connect {
+        /// The namespace path
+        ns: Str,
+        /// The socket id
+        sid: Sid,
+    },
+
+    /// A socket disconnected from a namespace.
+    Disconnect {
+        /// The namespace path
+        ns: Str,
+        /// The socket id
+        sid: Sid,
+        /// The reason of the disconnection
+        reason: DisconnectReason,
+    },
+
+    /// An event was emitted to a socket. When broadcasting, one event is produced for each socket.
+    Emit {
+        /// The namespace path
+        ns: Str,
+        /// The socket id
+        sid: Sid,
+        /// The event name
+        event: String,
+    },
+
+    /// A socket joined rooms with [`Socket::join`](crate::socket::Socket#method.join).
+    Join {
+        /// The namespace path
+        ns: Str,
+        /// The socket id
+        sid: Sid,
+        /// The joined rooms
+        rooms: Vec<Room>,
+    },
+
+    /// A socket left rooms with [`Socket::leave`](crate::socket::Socket#method.leave)
+    /// or [`Socket::leave_all`](crate::socket::Socket#method.leave_all), or because it disconnected.
+    Leave {
+        /// The namespace path
+        ns: Str,
+        /// The socket id
+        sid: Sid,
+        /// The left rooms
+        rooms: Vec<Room>,
+    },
+
+    /// An error occurred for a socket, when processing a packet or in a connect middleware.
+    Error {
+        /// The namespace path if it is known
+        ns: Option<Str>,
+        /// The socket id, or the engine.io session id if the namespace is unknown
+        /// (see [`Socket::session_id`](crate::socket::Socket::session_id))
+        sid: Sid,
+        /// The error message
+        message: String,
+    },
+}
+
+/// The reason why a packet was dropped, see [`DropEvent`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DropReason {
+    /// The packet buffer of the socket is full.
+    /// You might need to increase the buffer size with [`SocketIoBuilder::max_buffer_size`].
+    ///
+    /// [`SocketIoBuilder::max_buffer_size`]: crate::SocketIoBuilder#method.max_buffer_size
+    BufferFull,
+    /// The socket is disconnected or its underlying connection is closed.
+    SocketClosed,
+}
+
+impl<T> From<&SocketError<T>> for DropReason {
+    fn from(err: &SocketError<T>) -> Self {
+        match err {
+            SocketError::InternalChannelFull(_) => DropReason::BufferFull,
+            SocketError::Closed(_) => DropReason::SocketClosed,
+        }
+    }
+}
+
+/// A packet that could not be sent to a socket,
+/// collected with a callback registered with [`SocketIo::on_drop`].
+///
+/// [`SocketIo::on_drop`]: crate::SocketIo#method.on_drop
+#[derive(Debug, Clone, PartialEq)]
+pub struct DropEvent {
+    /// The namespace path
+    pub ns: Str,
+    /// The socket id
+    pub sid: Sid,
+    /// The reason why the packet was dropped
+    pub reason: DropReason,
+    /// The event name if the packet was an event
+    pub event: Option<String>,
+}
+
+pub(crate) type BoxedEventSink = Arc<dyn Fn(LifecycleEvent) + Send + Sync + 'static>;
+pub(crate) type BoxedDropSink = Arc<dyn Fn(DropEvent) + Send + Sync + 'static>;
+pub(crate) type BoxedJoinHook = Arc<dyn Fn(Sid, &Room) -> bool + Send + Sync + 'static>;
+pub(crate) type BoxedLeaveHook = Arc<dyn Fn(Sid, &Room) + Send + Sync + 'static>;
+pub(crate) type BoxedConnectedHook<A> =
+    Arc<dyn Fn(SocketRef<A>) -> BoxFuture<'static, ()> + Send + Sync + 'static>;
+pub(crate) type BoxedRoomHook = Arc<dyn Fn(&Room) + Send + Sync + 'static>;
+pub(crate) type BoxedEventRewriter =
+    Arc<dyn for<'a> Fn(&'a str) -> Option<Cow<'a, str>> + Send + Sync + 'static>;
+
+/// The hooks called when a room gets its first member or loses its last one,
+/// shared between the client and its namespaces.
+#[derive(Default)]
+pub(crate) struct RoomHooks {
+    pub created: RwLock<Option<BoxedRoomHook>>,
+    pub empty: RwLock<Option<BoxedRoomHook>>,
+}
@@ -0,0 +1,45 @@
+//! Handoff of the connections to another node of a cluster, e.g. to drain a node during a rolling deploy.
+//!
+//! [`Socket::request_reconnect`] emits a [`MIGRATE_EVENT`] to the client with a [`Migration`] payload,
+//! hinting the node it should reconnect to. Once the client acknowledges the event, the socket is disconnected
+//! from its namespace with the [`DisconnectReason::Migrating`] reason, so the users are moved to another node
+//! without being dropped abruptly.
+//!
+//! The client should acknowledge the event and reconnect when it is disconnected:
+//! ```js
+//! socket.on("server:migrate", ({ target }, ack) => {
+//!     migrating = target ?? true;
+//!     ack();
+//! });
+//! socket.on("disconnect", (reason) => {
+//!     if (migrating) socket.connect();
+//! });
+//! ```
+//!
+//! #### Example
+//! ```
+//! # use socketioxide::{SocketIo, extract::SocketRef};
+//! let (_, io) = SocketIo::new_svc();
+//! io.ns("/", |socket: SocketRef| {
+//!     socket.on("drain", |socket: SocketRef| async move {
+//!         if let Err(e) = socket.request_reconnect(Some("node-b".into())).await {
+//!             println!("the client did not acknowledge the migration: {e}");
+//!         }
+//!     });
+//! });
+//! ```
+//!
+//! [`Socket::request_reconnect`]: crate::socket::Socket#method.request_reconnect
+//! [`DisconnectReason::Migrating`]: crate::socket::DisconnectReason::Migrating
+use serde::{Deserialize, Serialize};
+
+/// The event emitted to a socket requested to reconnect, with a [`Migration`] payload.
+pub const MIGRATE_EVENT: &str = "server:migrate";
+
+/// The payload of the [`MIGRATE_EVENT`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Migration {
+    /// A hint of the node to reconnect to, e.g. its url or its name.
+    /// `None` to let the load balancer choose.
+    pub target: Option<String>,
+}
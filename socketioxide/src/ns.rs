@@ -1,17 +1,28 @@
 use std::{
-    collections::HashMap,
-    sync::{Arc, RwLock},
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex, OnceLock, RwLock},
+    time::Duration,
 };
 
 use crate::{
-    adapter::Adapter,
-    errors::{ConnectFail, Error},
+    adapter::{Adapter, BroadcastOptions, Room},
+    errors::{BroadcastError, ConnectFail, Error, SchemaError},
     handler::{BoxedConnectHandler, ConnectHandler, MakeErasedHandler},
+    lifecycle::{LifecycleEvent, RoomHooks},
     packet::{Packet, PacketData},
+    remote::{SocketRequest, SocketRequestKind, SocketResponse},
     socket::{DisconnectReason, Socket},
+    DisconnectingEventPolicy, DuplicatePolicy, FreezePolicy,
 };
 use crate::{client::SocketData, errors::AdapterError};
 use engineioxide::{sid::Sid, Str};
+use serde_json::Value;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// A handler for the server events sent by the other servers with
+/// [`SocketIo::server_emit`](crate::SocketIo#method.server_emit).
+pub(crate) type BoxedServerEventHandler = Arc<dyn Fn(Value) + Send + Sync + 'static>;
 
 /// A [`Namespace`] constructor used for dynamic namespaces
 /// A namespace constructor only hold a common handler that will be cloned
@@ -24,6 +35,44 @@ pub struct Namespace<A: Adapter> {
     pub(crate) adapter: A,
     handler: BoxedConnectHandler<A>,
     sockets: RwLock<HashMap<Sid, Arc<Socket<A>>>>,
+    /// The sockets of each user id, see [`SocketIoBuilder::user_id_from_auth`](crate::SocketIoBuilder#method.user_id_from_auth).
+    /// It is only updated with the `sockets` write lock held.
+    user_sids: Mutex<HashMap<String, Vec<Sid>>>,
+    /// The rooms whose presence is tracked, see [`Presence`](crate::presence::Presence)
+    pub(crate) presence_rooms: RwLock<HashSet<Room>>,
+    /// The rooms whose broadcasts are held, see [`SocketIo::freeze_room`](crate::SocketIo#method.freeze_room)
+    frozen_rooms: Mutex<HashMap<Room, FrozenRoom>>,
+    /// The documents of the rooms, see the [`state_sync`](crate::state_sync) module
+    pub(crate) room_states: Mutex<HashMap<Room, Value>>,
+    server_handlers: RwLock<HashMap<Cow<'static, str>, BoxedServerEventHandler>>,
+    /// Whether the events of each socket are handled one after the other,
+    /// see [`SocketIo::ordered_ns`](crate::SocketIo#method.ordered_ns)
+    pub(crate) ordered: bool,
+    /// The room hooks of the server, set when the namespace is added to the client
+    pub(crate) room_hooks: OnceLock<Arc<RoomHooks>>,
+    /// The limit of concurrent connects, see [`SocketIo::max_concurrent_connects`](crate::SocketIo#method.max_concurrent_connects)
+    connect_limit: RwLock<Option<ConnectLimit>>,
+    /// The behavior of the sockets receiving an event while disconnecting,
+    /// see [`SocketIo::disconnecting_event_policy`](crate::SocketIo#method.disconnecting_event_policy)
+    pub(crate) disconnecting_event_policy: RwLock<DisconnectingEventPolicy>,
+    /// The schemas of the outgoing events, set when the namespace is added to the client
+    #[cfg(feature = "schema")]
+    pub(crate) schemas: OnceLock<Arc<crate::schema::SchemaRegistry>>,
+}
+
+/// A frozen room and the broadcasts queued until it is unfrozen
+struct FrozenRoom {
+    policy: FreezePolicy,
+    queue: Vec<(Packet<'static>, BroadcastOptions)>,
+    /// Set while [`Namespace::unfreeze_room`] sends the queued broadcasts
+    flushing: bool,
+}
+
+/// The permits of the connections running their middlewares, and the maximum time to wait for one
+#[derive(Clone)]
+struct ConnectLimit {
+    semaphore: Arc<Semaphore>,
+    timeout: Duration,
 }
 
 /// ===== impl NamespaceCtr =====
@@ -42,6 +91,17 @@ impl<A: Adapter> NamespaceCtr<A> {
             path,
             handler: self.handler.boxed_clone(),
             sockets: HashMap::new().into(),
+            user_sids: HashMap::new().into(),
+            presence_rooms: HashSet::new().into(),
+            frozen_rooms: HashMap::new().into(),
+            room_states: HashMap::new().into(),
+            server_handlers: HashMap::new().into(),
+            ordered: false,
+            room_hooks: OnceLock::new(),
+            connect_limit: RwLock::new(None),
+            disconnecting_event_policy: RwLock::default(),
+            #[cfg(feature = "schema")]
+            schemas: OnceLock::new(),
             adapter: A::new(ns.clone()),
         })
     }
@@ -49,6 +109,23 @@ impl<A: Adapter> NamespaceCtr<A> {
 
 impl<A: Adapter> Namespace<A> {
     pub fn new<C, T>(path: Str, handler: C) -> Arc<Self>
+    where
+        C: ConnectHandler<A, T> + Send + Sync + 'static,
+        T: Send + Sync + 'static,
+    {
+        Self::new_inner(path, handler, false)
+    }
+
+    /// Creates a namespace whose socket events are handled one after the other
+    pub fn new_ordered<C, T>(path: Str, handler: C) -> Arc<Self>
+    where
+        C: ConnectHandler<A, T> + Send + Sync + 'static,
+        T: Send + Sync + 'static,
+    {
+        Self::new_inner(path, handler, true)
+    }
+
+    fn new_inner<C, T>(path: Str, handler: C, ordered: bool) -> Arc<Self>
     where
         C: ConnectHandler<A, T> + Send + Sync + 'static,
         T: Send + Sync + 'static,
@@ -57,10 +134,148 @@ impl<A: Adapter> Namespace<A> {
             path,
             handler: MakeErasedHandler::new_ns_boxed(handler),
             sockets: HashMap::new().into(),
+            user_sids: HashMap::new().into(),
+            presence_rooms: HashSet::new().into(),
+            frozen_rooms: HashMap::new().into(),
+            room_states: HashMap::new().into(),
+            server_handlers: HashMap::new().into(),
+            ordered,
+            room_hooks: OnceLock::new(),
+            connect_limit: RwLock::new(None),
+            disconnecting_event_policy: RwLock::default(),
+            #[cfg(feature = "schema")]
+            schemas: OnceLock::new(),
             adapter: A::new(ns.clone()),
         })
     }
 
+    /// Broadcasts a packet through the adapter, unless it selects a frozen room.
+    /// The lock is released before broadcasting, so the lifecycle sinks called
+    /// by the adapter can broadcast on this namespace.
+    pub(crate) fn broadcast(
+        &self,
+        packet: Packet<'static>,
+        opts: BroadcastOptions,
+    ) -> Result<(), BroadcastError> {
+        let held = Self::hold(&mut self.frozen_rooms.lock().unwrap(), packet, opts, None);
+        match held {
+            Some((packet, opts)) => self.adapter.broadcast(packet, opts),
+            None => Ok(()),
+        }
+    }
+
+    /// Fails with [`BroadcastError::FrozenRoom`] if the broadcast selects a frozen room.
+    /// It is used by the broadcasts that cannot be queued, such as the ones waiting for acks.
+    pub(crate) fn check_frozen(&self, opts: &BroadcastOptions) -> Result<(), BroadcastError> {
+        let frozen = self.frozen_rooms.lock().unwrap();
+        match Self::frozen_room(&frozen, opts, None) {
+            Some(room) => Err(BroadcastError::FrozenRoom(room.clone())),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns the first frozen room selected by the broadcast, with either `to` or `to_all_of`,
+    /// other than the `flushed` room.
+    fn frozen_room<'a>(
+        frozen: &HashMap<Room, FrozenRoom>,
+        opts: &'a BroadcastOptions,
+        flushed: Option<&str>,
+    ) -> Option<&'a Room> {
+        opts.rooms
+            .iter()
+            .chain(&opts.rooms_all)
+            .find(|room| Some(room.as_ref()) != flushed && frozen.contains_key(*room))
+    }
+
+    /// Queues or drops the broadcast if it selects a frozen room other than the `flushed` room,
+    /// otherwise gives it back.
+    ///
+    /// The broadcasts to a room being flushed are always queued, so that they are sent after the flushed ones.
+    fn hold(
+        frozen: &mut HashMap<Room, FrozenRoom>,
+        packet: Packet<'static>,
+        opts: BroadcastOptions,
+        flushed: Option<&str>,
+    ) -> Option<(Packet<'static>, BroadcastOptions)> {
+        let Some(room) = Self::frozen_room(frozen, &opts, flushed) else {
+            return Some((packet, opts));
+        };
+        let room = frozen.get_mut(room).unwrap();
+        match room.policy {
+            _ if room.flushing => room.queue.push((packet, opts)),
+            FreezePolicy::Queue => room.queue.push((packet, opts)),
+            FreezePolicy::Drop => {
+                #[cfg(feature = "tracing")]
+                tracing::debug!("dropping broadcast to frozen rooms {:?}", opts.rooms);
+            }
+        }
+        None
+    }
+
+    /// Freezes a room. A room being flushed by [`Namespace::unfreeze_room`] is frozen again
+    /// and keeps the broadcasts that are not flushed yet.
+    pub(crate) fn freeze_room(&self, room: Room, policy: FreezePolicy) {
+        self.frozen_rooms
+            .lock()
+            .unwrap()
+            .entry(room)
+            .or_insert_with(|| FrozenRoom {
+                policy,
+                queue: Vec::new(),
+                flushing: false,
+            })
+            .flushing = false;
+    }
+
+    /// Unfreezes a room and sends its queued broadcasts in order.
+    ///
+    /// The room is marked as flushing rather than removed while its queue is sent without the lock held:
+    /// the new broadcasts to the room are queued behind the flushed ones until the queue is empty.
+    pub(crate) fn unfreeze_room(&self, room: &str) -> usize {
+        let mut queue = {
+            let mut frozen = self.frozen_rooms.lock().unwrap();
+            match frozen.get_mut(room) {
+                Some(frozen) if !frozen.flushing => {
+                    frozen.flushing = true;
+                    std::mem::take(&mut frozen.queue)
+                }
+                _ => return 0,
+            }
+        };
+        let mut sent = 0;
+        loop {
+            for (packet, opts) in queue {
+                let held = Self::hold(
+                    &mut self.frozen_rooms.lock().unwrap(),
+                    packet,
+                    opts,
+                    Some(room),
+                );
+                let Some((packet, opts)) = held else {
+                    continue;
+                };
+                sent += 1;
+                if let Err(_e) = self.adapter.broadcast(packet, opts) {
+                    #[cfg(feature = "tracing")]
+                    tracing::debug!("broadcast error while unfreezing room: {_e:?}");
+                }
+            }
+            let mut frozen = self.frozen_rooms.lock().unwrap();
+            match frozen.get_mut(room) {
+                // The room was frozen again while flushing
+                Some(frozen) if !frozen.flushing => break,
+                Some(frozen) if !frozen.queue.is_empty() => {
+                    queue = std::mem::take(&mut frozen.queue)
+                }
+                _ => {
+                    frozen.remove(room);
+                    break;
+                }
+            }
+        }
+        sent
+    }
+
     /// Connects a socket to a namespace.
     ///
     /// Middlewares are first called to check if the connection is allowed.
@@ -74,12 +289,29 @@ impl<A: Adapter> Namespace<A> {
         auth: Option<String>,
     ) -> Result<(), ConnectFail> {
         let socket: Arc<Socket<A>> = Socket::new(sid, self.clone(), esocket.clone()).into();
+        socket.set_from_auth(&auth);
 
-        if let Err(e) = self.handler.call_middleware(socket.clone(), &auth).await {
+        // The permit is held until the socket is connected
+        let mut _permit = None;
+        let res = if !Self::wait_ready(&esocket).await {
+            Err(Box::new("server not ready") as Box<dyn std::fmt::Display + Send>)
+        } else if let Some(permit) = self.acquire_connect_permit().await {
+            _permit = permit;
+            let res = self.handler.call_middleware(socket.clone(), &auth).await;
+            res.and_then(|_| self.insert_socket(&socket, &esocket))
+        } else {
+            Err(Box::new("too many concurrent connections") as Box<dyn std::fmt::Display + Send>)
+        };
+        if let Err(e) = res {
             #[cfg(feature = "tracing")]
             tracing::trace!(ns = self.path.as_str(), ?socket.id, "emitting connect_error packet");
 
             let data = e.to_string();
+            socket.notify(|| LifecycleEvent::Error {
+                ns: Some(self.path.clone()),
+                sid,
+                message: data.clone(),
+            });
             if let Err(_e) = socket.send(Packet::connect_error(self.path.clone(), &data)) {
                 #[cfg(feature = "tracing")]
                 tracing::debug!("error sending connect_error packet: {:?}, closing conn", _e);
@@ -88,7 +320,12 @@ impl<A: Adapter> Namespace<A> {
             return Err(ConnectFail);
         }
 
-        self.sockets.write().unwrap().insert(sid, socket.clone());
+        esocket
+            .data
+            .ns_sids
+            .lock()
+            .unwrap()
+            .insert(Cow::Owned(self.path.to_string()), sid);
         #[cfg(feature = "tracing")]
         tracing::trace!(?socket.id, ?self.path, "socket added to namespace");
 
@@ -102,31 +339,271 @@ impl<A: Adapter> Namespace<A> {
         }
 
         socket.set_connected(true);
-        self.handler.call(socket, auth);
+        socket.notify(|| LifecycleEvent::Connect {
+            ns: self.path.clone(),
+            sid,
+        });
+        self.join_default_rooms(&socket, &esocket);
+        socket.spawn_idle_reaper();
+        if let Some(interval) = esocket.data.io.get().and_then(|io| io.config().time_sync) {
+            socket.sync_time(interval);
+        }
+        self.handler.call(socket.clone(), auth);
+        Self::spawn_connected_hook(socket, &esocket);
 
         Ok(())
     }
 
+    /// Calls the [`SocketIo::on_connected`](crate::SocketIo#method.on_connected) hook, if any,
+    /// once the connect packet has been flushed by the transport.
+    fn spawn_connected_hook(socket: Arc<Socket<A>>, esocket: &engineioxide::Socket<SocketData<A>>) {
+        let Some(hook) = esocket.data.io.get().and_then(|io| io.connected_hook()) else {
+            return;
+        };
+        tokio::spawn(async move {
+            if socket.flushed().await && socket.connected() {
+                hook(socket.into()).await;
+            }
+        });
+    }
+
+    /// Adds the socket to the namespace, applying the [`DuplicatePolicy`] to the sockets
+    /// sharing its user id. The policy is checked under the same lock as the insertion
+    /// so that concurrent connections of the same user cannot both be accepted.
+    /// Returns an error if the connection should be rejected.
+    fn insert_socket(
+        &self,
+        socket: &Arc<Socket<A>>,
+        esocket: &engineioxide::Socket<SocketData<A>>,
+    ) -> Result<(), Box<dyn std::fmt::Display + Send>> {
+        let policy = esocket
+            .data
+            .io
+            .get()
+            .map(|io| io.config().duplicate_policy)
+            .unwrap_or(DuplicatePolicy::AllowMultiple);
+        let duplicates = {
+            let mut sockets = self.sockets.write().unwrap();
+            let duplicates = match socket.user_id() {
+                Some(user_id) => {
+                    let mut user_sids = self.user_sids.lock().unwrap();
+                    let sids = user_sids.entry(user_id.to_string()).or_default();
+                    if policy == DuplicatePolicy::RejectNew && !sids.is_empty() {
+                        return Err(Box::new("user already connected"));
+                    }
+                    let duplicates = match policy {
+                        DuplicatePolicy::KickOld => std::mem::take(sids),
+                        _ => Vec::new(),
+                    };
+                    sids.push(socket.id);
+                    duplicates
+                }
+                None => Vec::new(),
+            };
+            sockets.insert(socket.id, socket.clone());
+            duplicates
+                .into_iter()
+                .filter_map(|sid| sockets.get(&sid).cloned())
+                .collect::<Vec<_>>()
+        };
+        for old in duplicates {
+            #[cfg(feature = "tracing")]
+            tracing::debug!(?old.id, "disconnecting duplicate socket of user {:?}", socket.user_id());
+            if let Err(_e) = old.disconnect() {
+                #[cfg(feature = "tracing")]
+                tracing::debug!("error disconnecting duplicate socket: {_e}");
+            }
+        }
+        Ok(())
+    }
+
+    /// Holds the connection until the [`ReadinessGate`](crate::readiness::ReadinessGate) is open, if any.
+    /// Returns false if the gate is still closed after the configured timeout.
+    async fn wait_ready(esocket: &engineioxide::Socket<SocketData<A>>) -> bool {
+        let Some(io) = esocket.data.io.get() else {
+            return true;
+        };
+        match &io.config().readiness_gate {
+            Some(gate) => gate.wait(io.config().readiness_timeout).await,
+            None => true,
+        }
+    }
+
+    /// Sets the limit of concurrent connects, see [`SocketIo::max_concurrent_connects`](crate::SocketIo#method.max_concurrent_connects).
+    pub(crate) fn set_connect_limit(&self, max: usize, timeout: Duration) {
+        let limit = ConnectLimit {
+            semaphore: Arc::new(Semaphore::new(max)),
+            timeout,
+        };
+        self.connect_limit.write().unwrap().replace(limit);
+    }
+
+    /// Waits for a connect permit if the concurrent connects are limited.
+    /// Returns `None` if no permit could be acquired before the timeout.
+    async fn acquire_connect_permit(&self) -> Option<Option<OwnedSemaphorePermit>> {
+        let Some(limit) = self.connect_limit.read().unwrap().clone() else {
+            return Some(None);
+        };
+        let permit = limit.semaphore.acquire_owned();
+        match tokio::time::timeout(limit.timeout, permit).await {
+            Ok(Ok(permit)) => Some(Some(permit)),
+            Ok(Err(_)) | Err(_) => None,
+        }
+    }
+
+    /// Joins the [`SocketIoConfig::default_rooms`](crate::SocketIoConfig::default_rooms), if any,
+    /// and the sid room if [`SocketIoConfig::auto_sid_room`](crate::SocketIoConfig::auto_sid_room) is enabled.
+    fn join_default_rooms(
+        &self,
+        socket: &Socket<A>,
+        esocket: &engineioxide::Socket<SocketData<A>>,
+    ) {
+        let Some(io) = esocket.data.io.get() else {
+            return;
+        };
+        let config = io.config();
+        let mut rooms = config.default_rooms.clone();
+        if config.auto_sid_room {
+            rooms.push(socket.id.to_string().into());
+        }
+        if rooms.is_empty() {
+            return;
+        }
+        if let Err(e) = socket.join(rooms) {
+            #[cfg(feature = "tracing")]
+            tracing::debug!(?socket.id, "error joining the default rooms: {e}");
+            socket.notify(|| LifecycleEvent::Error {
+                ns: Some(self.path.clone()),
+                sid: socket.id,
+                message: e.to_string(),
+            });
+        }
+    }
+
     /// Removes a socket from a namespace and propagate the event to the adapter
     pub fn remove_socket(&self, sid: Sid) -> Result<(), AdapterError> {
         #[cfg(feature = "tracing")]
         tracing::trace!(?sid, "removing socket from namespace");
 
-        self.sockets.write().unwrap().remove(&sid);
+        {
+            let mut sockets = self.sockets.write().unwrap();
+            let user_id = sockets
+                .remove(&sid)
+                .and_then(|s| s.user_id().map(str::to_string));
+            if let Some(user_id) = user_id {
+                let mut user_sids = self.user_sids.lock().unwrap();
+                if let Some(sids) = user_sids.get_mut(&user_id) {
+                    sids.retain(|s| *s != sid);
+                    if sids.is_empty() {
+                        user_sids.remove(&user_id);
+                    }
+                }
+            }
+        }
         self.adapter
             .del_all(sid)
+            .and_then(|_| self.adapter.del_meta(sid))
             .map_err(|err| AdapterError(Box::new(err)))
     }
 
+    /// Returns the given rooms that have their presence tracked
+    pub(crate) fn tracked_rooms<'a>(&self, rooms: impl IntoIterator<Item = &'a Room>) -> Vec<Room> {
+        let tracked = self.presence_rooms.read().unwrap();
+        if tracked.is_empty() {
+            return Vec::new();
+        }
+        rooms
+            .into_iter()
+            .filter(|room| tracked.contains(*room))
+            .cloned()
+            .collect()
+    }
+
+    /// Registers a handler for the given server event, replacing the previous one.
+    pub(crate) fn on_server_event(
+        &self,
+        event: Cow<'static, str>,
+        handler: BoxedServerEventHandler,
+    ) {
+        self.server_handlers.write().unwrap().insert(event, handler);
+    }
+
+    /// Validates the data of an outgoing event against the schema registered for it,
+    /// see the [`schema`](crate::schema) module. Nothing is checked without the `schema` feature.
+    pub(crate) fn check_schema(&self, event: &str, data: &Value) -> Result<(), SchemaError> {
+        #[cfg(feature = "schema")]
+        if let Some(schemas) = self.schemas.get() {
+            schemas.check(event, data)?;
+        }
+        #[cfg(not(feature = "schema"))]
+        let _ = (event, data);
+        Ok(())
+    }
+
+    /// Calls the hook registered with [`SocketIo::on_room_created`](crate::SocketIo#method.on_room_created).
+    /// The adapter should call it when a room gets its first member.
+    pub fn notify_room_created(&self, room: &Room) {
+        let hook = self
+            .room_hooks
+            .get()
+            .and_then(|h| h.created.read().unwrap().clone());
+        if let Some(hook) = hook {
+            hook(room);
+        }
+    }
+
+    /// Calls the hook registered with [`SocketIo::on_room_empty`](crate::SocketIo#method.on_room_empty).
+    /// The adapter should call it when the last member of a room leaves it.
+    pub fn notify_room_empty(&self, room: &Room) {
+        let hook = self
+            .room_hooks
+            .get()
+            .and_then(|h| h.empty.read().unwrap().clone());
+        if let Some(hook) = hook {
+            hook(room);
+        }
+    }
+
+    /// Called by the adapter when a server event is received from another server.
+    /// It calls the handler registered for this event if there is one.
+    pub fn recv_server_event(&self, event: &str, data: Value) {
+        let handler = self.server_handlers.read().unwrap().get(event).cloned();
+        if let Some(handler) = handler {
+            handler(data);
+        } else {
+            #[cfg(feature = "tracing")]
+            tracing::debug!(?self.path, "no handler for server event {event}");
+        }
+    }
+
+    /// Called by the adapter when a [`SocketRequest`] is received from another server.
+    /// Returns the response to send back, or `None` if the socket is not connected to this server.
+    pub fn recv_socket_request(&self, req: &SocketRequest) -> Option<SocketResponse> {
+        let socket = self.get_socket(req.sid).ok()?;
+        let res = match req.kind {
+            SocketRequestKind::Rooms => SocketResponse::Rooms(socket.rooms().ok()?),
+            SocketRequestKind::Data => SocketResponse::Data(socket.raw_data()),
+        };
+        Some(res)
+    }
+
     pub fn has(&self, sid: Sid) -> bool {
         self.sockets.read().unwrap().values().any(|s| s.id == sid)
     }
 
-    pub fn recv(&self, sid: Sid, packet: PacketData<'_>) -> Result<(), Error> {
+    /// Dispatches a packet of the given encoded size, with its binary attachments, to a socket.
+    pub fn recv(&self, sid: Sid, packet: PacketData<'_>, size: usize) -> Result<(), Error> {
         match packet {
             PacketData::Connect(_) => unreachable!("connect packets should be handled before"),
             PacketData::ConnectError(_) => Err(Error::InvalidPacketType),
-            packet => self.get_socket(sid)?.recv(packet),
+            packet => {
+                let socket = self.get_socket(sid)?;
+                if !socket.record_recv(&packet, size) {
+                    socket.quota_exceeded();
+                    return Ok(());
+                }
+                socket.recv(packet)
+            }
         }
     }
 
@@ -143,13 +620,19 @@ impl<A: Adapter> Namespace<A> {
         self.sockets.read().unwrap().values().cloned().collect()
     }
 
+    /// Gets the ids of all the sockets of the namespace.
+    pub fn get_sids(&self) -> Vec<Sid> {
+        self.sockets.read().unwrap().keys().copied().collect()
+    }
+
     /// Closes the entire namespace :
     /// * Closes the adapter
     /// * Closes all the sockets and
     /// their underlying connections in case of [`DisconnectReason::ClosingServer`]
+    /// or [`DisconnectReason::ServerShutdown`]
     /// * Removes all the sockets from the namespace
     ///
-    /// This function is using .await points only when called with [`DisconnectReason::ClosingServer`]
+    /// This function is using .await points only when the server is closing
     pub async fn close(&self, reason: DisconnectReason) {
         use futures_util::future;
         let sockets = self.sockets.read().unwrap().clone();
@@ -157,18 +640,22 @@ impl<A: Adapter> Namespace<A> {
         #[cfg(feature = "tracing")]
         tracing::debug!(?self.path, "closing {} sockets in namespace", sockets.len());
 
-        if reason == DisconnectReason::ClosingServer {
-            // When closing the underlying transport, this will indirectly close the socket
-            // Therefore there is no need to manually call `s.close()`.
+        if matches!(
+            reason,
+            DisconnectReason::ClosingServer | DisconnectReason::ServerShutdown
+        ) {
             future::join_all(sockets.values().map(|s| s.close_underlying_transport())).await;
-        } else {
-            for s in sockets.into_values() {
-                let _sid = s.id;
-                let _err = s.close(reason);
-                #[cfg(feature = "tracing")]
-                if let Err(err) = _err {
-                    tracing::debug!(?_sid, ?err, "error closing socket");
-                }
+        }
+        // Closing the underlying transport closes the socket only if the namespace is still
+        // reachable from the client. When the whole server is closing, it is not the case anymore
+        // so the remaining sockets are closed here.
+        let sockets = self.sockets.read().unwrap().clone();
+        for s in sockets.into_values() {
+            let _sid = s.id;
+            let _err = s.close(reason);
+            #[cfg(feature = "tracing")]
+            if let Err(err) = _err {
+                tracing::debug!(?_sid, ?err, "error closing socket");
             }
         }
         #[cfg(feature = "tracing")]
@@ -6,18 +6,31 @@
 //! There is two types of operators:
 //! * [`ConfOperators`]: Chainable operators to configure the message to be sent.
 //! * [`BroadcastOperators`]: Chainable operators to select sockets to send a message to and to configure the message to be sent.
+//!
+//! The terminal methods never panic, their failures are returned as typed errors:
+//! * The [`ConfOperators`] methods emitting to a single socket return a [`SendError`],
+//!   with the serialization errors and the errors of the socket.
+//! * The [`BroadcastOperators`] methods return a [`BroadcastError`], with the serialization errors,
+//!   the errors of the adapter, the errors of the recipient sockets,
+//!   and the [contradictory operators](BroadcastError::ContradictoryOperators) selecting no socket.
 use std::borrow::Cow;
 use std::marker::PhantomData;
 use std::{sync::Arc, time::Duration};
 
 use bytes::Bytes;
 use engineioxide::sid::Sid;
+use futures_util::future;
 use serde_json::Value;
 
 use crate::ack::{AckInnerStream, AckStream};
 use crate::adapter::LocalAdapter;
-use crate::errors::{BroadcastError, DisconnectError};
+#[cfg(feature = "extensions")]
+use crate::adapter::SocketFilter;
+use crate::context::{SerializeWithContext, WithContext};
+use crate::errors::{BroadcastError, DisconnectError, JoinError, SchemaError};
 use crate::extract::SocketRef;
+use crate::lifecycle::DropReason;
+use crate::remote::RemoteSocket;
 use crate::socket::Socket;
 use crate::SendError;
 use crate::{
@@ -120,9 +133,57 @@ impl RoomParam for Sid {
 pub struct ConfOperators<'a, BH, A: Adapter = LocalAdapter> {
     binary: Vec<Bytes>,
     timeout: Option<Duration>,
+    retries: u32,
+    retry_delay: Duration,
     socket: &'a Socket<A>,
     _phantom: PhantomData<BH>,
 }
+/// The report of a [`BroadcastOperators::emit_flush`] call.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FlushReport {
+    /// The local sockets whose transport flushed the packet.
+    pub flushed: Vec<Sid>,
+    /// The local sockets closed before their transport flushed the packet.
+    pub closed: Vec<Sid>,
+    /// The number of other servers the packet was forwarded to.
+    /// Their recipients are excluded from the flush guarantee.
+    pub remote_servers: u16,
+}
+
+/// An event whose data is serialized once, to be emitted with [`BroadcastOperators::emit_prepared`]
+/// to several rooms or namespaces without serializing it again.
+///
+/// The binary attachments are shared buffers, so they are not copied for each emit.
+/// The packet is still encoded to the socket.io format for each recipient.
+#[derive(Debug, Clone)]
+pub struct PreparedPacket {
+    event: Cow<'static, str>,
+    data: Value,
+    bins: Vec<Bytes>,
+}
+
+impl PreparedPacket {
+    /// Serializes the data of the event, extracting its binary attachments.
+    ///
+    /// See [`SocketIo::prepare`](crate::SocketIo#method.prepare) for an example.
+    pub fn new<T: serde::Serialize>(
+        event: impl Into<Cow<'static, str>>,
+        data: T,
+    ) -> Result<Self, serde_json::Error> {
+        let (data, bins) = crate::to_value(data)?;
+        Ok(Self {
+            event: event.into(),
+            data,
+            bins,
+        })
+    }
+
+    /// The name of the event.
+    pub fn event(&self) -> &str {
+        &self.event
+    }
+}
+
 /// Chainable operators to select sockets to send a message to and to configure the message to be sent.
 pub struct BroadcastOperators<BH, A: Adapter = LocalAdapter> {
     binary: Vec<Bytes>,
@@ -153,6 +214,8 @@ impl<'a, A: Adapter> ConfOperators<'a, WithoutBinary, A> {
         Self {
             binary: vec![],
             timeout: None,
+            retries: 0,
+            retry_delay: Duration::ZERO,
             socket: sender,
             _phantom: PhantomData::<WithoutBinary>,
         }
@@ -232,8 +295,51 @@ impl<'a, BH: BinaryHolding, A: Adapter> ConfOperators<'a, BH, A> {
         BroadcastOperators::from(self).except(rooms)
     }
 
-    /// Broadcasts to all sockets only connected on this node (when using multiple nodes).
-    /// When using the default in-memory adapter, this operator is a no-op.
+    /// Selects all sockets that are in **any** of the given rooms (union) except the current socket.
+    ///
+    /// This is the same as the `to()` operator.
+    /// #### Example
+    /// ```
+    /// # use socketioxide::{SocketIo, extract::*};
+    /// # use serde_json::Value;
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.ns("/", |socket: SocketRef| {
+    ///     socket.on("test", |socket: SocketRef, Data::<Value>(data)| async move {
+    ///         // In region:eu or in plan:pro except the current socket
+    ///         socket
+    ///             .timeout(std::time::Duration::from_secs(5))
+    ///             .to_any_of(["region:eu", "plan:pro"])
+    ///             .emit("test", data);
+    ///     });
+    /// });
+    pub fn to_any_of(self, rooms: impl RoomParam) -> BroadcastOperators<BH, A> {
+        BroadcastOperators::from(self).to_any_of(rooms)
+    }
+
+    /// Selects all sockets that are in **all** the given rooms (intersection) except the current socket.
+    ///
+    /// See [`BroadcastOperators::to_all_of`] for more details.
+    /// #### Example
+    /// ```
+    /// # use socketioxide::{SocketIo, extract::*};
+    /// # use serde_json::Value;
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.ns("/", |socket: SocketRef| {
+    ///     socket.on("test", |socket: SocketRef, Data::<Value>(data)| async move {
+    ///         // In both region:eu and plan:pro except the current socket
+    ///         socket
+    ///             .timeout(std::time::Duration::from_secs(5))
+    ///             .to_all_of(["region:eu", "plan:pro"])
+    ///             .emit("test", data);
+    ///     });
+    /// });
+    pub fn to_all_of(self, rooms: impl RoomParam) -> BroadcastOperators<BH, A> {
+        BroadcastOperators::from(self).to_all_of(rooms)
+    }
+
+    /// Broadcasts to all sockets only connected on this node (when using multiple nodes),
+    /// except the current socket.
+    /// When using the default in-memory adapter, this operator is the same as `broadcast()`.
     /// #### Example
     /// ```
     /// # use socketioxide::{SocketIo, extract::*};
@@ -246,7 +352,7 @@ impl<'a, BH: BinaryHolding, A: Adapter> ConfOperators<'a, BH, A> {
     ///     });
     /// });
     pub fn local(self) -> BroadcastOperators<BH, A> {
-        BroadcastOperators::from(self).local()
+        BroadcastOperators::from(self).broadcast().local()
     }
 
     /// Broadcasts to all sockets without any filtering (except the current socket).
@@ -265,6 +371,64 @@ impl<'a, BH: BinaryHolding, A: Adapter> ConfOperators<'a, BH, A> {
         BroadcastOperators::from(self).broadcast()
     }
 
+    /// Broadcasts to all sockets matching a predicate over their [`Extensions`](crate::extensions::Extensions)
+    /// (except the current socket).
+    ///
+    /// See [`BroadcastOperators::filter`] for more details.
+    /// #### Example
+    /// ```
+    /// # use socketioxide::{SocketIo, extract::*};
+    /// # use serde_json::Value;
+    /// #[derive(Clone, PartialEq)]
+    /// enum Role { Admin, User }
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.ns("/", |socket: SocketRef| {
+    ///     socket.extensions.insert(Role::User);
+    ///     socket.on("test", |socket: SocketRef, Data::<Value>(data)| async move {
+    ///         // This message will be broadcast to all the admins of this namespace
+    ///         socket
+    ///             .timeout(std::time::Duration::from_secs(5))
+    ///             .filter(|ext| ext.get::<Role>() == Some(Role::Admin))
+    ///             .emit("test", data);
+    ///     });
+    /// });
+    #[cfg_attr(docsrs, doc(cfg(feature = "extensions")))]
+    #[cfg(feature = "extensions")]
+    pub fn filter<F>(self, predicate: F) -> BroadcastOperators<BH, A>
+    where
+        F: Fn(&crate::extensions::Extensions) -> bool + Send + Sync + 'static,
+    {
+        BroadcastOperators::from(self).broadcast().filter(predicate)
+    }
+
+    /// Broadcasts to all sockets with the metadata `key` set to `value` (except the current socket).
+    ///
+    /// See [`BroadcastOperators::where_meta`] for more details.
+    /// #### Example
+    /// ```
+    /// # use socketioxide::{SocketIo, extract::*};
+    /// # use serde_json::Value;
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.ns("/", |socket: SocketRef| {
+    ///     socket.set_meta("region", "eu").unwrap();
+    ///     socket.on("test", |socket: SocketRef, Data::<Value>(data)| async move {
+    ///         // This message will be broadcast to all the sockets of the eu region
+    ///         socket
+    ///             .timeout(std::time::Duration::from_secs(5))
+    ///             .where_meta("region", "eu")
+    ///             .emit("test", data);
+    ///     });
+    /// });
+    pub fn where_meta(
+        self,
+        key: impl Into<String>,
+        value: impl Into<String>,
+    ) -> BroadcastOperators<BH, A> {
+        BroadcastOperators::from(self)
+            .broadcast()
+            .where_meta(key, value)
+    }
+
     /// Sets a custom timeout when sending a message with an acknowledgement.
     ///
     /// See [`SocketIoBuilder::ack_timeout`](crate::SocketIoBuilder) for the default timeout.
@@ -304,6 +468,54 @@ impl<'a, BH: BinaryHolding, A: Adapter> ConfOperators<'a, BH, A> {
         self.timeout = Some(timeout);
         self
     }
+
+    /// Re-emits the message up to `retries` times if the client doesn't acknowledge it before the timeout.
+    ///
+    /// Each attempt waits for the acknowledgement during the [`timeout()`], then the message is sent again
+    /// after the [`retry_delay()`] with a new ack id. The first acknowledgement received ends the retries.
+    /// An [`AckError::Timeout`] is only yielded once all the attempts timed out.
+    ///
+    /// With the [packet ids](crate::SocketIoBuilder#method.packet_ids) enabled, all the attempts
+    /// carry the same packet id so the client can ignore the duplicates.
+    ///
+    /// Retries only apply to [`emit_with_ack()`], the other emits are sent once.
+    ///
+    /// [`timeout()`]: #method.timeout
+    /// [`retry_delay()`]: #method.retry_delay
+    /// [`emit_with_ack()`]: #method.emit_with_ack
+    /// [`AckError::Timeout`]: crate::AckError::Timeout
+    ///
+    /// # Example
+    /// ```
+    /// # use socketioxide::{SocketIo, extract::*};
+    /// # use serde_json::Value;
+    /// # use std::time::Duration;
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.ns("/", |socket: SocketRef| async move {
+    ///     // Try 3 times in total, waiting 500ms between each attempt
+    ///     let ack = socket
+    ///         .timeout(Duration::from_secs(2))
+    ///         .retries(2)
+    ///         .retry_delay(Duration::from_millis(500))
+    ///         .emit_with_ack::<_, Value>("order", "ping")
+    ///         .unwrap()
+    ///         .await;
+    /// });
+    /// ```
+    pub fn retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Sets the delay before re-emitting a message that was not acknowledged, see [`retries()`].
+    ///
+    /// Defaults to no delay.
+    ///
+    /// [`retries()`]: #method.retries
+    pub fn retry_delay(mut self, delay: Duration) -> Self {
+        self.retry_delay = delay;
+        self
+    }
 }
 
 // ==== impl ConfOperators consume fns for any BinaryHolding state ====
@@ -320,12 +532,17 @@ impl<BH: BinaryHolding, A: Adapter> ConfOperators<'_, BH, A> {
     ///     socket.within("room1").within("room3").join(["room4", "room5"]).unwrap();
     ///   });
     /// });
-    pub fn join(self, rooms: impl RoomParam) -> Result<(), A::Error> {
+    pub fn join(self, rooms: impl RoomParam) -> Result<(), JoinError<A::Error>> {
         self.socket.join(rooms)
     }
 
     /// Makes all sockets selected with the previous operators leave the given room(s).
     ///
+    /// Each selected socket leaves the rooms with [`Socket::leave`], so the presence events,
+    /// the lifecycle events and the leave hook are sent as for a single socket.
+    ///
+    /// [`Socket::leave`]: crate::socket::Socket#method.leave
+    ///
     /// ### Example
     /// ```
     /// # use socketioxide::{SocketIo, extract::*};
@@ -367,6 +584,8 @@ impl<'a, A: Adapter> ConfOperators<'a, WithoutBinary, A> {
         ConfOperators {
             binary: binary.into_iter().map(Into::into).collect(),
             timeout: self.timeout,
+            retries: self.retries,
+            retry_delay: self.retry_delay,
             socket: self.socket,
             _phantom: PhantomData::<WithBinary>,
         }
@@ -425,6 +644,26 @@ impl<A: Adapter> ConfOperators<'_, WithoutBinary, A> {
         self.bin(bins).emit(event, data)
     }
 
+    /// Emits a message to the client and apply the previous operators on the message,
+    /// waiting for space in the packet buffer if it is full.
+    ///
+    /// See [`Socket::emit_wait`](crate::socket::Socket#method.emit_wait) for more details on backpressure.
+    ///
+    /// ## Errors
+    /// * When encoding the data into JSON a [`SendError::Serialize`] may be returned.
+    /// * If the underlying engine.io connection is closed a [`SendError::Socket(SocketError::Closed)`]
+    /// will be returned and the provided data to be send will be given back in the error.
+    ///
+    /// [`SendError::Socket(SocketError::Closed)`]: crate::SocketError::Closed
+    pub async fn emit_wait<T: serde::Serialize>(
+        self,
+        event: impl Into<Cow<'static, str>>,
+        data: T,
+    ) -> Result<(), SendError<Value>> {
+        let (data, bins) = crate::to_value(data)?;
+        self.bin(bins).emit_wait(event, data).await
+    }
+
     /// Emits a message to the client and wait for acknowledgement.
     ///
     /// The acknowledgement has a timeout specified in the config (5s by default)
@@ -535,7 +774,10 @@ impl<A: Adapter> ConfOperators<'_, WithBinary, A> {
     ) -> Result<(), SendError<Value>> {
         use crate::errors::SocketError;
         use crate::socket::PermitExt;
+        let event = event.into();
         if !self.socket.connected() {
+            self.socket
+                .notify_drop(DropReason::SocketClosed, Some(&event));
             return Err(SendError::Socket(SocketError::Closed(data)));
         }
         let permit = match self.socket.reserve() {
@@ -543,11 +785,54 @@ impl<A: Adapter> ConfOperators<'_, WithBinary, A> {
             Err(e) => {
                 #[cfg(feature = "tracing")]
                 tracing::debug!("sending error during emit message: {e:?}");
+                self.socket.notify_drop((&e).into(), Some(&event));
                 return Err(e.with_value(data).into());
             }
         };
-        let packet = self.get_packet(event, data)?;
-        permit.send(packet);
+        let mut packet = self.get_packet(event, data)?;
+        self.socket.tag_packet(&mut packet);
+        self.socket.notify_emit(&packet);
+        self.socket.record_bytes_out(permit.send(packet));
+
+        Ok(())
+    }
+
+    /// Emits a message to the client and apply the previous operators on the message,
+    /// waiting for space in the packet buffer if it is full.
+    ///
+    /// See [`Socket::emit_wait`](crate::socket::Socket#method.emit_wait) for more details on backpressure.
+    ///
+    /// ## Errors
+    /// * If the underlying engine.io connection is closed a [`SendError::Socket(SocketError::Closed)`]
+    /// will be returned and the provided data to be send will be given back in the error.
+    ///
+    /// [`SendError::Socket(SocketError::Closed)`]: crate::SocketError::Closed
+    pub async fn emit_wait(
+        mut self,
+        event: impl Into<Cow<'static, str>>,
+        data: Value,
+    ) -> Result<(), SendError<Value>> {
+        use crate::errors::SocketError;
+        use crate::socket::PermitExt;
+        let event = event.into();
+        if !self.socket.connected() {
+            self.socket
+                .notify_drop(DropReason::SocketClosed, Some(&event));
+            return Err(SendError::Socket(SocketError::Closed(data)));
+        }
+        let permit = match self.socket.reserve_wait().await {
+            Ok(permit) => permit,
+            Err(e) => {
+                #[cfg(feature = "tracing")]
+                tracing::debug!("sending error during emit message: {e:?}");
+                self.socket.notify_drop((&e).into(), Some(&event));
+                return Err(e.with_value(data).into());
+            }
+        };
+        let mut packet = self.get_packet(event, data)?;
+        self.socket.tag_packet(&mut packet);
+        self.socket.notify_emit(&packet);
+        self.socket.record_bytes_out(permit.send(packet));
 
         Ok(())
     }
@@ -607,7 +892,10 @@ impl<A: Adapter> ConfOperators<'_, WithBinary, A> {
         data: Value,
     ) -> Result<AckStream<V>, SendError<Value>> {
         use crate::errors::SocketError;
+        let event = event.into();
         if !self.socket.connected() {
+            self.socket
+                .notify_drop(DropReason::SocketClosed, Some(&event));
             return Err(SendError::Socket(SocketError::Closed(data)));
         }
         let permit = match self.socket.reserve() {
@@ -615,16 +903,28 @@ impl<A: Adapter> ConfOperators<'_, WithBinary, A> {
             Err(e) => {
                 #[cfg(feature = "tracing")]
                 tracing::debug!("sending error during emit message: {e:?}");
+                self.socket.notify_drop((&e).into(), Some(&event));
                 return Err(e.with_value(data).into());
             }
         };
-        let timeout = self
-            .timeout
-            .unwrap_or_else(|| self.socket.get_io().config().ack_timeout);
+        let timeout = self.timeout.unwrap_or_else(|| self.socket.ack_timeout());
         let packet = self.get_packet(event, data)?;
-        let rx = self.socket.send_with_ack_permit(packet, permit);
-        let stream = AckInnerStream::send(rx, timeout, self.socket.id);
-        Ok(AckStream::<V>::from(stream))
+        let (stream, handle) = if self.retries > 0 {
+            let (rx, handle) = self.socket.send_with_ack_retries(
+                packet,
+                permit,
+                timeout,
+                self.retries,
+                self.retry_delay,
+            );
+            // The retrying task yields the timeout itself once all the attempts timed out
+            let stream = AckInnerStream::send(rx, Duration::MAX, self.socket.id);
+            (stream, handle)
+        } else {
+            let (rx, handle) = self.socket.send_with_ack_permit(packet, permit);
+            (AckInnerStream::send(rx, timeout, self.socket.id), handle)
+        };
+        Ok(AckStream::<V>::from(stream).with_handle(handle))
     }
 
     /// Creates a packet with the given event and data.
@@ -632,13 +932,15 @@ impl<A: Adapter> ConfOperators<'_, WithBinary, A> {
         &mut self,
         event: impl Into<Cow<'static, str>>,
         data: Value,
-    ) -> Result<Packet<'static>, serde_json::Error> {
+    ) -> Result<Packet<'static>, SchemaError> {
+        let event = event.into();
+        self.socket.ns.check_schema(&event, &data)?;
         let ns = self.socket.ns.path.clone();
         let packet = if self.binary.is_empty() {
-            Packet::event(ns, event.into(), data)
+            Packet::event(ns, event, data)
         } else {
             let binary = std::mem::take(&mut self.binary);
-            Packet::bin_event(ns, event.into(), data, binary)
+            Packet::bin_event(ns, event, data, binary)
         };
         Ok(packet)
     }
@@ -743,8 +1045,76 @@ impl<BH: BinaryHolding, A: Adapter> BroadcastOperators<BH, A> {
         self.broadcast()
     }
 
+    /// Selects all sockets that are in **any** of the given rooms (union) except the current socket.
+    ///
+    /// This is the same as the `to()` operator.
+    /// #### Example
+    /// ```
+    /// # use socketioxide::{SocketIo, extract::*};
+    /// # use serde_json::Value;
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.ns("/", |socket: SocketRef| {
+    ///     socket.on("test", |socket: SocketRef, Data::<Value>(data)| async move {
+    ///         // In region:eu or in plan:pro except room1 and the current socket
+    ///         socket
+    ///             .except("room1")
+    ///             .to_any_of(["region:eu", "plan:pro"])
+    ///             .emit("test", data);
+    ///     });
+    /// });
+    pub fn to_any_of(self, rooms: impl RoomParam) -> Self {
+        self.to(rooms)
+    }
+
+    /// Selects all sockets that are in **all** the given rooms (intersection) except the current socket.
+    /// Calling it multiple times adds rooms to the intersection.
+    ///
+    /// When combined with the `to()` or `within()` operators, the sockets selected by them
+    /// are narrowed down to the ones that are also in all the given rooms.
+    /// #### Example
+    /// ```
+    /// # use socketioxide::{SocketIo, extract::*};
+    /// # use serde_json::Value;
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.ns("/", |socket: SocketRef| {
+    ///     socket.on("test", |socket: SocketRef, Data::<Value>(data)| async move {
+    ///         // In both region:eu and plan:pro except room1 and the current socket
+    ///         socket
+    ///             .except("room1")
+    ///             .to_all_of(["region:eu", "plan:pro"])
+    ///             .emit("test", data);
+    ///     });
+    /// });
+    pub fn to_all_of(mut self, rooms: impl RoomParam) -> Self {
+        self.opts.rooms_all.extend(rooms.into_room_iter());
+        self.broadcast()
+    }
+
+    /// Selects the sockets with the given ids, in addition to the sockets selected with the previous operators.
+    ///
+    /// The recipients are selected by the adapter in a single broadcast,
+    /// so with a distributed adapter the sockets connected to other servers are also reached.
+    /// The ids of unknown sockets are ignored.
+    /// #### Example
+    /// ```
+    /// # use socketioxide::{SocketIo, extract::*, socket::Sid};
+    /// # use serde_json::Value;
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.ns("/", |socket: SocketRef| {
+    ///     socket.on("test", |socket: SocketRef, Data::<Vec<Sid>>(sids)| async move {
+    ///         // To the sockets sent by the client and to the sockets in room1
+    ///         socket.within("room1").to_sockets(sids).emit("test", "hello");
+    ///     });
+    /// });
+    pub fn to_sockets(mut self, sids: impl IntoIterator<Item = Sid>) -> Self {
+        self.opts.sids.extend(sids);
+        self
+    }
+
     /// Broadcasts to all sockets only connected on this node (when using multiple nodes).
     /// When using the default in-memory adapter, this operator is a no-op.
+    ///
+    /// Every other operator narrows down the sockets of this node, so all of them can be combined with it.
     /// #### Example
     /// ```
     /// # use socketioxide::{SocketIo, extract::*};
@@ -778,6 +1148,132 @@ impl<BH: BinaryHolding, A: Adapter> BroadcastOperators<BH, A> {
         self
     }
 
+    /// Filters the selected sockets with a predicate over their [`Extensions`](crate::extensions::Extensions).
+    /// Calling it multiple times combines the predicates.
+    ///
+    /// The predicate is evaluated by the adapter on the candidate sockets before the delivery.
+    /// It can only be evaluated on the sockets connected to this node so
+    /// when using multiple nodes, **the remote sockets are skipped**.
+    /// #### Example
+    /// ```
+    /// # use socketioxide::{SocketIo, extract::*};
+    /// # use serde_json::Value;
+    /// #[derive(Clone, PartialEq)]
+    /// enum Role { Admin, User }
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.ns("/", |socket: SocketRef| {
+    ///     socket.extensions.insert(Role::User);
+    ///     socket.on("test", |socket: SocketRef, Data::<Value>(data)| async move {
+    ///         // This message will be broadcast to the admins of room1 except the current socket
+    ///         socket
+    ///             .to("room1")
+    ///             .filter(|ext| ext.get::<Role>() == Some(Role::Admin))
+    ///             .emit("test", data);
+    ///     });
+    /// });
+    #[cfg_attr(docsrs, doc(cfg(feature = "extensions")))]
+    #[cfg(feature = "extensions")]
+    pub fn filter<F>(mut self, predicate: F) -> Self
+    where
+        F: Fn(&crate::extensions::Extensions) -> bool + Send + Sync + 'static,
+    {
+        let filter = SocketFilter::new(predicate);
+        self.opts.filter = Some(match self.opts.filter.take() {
+            Some(prev) => prev.and(filter),
+            None => filter,
+        });
+        self
+    }
+
+    /// Only asks the selected sockets matching the predicate over their [`Extensions`](crate::extensions::Extensions)
+    /// for an acknowledgement, when emitting with `emit_with_ack()`. The event is still sent to all the selected sockets,
+    /// but the acknowledgement stream only waits for and yields the responses of the matching ones.
+    /// Calling it multiple times selects the sockets matching all the predicates.
+    ///
+    /// As for the `filter()` operator, it is evaluated on the sockets connected to this node only.
+    /// #### Example
+    /// ```
+    /// # use socketioxide::{SocketIo, extract::*};
+    /// # use serde_json::Value;
+    /// # use futures_util::stream::StreamExt;
+    /// #[derive(Clone, PartialEq)]
+    /// enum Role { Admin, User }
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.ns("/", |socket: SocketRef| {
+    ///     socket.extensions.insert(Role::User);
+    ///     socket.on("vote", |socket: SocketRef, Data::<Value>(data)| async move {
+    ///         // Everyone in room1 is notified but only the admins are expected to approve
+    ///         let approvals = socket
+    ///             .to("room1")
+    ///             .ack_filter(|ext| ext.get::<Role>() == Some(Role::Admin))
+    ///             .emit_with_ack::<bool>("vote", data)
+    ///             .unwrap()
+    ///             .count()
+    ///             .await;
+    ///     });
+    /// });
+    #[cfg_attr(docsrs, doc(cfg(feature = "extensions")))]
+    #[cfg(feature = "extensions")]
+    pub fn ack_filter<F>(mut self, predicate: F) -> Self
+    where
+        F: Fn(&crate::extensions::Extensions) -> bool + Send + Sync + 'static,
+    {
+        let filter = SocketFilter::new(predicate);
+        self.opts.ack_filter = Some(match self.opts.ack_filter.take() {
+            Some(prev) => prev.and(filter),
+            None => filter,
+        });
+        self
+    }
+
+    /// Narrows down the selected sockets to the ones with the metadata `key` set to `value`,
+    /// see [`Socket::set_meta`](crate::socket::Socket#method.set_meta).
+    /// Calling it multiple times with different keys selects the sockets matching all of them,
+    /// calling it again with the same key replaces the value.
+    ///
+    /// Contrary to the `filter()` operator, the metadata are indexed by the adapter
+    /// so the sockets are selected without iterating over all the sockets of the namespace.
+    /// #### Example
+    /// ```
+    /// # use socketioxide::{SocketIo, extract::*};
+    /// # use serde_json::Value;
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.ns("/", |socket: SocketRef| {
+    ///     socket.set_meta("region", "eu").unwrap();
+    ///     socket.on("test", |socket: SocketRef, Data::<Value>(data)| async move {
+    ///         // This message will be broadcast to the sockets of room1 in the eu region
+    ///         socket
+    ///             .to("room1")
+    ///             .where_meta("region", "eu")
+    ///             .emit("test", data);
+    ///     });
+    /// });
+    pub fn where_meta(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.opts.meta.insert(key.into(), value.into());
+        self
+    }
+
+    /// Narrows down the selected sockets to the ones that joined one of the selected rooms after `instant`,
+    /// or any room if no room is selected. It can be used to reach only the recent members of a room.
+    /// #### Example
+    /// ```
+    /// # use socketioxide::{SocketIo, extract::*};
+    /// # use serde_json::Value;
+    /// # use std::time::{Duration, Instant};
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.ns("/", |socket: SocketRef| {
+    ///     socket.join("room1").unwrap();
+    ///     socket.on("test", |socket: SocketRef, Data::<Value>(data)| async move {
+    ///         // This message will be broadcast to the sockets that joined room1 in the last hour
+    ///         let cutoff = Instant::now() - Duration::from_secs(3600);
+    ///         socket.to("room1").joined_after(cutoff).emit("test", data);
+    ///     });
+    /// });
+    pub fn joined_after(mut self, instant: std::time::Instant) -> Self {
+        self.opts.joined_after = Some(instant);
+        self
+    }
+
     /// Sets a custom timeout when sending a message with an acknowledgement.
     ///
     /// See [`SocketIoBuilder::ack_timeout`](crate::SocketIoBuilder) for the default timeout.
@@ -822,6 +1318,9 @@ impl<BH: BinaryHolding, A: Adapter> BroadcastOperators<BH, A> {
 // ==== impl BroadcastOperators consume fns for WithoutBinary ====
 impl<A: Adapter> BroadcastOperators<WithoutBinary, A> {
     /// Adds a binary payload to the message.
+    ///
+    /// The payloads are stored as reference-counted [`Bytes`], so their buffers are shared
+    /// between all the recipients of the broadcast rather than copied for each of them.
     /// #### Example
     /// ```
     /// # use socketioxide::{SocketIo, extract::*};
@@ -863,6 +1362,8 @@ impl<A: Adapter> BroadcastOperators<WithoutBinary, A> {
     /// * If the packet buffer is full for a given socket, a [`BroadcastError::Socket(SocketError::InternalChannelFull)`]
     /// will be retured.
     /// See [`SocketIoBuilder::max_buffer_size`] option for more infos on internal buffer config
+    /// * If all the selected rooms are also excluded with the `except()` operator,
+    /// a [`BroadcastError::ContradictoryOperators`] will be returned.
     ///
     /// > **Note**: If a error is returned because of a specific socket, the message will still be sent to all other sockets.
     ///
@@ -897,6 +1398,161 @@ impl<A: Adapter> BroadcastOperators<WithoutBinary, A> {
         self.bin(bins).emit(event, data)
     }
 
+    /// Emits a message to all sockets selected with the previous operators, serializing the data
+    /// according to the given context with [`SerializeWithContext`].
+    ///
+    /// The data is serialized once for all the recipients, so a broadcast should target a class of
+    /// recipients sharing the same context, e.g. a room. See the [`context`](crate::context) module doc for more details.
+    ///
+    /// ## Errors
+    /// The errors are the same as the ones of [`emit()`](#method.emit).
+    pub fn emit_with_context<T, C>(
+        self,
+        event: impl Into<Cow<'static, str>>,
+        data: &T,
+        ctx: &C,
+    ) -> Result<(), BroadcastError>
+    where
+        T: SerializeWithContext<C> + ?Sized,
+    {
+        self.emit(event, WithContext::new(data, ctx))
+    }
+
+    /// Emits an event prepared with [`SocketIo::prepare`](crate::SocketIo#method.prepare)
+    /// to all sockets selected with the previous operators.
+    ///
+    /// The data was serialized when the packet was prepared, so emitting the same packet
+    /// to many rooms or namespaces doesn't serialize it again.
+    ///
+    /// ## Errors
+    /// The errors are the same as the ones of [`emit()`](#method.emit), except for the serialization errors
+    /// that are returned when the packet is prepared.
+    ///
+    /// #### Example
+    /// ```
+    /// # use socketioxide::SocketIo;
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.ns("/", || {});
+    /// io.ns("/admin", || {});
+    /// let packet = io.prepare("alert", "maintenance in 5 minutes").unwrap();
+    /// io.to("room1").emit_prepared(&packet).unwrap();
+    /// io.of("/admin").unwrap().emit_prepared(&packet).unwrap();
+    /// ```
+    pub fn emit_prepared(self, packet: &PreparedPacket) -> Result<(), BroadcastError> {
+        self.bin(packet.bins.iter().cloned())
+            .emit(packet.event.clone(), packet.data.clone())
+    }
+
+    /// Applies a JSON merge patch to the document of each selected room and emits it to the members of the room
+    /// in a [`PATCH_EVENT`](crate::state_sync::PATCH_EVENT). The sockets joining the room later receive the
+    /// whole document. See the [`state_sync`](crate::state_sync) module doc for more details.
+    ///
+    /// The patch is emitted once per room, with the other operators applied. Nothing is done if no room is selected.
+    ///
+    /// ## Errors
+    /// The errors are the same as the ones of [`emit()`](#method.emit).
+    /// The documents of the rooms whose patch was emitted before the error stay patched.
+    ///
+    /// #### Example
+    /// ```
+    /// # use socketioxide::{SocketIo, extract::*};
+    /// # use serde_json::{json, Value};
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.ns("/", |socket: SocketRef| {
+    ///     socket.join("board").unwrap();
+    ///     socket.on("move", |socket: SocketRef, Data::<Value>(pos)| {
+    ///         socket.to("board").patch(json!({ "cursors": { socket.id.to_string(): pos } })).ok();
+    ///     });
+    /// });
+    /// ```
+    pub fn patch(self, patch: Value) -> Result<(), BroadcastError> {
+        use crate::state_sync::{merge_patch, StatePatch, PATCH_EVENT};
+        self.check_operators()?;
+        let mut states = self.ns.room_states.lock().unwrap();
+        for room in &self.opts.rooms {
+            merge_patch(states.entry(room.clone()).or_default(), &patch);
+            let data = serde_json::to_value(StatePatch {
+                room: room.clone(),
+                patch: patch.clone(),
+            })?;
+            let packet = Packet::event(self.ns.path.clone(), PATCH_EVENT, data);
+            let opts = BroadcastOptions {
+                rooms: [room.clone()].into(),
+                ..self.opts.clone()
+            };
+            self.ns.broadcast(packet, opts)?;
+        }
+        Ok(())
+    }
+
+    /// Emits a message to all sockets selected with the previous operators and waits for the
+    /// transport of every local recipient to flush it.
+    ///
+    /// It is weaker than an acknowledgement: it only confirms that the server pushed the packet,
+    /// not that the client received it. See [`FlushReport`] for the reported sockets,
+    /// the recipients connected to other servers are excluded from the guarantee.
+    ///
+    /// ## Errors
+    /// The errors are the same as the ones of [`emit()`](#method.emit).
+    /// If a selected room is frozen with [`SocketIo::freeze_room`], a [`BroadcastError::FrozenRoom`] is returned.
+    ///
+    /// [`SocketIo::freeze_room`]: crate::SocketIo#method.freeze_room
+    ///
+    /// #### Example
+    /// ```
+    /// # use socketioxide::{SocketIo, extract::*};
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.ns("/", |socket: SocketRef| {
+    ///     socket.on("test", |io: SocketIo| async move {
+    ///         let report = io.to("room1").emit_flush("test", &[1, 2, 3]).await.unwrap();
+    ///         println!("packet flushed to {} sockets", report.flushed.len());
+    ///     });
+    /// });
+    pub async fn emit_flush<T: serde::Serialize>(
+        self,
+        event: impl Into<Cow<'static, str>>,
+        data: T,
+    ) -> Result<FlushReport, BroadcastError> {
+        let (data, bins) = crate::to_value(data)?;
+        self.bin(bins).emit_flush(event, data).await
+    }
+
+    /// Emits a message to all the local sockets selected with the previous operators and returns
+    /// the ids of the sockets it was actually sent to.
+    ///
+    /// Unlike [`sockets()`](#method.sockets), the ids are collected after the delivery: the sockets
+    /// disconnected during the broadcast or whose packet buffer is full are excluded.
+    /// Because the delivery on other servers can't be audited, the message is only sent to the sockets
+    /// connected to this server, as if the [`local()`](#method.local) flag was set.
+    ///
+    /// ## Errors
+    /// If the packet encoding failed a [`BroadcastError::Serialize`] is returned.
+    /// If all the selected rooms are also excluded with the `except()` operator,
+    /// a [`BroadcastError::ContradictoryOperators`] is returned.
+    /// If a selected room is frozen with [`SocketIo::freeze_room`], a [`BroadcastError::FrozenRoom`] is returned.
+    ///
+    /// [`SocketIo::freeze_room`]: crate::SocketIo#method.freeze_room
+    ///
+    /// #### Example
+    /// ```
+    /// # use socketioxide::{SocketIo, extract::*};
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.ns("/", |socket: SocketRef| {
+    ///     socket.on("test", |io: SocketIo| {
+    ///         let delivered = io.to("room1").emit_audited("test", &[1, 2, 3]).unwrap();
+    ///         println!("packet sent to {delivered:?}");
+    ///     });
+    /// });
+    /// ```
+    pub fn emit_audited<T: serde::Serialize>(
+        self,
+        event: impl Into<Cow<'static, str>>,
+        data: T,
+    ) -> Result<Vec<Sid>, BroadcastError> {
+        let (data, bins) = crate::to_value(data)?;
+        self.bin(bins).emit_audited(event, data)
+    }
+
     /// Emits a message to all sockets selected with the previous operators and
     /// waits for the acknowledgement(s).
     ///
@@ -915,7 +1571,12 @@ impl<A: Adapter> BroadcastOperators<WithoutBinary, A> {
     /// other type that will serialize to binary data), it will be extracted and added to the
     /// outgoing event as if [`bin()`] was called first.
     ///
-    /// If the packet encoding failed a [`serde_json::Error`] is **immediately** returned.
+    /// If the packet encoding failed a [`BroadcastError::Serialize`] is **immediately** returned.
+    /// If all the selected rooms are also excluded with the `except()` operator,
+    /// a [`BroadcastError::ContradictoryOperators`] is **immediately** returned.
+    ///
+    /// If a selected room is frozen with [`SocketIo::freeze_room`], the acknowledgements
+    /// cannot be awaited until it is unfrozen and a [`BroadcastError::FrozenRoom`] is **immediately** returned.
     ///
     /// If the socket is full or if it has been closed before receiving the acknowledgement,
     /// an [`AckError::Socket`] will be yielded.
@@ -933,6 +1594,7 @@ impl<A: Adapter> BroadcastOperators<WithoutBinary, A> {
     /// [`AckError::Socket`]: crate::AckError::Socket
     /// [`AckError::Socket(SocketError::Closed)`]: crate::SocketError::Closed
     /// [`io::get_socket()`]: crate::SocketIo#method.get_socket
+    /// [`SocketIo::freeze_room`]: crate::SocketIo#method.freeze_room
     ///
     /// # Example
     /// ```
@@ -963,7 +1625,7 @@ impl<A: Adapter> BroadcastOperators<WithoutBinary, A> {
         self,
         event: impl Into<Cow<'static, str>>,
         data: impl serde::Serialize,
-    ) -> Result<AckStream<V>, serde_json::Error> {
+    ) -> Result<AckStream<V>, BroadcastError> {
         let (data, bins) = crate::to_value(data)?;
         self.bin(bins).emit_with_ack(event, data)
     }
@@ -983,6 +1645,8 @@ impl<A: Adapter> BroadcastOperators<WithBinary, A> {
     /// * If the packet buffer is full for a given socket, a [`BroadcastError::Socket(SocketError::InternalChannelFull)`]
     /// will be retured.
     /// See [`SocketIoBuilder::max_buffer_size`] option for more infos on internal buffer config
+    /// * If all the selected rooms are also excluded with the `except()` operator,
+    /// a [`BroadcastError::ContradictoryOperators`] will be returned.
     ///
     /// > **Note**: If a error is returned because of a specific socket, the message will still be sent to all other sockets.
     ///
@@ -1013,8 +1677,9 @@ impl<A: Adapter> BroadcastOperators<WithBinary, A> {
         event: impl Into<Cow<'static, str>>,
         data: Value,
     ) -> Result<(), BroadcastError> {
+        self.check_operators()?;
         let packet = self.get_packet(event, data)?;
-        if let Err(e) = self.ns.adapter.broadcast(packet, self.opts) {
+        if let Err(e) = self.ns.broadcast(packet, self.opts) {
             #[cfg(feature = "tracing")]
             tracing::debug!("broadcast error: {e:?}");
             return Err(e);
@@ -1022,6 +1687,83 @@ impl<A: Adapter> BroadcastOperators<WithBinary, A> {
         Ok(())
     }
 
+    /// Emits a message to all sockets selected with the previous operators and waits for the
+    /// transport of every local recipient to flush it.
+    ///
+    /// It is weaker than an acknowledgement, see [`FlushReport`] for the reported sockets.
+    ///
+    /// If a selected room is frozen with [`SocketIo::freeze_room`], the broadcast cannot be
+    /// held and a [`BroadcastError::FrozenRoom`] is returned.
+    ///
+    /// [`SocketIo::freeze_room`]: crate::SocketIo#method.freeze_room
+    pub async fn emit_flush(
+        mut self,
+        event: impl Into<Cow<'static, str>>,
+        data: Value,
+    ) -> Result<FlushReport, BroadcastError> {
+        self.check_operators()?;
+        self.ns.check_frozen(&self.opts)?;
+        let packet = self.get_packet(event, data)?;
+        let local = self.ns.adapter.fetch_sockets(self.opts.clone());
+        let local = local.map_err(|e| BroadcastError::Adapter(e.into()))?;
+        let remote_servers = if self.opts.flags.contains(&BroadcastFlags::Local) {
+            0
+        } else {
+            self.ns
+                .adapter
+                .server_count()
+                .map_err(|e| BroadcastError::Adapter(e.into()))?
+                .saturating_sub(1)
+        };
+        if let Err(e) = self.ns.adapter.broadcast(packet, self.opts) {
+            #[cfg(feature = "tracing")]
+            tracing::debug!("broadcast error: {e:?}");
+            return Err(e);
+        }
+
+        let flushed = future::join_all(local.iter().map(|s| s.flushed())).await;
+        let mut report = FlushReport {
+            remote_servers,
+            ..Default::default()
+        };
+        for (socket, flushed) in local.iter().zip(flushed) {
+            match flushed {
+                true => report.flushed.push(socket.id),
+                false => report.closed.push(socket.id),
+            }
+        }
+        Ok(report)
+    }
+
+    /// Emits a message to all the local sockets selected with the previous operators and returns
+    /// the ids of the sockets it was actually sent to.
+    ///
+    /// If a selected room is frozen with [`SocketIo::freeze_room`], the broadcast cannot be
+    /// held and a [`BroadcastError::FrozenRoom`] is returned.
+    ///
+    /// [`SocketIo::freeze_room`]: crate::SocketIo#method.freeze_room
+    pub fn emit_audited(
+        mut self,
+        event: impl Into<Cow<'static, str>>,
+        data: Value,
+    ) -> Result<Vec<Sid>, BroadcastError> {
+        self.check_operators()?;
+        self.ns.check_frozen(&self.opts)?;
+        let event = event.into();
+        let packet = self.get_packet(event.clone(), data)?;
+        let sockets = self.ns.adapter.fetch_sockets(self.opts);
+        let sockets = sockets.map_err(|e| BroadcastError::Adapter(e.into()))?;
+        let mut delivered = Vec::with_capacity(sockets.len());
+        for socket in sockets {
+            if !socket.connected() {
+                socket.notify_drop(DropReason::SocketClosed, Some(&event));
+            } else if socket.send_broadcast(packet.clone()).is_ok() {
+                delivered.push(socket.id);
+            }
+        }
+        Ok(delivered)
+    }
+
     /// Emits a message to all sockets selected with the previous operators and
     /// waits for the acknowledgement(s).
     ///
@@ -1036,6 +1778,12 @@ impl<A: Adapter> BroadcastOperators<WithBinary, A> {
     /// * As a [`Future`]: It will yield the first [`AckResponse`] received from the client.
     /// Useful when expecting only one acknowledgement.
     ///
+    /// If all the selected rooms are also excluded with the `except()` operator,
+    /// a [`BroadcastError::ContradictoryOperators`] is **immediately** returned.
+    ///
+    /// If a selected room is frozen with [`SocketIo::freeze_room`], the acknowledgements
+    /// cannot be awaited until it is unfrozen and a [`BroadcastError::FrozenRoom`] is **immediately** returned.
+    ///
     /// If the socket is full or if it has been closed before receiving the acknowledgement,
     /// an [`AckError::Socket`] will be yielded.
     ///
@@ -1052,6 +1800,7 @@ impl<A: Adapter> BroadcastOperators<WithBinary, A> {
     /// [`AckError::Socket`]: crate::AckError::Socket
     /// [`AckError::Socket(SocketError::Closed)`]: crate::SocketError::Closed
     /// [`io::get_socket()`]: crate::SocketIo#method.get_socket
+    /// [`SocketIo::freeze_room`]: crate::SocketIo#method.freeze_room
     ///
     /// # Example
     /// ```
@@ -1082,7 +1831,9 @@ impl<A: Adapter> BroadcastOperators<WithBinary, A> {
         mut self,
         event: impl Into<Cow<'static, str>>,
         data: Value,
-    ) -> Result<AckStream<V>, serde_json::Error> {
+    ) -> Result<AckStream<V>, BroadcastError> {
+        self.check_operators()?;
+        self.ns.check_frozen(&self.opts)?;
         let packet = self.get_packet(event, data)?;
         let stream = self
             .ns
@@ -1097,13 +1848,15 @@ impl<A: Adapter> BroadcastOperators<WithBinary, A> {
         &mut self,
         event: impl Into<Cow<'static, str>>,
         data: Value,
-    ) -> Result<Packet<'static>, serde_json::Error> {
+    ) -> Result<Packet<'static>, SchemaError> {
+        let event = event.into();
+        self.ns.check_schema(&event, &data)?;
         let ns = self.ns.path.clone();
         let packet = if self.binary.is_empty() {
-            Packet::event(ns, event.into(), data)
+            Packet::event(ns, event, data)
         } else {
             let binary = std::mem::take(&mut self.binary);
-            Packet::bin_event(ns, event.into(), data, binary)
+            Packet::bin_event(ns, event, data, binary)
         };
         Ok(packet)
     }
@@ -1149,6 +1902,18 @@ impl<BH: BinaryHolding, A: Adapter> BroadcastOperators<BH, A> {
 
     /// Makes all sockets selected with the previous operators join the given room(s).
     ///
+    /// Each selected socket joins the rooms with [`Socket::join`], so the join hook, the
+    /// [`max_rooms_per_socket`] limit and the room [capacity] apply, and the presence events,
+    /// the lifecycle events and the room state snapshots are sent as for a single socket.
+    ///
+    /// ## Errors
+    /// The sockets that cannot join the rooms are skipped and their [`JoinError`]s are returned,
+    /// the other sockets still join the rooms.
+    ///
+    /// [`Socket::join`]: crate::socket::Socket#method.join
+    /// [`max_rooms_per_socket`]: crate::SocketIoBuilder#method.max_rooms_per_socket
+    /// [capacity]: crate::room_meta::RoomMeta#method.set_capacity
+    ///
     /// ### Example
     /// ```
     /// # use socketioxide::{SocketIo, extract::*};
@@ -1159,12 +1924,30 @@ impl<BH: BinaryHolding, A: Adapter> BroadcastOperators<BH, A> {
     ///     socket.within("room1").within("room3").join(["room4", "room5"]).unwrap();
     ///   });
     /// });
-    pub fn join(self, rooms: impl RoomParam) -> Result<(), A::Error> {
-        self.ns.adapter.add_sockets(self.opts, rooms)
+    pub fn join(self, rooms: impl RoomParam) -> Result<(), Vec<JoinError<A::Error>>> {
+        let rooms: Vec<Room> = rooms.into_room_iter().collect();
+        let sockets = self
+            .ns
+            .adapter
+            .fetch_sockets(self.opts)
+            .map_err(|e| vec![JoinError::Adapter(e)])?;
+        let errors: Vec<_> = sockets
+            .into_iter()
+            .filter_map(|socket| socket.join(rooms.clone()).err())
+            .collect();
+        match errors.is_empty() {
+            true => Ok(()),
+            false => Err(errors),
+        }
     }
 
     /// Makes all sockets selected with the previous operators leave the given room(s).
     ///
+    /// Each selected socket leaves the rooms with [`Socket::leave`], so the presence events,
+    /// the lifecycle events and the leave hook are sent as for a single socket.
+    ///
+    /// [`Socket::leave`]: crate::socket::Socket#method.leave
+    ///
     /// ### Example
     /// ```
     /// # use socketioxide::{SocketIo, extract::*};
@@ -1176,7 +1959,11 @@ impl<BH: BinaryHolding, A: Adapter> BroadcastOperators<BH, A> {
     ///   });
     /// });
     pub fn leave(self, rooms: impl RoomParam) -> Result<(), A::Error> {
-        self.ns.adapter.del_sockets(self.opts, rooms)
+        let rooms: Vec<Room> = rooms.into_room_iter().collect();
+        for socket in self.ns.adapter.fetch_sockets(self.opts)? {
+            socket.leave(rooms.clone())?;
+        }
+        Ok(())
     }
 
     /// Gets all room names for a given namespace
@@ -1188,4 +1975,35 @@ impl<BH: BinaryHolding, A: Adapter> BroadcastOperators<BH, A> {
     pub fn get_socket(&self, sid: Sid) -> Option<SocketRef<A>> {
         self.ns.get_socket(sid).map(SocketRef::from).ok()
     }
+
+    /// Gets a [`RemoteSocket`] handle to the socket with the specified [`Sid`],
+    /// connected to any node of the cluster.
+    ///
+    /// See the [`remote`](crate::remote) module doc for more details.
+    pub fn remote_socket(&self, sid: Sid) -> RemoteSocket<A> {
+        RemoteSocket::new(sid, self.ns.clone())
+    }
+
+    /// Checks that the operators are not contradictory.
+    /// If all the selected rooms are also excluded, or if one of the rooms of the intersection
+    /// is excluded, the message would be sent to nobody.
+    fn check_operators(&self) -> Result<(), BroadcastError> {
+        let rooms = &self.opts.rooms;
+        if !rooms.is_empty() && rooms.iter().all(|room| self.opts.except.contains(room)) {
+            let mut rooms: Vec<Room> = rooms.iter().cloned().collect();
+            rooms.sort();
+            return Err(BroadcastError::ContradictoryOperators(rooms));
+        }
+        let mut rooms: Vec<Room> = self
+            .opts
+            .rooms_all
+            .intersection(&self.opts.except)
+            .cloned()
+            .collect();
+        if !rooms.is_empty() {
+            rooms.sort();
+            return Err(BroadcastError::ContradictoryOperators(rooms));
+        }
+        Ok(())
+    }
 }
@@ -294,7 +294,7 @@ impl BinaryPacket {
         Self {
             data,
             bin,
-            payload_count,
+            payload_count: payload_count.max(bin_count),
         }
     }
 
@@ -307,6 +307,11 @@ impl BinaryPacket {
     pub fn is_complete(&self) -> bool {
         self.payload_count == self.bin.len()
     }
+
+    /// The number of expected binary payloads
+    pub(crate) fn payload_count(&self) -> usize {
+        self.payload_count
+    }
 }
 
 fn is_placeholder(o: &Map<String, Value>) -> bool {
@@ -423,11 +428,33 @@ impl<'a> From<Packet<'a>> for String {
     }
 }
 
+/// The limits applied when decoding the event packets, see [`Packet::decode`].
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct DecodeLimits {
+    /// The maximum length in bytes of the event name
+    pub max_event_name_len: Option<usize>,
+    /// The maximum number of arguments of the event
+    pub max_event_args: Option<usize>,
+    /// The maximum number of binary attachments of the binary packets
+    pub max_attachments: Option<usize>,
+}
+
+/// The attachments awaited for a binary packet are counted from its placeholders,
+/// so they are checked in addition to the count declared in the header.
+fn check_attachments(bin: &BinaryPacket, limits: DecodeLimits) -> Result<(), Error> {
+    match limits.max_attachments {
+        Some(max) if bin.payload_count() > max => {
+            Err(Error::TooManyAttachments(bin.payload_count()))
+        }
+        _ => Ok(()),
+    }
+}
+
 /// Deserialize an event packet from a string, formated as:
 /// ```text
 /// ["<event name>", ...<JSON-stringified payload without binary>]
 /// ```
-fn deserialize_event_packet(data: &str) -> Result<(String, Value), Error> {
+fn deserialize_event_packet(data: &str, limits: DecodeLimits) -> Result<(String, Value), Error> {
     #[cfg(feature = "tracing")]
     tracing::debug!("Deserializing event packet: {:?}", data);
     let packet = match serde_json::from_str::<Value>(data)? {
@@ -439,12 +466,49 @@ fn deserialize_event_packet(data: &str) -> Result<(String, Value), Error> {
         .first()
         .ok_or(Error::InvalidEventName)?
         .as_str()
-        .ok_or(Error::InvalidEventName)?
-        .to_string();
+        .ok_or(Error::InvalidEventName)?;
+    if limits
+        .max_event_name_len
+        .is_some_and(|max| event.len() > max)
+    {
+        return Err(Error::EventNameTooLong(event.len()));
+    }
+    let args = packet.len() - 1;
+    if limits.max_event_args.is_some_and(|max| args > max) {
+        return Err(Error::TooManyEventArgs(args));
+    }
+    let event = event.to_string();
     let payload = Value::from_iter(packet.into_iter().skip(1));
     Ok((event, payload))
 }
 
+/// A predicate telling if the arguments of the event of the given namespace should be kept undecoded
+pub(crate) type IsRawEvent<'a> = &'a dyn Fn(&str, &str) -> bool;
+
+/// Splits an event packet formated as `["<event name>", ...<args>]` into its event name
+/// and the JSON array of its arguments, without deserializing them.
+fn split_event_packet(data: &str, limits: DecodeLimits) -> Result<(String, String), Error> {
+    let rest = data.strip_prefix('[').ok_or(Error::InvalidEventName)?;
+    let mut stream = serde_json::Deserializer::from_str(rest).into_iter::<String>();
+    let event = match stream.next() {
+        Some(Ok(event)) => event,
+        _ => return Err(Error::InvalidEventName),
+    };
+    if limits
+        .max_event_name_len
+        .is_some_and(|max| event.len() > max)
+    {
+        return Err(Error::EventNameTooLong(event.len()));
+    }
+    let rest = rest[stream.byte_offset()..].trim_start();
+    let args = match rest.strip_prefix(',') {
+        Some(args) => format!("[{args}"),
+        None if rest == "]" => "[]".to_string(),
+        None => return Err(Error::InvalidEventName),
+    };
+    Ok((event, args))
+}
+
 fn deserialize_packet<T: DeserializeOwned>(data: &str) -> Result<Option<T>, serde_json::Error> {
     #[cfg(feature = "tracing")]
     tracing::debug!("Deserializing packet: {:?}", data);
@@ -466,19 +530,49 @@ impl<'a> TryFrom<Str> for Packet<'a> {
     type Error = Error;
 
     fn try_from(value: Str) -> Result<Self, Self::Error> {
+        Packet::decode(value, DecodeLimits::default())
+    }
+}
+
+impl<'a> Packet<'a> {
+    /// Deserialize a packet from a string, rejecting the event packets that exceed the given limits.
+    /// See the `TryFrom<Str>` implementation for the format.
+    pub(crate) fn decode(value: Str, limits: DecodeLimits) -> Result<Self, Error> {
+        Self::decode_with(value, limits, None)
+    }
+
+    /// Deserialize a packet from a string like [`Packet::decode`].
+    /// The arguments of the events for which `is_raw(ns, event)` returns `true` are not deserialized,
+    /// they are kept as a JSON array string in a [`Value::String`].
+    pub(crate) fn decode_with(
+        value: Str,
+        limits: DecodeLimits,
+        is_raw: Option<IsRawEvent<'_>>,
+    ) -> Result<Self, Error> {
         let chars = value.as_bytes();
         // It is possible to parse the packet from a byte slice because separators are only ASCII
         let mut i = 1;
-        let index = (b'0'..=b'6')
-            .contains(&chars[0])
-            .then_some(chars[0])
+        let index = chars
+            .first()
+            .copied()
+            .filter(|c| (b'0'..=b'6').contains(c))
             .ok_or(Error::InvalidPacketType)?;
 
         // Move the cursor to skip the payload count if it is a binary packet
         if index == b'5' || index == b'6' {
             while chars.get(i) != Some(&b'-') {
+                if i >= chars.len() {
+                    return Err(Error::InvalidPacketType);
+                }
                 i += 1;
             }
+            if let Some(max) = limits.max_attachments {
+                // A count too large to be parsed is necessarily over the limit
+                let count = value[1..i].parse().unwrap_or(usize::MAX);
+                if count > max {
+                    return Err(Error::TooManyAttachments(count));
+                }
+            }
             i += 1;
         }
 
@@ -517,8 +611,20 @@ impl<'a> TryFrom<Str> for Packet<'a> {
         let inner = match index {
             b'0' => PacketData::Connect((!data.is_empty()).then(|| data.to_string())),
             b'1' => PacketData::Disconnect,
+            b'2' if is_raw.is_some() => {
+                let (event, args) = split_event_packet(data, limits)?;
+                match is_raw {
+                    Some(is_raw) if is_raw(&ns, &event) => {
+                        PacketData::Event(event.into(), Value::String(args), ack)
+                    }
+                    _ => {
+                        let (event, payload) = deserialize_event_packet(data, limits)?;
+                        PacketData::Event(event.into(), payload, ack)
+                    }
+                }
+            }
             b'2' => {
-                let (event, payload) = deserialize_event_packet(data)?;
+                let (event, payload) = deserialize_event_packet(data, limits)?;
                 PacketData::Event(event.into(), payload, ack)
             }
             b'3' => {
@@ -526,15 +632,16 @@ impl<'a> TryFrom<Str> for Packet<'a> {
                 PacketData::EventAck(packet, ack.ok_or(Error::InvalidPacketType)?)
             }
             b'5' => {
-                let (event, payload) = deserialize_event_packet(data)?;
-                PacketData::BinaryEvent(event.into(), BinaryPacket::incoming(payload), ack)
+                let (event, payload) = deserialize_event_packet(data, limits)?;
+                let bin = BinaryPacket::incoming(payload);
+                check_attachments(&bin, limits)?;
+                PacketData::BinaryEvent(event.into(), bin, ack)
             }
             b'6' => {
                 let packet = deserialize_packet(data)?.ok_or(Error::InvalidPacketType)?;
-                PacketData::BinaryAck(
-                    BinaryPacket::incoming(packet),
-                    ack.ok_or(Error::InvalidPacketType)?,
-                )
+                let bin = BinaryPacket::incoming(packet);
+                check_attachments(&bin, limits)?;
+                PacketData::BinaryAck(bin, ack.ok_or(Error::InvalidPacketType)?)
             }
             _ => return Err(Error::InvalidPacketType),
         };
@@ -558,6 +665,60 @@ pub struct ConnectPacket {
     sid: Sid,
 }
 
+/// An event packet encoded to the socket.io format, returned by
+/// [`SocketIo::encode_event`](crate::SocketIo#method.encode_event).
+///
+/// It holds the text packet and the binary attachments exactly as they are written to the engine.io connection,
+/// without the engine.io framing (the `4` message prefix).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EncodedPacket {
+    text: String,
+    binary: Vec<Bytes>,
+}
+
+impl EncodedPacket {
+    /// Serializes and encodes an event for the given namespace.
+    ///
+    /// ## Errors
+    /// When encoding the data into JSON a [`serde_json::Error`] is returned.
+    pub fn new<T: Serialize>(
+        ns: impl Into<Str>,
+        event: impl Into<Cow<'static, str>>,
+        data: T,
+    ) -> Result<Self, serde_json::Error> {
+        let (data, bins) = crate::to_value(data)?;
+        let mut packet = if bins.is_empty() {
+            Packet::event(ns, event, data)
+        } else {
+            Packet::bin_event(ns, event, data, bins)
+        };
+        let binary = match packet.inner {
+            PacketData::BinaryEvent(_, ref mut bin, _) => std::mem::take(&mut bin.bin),
+            _ => Vec::new(),
+        };
+        Ok(Self {
+            text: packet.into(),
+            binary,
+        })
+    }
+
+    /// The text packet, e.g. `2["event","data"]`.
+    /// For a binary event it contains a placeholder for each attachment.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// The binary attachments, sent right after the text packet.
+    pub fn binary(&self) -> &[Bytes] {
+        &self.binary
+    }
+
+    /// Returns the text packet and the binary attachments.
+    pub fn into_parts(self) -> (String, Vec<Bytes>) {
+        (self.text, self.binary)
+    }
+}
+
 /// Creates a binary placeholder in the packet data
 ///
 /// Packets that contain binary payloads need to have a "placeholder" object placed in the location
@@ -615,6 +776,14 @@ mod test {
         assert_eq!(Packet::connect("/admin™", sid, ProtocolVersion::V5), packet);
     }
 
+    #[test]
+    fn packet_decode_invalid() {
+        assert!(Packet::try_from("".to_string()).is_err());
+        assert!(Packet::try_from("7".to_string()).is_err());
+        // Binary packet header without the attachment count separator
+        assert!(Packet::try_from("51".to_string()).is_err());
+    }
+
     #[test]
     fn packet_encode_connect() {
         let sid = Sid::new();
@@ -694,6 +863,97 @@ mod test {
         assert_eq!(packet, comparison_packet);
     }
 
+    #[test]
+    fn packet_decode_event_limits() {
+        let limits = DecodeLimits {
+            max_event_name_len: Some(5),
+            max_event_args: Some(2),
+            ..Default::default()
+        };
+        let payload = format!("2{}", json!(["event", 1, 2]));
+        assert!(Packet::decode(payload.into(), limits).is_ok());
+
+        let payload = format!("2{}", json!(["events", 1]));
+        let err = Packet::decode(payload.into(), limits).unwrap_err();
+        assert!(matches!(err, Error::EventNameTooLong(6)));
+
+        let payload = format!("21{}", json!(["event", 1, 2, 3]));
+        let err = Packet::decode(payload.into(), limits).unwrap_err();
+        assert!(matches!(err, Error::TooManyEventArgs(3)));
+
+        let payload = format!(
+            "51-{}",
+            json!(["event", 1, 2, { "_placeholder": true, "num": 0 }])
+        );
+        let err = Packet::decode(payload.into(), limits).unwrap_err();
+        assert!(matches!(err, Error::TooManyEventArgs(3)));
+    }
+
+    #[test]
+    fn packet_decode_raw_event() {
+        let limits = DecodeLimits {
+            max_event_name_len: Some(5),
+            ..Default::default()
+        };
+        let is_raw = |ns: &str, event: &str| ns == "/admin" && event == "raw";
+        let decode = |payload: &str| {
+            Packet::decode_with(payload.to_string().into(), limits, Some(&is_raw)).unwrap()
+        };
+        assert_eq!(
+            decode(r#"2/admin,12[ "raw" , 1, {"a" : 2}]"#),
+            Packet {
+                inner: PacketData::Event(
+                    "raw".into(),
+                    Value::String(r#"[ 1, {"a" : 2}]"#.into()),
+                    Some(12)
+                ),
+                ns: "/admin".into(),
+            }
+        );
+        // The other events and namespaces are decoded
+        assert_eq!(
+            decode(r#"2/admin,["event",1]"#).inner,
+            PacketData::Event("event".into(), json!([1]), None)
+        );
+        assert_eq!(
+            decode(r#"2["raw",1]"#).inner,
+            PacketData::Event("raw".into(), json!([1]), None)
+        );
+
+        let payload = r#"2/admin,["events"]"#.to_string();
+        let err = Packet::decode_with(payload.into(), limits, Some(&is_raw)).unwrap_err();
+        assert!(matches!(err, Error::EventNameTooLong(6)));
+        let payload = r#"2/admin,["raw"1]"#.to_string();
+        let err = Packet::decode_with(payload.into(), limits, Some(&is_raw)).unwrap_err();
+        assert!(matches!(err, Error::InvalidEventName));
+    }
+
+    #[test]
+    fn packet_decode_attachment_limit() {
+        let limits = DecodeLimits {
+            max_attachments: Some(1),
+            ..Default::default()
+        };
+        let placeholder = json!({ "_placeholder": true, "num": 0 });
+        let payload = format!("51-{}", json!(["event", placeholder]));
+        assert!(Packet::decode(payload.into(), limits).is_ok());
+
+        let payload = format!("5100000-{}", json!(["event", placeholder]));
+        let err = Packet::decode(payload.into(), limits).unwrap_err();
+        assert!(matches!(err, Error::TooManyAttachments(100000)));
+
+        let payload = format!("699999999999999999999999-1{}", json!([placeholder]));
+        let err = Packet::decode(payload.into(), limits).unwrap_err();
+        assert!(matches!(err, Error::TooManyAttachments(usize::MAX)));
+
+        let payload = format!(
+            "51-{}",
+            json!(["event", placeholder, { "_placeholder": true, "num": 1 }])
+        );
+        let err = Packet::decode(payload.into(), limits).unwrap_err();
+        assert!(matches!(err, Error::TooManyAttachments(2)));
+    }
+
     #[test]
     fn packet_encode_event() {
         let payload = format!("2{}", json!(["event", { "data": "value™" }]));
@@ -0,0 +1,90 @@
+//! Presence tracking for rooms, obtained with [`SocketIo::presence`].
+//!
+//! When a room is tracked, a [`PRESENCE_JOIN_EVENT`] is emitted to the other members of the room
+//! each time a socket joins it, and a [`PRESENCE_LEAVE_EVENT`] is emitted to the remaining members
+//! each time a socket leaves it, either explicitly or because it disconnected.
+//! The sockets joining or leaving with the [`join`] and [`leave`] operators, e.g. `io.within("a").join("lobby")`,
+//! are notified like the ones calling [`Socket::join`] themselves.
+//! Both events are sent with a [`PresenceUpdate`] payload.
+//!
+//! #### Example
+//! ```
+//! # use socketioxide::{SocketIo, extract::SocketRef};
+//! let (_, io) = SocketIo::new_svc();
+//! io.ns("/", |socket: SocketRef| {
+//!     socket.join("lobby").unwrap();
+//! });
+//!
+//! // Every member of the "lobby" room will receive
+//! // `presence:join` and `presence:leave` events
+//! io.presence("/").unwrap().track("lobby");
+//! ```
+//!
+//! [`SocketIo::presence`]: crate::SocketIo#method.presence
+//! [`join`]: crate::operators::BroadcastOperators#method.join
+//! [`leave`]: crate::operators::BroadcastOperators#method.leave
+//! [`Socket::join`]: crate::socket::Socket#method.join
+use std::sync::Arc;
+
+use engineioxide::sid::Sid;
+use serde::Serialize;
+
+use crate::{
+    adapter::{Adapter, LocalAdapter, Room},
+    ns::Namespace,
+    operators::RoomParam,
+};
+
+/// The event emitted to the members of a tracked room when a socket joins it.
+pub const PRESENCE_JOIN_EVENT: &str = "presence:join";
+
+/// The event emitted to the remaining members of a tracked room when a socket leaves it.
+pub const PRESENCE_LEAVE_EVENT: &str = "presence:leave";
+
+/// The payload of the [`PRESENCE_JOIN_EVENT`] and [`PRESENCE_LEAVE_EVENT`] events.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PresenceUpdate {
+    /// The tracked room
+    pub room: Room,
+    /// The id of the socket that joined or left the room
+    pub sid: Sid,
+}
+
+/// A handle to configure the rooms whose presence is tracked on a namespace.
+pub struct Presence<A: Adapter = LocalAdapter> {
+    ns: Arc<Namespace<A>>,
+}
+
+impl<A: Adapter> Presence<A> {
+    pub(crate) fn new(ns: Arc<Namespace<A>>) -> Self {
+        Self { ns }
+    }
+
+    /// Starts tracking the presence of the given rooms.
+    pub fn track(&self, rooms: impl RoomParam) -> &Self {
+        self.ns
+            .presence_rooms
+            .write()
+            .unwrap()
+            .extend(rooms.into_room_iter());
+        self
+    }
+
+    /// Stops tracking the presence of the given rooms.
+    pub fn untrack(&self, rooms: impl RoomParam) -> &Self {
+        let mut tracked = self.ns.presence_rooms.write().unwrap();
+        for room in rooms.into_room_iter() {
+            tracked.remove(&room);
+        }
+        self
+    }
+
+    /// Returns true if the presence of the given room is tracked.
+    pub fn is_tracked(&self, room: impl Into<Room>) -> bool {
+        self.ns
+            .presence_rooms
+            .read()
+            .unwrap()
+            .contains(&room.into())
+    }
+}
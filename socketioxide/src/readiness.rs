@@ -0,0 +1,67 @@
+//! A readiness gate holding the namespace connections until the server is ready to accept them,
+//! set with [`SocketIoBuilder::readiness_gate`].
+//!
+//! While the gate is closed, the `CONNECT` packets of the clients are held: the middlewares and the
+//! connect handlers are not called until the gate is opened. If the gate is still closed after the
+//! configured max hold time, the connection is rejected with a `connect_error` packet.
+//!
+//! #### Example
+//! ```
+//! # use socketioxide::{SocketIo, extract::SocketRef, readiness::ReadinessGate};
+//! # use std::time::Duration;
+//! let gate = ReadinessGate::new();
+//! let (_, io) = SocketIo::builder()
+//!     .readiness_gate(gate.clone(), Duration::from_secs(5))
+//!     .build_svc();
+//! io.ns("/", |socket: SocketRef| {});
+//!
+//! // Once the cache is warmed for example, the held and the next connections are accepted
+//! gate.open();
+//! ```
+//!
+//! [`SocketIoBuilder::readiness_gate`]: crate::SocketIoBuilder#method.readiness_gate
+use std::{sync::Arc, time::Duration};
+
+use tokio::sync::watch;
+
+/// A gate shared by all the namespace connections, see the [module doc](self) for more details.
+///
+/// It is cheap to clone, all the clones control the same gate.
+#[derive(Debug, Clone)]
+pub struct ReadinessGate(Arc<watch::Sender<bool>>);
+
+impl ReadinessGate {
+    /// Creates a new closed [`ReadinessGate`].
+    pub fn new() -> Self {
+        Self(Arc::new(watch::channel(false).0))
+    }
+
+    /// Opens the gate, the held connections and the next ones are accepted.
+    pub fn open(&self) {
+        self.0.send_replace(true);
+    }
+
+    /// Closes the gate, the next connections are held until it is opened again.
+    pub fn close(&self) {
+        self.0.send_replace(false);
+    }
+
+    /// Returns true if the gate is open.
+    pub fn is_open(&self) -> bool {
+        *self.0.borrow()
+    }
+
+    /// Waits for the gate to be open, at most for `timeout`.
+    /// Returns false if the gate is still closed after the timeout.
+    pub(crate) async fn wait(&self, timeout: Duration) -> bool {
+        let mut rx = self.0.subscribe();
+        let ready = tokio::time::timeout(timeout, rx.wait_for(|open| *open)).await;
+        matches!(ready, Ok(Ok(_)))
+    }
+}
+
+impl Default for ReadinessGate {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,134 @@
+//! Handles to the sockets connected to any node of a cluster.
+//!
+//! A [`RemoteSocket`] is obtained with [`SocketIo::remote_socket`] from its [`Sid`].
+//! Its rooms and its [data](crate::socket::Socket#method.set_data) are read directly if the socket
+//! is connected to the current node, otherwise they are fetched from the node owning the socket
+//! with [`Adapter::fetch_socket`]:
+//! * The requesting node sends a [`SocketRequest`] to the other nodes.
+//! * The node owning the socket answers it by calling `recv_socket_request` on its namespace
+//! and sends back the [`SocketResponse`].
+//!
+//! With the default [`LocalAdapter`](crate::adapter::LocalAdapter), there are no other nodes.
+//!
+//! #### Example
+//! ```
+//! # use socketioxide::{SocketIo, extract::*};
+//! let (_, io) = SocketIo::new_svc();
+//! io.ns("/", |socket: SocketRef| {
+//!     socket.set_data("john").ok();
+//! });
+//! io.ns("/admin", |socket: SocketRef, io: SocketIo| {
+//!     socket.on("inspect", |io: SocketIo, Data::<socketioxide::socket::Sid>(sid)| async move {
+//!         let socket = io.remote_socket(sid);
+//!         let rooms = socket.rooms().await.unwrap();
+//!         let name: Option<String> = socket.data().await.unwrap();
+//!         println!("{sid} in {rooms:?}: {name:?}");
+//!     });
+//! });
+//! ```
+//!
+//! [`SocketIo::remote_socket`]: crate::SocketIo#method.remote_socket
+use std::sync::Arc;
+
+use engineioxide::sid::Sid;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    adapter::{Adapter, Room},
+    errors::RemoteSocketError,
+    ns::Namespace,
+};
+
+/// A request sent by the [`Adapter`] to the node owning the socket `sid`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SocketRequest {
+    /// The id of the requested socket.
+    pub sid: Sid,
+    /// What is requested.
+    pub kind: SocketRequestKind,
+}
+
+/// The kind of a [`SocketRequest`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SocketRequestKind {
+    /// The rooms of the socket.
+    Rooms,
+    /// The data of the socket.
+    Data,
+}
+
+/// The response to a [`SocketRequest`], sent back by the node owning the socket.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum SocketResponse {
+    /// The rooms of the socket, answering a [`SocketRequestKind::Rooms`] request.
+    Rooms(Vec<Room>),
+    /// The data of the socket if set, answering a [`SocketRequestKind::Data`] request.
+    Data(Option<Value>),
+}
+
+/// A handle to a socket connected to any node of the cluster,
+/// see the [module doc](self) for more details.
+pub struct RemoteSocket<A: Adapter> {
+    /// The socket id
+    pub id: Sid,
+    ns: Arc<Namespace<A>>,
+}
+
+impl<A: Adapter> RemoteSocket<A> {
+    pub(crate) fn new(id: Sid, ns: Arc<Namespace<A>>) -> Self {
+        Self { id, ns }
+    }
+
+    /// Fetches the rooms of the socket from the node owning it.
+    pub async fn rooms(&self) -> Result<Vec<Room>, RemoteSocketError<A::Error>> {
+        match self.fetch(SocketRequestKind::Rooms).await? {
+            SocketResponse::Rooms(rooms) => Ok(rooms),
+            SocketResponse::Data(_) => Err(RemoteSocketError::UnexpectedResponse),
+        }
+    }
+
+    /// Fetches the data of the socket from the node owning it.
+    /// Returns `None` if the [data](crate::socket::Socket#method.set_data) of the socket is not set.
+    pub async fn data<T: DeserializeOwned>(
+        &self,
+    ) -> Result<Option<T>, RemoteSocketError<A::Error>> {
+        match self.fetch(SocketRequestKind::Data).await? {
+            SocketResponse::Data(Some(data)) => serde_json::from_value(data)
+                .map(Some)
+                .map_err(RemoteSocketError::Decode),
+            SocketResponse::Data(None) => Ok(None),
+            SocketResponse::Rooms(_) => Err(RemoteSocketError::UnexpectedResponse),
+        }
+    }
+
+    async fn fetch(
+        &self,
+        kind: SocketRequestKind,
+    ) -> Result<SocketResponse, RemoteSocketError<A::Error>> {
+        let req = SocketRequest { sid: self.id, kind };
+        if let Some(res) = self.ns.recv_socket_request(&req) {
+            return Ok(res);
+        }
+        self.ns
+            .adapter
+            .fetch_socket(req)
+            .await?
+            .ok_or(RemoteSocketError::SocketGone(self.id))
+    }
+}
+
+impl<A: Adapter> Clone for RemoteSocket<A> {
+    fn clone(&self) -> Self {
+        Self::new(self.id, self.ns.clone())
+    }
+}
+
+impl<A: Adapter> std::fmt::Debug for RemoteSocket<A> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RemoteSocket")
+            .field("id", &self.id)
+            .field("ns", &self.ns.path)
+            .finish()
+    }
+}
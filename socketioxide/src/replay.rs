@@ -0,0 +1,165 @@
+//! Replay of the missed broadcasts, enabled with [`SocketIoBuilder::replay_buffer`].
+//!
+//! When enabled, each socket keeps a ring buffer of the last event packets broadcast to it.
+//! Each of these packets is given an increasing offset, sent to the client as the last argument of the event
+//! (as a string, like with the socket.io connection state recovery).
+//!
+//! A client detecting a gap can then ask for the packets it missed without reconnecting, by emitting a
+//! [`RESYNC_EVENT`] event with the last offset it received. The server can also trigger it with
+//! [`Socket::resync`].
+//!
+//! #### Example
+//! ```
+//! # use socketioxide::{SocketIo, extract::SocketRef};
+//! let (_, io) = SocketIo::builder().replay_buffer(128).build_svc();
+//! io.ns("/", |socket: SocketRef| {
+//!     socket.join("news").unwrap();
+//! });
+//!
+//! // Each member of the "news" room receives `["article", "hello", "<offset>"]`
+//! io.to("news").emit("article", "hello").ok();
+//! ```
+//!
+//! [`SocketIoBuilder::replay_buffer`]: crate::SocketIoBuilder#method.replay_buffer
+//! [`Socket::resync`]: crate::socket::Socket#method.resync
+use std::{borrow::Cow, collections::VecDeque};
+
+use serde_json::Value;
+
+use crate::{
+    errors::ResyncError,
+    packet::{Packet, PacketData},
+};
+
+/// The reserved event emitted by a client to replay the packets sent after the given offset.
+pub const RESYNC_EVENT: &str = "replay:resync";
+
+/// A ring buffer of the last event packets broadcast to a socket.
+#[derive(Debug)]
+pub(crate) struct ReplayBuffer {
+    capacity: usize,
+    last_offset: u64,
+    packets: VecDeque<(u64, Packet<'static>)>,
+}
+
+impl ReplayBuffer {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            last_offset: 0,
+            packets: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    /// The offset of the last recorded packet, 0 if no packet was recorded.
+    pub fn last_offset(&self) -> u64 {
+        self.last_offset
+    }
+
+    /// Gives the next offset to an event packet without acknowledgement, appends it
+    /// to the arguments of the event and records the packet. Other packets are ignored.
+    pub fn record(&mut self, packet: &mut Packet<'_>) {
+        let data = match &mut packet.inner {
+            PacketData::Event(_, data, None) => data,
+            PacketData::BinaryEvent(_, bin, None) => &mut bin.data,
+            _ => return,
+        };
+        self.last_offset += 1;
+        let offset = Value::String(self.last_offset.to_string());
+        match data {
+            Value::Array(args) => args.push(offset),
+            data => *data = Value::Array(vec![data.take(), offset]),
+        }
+
+        if self.capacity == 0 {
+            return;
+        }
+        if self.packets.len() == self.capacity {
+            self.packets.pop_front();
+        }
+        let inner = match packet.inner.clone() {
+            PacketData::Event(e, data, _) => {
+                PacketData::Event(Cow::Owned(e.into_owned()), data, None)
+            }
+            PacketData::BinaryEvent(e, bin, _) => {
+                PacketData::BinaryEvent(Cow::Owned(e.into_owned()), bin, None)
+            }
+            _ => unreachable!(),
+        };
+        let packet = Packet {
+            ns: packet.ns.clone(),
+            inner,
+        };
+        self.packets.push_back((self.last_offset, packet));
+    }
+
+    /// Returns the recorded packets sent after `offset`.
+    /// Fails if some of them were already dropped from the buffer.
+    pub fn since(&self, offset: u64) -> Result<Vec<Packet<'static>>, ResyncError> {
+        if offset >= self.last_offset {
+            return Ok(Vec::new());
+        }
+        let oldest = self
+            .packets
+            .front()
+            .map_or(self.last_offset + 1, |(o, _)| *o);
+        if offset + 1 < oldest {
+            return Err(ResyncError::OffsetExpired(oldest));
+        }
+        Ok(self
+            .packets
+            .iter()
+            .filter(|(o, _)| *o > offset)
+            .map(|(_, packet)| packet.clone())
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(i: u32) -> Packet<'static> {
+        Packet::event("/", "test", Value::from(i))
+    }
+
+    #[test]
+    fn record_appends_offset() {
+        let mut buffer = ReplayBuffer::new(2);
+        let mut packet = event(1);
+        buffer.record(&mut packet);
+        assert_eq!(packet.inner, event_data(1, "1"));
+        assert_eq!(buffer.last_offset(), 1);
+
+        // Acknowledgements and events with an ack id are not recorded
+        let mut ack = Packet::ack("/", Value::Null, 1);
+        buffer.record(&mut ack);
+        assert_eq!(ack, Packet::ack("/", Value::Null, 1));
+        assert_eq!(buffer.last_offset(), 1);
+    }
+
+    #[test]
+    fn since_offset() {
+        let mut buffer = ReplayBuffer::new(2);
+        for i in 1..=3 {
+            buffer.record(&mut event(i));
+        }
+        let replayed: Vec<_> = buffer
+            .since(1)
+            .unwrap()
+            .into_iter()
+            .map(|p| p.inner)
+            .collect();
+        assert_eq!(replayed, vec![event_data(2, "2"), event_data(3, "3")]);
+        assert_eq!(buffer.since(2).unwrap().len(), 1);
+        assert!(buffer.since(3).unwrap().is_empty());
+        assert!(matches!(
+            buffer.since(0),
+            Err(ResyncError::OffsetExpired(2))
+        ));
+    }
+
+    fn event_data(i: u32, offset: &str) -> PacketData<'static> {
+        PacketData::Event("test".into(), serde_json::json!([i, offset]), None)
+    }
+}
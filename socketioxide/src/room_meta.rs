@@ -0,0 +1,161 @@
+//! Metadata attached to the rooms, obtained with [`SocketIo::room_meta`] or [`Socket::room_meta`].
+//!
+//! The metadata are key/values stored in the adapter alongside the room memberships,
+//! e.g. the owner or the creation time of a room. Adapters sharing their state between multiple servers
+//! replicate them, so they can be read from any node of the cluster.
+//!
+//! They do not depend on the members of the room: they can be set before the first socket joins it
+//! and they are kept when the room is empty, until they are removed with [`RoomMeta::clear`].
+//!
+//! The capacity set with [`RoomMeta::set_capacity`] limits the number of members of the room:
+//! [`Socket::join`] and the [`join`] operator fail with a [`JoinError::RoomFull`] error once it is reached.
+//! It is stored under the [`CAPACITY_KEY`], apart from the user metadata: the keys starting with
+//! the [`RESERVED_PREFIX`] are rejected by [`RoomMeta::set`] and hidden from [`RoomMeta::all`].
+//!
+//! #### Example
+//! ```
+//! # use socketioxide::{SocketIo, extract::*};
+//! let (_, io) = SocketIo::new_svc();
+//! io.ns("/", |socket: SocketRef| {
+//!     socket.on("create", |socket: SocketRef, Data::<String>(room)| {
+//!         let meta = socket.room_meta(room.clone());
+//!         meta.set("owner", socket.id).unwrap();
+//!         meta.set_capacity(4).unwrap();
+//!         socket.join(room).unwrap();
+//!     });
+//! });
+//! ```
+//!
+//! [`SocketIo::room_meta`]: crate::SocketIo#method.room_meta
+//! [`Socket::room_meta`]: crate::socket::Socket#method.room_meta
+//! [`Socket::join`]: crate::socket::Socket#method.join
+//! [`join`]: crate::operators::BroadcastOperators#method.join
+//! [`JoinError::RoomFull`]: crate::JoinError::RoomFull
+use std::{collections::HashMap, sync::Arc};
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{
+    adapter::{Adapter, LocalAdapter, Room},
+    errors::RoomMetaError,
+    ns::Namespace,
+};
+
+/// The prefix of the metadata keys managed by socketioxide.
+/// They cannot be set or removed with [`RoomMeta::set`] and [`RoomMeta::remove`].
+pub const RESERVED_PREFIX: &str = "socketioxide:";
+
+/// The metadata key of the maximum number of members of a room,
+/// see [`RoomMeta::set_capacity`].
+pub const CAPACITY_KEY: &str = "socketioxide:capacity";
+
+/// A handle to the metadata of a room of a namespace.
+pub struct RoomMeta<A: Adapter = LocalAdapter> {
+    ns: Arc<Namespace<A>>,
+    room: Room,
+}
+
+impl<A: Adapter> RoomMeta<A> {
+    pub(crate) fn new(ns: Arc<Namespace<A>>, room: Room) -> Self {
+        Self { ns, room }
+    }
+
+    /// Returns the room of this handle.
+    pub fn room(&self) -> &Room {
+        &self.room
+    }
+
+    /// Sets the metadata `key` of the room to `value`, replacing the previous value.
+    ///
+    /// ## Errors
+    /// * If the key starts with the [`RESERVED_PREFIX`] a [`RoomMetaError::ReservedKey`] is returned.
+    /// * If the value cannot be serialized a [`RoomMetaError::Serialize`] is returned.
+    /// * If the adapter fails to store it a [`RoomMetaError::Adapter`] is returned.
+    pub fn set<T: Serialize>(&self, key: &str, value: T) -> Result<(), RoomMetaError<A::Error>> {
+        check_key(key)?;
+        let value = serde_json::to_value(value).map_err(RoomMetaError::Serialize)?;
+        Ok(self
+            .ns
+            .adapter
+            .set_room_meta(self.room.clone(), key, value)?)
+    }
+
+    /// Gets the metadata `key` of the room. The reserved keys are never returned.
+    pub fn get(&self, key: &str) -> Result<Option<Value>, RoomMetaError<A::Error>> {
+        Ok(self.all()?.remove(key))
+    }
+
+    /// Gets all the metadata of the room, without the reserved keys.
+    pub fn all(&self) -> Result<HashMap<String, Value>, RoomMetaError<A::Error>> {
+        let mut meta = self.ns.adapter.room_meta(&self.room)?;
+        meta.retain(|key, _| !key.starts_with(RESERVED_PREFIX));
+        Ok(meta)
+    }
+
+    /// Removes the metadata `key` of the room.
+    ///
+    /// ## Errors
+    /// If the key starts with the [`RESERVED_PREFIX`] a [`RoomMetaError::ReservedKey`] is returned.
+    pub fn remove(&self, key: &str) -> Result<(), RoomMetaError<A::Error>> {
+        check_key(key)?;
+        Ok(self.ns.adapter.del_room_meta(&self.room, Some(key))?)
+    }
+
+    /// Removes all the metadata of the room, including its capacity.
+    pub fn clear(&self) -> Result<(), RoomMetaError<A::Error>> {
+        Ok(self.ns.adapter.del_room_meta(&self.room, None)?)
+    }
+
+    /// Limits the number of members of the room, stored under the reserved [`CAPACITY_KEY`].
+    /// Once it is reached, the sockets trying to join the room with [`Socket::join`] or the [`join`] operator
+    /// get a [`JoinError::RoomFull`] error.
+    ///
+    /// The sockets that are already members of the room are kept if the capacity is lowered below their count.
+    ///
+    /// [`Socket::join`]: crate::socket::Socket#method.join
+    /// [`join`]: crate::operators::BroadcastOperators#method.join
+    /// [`JoinError::RoomFull`]: crate::JoinError::RoomFull
+    pub fn set_capacity(&self, capacity: usize) -> Result<(), RoomMetaError<A::Error>> {
+        Ok(self
+            .ns
+            .adapter
+            .set_room_meta(self.room.clone(), CAPACITY_KEY, capacity.into())?)
+    }
+
+    /// Removes the capacity of the room set with [`set_capacity()`](#method.set_capacity).
+    pub fn remove_capacity(&self) -> Result<(), RoomMetaError<A::Error>> {
+        Ok(self
+            .ns
+            .adapter
+            .del_room_meta(&self.room, Some(CAPACITY_KEY))?)
+    }
+
+    /// Gets the capacity of the room set with [`set_capacity()`](#method.set_capacity).
+    pub fn capacity(&self) -> Result<Option<usize>, RoomMetaError<A::Error>> {
+        Ok(self.stored_capacity()?)
+    }
+
+    /// Reads the capacity from the adapter, for the joins.
+    pub(crate) fn stored_capacity(&self) -> Result<Option<usize>, A::Error> {
+        let capacity = self.ns.adapter.room_meta(&self.room)?.remove(CAPACITY_KEY);
+        Ok(capacity.and_then(|c| c.as_u64()).map(|c| c as usize))
+    }
+}
+
+/// Rejects the keys managed by socketioxide.
+fn check_key<E>(key: &str) -> Result<(), RoomMetaError<E>> {
+    match key.starts_with(RESERVED_PREFIX) {
+        true => Err(RoomMetaError::ReservedKey(key.to_string())),
+        false => Ok(()),
+    }
+}
+
+impl<A: Adapter> std::fmt::Debug for RoomMeta<A> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RoomMeta")
+            .field("ns", &self.ns.path)
+            .field("room", &self.room)
+            .finish()
+    }
+}
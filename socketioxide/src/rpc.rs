@@ -0,0 +1,151 @@
+//! Request/response RPCs over an event and its acknowledgement.
+//!
+//! A handler registered with [`Socket::on_rpc`] receives the request deserialized from the event data
+//! and returns a `Result`. The result is sent back through the acknowledgement of the event as an [`RpcResponse`]:
+//! * `{ "result": <response> }` if the handler succeeded
+//! * `{ "error": { "code": <code>, "message": <message> } }` if it failed
+//!   or if the request could not be deserialized, with the `bad_request` code.
+//!
+//! The client should emit the event with an acknowledgement callback, otherwise the response is dropped.
+//!
+//! #### Example
+//! ```
+//! # use socketioxide::{SocketIo, extract::*, rpc::RpcError};
+//! # use serde::{Serialize, Deserialize};
+//! #[derive(Deserialize)]
+//! struct Divide {
+//!     a: i64,
+//!     b: i64,
+//! }
+//!
+//! let (_, io) = SocketIo::new_svc();
+//! io.ns("/", |socket: SocketRef| {
+//!     socket.on_rpc("divide", |Data(req): Data<Divide>| async move {
+//!         match req.b {
+//!             0 => Err(RpcError::new("division_by_zero", "cannot divide by zero")),
+//!             b => Ok(req.a / b),
+//!         }
+//!     });
+//! });
+//! ```
+//!
+//! [`Socket::on_rpc`]: crate::socket::Socket#method.on_rpc
+use std::{borrow::Cow, future::Future};
+
+use futures_util::future::BoxFuture;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    adapter::Adapter,
+    extract::{Data, SocketRef},
+};
+
+/// The error returned by an RPC handler, sent to the client in the `error` field of the [`RpcResponse`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RpcError {
+    /// A machine-readable code identifying the error, e.g. `not_found`
+    pub code: Cow<'static, str>,
+    /// A human-readable description of the error
+    pub message: String,
+}
+
+impl RpcError {
+    /// The code of the error sent when the request could not be deserialized.
+    pub const BAD_REQUEST: &'static str = "bad_request";
+    /// The code of the error sent when the response could not be serialized.
+    pub const INTERNAL: &'static str = "internal";
+
+    /// Creates a new error with the given code and message.
+    pub fn new(code: impl Into<Cow<'static, str>>, message: impl Into<String>) -> Self {
+        Self {
+            code: code.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for RpcError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.code, self.message)
+    }
+}
+
+impl std::error::Error for RpcError {}
+
+/// The payload of the acknowledgement sent in response to an RPC,
+/// see the [module doc](self) for more details.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RpcResponse<T = Value> {
+    /// The response of a successful call
+    Result(T),
+    /// The error of a failed call
+    Error(RpcError),
+}
+
+impl<T> From<Result<T, RpcError>> for RpcResponse<T> {
+    fn from(res: Result<T, RpcError>) -> Self {
+        match res {
+            Ok(res) => RpcResponse::Result(res),
+            Err(e) => RpcResponse::Error(e),
+        }
+    }
+}
+
+/// A handler for the calls of an RPC method, registered with [`Socket::on_rpc`].
+///
+/// It is implemented for async closures taking the request as a [`Data`] extractor,
+/// and optionally a [`SocketRef`] as first argument, and returning a `Result<Resp, RpcError>`.
+///
+/// [`Socket::on_rpc`]: crate::socket::Socket#method.on_rpc
+pub trait RpcHandler<A: Adapter, T>: Send + Sync + 'static {
+    /// Calls the handler with the data of the event,
+    /// returning the serialized response or the error to send back.
+    #[doc(hidden)]
+    fn call(&self, s: SocketRef<A>, req: Value) -> BoxFuture<'static, Result<Value, RpcError>>;
+}
+
+/// Deserializes the request of an RPC, with the same rules as the [`Data`] extractor.
+fn request<A: Adapter, Req: DeserializeOwned>(
+    s: &SocketRef<A>,
+    req: Value,
+) -> Result<Req, RpcError> {
+    crate::extract::deserialize(req, s.deny_unknown_fields())
+        .map_err(|e| RpcError::new(RpcError::BAD_REQUEST, s.deser_error_message(&e)))
+}
+
+/// Serializes the response of an RPC.
+fn response<Resp: Serialize>(res: Result<Resp, RpcError>) -> Result<Value, RpcError> {
+    serde_json::to_value(res?).map_err(|e| RpcError::new(RpcError::INTERNAL, e.to_string()))
+}
+
+impl<A, F, Fut, Req, Resp> RpcHandler<A, (Data<Req>,)> for F
+where
+    A: Adapter,
+    F: FnOnce(Data<Req>) -> Fut + Send + Sync + Clone + 'static,
+    Fut: Future<Output = Result<Resp, RpcError>> + Send + 'static,
+    Req: DeserializeOwned + Send + Sync + 'static,
+    Resp: Serialize + Send + 'static,
+{
+    fn call(&self, s: SocketRef<A>, req: Value) -> BoxFuture<'static, Result<Value, RpcError>> {
+        let req = request(&s, req);
+        let f = self.clone();
+        Box::pin(async move { response(f(Data(req?)).await) })
+    }
+}
+
+impl<A, F, Fut, Req, Resp> RpcHandler<A, (SocketRef<A>, Data<Req>)> for F
+where
+    A: Adapter,
+    F: FnOnce(SocketRef<A>, Data<Req>) -> Fut + Send + Sync + Clone + 'static,
+    Fut: Future<Output = Result<Resp, RpcError>> + Send + 'static,
+    Req: DeserializeOwned + Send + Sync + 'static,
+    Resp: Serialize + Send + 'static,
+{
+    fn call(&self, s: SocketRef<A>, req: Value) -> BoxFuture<'static, Result<Value, RpcError>> {
+        let req = request(&s, req);
+        let f = self.clone();
+        Box::pin(async move { response(f(s, Data(req?)).await) })
+    }
+}
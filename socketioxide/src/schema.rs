@@ -0,0 +1,477 @@
+//! Validation of the outgoing event payloads against a JSON schema, to catch the wrong payloads emitted by mistake.
+//!
+//! The schemas are registered per event name with [`SocketIo::register_schema`], and every event emitted
+//! to the clients with this name is validated before being sent. The data is validated as it is given to `emit`,
+//! once serialized: e.g. an object for a struct, or an array for multiple arguments.
+//! The events without a registered schema are not validated.
+//!
+//! On a mismatch, the [`SchemaPolicy`] set with [`SocketIoBuilder::schema_policy`] either fails the emit
+//! with a [`SchemaError`] describing the mismatch, or only logs it with the `tracing` feature.
+//!
+//! The validation has a runtime cost for each emit, so it is only available with the `schema` feature,
+//! which is meant to be enabled in development and tests.
+//!
+//! ## Supported keywords
+//! A subset of the JSON schema keywords is supported:
+//! `type`, `enum`, `const`, `properties`, `required`, `additionalProperties`, `items`,
+//! `minItems`, `maxItems`, `minLength`, `maxLength`, `minimum`, `maximum` and `anyOf`.
+//! The annotations (`$schema`, `$id`, `$comment`, `title`, `description`, `default` and `examples`)
+//! are accepted and have no effect. [`SocketIo::register_schema`] returns an [`InvalidSchemaError`]
+//! for the schemas using any other keyword or a keyword with a value of the wrong type,
+//! rather than silently accepting the payloads it would not match.
+//!
+//! #### Example
+//! ```
+//! # use socketioxide::{SocketIo, SendError, extract::SocketRef};
+//! # use serde_json::json;
+//! let (_, io) = SocketIo::new_svc();
+//! io.register_schema("score", json!({
+//!     "type": "object",
+//!     "properties": { "points": { "type": "integer", "minimum": 0 } },
+//!     "required": ["points"],
+//! })).unwrap();
+//! io.ns("/", |socket: SocketRef| {
+//!     socket.emit("score", json!({ "points": 3 })).unwrap();
+//!     let res = socket.emit("score", json!({ "points": "3" }));
+//!     assert!(matches!(res, Err(SendError::Schema(_))));
+//! });
+//!
+//! let res = io.register_schema("name", json!({ "type": "string", "pattern": "^[a-z]+$" }));
+//! assert_eq!(res.unwrap_err().to_string(), "invalid schema at /pattern: unsupported keyword");
+//! ```
+//!
+//! [`SchemaError`]: crate::SchemaError
+//! [`SocketIo::register_schema`]: crate::SocketIo#method.register_schema
+//! [`SocketIoBuilder::schema_policy`]: crate::SocketIoBuilder#method.schema_policy
+use std::{collections::HashMap, sync::RwLock};
+
+use serde_json::Value;
+
+use crate::errors::SchemaError;
+
+/// The behavior when an outgoing payload doesn't match the schema of its event.
+/// It is set with [`SocketIoBuilder::schema_policy`](crate::SocketIoBuilder#method.schema_policy).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SchemaPolicy {
+    /// The emit fails with a [`SendError::Schema`](crate::SendError::Schema) or a
+    /// [`BroadcastError::Schema`](crate::BroadcastError::Schema) error, and nothing is sent.
+    #[default]
+    Reject,
+    /// The mismatch is logged with the `tracing` feature and the payload is still sent.
+    Log,
+}
+
+/// Error type returned by [`SocketIo::register_schema`] when the schema cannot be validated,
+/// see the [supported keywords](crate::schema#supported-keywords).
+///
+/// [`SocketIo::register_schema`]: crate::SocketIo#method.register_schema
+#[derive(thiserror::Error, Debug)]
+#[error("invalid schema at {path}: {reason}")]
+pub struct InvalidSchemaError {
+    /// The path of the invalid keyword in the schema.
+    pub path: String,
+    /// The reason why the keyword is invalid.
+    pub reason: String,
+}
+
+/// The keywords without effect on the validation, accepted in the schemas.
+const ANNOTATIONS: [&str; 7] = [
+    "$schema",
+    "$id",
+    "$comment",
+    "title",
+    "description",
+    "default",
+    "examples",
+];
+
+/// The schemas of the outgoing events, shared by all the namespaces.
+#[derive(Debug, Default)]
+pub(crate) struct SchemaRegistry {
+    schemas: RwLock<HashMap<String, Value>>,
+    policy: SchemaPolicy,
+}
+
+impl SchemaRegistry {
+    pub fn new(policy: SchemaPolicy) -> Self {
+        Self {
+            schemas: RwLock::default(),
+            policy,
+        }
+    }
+
+    pub fn register(&self, event: String, schema: Value) -> Result<(), InvalidSchemaError> {
+        check_schema(&schema, "")?;
+        self.schemas.write().unwrap().insert(event, schema);
+        Ok(())
+    }
+
+    /// Validates the data of an outgoing event, returning an error if it should not be sent.
+    pub fn check(&self, event: &str, data: &Value) -> Result<(), SchemaError> {
+        let schemas = self.schemas.read().unwrap();
+        let Some(schema) = schemas.get(event) else {
+            return Ok(());
+        };
+        let Err(reason) = validate(schema, data, "") else {
+            return Ok(());
+        };
+        let err = SchemaError {
+            event: event.to_string(),
+            reason,
+        };
+        match self.policy {
+            SchemaPolicy::Reject => Err(err),
+            SchemaPolicy::Log => {
+                #[cfg(feature = "tracing")]
+                tracing::warn!("{}", err);
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Checks that the schema only uses the supported keywords, with values of the right type.
+fn check_schema(schema: &Value, path: &str) -> Result<(), InvalidSchemaError> {
+    let schema = match schema {
+        Value::Bool(_) => return Ok(()),
+        Value::Object(schema) => schema,
+        _ => {
+            return Err(InvalidSchemaError {
+                path: display(path).to_string(),
+                reason: "expected an object or a boolean".to_string(),
+            })
+        }
+    };
+    for (keyword, value) in schema {
+        let path = format!("{path}/{keyword}");
+        let valid = match keyword.as_str() {
+            "type" => match value {
+                Value::String(ty) => is_known_type(ty),
+                Value::Array(types) => types
+                    .iter()
+                    .all(|ty| ty.as_str().is_some_and(is_known_type)),
+                _ => false,
+            },
+            "enum" => value.is_array(),
+            "const" => true,
+            "required" => value
+                .as_array()
+                .is_some_and(|keys| keys.iter().all(Value::is_string)),
+            "properties" => match value {
+                Value::Object(properties) => {
+                    for (key, schema) in properties {
+                        check_schema(schema, &format!("{path}/{key}"))?;
+                    }
+                    true
+                }
+                _ => false,
+            },
+            "additionalProperties" | "items" => {
+                check_schema(value, &path)?;
+                true
+            }
+            "anyOf" => match value {
+                Value::Array(schemas) if !schemas.is_empty() => {
+                    for (i, schema) in schemas.iter().enumerate() {
+                        check_schema(schema, &format!("{path}/{i}"))?;
+                    }
+                    true
+                }
+                _ => false,
+            },
+            "minItems" | "maxItems" | "minLength" | "maxLength" => value.is_u64(),
+            "minimum" | "maximum" => value.is_number(),
+            keyword if ANNOTATIONS.contains(&keyword) => true,
+            _ => {
+                return Err(InvalidSchemaError {
+                    path,
+                    reason: "unsupported keyword".to_string(),
+                })
+            }
+        };
+        if !valid {
+            return Err(InvalidSchemaError {
+                path,
+                reason: format!("invalid value {value}"),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Validates the value against the schema, returning the path and the reason of the first mismatch.
+fn validate(schema: &Value, value: &Value, path: &str) -> Result<(), String> {
+    let Value::Object(schema) = schema else {
+        // A `true` schema or a non object one accepts everything, a `false` one nothing
+        return match schema {
+            Value::Bool(false) => Err(format!("{}: no value is allowed", display(path))),
+            _ => Ok(()),
+        };
+    };
+    let fail = |reason: String| Err(format!("{}: {reason}", display(path)));
+
+    if let Some(types) = schema.get("type") {
+        let matches = match types {
+            Value::String(ty) => is_type(ty, value),
+            Value::Array(types) => types
+                .iter()
+                .filter_map(Value::as_str)
+                .any(|ty| is_type(ty, value)),
+            _ => true,
+        };
+        if !matches {
+            return fail(format!("expected type {types}, got {value}"));
+        }
+    }
+    if let Some(Value::Array(values)) = schema.get("enum") {
+        if !values.contains(value) {
+            return fail(format!(
+                "{value} is not one of {}",
+                Value::Array(values.clone())
+            ));
+        }
+    }
+    if let Some(expected) = schema.get("const") {
+        if expected != value {
+            return fail(format!("expected {expected}, got {value}"));
+        }
+    }
+    if let Some(Value::Array(schemas)) = schema.get("anyOf") {
+        if !schemas.iter().any(|s| validate(s, value, path).is_ok()) {
+            return fail(format!("{value} does not match any of the schemas"));
+        }
+    }
+
+    match value {
+        Value::Object(obj) => {
+            if let Some(Value::Array(required)) = schema.get("required") {
+                if let Some(key) = required
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .find(|key| !obj.contains_key(*key))
+                {
+                    return fail(format!("missing required property {key:?}"));
+                }
+            }
+            let properties = schema.get("properties").and_then(Value::as_object);
+            for (key, value) in obj {
+                let path = format!("{path}/{key}");
+                match (
+                    properties.and_then(|p| p.get(key)),
+                    schema.get("additionalProperties"),
+                ) {
+                    (Some(schema), _) | (None, Some(schema)) => validate(schema, value, &path)?,
+                    (None, None) => (),
+                }
+            }
+        }
+        Value::Array(items) => {
+            let len = items.len() as u64;
+            if let Some(min) = schema.get("minItems").and_then(Value::as_u64) {
+                if len < min {
+                    return fail(format!("expected at least {min} items, got {len}"));
+                }
+            }
+            if let Some(max) = schema.get("maxItems").and_then(Value::as_u64) {
+                if len > max {
+                    return fail(format!("expected at most {max} items, got {len}"));
+                }
+            }
+            if let Some(schema) = schema.get("items") {
+                for (i, item) in items.iter().enumerate() {
+                    validate(schema, item, &format!("{path}/{i}"))?;
+                }
+            }
+        }
+        Value::String(s) => {
+            let len = s.chars().count() as u64;
+            if let Some(min) = schema.get("minLength").and_then(Value::as_u64) {
+                if len < min {
+                    return fail(format!("expected at least {min} characters, got {len}"));
+                }
+            }
+            if let Some(max) = schema.get("maxLength").and_then(Value::as_u64) {
+                if len > max {
+                    return fail(format!("expected at most {max} characters, got {len}"));
+                }
+            }
+        }
+        Value::Number(n) => {
+            let n = n.as_f64().unwrap_or_default();
+            if let Some(min) = schema.get("minimum").and_then(Value::as_f64) {
+                if n < min {
+                    return fail(format!("{n} is less than the minimum {min}"));
+                }
+            }
+            if let Some(max) = schema.get("maximum").and_then(Value::as_f64) {
+                if n > max {
+                    return fail(format!("{n} is greater than the maximum {max}"));
+                }
+            }
+        }
+        Value::Bool(_) | Value::Null => (),
+    }
+    Ok(())
+}
+
+fn is_type(ty: &str, value: &Value) -> bool {
+    match ty {
+        "null" => value.is_null(),
+        "boolean" => value.is_boolean(),
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => {
+            value.is_i64() || value.is_u64() || value.as_f64().is_some_and(|n| n.fract() == 0.0)
+        }
+        _ => true,
+    }
+}
+
+fn is_known_type(ty: &str) -> bool {
+    matches!(
+        ty,
+        "null" | "boolean" | "object" | "array" | "string" | "number" | "integer"
+    )
+}
+
+fn display(path: &str) -> &str {
+    match path {
+        "" => "/",
+        path => path,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{check_schema, validate};
+
+    #[test]
+    fn validate_keywords() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "name": { "type": "string", "minLength": 1, "maxLength": 3 },
+                "age": { "type": "integer", "minimum": 0, "maximum": 150 },
+                "tags": { "type": "array", "items": { "enum": ["a", "b"] }, "maxItems": 2 },
+                "kind": { "const": "user" },
+                "id": { "anyOf": [{ "type": "string" }, { "type": "integer" }] },
+            },
+            "required": ["name"],
+            "additionalProperties": false,
+        });
+        let ok = json!({ "name": "bob", "age": 3, "tags": ["a"], "kind": "user", "id": 1 });
+        assert_eq!(validate(&schema, &ok, ""), Ok(()));
+
+        let cases = [
+            (json!([]), "/: expected type \"object\", got []"),
+            (json!({}), "/: missing required property \"name\""),
+            (
+                json!({ "name": "" }),
+                "/name: expected at least 1 characters, got 0",
+            ),
+            (
+                json!({ "name": "bobby" }),
+                "/name: expected at most 3 characters, got 5",
+            ),
+            (
+                json!({ "name": "b", "age": 1.5 }),
+                "/age: expected type \"integer\", got 1.5",
+            ),
+            (
+                json!({ "name": "b", "age": -1 }),
+                "/age: -1 is less than the minimum 0",
+            ),
+            (
+                json!({ "name": "b", "tags": ["c"] }),
+                "/tags/0: \"c\" is not one of [\"a\",\"b\"]",
+            ),
+            (
+                json!({ "name": "b", "tags": ["a", "a", "b"] }),
+                "/tags: expected at most 2 items, got 3",
+            ),
+            (
+                json!({ "name": "b", "kind": "admin" }),
+                "/kind: expected \"user\", got \"admin\"",
+            ),
+            (
+                json!({ "name": "b", "id": true }),
+                "/id: true does not match any of the schemas",
+            ),
+            (
+                json!({ "name": "b", "other": 1 }),
+                "/other: no value is allowed",
+            ),
+        ];
+        for (value, err) in cases {
+            assert_eq!(
+                validate(&schema, &value, ""),
+                Err(err.to_string()),
+                "{value}"
+            );
+        }
+    }
+
+    #[test]
+    fn check_schema_keywords() {
+        let schema = json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "title": "user",
+            "type": ["object", "null"],
+            "properties": {
+                "name": { "type": "string", "minLength": 1, "description": "the name" },
+                "tags": { "items": { "enum": ["a", "b"] }, "maxItems": 2 },
+                "id": { "anyOf": [{ "type": "string" }, true] },
+            },
+            "required": ["name"],
+            "additionalProperties": false,
+        });
+        assert!(check_schema(&schema, "").is_ok());
+
+        let cases = [
+            (
+                json!(1),
+                "invalid schema at /: expected an object or a boolean",
+            ),
+            (
+                json!({ "pattern": "^a" }),
+                "invalid schema at /pattern: unsupported keyword",
+            ),
+            (
+                json!({ "properties": { "a": { "format": "email" } } }),
+                "invalid schema at /properties/a/format: unsupported keyword",
+            ),
+            (
+                json!({ "items": { "items": { "oneOf": [] } } }),
+                "invalid schema at /items/items/oneOf: unsupported keyword",
+            ),
+            (
+                json!({ "anyOf": [{ "type": "string" }, { "not": {} }] }),
+                "invalid schema at /anyOf/1/not: unsupported keyword",
+            ),
+            (
+                json!({ "type": "int" }),
+                "invalid schema at /type: invalid value \"int\"",
+            ),
+            (
+                json!({ "required": "name" }),
+                "invalid schema at /required: invalid value \"name\"",
+            ),
+            (
+                json!({ "maxLength": -1 }),
+                "invalid schema at /maxLength: invalid value -1",
+            ),
+            (
+                json!({ "anyOf": [] }),
+                "invalid schema at /anyOf: invalid value []",
+            ),
+        ];
+        for (schema, err) in cases {
+            let res = check_schema(&schema, "");
+            assert_eq!(res.unwrap_err().to_string(), err, "{schema}");
+        }
+    }
+}
@@ -43,7 +43,11 @@
 //! }
 //! ```
 
-use engineioxide::service::{EngineIoService, MakeEngineIoService};
+use engineioxide::{
+    service::{EngineIoService, MakeEngineIoService},
+    sid::Sid,
+    Transport,
+};
 use http::{Request, Response};
 use http_body::Body;
 use hyper::service::Service as HyperSvc;
@@ -116,6 +120,19 @@ impl<A: Adapter, S: Clone> SocketIoService<S, A> {
         self.engine_svc.into_make_service()
     }
 
+    /// Connects a client through a custom [`Transport`], e.g. an in-memory
+    /// [`MemoryTransport`](engineioxide::memory::MemoryTransport), and returns the id of its engine.io session.
+    /// The request is used as the request of the session (headers, uri, extensions...).
+    ///
+    /// The client must then connect to the namespaces with socket.io `CONNECT` packets as with any other transport.
+    ///
+    /// Returns `None` if the [`SocketIoBuilder::max_connections`](crate::SocketIoBuilder#method.max_connections)
+    /// limit is reached.
+    #[inline]
+    pub fn connect<T: Transport, B>(&self, transport: T, req: Request<B>) -> Option<Sid> {
+        self.engine_svc.connect(transport, req)
+    }
+
     /// Creates a new [`EngineIoService`] with a custom inner service and a custom config.
     pub(crate) fn with_config_inner(
         inner: S,
@@ -2,39 +2,68 @@
 //! The socket struct itself should not be used directly, but through a [`SocketRef`](crate::extract::SocketRef).
 use std::{
     borrow::Cow,
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     fmt::Debug,
+    future::Future,
+    panic::AssertUnwindSafe,
     sync::Mutex,
     sync::{
-        atomic::{AtomicBool, AtomicI64, Ordering},
-        Arc, RwLock,
+        atomic::{AtomicBool, AtomicI64, AtomicU64, AtomicUsize, Ordering},
+        Arc, OnceLock, RwLock,
     },
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 use bytes::Bytes;
-use engineioxide::socket::{DisconnectReason as EIoDisconnectReason, Permit};
+use engineioxide::{
+    socket::{DisconnectReason as EIoDisconnectReason, Permit},
+    Str,
+};
+use futures_util::{future::BoxFuture, FutureExt};
 use serde::{de::DeserializeOwned, Serialize};
 use serde_json::Value;
-use tokio::sync::oneshot::{self, Receiver};
+use tokio::{
+    sync::{
+        mpsc,
+        oneshot::{self, Receiver},
+        Notify,
+    },
+    task::AbortHandle,
+};
 
 #[cfg(feature = "extensions")]
 use crate::extensions::Extensions;
 
 use crate::{
-    ack::{AckInnerStream, AckResponse, AckResult, AckStream},
+    ack::{AckHandle, AckInnerStream, AckMap, AckResponse, AckResult, AckStream},
     adapter::{Adapter, LocalAdapter, Room},
-    errors::{DisconnectError, Error, SendError},
+    binary_stream::BinaryStream,
+    clock::{ServerTime, TIME_EVENT},
+    context::{SerializeWithContext, WithContext},
+    errors::{
+        DisconnectError, Error, JoinError, RawSendError, ResyncError, SendError,
+        SwitchNamespaceError,
+    },
+    extract::{AckSender, Data, SocketRef},
     handler::{
         BoxedDisconnectHandler, BoxedMessageHandler, DisconnectHandler, MakeErasedHandler,
         MessageHandler,
     },
+    io::{DisconnectingEventPolicy, HandlerPanicPolicy, MaxListenersPolicy, UnknownEventPolicy},
+    lifecycle::{BoxedLeaveHook, DropEvent, DropReason, LifecycleEvent},
+    migration::{Migration, MIGRATE_EVENT},
     ns::Namespace,
     operators::{
         holding::{WithBinary, WithoutBinary},
         BroadcastOperators, ConfOperators, RoomParam,
     },
     packet::{BinaryPacket, Packet, PacketData},
+    presence::{PresenceUpdate, PRESENCE_JOIN_EVENT, PRESENCE_LEAVE_EVENT},
+    replay::{ReplayBuffer, RESYNC_EVENT},
+    room_meta::RoomMeta,
+    rpc::{RpcHandler, RpcResponse},
+    state_sync::{StateSnapshot, SNAPSHOT_EVENT},
+    typed::TypedEvent,
     AckError, SocketIo,
 };
 use crate::{
@@ -44,6 +73,58 @@ use crate::{
 
 pub use engineioxide::sid::Sid;
 
+/// The reserved field of the object appended as the last argument of the emitted events
+/// when the [packet ids](crate::SocketIoBuilder#method.packet_ids) are enabled.
+pub const PACKET_ID_KEY: &str = "_pid";
+
+/// The settings of a single socket overriding the global [`SocketIoConfig`] ones,
+/// set with [`Socket::configure`].
+///
+/// The fields left to `None` keep their current value.
+///
+/// [`SocketIoConfig`]: crate::SocketIoConfig
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SocketConfig {
+    /// The default timeout of the acknowledgements requested to the socket,
+    /// overriding [`SocketIoConfig::ack_timeout`](crate::SocketIoConfig::ack_timeout).
+    pub ack_timeout: Option<Duration>,
+
+    /// The maximum number of rooms the socket can be in,
+    /// overriding [`SocketIoConfig::max_rooms_per_socket`](crate::SocketIoConfig::max_rooms_per_socket).
+    pub max_rooms: Option<usize>,
+
+    /// The maximum number of event handlers that can be registered on the socket,
+    /// overriding [`SocketIoConfig::max_listeners`](crate::SocketIoConfig::max_listeners).
+    pub max_listeners: Option<usize>,
+}
+
+/// The throughput of a [`Socket`] since it connected to its namespace, returned by [`Socket::stats`].
+///
+/// The sizes are the ones of the socket.io packets and their binary attachments,
+/// without the framing of the engine.io transport.
+///
+/// It can also be used as an extractor.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SocketStats {
+    /// The number of events received from the client.
+    pub events_received: u64,
+    /// The number of events sent to the client, including the broadcasts.
+    pub events_sent: u64,
+    /// The number of bytes received from the client.
+    pub bytes_in: u64,
+    /// The number of bytes sent to the client.
+    pub bytes_out: u64,
+}
+
+/// The counters behind [`SocketStats`], updated on the receive and send paths.
+#[derive(Debug, Default)]
+struct StatsCounters {
+    events_received: AtomicU64,
+    events_sent: AtomicU64,
+    bytes_in: AtomicU64,
+    bytes_out: AtomicU64,
+}
+
 /// All the possible reasons for a [`Socket`] to be disconnected from a namespace.
 ///
 /// It can be used as an extractor in the [`on_disconnect`](crate::handler::disconnect) handler.
@@ -72,6 +153,29 @@ pub enum DisconnectReason {
 
     /// The server is being closed
     ClosingServer,
+
+    /// The server is being gracefully shut down with
+    /// [`SocketIo::serve_with_graceful_shutdown`](crate::SocketIo::serve_with_graceful_shutdown)
+    ServerShutdown,
+
+    /// The socket did not have any activity during the [`SocketIoBuilder::idle_timeout`] delay
+    ///
+    /// [`SocketIoBuilder::idle_timeout`]: crate::SocketIoBuilder#method.idle_timeout
+    IdleTimeout,
+
+    /// An event handler panicked with the [`HandlerPanicPolicy::Disconnect`] policy
+    ///
+    /// [`HandlerPanicPolicy::Disconnect`]: crate::HandlerPanicPolicy::Disconnect
+    HandlerPanic,
+
+    /// The socket received more bytes than its [`SocketIoBuilder::byte_quota`] during a window
+    ///
+    /// [`SocketIoBuilder::byte_quota`]: crate::SocketIoBuilder#method.byte_quota
+    QuotaExceeded,
+
+    /// The socket was requested to reconnect to another node with [`Socket::request_reconnect`]
+    /// and acknowledged it
+    Migrating,
 }
 
 impl std::fmt::Display for DisconnectReason {
@@ -86,6 +190,11 @@ impl std::fmt::Display for DisconnectReason {
             ClientNSDisconnect => "client has manually disconnected the socket from the namespace",
             ServerNSDisconnect => "socket was forcefully disconnected from the namespace",
             ClosingServer => "server is being closed",
+            ServerShutdown => "server is being gracefully shut down",
+            IdleTimeout => "socket did not have any activity in time",
+            HandlerPanic => "an event handler panicked",
+            QuotaExceeded => "socket exceeded its byte quota",
+            Migrating => "socket is migrating to another node",
         };
         f.write_str(str)
     }
@@ -105,25 +214,52 @@ impl From<EIoDisconnectReason> for DisconnectReason {
     }
 }
 
+/// Sends encoded packets with a permit, returning the number of bytes sent.
 pub(crate) trait PermitExt<'a> {
-    fn send(self, packet: Packet<'_>);
+    fn send(self, packet: Packet<'_>) -> usize;
+    fn send_batch(self, packets: Vec<Packet<'_>>) -> usize;
 }
 impl<'a> PermitExt<'a> for Permit<'a> {
-    fn send(self, mut packet: Packet<'_>) {
-        let bin_payloads = match packet.inner {
-            PacketData::BinaryEvent(_, ref mut bin, _) | PacketData::BinaryAck(ref mut bin, _) => {
-                Some(std::mem::take(&mut bin.bin))
-            }
-            _ => None,
-        };
+    fn send(self, mut packet: Packet<'_>) -> usize {
+        let bin_payloads = take_bin_payloads(&mut packet);
 
-        let msg = packet.into();
+        let msg: String = packet.into();
+        let size = msg.len();
 
         if let Some(bin_payloads) = bin_payloads {
+            let size = size + bin_payloads.iter().map(Bytes::len).sum::<usize>();
             self.emit_many(msg, bin_payloads);
+            size
         } else {
             self.emit(msg);
+            size
+        }
+    }
+
+    fn send_batch(self, packets: Vec<Packet<'_>>) -> usize {
+        let msgs: Vec<(String, Vec<Bytes>)> = packets
+            .into_iter()
+            .map(|mut packet| {
+                let bin_payloads = take_bin_payloads(&mut packet).unwrap_or_default();
+                (packet.into(), bin_payloads)
+            })
+            .collect();
+        let size = msgs
+            .iter()
+            .map(|(msg, bins)| msg.len() + bins.iter().map(Bytes::len).sum::<usize>())
+            .sum();
+        self.emit_batch(msgs);
+        size
+    }
+}
+
+/// Takes the binary attachments out of a binary packet, so that the packet can be encoded.
+fn take_bin_payloads(packet: &mut Packet<'_>) -> Option<Vec<Bytes>> {
+    match packet.inner {
+        PacketData::BinaryEvent(_, ref mut bin, _) | PacketData::BinaryAck(ref mut bin, _) => {
+            Some(std::mem::take(&mut bin.bin))
         }
+        _ => None,
     }
 }
 
@@ -133,10 +269,49 @@ impl<'a> PermitExt<'a> for Permit<'a> {
 pub struct Socket<A: Adapter = LocalAdapter> {
     pub(crate) ns: Arc<Namespace<A>>,
     message_handlers: RwLock<HashMap<Cow<'static, str>, BoxedMessageHandler<A>>>,
+    /// The events whose handler receives the undecoded arguments, see [`Socket::on_raw`]
+    raw_events: RwLock<HashSet<Cow<'static, str>>>,
     disconnect_handler: Mutex<Option<BoxedDisconnectHandler<A>>>,
-    ack_message: Mutex<HashMap<i64, oneshot::Sender<AckResult<Value>>>>,
+    /// The handler called only when the client leaves the namespace, see [`Socket::on_ns_disconnect`]
+    ns_disconnect_handler: Mutex<Option<BoxedDisconnectHandler<A>>>,
+    ack_message: Arc<AckMap>,
     ack_counter: AtomicI64,
     connected: AtomicBool,
+    scheduled_disconnect: Mutex<Option<AbortHandle>>,
+    /// The task emitting the server time, see [`Socket::sync_time`]
+    time_sync: Mutex<Option<AbortHandle>>,
+    /// The last time an event or an acknowledgement was received from the client
+    last_activity: Mutex<Instant>,
+    /// The serializable data of the socket, that can be fetched from any node of the cluster
+    data: RwLock<Option<Value>>,
+    /// The metadata of the socket, also indexed by the adapter
+    meta: RwLock<HashMap<String, String>>,
+    /// The last broadcast packets, if the replay buffer is enabled
+    replay: Option<Mutex<ReplayBuffer>>,
+    /// The id of the last tagged packet, if the packet ids are enabled
+    packet_id: Option<AtomicU64>,
+    /// The settings overriding the global config, with the ack timeout extracted from the handshake auth payload
+    config: RwLock<SocketConfig>,
+    /// The reconnection attempt count reported by the client in its handshake auth payload
+    reconnect_attempts: OnceLock<u32>,
+    /// The user id extracted from the handshake auth payload
+    user_id: OnceLock<String>,
+    /// The number of async handlers still running or queued, awaited by a graceful disconnect
+    in_flight: AtomicUsize,
+    /// Notified when the last in flight handler completes
+    drained: Notify,
+    /// Whether the socket is gracefully disconnecting and ignores the received events
+    draining: AtomicBool,
+    /// Whether the socket is closed and removed from its namespace
+    closed: AtomicBool,
+    /// Notified when the socket is closed
+    closed_notify: Notify,
+    /// The queue of the handlers to run one after the other, if the namespace is ordered
+    ordered_queue: OnceLock<mpsc::UnboundedSender<BoxFuture<'static, ()>>>,
+    /// The throughput counters since the connection, see [`Socket::stats`]
+    stats: StatsCounters,
+    /// The start of the current byte quota window and the bytes received since then
+    quota_window: Mutex<(Instant, u64)>,
     /// The socket id
     pub id: Sid,
 
@@ -160,10 +335,40 @@ impl<A: Adapter> Socket<A> {
         Self {
             ns,
             message_handlers: RwLock::new(HashMap::new()),
+            raw_events: RwLock::new(HashSet::new()),
             disconnect_handler: Mutex::new(None),
-            ack_message: Mutex::new(HashMap::new()),
+            ns_disconnect_handler: Mutex::new(None),
+            ack_message: Arc::new(Mutex::new(HashMap::new())),
             ack_counter: AtomicI64::new(0),
             connected: AtomicBool::new(false),
+            scheduled_disconnect: Mutex::new(None),
+            time_sync: Mutex::new(None),
+            last_activity: Mutex::new(Instant::now()),
+            data: RwLock::new(None),
+            meta: RwLock::new(HashMap::new()),
+            replay: esocket
+                .data
+                .io
+                .get()
+                .and_then(|io| io.config().replay_buffer_size)
+                .map(|size| Mutex::new(ReplayBuffer::new(size))),
+            packet_id: esocket
+                .data
+                .io
+                .get()
+                .filter(|io| io.config().packet_ids)
+                .map(|_| AtomicU64::new(0)),
+            config: RwLock::new(SocketConfig::default()),
+            reconnect_attempts: OnceLock::new(),
+            user_id: OnceLock::new(),
+            in_flight: AtomicUsize::new(0),
+            drained: Notify::new(),
+            draining: AtomicBool::new(false),
+            closed: AtomicBool::new(false),
+            closed_notify: Notify::new(),
+            ordered_queue: OnceLock::new(),
+            stats: StatsCounters::default(),
+            quota_window: Mutex::new((Instant::now(), 0)),
             id: sid,
             #[cfg(feature = "extensions")]
             extensions: Extensions::new(),
@@ -175,6 +380,9 @@ impl<A: Adapter> Socket<A> {
     ///
     /// * See the [`message`](crate::handler::message) module doc for more details on message handler.
     /// * See the [`extract`](crate::extract) module doc for more details on available extractors.
+    /// * The number of handlers per socket can be limited with [`SocketIoBuilder::max_listeners`].
+    ///
+    /// [`SocketIoBuilder::max_listeners`]: crate::SocketIoBuilder#method.max_listeners
     ///
     /// #### Simple example with a sync closure:
     /// ```
@@ -228,10 +436,125 @@ impl<A: Adapter> Socket<A> {
         H: MessageHandler<A, T>,
         T: Send + Sync + 'static,
     {
-        self.message_handlers
-            .write()
-            .unwrap()
-            .insert(event.into(), MakeErasedHandler::new_message_boxed(handler));
+        let event = event.into();
+        let mut handlers = self.message_handlers.write().unwrap();
+        if !handlers.contains_key(&event) && !self.check_max_listeners(&event, handlers.len()) {
+            return;
+        }
+        self.raw_events.write().unwrap().remove(&event);
+        handlers.insert(event, MakeErasedHandler::new_message_boxed(handler));
+    }
+
+    /// Registers a handler for the given event receiving the undecoded arguments of the event,
+    /// to be extracted as JSON bytes with the [`RawPacket`](crate::extract::RawPacket) extractor.
+    ///
+    /// Only the event name is parsed when the packet is received, the arguments are not deserialized.
+    /// It is useful for pass-through handlers, such as proxies or loggers, that don't need to read the payload.
+    /// The other events of the socket are still decoded for their normal handlers.
+    ///
+    /// The arguments of a raw event are not checked against the
+    /// [`max_event_args`](crate::SocketIoBuilder#method.max_event_args) limit, and the extractors
+    /// deserializing the data, such as [`Data`](crate::extract::Data), fail on them.
+    /// The binary events are still decoded.
+    ///
+    /// It replaces any handler previously registered for this event.
+    ///
+    /// # Example
+    /// ```
+    /// # use socketioxide::{SocketIo, extract::*};
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.ns("/", |socket: SocketRef| {
+    ///     socket.on_raw("forward", |socket: SocketRef, RawPacket(args)| {
+    ///         // `args` is the JSON array of the arguments, e.g. `[1,{"foo":"bar"}]`
+    ///         println!("forwarding {} bytes", args.len());
+    ///     });
+    /// });
+    /// ```
+    pub fn on_raw<H, T>(&self, event: impl Into<Cow<'static, str>>, handler: H)
+    where
+        H: MessageHandler<A, T>,
+        T: Send + Sync + 'static,
+    {
+        let event = event.into();
+        self.on(event.clone(), handler);
+        if self.message_handlers.read().unwrap().contains_key(&event) {
+            self.raw_events.write().unwrap().insert(event);
+            self.esocket.data.raw_events.store(true, Ordering::Relaxed);
+        }
+    }
+
+    /// Returns `true` if the event handler was registered with [`Socket::on_raw`].
+    pub(crate) fn is_raw_event(&self, event: &str) -> bool {
+        self.raw_events.read().unwrap().contains(event)
+    }
+
+    /// Registers a handler for the calls of an RPC method: the client emits the `method` event with an
+    /// acknowledgement, and the result of the handler is sent back as the acknowledgement.
+    ///
+    /// The request is deserialized from the event data like with the [`Data`](crate::extract::Data) extractor.
+    /// The response is an [`RpcResponse`](crate::rpc::RpcResponse) with either the result or the error of the handler,
+    /// see the [`rpc`](crate::rpc) module doc for more details.
+    ///
+    /// It replaces any handler previously registered for this event.
+    ///
+    /// # Example
+    /// ```
+    /// # use socketioxide::{SocketIo, extract::*, rpc::RpcError};
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.ns("/", |socket: SocketRef| {
+    ///     socket.on_rpc("rooms:join", |socket: SocketRef, Data::<String>(room)| async move {
+    ///         if room.is_empty() {
+    ///             return Err(RpcError::new("invalid_room", "the room name is empty"));
+    ///         }
+    ///         socket.join(room).ok();
+    ///         Ok(socket.rooms().unwrap_or_default())
+    ///     });
+    /// });
+    /// ```
+    pub fn on_rpc<H, T>(&self, method: impl Into<Cow<'static, str>>, handler: H)
+    where
+        H: RpcHandler<A, T>,
+        T: Send + Sync + 'static,
+    {
+        let handler = Arc::new(handler);
+        self.on(
+            method,
+            move |s: SocketRef<A>, Data(req): Data<Value>, ack: AckSender<A>| async move {
+                let res = handler.call(s, req).await;
+                if let Err(_e) = ack.send(RpcResponse::from(res)) {
+                    #[cfg(feature = "tracing")]
+                    tracing::debug!("error while sending an rpc response: {_e:?}");
+                }
+            },
+        );
+    }
+
+    /// Registers a handler for the given event exposing the binary chunks sent by the client
+    /// as a [`BinaryStream`], to receive large payloads such as file uploads.
+    ///
+    /// The chunks are the binary attachments of the events, and an event without any attachment
+    /// ends the stream. See the [`binary_stream`](crate::binary_stream) module doc for more details.
+    ///
+    /// It replaces any handler previously registered for this event.
+    ///
+    /// # Example
+    /// ```
+    /// # use socketioxide::{SocketIo, extract::*};
+    /// # use futures_util::StreamExt;
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.ns("/", |socket: SocketRef| {
+    ///     let mut upload = socket.binary_stream("upload");
+    ///     tokio::spawn(async move {
+    ///         while let Some(chunk) = upload.next().await {
+    ///             println!("received a chunk of {} bytes", chunk.len());
+    ///         }
+    ///     });
+    /// });
+    /// ```
+    pub fn binary_stream(&self, event: impl Into<Cow<'static, str>>) -> BinaryStream {
+        let (stream, handler) = BinaryStream::new();
+        self.on(event, handler);
+        stream
     }
 
     /// ## Registers a disconnect handler.
@@ -266,6 +589,31 @@ impl<A: Adapter> Socket<A> {
         self.disconnect_handler.lock().unwrap().replace(handler);
     }
 
+    /// ## Registers a namespace disconnect handler.
+    /// It is only called when the client leaves this namespace with a disconnect packet
+    /// ([`DisconnectReason::ClientNSDisconnect`]), while its connection and its sockets on the other namespaces stay open.
+    /// It can be used to run a per-namespace cleanup with clients that connect to multiple namespaces.
+    ///
+    /// The [`on_disconnect`](Socket::on_disconnect) handler is still called, for any disconnect reason.
+    /// You can register only one namespace disconnect handler per socket, the last one is used.
+    /// ### Example
+    /// ```
+    /// # use socketioxide::{SocketIo, extract::*};
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.ns("/chat", |socket: SocketRef| {
+    ///     socket.on_ns_disconnect(|socket: SocketRef| {
+    ///         println!("Socket {} left the chat but is still connected", socket.id);
+    ///     });
+    /// });
+    pub fn on_ns_disconnect<C, T>(&self, callback: C)
+    where
+        C: DisconnectHandler<A, T> + Send + Sync + 'static,
+        T: Send + Sync + 'static,
+    {
+        let handler = MakeErasedHandler::new_disconnect_boxed(callback);
+        self.ns_disconnect_handler.lock().unwrap().replace(handler);
+    }
+
     /// Emits a message to the client
     ///
     /// If you provide array-like data (tuple, vec, arrays), it will be considered as multiple arguments.
@@ -274,6 +622,8 @@ impl<A: Adapter> Socket<A> {
     ///
     /// ## Errors
     /// * When encoding the data into JSON a [`SendError::Serialize`] may be returned.
+    /// * If the payload does not match the schema registered for the event with the `schema` feature,
+    /// a [`SendError::Schema`](crate::SendError::Schema) is returned.
     /// * If the underlying engine.io connection is closed a [`SendError::Socket(SocketError::Closed)`]
     /// will be returned and the provided data to be send will be given back in the error.
     /// * If the packet buffer is full, a [`SendError::Socket(SocketError::InternalChannelFull)`]
@@ -313,6 +663,187 @@ impl<A: Adapter> Socket<A> {
         self.bin(bins).emit(event, data)
     }
 
+    /// Emits several events to the client at once, in a single write to the transport.
+    ///
+    /// The events are queued together, so they reach the client contiguously,
+    /// without any other packet emitted concurrently to this socket in between.
+    /// The payloads are serialized as with [`emit()`](#method.emit), binary attachments included.
+    /// To batch payloads of different types, use [`serde_json::Value`] payloads.
+    ///
+    /// ## Errors
+    /// * When encoding a payload into JSON, a [`SendError::Serialize`] is returned and nothing is sent.
+    /// * If a payload does not match the schema registered for its event with the `schema` feature,
+    /// a [`SendError::Schema`](crate::SendError::Schema) is returned and nothing is sent.
+    /// * If the underlying engine.io connection is closed or if the packet buffer is full,
+    /// a [`SendError::Socket`] is returned and none of the events are sent.
+    ///
+    /// [`SendError::Serialize`]: crate::SendError::Serialize
+    /// [`SendError::Socket`]: crate::SendError::Socket
+    ///
+    /// ## Example
+    /// ```
+    /// # use socketioxide::{SocketIo, extract::*};
+    /// # use serde_json::json;
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.ns("/", |socket: SocketRef| {
+    ///     // The snapshot and its cursor are received together
+    ///     socket.emit_batch(&[
+    ///         ("snapshot", json!({ "items": [1, 2, 3] })),
+    ///         ("cursor", json!(3)),
+    ///     ]).ok();
+    /// });
+    /// ```
+    pub fn emit_batch<T: Serialize>(&self, events: &[(&str, T)]) -> Result<(), SendError<()>> {
+        if !self.connected() {
+            for (event, _) in events {
+                self.notify_drop(DropReason::SocketClosed, Some(event));
+            }
+            return Err(SendError::Socket(SocketError::Closed(())));
+        }
+        let mut packets = Vec::with_capacity(events.len());
+        for (event, data) in events {
+            let (data, bins) = crate::to_value(data)?;
+            self.ns.check_schema(event, &data)?;
+            let ns = self.ns.path.clone();
+            packets.push(match bins.is_empty() {
+                true => Packet::event(ns, *event, data),
+                false => Packet::bin_event(ns, *event, data, bins),
+            });
+        }
+        let permit = match self.reserve() {
+            Ok(permit) => permit,
+            Err(e) => {
+                #[cfg(feature = "tracing")]
+                tracing::debug!("sending error during emit batch: {e:?}");
+                for (event, _) in events {
+                    self.notify_drop((&e).into(), Some(event));
+                }
+                return Err(e.into());
+            }
+        };
+        for packet in &mut packets {
+            self.tag_packet(packet);
+            self.notify_emit(packet);
+        }
+        self.record_bytes_out(permit.send_batch(packets));
+        Ok(())
+    }
+
+    /// Emits a message to the client with an already built [`serde_json::Value`] payload.
+    ///
+    /// Contrary to [`emit()`](#method.emit), the value is directly put in the packet
+    /// instead of being serialized again, which is useful for dynamically built payloads.
+    /// As with [`emit()`](#method.emit), an array value is considered as multiple arguments.
+    ///
+    /// The value cannot contain any binary payload, use [`bin()`](#method.bin) to attach binary data.
+    ///
+    /// ## Errors
+    /// The errors are the same as the ones of [`emit()`](#method.emit),
+    /// except that [`SendError::Serialize`](crate::SendError::Serialize) is never returned.
+    ///
+    /// ## Example
+    /// ```
+    /// # use socketioxide::{SocketIo, extract::*};
+    /// # use serde_json::json;
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.ns("/", |socket: SocketRef| {
+    ///     let value = json!({ "user": { "name": "foo", "tags": ["a", "b"] } });
+    ///     socket.emit_value("profile", value).ok();
+    /// });
+    /// ```
+    pub fn emit_value(
+        &self,
+        event: impl Into<Cow<'static, str>>,
+        value: Value,
+    ) -> Result<(), SendError<Value>> {
+        self.bin(Vec::<Bytes>::new()).emit(event, value)
+    }
+
+    /// Emits a [`TypedEvent`] to the client, the event name is given by [`TypedEvent::NAME`]
+    /// and the event is serialized as a single argument.
+    ///
+    /// See the [`typed`](crate::typed) module doc for more details.
+    ///
+    /// ## Errors
+    /// The errors are the same as the ones of [`emit()`](#method.emit).
+    ///
+    /// ## Example
+    /// ```
+    /// # use socketioxide::{SocketIo, extract::*, typed::TypedEvent};
+    /// # use serde::Serialize;
+    /// #[derive(Serialize)]
+    /// struct Welcome {
+    ///     motd: String,
+    /// }
+    /// impl TypedEvent for Welcome {
+    ///     const NAME: &'static str = "welcome";
+    /// }
+    ///
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.ns("/", |socket: SocketRef| {
+    ///     socket.emit_typed(Welcome { motd: "hello".into() }).ok();
+    /// });
+    /// ```
+    pub fn emit_typed<E: TypedEvent>(&self, event: E) -> Result<(), SendError<Value>> {
+        self.emit(E::NAME, event)
+    }
+
+    /// Emits a message to the client, serializing the data according to the given context
+    /// with [`SerializeWithContext`].
+    ///
+    /// See the [`context`](crate::context) module doc for more details.
+    ///
+    /// ## Errors
+    /// The errors are the same as the ones of [`emit()`](#method.emit).
+    pub fn emit_with_context<T, C>(
+        &self,
+        event: impl Into<Cow<'static, str>>,
+        data: &T,
+        ctx: &C,
+    ) -> Result<(), SendError<Value>>
+    where
+        T: SerializeWithContext<C> + ?Sized,
+    {
+        self.emit(event, WithContext::new(data, ctx))
+    }
+
+    /// Emits a message to the client, waiting for space in the packet buffer if it is full.
+    ///
+    /// Contrary to [`emit()`](#method.emit), it never returns an [`InternalChannelFull`] error,
+    /// it applies backpressure to the caller instead. The packet buffer is drained by the transport
+    /// so awaiting this method can't block the connection, even from a handler of this socket.
+    /// However, if the client stops reading, it will wait until the socket is closed.
+    ///
+    /// ## Errors
+    /// * When encoding the data into JSON a [`SendError::Serialize`] may be returned.
+    /// * If the payload does not match the schema registered for the event with the `schema` feature,
+    /// a [`SendError::Schema`](crate::SendError::Schema) is returned.
+    /// * If the underlying engine.io connection is closed a [`SendError::Socket(SocketError::Closed)`]
+    /// will be returned and the provided data to be send will be given back in the error.
+    ///
+    /// [`InternalChannelFull`]: crate::SocketError::InternalChannelFull
+    /// [`SendError::Serialize`]: crate::SendError::Serialize
+    /// [`SendError::Socket(SocketError::Closed)`]: crate::SocketError::Closed
+    /// ## Example
+    /// ```
+    /// # use socketioxide::{SocketIo, extract::*};
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.ns("/", |socket: SocketRef| async move {
+    ///     // Stream a large amount of messages without overflowing the packet buffer
+    ///     for i in 0..10_000 {
+    ///         socket.emit_wait("progress", i).await.ok();
+    ///     }
+    /// });
+    /// ```
+    pub async fn emit_wait<T: Serialize>(
+        &self,
+        event: impl Into<Cow<'static, str>>,
+        data: T,
+    ) -> Result<(), SendError<Value>> {
+        let (data, bins) = crate::to_value(data)?;
+        self.bin(bins).emit_wait(event, data).await
+    }
+
     /// Emits a message to the client and wait for acknowledgement.
     ///
     /// The acknowledgement has a timeout specified in the config (5s by default)
@@ -337,11 +868,16 @@ impl<A: Adapter> Socket<A> {
     /// an [`AckError::Timeout`]. If the data sent by the client is not deserializable as `V`,
     /// an [`AckError::Serde`] will be yielded.
     ///
+    /// If the [`SocketIoBuilder::max_pending_acks`] limit is reached, the packet is not sent
+    /// and the [`AckStream`] will yield an [`AckError::TooManyPending`].
+    ///
     /// [`timeout()`]: crate::operators::ConfOperators#method.timeout
     /// [`SocketIoBuilder::ack_timeout`]: crate::SocketIoBuilder#method.ack_timeout
+    /// [`SocketIoBuilder::max_pending_acks`]: crate::SocketIoBuilder#method.max_pending_acks
     /// [`Stream`]: futures_core::stream::Stream
     /// [`Future`]: futures_core::future::Future
     /// [`AckError`]: crate::AckError
+    /// [`AckError::TooManyPending`]: crate::AckError::TooManyPending
     /// [`AckError::Serde`]: crate::AckError::Serde
     /// [`AckError::Timeout`]: crate::AckError::Timeout
     /// [`AckError::Socket`]: crate::AckError::Socket
@@ -369,7 +905,9 @@ impl<A: Adapter> Socket<A> {
         event: impl Into<Cow<'static, str>>,
         data: T,
     ) -> Result<AckStream<V>, SendError<T>> {
+        let event = event.into();
         if !self.connected() {
+            self.notify_drop(DropReason::SocketClosed, Some(&event));
             return Err(SendError::Socket(SocketError::Closed(data)));
         }
         let permit = match self.reserve() {
@@ -377,64 +915,418 @@ impl<A: Adapter> Socket<A> {
             Err(e) => {
                 #[cfg(feature = "tracing")]
                 tracing::debug!("sending error during emit message: {e:?}");
+                self.notify_drop((&e).into(), Some(&event));
                 return Err(e.with_value(data).into());
             }
         };
         let ns = self.ns.path.clone();
         let data = serde_json::to_value(data)?;
-        let packet = Packet::event(ns, event.into(), data);
-        let rx = self.send_with_ack_permit(packet, permit);
-        let stream = AckInnerStream::send(rx, self.get_io().config().ack_timeout, self.id);
-        Ok(AckStream::<V>::from(stream))
+        self.ns.check_schema(&event, &data)?;
+        let packet = Packet::event(ns, event, data);
+        let (rx, handle) = self.send_with_ack_permit(packet, permit);
+        let stream = AckInnerStream::send(rx, self.ack_timeout(), self.id);
+        Ok(AckStream::<V>::from(stream).with_handle(handle))
     }
 
-    // Room actions
-
-    /// Joins the given rooms.
+    /// Sends a raw [`Packet`] to the client, without any serialization nor check on its content.
     ///
-    /// If the room does not exist, it will be created.
+    /// This is an escape hatch for advanced usages like replaying captured packets or
+    /// layering custom protocols on top of socket.io, you should know the protocol to use it.
+    /// The binary attachments of binary packets are sent right after the packet.
     ///
     /// ## Errors
-    /// When using a distributed adapter, it can return an [`Adapter::Error`] which is mostly related to network errors.
-    /// For the default [`LocalAdapter`] it is always an [`Infallible`](std::convert::Infallible) error
-    pub fn join(&self, rooms: impl RoomParam) -> Result<(), A::Error> {
-        self.ns.adapter.add_all(self.id, rooms)
+    /// * If a binary packet declares more attachments than it contains,
+    /// a [`RawSendError::MissingAttachments`] is returned.
+    /// * If the underlying engine.io connection is closed or if the packet buffer is full,
+    /// a [`RawSendError::Socket`] is returned.
+    ///
+    /// [`RawSendError::MissingAttachments`]: crate::RawSendError::MissingAttachments
+    /// [`RawSendError::Socket`]: crate::RawSendError::Socket
+    /// ## Example
+    /// ```
+    /// # use socketioxide::{SocketIo, extract::*, packet::Packet};
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.ns("/", |socket: SocketRef| {
+    ///     let packet = Packet::event("/", "hello", serde_json::json!(["world"]));
+    ///     socket.send_raw(packet).ok();
+    /// });
+    /// ```
+    pub fn send_raw(&self, packet: Packet<'_>) -> Result<(), RawSendError> {
+        if let PacketData::BinaryEvent(_, bin, _) | PacketData::BinaryAck(bin, _) = &packet.inner {
+            if bin.bin.len() < bin.payload_count() {
+                return Err(RawSendError::MissingAttachments {
+                    expected: bin.payload_count(),
+                    got: bin.bin.len(),
+                });
+            }
+        }
+        Ok(self.send_untagged(packet)?)
     }
 
-    /// Leaves the given rooms.
+    /// Sends a raw pre-encoded socket.io packet to the client, without any serialization.
+    ///
+    /// The data should be a complete socket.io text packet (e.g. `2["hello","world"]`),
+    /// it is parsed to check its framing before being sent.
+    /// Because there is no way to give binary attachments, binary packets are rejected,
+    /// use [`Socket::send_raw`] with a binary packet instead.
     ///
-    /// If the room does not exist, it will do nothing
     /// ## Errors
-    /// When using a distributed adapter, it can return an [`Adapter::Error`] which is mostly related to network errors.
-    /// For the default [`LocalAdapter`] it is always an [`Infallible`](std::convert::Infallible) error
-    pub fn leave(&self, rooms: impl RoomParam) -> Result<(), A::Error> {
-        self.ns.adapter.del(self.id, rooms)
+    /// * If the data is not valid UTF-8 a [`RawSendError::InvalidUtf8`] is returned.
+    /// * If the data is not a valid socket.io packet a [`RawSendError::InvalidPacket`] is returned.
+    /// * If the packet declares binary attachments a [`RawSendError::MissingAttachments`] is returned.
+    /// * If the underlying engine.io connection is closed or if the packet buffer is full,
+    /// a [`RawSendError::Socket`] is returned.
+    ///
+    /// [`RawSendError::InvalidUtf8`]: crate::RawSendError::InvalidUtf8
+    /// [`RawSendError::InvalidPacket`]: crate::RawSendError::InvalidPacket
+    /// [`RawSendError::MissingAttachments`]: crate::RawSendError::MissingAttachments
+    /// [`RawSendError::Socket`]: crate::RawSendError::Socket
+    /// ## Example
+    /// ```
+    /// # use socketioxide::{SocketIo, extract::*};
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.ns("/", |socket: SocketRef| {
+    ///     socket.send_raw_bytes(br#"2["hello","world"]"#).ok();
+    /// });
+    /// ```
+    pub fn send_raw_bytes(&self, data: &[u8]) -> Result<(), RawSendError> {
+        let data = std::str::from_utf8(data)?;
+        let packet = Packet::try_from(Str::from(data.to_string()))
+            .map_err(|e| RawSendError::InvalidPacket(e.to_string()))?;
+        self.send_raw(packet)
     }
 
-    /// Leaves all rooms where the socket is connected.
+    /// Emits a message to the client with the given namespace set on the packet instead of the namespace of this socket.
+    ///
+    /// Because all the sockets of a client share the same connection, the message is received by the client
+    /// socket connected to the target namespace. It is a low-level escape hatch for gateway-like servers
+    /// routing messages between namespaces, most of the time you should emit from the socket of the target namespace.
+    ///
     /// ## Errors
-    /// When using a distributed adapter, it can return an [`Adapter::Error`] which is mostly related to network errors.
-    /// For the default [`LocalAdapter`] it is always an [`Infallible`](std::convert::Infallible) error
-    pub fn leave_all(&self) -> Result<(), A::Error> {
-        self.ns.adapter.del_all(self.id)
+    /// * If the target namespace does not exist on the server a [`RawSendError::UnknownNamespace`] is returned.
+    /// * When encoding the data into JSON a [`RawSendError::Serialize`] may be returned.
+    /// * If the underlying engine.io connection is closed or if the packet buffer is full,
+    /// a [`RawSendError::Socket`] is returned.
+    ///
+    /// [`RawSendError::UnknownNamespace`]: crate::RawSendError::UnknownNamespace
+    /// [`RawSendError::Serialize`]: crate::RawSendError::Serialize
+    /// [`RawSendError::Socket`]: crate::RawSendError::Socket
+    /// ## Example
+    /// ```
+    /// # use socketioxide::{SocketIo, extract::*};
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.ns("/admin", |socket: SocketRef| {});
+    /// io.ns("/", |socket: SocketRef| {
+    ///     socket.emit_to_ns("/admin", "hello", "world").ok();
+    /// });
+    /// ```
+    pub fn emit_to_ns<T: Serialize>(
+        &self,
+        ns: &str,
+        event: impl Into<Cow<'static, str>>,
+        data: T,
+    ) -> Result<(), RawSendError> {
+        if !self.get_io().has_ns(ns) {
+            return Err(RawSendError::UnknownNamespace(ns.to_string()));
+        }
+        // The schemas are shared by all the namespaces
+        let event = event.into();
+        let (data, bins) = crate::to_value(data)?;
+        self.ns.check_schema(&event, &data)?;
+        let ns = Str::from(ns.to_string());
+        let packet = match (data, bins) {
+            (data, bins) if bins.is_empty() => Packet::event(ns, event, data),
+            (data, bins) => Packet::bin_event(ns, event, data, bins),
+        };
+        Ok(self.send(packet)?)
     }
 
-    /// Gets all rooms where the socket is connected.
+    // Room actions
+
+    /// Joins the given rooms.
+    ///
+    /// If the room does not exist, it will be created.
+    ///
     /// ## Errors
-    /// When using a distributed adapter, it can return an [`Adapter::Error`] which is mostly related to network errors.
-    /// For the default [`LocalAdapter`] it is always an [`Infallible`](std::convert::Infallible) error
-    pub fn rooms(&self) -> Result<Vec<Room>, A::Error> {
-        self.ns.adapter.socket_rooms(self.id)
+    /// * If a hook registered with [`SocketIo::on_join`] vetoes one of the rooms,
+    /// none of them are joined and a [`JoinError::Vetoed`] error is returned.
+    /// * If joining the rooms would exceed the [`SocketIoBuilder::max_rooms_per_socket`] limit,
+    /// none of them are joined and a [`JoinError::TooManyRooms`] error is returned.
+    /// * If one of the rooms reached its [capacity](crate::room_meta::RoomMeta#method.set_capacity),
+    /// none of them are joined and a [`JoinError::RoomFull`] error is returned.
+    /// The capacity is checked with [`Adapter::try_add`], atomic with the [`LocalAdapter`], so concurrent joins never exceed it.
+    /// * When using a distributed adapter, it can return a [`JoinError::Adapter`] error which is mostly related to network errors.
+    /// For the default [`LocalAdapter`] the adapter error is always [`Infallible`](std::convert::Infallible).
+    ///
+    /// [`SocketIoBuilder::max_rooms_per_socket`]: crate::SocketIoBuilder#method.max_rooms_per_socket
+    pub fn join(&self, rooms: impl RoomParam) -> Result<(), JoinError<A::Error>> {
+        let rooms: Vec<Room> = rooms.into_room_iter().collect();
+        self.check_join(&rooms)?;
+        let joined = self.tracked_rooms(&rooms, false)?;
+        let uncapped = self.add_capped(&rooms)?;
+        self.ns.adapter.add_all(self.id, uncapped)?;
+        self.send_snapshots(&rooms);
+        self.notify(|| LifecycleEvent::Join {
+            ns: self.ns.path.clone(),
+            sid: self.id,
+            rooms,
+        });
+        self.emit_presence(PRESENCE_JOIN_EVENT, joined);
+        Ok(())
     }
 
-    /// Return true if the socket is connected to the namespace.
+    /// Joins the room only if it has less than `max_size` members, and returns whether the socket is in the room.
     ///
-    /// A socket is considered connected when it has been successfully handshaked with the server
-    /// and that all [connect middlewares](crate::handler::connect#middlewares) have been executed.
+    /// The size check and the join are atomic in the adapter, so concurrent calls never exceed `max_size`,
+    /// contrary to checking the number of sockets of the room before joining it.
+    /// If the room also has a [capacity](crate::room_meta::RoomMeta#method.set_capacity), the lowest limit applies.
+    /// A socket already in the room stays in it and `true` is returned, without any join event or presence update.
+    ///
+    /// ## Errors
+    /// The errors are the same as the ones of [`join`](#method.join), except that a full room
+    /// returns `Ok(false)` rather than a [`JoinError::RoomFull`] error.
+    ///
+    /// #### Example
+    /// ```
+    /// # use socketioxide::{SocketIo, extract::*};
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.ns("/", |socket: SocketRef| {
+    ///     socket.on("play", |socket: SocketRef| {
+    ///         match socket.try_join("game", 4).unwrap() {
+    ///             true => socket.emit("joined", "game").ok(),
+    ///             false => socket.emit("full", "game").ok(),
+    ///         };
+    ///     });
+    /// });
+    /// ```
+    pub fn try_join(
+        &self,
+        room: impl Into<Room>,
+        max_size: usize,
+    ) -> Result<bool, JoinError<A::Error>> {
+        let room = room.into();
+        if self.rooms()?.contains(&room) {
+            return Ok(true);
+        }
+        let rooms = [room.clone()];
+        self.check_join(&rooms)?;
+        let capacity = RoomMeta::new(self.ns.clone(), room.clone()).stored_capacity()?;
+        let max = capacity.map_or(max_size, |capacity| capacity.min(max_size));
+        let joined = self.tracked_rooms(&rooms, false)?;
+        if !self.ns.adapter.try_add(self.id, room, max)? {
+            return Ok(false);
+        }
+        self.send_snapshots(&rooms);
+        self.notify(|| LifecycleEvent::Join {
+            ns: self.ns.path.clone(),
+            sid: self.id,
+            rooms: rooms.into(),
+        });
+        self.emit_presence(PRESENCE_JOIN_EVENT, joined);
+        Ok(true)
+    }
+
+    /// Checks that the rooms are not vetoed by the join hook and do not exceed the max rooms of the socket.
+    fn check_join(&self, rooms: &[Room]) -> Result<(), JoinError<A::Error>> {
+        if let Some(hook) = self.esocket.data.io.get().and_then(|io| io.join_hook()) {
+            if let Some(room) = rooms.iter().find(|room| !hook(self.id, room)) {
+                return Err(JoinError::Vetoed(room.clone()));
+            }
+        }
+        let max = self.config.read().unwrap().max_rooms;
+        let max = max.or_else(|| {
+            let io = self.esocket.data.io.get()?;
+            io.config().max_rooms_per_socket
+        });
+        if let Some(max) = max {
+            let current = self.rooms()?;
+            let sid = self.id.to_string();
+            let total: HashSet<&Room> = current
+                .iter()
+                .chain(rooms.iter())
+                .filter(|room| **room != sid)
+                .collect();
+            if total.len() > max {
+                return Err(JoinError::TooManyRooms(max));
+            }
+        }
+        Ok(())
+    }
+
+    /// Adds the socket to the given rooms that have a [capacity](crate::room_meta::RoomMeta#method.set_capacity)
+    /// with [`Adapter::try_add`], and returns the other rooms.
+    /// If one of them is full, the rooms added here are left and a [`JoinError::RoomFull`] error is returned.
+    fn add_capped(&self, rooms: &[Room]) -> Result<Vec<Room>, JoinError<A::Error>> {
+        let mut current = None;
+        let mut uncapped = Vec::with_capacity(rooms.len());
+        let mut added = Vec::new();
+        for room in rooms {
+            let capacity = RoomMeta::new(self.ns.clone(), room.clone()).stored_capacity()?;
+            let Some(capacity) = capacity else {
+                uncapped.push(room.clone());
+                continue;
+            };
+            let current = match &mut current {
+                Some(current) => current,
+                None => current.insert(self.rooms()?),
+            };
+            // A member joining again doesn't count twice
+            if current.contains(room) {
+                uncapped.push(room.clone());
+                continue;
+            }
+            if !self.ns.adapter.try_add(self.id, room.clone(), capacity)? {
+                self.ns.adapter.del(self.id, added)?;
+                return Err(JoinError::RoomFull(room.clone()));
+            }
+            added.push(room.clone());
+        }
+        Ok(uncapped)
+    }
+
+    /// Returns a [`RoomMeta`] handle to the metadata of a room of the socket namespace,
+    /// see the [`room_meta`](crate::room_meta) module doc for more details.
+    ///
+    /// #### Example
+    /// ```
+    /// # use socketioxide::{SocketIo, extract::*};
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.ns("/", |socket: SocketRef| {
+    ///     socket.room_meta("lobby").set("topic", "general").unwrap();
+    /// });
+    /// ```
+    pub fn room_meta(&self, room: impl Into<Room>) -> RoomMeta<A> {
+        RoomMeta::new(self.ns.clone(), room.into())
+    }
+
+    /// Joins the given rooms, then calls `f` with the operators selecting all the clients in these rooms,
+    /// including the current socket, and returns its result.
+    ///
+    /// The operators are only built once the rooms are joined, so a broadcast made with them is guaranteed
+    /// to reach the joining socket. The other broadcasts running concurrently with the join may or may not reach it.
+    ///
+    /// ## Errors
+    /// The rooms are joined with [`join`](Socket::join) and `f` is not called if it fails.
+    /// #### Example
+    /// ```
+    /// # use socketioxide::{SocketIo, extract::*};
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.ns("/", |socket: SocketRef| {
+    ///     socket.on("join", |socket: SocketRef, Data::<String>(room)| {
+    ///         // Announce the new member to the room, including itself
+    ///         socket.join_then(room, |op| op.emit("joined", socket.id)).ok();
+    ///     });
+    /// });
+    /// ```
+    pub fn join_then<R>(
+        &self,
+        rooms: impl RoomParam,
+        f: impl FnOnce(BroadcastOperators<WithoutBinary, A>) -> R,
+    ) -> Result<R, JoinError<A::Error>> {
+        let rooms: Vec<Room> = rooms.into_room_iter().collect();
+        self.join(rooms.clone())?;
+        Ok(f(self.within(rooms)))
+    }
+
+    /// Leaves the given rooms.
+    ///
+    /// If the room does not exist, it will do nothing
+    /// ## Errors
+    /// When using a distributed adapter, it can return an [`Adapter::Error`] which is mostly related to network errors.
+    /// For the default [`LocalAdapter`] it is always an [`Infallible`](std::convert::Infallible) error
+    pub fn leave(&self, rooms: impl RoomParam) -> Result<(), A::Error> {
+        let rooms: Vec<Room> = rooms.into_room_iter().collect();
+        let left = self.tracked_rooms(&rooms, true)?;
+        let hook = self.leave_hook();
+        let hooked = match hook {
+            Some(_) => {
+                let current = self.rooms()?;
+                rooms
+                    .iter()
+                    .filter(|r| current.contains(r))
+                    .cloned()
+                    .collect()
+            }
+            None => Vec::new(),
+        };
+        self.ns.adapter.del(self.id, rooms.clone())?;
+        self.notify(|| LifecycleEvent::Leave {
+            ns: self.ns.path.clone(),
+            sid: self.id,
+            rooms,
+        });
+        self.emit_presence(PRESENCE_LEAVE_EVENT, left);
+        self.call_leave_hook(hook, hooked);
+        Ok(())
+    }
+
+    /// Leaves all rooms where the socket is connected.
+    /// ## Errors
+    /// When using a distributed adapter, it can return an [`Adapter::Error`] which is mostly related to network errors.
+    /// For the default [`LocalAdapter`] it is always an [`Infallible`](std::convert::Infallible) error
+    pub fn leave_all(&self) -> Result<(), A::Error> {
+        let left = self.tracked_joined_rooms()?;
+        let hook = self.leave_hook();
+        let rooms = match hook.is_some() || self.has_event_sink() {
+            true => self.rooms()?,
+            false => Vec::new(),
+        };
+        self.ns.adapter.del_all(self.id)?;
+        self.notify_leave(&rooms);
+        self.emit_presence(PRESENCE_LEAVE_EVENT, left);
+        self.call_leave_hook(hook, rooms);
+        Ok(())
+    }
+
+    /// Gets all rooms where the socket is connected.
+    /// ## Errors
+    /// When using a distributed adapter, it can return an [`Adapter::Error`] which is mostly related to network errors.
+    /// For the default [`LocalAdapter`] it is always an [`Infallible`](std::convert::Infallible) error
+    pub fn rooms(&self) -> Result<Vec<Room>, A::Error> {
+        self.ns.adapter.socket_rooms(self.id)
+    }
+
+    /// Return true if the socket is connected to the namespace.
+    ///
+    /// A socket is considered connected when it has been successfully handshaked with the server
+    /// and that all [connect middlewares](crate::handler::connect#middlewares) have been executed.
     pub fn connected(&self) -> bool {
         self.connected.load(Ordering::SeqCst)
     }
 
+    /// Returns true if the socket is connected to the namespace and if its underlying
+    /// engine.io connection is still open.
+    ///
+    /// It only reads the current state of the socket, so it can be cheaply called
+    /// before doing some work for a socket or emitting to it.
+    /// It becomes `false` as soon as the disconnection is processed by the server.
+    ///
+    /// # Example
+    /// ```
+    /// # use socketioxide::{SocketIo, extract::*};
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.ns("/", |socket: SocketRef| {
+    ///     tokio::spawn(async move {
+    ///         tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+    ///         if socket.is_connected() {
+    ///             socket.emit("hello", "world").ok();
+    ///         }
+    ///     });
+    /// });
+    /// ```
+    pub fn is_connected(&self) -> bool {
+        self.connected() && !self.esocket.is_closed()
+    }
+
+    /// Returns true if the socket is connected to the namespace, regardless of
+    /// the state of its underlying engine.io connection.
+    ///
+    /// It is the same as [`Socket::connected`].
+    #[inline]
+    pub fn is_namespace_connected(&self) -> bool {
+        self.connected()
+    }
+
     // Socket operators
 
     /// Selects all clients in the given rooms except the current socket.
@@ -461,6 +1353,37 @@ impl<A: Adapter> Socket<A> {
         BroadcastOperators::from_sock(self.ns.clone(), self.id).to(rooms)
     }
 
+    /// Selects all clients sharing at least one room with the current socket, except the current socket,
+    /// e.g. to notify the peers of a user. It is the union of the rooms the socket is currently in.
+    ///
+    /// A room named after the socket id is not taken into account, so that the socket's personal room
+    /// doesn't select the socket alone. If the socket is not in any room, no client is selected.
+    ///
+    /// ## Errors
+    /// When using a distributed adapter, it can return an [`Adapter::Error`] which is mostly related to network errors.
+    /// For the default [`LocalAdapter`] it is always an [`Infallible`](std::convert::Infallible) error
+    /// # Example
+    /// ```
+    /// # use socketioxide::{SocketIo, extract::*};
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.ns("/", |socket: SocketRef| {
+    ///     socket.join(["friends:1", "friends:2"]).unwrap();
+    ///     // Notify all the sockets in friends:1 or friends:2, except the current one
+    ///     socket.to_own_rooms().unwrap().emit("online", socket.id).ok();
+    /// });
+    /// ```
+    pub fn to_own_rooms(&self) -> Result<BroadcastOperators<WithoutBinary, A>, A::Error> {
+        let sid = self.id.to_string();
+        let rooms: Vec<Room> = self
+            .rooms()?
+            .into_iter()
+            .filter(|room| *room != sid)
+            .collect();
+        // Without rooms, select only the current socket, which is then excluded
+        let ops = BroadcastOperators::from_sock(self.ns.clone(), self.id);
+        Ok(ops.to(rooms).to_sockets([self.id]))
+    }
+
     /// Selects all clients in the given rooms.
     ///
     /// It does include the current socket contrary to the `to()` operator.
@@ -509,8 +1432,47 @@ impl<A: Adapter> Socket<A> {
         BroadcastOperators::from_sock(self.ns.clone(), self.id).except(rooms)
     }
 
-    /// Broadcasts to all clients only connected on this node (when using multiple nodes).
-    /// When using the default in-memory [`LocalAdapter`], this operator is a no-op.
+    /// Selects all sockets that are in **any** of the given rooms (union) except the current socket.
+    ///
+    /// This is the same as the `to()` operator.
+    /// # Example
+    /// ```
+    /// # use socketioxide::{SocketIo, extract::*};
+    /// # use serde_json::Value;
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.ns("/", |socket: SocketRef| {
+    ///     socket.on("test", |socket: SocketRef, Data::<Value>(data)| async move {
+    ///         // In region:eu or in plan:pro except the current socket
+    ///         socket.to_any_of(["region:eu", "plan:pro"]).emit("test", data);
+    ///     });
+    /// });
+    /// ```
+    pub fn to_any_of(&self, rooms: impl RoomParam) -> BroadcastOperators<WithoutBinary, A> {
+        BroadcastOperators::from_sock(self.ns.clone(), self.id).to_any_of(rooms)
+    }
+
+    /// Selects all sockets that are in **all** the given rooms (intersection) except the current socket.
+    ///
+    /// See [`BroadcastOperators::to_all_of`] for more details.
+    /// # Example
+    /// ```
+    /// # use socketioxide::{SocketIo, extract::*};
+    /// # use serde_json::Value;
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.ns("/", |socket: SocketRef| {
+    ///     socket.on("test", |socket: SocketRef, Data::<Value>(data)| async move {
+    ///         // In both region:eu and plan:pro except the current socket
+    ///         socket.to_all_of(["region:eu", "plan:pro"]).emit("test", data);
+    ///     });
+    /// });
+    /// ```
+    pub fn to_all_of(&self, rooms: impl RoomParam) -> BroadcastOperators<WithoutBinary, A> {
+        BroadcastOperators::from_sock(self.ns.clone(), self.id).to_all_of(rooms)
+    }
+
+    /// Broadcasts to all clients only connected on this node (when using multiple nodes),
+    /// except the current socket.
+    /// When using the default in-memory [`LocalAdapter`], this operator is the same as [`broadcast()`](#method.broadcast).
     /// # Example
     /// ```
     /// # use socketioxide::{SocketIo, extract::*};
@@ -524,7 +1486,9 @@ impl<A: Adapter> Socket<A> {
     ///     });
     /// });
     pub fn local(&self) -> BroadcastOperators<WithoutBinary, A> {
-        BroadcastOperators::from_sock(self.ns.clone(), self.id).local()
+        BroadcastOperators::from_sock(self.ns.clone(), self.id)
+            .broadcast()
+            .local()
     }
 
     /// Sets a custom timeout when sending a message with an acknowledgement.
@@ -603,6 +1567,62 @@ impl<A: Adapter> Socket<A> {
         BroadcastOperators::from_sock(self.ns.clone(), self.id).broadcast()
     }
 
+    /// Broadcasts to all sockets matching a predicate over their [`Extensions`]
+    /// (except the current socket).
+    ///
+    /// See [`BroadcastOperators::filter`] for more details.
+    /// # Example
+    /// ```
+    /// # use socketioxide::{SocketIo, extract::*};
+    /// # use serde_json::Value;
+    /// #[derive(Clone, PartialEq)]
+    /// enum Role { Admin, User }
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.ns("/", |socket: SocketRef| {
+    ///     socket.extensions.insert(Role::User);
+    ///     socket.on("test", |socket: SocketRef, Data::<Value>(data)| async move {
+    ///         // This message will be broadcast to all the admins of this namespace
+    ///         socket.filter(|ext| ext.get::<Role>() == Some(Role::Admin)).emit("test", data);
+    ///     });
+    /// });
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "extensions")))]
+    #[cfg(feature = "extensions")]
+    pub fn filter<F>(&self, predicate: F) -> BroadcastOperators<WithoutBinary, A>
+    where
+        F: Fn(&Extensions) -> bool + Send + Sync + 'static,
+    {
+        BroadcastOperators::from_sock(self.ns.clone(), self.id)
+            .broadcast()
+            .filter(predicate)
+    }
+
+    /// Broadcasts to all sockets with the metadata `key` set to `value` (except the current socket).
+    ///
+    /// See [`BroadcastOperators::where_meta`] for more details.
+    /// # Example
+    /// ```
+    /// # use socketioxide::{SocketIo, extract::*};
+    /// # use serde_json::Value;
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.ns("/", |socket: SocketRef| {
+    ///     socket.set_meta("region", "eu").unwrap();
+    ///     socket.on("test", |socket: SocketRef, Data::<Value>(data)| async move {
+    ///         // This message will be broadcast to all the sockets of the eu region
+    ///         socket.where_meta("region", "eu").emit("test", data);
+    ///     });
+    /// });
+    /// ```
+    pub fn where_meta(
+        &self,
+        key: impl Into<String>,
+        value: impl Into<String>,
+    ) -> BroadcastOperators<WithoutBinary, A> {
+        BroadcastOperators::from_sock(self.ns.clone(), self.id)
+            .broadcast()
+            .where_meta(key, value)
+    }
+
     /// Get the [`SocketIo`] context related to this socket
     ///
     /// # Panics
@@ -612,70 +1632,839 @@ impl<A: Adapter> Socket<A> {
         self.esocket.data.io.get().unwrap()
     }
 
-    /// Disconnects the socket from the current namespace,
+    /// Returns true if the [`SocketIoBuilder::deny_unknown_fields`] option is enabled.
     ///
-    /// It will also call the disconnect handler if it is set.
-    pub fn disconnect(self: Arc<Self>) -> Result<(), DisconnectError> {
-        let res = self.send(Packet::disconnect(self.ns.path.clone()));
-        if let Err(SocketError::InternalChannelFull(_)) = res {
-            return Err(DisconnectError::InternalChannelFull);
-        }
+    /// [`SocketIoBuilder::deny_unknown_fields`]: crate::SocketIoBuilder#method.deny_unknown_fields
+    pub(crate) fn deny_unknown_fields(&self) -> bool {
+        self.esocket
+            .data
+            .io
+            .get()
+            .is_some_and(|io| io.config().deny_unknown_fields)
+    }
 
-        self.close(DisconnectReason::ServerNSDisconnect)?;
-        Ok(())
+    /// Returns the [`SocketIoBuilder::empty_ack_shape`] option.
+    ///
+    /// [`SocketIoBuilder::empty_ack_shape`]: crate::SocketIoBuilder#method.empty_ack_shape
+    pub(crate) fn empty_ack_shape(&self) -> crate::EmptyAckShape {
+        self.esocket
+            .data
+            .io
+            .get()
+            .map(|io| io.config().empty_ack_shape)
+            .unwrap_or_default()
     }
 
-    /// Closes the engine.io connection if it is not already closed.
-    /// Return a future that resolves when the underlying transport is closed.
-    pub(crate) async fn close_underlying_transport(&self) {
-        if !self.esocket.is_closed() {
-            #[cfg(feature = "tracing")]
-            tracing::debug!("closing underlying transport for socket: {}", self.id);
-            self.esocket.close(EIoDisconnectReason::ClosingServer);
+    /// Gets the default timeout of the acknowledgements requested to this socket.
+    ///
+    /// It is the timeout set with [`Socket::configure`], or the timeout extracted from the handshake
+    /// auth payload if [`SocketIoBuilder::ack_timeout_from_auth`] is set and the payload provides one.
+    /// Otherwise it is the [`SocketIoBuilder::ack_timeout`] value.
+    ///
+    /// [`SocketIoBuilder::ack_timeout_from_auth`]: crate::SocketIoBuilder#method.ack_timeout_from_auth
+    /// [`SocketIoBuilder::ack_timeout`]: crate::SocketIoBuilder#method.ack_timeout
+    pub fn ack_timeout(&self) -> Duration {
+        match self.config.read().unwrap().ack_timeout {
+            Some(timeout) => timeout,
+            None => self.get_io().config().ack_timeout,
         }
-        self.esocket.closed().await;
     }
 
-    pub(crate) fn set_connected(&self, connected: bool) {
-        self.connected.store(connected, Ordering::SeqCst);
+    /// Overrides the global settings for this socket, for the rest of its lifetime.
+    /// It is typically called in the connect handler, to tune the socket depending on who connected.
+    ///
+    /// The fields of the [`SocketConfig`] left to `None` keep their current value.
+    /// The engine.io buffer is shared by all the namespaces of the connection and allocated at the handshake,
+    /// so it can only be set globally with [`SocketIoBuilder::max_buffer_size`].
+    ///
+    /// [`SocketIoBuilder::max_buffer_size`]: crate::SocketIoBuilder#method.max_buffer_size
+    ///
+    /// # Example
+    /// ```
+    /// # use socketioxide::{SocketIo, extract::*, socket::SocketConfig};
+    /// # use std::time::Duration;
+    /// # use serde_json::Value;
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.ns("/", |socket: SocketRef, Data::<Value>(auth)| {
+    ///     if auth["plan"] == "premium" {
+    ///         socket.configure(SocketConfig {
+    ///             ack_timeout: Some(Duration::from_secs(30)),
+    ///             max_rooms: Some(100),
+    ///             ..Default::default()
+    ///         });
+    ///     }
+    /// });
+    /// ```
+    pub fn configure(&self, config: SocketConfig) {
+        let mut current = self.config.write().unwrap();
+        current.ack_timeout = config.ack_timeout.or(current.ack_timeout);
+        current.max_rooms = config.max_rooms.or(current.max_rooms);
+        current.max_listeners = config.max_listeners.or(current.max_listeners);
     }
 
-    /// Gets the current namespace path.
-    #[inline]
-    pub fn ns(&self) -> &str {
-        &self.ns.path
+    /// Gets the reconnection attempt count reported by the client in its handshake auth payload.
+    ///
+    /// It is `None` if [`SocketIoBuilder::reconnect_attempts_from_auth`] is not set
+    /// or if the payload doesn't provide it.
+    ///
+    /// [`SocketIoBuilder::reconnect_attempts_from_auth`]: crate::SocketIoBuilder#method.reconnect_attempts_from_auth
+    ///
+    /// # Example
+    /// ```
+    /// # use socketioxide::{SocketIo, extract::*};
+    /// let (_, io) = SocketIo::builder()
+    ///     .reconnect_attempts_from_auth(|auth| auth.get("attempts")?.as_u64()?.try_into().ok())
+    ///     .build_svc();
+    /// io.ns("/", |socket: SocketRef| {
+    ///     if socket.reconnect_attempts().unwrap_or(0) > 5 {
+    ///         println!("socket {} is flapping", socket.id);
+    ///     }
+    /// });
+    /// ```
+    pub fn reconnect_attempts(&self) -> Option<u32> {
+        self.reconnect_attempts.get().copied()
     }
 
-    pub(crate) fn reserve(&self) -> Result<Permit<'_>, SocketError<()>> {
-        Ok(self.esocket.reserve()?)
+    /// Gets the user id read from the handshake auth payload of the client.
+    ///
+    /// It is `None` if [`SocketIoBuilder::user_id_from_auth`] is not set
+    /// or if the payload doesn't provide it.
+    ///
+    /// [`SocketIoBuilder::user_id_from_auth`]: crate::SocketIoBuilder#method.user_id_from_auth
+    pub fn user_id(&self) -> Option<&str> {
+        self.user_id.get().map(String::as_str)
     }
 
-    pub(crate) fn send(&self, packet: Packet<'_>) -> Result<(), SocketError<()>> {
-        let permit = self.reserve()?;
-        permit.send(packet);
-        Ok(())
+    /// Sets the ack timeout, the reconnection attempt count and the user id of the socket
+    /// from the handshake auth payload, if an [`AckTimeoutExtractor`](crate::AckTimeoutExtractor),
+    /// a [`ReconnectAttemptsExtractor`](crate::ReconnectAttemptsExtractor)
+    /// or a [`UserIdExtractor`](crate::UserIdExtractor) is configured.
+    pub(crate) fn set_from_auth(&self, auth: &Option<String>) {
+        let (Some(io), Some(auth)) = (self.esocket.data.io.get(), auth) else {
+            return;
+        };
+        let config = io.config();
+        if config.ack_timeout_extractor.is_none()
+            && config.reconnect_attempts_extractor.is_none()
+            && config.user_id_extractor.is_none()
+        {
+            return;
+        }
+        let Ok(auth) = serde_json::from_str::<Value>(auth) else {
+            return;
+        };
+        let timeout = config.ack_timeout_extractor.as_ref();
+        if let Some(timeout) = timeout.and_then(|extractor| extractor.extract(&auth)) {
+            self.config.write().unwrap().ack_timeout = Some(timeout);
+        }
+        let attempts = config.reconnect_attempts_extractor.as_ref();
+        if let Some(attempts) = attempts.and_then(|extractor| extractor.extract(&auth)) {
+            self.reconnect_attempts.set(attempts).ok();
+        }
+        let user_id = config.user_id_extractor.as_ref();
+        if let Some(user_id) = user_id.and_then(|extractor| extractor.extract(&auth)) {
+            self.user_id.set(user_id).ok();
+        }
     }
 
-    pub(crate) fn send_with_ack_permit(
-        &self,
-        mut packet: Packet<'_>,
-        permit: Permit<'_>,
-    ) -> Receiver<AckResult<Value>> {
-        let (tx, rx) = oneshot::channel();
+    /// Disconnects the socket from the current namespace,
+    ///
+    /// It will also call the disconnect handler if it is set.
+    pub fn disconnect(self: Arc<Self>) -> Result<(), DisconnectError> {
+        let res = self.send(Packet::disconnect(self.ns.path.clone()));
+        if let Err(SocketError::InternalChannelFull(_)) = res {
+            return Err(DisconnectError::InternalChannelFull);
+        }
 
-        let ack = self.ack_counter.fetch_add(1, Ordering::SeqCst) + 1;
-        packet.inner.set_ack_id(ack);
-        permit.send(packet);
-        self.ack_message.lock().unwrap().insert(ack, tx);
-        rx
+        self.close(DisconnectReason::ServerNSDisconnect)?;
+        Ok(())
     }
 
-    pub(crate) fn send_with_ack(&self, mut packet: Packet<'_>) -> Receiver<AckResult<Value>> {
-        let (tx, rx) = oneshot::channel();
-
+    /// Gracefully disconnects the socket from the current namespace: the events received after this call
+    /// are dropped unless the [`DisconnectingEventPolicy`] of the namespace dispatches them, and the returned future
+    /// disconnects the socket once its running handlers complete, or after the given timeout if they take longer.
+    ///
+    /// Only the async and [blocking](crate::handler::MessageHandler::blocking) handlers can be in flight,
+    /// a sync handler completes before the next event is processed. In an
+    /// [ordered namespace](crate::SocketIo#method.ordered_ns), the handlers queued before the call are also awaited.
+    /// The acknowledgements are still received while draining. If the client reconnects to the namespace meanwhile,
+    /// the new connection is only handled once this socket is disconnected.
+    ///
+    /// ⚠️ When called from an async event handler, this handler is itself in flight:
+    /// spawn the returned future instead of awaiting it in the handler,
+    /// otherwise the socket is only disconnected after the timeout.
+    ///
+    /// # Errors
+    /// The same errors as [`disconnect()`](#method.disconnect).
+    ///
+    /// # Example
+    /// ```
+    /// # use socketioxide::{SocketIo, extract::SocketRef};
+    /// # use std::time::Duration;
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.ns("/", |socket: SocketRef| {
+    ///     socket.on("logout", |socket: SocketRef| {
+    ///         tokio::spawn(socket.disconnect_graceful(Duration::from_secs(5)));
+    ///     });
+    /// });
+    /// ```
+    pub fn disconnect_graceful(
+        self: Arc<Self>,
+        timeout: Duration,
+    ) -> impl Future<Output = Result<(), DisconnectError>> + Send + 'static {
+        // The events are dropped right away, even if the future is spawned
+        self.draining.store(true, Ordering::Release);
+        async move {
+            let drained = async {
+                loop {
+                    // The future is created before the check so that a completion in between is not missed
+                    let notified = self.drained.notified();
+                    if self.in_flight.load(Ordering::Acquire) == 0 {
+                        return;
+                    }
+                    notified.await;
+                }
+            };
+            if tokio::time::timeout(timeout, drained).await.is_err() {
+                #[cfg(feature = "tracing")]
+                tracing::debug!(
+                    ?self.id,
+                    "graceful disconnect timed out with {} handlers in flight",
+                    self.in_flight.load(Ordering::Acquire)
+                );
+            }
+            if !self.connected() {
+                return Ok(());
+            }
+            self.disconnect()
+        }
+    }
+
+    /// Requests the client to reconnect to another node, e.g. to drain this node during a rolling deploy.
+    ///
+    /// A [`MIGRATE_EVENT`] is emitted right away to the client with the `target_hint`, and the returned future
+    /// waits for its acknowledgement. The socket is then disconnected from the namespace
+    /// with the [`DisconnectReason::Migrating`] reason. See the [`migration`](crate::migration) module.
+    ///
+    /// ## Errors
+    /// * If the client doesn't acknowledge the event before the ack timeout, an [`AckError::Timeout`] is returned.
+    /// * If the underlying engine.io connection is closed or if the packet buffer is full,
+    /// an [`AckError::Socket`] is returned.
+    ///
+    /// The socket stays connected on an error, it can then be disconnected with [`disconnect()`](#method.disconnect).
+    ///
+    /// [`AckError::Timeout`]: crate::AckError::Timeout
+    /// [`AckError::Socket`]: crate::AckError::Socket
+    /// ## Example
+    /// ```
+    /// # use socketioxide::{SocketIo, extract::*};
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.ns("/", |socket: SocketRef| {
+    ///     socket.on("drain", |socket: SocketRef| async move {
+    ///         if socket.clone().request_reconnect(None).await.is_err() {
+    ///             socket.disconnect().ok();
+    ///         }
+    ///     });
+    /// });
+    /// ```
+    pub fn request_reconnect(
+        self: Arc<Self>,
+        target_hint: Option<String>,
+    ) -> impl Future<Output = Result<(), AckError<()>>> + Send + 'static {
+        let migration = Migration {
+            target: target_hint,
+        };
+        let ack = self
+            .emit_with_ack::<_, Value>(MIGRATE_EVENT, migration)
+            .map_err(|e| match e {
+                SendError::Serialize(e) => AckError::Serde(e),
+                SendError::Schema(e) => AckError::Schema(e),
+                SendError::Socket(e) => AckError::Socket(e.without_value()),
+            });
+        async move {
+            ack?.await?;
+            #[cfg(feature = "tracing")]
+            tracing::debug!(?self.id, "disconnecting migrating socket");
+            if self.connected() {
+                self.send(Packet::disconnect(self.ns.path.clone())).ok();
+                if let Err(_e) = self.close(DisconnectReason::Migrating) {
+                    #[cfg(feature = "tracing")]
+                    tracing::debug!("error while disconnecting migrating socket: {_e:?}");
+                }
+            }
+            Ok(())
+        }
+    }
+
+    /// Moves the socket from its current namespace to the namespace at the given path
+    /// without closing the underlying engine.io connection.
+    ///
+    /// The socket is disconnected from the current namespace as with [`disconnect()`](#method.disconnect):
+    /// its disconnect handler is called with [`DisconnectReason::ServerNSDisconnect`], it leaves all its rooms
+    /// and a `DISCONNECT` packet is sent to the client. Then a new socket is connected to the target namespace
+    /// as if the client requested it: the middlewares and the connect handler of the target namespace are called
+    /// without any auth payload, and a `CONNECT` packet is sent to the client.
+    ///
+    /// The new socket has its own id, rooms and extensions, nothing is carried over from the current namespace.
+    ///
+    /// ## Errors
+    /// If the target namespace does not exist, [`SwitchNamespaceError::InvalidNamespace`] is returned
+    /// and the socket stays connected to its current namespace.
+    ///
+    /// # Example
+    /// ```
+    /// # use socketioxide::{SocketIo, extract::SocketRef};
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.ns("/lobby", |socket: SocketRef| {
+    ///     socket.on("ready", |socket: SocketRef| {
+    ///         socket.switch_namespace("/game").ok();
+    ///     });
+    /// });
+    /// io.ns("/game", |socket: SocketRef| {
+    ///     println!("socket {} joined the game", socket.id);
+    /// });
+    /// ```
+    pub fn switch_namespace(
+        self: Arc<Self>,
+        path: impl Into<String>,
+    ) -> Result<(), SwitchNamespaceError> {
+        let path = path.into();
+        let esocket = self.esocket.clone();
+        let io = match esocket.data.io.get() {
+            Some(io) if io.client().has_ns(&path) => io,
+            _ => return Err(SwitchNamespaceError::InvalidNamespace(path)),
+        };
+        self.disconnect()?;
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(?esocket.id, path, "switching namespace");
+        io.client().sock_connect(None, Str::from(path), &esocket);
+        Ok(())
+    }
+
+    /// Schedules the disconnection of the socket from the current namespace after the given delay.
+    ///
+    /// A `disconnect_scheduled` event is immediately emitted to the client with the given reason
+    /// and the delay in milliseconds as arguments, so it can clean up before being disconnected.
+    /// The disconnection can be canceled with [`cancel_scheduled_disconnect()`](#method.cancel_scheduled_disconnect).
+    ///
+    /// If a disconnection was already scheduled, it is replaced by this one.
+    ///
+    /// ## Errors
+    /// If the `disconnect_scheduled` event cannot be emitted, the error is returned
+    /// and the disconnection is not scheduled.
+    pub fn schedule_disconnect<T: Serialize>(
+        self: Arc<Self>,
+        delay: Duration,
+        reason: T,
+    ) -> Result<(), SendError<Value>> {
+        self.emit("disconnect_scheduled", (reason, delay.as_millis() as u64))?;
+
+        // The lock is held until the handle is stored so that the task can't take it before
+        let mut scheduled = self.scheduled_disconnect.lock().unwrap();
+        let socket = self.clone();
+        let handle = tokio::spawn(async move {
+            tokio::time::sleep(delay).await;
+            socket.scheduled_disconnect.lock().unwrap().take();
+            if let Err(_e) = socket.disconnect() {
+                #[cfg(feature = "tracing")]
+                tracing::debug!("error while disconnecting socket: {_e:?}");
+            }
+        });
+        if let Some(prev) = scheduled.replace(handle.abort_handle()) {
+            prev.abort();
+        }
+        Ok(())
+    }
+
+    /// Cancels the disconnection scheduled with [`schedule_disconnect()`](#method.schedule_disconnect).
+    ///
+    /// Returns `true` if a disconnection was scheduled.
+    pub fn cancel_scheduled_disconnect(&self) -> bool {
+        match self.scheduled_disconnect.lock().unwrap().take() {
+            Some(handle) => {
+                handle.abort();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Emits a [`TIME_EVENT`] with the current [`ServerTime`] to the client now and then every `interval`,
+    /// so that the client can synchronize its clock, see the [`clock`](crate::clock) module.
+    ///
+    /// It can be used to synchronize only some sockets, while [`SocketIoBuilder::time_sync`] synchronizes all of them.
+    /// If the server time was already emitted, the previous interval is replaced by this one.
+    /// The emission stops when the socket is disconnected or with [`stop_time_sync()`](#method.stop_time_sync).
+    ///
+    /// [`SocketIoBuilder::time_sync`]: crate::SocketIoBuilder#method.time_sync
+    /// ## Example
+    /// ```
+    /// # use socketioxide::{SocketIo, extract::*};
+    /// # use std::time::Duration;
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.ns("/", |socket: SocketRef| {
+    ///     socket.on("game:join", |socket: SocketRef| {
+    ///         socket.sync_time(Duration::from_secs(10));
+    ///     });
+    /// });
+    /// ```
+    pub fn sync_time(self: &Arc<Self>, interval: Duration) {
+        // The lock is held until the handle is stored so that a concurrent call can't replace it before
+        let mut time_sync = self.time_sync.lock().unwrap();
+        let socket = Arc::downgrade(self);
+        let handle = tokio::spawn(async move {
+            let mut interval = tokio::time::interval(interval);
+            loop {
+                interval.tick().await;
+                let socket = match socket.upgrade() {
+                    Some(socket) if socket.connected() => socket,
+                    _ => return,
+                };
+                if let Err(_e) = socket.emit(TIME_EVENT, ServerTime::now()) {
+                    #[cfg(feature = "tracing")]
+                    tracing::debug!(?socket.id, "error while emitting the server time: {_e:?}");
+                }
+            }
+        });
+        if let Some(prev) = time_sync.replace(handle.abort_handle()) {
+            prev.abort();
+        }
+    }
+
+    /// Stops the emission of the server time started with [`sync_time()`](#method.sync_time)
+    /// or [`SocketIoBuilder::time_sync`](crate::SocketIoBuilder#method.time_sync).
+    ///
+    /// Returns `true` if the server time was emitted.
+    pub fn stop_time_sync(&self) -> bool {
+        match self.time_sync.lock().unwrap().take() {
+            Some(handle) => {
+                handle.abort();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Measures the round-trip time to the client, by emitting a [`TIME_EVENT`] with an acknowledgement
+    /// and waiting for it. The client must acknowledge the event, see the [`clock`](crate::clock) module.
+    ///
+    /// ## Errors
+    /// * If the client doesn't acknowledge the event before the ack timeout, an [`AckError::Timeout`] is returned.
+    /// * If the underlying engine.io connection is closed or if the packet buffer is full,
+    /// an [`AckError::Socket`] is returned.
+    ///
+    /// [`AckError::Timeout`]: crate::AckError::Timeout
+    /// [`AckError::Socket`]: crate::AckError::Socket
+    /// ## Example
+    /// ```
+    /// # use socketioxide::{SocketIo, extract::*};
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.ns("/", |socket: SocketRef| async move {
+    ///     match socket.measure_rtt().await {
+    ///         Ok(rtt) => println!("rtt: {:?}", rtt),
+    ///         Err(e) => println!("could not measure the rtt: {e}"),
+    ///     }
+    /// });
+    /// ```
+    pub async fn measure_rtt(&self) -> Result<Duration, AckError<()>> {
+        let start = Instant::now();
+        let ack = self
+            .emit_with_ack::<_, Value>(TIME_EVENT, ServerTime::now())
+            .map_err(|e| match e {
+                SendError::Serialize(e) => AckError::Serde(e),
+                SendError::Schema(e) => AckError::Schema(e),
+                SendError::Socket(e) => AckError::Socket(e.without_value()),
+            })?;
+        ack.await?;
+        Ok(start.elapsed())
+    }
+
+    /// Sets the serializable data of the socket, replacing the previous one.
+    ///
+    /// Contrary to the [`extensions`](#structfield.extensions), the data can be fetched
+    /// from any node of the cluster with a [`RemoteSocket`](crate::remote::RemoteSocket).
+    ///
+    /// ## Errors
+    /// If the data cannot be serialized, the error is returned and the previous data is kept.
+    pub fn set_data<T: Serialize>(&self, data: T) -> Result<(), serde_json::Error> {
+        let data = serde_json::to_value(data)?;
+        self.data.write().unwrap().replace(data);
+        Ok(())
+    }
+
+    /// Replays the event packets broadcast to the socket after the given `offset`,
+    /// without reconnecting. Returns the number of replayed packets.
+    ///
+    /// The client can trigger it by emitting a [`RESYNC_EVENT`](crate::replay::RESYNC_EVENT) event
+    /// with the last offset it received. See the [`replay`](crate::replay) module doc for more details.
+    ///
+    /// ## Errors
+    /// * [`ResyncError::Disabled`] if the [replay buffer](crate::SocketIoBuilder#method.replay_buffer) is not enabled.
+    /// * [`ResyncError::OffsetExpired`] if some of the packets were already dropped from the buffer,
+    /// nothing is replayed in this case.
+    /// * [`ResyncError::Socket`] if the packets could not be sent.
+    pub fn resync(&self, offset: u64) -> Result<usize, ResyncError> {
+        let replay = self.replay.as_ref().ok_or(ResyncError::Disabled)?;
+        let packets = replay.lock().unwrap().since(offset)?;
+        let count = packets.len();
+        for packet in packets {
+            self.send(packet)?;
+        }
+        Ok(count)
+    }
+
+    /// Gets the offset of the last packet recorded in the replay buffer,
+    /// or `None` if the [replay buffer](crate::SocketIoBuilder#method.replay_buffer) is not enabled.
+    pub fn replay_offset(&self) -> Option<u64> {
+        self.replay
+            .as_ref()
+            .map(|replay| replay.lock().unwrap().last_offset())
+    }
+
+    /// Returns the auth result cached for `key` on the engine.io session of this socket,
+    /// or computes it with `validate` and caches it if it succeeds.
+    ///
+    /// A client connecting to several namespaces through the same session can then be
+    /// authenticated once, e.g. the `key` can be the token sent by the client and `validate`
+    /// the verification of this token. The cache is shared by all the namespaces
+    /// and is cleared when the session closes.
+    ///
+    /// If the cached result cannot be deserialized to `T`, it is validated again.
+    ///
+    /// #### Example
+    /// ```
+    /// # use socketioxide::{SocketIo, extract::*};
+    /// # use std::convert::Infallible;
+    /// fn auth(socket: SocketRef, Data(token): Data<String>) -> Result<(), &'static str> {
+    ///     let user: String = socket.cached_auth(&token, || match token.as_str() {
+    ///         "secret" => Ok("admin".to_string()),
+    ///         _ => Err("invalid token"),
+    ///     })?;
+    ///     socket.extensions.insert(user);
+    ///     Ok(())
+    /// }
+    /// # use socketioxide::handler::ConnectHandler;
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.ns("/", (|| {}).with(auth));
+    /// io.ns("/admin", (|| {}).with(auth));
+    /// ```
+    pub fn cached_auth<T, E>(
+        &self,
+        key: &str,
+        validate: impl FnOnce() -> Result<T, E>,
+    ) -> Result<T, E>
+    where
+        T: Serialize + DeserializeOwned,
+    {
+        let cached = self
+            .esocket
+            .data
+            .auth_cache
+            .lock()
+            .unwrap()
+            .get(key)
+            .cloned();
+        if let Some(data) = cached.and_then(|data| serde_json::from_value(data).ok()) {
+            return Ok(data);
+        }
+        let data = validate()?;
+        if let Ok(value) = serde_json::to_value(&data) {
+            let mut cache = self.esocket.data.auth_cache.lock().unwrap();
+            cache.insert(key.to_string(), value);
+        }
+        Ok(data)
+    }
+
+    /// Sets the metadata `key` of the socket to `value`, replacing the previous value.
+    ///
+    /// Contrary to the [`extensions`](#structfield.extensions) which are keyed by type,
+    /// the metadata are string key/values indexed by the adapter so that the sockets can be selected
+    /// efficiently when broadcasting with the [`where_meta()`](#method.where_meta) operator.
+    /// They are removed when the socket disconnects.
+    ///
+    /// ## Errors
+    /// When using a distributed adapter, it can return an [`Adapter::Error`] which is mostly related to network errors.
+    /// For the default [`LocalAdapter`] it is always an [`Infallible`](std::convert::Infallible) error
+    ///
+    /// #### Example
+    /// ```
+    /// # use socketioxide::{SocketIo, extract::*};
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.ns("/", |socket: SocketRef| {
+    ///     socket.set_meta("region", "eu").unwrap();
+    ///     assert_eq!(socket.meta("region").as_deref(), Some("eu"));
+    /// });
+    /// ```
+    pub fn set_meta(
+        &self,
+        key: impl Into<String>,
+        value: impl Into<String>,
+    ) -> Result<(), A::Error> {
+        let (key, value) = (key.into(), value.into());
+        self.ns.adapter.set_meta(self.id, &key, &value)?;
+        self.meta.write().unwrap().insert(key, value);
+        Ok(())
+    }
+
+    /// Gets the metadata `key` of the socket set with [`set_meta()`](#method.set_meta).
+    pub fn meta(&self, key: &str) -> Option<String> {
+        self.meta.read().unwrap().get(key).cloned()
+    }
+
+    /// Gets the data of the socket set with [`set_data()`](#method.set_data).
+    /// Returns `None` if the data is not set.
+    ///
+    /// ## Errors
+    /// If the data cannot be deserialized to `T`, the error is returned.
+    pub fn data<T: DeserializeOwned>(&self) -> Result<Option<T>, serde_json::Error> {
+        self.raw_data().map(serde_json::from_value).transpose()
+    }
+
+    pub(crate) fn raw_data(&self) -> Option<Value> {
+        self.data.read().unwrap().clone()
+    }
+
+    /// Returns the last time an event or an acknowledgement was received from the client,
+    /// or the connection time if nothing was received yet.
+    ///
+    /// The engine.io heartbeats are not considered as activity.
+    pub fn last_activity(&self) -> Instant {
+        *self.last_activity.lock().unwrap()
+    }
+
+    /// Spawns a task disconnecting the socket once it did not have any activity
+    /// for the [`SocketIoConfig::idle_timeout`](crate::SocketIoConfig::idle_timeout), if any.
+    ///
+    /// The task only holds a weak reference to the socket so it does not keep it alive.
+    pub(crate) fn spawn_idle_reaper(self: &Arc<Self>) {
+        let Some(idle_timeout) = self
+            .esocket
+            .data
+            .io
+            .get()
+            .and_then(|io| io.config().idle_timeout)
+        else {
+            return;
+        };
+        let socket = Arc::downgrade(self);
+        tokio::spawn(async move {
+            loop {
+                let deadline = match socket.upgrade() {
+                    Some(socket) if socket.connected() => socket.last_activity() + idle_timeout,
+                    _ => return,
+                };
+                tokio::time::sleep_until(deadline.into()).await;
+                let Some(socket) = socket.upgrade() else {
+                    return;
+                };
+                if socket.connected() && socket.last_activity().elapsed() >= idle_timeout {
+                    #[cfg(feature = "tracing")]
+                    tracing::debug!(?socket.id, "disconnecting idle socket");
+                    socket.send(Packet::disconnect(socket.ns.path.clone())).ok();
+                    if let Err(_e) = socket.close(DisconnectReason::IdleTimeout) {
+                        #[cfg(feature = "tracing")]
+                        tracing::debug!("error while disconnecting idle socket: {_e:?}");
+                    }
+                    return;
+                }
+            }
+        });
+    }
+
+    /// Closes the engine.io connection if it is not already closed.
+    /// Return a future that resolves when the underlying transport is closed.
+    pub(crate) async fn close_underlying_transport(&self) {
+        if !self.esocket.is_closed() {
+            #[cfg(feature = "tracing")]
+            tracing::debug!("closing underlying transport for socket: {}", self.id);
+            self.esocket.close(EIoDisconnectReason::ClosingServer);
+        }
+        self.esocket.closed().await;
+    }
+
+    pub(crate) fn set_connected(&self, connected: bool) {
+        self.connected.store(connected, Ordering::SeqCst);
+    }
+
+    /// Gets the current namespace path.
+    #[inline]
+    pub fn ns(&self) -> &str {
+        &self.ns.path
+    }
+
+    pub(crate) fn reserve(&self) -> Result<Permit<'_>, SocketError<()>> {
+        Ok(self.esocket.reserve()?)
+    }
+
+    pub(crate) async fn reserve_wait(&self) -> Result<Permit<'_>, SocketError<()>> {
+        Ok(self.esocket.reserve_wait().await?)
+    }
+
+    /// Waits for the transport to flush all the packets queued before this call.
+    /// Returns `false` if the socket is closed before.
+    pub(crate) async fn flushed(&self) -> bool {
+        self.esocket.flushed().await
+    }
+
+    /// Appends the next packet id to the event packets if the packet ids are enabled.
+    pub(crate) fn tag_packet(&self, packet: &mut Packet<'_>) {
+        let Some(packet_id) = &self.packet_id else {
+            return;
+        };
+        let data = match &mut packet.inner {
+            PacketData::Event(_, data, _) => data,
+            PacketData::BinaryEvent(_, bin, _) => &mut bin.data,
+            _ => return,
+        };
+        let id = packet_id.fetch_add(1, Ordering::SeqCst) + 1;
+        let tag = serde_json::json!({ PACKET_ID_KEY: id });
+        match data {
+            Value::Array(args) => args.push(tag),
+            data => *data = Value::Array(vec![data.take(), tag]),
+        }
+    }
+
+    pub(crate) fn send(&self, mut packet: Packet<'_>) -> Result<(), SocketError<()>> {
+        self.tag_packet(&mut packet);
+        self.send_untagged(packet)
+    }
+
+    fn send_untagged(&self, packet: Packet<'_>) -> Result<(), SocketError<()>> {
+        let permit = match self.reserve() {
+            Ok(permit) => permit,
+            Err(e) => {
+                let event = match &packet.inner {
+                    PacketData::Event(e, _, _) | PacketData::BinaryEvent(e, _, _) => Some(&**e),
+                    _ => None,
+                };
+                self.notify_drop((&e).into(), event);
+                return Err(e);
+            }
+        };
+        self.notify_emit(&packet);
+        self.record_bytes_out(permit.send(packet));
+        Ok(())
+    }
+
+    /// Sends a broadcast packet, after recording it in the replay buffer if it is enabled.
+    pub(crate) fn send_broadcast(&self, mut packet: Packet<'_>) -> Result<(), SocketError<()>> {
+        if let Some(replay) = &self.replay {
+            replay.lock().unwrap().record(&mut packet);
+        }
+        self.send(packet)
+    }
+
+    pub(crate) fn send_with_ack_permit(
+        &self,
+        mut packet: Packet<'_>,
+        permit: Permit<'_>,
+    ) -> (Receiver<AckResult<Value>>, AckHandle) {
+        let (tx, rx) = oneshot::channel();
+        if self.too_many_pending_acks() {
+            tx.send(Err(AckError::TooManyPending)).ok();
+            return (rx, AckHandle::new(&self.ack_message, 0));
+        }
+
+        self.tag_packet(&mut packet);
+        let ack = self.send_untagged_with_ack_permit(packet, permit, tx);
+        (rx, AckHandle::new(&self.ack_message, ack))
+    }
+
+    /// Sends a packet with an acknowledgement, re-sending it with a new ack id
+    /// each time the client doesn't acknowledge it before the timeout, up to `retries` times.
+    ///
+    /// The packet is tagged once so all the attempts carry the same packet id.
+    pub(crate) fn send_with_ack_retries(
+        &self,
+        mut packet: Packet<'static>,
+        permit: Permit<'_>,
+        timeout: Duration,
+        retries: u32,
+        delay: Duration,
+    ) -> (Receiver<AckResult<Value>>, AckHandle) {
+        let (tx, rx) = oneshot::channel();
+        if self.too_many_pending_acks() {
+            tx.send(Err(AckError::TooManyPending)).ok();
+            return (rx, AckHandle::new(&self.ack_message, 0));
+        }
+
+        self.tag_packet(&mut packet);
+        let (first_tx, first) = oneshot::channel();
+        let ack = self.send_untagged_with_ack_permit(packet.clone(), permit, first_tx);
+        let handle = AckHandle::new(&self.ack_message, ack);
+        let socket = self.ns.get_socket(self.id).ok();
+        let task_handle = handle.clone();
+        tokio::spawn(async move {
+            let handle = task_handle;
+            let mut attempt = first;
+            let mut remaining = retries;
+            let res = loop {
+                match tokio::time::timeout(timeout, attempt).await {
+                    Ok(Ok(res)) => break res,
+                    Ok(Err(_)) => break Err(AckError::Socket(SocketError::Closed(()))),
+                    Err(_) => {}
+                }
+                let socket = match &socket {
+                    Some(socket) if remaining > 0 && socket.connected() && !tx.is_closed() => {
+                        socket
+                    }
+                    _ => break Err(AckError::Timeout),
+                };
+                remaining -= 1;
+                tokio::time::sleep(delay).await;
+                if handle.cancelled() {
+                    break Err(AckError::Cancelled);
+                }
+                #[cfg(feature = "tracing")]
+                tracing::debug!(
+                    "retrying unacknowledged packet for socket {}, {} retries left",
+                    socket.id,
+                    remaining
+                );
+                let (rx, ack) = socket.send_untagged_with_ack(packet.clone());
+                handle.set_id(ack);
+                // The handle may have been cancelled with the previous id
+                if handle.cancelled() {
+                    handle.cancel();
+                }
+                attempt = rx;
+            };
+            tx.send(res).ok();
+        });
+        (rx, handle)
+    }
+
+    fn send_untagged_with_ack_permit(
+        &self,
+        mut packet: Packet<'_>,
+        permit: Permit<'_>,
+        tx: oneshot::Sender<AckResult<Value>>,
+    ) -> i64 {
         let ack = self.ack_counter.fetch_add(1, Ordering::SeqCst) + 1;
         packet.inner.set_ack_id(ack);
-        match self.send(packet) {
+        self.notify_emit(&packet);
+        self.record_bytes_out(permit.send(packet));
+        self.ack_message.lock().unwrap().insert(ack, tx);
+        ack
+    }
+
+    pub(crate) fn send_with_ack(&self, mut packet: Packet<'_>) -> Receiver<AckResult<Value>> {
+        self.tag_packet(&mut packet);
+        self.send_untagged_with_ack(packet).0
+    }
+
+    /// Sends a packet with an acknowledgement, returning the receiver and the ack id.
+    fn send_untagged_with_ack(&self, mut packet: Packet<'_>) -> (Receiver<AckResult<Value>>, i64) {
+        let (tx, rx) = oneshot::channel();
+        if self.too_many_pending_acks() {
+            tx.send(Err(AckError::TooManyPending)).ok();
+            return (rx, 0);
+        }
+
+        let ack = self.ack_counter.fetch_add(1, Ordering::SeqCst) + 1;
+        packet.inner.set_ack_id(ack);
+        match self.send_untagged(packet) {
             Ok(()) => {
                 self.ack_message.lock().unwrap().insert(ack, tx);
             }
@@ -683,7 +2472,26 @@ impl<A: Adapter> Socket<A> {
                 tx.send(Err(AckError::Socket(e))).ok();
             }
         }
-        rx
+        (rx, ack)
+    }
+
+    /// Returns true if the [`SocketIoBuilder::max_pending_acks`] limit is reached.
+    /// The acks that are not awaited anymore, e.g. because they timed out, are forgotten first.
+    ///
+    /// [`SocketIoBuilder::max_pending_acks`]: crate::SocketIoBuilder#method.max_pending_acks
+    fn too_many_pending_acks(&self) -> bool {
+        let max = self
+            .esocket
+            .data
+            .io
+            .get()
+            .and_then(|io| io.config().max_pending_acks);
+        let Some(max) = max else {
+            return false;
+        };
+        let mut acks = self.ack_message.lock().unwrap();
+        acks.retain(|_, tx| !tx.is_closed());
+        acks.len() >= max
     }
 
     /// Called when the socket is gracefully disconnected from the server or the client
@@ -691,6 +2499,8 @@ impl<A: Adapter> Socket<A> {
     /// It maybe also close when the underlying transport is closed or failed.
     pub(crate) fn close(self: Arc<Self>, reason: DisconnectReason) -> Result<(), AdapterError> {
         self.set_connected(false);
+        self.cancel_scheduled_disconnect();
+        self.stop_time_sync();
 
         let handler = { self.disconnect_handler.lock().unwrap().take() };
         if let Some(handler) = handler {
@@ -699,13 +2509,235 @@ impl<A: Adapter> Socket<A> {
 
             handler.call(self.clone(), reason);
         }
+        let ns_handler = { self.ns_disconnect_handler.lock().unwrap().take() };
+        if let Some(handler) = ns_handler.filter(|_| reason == DisconnectReason::ClientNSDisconnect)
+        {
+            handler.call(self.clone(), reason);
+        }
 
+        let left = self
+            .tracked_joined_rooms()
+            .map_err(|err| AdapterError(Box::new(err)))?;
+        let hook = self.leave_hook();
+        let rooms = match hook.is_some() || self.has_event_sink() {
+            true => self.rooms().map_err(|err| AdapterError(Box::new(err)))?,
+            false => Vec::new(),
+        };
         self.ns.remove_socket(self.id)?;
+        {
+            let mut ns_sids = self.esocket.data.ns_sids.lock().unwrap();
+            if ns_sids.get(self.ns.path.as_str()) == Some(&self.id) {
+                ns_sids.remove(self.ns.path.as_str());
+            }
+        }
+        self.closed.store(true, Ordering::Release);
+        self.closed_notify.notify_waiters();
+        self.notify_leave(&rooms);
+        self.emit_presence(PRESENCE_LEAVE_EVENT, left);
+        self.call_leave_hook(hook, rooms);
+        self.notify(|| LifecycleEvent::Disconnect {
+            ns: self.ns.path.clone(),
+            sid: self.id,
+            reason,
+        });
         Ok(())
     }
 
+    /// Whether the socket is disconnecting: gracefully draining its handlers or being closed,
+    /// but not yet removed from its namespace.
+    pub(crate) fn disconnecting(&self) -> bool {
+        !self.closed.load(Ordering::Acquire)
+            && (self.draining.load(Ordering::Acquire) || !self.connected())
+    }
+
+    /// Waits for the socket to be closed and removed from its namespace.
+    pub(crate) async fn closed(&self) {
+        loop {
+            // The future is created before the check so that a close in between is not missed
+            let notified = self.closed_notify.notified();
+            if self.closed.load(Ordering::Acquire) {
+                return;
+            }
+            notified.await;
+        }
+    }
+
+    /// Sends a lifecycle event to the sink registered with [`SocketIo::on_event`] if there is one.
+    pub(crate) fn notify(&self, event: impl FnOnce() -> LifecycleEvent) {
+        if let Some(io) = self.esocket.data.io.get() {
+            io.notify(event);
+        }
+    }
+
+    /// Returns true if a sink is registered with [`SocketIo::on_event`].
+    fn has_event_sink(&self) -> bool {
+        self.esocket
+            .data
+            .io
+            .get()
+            .is_some_and(|io| io.has_event_sink())
+    }
+
+    /// Sends a [`LifecycleEvent::Leave`] event for the rooms left all at once,
+    /// when leaving all the rooms or being disconnected.
+    fn notify_leave(&self, rooms: &[Room]) {
+        if !rooms.is_empty() {
+            self.notify(|| LifecycleEvent::Leave {
+                ns: self.ns.path.clone(),
+                sid: self.id,
+                rooms: rooms.to_vec(),
+            });
+        }
+    }
+
+    /// Returns the hook registered with [`SocketIo::on_leave`] if there is one.
+    fn leave_hook(&self) -> Option<BoxedLeaveHook> {
+        self.esocket.data.io.get().and_then(|io| io.leave_hook())
+    }
+
+    /// Calls the leave hook for each room left by the socket.
+    fn call_leave_hook(&self, hook: Option<BoxedLeaveHook>, rooms: Vec<Room>) {
+        if let Some(hook) = hook {
+            for room in &rooms {
+                hook(self.id, room);
+            }
+        }
+    }
+
+    /// Returns the given rooms that have their presence tracked
+    /// and that the socket has joined (`joined = true`) or not (`joined = false`).
+    fn tracked_rooms(&self, rooms: &[Room], joined: bool) -> Result<Vec<Room>, A::Error> {
+        let tracked = self.ns.tracked_rooms(rooms);
+        if tracked.is_empty() {
+            return Ok(tracked);
+        }
+        let current = self.rooms()?;
+        Ok(tracked
+            .into_iter()
+            .filter(|room| current.contains(room) == joined)
+            .collect())
+    }
+
+    /// Returns all the rooms joined by the socket that have their presence tracked.
+    fn tracked_joined_rooms(&self) -> Result<Vec<Room>, A::Error> {
+        if self.ns.presence_rooms.read().unwrap().is_empty() {
+            return Ok(Vec::new());
+        }
+        Ok(self.ns.tracked_rooms(&self.rooms()?))
+    }
+
+    /// Emits a presence event to the other members of each given room.
+    fn emit_presence(&self, event: &'static str, rooms: Vec<Room>) {
+        for room in rooms {
+            let update = PresenceUpdate {
+                room: room.clone(),
+                sid: self.id,
+            };
+            if let Err(_e) = self.to(room).emit(event, update) {
+                #[cfg(feature = "tracing")]
+                tracing::debug!(?self.id, "error emitting presence event: {:?}", _e);
+            }
+        }
+    }
+
+    /// Sends the documents of the given rooms, see the [`state_sync`](crate::state_sync) module.
+    fn send_snapshots(&self, rooms: &[Room]) {
+        let states = self.ns.room_states.lock().unwrap();
+        if states.is_empty() {
+            return;
+        }
+        for room in rooms {
+            let Some(state) = states.get(room) else {
+                continue;
+            };
+            let snapshot = StateSnapshot {
+                room: room.clone(),
+                state: state.clone(),
+            };
+            if let Err(_e) = self.emit(SNAPSHOT_EVENT, snapshot) {
+                #[cfg(feature = "tracing")]
+                tracing::debug!(?self.id, "error sending state snapshot: {:?}", _e);
+            }
+        }
+    }
+
+    /// Sends a [`LifecycleEvent::Emit`] event if the packet is an event packet.
+    pub(crate) fn notify_emit(&self, packet: &Packet<'_>) {
+        if let PacketData::Event(e, _, _) | PacketData::BinaryEvent(e, _, _) = &packet.inner {
+            self.stats.events_sent.fetch_add(1, Ordering::Relaxed);
+            self.notify(|| LifecycleEvent::Emit {
+                ns: self.ns.path.clone(),
+                sid: self.id,
+                event: e.to_string(),
+            });
+        }
+    }
+
+    /// Records the bytes of packets sent to the client, for the [`Socket::stats`].
+    pub(crate) fn record_bytes_out(&self, size: usize) {
+        self.stats
+            .bytes_out
+            .fetch_add(size as u64, Ordering::Relaxed);
+    }
+
+    /// Records a packet of the given size received from the client, for the [`Socket::stats`].
+    ///
+    /// Returns `false` if the packet goes over the [`SocketIoConfig::byte_quota`](crate::SocketIoConfig::byte_quota)
+    /// of the socket, in which case it should not be handled.
+    pub(crate) fn record_recv(&self, packet: &PacketData<'_>, size: usize) -> bool {
+        if matches!(packet, PacketData::Event(..) | PacketData::BinaryEvent(..)) {
+            self.stats.events_received.fetch_add(1, Ordering::Relaxed);
+        }
+        self.stats
+            .bytes_in
+            .fetch_add(size as u64, Ordering::Relaxed);
+
+        let Some(config) = self.esocket.data.io.get().map(|io| io.config()) else {
+            return true;
+        };
+        let Some(quota) = config.byte_quota else {
+            return true;
+        };
+        let mut window = self.quota_window.lock().unwrap();
+        let (start, received) = &mut *window;
+        if start.elapsed() >= config.byte_quota_window {
+            *start = Instant::now();
+            *received = 0;
+        }
+        *received += size as u64;
+        *received <= quota
+    }
+
+    /// Disconnects the socket from its namespace because it went over its byte quota.
+    pub(crate) fn quota_exceeded(self: Arc<Self>) {
+        #[cfg(feature = "tracing")]
+        tracing::debug!(?self.id, "disconnecting socket over its byte quota");
+        if self.connected() {
+            self.send(Packet::disconnect(self.ns.path.clone())).ok();
+            if let Err(_e) = self.close(DisconnectReason::QuotaExceeded) {
+                #[cfg(feature = "tracing")]
+                tracing::debug!("error while disconnecting socket over its byte quota: {_e:?}");
+            }
+        }
+    }
+
+    /// Sends a [`DropEvent`] to the callback registered with [`SocketIo::on_drop`] if there is one.
+    pub(crate) fn notify_drop(&self, reason: DropReason, event: Option<&str>) {
+        if let Some(io) = self.esocket.data.io.get() {
+            io.notify_drop(|| DropEvent {
+                ns: self.ns.path.clone(),
+                sid: self.id,
+                reason,
+                event: event.map(str::to_string),
+            });
+        }
+    }
+
     // Receives data from client:
     pub(crate) fn recv(self: Arc<Self>, packet: PacketData<'_>) -> Result<(), Error> {
+        if !matches!(packet, PacketData::Disconnect) {
+            *self.last_activity.lock().unwrap() = Instant::now();
+        }
         match packet {
             PacketData::Event(e, data, ack) => self.recv_event(&e, data, ack),
             PacketData::EventAck(data, ack_id) => self.recv_ack(data, ack_id),
@@ -754,25 +2786,377 @@ impl<A: Adapter> Socket<A> {
         self.esocket.protocol.into()
     }
 
+    /// Gets the engine.io [`EIoProtocolVersion`](crate::EIoProtocolVersion) negotiated by the client
+    /// with the `EIO` query parameter when connecting with this [`Socket`].
+    ///
+    /// Engine.io v3 clients are only supported with the `v4` feature flag.
+    ///
+    /// It can also be accessed as an extractor:
+    /// ## Example
+    /// ```
+    /// # use socketioxide::{SocketIo, EIoProtocolVersion, extract::*};
+    ///
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.ns("/", |socket: SocketRef, v: EIoProtocolVersion| {
+    ///     assert_eq!(socket.protocol_version(), v);
+    /// });
+    pub fn protocol_version(&self) -> crate::EIoProtocolVersion {
+        self.esocket.protocol
+    }
+
+    /// Returns true if the client connected over a secure transport (`https` or `wss`).
+    ///
+    /// It is derived from the scheme of the handshake request uri, which is only known when the
+    /// server terminates the TLS connection itself, or from the `X-Forwarded-Proto` header
+    /// if the [`SocketIoBuilder::trust_forwarded_proto`] option is enabled.
+    ///
+    /// It can also be accessed with the [`Secure`](crate::extract::Secure) extractor.
+    ///
+    /// [`SocketIoBuilder::trust_forwarded_proto`]: crate::SocketIoBuilder#method.trust_forwarded_proto
+    pub fn is_secure(&self) -> bool {
+        let is_secure =
+            |proto: &str| proto.eq_ignore_ascii_case("https") || proto.eq_ignore_ascii_case("wss");
+        let req = &self.esocket.req_parts;
+        if req.uri.scheme_str().is_some_and(is_secure) {
+            return true;
+        }
+        let trust = self
+            .esocket
+            .data
+            .io
+            .get()
+            .is_some_and(|io| io.config().trust_forwarded_proto);
+        trust
+            && req
+                .headers
+                .get("x-forwarded-proto")
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.split(',').next())
+                .is_some_and(|proto| is_secure(proto.trim()))
+    }
+
+    /// Gets the id of the underlying engine.io session.
+    ///
+    /// A single session can back multiple sockets connected to different namespaces,
+    /// each with its own [`Socket::id`]. The session id is shared between all of them,
+    /// so it can be used to correlate the sockets of a same client.
+    ///
+    /// With the legacy [`ProtocolVersion::V4`](crate::ProtocolVersion::V4) the socket id is the session id.
+    ///
+    /// It can also be extracted with the [`EngineSessionId`](crate::extract::EngineSessionId) extractor.
+    pub fn session_id(&self) -> Sid {
+        self.esocket.id
+    }
+
+    /// Gets the number of events and bytes received from and sent to the client since the socket connected.
+    ///
+    /// The counters are updated without synchronization between them,
+    /// so a snapshot taken while packets are exchanged may be slightly inconsistent.
+    ///
+    /// It can also be accessed as an extractor:
+    /// ## Example
+    /// ```
+    /// # use socketioxide::{SocketIo, socket::SocketStats, extract::*};
+    /// let (_, io) = SocketIo::new_svc();
+    /// io.ns("/", |socket: SocketRef| {
+    ///     socket.on("quota", |socket: SocketRef, stats: SocketStats| {
+    ///         if stats.bytes_in > 1_000_000 {
+    ///             socket.disconnect().ok();
+    ///         }
+    ///     });
+    /// });
+    /// ```
+    pub fn stats(&self) -> SocketStats {
+        SocketStats {
+            events_received: self.stats.events_received.load(Ordering::Relaxed),
+            events_sent: self.stats.events_sent.load(Ordering::Relaxed),
+            bytes_in: self.stats.bytes_in.load(Ordering::Relaxed),
+            bytes_out: self.stats.bytes_out.load(Ordering::Relaxed),
+        }
+    }
+
     fn recv_event(self: Arc<Self>, e: &str, data: Value, ack: Option<i64>) -> Result<(), Error> {
+        let Some(e) = self.rewrite_event(e) else {
+            return Ok(());
+        };
+        let e = e.as_ref();
+        if e == RESYNC_EVENT && self.replay.is_some() {
+            self.recv_resync(&data);
+            return Ok(());
+        }
         if let Some(handler) = self.message_handlers.read().unwrap().get(e) {
-            handler.call(self.clone(), data, vec![], ack);
+            self.call_handler(e, handler, data, vec![], ack);
+        } else {
+            self.unknown_event(e, ack);
         }
         Ok(())
     }
 
+    /// Applies the rewriter registered with [`SocketIo::rewrite_events`] to a received event name.
+    /// Returns `None` if the event is rejected.
+    fn rewrite_event<'a>(&self, e: &'a str) -> Option<Cow<'a, str>> {
+        let rewritten = match self
+            .esocket
+            .data
+            .io
+            .get()
+            .and_then(|io| io.event_rewriter())
+        {
+            Some(rewriter) => rewriter(e),
+            None => return Some(Cow::Borrowed(e)),
+        };
+        #[cfg(feature = "tracing")]
+        if rewritten.is_none() {
+            tracing::debug!(?self.id, "event {e} rejected by the event rewriter");
+        }
+        rewritten
+    }
+
+    /// Handles a [`RESYNC_EVENT`] sent by the client with the last offset it received.
+    fn recv_resync(&self, data: &Value) {
+        let offset = match data.get(0).unwrap_or(data) {
+            Value::String(offset) => offset.parse().ok(),
+            offset => offset.as_u64(),
+        };
+        let res = match offset {
+            Some(offset) => self.resync(offset).map(|_| ()).map_err(|e| e.to_string()),
+            None => Err(format!("invalid resync offset: {data}")),
+        };
+        if let Err(message) = res {
+            #[cfg(feature = "tracing")]
+            tracing::debug!(?self.id, "resync error: {message}");
+            self.notify(|| LifecycleEvent::Error {
+                ns: Some(self.ns.path.clone()),
+                sid: self.id,
+                message,
+            });
+        }
+    }
+
     fn recv_bin_event(
         self: Arc<Self>,
         e: &str,
         packet: BinaryPacket,
         ack: Option<i64>,
     ) -> Result<(), Error> {
+        let Some(e) = self.rewrite_event(e) else {
+            return Ok(());
+        };
+        let e = e.as_ref();
         if let Some(handler) = self.message_handlers.read().unwrap().get(e) {
-            handler.call(self.clone(), packet.data, packet.bin, ack);
+            self.call_handler(e, handler, packet.data, packet.bin, ack);
+        } else {
+            self.unknown_event(e, ack);
         }
         Ok(())
     }
 
+    /// Calls a message handler, or queues it after the previous ones if the namespace is ordered.
+    /// Its panics are caught unless the [`HandlerPanicPolicy`] is [`HandlerPanicPolicy::Propagate`].
+    fn call_handler(
+        self: &Arc<Self>,
+        e: &str,
+        handler: &BoxedMessageHandler<A>,
+        data: Value,
+        bin: Vec<Bytes>,
+        ack: Option<i64>,
+    ) {
+        if self.draining.load(Ordering::Acquire) || !self.connected() {
+            match *self.ns.disconnecting_event_policy.read().unwrap() {
+                DisconnectingEventPolicy::Dispatch => (),
+                DisconnectingEventPolicy::Drop => return,
+                DisconnectingEventPolicy::DropWithLog => {
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!(?self.id, "dropping event {e} received while disconnecting");
+                    return;
+                }
+            }
+        }
+        let policy = match self.esocket.data.io.get() {
+            Some(io) => io.config().handler_panic_policy,
+            None => HandlerPanicPolicy::Propagate,
+        };
+
+        let call = AssertUnwindSafe(|| match self.ns.ordered {
+            true => handler.call_deferred(self.clone(), data, bin, ack),
+            false => handler.call_unspawned(self.clone(), data, bin, ack),
+        });
+        let fut = match policy {
+            HandlerPanicPolicy::Propagate => call(),
+            _ => match std::panic::catch_unwind(call) {
+                Ok(fut) => fut,
+                Err(panic) => return self.handler_panicked(e, policy, panic),
+            },
+        };
+        let Some(fut) = fut else {
+            return;
+        };
+        let in_flight = InFlight::new(self);
+        let fut: BoxFuture<'static, ()> = match policy {
+            HandlerPanicPolicy::Propagate => Box::pin(async move {
+                let _in_flight = in_flight;
+                fut.await
+            }),
+            _ => {
+                let socket = self.clone();
+                let e = e.to_string();
+                Box::pin(async move {
+                    let _in_flight = in_flight;
+                    if let Err(panic) = AssertUnwindSafe(fut).catch_unwind().await {
+                        socket.handler_panicked(&e, policy, panic);
+                    }
+                })
+            }
+        };
+        if self.ns.ordered {
+            self.ordered_queue().send(fut).ok();
+        } else {
+            tokio::spawn(fut);
+        }
+    }
+
+    /// Applies the [`HandlerPanicPolicy`] after the handler of the event `e` panicked.
+    fn handler_panicked(
+        self: &Arc<Self>,
+        e: &str,
+        policy: HandlerPanicPolicy,
+        panic: Box<dyn std::any::Any + Send>,
+    ) {
+        let panic = match panic.downcast::<String>() {
+            Ok(msg) => *msg,
+            Err(panic) => match panic.downcast::<&'static str>() {
+                Ok(msg) => msg.to_string(),
+                Err(_) => "unknown panic payload".to_string(),
+            },
+        };
+        #[cfg(feature = "tracing")]
+        tracing::error!(?self.id, ns = self.ns(), "handler of event {e} panicked: {panic}");
+        self.notify(|| LifecycleEvent::Error {
+            ns: Some(self.ns.path.clone()),
+            sid: self.id,
+            message: format!("handler of event {e} panicked: {panic}"),
+        });
+
+        if policy == HandlerPanicPolicy::Disconnect && self.connected() {
+            self.send(Packet::disconnect(self.ns.path.clone())).ok();
+            if let Err(_e) = self.clone().close(DisconnectReason::HandlerPanic) {
+                #[cfg(feature = "tracing")]
+                tracing::debug!("error while disconnecting socket after a panic: {_e:?}");
+            }
+        }
+    }
+
+    /// Gets the queue of the handlers of an ordered namespace,
+    /// the task running them one after the other is spawned on the first event.
+    fn ordered_queue(&self) -> &mpsc::UnboundedSender<BoxFuture<'static, ()>> {
+        self.ordered_queue.get_or_init(|| {
+            let (tx, mut rx) = mpsc::unbounded_channel::<BoxFuture<'static, ()>>();
+            tokio::spawn(async move {
+                while let Some(fut) = rx.recv().await {
+                    // Each handler is spawned so that a panic doesn't stop the queue
+                    if let Err(_e) = tokio::spawn(fut).await {
+                        #[cfg(feature = "tracing")]
+                        tracing::error!("ordered message handler failed: {_e}");
+                    }
+                }
+            });
+            tx
+        })
+    }
+
+    /// Applies the [`MaxListenersPolicy`] when a handler for a new event is registered
+    /// and `count` handlers are already registered.
+    /// Returns false if the handler should not be registered.
+    fn check_max_listeners(&self, event: &str, count: usize) -> bool {
+        let Some(io) = self.esocket.data.io.get() else {
+            return true;
+        };
+        let config = io.config();
+        let max = self.config.read().unwrap().max_listeners;
+        let max = match max.or(config.max_listeners) {
+            Some(max) if count >= max => max,
+            _ => return true,
+        };
+        match config.max_listeners_policy {
+            MaxListenersPolicy::Log => {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(
+                    ?self.id,
+                    ns = self.ns(),
+                    "max listeners ({max}) exceeded when registering the {event} handler"
+                );
+                true
+            }
+            MaxListenersPolicy::Reject => {
+                self.notify(|| LifecycleEvent::Error {
+                    ns: Some(self.ns.path.clone()),
+                    sid: self.id,
+                    message: format!(
+                        "max listeners ({max}) exceeded, the {event} handler was not registered"
+                    ),
+                });
+                false
+            }
+        }
+    }
+
+    /// Applies the [`UnknownEventPolicy`] when an event without any handler is received.
+    fn unknown_event(&self, _e: &str, ack: Option<i64>) {
+        let policy = match self.esocket.data.io.get() {
+            Some(io) => io.config().unknown_event_policy,
+            None => UnknownEventPolicy::Ignore,
+        };
+        match policy {
+            UnknownEventPolicy::Ignore => (),
+            UnknownEventPolicy::Log => {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(?self.id, ns = self.ns(), "received unknown event: {_e}");
+            }
+            UnknownEventPolicy::ErrorAck => {
+                if let Some(ack) = ack {
+                    let data = serde_json::json!({ "code": "unknown_event" });
+                    if let Err(_e) = self.send(Packet::ack(self.ns.path.clone(), data, ack)) {
+                        #[cfg(feature = "tracing")]
+                        tracing::debug!(?self.id, "error sending unknown event ack: {:?}", _e);
+                    }
+                }
+            }
+        }
+    }
+
+    /// The message of a deserialization error sent to the client,
+    /// the error itself only if [`SocketIoBuilder::expose_deser_errors`] is enabled.
+    ///
+    /// [`SocketIoBuilder::expose_deser_errors`]: crate::SocketIoBuilder#method.expose_deser_errors
+    pub(crate) fn deser_error_message(&self, e: &dyn std::error::Error) -> String {
+        let expose = match self.esocket.data.io.get() {
+            Some(io) => io.config().expose_deser_errors,
+            None => false,
+        };
+        match expose {
+            true => e.to_string(),
+            false => "bad request".to_string(),
+        }
+    }
+
+    /// Called when an extractor of a message handler fails. An error ack is sent back if the event
+    /// expects one, with the error message only if [`SocketIoBuilder::expose_deser_errors`] is enabled.
+    ///
+    /// [`SocketIoBuilder::expose_deser_errors`]: crate::SocketIoBuilder#method.expose_deser_errors
+    pub(crate) fn extract_error(&self, e: &dyn std::error::Error, ack: Option<i64>) {
+        #[cfg(feature = "tracing")]
+        tracing::error!("Error while extracting data: {}", e);
+        let Some(ack) = ack else {
+            return;
+        };
+        let message = self.deser_error_message(e);
+        let data = serde_json::json!({ "code": "bad_request", "message": message });
+        if let Err(_e) = self.send(Packet::ack(self.ns.path.clone(), data, ack)) {
+            #[cfg(feature = "tracing")]
+            tracing::debug!(?self.id, "error sending bad request ack: {:?}", _e);
+        }
+    }
+
     fn recv_ack(self: Arc<Self>, data: Value, ack: i64) -> Result<(), Error> {
         if let Some(tx) = self.ack_message.lock().unwrap().remove(&ack) {
             let res = AckResponse {
@@ -796,6 +3180,25 @@ impl<A: Adapter> Socket<A> {
     }
 }
 
+/// Counts an async handler of the socket as in flight until it is dropped,
+/// see [`Socket::disconnect_graceful`].
+struct InFlight<A: Adapter>(Arc<Socket<A>>);
+
+impl<A: Adapter> InFlight<A> {
+    fn new(socket: &Arc<Socket<A>>) -> Self {
+        socket.in_flight.fetch_add(1, Ordering::AcqRel);
+        Self(socket.clone())
+    }
+}
+
+impl<A: Adapter> Drop for InFlight<A> {
+    fn drop(&mut self) {
+        if self.0.in_flight.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.0.drained.notify_waiters();
+        }
+    }
+}
+
 impl<A: Adapter> Debug for Socket<A> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Socket")
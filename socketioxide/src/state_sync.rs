@@ -0,0 +1,130 @@
+//! Synchronization of a JSON document per room, with a snapshot sent to the joiners and patches broadcast to the members.
+//!
+//! [`BroadcastOperators::patch`] applies a [JSON merge patch](https://www.rfc-editor.org/rfc/rfc7396)
+//! to the document retained for each selected room, and emits the patch in a [`PATCH_EVENT`] to the members of the room.
+//! Each time a socket then joins the room with [`Socket::join`], [`Socket::try_join`]
+//! or the [`join`] operator, the whole document is sent to it in a [`SNAPSHOT_EVENT`].
+//!
+//! The patches can be applied twice without changing the result, so a patch received right before
+//! the snapshot that already contains it is harmless: the client replaces its document with the snapshot.
+//! The documents are kept in memory on this server until they are reset with [`SocketIo::clear_room_state`].
+//!
+//! #### Example
+//! ```
+//! # use socketioxide::{SocketIo, extract::SocketRef};
+//! # use serde_json::json;
+//! let (_, io) = SocketIo::new_svc();
+//! io.ns("/", |socket: SocketRef| {
+//!     // The socket receives a `state:snapshot` event with the current document of the room
+//!     socket.join("board").unwrap();
+//! });
+//!
+//! // The members of the room receive a `state:patch` event with the patch
+//! io.to("board").patch(json!({ "title": "todo", "done": null })).unwrap();
+//! assert_eq!(io.room_state("board"), Some(json!({ "title": "todo" })));
+//! ```
+//!
+//! [`BroadcastOperators::patch`]: crate::operators::BroadcastOperators#method.patch
+//! [`Socket::join`]: crate::socket::Socket#method.join
+//! [`Socket::try_join`]: crate::socket::Socket#method.try_join
+//! [`join`]: crate::operators::BroadcastOperators#method.join
+//! [`SocketIo::clear_room_state`]: crate::SocketIo#method.clear_room_state
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::adapter::Room;
+
+/// The event sent to a socket joining a room with a document, with a [`StateSnapshot`] payload.
+pub const SNAPSHOT_EVENT: &str = "state:snapshot";
+
+/// The event emitted to the members of a room when its document is patched, with a [`StatePatch`] payload.
+pub const PATCH_EVENT: &str = "state:patch";
+
+/// The payload of the [`SNAPSHOT_EVENT`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StateSnapshot {
+    /// The room of the document
+    pub room: Room,
+    /// The whole document of the room
+    pub state: Value,
+}
+
+/// The payload of the [`PATCH_EVENT`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatePatch {
+    /// The room of the document
+    pub room: Room,
+    /// The merge patch applied to the document
+    pub patch: Value,
+}
+
+/// Applies a [JSON merge patch](https://www.rfc-editor.org/rfc/rfc7396) to the document:
+/// the `null` members are removed, the objects are merged recursively and any other value is replaced.
+pub fn merge_patch(doc: &mut Value, patch: &Value) {
+    let Value::Object(patch) = patch else {
+        *doc = patch.clone();
+        return;
+    };
+    if !doc.is_object() {
+        *doc = Value::Object(Default::default());
+    }
+    let doc = doc.as_object_mut().unwrap();
+    for (key, value) in patch {
+        if value.is_null() {
+            doc.remove(key);
+        } else {
+            merge_patch(doc.entry(key.clone()).or_insert(Value::Null), value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::merge_patch;
+
+    #[test]
+    fn merge_patch_rfc_examples() {
+        let cases = [
+            (json!({"a": "b"}), json!({"a": "c"}), json!({"a": "c"})),
+            (
+                json!({"a": "b"}),
+                json!({"b": "c"}),
+                json!({"a": "b", "b": "c"}),
+            ),
+            (json!({"a": "b"}), json!({"a": null}), json!({})),
+            (
+                json!({"a": {"b": "c"}}),
+                json!({"a": {"b": "d", "c": null}}),
+                json!({"a": {"b": "d"}}),
+            ),
+            (
+                json!({"a": [{"b": "c"}]}),
+                json!({"a": [1]}),
+                json!({"a": [1]}),
+            ),
+            (json!(["a", "b"]), json!(["c", "d"]), json!(["c", "d"])),
+            (json!({"a": "b"}), json!(["c"]), json!(["c"])),
+            (
+                json!({"e": null}),
+                json!({"a": 1}),
+                json!({"e": null, "a": 1}),
+            ),
+            (
+                json!([1, 2]),
+                json!({"a": "b", "c": null}),
+                json!({"a": "b"}),
+            ),
+            (
+                json!({}),
+                json!({"a": {"bb": {"ccc": null}}}),
+                json!({"a": {"bb": {}}}),
+            ),
+        ];
+        for (mut doc, patch, expected) in cases {
+            merge_patch(&mut doc, &patch);
+            assert_eq!(doc, expected, "patch {patch}");
+        }
+    }
+}
@@ -0,0 +1,46 @@
+//! Typed events, binding an event name to its payload type.
+//!
+//! A [`TypedEvent`] knows its own name, so it can be emitted with [`Socket::emit_typed`]
+//! without any risk to mismatch the event name and the payload shape.
+//! On the receiving side, the [`TypedEvent::NAME`] can be used to register the handler and the
+//! payload is extracted with [`Data`](crate::extract::Data).
+//!
+//! #### Example
+//! ```
+//! # use socketioxide::{SocketIo, extract::*, typed::TypedEvent};
+//! # use serde::{Serialize, Deserialize};
+//! #[derive(Debug, Serialize, Deserialize)]
+//! struct Ping {
+//!     count: u32,
+//! }
+//! impl TypedEvent for Ping {
+//!     const NAME: &'static str = "ping";
+//! }
+//!
+//! let (_, io) = SocketIo::new_svc();
+//! io.ns("/", |socket: SocketRef| {
+//!     socket.on(Ping::NAME, |socket: SocketRef, Data::<Ping>(ping)| {
+//!         socket.emit_typed(Ping { count: ping.count + 1 }).ok();
+//!     });
+//! });
+//! ```
+//!
+//! The payload is checked at compile time, a value that is not a [`TypedEvent`] can't be emitted:
+//! ```compile_fail
+//! # use socketioxide::{SocketIo, extract::*};
+//! let (_, io) = SocketIo::new_svc();
+//! io.ns("/", |socket: SocketRef| {
+//!     socket.emit_typed(("ping", 1)).ok();
+//! });
+//! ```
+//!
+//! [`Socket::emit_typed`]: crate::socket::Socket#method.emit_typed
+use serde::Serialize;
+
+/// An event whose name is bound to its payload type, see the [module doc](self) for more details.
+///
+/// The event is serialized as a single argument.
+pub trait TypedEvent: Serialize {
+    /// The name of the event
+    const NAME: &'static str;
+}
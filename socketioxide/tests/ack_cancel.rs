@@ -0,0 +1,74 @@
+//! Tests for the cancellation of the acknowledgements awaited from a socket
+mod utils;
+
+use std::time::Duration;
+
+use engineioxide::Packet::*;
+use serde_json::Value;
+use socketioxide::{AckError, SocketIo};
+
+#[tokio::test]
+pub async fn cancel_in_flight_ack() {
+    let (_svc, io) = SocketIo::builder().max_pending_acks(1).build_svc();
+    io.ns("/", || {});
+    let (stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await); // NS connect packet
+    let socket = io.sockets().unwrap().pop().unwrap();
+
+    let ack = socket.emit_with_ack::<_, Value>("test", 1).unwrap();
+    assert_eq!(
+        assert_some!(srx.recv().await),
+        Message("21[\"test\",1]".into())
+    );
+    let handle = assert_some!(ack.handle());
+    assert_eq!(handle.id(), 1);
+    assert!(handle.is_pending());
+
+    assert!(handle.cancel());
+    assert!(!handle.is_pending());
+    assert!(matches!(ack.await, Err(AckError::Cancelled)));
+    assert!(!handle.cancel());
+
+    // The pending entry is freed: another ack can be awaited, and the late ack is ignored
+    assert_ok!(stx.try_send(Message("31[\"late\"]".into())));
+    let ack = socket.emit_with_ack::<_, Value>("test", 2).unwrap();
+    assert_eq!(
+        assert_some!(srx.recv().await),
+        Message("22[\"test\",2]".into())
+    );
+    assert_ok!(stx.try_send(Message("32[\"ok\"]".into())));
+    assert_ok!(ack.await);
+
+    // The broadcasts have no handle
+    assert!(io
+        .emit_with_ack::<Value>("test", ())
+        .unwrap()
+        .handle()
+        .is_none());
+}
+
+#[tokio::test]
+pub async fn cancel_ack_with_retries() {
+    let (_svc, io) = SocketIo::new_svc();
+    io.ns("/", || {});
+    let (_stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await); // NS connect packet
+    let socket = io.sockets().unwrap().pop().unwrap();
+
+    let ack = socket
+        .timeout(Duration::from_millis(20))
+        .retries(5)
+        .emit_with_ack::<_, Value>("test", 1)
+        .unwrap();
+    let handle = assert_some!(ack.handle());
+    assert_some!(srx.recv().await);
+    // Cancel while the first retry is pending
+    assert_some!(srx.recv().await);
+    tokio::time::sleep(Duration::from_millis(5)).await;
+    assert_eq!(handle.id(), 2);
+
+    assert!(handle.cancel());
+    assert!(matches!(ack.await, Err(AckError::Cancelled)));
+    let res = tokio::time::timeout(Duration::from_millis(40), srx.recv()).await;
+    assert!(res.is_err());
+}
@@ -0,0 +1,69 @@
+//! Tests for the retries of the messages emitted with an acknowledgement
+mod utils;
+
+use std::time::Duration;
+
+use engineioxide::Packet::*;
+use serde_json::Value;
+use socketioxide::{AckError, SocketIo};
+
+#[tokio::test]
+pub async fn ack_retried_until_acknowledged() {
+    let (_svc, io) = SocketIo::builder().packet_ids(true).build_svc();
+    io.ns("/", || {});
+    let (stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await); // NS connect packet
+    let socket = io.sockets().unwrap().pop().unwrap();
+
+    let ack = socket
+        .timeout(Duration::from_millis(20))
+        .retries(2)
+        .retry_delay(Duration::from_millis(5))
+        .emit_with_ack::<_, Value>("test", 1)
+        .unwrap();
+
+    // The first two attempts time out, each retry has a new ack id and the same packet id
+    assert_eq!(
+        assert_some!(srx.recv().await),
+        Message("21[\"test\",1,{\"_pid\":1}]".into())
+    );
+    assert_eq!(
+        assert_some!(srx.recv().await),
+        Message("22[\"test\",1,{\"_pid\":1}]".into())
+    );
+    assert_eq!(
+        assert_some!(srx.recv().await),
+        Message("23[\"test\",1,{\"_pid\":1}]".into())
+    );
+    assert_ok!(stx.try_send(Message("33[\"ok\"]".into())));
+
+    let res = assert_ok!(ack.await);
+    assert_eq!(res.data, serde_json::json!(["ok"]));
+}
+
+#[tokio::test]
+pub async fn ack_timeout_after_all_retries() {
+    let (_svc, io) = SocketIo::new_svc();
+    io.ns("/", || {});
+    let (_stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await); // NS connect packet
+    let socket = io.sockets().unwrap().pop().unwrap();
+
+    let ack = socket
+        .timeout(Duration::from_millis(10))
+        .retries(1)
+        .emit_with_ack::<_, Value>("test", 1)
+        .unwrap();
+    assert!(matches!(ack.await, Err(AckError::Timeout)));
+
+    assert_eq!(
+        assert_some!(srx.recv().await),
+        Message("21[\"test\",1]".into())
+    );
+    assert_eq!(
+        assert_some!(srx.recv().await),
+        Message("22[\"test\",1]".into())
+    );
+    let res = tokio::time::timeout(Duration::from_millis(20), srx.recv()).await;
+    assert!(res.is_err());
+}
@@ -120,3 +120,92 @@ pub async fn broadcast_with_ack() {
         }
     }
 }
+
+#[tokio::test]
+pub async fn ack_timeout_from_auth() {
+    let (_svc, io) = SocketIo::builder()
+        .ack_timeout(Duration::from_millis(20))
+        .ack_timeout_from_auth(|auth| {
+            let ms = auth.get("ackTimeout")?.as_u64()?;
+            Some(Duration::from_millis(ms))
+        })
+        .build_svc();
+    let (tx, mut rx) = mpsc::channel::<(Duration, bool)>(4);
+
+    io.ns("/", move |s: SocketRef| {
+        let tx = tx.clone();
+        async move {
+            let res = assert_ok!(s.emit_with_ack::<_, [String; 1]>("test", "foo")).await;
+            let timed_out = matches!(res, Err(socketioxide::AckError::Timeout));
+            assert_ok!(tx.try_send((s.ack_timeout(), timed_out)));
+        }
+    });
+
+    // The client supplied timeout is used
+    let auth = serde_json::json!({ "ackTimeout": 200 });
+    let (stx, mut srx) = io.new_dummy_sock("/", auth).await;
+    assert_some!(srx.recv().await); // NS connect packet
+    assert_some!(srx.recv().await);
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_ok!(stx.send(Message("31[\"oof\"]".into())).await);
+    let (timeout, timed_out) = assert_some!(rx.recv().await);
+    assert_eq!(timeout, Duration::from_millis(200));
+    assert!(!timed_out);
+
+    // Otherwise the default timeout is used
+    let (stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await); // NS connect packet
+    assert_some!(srx.recv().await);
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_ok!(stx.send(Message("31[\"oof\"]".into())).await);
+    let (timeout, timed_out) = assert_some!(rx.recv().await);
+    assert_eq!(timeout, Duration::from_millis(20));
+    assert!(timed_out);
+}
+
+#[tokio::test]
+pub async fn ack_ids_after_reconnect() {
+    let (_svc, io) = SocketIo::new_svc();
+    let (tx, mut rx) = mpsc::channel::<(usize, Result<String, String>)>(4);
+    let connections = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+
+    io.ns("/", move |s: SocketRef| {
+        let n = connections.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        let tx = tx.clone();
+        async move {
+            let res = s
+                .timeout(Duration::from_millis(200))
+                .emit_with_ack::<_, [String; 1]>("test", n);
+            let res = assert_ok!(res).await;
+            let res = res
+                .map(|ack| ack.data[0].clone())
+                .map_err(|e| e.to_string());
+            assert_ok!(tx.try_send((n, res)));
+        }
+    });
+
+    let (stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await); // NS connect packet
+    assert_eq!(
+        assert_some!(srx.recv().await),
+        Message("21[\"test\",0]".into())
+    );
+
+    // Reconnect to the namespace before answering the first ack
+    assert_ok!(stx.send(Message("1".into())).await);
+    let (n, res) = assert_some!(rx.recv().await);
+    assert_eq!(n, 0);
+    assert!(res.is_err());
+
+    assert_ok!(stx.send(Message("0".into())).await);
+    assert_some!(srx.recv().await); // NS connect packet
+                                    // The new socket reuses the same low ack id
+    assert_eq!(
+        assert_some!(srx.recv().await),
+        Message("21[\"test\",1]".into())
+    );
+    assert_ok!(stx.send(Message("31[\"second\"]".into())).await);
+
+    // The ack only resolves the pending ack of the new socket
+    assert_eq!(assert_some!(rx.recv().await), (1, Ok("second".to_string())));
+}
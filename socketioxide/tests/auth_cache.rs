@@ -0,0 +1,59 @@
+//! Tests for the auth results cached on the engine.io session
+mod utils;
+
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use engineioxide::Packet::*;
+use socketioxide::{
+    extract::{Data, SocketRef},
+    handler::ConnectHandler,
+    SocketIo,
+};
+
+#[tokio::test]
+pub async fn auth_validated_once_per_session() {
+    let (_svc, io) = SocketIo::new_svc();
+    let validations = Arc::new(AtomicUsize::new(0));
+    let auth = {
+        let validations = validations.clone();
+        move |socket: SocketRef, Data(token): Data<String>| {
+            let user: String = socket.cached_auth(&token, || {
+                validations.fetch_add(1, Ordering::SeqCst);
+                match token.as_str() {
+                    "secret" => Ok("admin".to_string()),
+                    _ => Err("invalid token"),
+                }
+            })?;
+            assert_eq!(user, "admin");
+            Ok::<_, &'static str>(())
+        }
+    };
+    io.ns("/", (|| {}).with(auth.clone()));
+    io.ns("/admin", (|| {}).with(auth));
+
+    let (stx, mut srx) = io.new_dummy_sock("/", "secret").await;
+    let msg = assert_some!(srx.recv().await);
+    assert!(matches!(msg, Message(msg) if msg.starts_with("0")));
+
+    // Second namespace connect on the same session
+    assert_ok!(stx.try_send(Message("0/admin,\"secret\"".into())));
+    let msg = assert_some!(srx.recv().await);
+    assert!(matches!(msg, Message(msg) if msg.starts_with("0/admin,")));
+    assert_eq!(validations.load(Ordering::SeqCst), 1);
+
+    // Another session validates it again
+    let (_stx, mut srx) = io.new_dummy_sock("/", "secret").await;
+    assert_some!(srx.recv().await);
+    assert_eq!(validations.load(Ordering::SeqCst), 2);
+
+    // Failed validations are not cached
+    let (_stx, mut srx) = io.new_dummy_sock("/", "foo").await;
+    let msg = assert_some!(srx.recv().await);
+    assert_eq!(msg, Message("4{\"message\":\"invalid token\"}".into()));
+    let (_stx, mut srx) = io.new_dummy_sock("/", "foo").await;
+    assert_some!(srx.recv().await);
+    assert_eq!(validations.load(Ordering::SeqCst), 4);
+}
@@ -0,0 +1,58 @@
+//! Tests for the backpressure applied when the packet buffer of a socket is full
+mod utils;
+
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use engineioxide::Packet::*;
+use socketioxide::{extract::SocketRef, SendError, SocketError, SocketIo};
+
+const BUFFER_SIZE: usize = 8;
+const MSG_COUNT: usize = 1000;
+
+#[tokio::test]
+pub async fn emit_wait_bounded_buffer() {
+    let (_svc, io) = SocketIo::builder().max_buffer_size(BUFFER_SIZE).build_svc();
+    let sent = Arc::new(AtomicUsize::new(0));
+
+    io.ns("/", {
+        let sent = sent.clone();
+        move |socket: SocketRef| async move {
+            for i in 0..MSG_COUNT {
+                assert_ok!(socket.emit_wait("msg", i).await);
+                sent.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+    });
+
+    let (_stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    // The client is not reading: the handler is waiting and the buffered packets are bounded
+    // (the dummy socket pipes the packet buffer to another channel of the same size)
+    let buffered = sent.load(Ordering::SeqCst);
+    assert!(
+        buffered <= 2 * BUFFER_SIZE + 1,
+        "{buffered} packets buffered"
+    );
+    let socket = io.sockets().unwrap().pop().unwrap();
+    assert!(matches!(
+        socket.emit("msg", ()),
+        Err(SendError::Socket(SocketError::InternalChannelFull(_)))
+    ));
+
+    assert_some!(srx.recv().await); // NS connect packet
+    for i in 0..MSG_COUNT {
+        let msg = tokio::time::timeout(Duration::from_millis(200), srx.recv()).await;
+        assert_eq!(
+            assert_some!(assert_ok!(msg)),
+            Message(format!("2[\"msg\",{i}]").into())
+        );
+    }
+    assert_eq!(sent.load(Ordering::SeqCst), MSG_COUNT);
+}
@@ -0,0 +1,77 @@
+//! Tests for the client-initiated binary streams
+mod utils;
+
+use std::time::Duration;
+
+use bytes::Bytes;
+use engineioxide::Packet::*;
+use futures_util::StreamExt;
+use socketioxide::{extract::SocketRef, SocketIo};
+use tokio::{io::AsyncReadExt, sync::mpsc};
+
+fn send_chunks(stx: &mpsc::Sender<engineioxide::Packet>, chunks: &[&'static [u8]]) {
+    let placeholders = (0..chunks.len())
+        .map(|i| format!("{{\"_placeholder\":true,\"num\":{i}}}"))
+        .collect::<Vec<_>>()
+        .join(",");
+    let msg = format!("5{}-[\"upload\",{placeholders}]", chunks.len());
+    assert_ok!(stx.try_send(Message(msg.into())));
+    for chunk in chunks {
+        assert_ok!(stx.try_send(Binary(Bytes::from_static(chunk))));
+    }
+}
+
+#[tokio::test]
+pub async fn multi_chunk_upload() {
+    let (_svc, io) = SocketIo::new_svc();
+    let (tx, mut rx) = mpsc::channel::<Vec<Bytes>>(1);
+    io.ns("/", move |socket: SocketRef| {
+        let mut upload = socket.binary_stream("upload");
+        let tx = tx.clone();
+        tokio::spawn(async move {
+            let mut chunks = Vec::new();
+            while let Some(chunk) = upload.next().await {
+                chunks.push(chunk);
+            }
+            tx.try_send(chunks).unwrap();
+        });
+    });
+
+    let (stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await); // NS connect packet
+
+    send_chunks(&stx, &[b"hello", b" "]);
+    send_chunks(&stx, &[b"world"]);
+    // End marker, then a chunk sent after the end is ignored
+    assert_ok!(stx.try_send(Message("2[\"upload\"]".into())));
+    send_chunks(&stx, &[b"!"]);
+
+    let chunks = tokio::time::timeout(Duration::from_millis(50), rx.recv()).await;
+    let chunks = assert_some!(assert_ok!(chunks));
+    assert_eq!(chunks, ["hello", " ", "world"]);
+}
+
+#[tokio::test]
+pub async fn upload_as_async_read() {
+    let (_svc, io) = SocketIo::new_svc();
+    let (tx, mut rx) = mpsc::channel::<String>(1);
+    io.ns("/", move |socket: SocketRef| {
+        let mut upload = socket.binary_stream("upload");
+        let tx = tx.clone();
+        tokio::spawn(async move {
+            let mut file = String::new();
+            upload.read_to_string(&mut file).await.unwrap();
+            tx.try_send(file).unwrap();
+        });
+    });
+
+    let (stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await); // NS connect packet
+
+    send_chunks(&stx, &[b"chunked ", b"file "]);
+    send_chunks(&stx, &[b"upload"]);
+    assert_ok!(stx.try_send(Message("2[\"upload\"]".into())));
+
+    let file = tokio::time::timeout(Duration::from_millis(50), rx.recv()).await;
+    assert_eq!(assert_some!(assert_ok!(file)), "chunked file upload");
+}
@@ -0,0 +1,41 @@
+//! Tests for message handlers running on the blocking thread pool
+mod utils;
+
+use std::time::Duration;
+
+use engineioxide::Packet::*;
+use socketioxide::{extract::SocketRef, handler::MessageHandler, SocketIo};
+
+use utils::try_timeout_rcv;
+
+fn cpu(socket: SocketRef) {
+    std::thread::sleep(Duration::from_millis(200));
+    socket.emit("cpu", "done").unwrap();
+}
+
+#[tokio::test]
+pub async fn blocking_handler_does_not_starve_sockets() {
+    let (_svc, io) = SocketIo::new_svc();
+    io.ns("/", |socket: SocketRef| {
+        socket.on("cpu", cpu.blocking());
+        socket.on("ping", |socket: SocketRef| socket.emit("pong", ()).unwrap());
+    });
+
+    let (stx1, mut srx1) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx1.recv().await); // NS connect packet
+    let (stx2, mut srx2) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx2.recv().await); // NS connect packet
+
+    assert_ok!(stx1.try_send(Message("2[\"cpu\"]".into())));
+    // The runtime keeps processing the packets of the sockets while the handler is running
+    assert_ok!(stx2.try_send(Message("2[\"ping\"]".into())));
+    let msg = assert_some!(try_timeout_rcv(&mut srx2, 100).await);
+    assert_eq!(msg, Message("2[\"pong\",null]".into()));
+    assert_ok!(stx1.try_send(Message("2[\"ping\"]".into())));
+    let msg = assert_some!(try_timeout_rcv(&mut srx1, 100).await);
+    assert_eq!(msg, Message("2[\"pong\",null]".into()));
+
+    // The socket is still usable from the blocking handler
+    let msg = assert_some!(try_timeout_rcv(&mut srx1, 500).await);
+    assert_eq!(msg, Message("2[\"cpu\",\"done\"]".into()));
+}
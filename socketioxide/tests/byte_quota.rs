@@ -0,0 +1,74 @@
+//! Tests for the inbound byte quota of the sockets
+mod utils;
+
+use std::time::Duration;
+
+use engineioxide::Packet::*;
+use socketioxide::{extract::SocketRef, socket::DisconnectReason, SocketIo};
+use tokio::sync::mpsc;
+
+use utils::try_timeout_rcv;
+
+/// A 40 bytes event packet
+fn event() -> engineioxide::Packet {
+    let packet = format!("2[\"msg\",\"{}\"]", "a".repeat(29));
+    assert_eq!(packet.len(), 40);
+    Message(packet.into())
+}
+
+fn setup(window: Duration) -> (SocketIo, mpsc::Receiver<DisconnectReason>) {
+    let (_svc, io) = SocketIo::builder().byte_quota(100, window).build_svc();
+    let (tx, rx) = mpsc::channel::<DisconnectReason>(1);
+    io.ns("/", move |socket: SocketRef| {
+        let tx = tx.clone();
+        socket.on("msg", |socket: SocketRef| socket.emit("ok", ()).unwrap());
+        socket.on_disconnect(move |reason: DisconnectReason| tx.try_send(reason).unwrap());
+    });
+    (io, rx)
+}
+
+#[tokio::test]
+pub async fn socket_over_its_byte_quota_is_disconnected() {
+    let (io, mut rx) = setup(Duration::from_secs(10));
+    let (stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await); // NS connect packet
+
+    for _ in 0..2 {
+        assert_ok!(stx.try_send(event()));
+        assert_eq!(
+            assert_some!(try_timeout_rcv(&mut srx, 50).await),
+            Message("2[\"ok\",null]".into())
+        );
+    }
+    // The third packet goes over the 100 bytes budget and is not handled
+    assert_ok!(stx.try_send(event()));
+    assert_eq!(
+        assert_some!(try_timeout_rcv(&mut srx, 50).await),
+        Message("1".into())
+    );
+    assert_eq!(
+        assert_some!(try_timeout_rcv(&mut rx, 50).await),
+        DisconnectReason::QuotaExceeded
+    );
+    assert!(io.sockets().unwrap().is_empty());
+}
+
+#[tokio::test]
+pub async fn byte_quota_is_reset_each_window() {
+    let (io, mut rx) = setup(Duration::from_millis(100));
+    let (stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await); // NS connect packet
+
+    for _ in 0..3 {
+        for _ in 0..2 {
+            assert_ok!(stx.try_send(event()));
+            assert_eq!(
+                assert_some!(try_timeout_rcv(&mut srx, 50).await),
+                Message("2[\"ok\",null]".into())
+            );
+        }
+        tokio::time::sleep(Duration::from_millis(120)).await;
+    }
+    assert!(try_timeout_rcv(&mut rx, 50).await.is_none());
+    assert_eq!(io.sockets().unwrap().len(), 1);
+}
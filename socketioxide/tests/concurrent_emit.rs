@@ -65,3 +65,49 @@ pub async fn emit() {
         }
     }
 }
+
+#[tokio::test]
+pub async fn emit_batch() {
+    const BATCHES: usize = 1000;
+    let (_svc, io) = SocketIo::builder().max_buffer_size(10000).build_svc();
+    io.ns("/", move |socket: SocketRef| async move {
+        for _ in 0..10 {
+            let s = socket.clone();
+            tokio::task::spawn_blocking(move || {
+                for _ in 0..100 {
+                    s.emit("noise", ()).unwrap();
+                }
+            });
+        }
+        for _ in 0..10 {
+            let s = socket.clone();
+            tokio::task::spawn_blocking(move || {
+                for _ in 0..BATCHES / 10 {
+                    s.emit_batch(&[("a", 1), ("b", 2), ("c", 3)]).unwrap();
+                }
+            });
+        }
+    });
+
+    let (_stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await);
+
+    const BATCH: [&str; 3] = [r#"2["a",1]"#, r#"2["b",2]"#, r#"2["c",3]"#];
+    let mut count = 0;
+    let mut total = 0;
+    while let Some(msg) = srx.recv().await {
+        match msg {
+            Message(msg) if msg == BATCH[count] => {
+                count = (count + 1) % 3;
+                if count == 0 {
+                    total += 1;
+                }
+            }
+            Message(msg) if count == 0 && msg == r#"2["noise",null]"# => (),
+            msg => panic!("unexpected message: {:?}, count: {}", msg, count),
+        };
+        if total == BATCHES {
+            break;
+        }
+    }
+}
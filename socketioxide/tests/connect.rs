@@ -6,6 +6,8 @@ use serde_json::Value;
 use socketioxide::{extract::SocketRef, handler::ConnectHandler, packet::Packet, SendError, SocketError, SocketIo};
 use tokio::sync::mpsc;
 
+use utils::timeout_rcv;
+
 fn create_msg(
     ns: &'static str,
     event: &str,
@@ -14,12 +16,6 @@ fn create_msg(
     let packet: String = Packet::event(ns, event, data.into()).into();
     Message(packet.into())
 }
-async fn timeout_rcv<T: std::fmt::Debug>(srx: &mut tokio::sync::mpsc::Receiver<T>) -> T {
-    tokio::time::timeout(std::time::Duration::from_millis(10), srx.recv())
-        .await
-        .unwrap()
-        .unwrap()
-}
 
 #[tokio::test]
 pub async fn connect_middleware() {
@@ -33,7 +29,7 @@ pub async fn connect_middleware() {
 
             assert!(matches!(
                 s.emit("test", ()),
-                Err(SendError::Socket(SocketError::Closed(())))
+                Err(SendError::Socket(SocketError::Closed(Value::Null)))
             ));
 
             assert!(matches!(
@@ -233,3 +229,21 @@ async fn remove_ns_from_disconnect_handler() {
     let elapsed = tokio::time::timeout(std::time::Duration::from_millis(200), rx.recv()).await;
     assert!(elapsed.is_err() || elapsed.unwrap().is_none());
 }
+
+#[tokio::test]
+async fn list_namespaces() {
+    let (_svc, io) = SocketIo::new_svc();
+    io.ns("/", || {});
+    io.ns("/chat", || {});
+    io.ns("/admin", || {});
+    io.dyn_ns("/room/{id}", || {}).unwrap();
+    assert_eq!(io.namespaces(), ["/", "/admin", "/chat"]);
+
+    // A dynamic namespace is listed once a client connected to it
+    let (_stx, mut srx) = io.new_dummy_sock("/room/42", ()).await;
+    timeout_rcv(&mut srx).await;
+    assert_eq!(io.namespaces(), ["/", "/admin", "/chat", "/room/42"]);
+
+    io.delete_ns("/chat");
+    assert_eq!(io.namespaces(), ["/", "/admin", "/room/42"]);
+}
@@ -0,0 +1,54 @@
+//! Tests for the hook called once a socket is fully connected
+mod utils;
+
+use std::time::Duration;
+
+use engineioxide::Packet::*;
+use socketioxide::{extract::SocketRef, SocketIo};
+use tokio::sync::mpsc;
+
+use utils::timeout_rcv;
+
+#[tokio::test]
+pub async fn connected_hook_after_connect_packet() {
+    let (_svc, io) = SocketIo::new_svc();
+    io.ns("/", |socket: SocketRef| {
+        socket.emit("handler", ()).unwrap();
+    });
+    io.on_connected(|socket: SocketRef| async move {
+        socket.emit("connected", ()).unwrap();
+    });
+
+    let (_stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    let connect = timeout_rcv(&mut srx).await;
+    assert!(matches!(connect, Message(msg) if msg.starts_with("0{\"sid\"")));
+    assert_eq!(
+        timeout_rcv(&mut srx).await,
+        Message("2[\"handler\",null]".into())
+    );
+    assert_eq!(
+        timeout_rcv(&mut srx).await,
+        Message("2[\"connected\",null]".into())
+    );
+}
+
+#[tokio::test]
+pub async fn connected_hook_not_called_on_connect_error() {
+    let (_svc, io) = SocketIo::new_svc();
+    let (tx, mut rx) = mpsc::channel::<()>(1);
+    io.ns("/", || {});
+    io.on_connected(move |_: SocketRef| {
+        let tx = tx.clone();
+        async move { tx.try_send(()).unwrap() }
+    });
+
+    let (_stx, mut srx) = io.new_dummy_sock("/admin", ()).await;
+    assert_some!(srx.recv().await); // NS connect_error packet
+    assert!(tokio::time::timeout(Duration::from_millis(20), rx.recv())
+        .await
+        .is_err());
+
+    let (_stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await); // NS connect packet
+    timeout_rcv(&mut rx).await;
+}
@@ -0,0 +1,73 @@
+//! Tests for the session affinity cookie set on the handshake response
+mod fixture;
+
+use engineioxide::config::{CookieConfig, SameSite};
+use fixture::spawn_server;
+use http::{header::SET_COOKIE, HeaderValue, Request};
+use http_body_util::{BodyExt, Empty};
+use hyper_util::{client::legacy::Client, rt::TokioExecutor};
+use socketioxide::SocketIo;
+
+/// Sends a polling handshake request and returns its `Set-Cookie` header and the session id.
+async fn handshake(port: u16) -> (Option<HeaderValue>, String) {
+    let req = Request::get(format!(
+        "http://127.0.0.1:{port}/socket.io/?EIO=4&transport=polling"
+    ))
+    .body(Empty::<bytes::Bytes>::new())
+    .unwrap();
+    let res = Client::builder(TokioExecutor::new())
+        .build_http()
+        .request(req)
+        .await
+        .unwrap();
+    let cookie = res.headers().get(SET_COOKIE).cloned();
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let open: serde_json::Value = serde_json::from_slice(&body[1..]).unwrap();
+    (cookie, open["sid"].as_str().unwrap().to_string())
+}
+
+#[tokio::test]
+pub async fn handshake_sets_cookie() {
+    const PORT: u16 = 12375;
+    let cookie = CookieConfig {
+        name: "affinity".into(),
+        same_site: SameSite::Strict,
+        ..Default::default()
+    };
+    let (svc, io) = SocketIo::builder().with_cookie(cookie).build_svc();
+    io.ns("/", || {});
+    spawn_server(PORT, svc).await;
+
+    let (cookie, sid) = handshake(PORT).await;
+    let expected = format!("affinity={sid}; Path=/; SameSite=Strict; HttpOnly");
+    assert_eq!(cookie.unwrap(), expected.as_str());
+}
+
+#[tokio::test]
+pub async fn cross_site_cookie_is_secure() {
+    const PORT: u16 = 12376;
+    let cookie = CookieConfig {
+        path: "/socket.io".into(),
+        same_site: SameSite::None,
+        http_only: false,
+        ..Default::default()
+    };
+    let (svc, io) = SocketIo::builder().with_cookie(cookie).build_svc();
+    io.ns("/", || {});
+    spawn_server(PORT, svc).await;
+
+    let (cookie, sid) = handshake(PORT).await;
+    let expected = format!("io={sid}; Path=/socket.io; SameSite=None; Secure");
+    assert_eq!(cookie.unwrap(), expected.as_str());
+}
+
+#[tokio::test]
+pub async fn no_cookie_by_default() {
+    const PORT: u16 = 12377;
+    let (svc, io) = SocketIo::new_svc();
+    io.ns("/", || {});
+    spawn_server(PORT, svc).await;
+
+    let (cookie, _) = handshake(PORT).await;
+    assert!(cookie.is_none());
+}
@@ -0,0 +1,131 @@
+//! Tests for the default rooms joined on connect
+mod utils;
+
+use engineioxide::Packet::*;
+use socketioxide::{extract::SocketRef, SocketIo};
+use tokio::sync::mpsc;
+
+#[tokio::test]
+pub async fn socket_in_default_rooms_on_connect() {
+    let (_svc, io) = SocketIo::builder()
+        .default_rooms(["all", "lobby"])
+        .build_svc();
+    let (tx, mut rx) = mpsc::channel::<Vec<String>>(1);
+    io.ns("/", move |socket: SocketRef| {
+        let mut rooms: Vec<String> = socket
+            .rooms()
+            .unwrap()
+            .into_iter()
+            .map(|r| r.to_string())
+            .collect();
+        rooms.sort();
+        tx.try_send(rooms).unwrap();
+    });
+
+    let (_stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await); // NS connect packet
+    assert_eq!(
+        assert_some!(rx.recv().await),
+        vec!["all".to_string(), "lobby".to_string()]
+    );
+
+    assert_ok!(io.to("all").emit("test", "foo"));
+    let msg = assert_some!(srx.recv().await);
+    assert_eq!(msg, Message("2[\"test\",\"foo\"]".into()));
+}
+
+#[tokio::test]
+pub async fn default_rooms_checked_by_join_hook() {
+    let (_svc, io) = SocketIo::builder()
+        .default_rooms(["all", "admin:logs"])
+        .build_svc();
+    let (tx, mut rx) = mpsc::channel::<usize>(1);
+    io.on_join(|_, room| !room.starts_with("admin:"));
+    io.ns("/", move |socket: SocketRef| {
+        tx.try_send(socket.rooms().unwrap().len()).unwrap();
+    });
+
+    let (_stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await); // NS connect packet
+                                    // The join is vetoed but the socket is still connected
+    assert_eq!(assert_some!(rx.recv().await), 0);
+}
+
+#[tokio::test]
+pub async fn sid_room_absent_when_disabled() {
+    let (_svc, io) = SocketIo::builder().auto_sid_room(false).build_svc();
+    io.ns("/", || {});
+
+    let (_stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await); // NS connect packet
+    let socket = io.sockets().unwrap().pop().unwrap();
+    assert!(socket.rooms().unwrap().is_empty());
+    assert!(io.rooms().unwrap().is_empty());
+
+    // The socket is not reached through its sid room, but it is still reached directly
+    assert_ok!(io.to(socket.id).emit("test", "room"));
+    assert_ok!(io.to_sockets([socket.id]).emit("test", "direct"));
+    let msg = assert_some!(srx.recv().await);
+    assert_eq!(msg, Message("2[\"test\",\"direct\"]".into()));
+}
+
+#[tokio::test]
+pub async fn sid_room_joined_when_enabled() {
+    let (_svc, io) = SocketIo::builder()
+        .auto_sid_room(true)
+        .default_rooms("all")
+        .build_svc();
+    io.ns("/", || {});
+
+    let (_stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await); // NS connect packet
+    let socket = io.sockets().unwrap().pop().unwrap();
+    let rooms = socket.rooms().unwrap();
+    assert_eq!(rooms.len(), 2);
+    assert!(rooms.contains(&"all".into()));
+    assert!(rooms.contains(&socket.id.to_string().into()));
+
+    assert_ok!(io.to(socket.id).emit("test", "room"));
+    let msg = assert_some!(srx.recv().await);
+    assert_eq!(msg, Message("2[\"test\",\"room\"]".into()));
+}
+
+#[tokio::test]
+pub async fn to_sid_reaches_only_that_socket() {
+    let (_svc, io) = SocketIo::builder().auto_sid_room(true).build_svc();
+    io.ns("/", || {});
+
+    let (_stx1, mut srx1) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx1.recv().await); // NS connect packet
+    let socket = io.sockets().unwrap().pop().unwrap();
+    let (_stx2, mut srx2) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx2.recv().await);
+
+    assert_eq!(io.to_sid(socket.id).sockets().unwrap().len(), 1);
+    assert_ok!(io.to_sid(socket.id).emit("test", "sid"));
+    let msg = assert_some!(srx1.recv().await);
+    assert_eq!(msg, Message("2[\"test\",\"sid\"]".into()));
+    let timeout = tokio::time::timeout(std::time::Duration::from_millis(20), srx2.recv());
+    assert!(timeout.await.is_err());
+}
+
+#[tokio::test]
+pub async fn to_sid_reaches_socket_with_default_config() {
+    let (_svc, io) = SocketIo::new_svc();
+    io.ns("/", || {});
+
+    let (_stx1, mut srx1) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx1.recv().await); // NS connect packet
+    let socket = io.sockets().unwrap().pop().unwrap();
+    let (_stx2, mut srx2) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx2.recv().await);
+
+    // Without the sid room the socket is targeted directly
+    assert!(socket.rooms().unwrap().is_empty());
+    assert_eq!(io.to_sid(socket.id).sockets().unwrap().len(), 1);
+    assert_ok!(io.to_sid(socket.id).emit("test", "sid"));
+    let msg = assert_some!(srx1.recv().await);
+    assert_eq!(msg, Message("2[\"test\",\"sid\"]".into()));
+    let timeout = tokio::time::timeout(std::time::Duration::from_millis(20), srx2.recv());
+    assert!(timeout.await.is_err());
+}
@@ -0,0 +1,71 @@
+//! Tests for the error acks sent when an extractor fails
+mod utils;
+
+use engineioxide::Packet::*;
+use socketioxide::{
+    extract::{AckSender, Data, SocketRef},
+    SocketIo,
+};
+use tokio::sync::mpsc;
+
+use utils::try_timeout_rcv;
+
+async fn create_socket(
+    expose: bool,
+) -> (
+    mpsc::Sender<engineioxide::Packet>,
+    mpsc::Receiver<engineioxide::Packet>,
+) {
+    let (_svc, io) = SocketIo::builder().expose_deser_errors(expose).build_svc();
+    io.ns("/", |socket: SocketRef| {
+        socket.on("add", |Data::<(i32, i32)>((a, b)), ack: AckSender| {
+            ack.send(a + b).unwrap()
+        });
+        socket.on(
+            "async_add",
+            |Data::<(i32, i32)>((a, b)), ack: AckSender| async move { ack.send(a + b).unwrap() },
+        );
+    });
+    let (stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await); // NS connect packet
+    (stx, srx)
+}
+
+#[tokio::test]
+pub async fn generic_error_message() {
+    let (stx, mut srx) = create_socket(false).await;
+
+    for event in ["add", "async_add"] {
+        assert_ok!(stx.try_send(Message(format!("21[\"{event}\",\"a\",2]").into())));
+        let msg = assert_some!(try_timeout_rcv(&mut srx, 20).await);
+        assert_eq!(
+            msg,
+            Message("31[{\"code\":\"bad_request\",\"message\":\"bad request\"}]".into())
+        );
+    }
+
+    // Events without ack are ignored
+    assert_ok!(stx.try_send(Message("2[\"add\",\"a\",2]".into())));
+    assert!(try_timeout_rcv(&mut srx, 20).await.is_none());
+
+    assert_ok!(stx.try_send(Message("22[\"add\",1,2]".into())));
+    let msg = assert_some!(try_timeout_rcv(&mut srx, 20).await);
+    assert_eq!(msg, Message("32[3]".into()));
+}
+
+#[tokio::test]
+pub async fn detailed_error_message() {
+    let (stx, mut srx) = create_socket(true).await;
+
+    for event in ["add", "async_add"] {
+        assert_ok!(stx.try_send(Message(format!("21[\"{event}\",\"a\",2]").into())));
+        let msg = assert_some!(try_timeout_rcv(&mut srx, 20).await);
+        assert_eq!(
+            msg,
+            Message(
+                "31[{\"code\":\"bad_request\",\"message\":\"invalid type: string \\\"a\\\", expected i32\"}]"
+                    .into()
+            )
+        );
+    }
+}
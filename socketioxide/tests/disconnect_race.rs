@@ -0,0 +1,80 @@
+//! Tests for the connection to a namespace while the previous socket of the session is disconnecting
+mod utils;
+
+use std::time::Duration;
+
+use engineioxide::Packet::*;
+use socketioxide::{extract::SocketRef, SocketIo};
+use tokio::sync::mpsc;
+
+use utils::try_timeout_rcv;
+
+async fn work() {
+    tokio::time::sleep(Duration::from_millis(50)).await;
+}
+
+#[tokio::test]
+pub async fn connect_waits_for_previous_disconnect() {
+    let (_svc, io) = SocketIo::new_svc();
+    let (disconnect_tx, mut disconnect_rx) = mpsc::channel(4);
+    io.ns("/", move |socket: SocketRef| {
+        let disconnect_tx = disconnect_tx.clone();
+        socket.on_disconnect(move |socket: SocketRef| {
+            disconnect_tx.try_send(socket.id).unwrap();
+        });
+        socket.on("work", work);
+        socket.on("leave", |socket: SocketRef| {
+            tokio::spawn(socket.disconnect_graceful(Duration::from_secs(1)));
+        });
+    });
+
+    let (stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await); // NS connect packet
+    let old = io.sockets().unwrap().remove(0).id;
+
+    assert_ok!(stx.try_send(Message("2[\"work\"]".into())));
+    assert_ok!(stx.try_send(Message("2[\"leave\"]".into())));
+    // The client reconnects while the previous socket is still draining
+    assert_ok!(stx.try_send(Message("0".into())));
+
+    // The disconnect packet of the previous socket is sent before the new connect packet
+    assert_eq!(
+        assert_some!(try_timeout_rcv(&mut srx, 200).await),
+        Message("1".into())
+    );
+    let connect = assert_some!(try_timeout_rcv(&mut srx, 200).await);
+    assert!(matches!(connect, Message(msg) if msg.starts_with('0')));
+    assert_eq!(
+        assert_some!(try_timeout_rcv(&mut disconnect_rx, 200).await),
+        old
+    );
+
+    let sockets = io.sockets().unwrap();
+    assert_eq!(sockets.len(), 1);
+    assert!(sockets[0].connected());
+    assert!(try_timeout_rcv(&mut disconnect_rx, 200).await.is_none());
+}
+
+#[tokio::test]
+pub async fn connect_rejected_if_previous_disconnect_hangs() {
+    let (_svc, io) = SocketIo::builder()
+        .connect_timeout(Duration::from_millis(50))
+        .build_svc();
+    io.ns("/", |socket: SocketRef| {
+        socket.on("leave", |socket: SocketRef| {
+            // The graceful disconnect is never run, the socket stays draining
+            drop(socket.disconnect_graceful(Duration::from_secs(1)));
+        });
+    });
+
+    let (stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await); // NS connect packet
+
+    assert_ok!(stx.try_send(Message("2[\"leave\"]".into())));
+    assert_ok!(stx.try_send(Message("0".into())));
+    assert_eq!(
+        assert_some!(try_timeout_rcv(&mut srx, 200).await),
+        Message("4{\"message\":\"Disconnect in progress\"}".into())
+    );
+    assert_eq!(io.sockets().unwrap().len(), 1);
+}
@@ -308,3 +308,27 @@ pub async fn server_http_closing() {
         );
     }
 }
+
+#[tokio::test]
+pub async fn server_closing_disconnect_handlers() {
+    let io = create_server(12352).await;
+    let mut rx = attach_handler(&io, 10);
+    let mut streams =
+        futures_util::future::join_all((0..10).map(|_| create_ws_connection(12352))).await;
+    futures_util::future::join_all(streams.iter_mut().map(|s| async move {
+        s.next().await; // engine.io open packet
+        s.next().await; // socket.io open packet
+    }))
+    .await;
+
+    tokio::time::timeout(Duration::from_millis(20), io.close())
+        .await
+        .expect("timeout waiting for server closing");
+    for _ in 0..10 {
+        let data = tokio::time::timeout(Duration::from_millis(20), rx.recv())
+            .await
+            .expect("timeout waiting for DisconnectReason::ClosingServer")
+            .unwrap();
+        assert_eq!(data, DisconnectReason::ClosingServer);
+    }
+}
@@ -0,0 +1,70 @@
+//! Tests for the events received by a socket once its disconnection has started
+mod utils;
+
+use std::time::Duration;
+
+use engineioxide::Packet::*;
+use socketioxide::{extract::SocketRef, DisconnectingEventPolicy, SocketIo};
+use tokio::sync::mpsc;
+
+use utils::try_timeout_rcv;
+
+/// Starts a graceful disconnect while a slow handler is in flight, then sends an event.
+/// Returns the events handled after the disconnect started.
+async fn late_events(policy: Option<DisconnectingEventPolicy>) -> Vec<&'static str> {
+    let (_svc, io) = SocketIo::new_svc();
+    let (tx, mut rx) = mpsc::channel::<&'static str>(4);
+    io.ns("/", move |socket: SocketRef| {
+        let tx = tx.clone();
+        socket.on("slow", || tokio::time::sleep(Duration::from_millis(50)));
+        socket.on("drain", |socket: SocketRef| {
+            tokio::spawn(socket.disconnect_graceful(Duration::from_secs(1)));
+        });
+        socket.on("late", move || tx.try_send("late").unwrap());
+    });
+    if let Some(policy) = policy {
+        assert!(io.disconnecting_event_policy("/", policy));
+    }
+
+    let (stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await); // NS connect packet
+    assert_ok!(stx.try_send(Message("2[\"slow\"]".into())));
+    assert_ok!(stx.try_send(Message("2[\"drain\"]".into())));
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    assert_eq!(io.sockets().unwrap().len(), 1);
+    assert_ok!(stx.try_send(Message("2[\"late\"]".into())));
+
+    // The socket is disconnected once the slow handler completes
+    assert_eq!(
+        assert_some!(try_timeout_rcv(&mut srx, 100).await),
+        Message("1".into())
+    );
+    let mut events = Vec::new();
+    while let Ok(event) = rx.try_recv() {
+        events.push(event);
+    }
+    events
+}
+
+#[tokio::test]
+pub async fn late_events_are_dropped_by_default() {
+    assert_eq!(late_events(None).await, Vec::<&str>::new());
+}
+
+#[tokio::test]
+pub async fn late_events_are_dropped_with_log() {
+    let policy = DisconnectingEventPolicy::DropWithLog;
+    assert_eq!(late_events(Some(policy)).await, Vec::<&str>::new());
+}
+
+#[tokio::test]
+pub async fn late_events_are_dispatched() {
+    let policy = DisconnectingEventPolicy::Dispatch;
+    assert_eq!(late_events(Some(policy)).await, ["late"]);
+}
+
+#[tokio::test]
+pub async fn unknown_namespace_policy() {
+    let (_svc, io) = SocketIo::new_svc();
+    assert!(!io.disconnecting_event_policy("/", DisconnectingEventPolicy::Dispatch));
+}
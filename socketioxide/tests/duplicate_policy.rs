@@ -0,0 +1,127 @@
+//! Tests for the handling of the connections sharing a user id
+mod utils;
+
+use std::time::Duration;
+
+use engineioxide::Packet::*;
+use serde_json::json;
+use socketioxide::{extract::SocketRef, handler::ConnectHandler, DuplicatePolicy, SocketIo};
+
+use utils::try_timeout_rcv;
+
+fn create_io(policy: DuplicatePolicy) -> SocketIo {
+    let (_svc, io) = SocketIo::builder()
+        .user_id_from_auth(|auth| Some(auth.get("user")?.as_str()?.to_string()))
+        .duplicate_policy(policy)
+        .build_svc();
+    io.ns("/", |socket: SocketRef| {
+        assert!(socket.user_id().is_some());
+    });
+    io
+}
+
+#[tokio::test]
+pub async fn duplicate_allow_multiple() {
+    let io = create_io(DuplicatePolicy::AllowMultiple);
+    let (_stx1, mut srx1) = io.new_dummy_sock("/", json!({ "user": "alice" })).await;
+    let (_stx2, mut srx2) = io.new_dummy_sock("/", json!({ "user": "alice" })).await;
+    for srx in [&mut srx1, &mut srx2] {
+        let msg = assert_some!(srx.recv().await);
+        assert!(matches!(msg, Message(msg) if msg.starts_with("0{")));
+    }
+    assert!(try_timeout_rcv(&mut srx1, 20).await.is_none());
+
+    let sockets = io.sockets().unwrap();
+    assert_eq!(sockets.len(), 2);
+    assert!(sockets.iter().all(|s| s.user_id() == Some("alice")));
+}
+
+#[tokio::test]
+pub async fn duplicate_kick_old() {
+    let io = create_io(DuplicatePolicy::KickOld);
+    let (_stx1, mut srx1) = io.new_dummy_sock("/", json!({ "user": "alice" })).await;
+    assert_some!(srx1.recv().await); // NS connect packet
+    let old = io.sockets().unwrap().pop().unwrap().id;
+    let (_stx3, mut srx3) = io.new_dummy_sock("/", json!({ "user": "bob" })).await;
+    assert_some!(srx3.recv().await);
+
+    let (_stx2, mut srx2) = io.new_dummy_sock("/", json!({ "user": "alice" })).await;
+    let msg = assert_some!(srx2.recv().await);
+    assert!(matches!(msg, Message(msg) if msg.starts_with("0{")));
+    assert_eq!(assert_some!(srx1.recv().await), Message("1".into()));
+    // The other users are not affected
+    assert!(try_timeout_rcv(&mut srx3, 20).await.is_none());
+
+    let sockets = io.sockets().unwrap();
+    assert_eq!(sockets.len(), 2);
+    assert!(sockets.iter().all(|s| s.id != old));
+}
+
+#[tokio::test]
+pub async fn duplicate_reject_new() {
+    let io = create_io(DuplicatePolicy::RejectNew);
+    let (_stx1, mut srx1) = io.new_dummy_sock("/", json!({ "user": "alice" })).await;
+    assert_some!(srx1.recv().await); // NS connect packet
+    let first = io.sockets().unwrap().pop().unwrap().id;
+
+    let (_stx2, mut srx2) = io.new_dummy_sock("/", json!({ "user": "alice" })).await;
+    assert_eq!(
+        assert_some!(srx2.recv().await),
+        Message("4{\"message\":\"user already connected\"}".into())
+    );
+    assert!(try_timeout_rcv(&mut srx1, 20).await.is_none());
+
+    let sockets = io.sockets().unwrap();
+    assert_eq!(sockets.len(), 1);
+    assert_eq!(sockets[0].id, first);
+
+    // Another user can still connect
+    let (_stx3, mut srx3) = io.new_dummy_sock("/", json!({ "user": "bob" })).await;
+    let msg = assert_some!(srx3.recv().await);
+    assert!(matches!(msg, Message(msg) if msg.starts_with("0{")));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+pub async fn duplicate_reject_new_concurrent() {
+    let (_svc, io) = SocketIo::builder()
+        .user_id_from_auth(|auth| Some(auth.get("user")?.as_str()?.to_string()))
+        .duplicate_policy(DuplicatePolicy::RejectNew)
+        .build_svc();
+    // The connections are all past their middleware at the same time
+    let middleware = || async {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        Ok::<_, &'static str>(())
+    };
+    io.ns("/", (|| {}).with(middleware));
+
+    let conns = (0..20).map(|_| {
+        let io = io.clone();
+        tokio::spawn(async move { io.new_dummy_sock("/", json!({ "user": "alice" })).await })
+    });
+    let mut accepted = 0;
+    for conn in futures_util::future::join_all(conns).await {
+        let (_stx, mut srx) = assert_ok!(conn);
+        match assert_some!(srx.recv().await) {
+            Message(msg) if msg.starts_with("0{") => accepted += 1,
+            msg => assert_eq!(
+                msg,
+                Message("4{\"message\":\"user already connected\"}".into())
+            ),
+        }
+    }
+    assert_eq!(accepted, 1);
+    assert_eq!(io.sockets().unwrap().len(), 1);
+}
+
+#[tokio::test]
+pub async fn duplicate_reject_new_after_disconnect() {
+    let io = create_io(DuplicatePolicy::RejectNew);
+    let (_stx1, mut srx1) = io.new_dummy_sock("/", json!({ "user": "alice" })).await;
+    assert_some!(srx1.recv().await); // NS connect packet
+    assert_ok!(io.sockets().unwrap().pop().unwrap().disconnect());
+
+    // The user can connect again once its socket is disconnected
+    let (_stx2, mut srx2) = io.new_dummy_sock("/", json!({ "user": "alice" })).await;
+    let msg = assert_some!(srx2.recv().await);
+    assert!(matches!(msg, Message(msg) if msg.starts_with("0{")));
+}
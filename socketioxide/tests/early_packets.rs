@@ -0,0 +1,67 @@
+//! Tests for the packets received before connecting to their namespace
+mod utils;
+
+use std::time::Duration;
+
+use engineioxide::Packet::*;
+use socketioxide::{extract::SocketRef, handler::ConnectHandler, EarlyPacketPolicy, SocketIo};
+use tokio::sync::mpsc;
+
+async fn slow_auth() -> Result<(), std::convert::Infallible> {
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    Ok(())
+}
+
+/// Creates a server with a slow connect middleware, connects a socket and sends an event before it is connected
+async fn early_event(
+    policy: EarlyPacketPolicy,
+) -> (
+    mpsc::Receiver<&'static str>,
+    mpsc::Receiver<engineioxide::Packet>,
+) {
+    let (tx, rx) = mpsc::channel(10);
+    let (_, io) = SocketIo::builder().early_packet_policy(policy).build_svc();
+    let handler = move |socket: SocketRef| {
+        let tx = tx.clone();
+        socket.on("msg", move || tx.try_send("msg").unwrap());
+    };
+    io.ns("/", handler.with(slow_auth));
+
+    let (stx, srx) = io.new_dummy_sock("/", ()).await;
+    assert_ok!(stx.try_send(Message("2[\"msg\"]".into())));
+    // Keep the connection open
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        drop(stx);
+    });
+    (rx, srx)
+}
+
+#[tokio::test]
+pub async fn early_packet_dropped() {
+    let (mut rx, mut srx) = early_event(EarlyPacketPolicy::Drop).await;
+
+    let p = assert_some!(srx.recv().await);
+    assert!(matches!(p, Message(s) if s.starts_with('0')));
+    let res = tokio::time::timeout(Duration::from_millis(20), rx.recv()).await;
+    assert_err!(res);
+}
+
+#[tokio::test]
+pub async fn early_packet_buffered() {
+    let (mut rx, mut srx) = early_event(EarlyPacketPolicy::Buffer).await;
+
+    let p = assert_some!(srx.recv().await);
+    assert!(matches!(p, Message(s) if s.starts_with('0')));
+    let res = tokio::time::timeout(Duration::from_millis(20), rx.recv()).await;
+    assert_eq!(assert_some!(assert_ok!(res)), "msg");
+}
+
+#[tokio::test]
+pub async fn early_packet_disconnect() {
+    let (mut rx, mut srx) = early_event(EarlyPacketPolicy::Disconnect).await;
+
+    let p = tokio::time::timeout(Duration::from_millis(20), srx.recv()).await;
+    assert!(matches!(assert_some!(assert_ok!(p)), Close));
+    assert_err!(rx.try_recv());
+}
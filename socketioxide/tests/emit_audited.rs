@@ -0,0 +1,76 @@
+//! Tests for the broadcasts reporting the sockets they were delivered to
+mod utils;
+
+use std::time::Duration;
+
+use engineioxide::Packet::*;
+use socketioxide::{extract::SocketRef, lifecycle::LifecycleEvent, SocketIo};
+
+use utils::try_timeout_rcv;
+
+#[tokio::test]
+pub async fn emit_audited_excludes_disconnected_sockets() {
+    let (_svc, io) = SocketIo::new_svc();
+    io.ns("/", |socket: SocketRef| {
+        socket.join("room").unwrap();
+    });
+
+    let (_stx1, mut srx1) = io.new_dummy_sock("/", ()).await;
+    let (_stx2, mut srx2) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx1.recv().await); // NS connect packet
+    assert_some!(srx2.recv().await);
+    tokio::time::sleep(Duration::from_millis(10)).await;
+
+    // The first recipient disconnects the other one in the middle of the broadcast
+    let io2 = io.clone();
+    io.on_event(move |event| {
+        if let LifecycleEvent::Emit { sid, event, .. } = event {
+            if event == "audited" {
+                let sockets = io2.within("room").sockets().unwrap();
+                for socket in sockets.into_iter().filter(|s| s.id != sid) {
+                    socket.disconnect().unwrap();
+                }
+            }
+        }
+    });
+    let delivered = assert_ok!(io.to("room").emit_audited("audited", "hello"));
+    assert_eq!(delivered.len(), 1);
+
+    let msg = Message("2[\"audited\",\"hello\"]".into());
+    let disconnect = Message("1".into());
+    let (recv1, recv2) = (
+        assert_some!(try_timeout_rcv(&mut srx1, 50).await),
+        assert_some!(try_timeout_rcv(&mut srx2, 50).await),
+    );
+    // Only the delivered socket received the event, the other one the disconnect packet
+    let delivered = delivered[0];
+    let sids: Vec<_> = io.sockets().unwrap().into_iter().map(|s| s.id).collect();
+    assert_eq!(sids, [delivered]);
+    assert!(
+        (recv1 == msg && recv2 == disconnect) || (recv1 == disconnect && recv2 == msg),
+        "{recv1:?} {recv2:?}"
+    );
+}
+
+#[tokio::test]
+pub async fn emit_audited_all_connected() {
+    let (_svc, io) = SocketIo::new_svc();
+    io.ns("/", |socket: SocketRef| {
+        socket.join("room").unwrap();
+    });
+
+    let (_stx1, mut srx1) = io.new_dummy_sock("/", ()).await;
+    let (_stx2, mut srx2) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx1.recv().await); // NS connect packet
+    assert_some!(srx2.recv().await);
+    tokio::time::sleep(Duration::from_millis(10)).await;
+
+    let mut delivered = assert_ok!(io.to("room").emit_audited("audited", "hello"));
+    let mut sids: Vec<_> = io.sockets().unwrap().into_iter().map(|s| s.id).collect();
+    delivered.sort();
+    sids.sort();
+    assert_eq!(delivered, sids);
+    let msg = Message("2[\"audited\",\"hello\"]".into());
+    assert_eq!(assert_some!(try_timeout_rcv(&mut srx1, 50).await), msg);
+    assert_eq!(assert_some!(try_timeout_rcv(&mut srx2, 50).await), msg);
+}
@@ -0,0 +1,96 @@
+//! Tests for emitting payloads serialized with a per-call context
+mod utils;
+
+use std::time::Duration;
+
+use engineioxide::Packet::*;
+use serde::{ser::SerializeStruct, Serializer};
+use serde_json::{json, Value};
+use socketioxide::{
+    context::SerializeWithContext,
+    extract::{Data, SocketRef},
+    SocketIo,
+};
+
+use utils::try_timeout_rcv;
+
+struct User {
+    name: &'static str,
+    email: &'static str,
+}
+
+enum View {
+    Public,
+    Admin,
+}
+
+impl SerializeWithContext<View> for User {
+    fn serialize_with_context<S: Serializer>(
+        &self,
+        view: &View,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut s = serializer.serialize_struct("User", 2)?;
+        s.serialize_field("name", self.name)?;
+        match view {
+            View::Admin => s.serialize_field("email", self.email)?,
+            View::Public => s.skip_field("email")?,
+        }
+        s.end()
+    }
+}
+
+const USER: User = User {
+    name: "bob",
+    email: "bob@example.com",
+};
+
+#[tokio::test]
+pub async fn redacted_and_full_views_to_rooms() {
+    let (_svc, io) = SocketIo::new_svc();
+    io.ns("/", |socket: SocketRef, Data::<Value>(auth)| {
+        socket
+            .join(auth["room"].as_str().unwrap().to_string())
+            .unwrap();
+    });
+
+    let (_stx1, mut admin) = io.new_dummy_sock("/", json!({ "room": "admins" })).await;
+    let (_stx2, mut user) = io.new_dummy_sock("/", json!({ "room": "users" })).await;
+    assert_some!(admin.recv().await); // NS connect packet
+    assert_some!(user.recv().await);
+    // Wait for the connect handlers to join the rooms
+    tokio::time::sleep(Duration::from_millis(10)).await;
+
+    assert_ok!(io
+        .to("admins")
+        .emit_with_context("user", &USER, &View::Admin));
+    assert_ok!(io
+        .to("users")
+        .emit_with_context("user", &USER, &View::Public));
+
+    assert_eq!(
+        assert_some!(try_timeout_rcv(&mut admin, 20).await),
+        Message("2[\"user\",{\"email\":\"bob@example.com\",\"name\":\"bob\"}]".into())
+    );
+    assert_eq!(
+        assert_some!(try_timeout_rcv(&mut user, 20).await),
+        Message("2[\"user\",{\"name\":\"bob\"}]".into())
+    );
+    assert!(try_timeout_rcv(&mut admin, 20).await.is_none());
+    assert!(try_timeout_rcv(&mut user, 20).await.is_none());
+}
+
+#[tokio::test]
+pub async fn socket_emit_with_context() {
+    let (_svc, io) = SocketIo::new_svc();
+    io.ns("/", |socket: SocketRef| {
+        assert_ok!(socket.emit_with_context("user", &USER, &View::Public));
+    });
+
+    let (_stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await); // NS connect packet
+    assert_eq!(
+        assert_some!(try_timeout_rcv(&mut srx, 20).await),
+        Message("2[\"user\",{\"name\":\"bob\"}]".into())
+    );
+}
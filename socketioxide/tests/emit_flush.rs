@@ -0,0 +1,113 @@
+//! Tests for the broadcast waiting for the transport flush of the recipients
+mod fixture;
+mod utils;
+
+use std::time::Duration;
+
+use engineioxide::Packet::*;
+use socketioxide::{extract::SocketRef, socket::Sid, SocketIo};
+
+use fixture::{create_polling_connection, send_req, spawn_server};
+
+use utils::timeout_rcv;
+
+#[tokio::test]
+pub async fn emit_flush_waits_blocked_writer() {
+    let (_svc, io) = SocketIo::builder().max_buffer_size(2).build_svc();
+    io.ns("/", |socket: SocketRef| socket.join("room").unwrap());
+
+    let (_stx1, mut srx1) = io.new_dummy_sock("/", ()).await;
+    let (_stx2, mut srx2) = io.new_dummy_sock("/", ()).await;
+    let connect = timeout_rcv(&mut srx2).await; // NS connect packet
+    let sid2: Sid = match connect {
+        Message(msg) => {
+            let connect: serde_json::Value = serde_json::from_str(&msg[1..]).unwrap();
+            connect["sid"].as_str().unwrap().parse().unwrap()
+        }
+        msg => panic!("unexpected packet {msg:?}"),
+    };
+    let sid1 = io.sockets().unwrap().into_iter().find(|s| s.id != sid2);
+    let sid1 = assert_some!(sid1).id;
+
+    // The first client does not read: once its pipe is full, its writer is blocked
+    let socket1 = assert_some!(io.get_socket(sid1));
+    assert_ok!(socket1.emit("fill", 1));
+    assert_ok!(socket1.emit("fill", 2));
+    tokio::time::sleep(Duration::from_millis(10)).await;
+
+    let flush = tokio::spawn({
+        let io = io.clone();
+        async move { io.to("room").emit_flush("test", "foo").await }
+    });
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(!flush.is_finished());
+    // The second client is not blocked
+    assert_eq!(
+        timeout_rcv(&mut srx2).await,
+        Message("2[\"test\",\"foo\"]".into())
+    );
+
+    // Reading the first client unblocks its writer
+    timeout_rcv(&mut srx1).await; // NS connect packet
+    assert_eq!(
+        timeout_rcv(&mut srx1).await,
+        Message("2[\"fill\",1]".into())
+    );
+    assert_eq!(
+        timeout_rcv(&mut srx1).await,
+        Message("2[\"fill\",2]".into())
+    );
+    let report = tokio::time::timeout(Duration::from_millis(20), flush).await;
+    let mut report = assert_ok!(assert_ok!(assert_ok!(report)));
+    report.flushed.sort();
+    let mut expected = vec![sid1, sid2];
+    expected.sort();
+    assert_eq!(report.flushed, expected);
+    assert!(report.closed.is_empty());
+    assert_eq!(report.remote_servers, 0);
+    assert_eq!(
+        timeout_rcv(&mut srx1).await,
+        Message("2[\"test\",\"foo\"]".into())
+    );
+}
+
+#[tokio::test]
+pub async fn emit_flush_after_heartbeat() {
+    const PORT: u16 = 12382;
+    let (svc, io) = SocketIo::builder()
+        .ping_interval(Duration::from_millis(50))
+        .ping_timeout(Duration::from_secs(1))
+        .build_svc();
+    io.ns("/", |_: SocketRef| {});
+    spawn_server(PORT, svc).await;
+
+    let sid = create_polling_connection(PORT).await;
+    let poll = || {
+        send_req(
+            PORT,
+            format!("transport=polling&sid={sid}"),
+            http::Method::GET,
+            None,
+        )
+    };
+    // The engine.io packet type is stripped by `send_req`
+    let connect = poll().await;
+    assert!(connect.starts_with('0'), "{connect}");
+    // The ping packet is flushed by this polling request
+    let ping = tokio::time::timeout(Duration::from_millis(200), poll()).await;
+    assert_eq!(assert_ok!(ping), "");
+
+    // Without a pending polling request, the packet is not flushed
+    let flush = tokio::spawn({
+        let io = io.clone();
+        async move { io.of("/").unwrap().emit_flush("test", "foo").await }
+    });
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(!flush.is_finished());
+
+    assert_eq!(poll().await, "2[\"test\",\"foo\"]");
+    let report = tokio::time::timeout(Duration::from_millis(20), flush).await;
+    let report = assert_ok!(assert_ok!(assert_ok!(report)));
+    assert_eq!(report.flushed.len(), 1);
+    assert!(report.closed.is_empty());
+}
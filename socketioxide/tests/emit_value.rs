@@ -0,0 +1,36 @@
+//! Tests for emitting dynamically built json values
+mod utils;
+
+use engineioxide::Packet::*;
+use serde_json::json;
+use socketioxide::{extract::SocketRef, SocketIo};
+
+use utils::try_timeout_rcv;
+
+#[tokio::test]
+pub async fn emit_nested_value() {
+    let (_svc, io) = SocketIo::new_svc();
+    io.ns("/", |socket: SocketRef| {
+        let value = json!({
+            "user": { "name": "foo", "tags": ["a", "b"], "meta": { "age": 3, "admin": null } }
+        });
+        assert_ok!(socket.emit("profile", &value));
+        assert_ok!(socket.emit_value("profile", value));
+        // Array values are sent as multiple arguments
+        assert_ok!(socket.emit_value("args", json!([1, { "a": [true] }])));
+    });
+
+    let (_stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await); // NS connect packet
+
+    let expected = Message(
+        "2[\"profile\",{\"user\":{\"meta\":{\"admin\":null,\"age\":3},\"name\":\"foo\",\"tags\":[\"a\",\"b\"]}}]"
+            .into(),
+    );
+    assert_eq!(assert_some!(try_timeout_rcv(&mut srx, 20).await), expected);
+    assert_eq!(assert_some!(try_timeout_rcv(&mut srx, 20).await), expected);
+    assert_eq!(
+        assert_some!(try_timeout_rcv(&mut srx, 20).await),
+        Message("2[\"args\",1,{\"a\":[true]}]".into())
+    );
+}
@@ -0,0 +1,74 @@
+//! Tests for the wire shape of the acknowledgements sent with an empty payload
+mod utils;
+
+use engineioxide::Packet::*;
+use socketioxide::{
+    extract::{AckSender, SocketRef},
+    EmptyAckShape, SocketIo,
+};
+use tokio::sync::mpsc;
+
+async fn create_socket(
+    shape: Option<EmptyAckShape>,
+) -> (
+    mpsc::Sender<engineioxide::Packet>,
+    mpsc::Receiver<engineioxide::Packet>,
+) {
+    let mut builder = SocketIo::builder();
+    if let Some(shape) = shape {
+        builder = builder.empty_ack_shape(shape);
+    }
+    let (_svc, io) = builder.build_svc();
+    io.ns("/", |socket: SocketRef| {
+        socket.on("unit", |ack: AckSender| ack.send(()).unwrap());
+        socket.on("none", |ack: AckSender| ack.send(None::<String>).unwrap());
+        socket.on("some", |ack: AckSender| ack.send(Some("ok")).unwrap());
+    });
+    let (stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await); // NS connect packet
+    (stx, srx)
+}
+
+async fn ack(
+    stx: &mpsc::Sender<engineioxide::Packet>,
+    srx: &mut mpsc::Receiver<engineioxide::Packet>,
+    event: &str,
+) -> engineioxide::Packet {
+    assert_ok!(stx.try_send(Message(format!("21[\"{event}\"]").into())));
+    assert_some!(srx.recv().await)
+}
+
+#[tokio::test]
+pub async fn empty_ack_without_args_by_default() {
+    let (stx, mut srx) = create_socket(None).await;
+    assert_eq!(ack(&stx, &mut srx, "unit").await, Message("31[]".into()));
+    assert_eq!(ack(&stx, &mut srx, "none").await, Message("31[]".into()));
+    assert_eq!(
+        ack(&stx, &mut srx, "some").await,
+        Message("31[\"ok\"]".into())
+    );
+}
+
+#[tokio::test]
+pub async fn empty_ack_without_args() {
+    let (stx, mut srx) = create_socket(Some(EmptyAckShape::NoArgs)).await;
+    assert_eq!(ack(&stx, &mut srx, "unit").await, Message("31[]".into()));
+    assert_eq!(ack(&stx, &mut srx, "none").await, Message("31[]".into()));
+}
+
+#[tokio::test]
+pub async fn empty_ack_with_null() {
+    let (stx, mut srx) = create_socket(Some(EmptyAckShape::Null)).await;
+    assert_eq!(
+        ack(&stx, &mut srx, "unit").await,
+        Message("31[null]".into())
+    );
+    assert_eq!(
+        ack(&stx, &mut srx, "none").await,
+        Message("31[null]".into())
+    );
+    assert_eq!(
+        ack(&stx, &mut srx, "some").await,
+        Message("31[\"ok\"]".into())
+    );
+}
@@ -0,0 +1,56 @@
+//! Tests for the events encoded without being emitted
+mod utils;
+
+use std::time::Duration;
+
+use bytes::Bytes;
+use engineioxide::Packet::*;
+use serde_json::json;
+use socketioxide::{extract::SocketRef, SocketIo};
+
+#[tokio::test]
+pub async fn encoded_event_matches_wire_format() {
+    let (_, io) = SocketIo::new_svc();
+    io.ns("/", || {});
+    let (_stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await);
+
+    let data = json!({ "text": "hello", "list": [1, 2] });
+    let packet = io.encode_event("msg", &data).unwrap();
+    assert_eq!(packet.text(), r#"2["msg",{"list":[1,2],"text":"hello"}]"#);
+    assert!(packet.binary().is_empty());
+    io.emit("msg", &data).unwrap();
+    assert_eq!(
+        assert_some!(srx.recv().await),
+        Message(packet.text().to_string().into())
+    );
+
+    let bin = Bytes::from_static(&[1, 2, 3]);
+    let packet = io.encode_event("bin", ("file", &bin)).unwrap();
+    assert_eq!(
+        packet.text(),
+        r#"51-["bin","file",{"_placeholder":true,"num":0}]"#
+    );
+    assert_eq!(packet.binary(), std::slice::from_ref(&bin));
+    io.emit("bin", ("file", &bin)).unwrap();
+    let (text, binary) = packet.into_parts();
+    assert_eq!(assert_some!(srx.recv().await), Message(text.into()));
+    assert_eq!(assert_some!(srx.recv().await), Binary(binary[0].clone()));
+}
+
+#[tokio::test]
+pub async fn encoded_packet_can_be_sent_raw() {
+    let (_, io) = SocketIo::new_svc();
+    io.ns("/", || {});
+    let (_stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await);
+
+    let packet = io.encode_event("cached", "value").unwrap();
+    let socket: SocketRef = io.sockets().unwrap().remove(0);
+    socket.send_raw_bytes(packet.text().as_bytes()).unwrap();
+    let msg = tokio::time::timeout(Duration::from_millis(20), srx.recv()).await;
+    assert_eq!(
+        assert_some!(assert_ok!(msg)),
+        Message("2[\"cached\",\"value\"]".into())
+    );
+}
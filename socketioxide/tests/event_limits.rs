@@ -0,0 +1,49 @@
+//! Tests for the event name length and arguments limits
+mod utils;
+
+use engineioxide::Packet::*;
+use socketioxide::{
+    extract::{Data, SocketRef},
+    EventLimitsPolicy, SocketIo,
+};
+use tokio::sync::mpsc;
+
+use utils::try_timeout_rcv;
+
+#[tokio::test]
+pub async fn overlong_event_name_closes_connection() {
+    let (_svc, io) = SocketIo::builder().max_event_name_len(8).build_svc();
+    io.ns("/", || {});
+
+    let (stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await); // NS connect packet
+
+    assert_ok!(stx.try_send(Message("2[\"overlong-event\"]".into())));
+    assert_eq!(assert_some!(try_timeout_rcv(&mut srx, 20).await), Close);
+}
+
+#[tokio::test]
+pub async fn too_many_args_dropped() {
+    let (_svc, io) = SocketIo::builder()
+        .max_event_args(2)
+        .event_limits_policy(EventLimitsPolicy::Drop)
+        .build_svc();
+    let (tx, mut rx) = mpsc::channel::<usize>(2);
+    io.ns("/", move |socket: SocketRef| {
+        let tx = tx.clone();
+        socket.on("test", move |Data::<Vec<u32>>(args)| {
+            tx.try_send(args.len()).unwrap()
+        });
+    });
+
+    let (stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await); // NS connect packet
+
+    assert_ok!(stx.try_send(Message("2[\"test\",1,2,3]".into())));
+    assert!(try_timeout_rcv(&mut rx, 20).await.is_none());
+
+    // The connection is kept open and the next events are handled
+    assert_ok!(stx.try_send(Message("2[\"test\",1,2]".into())));
+    assert_eq!(assert_some!(try_timeout_rcv(&mut rx, 20).await), 2);
+    assert_eq!(io.sockets().unwrap().len(), 1);
+}
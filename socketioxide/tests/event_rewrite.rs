@@ -0,0 +1,59 @@
+//! Tests for the rewriting of the incoming event names
+mod utils;
+
+use std::borrow::Cow;
+
+use bytes::Bytes;
+use engineioxide::Packet::*;
+use socketioxide::{
+    extract::{AckSender, Data, SocketRef},
+    SocketIo,
+};
+
+use utils::try_timeout_rcv;
+
+#[tokio::test]
+pub async fn rewrite_aliases_old_event_name() {
+    let (_svc, io) = SocketIo::new_svc();
+    io.rewrite_events(|name| match name {
+        "v1:removed" => None,
+        "v1:greet" => Some(Cow::Borrowed("greet")),
+        name => Some(Cow::Owned(name.trim_start_matches("v2:").to_string())),
+    });
+    io.ns("/", |socket: SocketRef| {
+        socket.on("greet", |ack: AckSender, Data(name): Data<String>| {
+            ack.send(format!("hello {name}")).unwrap()
+        });
+        socket.on("v1:removed", |ack: AckSender| {
+            ack.send("unreachable").unwrap()
+        });
+        socket.on("bin", |ack: AckSender| ack.send("bin").unwrap());
+    });
+    let (stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await); // NS connect packet
+
+    assert_ok!(stx.try_send(Message("21[\"v1:greet\",\"alice\"]".into())));
+    let msg = assert_some!(srx.recv().await);
+    assert_eq!(msg, Message("31[\"hello alice\"]".into()));
+
+    // The current name still reaches the handler
+    assert_ok!(stx.try_send(Message("22[\"greet\",\"bob\"]".into())));
+    let msg = assert_some!(srx.recv().await);
+    assert_eq!(msg, Message("32[\"hello bob\"]".into()));
+
+    assert_ok!(stx.try_send(Message("23[\"v2:greet\",\"carol\"]".into())));
+    let msg = assert_some!(srx.recv().await);
+    assert_eq!(msg, Message("33[\"hello carol\"]".into()));
+
+    // Binary events are rewritten too
+    assert_ok!(stx.try_send(Message(
+        "51-4[\"v2:bin\",{\"_placeholder\":true,\"num\":0}]".into()
+    )));
+    assert_ok!(stx.try_send(Binary(Bytes::from_static(&[1, 2, 3]))));
+    let msg = assert_some!(srx.recv().await);
+    assert_eq!(msg, Message("34[\"bin\"]".into()));
+
+    // Rejected events are dropped before the handler lookup
+    assert_ok!(stx.try_send(Message("25[\"v1:removed\"]".into())));
+    assert!(try_timeout_rcv(&mut srx, 20).await.is_none());
+}
@@ -3,22 +3,20 @@ use std::convert::Infallible;
 use std::time::Duration;
 
 use serde_json::{json, Value};
-use socketioxide::extract::{Data, Extension, MaybeExtension, SocketRef, State, TryData};
+use socketioxide::extract::{
+    Arg0, Arg1, Data, Extension, MaybeData, MaybeExtension, SocketRef, State, TryData,
+};
 use socketioxide::handler::ConnectHandler;
 use tokio::sync::mpsc;
 
 use engineioxide::Packet as EioPacket;
 use socketioxide::packet::Packet;
 use socketioxide::SocketIo;
+
+use utils::timeout_rcv;
 mod fixture;
 mod utils;
 
-async fn timeout_rcv<T: std::fmt::Debug>(srx: &mut tokio::sync::mpsc::Receiver<T>) -> T {
-    tokio::time::timeout(Duration::from_millis(10), srx.recv())
-        .await
-        .unwrap()
-        .unwrap()
-}
 async fn timeout_rcv_err<T: std::fmt::Debug>(srx: &mut tokio::sync::mpsc::Receiver<T>) {
     tokio::time::timeout(Duration::from_millis(10), srx.recv())
         .await
@@ -126,6 +124,47 @@ pub async fn try_data_extractor() {
     assert_err!(timeout_rcv(&mut rx).await);
 }
 
+#[tokio::test]
+pub async fn maybe_data_extractor() {
+    let (_, io) = SocketIo::new_svc();
+    let (tx, mut rx) = mpsc::channel::<Result<Option<String>, serde_json::Error>>(4);
+    io.ns(
+        "/",
+        move |s: SocketRef, MaybeData(data): MaybeData<String>| {
+            assert_ok!(tx.try_send(data));
+            s.on("test", move |MaybeData(data): MaybeData<String>| {
+                assert_ok!(tx.try_send(data));
+            });
+        },
+    );
+
+    // Absent data
+    io.new_dummy_sock("/", ()).await;
+    assert_eq!(assert_ok!(timeout_rcv(&mut rx).await), None);
+
+    // Non deserializable data
+    io.new_dummy_sock("/", json!({ "test": 132 })).await;
+    assert_err!(timeout_rcv(&mut rx).await);
+
+    let (stx, _rtx) = io.new_dummy_sock("/", "foo").await;
+    let res = assert_ok!(timeout_rcv(&mut rx).await);
+    assert_eq!(res, Some("foo".to_string()));
+
+    assert_ok!(stx.try_send(create_msg("/", "test", "oof")));
+    let res = assert_ok!(timeout_rcv(&mut rx).await);
+    assert_eq!(res, Some("oof".to_string()));
+
+    // Absent data
+    assert_ok!(stx.try_send(EioPacket::Message("2[\"test\"]".into())));
+    assert_eq!(assert_ok!(timeout_rcv(&mut rx).await), None);
+    assert_ok!(stx.try_send(create_msg("/", "test", Value::Null)));
+    assert_eq!(assert_ok!(timeout_rcv(&mut rx).await), None);
+
+    // Non deserializable data
+    assert_ok!(stx.try_send(create_msg("/", "test", json!({ "test": 132 }))));
+    assert_err!(timeout_rcv(&mut rx).await);
+}
+
 #[tokio::test]
 pub async fn extension_extractor() {
     let (_, io) = SocketIo::new_svc();
@@ -215,3 +254,128 @@ pub async fn maybe_extension_extractor() {
         EioPacket::Message("2/test,[\"from_ev_test\",null]".into())
     );
 }
+
+#[allow(dead_code)]
+#[derive(Debug, serde::Deserialize)]
+struct User {
+    name: String,
+    address: Address,
+}
+#[allow(dead_code)]
+#[derive(Debug, serde::Deserialize)]
+struct Address {
+    city: String,
+}
+
+#[tokio::test]
+pub async fn strict_data_extractor() {
+    let (_, io) = SocketIo::builder().deny_unknown_fields(true).build_svc();
+    let (tx, mut rx) = mpsc::channel::<Result<(), String>>(4);
+    let tx1 = tx.clone();
+
+    io.ns(
+        "/",
+        move |socket: SocketRef, TryData(data): TryData<User>| {
+            assert_ok!(tx.try_send(data.map(drop).map_err(|e| e.to_string())));
+            socket.on("test", move |TryData(data): TryData<User>| {
+                assert_ok!(tx1.try_send(data.map(drop).map_err(|e| e.to_string())));
+            });
+        },
+    );
+
+    let user = json!({ "name": "foo", "address": { "city": "bar" } });
+    io.new_dummy_sock("/", user.clone()).await;
+    assert_ok!(timeout_rcv(&mut rx).await);
+
+    let auth = json!({ "name": "foo", "age": 21, "address": { "city": "bar" } });
+    io.new_dummy_sock("/", auth).await;
+    assert_eq!(
+        timeout_rcv(&mut rx).await.unwrap_err(),
+        "unknown field `age`"
+    );
+
+    let (stx, _srx) = io.new_dummy_sock("/", user.clone()).await;
+    assert_ok!(timeout_rcv(&mut rx).await);
+
+    assert_ok!(stx.try_send(create_msg("/", "test", user)));
+    assert_ok!(timeout_rcv(&mut rx).await);
+
+    let data = json!({ "name": "foo", "address": { "city": "bar", "zip": 1234 } });
+    assert_ok!(stx.try_send(create_msg("/", "test", data)));
+    assert_eq!(
+        timeout_rcv(&mut rx).await.unwrap_err(),
+        "unknown field `address.zip`"
+    );
+
+    // Regular deserialization errors are still reported
+    assert_ok!(stx.try_send(create_msg("/", "test", "oof")));
+    assert_err!(timeout_rcv(&mut rx).await);
+}
+
+#[tokio::test]
+pub async fn lenient_data_extractor() {
+    let (_, io) = SocketIo::new_svc();
+    let (tx, mut rx) = mpsc::channel::<Result<(), String>>(4);
+
+    io.ns("/", move |socket: SocketRef| {
+        socket.on("test", move |TryData(data): TryData<User>| {
+            assert_ok!(tx.try_send(data.map(drop).map_err(|e| e.to_string())));
+        });
+    });
+
+    let (stx, _srx) = io.new_dummy_sock("/", ()).await;
+    let data = json!({ "name": "foo", "age": 21, "address": { "city": "bar", "zip": 1234 } });
+    assert_ok!(stx.try_send(create_msg("/", "test", data)));
+    assert_ok!(timeout_rcv(&mut rx).await);
+}
+
+#[tokio::test]
+pub async fn positional_args_extractor() {
+    let (_, io) = SocketIo::new_svc();
+    let (tx, mut rx) = mpsc::channel::<(String, i32)>(4);
+    io.ns("/", move |socket: SocketRef| {
+        let tx = tx.clone();
+        socket.on("test", move |Arg0(a): Arg0<String>, Arg1(b): Arg1<i32>| {
+            assert_ok!(tx.try_send((a, b)));
+        });
+    });
+    let (stx, _rtx) = io.new_dummy_sock("/", ()).await;
+
+    assert_ok!(stx.try_send(create_msg("/", "test", json!(["hi", 5]))));
+    assert_eq!(timeout_rcv(&mut rx).await, ("hi".to_string(), 5));
+
+    // Extra arguments are ignored
+    assert_ok!(stx.try_send(create_msg("/", "test", json!(["foo", 1, true]))));
+    assert_eq!(timeout_rcv(&mut rx).await, ("foo".to_string(), 1));
+
+    // Too few arguments: the handler is not called
+    assert_ok!(stx.try_send(create_msg("/", "test", json!(["hi"]))));
+    timeout_rcv_err(&mut rx).await;
+
+    // Wrong positional types: the handler is not called
+    assert_ok!(stx.try_send(create_msg("/", "test", json!([5, "hi"]))));
+    timeout_rcv_err(&mut rx).await;
+}
+
+#[tokio::test]
+pub async fn io_extractor() {
+    let (_, io) = SocketIo::new_svc();
+    io.ns("/", |socket: SocketRef| {
+        socket.on("notify", |io: SocketIo, Data::<String>(data)| {
+            assert_ok!(assert_some!(io.of("/admin")).emit("notify", data));
+        });
+    });
+    io.ns("/admin", || {});
+
+    let (stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    srx.recv().await;
+    let (_atx, mut arx) = io.new_dummy_sock("/admin", ()).await;
+    arx.recv().await;
+
+    assert_ok!(stx.try_send(create_msg("/", "notify", "foo")));
+    assert_eq!(
+        timeout_rcv(&mut arx).await,
+        create_msg("/admin", "notify", "foo")
+    );
+    timeout_rcv_err(&mut srx).await;
+}
@@ -0,0 +1,195 @@
+//! Tests for the broadcasts held while a room is frozen
+mod utils;
+
+use std::time::Duration;
+
+use engineioxide::Packet::*;
+use serde_json::json;
+use socketioxide::{
+    extract::{Data, SocketRef},
+    lifecycle::LifecycleEvent,
+    BroadcastError, FreezePolicy, SocketIo,
+};
+use tokio::sync::mpsc;
+
+async fn recv_all(srx: &mut mpsc::Receiver<engineioxide::Packet>) -> Vec<engineioxide::Packet> {
+    let mut packets = Vec::new();
+    while let Ok(Some(packet)) = tokio::time::timeout(Duration::from_millis(20), srx.recv()).await {
+        packets.push(packet);
+    }
+    packets
+}
+
+#[tokio::test]
+pub async fn frozen_broadcasts_sent_in_order_after_unfreeze() {
+    let (_svc, io) = SocketIo::new_svc();
+    io.ns("/", |socket: SocketRef, Data(rooms): Data<Vec<String>>| {
+        socket.join(rooms).unwrap();
+    });
+    let (_stx1, mut srx1) = io.new_dummy_sock("/", json!(["game"])).await;
+    let (_stx2, mut srx2) = io.new_dummy_sock("/", json!(["lobby"])).await;
+    assert_some!(srx1.recv().await);
+    assert_some!(srx2.recv().await);
+    let socket = io.within("game").sockets().unwrap().remove(0);
+
+    io.freeze_room("game");
+    io.to("game").emit("msg", 1).unwrap();
+    io.to("lobby").emit("msg", 2).unwrap();
+    io.to(["game", "lobby"]).emit("msg", 3).unwrap();
+    io.to("game").emit("msg", 4).unwrap();
+    // Direct emits and broadcasts to other rooms are not held
+    socket.emit("direct", ()).unwrap();
+    assert_eq!(
+        recv_all(&mut srx1).await,
+        [Message("2[\"direct\",null]".into())]
+    );
+    assert_eq!(recv_all(&mut srx2).await, [Message("2[\"msg\",2]".into())]);
+
+    assert_eq!(io.unfreeze_room("game"), 3);
+    assert_eq!(
+        recv_all(&mut srx1).await,
+        [
+            Message("2[\"msg\",1]".into()),
+            Message("2[\"msg\",3]".into()),
+            Message("2[\"msg\",4]".into()),
+        ]
+    );
+    assert_eq!(recv_all(&mut srx2).await, [Message("2[\"msg\",3]".into())]);
+
+    io.to("game").emit("msg", 5).unwrap();
+    assert_eq!(recv_all(&mut srx1).await, [Message("2[\"msg\",5]".into())]);
+    assert_eq!(io.unfreeze_room("game"), 0);
+}
+
+#[tokio::test]
+pub async fn frozen_broadcasts_dropped() {
+    let (_svc, io) = SocketIo::builder()
+        .freeze_policy(FreezePolicy::Drop)
+        .build_svc();
+    io.ns("/", |socket: SocketRef| {
+        socket.join("game").unwrap();
+    });
+    let (_stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await);
+
+    io.freeze_room("game");
+    io.to("game").emit("msg", 1).unwrap();
+    assert_eq!(io.unfreeze_room("game"), 0);
+    assert_eq!(recv_all(&mut srx).await, []);
+
+    io.to("game").emit("msg", 2).unwrap();
+    assert_eq!(recv_all(&mut srx).await, [Message("2[\"msg\",2]".into())]);
+}
+
+#[tokio::test]
+pub async fn frozen_broadcasts_to_all_of_held() {
+    let (_svc, io) = SocketIo::new_svc();
+    io.ns("/", |socket: SocketRef| {
+        socket.join(["game", "team"]).unwrap();
+    });
+    let (_stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await);
+
+    io.freeze_room("game");
+    io.to_all_of(["game", "team"]).emit("msg", 1).unwrap();
+    assert_eq!(recv_all(&mut srx).await, []);
+
+    assert_eq!(io.unfreeze_room("game"), 1);
+    assert_eq!(recv_all(&mut srx).await, [Message("2[\"msg\",1]".into())]);
+}
+
+#[tokio::test]
+pub async fn frozen_emit_with_ack_rejected() {
+    let (_svc, io) = SocketIo::new_svc();
+    io.ns("/", |socket: SocketRef| {
+        socket.join("game").unwrap();
+    });
+    let (_stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await);
+
+    io.freeze_room("game");
+    let err = io.to("game").emit_with_ack::<()>("msg", 1);
+    assert!(matches!(err, Err(BroadcastError::FrozenRoom(room)) if room == "game"));
+    assert_eq!(recv_all(&mut srx).await, []);
+
+    io.unfreeze_room("game");
+    io.to("game").emit_with_ack::<()>("msg", 2).unwrap();
+    assert_eq!(recv_all(&mut srx).await, [Message("21[\"msg\",2]".into())]);
+}
+
+#[tokio::test]
+pub async fn frozen_emit_flush_rejected() {
+    let (_svc, io) = SocketIo::new_svc();
+    io.ns("/", |socket: SocketRef| {
+        socket.join("game").unwrap();
+    });
+    let (_stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await);
+
+    io.freeze_room("game");
+    let err = io.to("game").emit_flush("msg", 1).await.unwrap_err();
+    assert!(matches!(err, BroadcastError::FrozenRoom(room) if room == "game"));
+    assert_eq!(recv_all(&mut srx).await, []);
+    assert_eq!(io.unfreeze_room("game"), 0);
+}
+
+#[tokio::test]
+pub async fn frozen_emit_audited_rejected() {
+    let (_svc, io) = SocketIo::new_svc();
+    io.ns("/", |socket: SocketRef| {
+        socket.join(["game", "team"]).unwrap();
+    });
+    let (_stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await);
+
+    io.freeze_room("game");
+    let err = io.to_all_of(["game", "team"]).emit_audited("msg", 1);
+    assert!(matches!(err, Err(BroadcastError::FrozenRoom(room)) if room == "game"));
+    assert_eq!(recv_all(&mut srx).await, []);
+    assert_eq!(io.unfreeze_room("game"), 0);
+}
+
+#[tokio::test]
+pub async fn broadcast_from_event_sink() {
+    let (_svc, io) = SocketIo::new_svc();
+    io.ns("/", |socket: SocketRef| {
+        socket.join("game").unwrap();
+    });
+    let io2 = io.clone();
+    io.on_event(move |event| {
+        if let LifecycleEvent::Emit { event, .. } = event {
+            if event == "msg" {
+                io2.to("game").emit("echo", ()).unwrap();
+            }
+        }
+    });
+    let (_stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await);
+
+    // The emit event is notified before the packet is sent
+    io.to("game").emit("msg", 1).unwrap();
+    assert_eq!(
+        recv_all(&mut srx).await,
+        [
+            Message("2[\"echo\",null]".into()),
+            Message("2[\"msg\",1]".into()),
+        ]
+    );
+
+    // The broadcasts of the sink while unfreezing are sent after the queued ones
+    io.freeze_room("game");
+    io.to("game").emit("msg", 2).unwrap();
+    io.to("game").emit("msg", 3).unwrap();
+    assert_eq!(io.unfreeze_room("game"), 4);
+    assert_eq!(
+        recv_all(&mut srx).await,
+        [
+            Message("2[\"msg\",2]".into()),
+            Message("2[\"msg\",3]".into()),
+            Message("2[\"echo\",null]".into()),
+            Message("2[\"echo\",null]".into()),
+        ]
+    );
+    io.to("game").emit("msg", 4).unwrap();
+    assert_eq!(recv_all(&mut srx).await.len(), 2);
+}
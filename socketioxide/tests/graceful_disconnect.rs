@@ -0,0 +1,96 @@
+//! Tests for the graceful disconnection of a socket, waiting for its in-flight handlers
+mod utils;
+
+use std::time::Duration;
+
+use engineioxide::Packet::*;
+use socketioxide::{extract::SocketRef, SocketIo};
+
+use utils::try_timeout_rcv;
+
+async fn work(socket: SocketRef) {
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    socket.emit("done", ()).ok();
+}
+
+fn leave(socket: SocketRef) {
+    tokio::spawn(socket.disconnect_graceful(Duration::from_secs(1)));
+}
+
+#[tokio::test]
+pub async fn in_flight_handler_completes_before_disconnect() {
+    let (_svc, io) = SocketIo::new_svc();
+    io.ns("/", |socket: SocketRef| {
+        socket.on("work", work);
+        socket.on("leave", leave);
+    });
+
+    let (stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await); // NS connect packet
+
+    assert_ok!(stx.try_send(Message("2[\"work\"]".into())));
+    assert_ok!(stx.try_send(Message("2[\"leave\"]".into())));
+    // Ignored, the socket is draining
+    assert_ok!(stx.try_send(Message("2[\"work\"]".into())));
+
+    assert_eq!(
+        assert_some!(try_timeout_rcv(&mut srx, 200).await),
+        Message("2[\"done\",null]".into())
+    );
+    assert_eq!(
+        assert_some!(try_timeout_rcv(&mut srx, 200).await),
+        Message("1".into())
+    );
+    assert!(try_timeout_rcv(&mut srx, 200).await.is_none());
+}
+
+#[tokio::test]
+pub async fn ordered_queue_is_drained() {
+    let (_svc, io) = SocketIo::new_svc();
+    io.ordered_ns("/", |socket: SocketRef| {
+        socket.on("work", work);
+        socket.on("leave", leave);
+    });
+
+    let (stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await); // NS connect packet
+
+    assert_ok!(stx.try_send(Message("2[\"work\"]".into())));
+    assert_ok!(stx.try_send(Message("2[\"work\"]".into())));
+    assert_ok!(stx.try_send(Message("2[\"leave\"]".into())));
+
+    for _ in 0..2 {
+        assert_eq!(
+            assert_some!(try_timeout_rcv(&mut srx, 200).await),
+            Message("2[\"done\",null]".into())
+        );
+    }
+    assert_eq!(
+        assert_some!(try_timeout_rcv(&mut srx, 200).await),
+        Message("1".into())
+    );
+}
+
+#[tokio::test]
+pub async fn disconnect_after_timeout() {
+    let (_svc, io) = SocketIo::new_svc();
+    io.ns("/", |socket: SocketRef| {
+        socket.on("work", |socket: SocketRef| async move {
+            tokio::time::sleep(Duration::from_secs(10)).await;
+            socket.emit("done", ()).ok();
+        });
+        socket.on("leave", |socket: SocketRef| {
+            tokio::spawn(socket.disconnect_graceful(Duration::from_millis(20)));
+        });
+    });
+
+    let (stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await); // NS connect packet
+
+    assert_ok!(stx.try_send(Message("2[\"work\"]".into())));
+    assert_ok!(stx.try_send(Message("2[\"leave\"]".into())));
+    assert_eq!(
+        assert_some!(try_timeout_rcv(&mut srx, 200).await),
+        Message("1".into())
+    );
+}
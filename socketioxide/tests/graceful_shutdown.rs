@@ -0,0 +1,113 @@
+//! Tests for the graceful shutdown of the built-in server
+mod utils;
+
+use std::time::{Duration, Instant};
+
+use futures_util::{SinkExt, StreamExt};
+use socketioxide::{extract::SocketRef, socket::DisconnectReason, SocketIo};
+use tokio::{
+    io::AsyncWriteExt,
+    net::{TcpListener, TcpStream},
+    sync::{mpsc, oneshot},
+};
+use tokio_tungstenite::tungstenite::Message;
+
+async fn spawn_serve(
+    io: SocketIo,
+    port: u16,
+) -> (oneshot::Sender<()>, tokio::task::JoinHandle<()>) {
+    let listener = TcpListener::bind(("127.0.0.1", port)).await.unwrap();
+    let (tx, rx) = oneshot::channel::<()>();
+    let handle = tokio::spawn(async move {
+        io.serve_with_graceful_shutdown(listener, async {
+            rx.await.ok();
+        })
+        .await
+    });
+    (tx, handle)
+}
+
+#[tokio::test]
+pub async fn graceful_shutdown() {
+    const PORT: u16 = 12365;
+    let (_, io) = SocketIo::new_svc();
+    let (tx, mut rx) = mpsc::channel::<DisconnectReason>(1);
+    io.ns("/", move |socket: SocketRef| {
+        let tx = tx.clone();
+        socket.on_disconnect(move |reason: DisconnectReason| {
+            tx.try_send(reason).unwrap();
+        });
+    });
+    let (shutdown, handle) = spawn_serve(io, PORT).await;
+
+    let (mut ws, _) = assert_ok!(
+        tokio_tungstenite::connect_async(format!(
+            "ws://127.0.0.1:{PORT}/socket.io/?EIO=4&transport=websocket"
+        ))
+        .await
+    );
+    assert_ok!(ws.send(Message::Text("40{}".to_string())).await);
+    for _ in 0..2 {
+        // engine.io open packet and socket.io connect packet
+        assert_ok!(assert_some!(ws.next().await));
+    }
+
+    // Other paths are not served
+    let mut stream = assert_ok!(TcpStream::connect(("127.0.0.1", PORT)).await);
+    assert_ok!(
+        stream
+            .write_all(b"GET /other HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+            .await
+    );
+    let mut res = String::new();
+    assert_ok!(tokio::io::AsyncReadExt::read_to_string(&mut stream, &mut res).await);
+    assert!(res.starts_with("HTTP/1.1 404"), "{res}");
+
+    shutdown.send(()).unwrap();
+    let reason = tokio::time::timeout(Duration::from_secs(1), rx.recv()).await;
+    assert_eq!(assert_ok!(reason), Some(DisconnectReason::ServerShutdown));
+
+    // The websocket connection is closed
+    loop {
+        match tokio::time::timeout(Duration::from_secs(1), ws.next()).await {
+            Ok(Some(Ok(Message::Close(_))) | Some(Err(_)) | None) => break,
+            Ok(Some(Ok(_))) => continue,
+            Err(_) => panic!("websocket connection not closed"),
+        }
+    }
+    assert_ok!(assert_ok!(
+        tokio::time::timeout(Duration::from_secs(1), handle).await
+    ));
+
+    // New connections are refused
+    assert_err!(TcpStream::connect(("127.0.0.1", PORT)).await);
+}
+
+#[tokio::test]
+pub async fn graceful_shutdown_drain_timeout() {
+    const PORT: u16 = 12366;
+    const DRAIN_TIMEOUT: Duration = Duration::from_millis(200);
+    let (_, io) = SocketIo::builder().drain_timeout(DRAIN_TIMEOUT).build_svc();
+    io.ns("/", |_: SocketRef| {});
+    let (shutdown, handle) = spawn_serve(io, PORT).await;
+
+    // A connection stuck in the middle of a request cannot be gracefully closed
+    let mut stream = assert_ok!(TcpStream::connect(("127.0.0.1", PORT)).await);
+    assert_ok!(stream.write_all(b"GET /socket.io/ HTTP/1.1\r\n").await);
+    tokio::time::sleep(Duration::from_millis(20)).await;
+
+    let start = Instant::now();
+    shutdown.send(()).unwrap();
+    assert_ok!(assert_ok!(
+        tokio::time::timeout(Duration::from_secs(1), handle).await
+    ));
+    let elapsed = start.elapsed();
+    assert!(elapsed >= DRAIN_TIMEOUT, "{elapsed:?}");
+
+    // The stuck connection was dropped
+    let mut buf = Vec::new();
+    let read = tokio::io::AsyncReadExt::read_to_end(&mut stream, &mut buf);
+    assert_ok!(assert_ok!(
+        tokio::time::timeout(Duration::from_secs(1), read).await
+    ));
+}
@@ -0,0 +1,84 @@
+//! Tests for the handler panic policies
+mod utils;
+
+use engineioxide::Packet::*;
+use socketioxide::{extract::SocketRef, socket::DisconnectReason, HandlerPanicPolicy, SocketIo};
+use tokio::sync::mpsc;
+
+use utils::try_timeout_rcv;
+
+fn buggy_handler() {
+    panic!("handler bug");
+}
+
+async fn buggy_async_handler() {
+    panic!("handler bug");
+}
+
+#[tokio::test]
+pub async fn panicking_handler_disconnects() {
+    let (_svc, io) = SocketIo::builder()
+        .handler_panic_policy(HandlerPanicPolicy::Disconnect)
+        .build_svc();
+    let (tx, mut rx) = mpsc::channel::<DisconnectReason>(1);
+    io.ns("/", move |socket: SocketRef| {
+        let tx = tx.clone();
+        socket.on("panic", buggy_handler);
+        socket.on_disconnect(move |reason: DisconnectReason| tx.try_send(reason).unwrap());
+    });
+
+    let (stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await); // NS connect packet
+    assert_ok!(stx.try_send(Message("2[\"panic\"]".into())));
+
+    assert_eq!(
+        assert_some!(try_timeout_rcv(&mut srx, 50).await),
+        Message("1".into())
+    );
+    assert_eq!(
+        assert_some!(try_timeout_rcv(&mut rx, 50).await),
+        DisconnectReason::HandlerPanic
+    );
+    assert!(io.sockets().unwrap().is_empty());
+}
+
+#[tokio::test]
+pub async fn panicking_async_handler_is_logged() {
+    let (_svc, io) = SocketIo::builder()
+        .handler_panic_policy(HandlerPanicPolicy::Log)
+        .build_svc();
+    io.ns("/", |socket: SocketRef| {
+        socket.on("panic", buggy_async_handler);
+        socket.on("ping", |socket: SocketRef| socket.emit("pong", ()).unwrap());
+    });
+
+    let (stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await); // NS connect packet
+    assert_ok!(stx.try_send(Message("2[\"panic\"]".into())));
+    assert_ok!(stx.try_send(Message("2[\"ping\"]".into())));
+
+    // The socket is still connected and handles the next events
+    assert_eq!(
+        assert_some!(try_timeout_rcv(&mut srx, 50).await),
+        Message("2[\"pong\",null]".into())
+    );
+    assert_eq!(io.sockets().unwrap().len(), 1);
+}
+
+#[tokio::test]
+pub async fn panicking_ordered_handler_disconnects() {
+    let (_svc, io) = SocketIo::builder()
+        .handler_panic_policy(HandlerPanicPolicy::Disconnect)
+        .build_svc();
+    io.ordered_ns("/", |socket: SocketRef| {
+        socket.on("panic", buggy_async_handler);
+    });
+
+    let (stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await); // NS connect packet
+    assert_ok!(stx.try_send(Message("2[\"panic\"]".into())));
+    assert_eq!(
+        assert_some!(try_timeout_rcv(&mut srx, 50).await),
+        Message("1".into())
+    );
+}
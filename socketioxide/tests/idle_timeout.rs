@@ -0,0 +1,50 @@
+//! Tests for the idle sockets reaper
+mod utils;
+
+use std::time::Duration;
+
+use engineioxide::Packet::*;
+use socketioxide::{extract::SocketRef, socket::DisconnectReason, SocketIo};
+use tokio::sync::mpsc;
+
+#[tokio::test]
+pub async fn idle_socket_reaped_active_survives() {
+    let (_svc, io) = SocketIo::builder()
+        .idle_timeout(Duration::from_millis(60))
+        .build_svc();
+    let (tx, mut rx) = mpsc::channel::<DisconnectReason>(2);
+    io.ns("/", move |socket: SocketRef| {
+        let tx = tx.clone();
+        socket.on_disconnect(move |reason: DisconnectReason| tx.try_send(reason).unwrap());
+        socket.on("ping", || {});
+    });
+
+    let (_idle_stx, mut idle_srx) = io.new_dummy_sock("/", ()).await;
+    let (active_stx, mut active_srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(idle_srx.recv().await); // NS connect packet
+    assert_some!(active_srx.recv().await); // NS connect packet
+
+    for _ in 0..6 {
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert_ok!(active_stx.try_send(Message("2[\"ping\"]".into())));
+    }
+
+    let reason = tokio::time::timeout(Duration::from_millis(10), rx.recv()).await;
+    assert_eq!(
+        assert_some!(assert_ok!(reason)),
+        DisconnectReason::IdleTimeout
+    );
+    assert_eq!(assert_some!(idle_srx.recv().await), Message("1".into()));
+
+    // The active socket is still connected
+    assert!(rx.try_recv().is_err());
+    assert_eq!(io.sockets().unwrap().len(), 1);
+
+    // And it is reaped once idle
+    let reason = tokio::time::timeout(Duration::from_millis(100), rx.recv()).await;
+    assert_eq!(
+        assert_some!(assert_ok!(reason)),
+        DisconnectReason::IdleTimeout
+    );
+    assert!(io.sockets().unwrap().is_empty());
+}
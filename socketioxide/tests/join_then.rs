@@ -0,0 +1,50 @@
+//! Tests for the broadcasts made right after joining a room
+mod utils;
+
+use std::time::Duration;
+
+use engineioxide::Packet::*;
+use socketioxide::{extract::SocketRef, SocketIo};
+
+#[tokio::test]
+pub async fn join_then_reaches_the_joining_socket() {
+    const SOCKETS: usize = 20;
+    let (_, io) = SocketIo::new_svc();
+    io.ns("/", |socket: SocketRef| {
+        socket.on("join", |socket: SocketRef| {
+            socket
+                .join_then("room", |op| op.emit("joined", socket.id))
+                .unwrap()
+                .unwrap();
+        });
+    });
+
+    let mut socks = Vec::with_capacity(SOCKETS);
+    for _ in 0..SOCKETS {
+        let (stx, mut srx) = io.new_dummy_sock("/", ()).await;
+        let connect = match assert_some!(srx.recv().await) {
+            Message(msg) => msg.to_string(),
+            p => panic!("unexpected packet {p:?}"),
+        };
+        // The connect packet is `0{"sid":"<sid>"}`
+        let sid = connect[9..connect.len() - 2].to_string();
+        socks.push((sid, stx, srx));
+    }
+
+    // All the sockets join the room concurrently
+    for (_, stx, _) in &socks {
+        assert_ok!(stx.try_send(Message("2[\"join\"]".into())));
+    }
+
+    for (sid, _, srx) in &mut socks {
+        let mut announced = Vec::new();
+        while let Ok(Some(Message(msg))) =
+            tokio::time::timeout(Duration::from_millis(20), srx.recv()).await
+        {
+            announced.push(msg.to_string());
+        }
+        // Each socket receives its own announcement, whatever the order of the joins
+        let own = format!("2[\"joined\",\"{sid}\"]");
+        assert!(announced.contains(&own), "{own} not in {announced:?}");
+    }
+}
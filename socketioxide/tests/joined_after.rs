@@ -0,0 +1,63 @@
+//! Tests for the broadcasts to the sockets that joined a room after an instant
+mod utils;
+
+use std::time::{Duration, Instant};
+
+use engineioxide::Packet::*;
+use serde_json::json;
+use socketioxide::{
+    extract::{Data, SocketRef},
+    SocketIo,
+};
+use tokio::sync::mpsc;
+
+async fn recv_all(srx: &mut mpsc::Receiver<engineioxide::Packet>) -> Vec<engineioxide::Packet> {
+    let mut packets = Vec::new();
+    while let Ok(Some(packet)) = tokio::time::timeout(Duration::from_millis(20), srx.recv()).await {
+        packets.push(packet);
+    }
+    packets
+}
+
+#[tokio::test]
+pub async fn joined_after_only_reaches_recent_members() {
+    let (_, io) = SocketIo::new_svc();
+    io.ns("/", |socket: SocketRef, Data(rooms): Data<Vec<String>>| {
+        socket.join(rooms).unwrap();
+    });
+
+    let (_stx1, mut srx1) = io.new_dummy_sock("/", json!(["room"])).await;
+    assert_some!(srx1.recv().await);
+    let s1 = io.within("room").sockets().unwrap().remove(0);
+    tokio::time::sleep(Duration::from_millis(5)).await;
+    let cutoff = Instant::now();
+    tokio::time::sleep(Duration::from_millis(5)).await;
+    let (_stx2, mut srx2) = io.new_dummy_sock("/", json!(["room"])).await;
+    let (_stx3, mut srx3) = io.new_dummy_sock("/", json!(["other"])).await;
+    assert_some!(srx2.recv().await);
+    assert_some!(srx3.recv().await);
+
+    // The first socket joins another room after the cutoff, it doesn't count for "room"
+    s1.join("late").unwrap();
+    io.emit("msg", ()).unwrap();
+    for srx in [&mut srx1, &mut srx2, &mut srx3] {
+        assert_eq!(recv_all(srx).await.len(), 1);
+    }
+
+    io.to("room").joined_after(cutoff).emit("news", ()).unwrap();
+    assert_eq!(recv_all(&mut srx1).await, []);
+    assert_eq!(
+        recv_all(&mut srx2).await,
+        [Message("2[\"news\",null]".into())]
+    );
+    assert_eq!(recv_all(&mut srx3).await, []);
+
+    // Without a room, any room joined after the cutoff counts
+    io.local().joined_after(cutoff).emit("news", ()).unwrap();
+    for srx in [&mut srx1, &mut srx2, &mut srx3] {
+        assert_eq!(recv_all(srx).await.len(), 1);
+    }
+
+    io.to("room").emit("news", ()).unwrap();
+    assert_eq!(recv_all(&mut srx1).await.len(), 1);
+}
@@ -0,0 +1,123 @@
+//! Tests for the jwt extractor
+mod utils;
+
+use std::convert::Infallible;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use engineioxide::Packet::*;
+use jsonwebtoken::{DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use socketioxide::{
+    extract::{Jwt, JwtKey, SocketRef},
+    handler::ConnectHandler,
+    SocketIo,
+};
+
+const SECRET: &[u8] = b"secret";
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Claims {
+    sub: String,
+    exp: u64,
+}
+
+fn create_token(exp: SystemTime, secret: &[u8]) -> String {
+    let claims = Claims {
+        sub: "user1".into(),
+        exp: exp.duration_since(UNIX_EPOCH).unwrap().as_secs(),
+    };
+    let key = EncodingKey::from_secret(secret);
+    jsonwebtoken::encode(&Header::default(), &claims, &key).unwrap()
+}
+
+fn middleware(Jwt(_): Jwt<Claims>) -> Result<(), Infallible> {
+    Ok(())
+}
+fn handler(socket: SocketRef, Jwt(claims): Jwt<Claims>) {
+    socket.emit("claims", claims.sub).unwrap();
+}
+
+fn create_io() -> SocketIo {
+    let key = JwtKey::new(DecodingKey::from_secret(SECRET), Validation::default());
+    let (_svc, io) = SocketIo::builder().with_state(key).build_svc();
+    io.ns("/", handler.with(middleware));
+    io
+}
+
+#[tokio::test]
+pub async fn jwt_valid_token() {
+    let io = create_io();
+    let token = create_token(SystemTime::now() + Duration::from_secs(3600), SECRET);
+
+    let (_stx, mut srx) = io.new_dummy_sock("/", json!({ "token": token })).await;
+    let msg = assert_some!(srx.recv().await);
+    assert!(matches!(msg, Message(msg) if msg.starts_with("0")));
+    let msg = assert_some!(srx.recv().await);
+    assert_eq!(msg, Message("2[\"claims\",\"user1\"]".into()));
+}
+
+#[tokio::test]
+pub async fn jwt_expired_token() {
+    let io = create_io();
+    let token = create_token(SystemTime::now() - Duration::from_secs(3600), SECRET);
+
+    let (_stx, mut srx) = io.new_dummy_sock("/", json!({ "token": token })).await;
+    let msg = assert_some!(srx.recv().await);
+    assert_eq!(msg, Message("4{\"message\":\"expired jwt token\"}".into()));
+}
+
+#[tokio::test]
+pub async fn jwt_tampered_token() {
+    let io = create_io();
+    let token = create_token(SystemTime::now() + Duration::from_secs(3600), b"other");
+
+    let (_stx, mut srx) = io.new_dummy_sock("/", json!({ "token": token })).await;
+    let msg = assert_some!(srx.recv().await);
+    assert_eq!(
+        msg,
+        Message("4{\"message\":\"invalid jwt signature\"}".into())
+    );
+
+    let (_stx, mut srx) = io.new_dummy_sock("/", json!({ "token": "foo" })).await;
+    let msg = assert_some!(srx.recv().await);
+    assert!(matches!(msg, Message(msg) if msg.starts_with("4{\"message\":\"invalid jwt token")));
+
+    let (_stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    let msg = assert_some!(srx.recv().await);
+    assert_eq!(msg, Message("4{\"message\":\"missing jwt token\"}".into()));
+}
+
+#[tokio::test]
+pub async fn jwt_session_cache() {
+    let key =
+        JwtKey::new(DecodingKey::from_secret(SECRET), Validation::default()).with_session_cache();
+    let (_svc, io) = SocketIo::builder().with_state(key).build_svc();
+    io.ns("/", handler.with(middleware));
+    io.ns("/admin", handler.with(middleware));
+    let token = create_token(SystemTime::now() + Duration::from_secs(3600), SECRET);
+
+    let (stx, mut srx) = io.new_dummy_sock("/", json!({ "token": token })).await;
+    let msg = assert_some!(srx.recv().await);
+    assert!(matches!(msg, Message(msg) if msg.starts_with("0")));
+    let msg = assert_some!(srx.recv().await);
+    assert_eq!(msg, Message("2[\"claims\",\"user1\"]".into()));
+
+    // The cached claims are reused on the same session
+    let connect = format!("0/admin,{}", json!({ "token": token }));
+    assert_ok!(stx.try_send(Message(connect.into())));
+    let msg = assert_some!(srx.recv().await);
+    assert!(matches!(msg, Message(msg) if msg.starts_with("0/admin,")));
+    let msg = assert_some!(srx.recv().await);
+    assert_eq!(msg, Message("2/admin,[\"claims\",\"user1\"]".into()));
+
+    // Tokens are still verified
+    let token = create_token(SystemTime::now() + Duration::from_secs(3600), b"other");
+    let connect = format!("0/admin,{}", json!({ "token": token }));
+    assert_ok!(stx.try_send(Message(connect.into())));
+    let msg = assert_some!(srx.recv().await);
+    assert_eq!(
+        msg,
+        Message("4/admin,{\"message\":\"invalid jwt signature\"}".into())
+    );
+}
@@ -0,0 +1,171 @@
+//! Tests for the lifecycle event sink
+mod utils;
+
+use engineioxide::{Packet::*, Str};
+use socketioxide::{
+    adapter::Room,
+    extract::SocketRef,
+    lifecycle::{DropEvent, DropReason, LifecycleEvent},
+    packet::Packet,
+    socket::DisconnectReason,
+    SocketIo,
+};
+use tokio::sync::mpsc;
+
+use utils::timeout_rcv;
+
+/// Skips the events until the next `Leave` event and returns its sorted rooms
+async fn next_leave(rx: &mut mpsc::Receiver<LifecycleEvent>) -> Vec<Room> {
+    loop {
+        match timeout_rcv(rx).await {
+            LifecycleEvent::Leave { mut rooms, .. } => {
+                rooms.sort();
+                return rooms;
+            }
+            LifecycleEvent::Disconnect { .. } => panic!("disconnected before leaving the rooms"),
+            _ => continue,
+        }
+    }
+}
+
+#[tokio::test]
+pub async fn lifecycle_events() {
+    let (_svc, io) = SocketIo::new_svc();
+    let (tx, mut rx) = mpsc::channel::<LifecycleEvent>(100);
+    io.on_event(move |event| tx.try_send(event).unwrap());
+
+    io.ns("/", |socket: SocketRef| {
+        socket.on("test", |socket: SocketRef| {
+            socket.join("room1").unwrap();
+            socket.to("room1").emit("broadcast", ()).unwrap();
+            socket.emit("test", ()).unwrap();
+            socket.leave("room1").unwrap();
+        });
+    });
+
+    let (stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    let sid = match timeout_rcv(&mut rx).await {
+        LifecycleEvent::Connect { ns, sid } if ns == "/" => sid,
+        event => panic!("unexpected event: {event:?}"),
+    };
+    assert_some!(srx.recv().await); // NS connect packet
+
+    let msg: String = Packet::event("/", "test", serde_json::Value::Null).into();
+    assert_ok!(stx.try_send(Message(msg.into())));
+
+    let ns = Str::from("/");
+    assert_eq!(
+        timeout_rcv(&mut rx).await,
+        LifecycleEvent::Join {
+            ns: ns.clone(),
+            sid,
+            rooms: vec!["room1".into()]
+        }
+    );
+    // The broadcast is not sent to the sender so the only emit is the socket-level one
+    assert_eq!(
+        timeout_rcv(&mut rx).await,
+        LifecycleEvent::Emit {
+            ns: ns.clone(),
+            sid,
+            event: "test".into()
+        }
+    );
+    assert_eq!(
+        timeout_rcv(&mut rx).await,
+        LifecycleEvent::Leave {
+            ns: ns.clone(),
+            sid,
+            rooms: vec!["room1".into()]
+        }
+    );
+
+    // Without a namespace, the error is reported with the engine.io session id
+    let session_id = assert_some!(io.get_socket(sid)).session_id();
+    assert_ok!(stx.try_send(Message("invalid".into())));
+    assert!(matches!(
+        timeout_rcv(&mut rx).await,
+        LifecycleEvent::Error { ns: None, sid: s, .. } if s == session_id
+    ));
+
+    assert_ok!(stx.try_send(Close));
+    assert_eq!(
+        timeout_rcv(&mut rx).await,
+        LifecycleEvent::Disconnect {
+            ns,
+            sid,
+            reason: DisconnectReason::TransportClose
+        }
+    );
+    assert_err!(rx.try_recv());
+}
+
+#[tokio::test]
+pub async fn leave_all_and_disconnect_leave_events() {
+    let (_svc, io) = SocketIo::new_svc();
+    let (tx, mut rx) = mpsc::channel::<LifecycleEvent>(100);
+    io.on_event(move |event| tx.try_send(event).unwrap());
+
+    io.ns("/", |socket: SocketRef| {
+        socket.join(["room1", "room2"]).unwrap();
+        socket.on("leave_all", |socket: SocketRef| {
+            socket.leave_all().unwrap();
+            socket.join("room3").unwrap();
+        });
+    });
+
+    let (stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await); // NS connect packet
+
+    let msg: String = Packet::event("/", "leave_all", serde_json::Value::Null).into();
+    assert_ok!(stx.try_send(Message(msg.into())));
+    assert_eq!(next_leave(&mut rx).await, ["room1", "room2"]);
+
+    // The rooms are left when the socket disconnects
+    assert_ok!(stx.try_send(Close));
+    assert_eq!(next_leave(&mut rx).await, ["room3"]);
+    assert!(matches!(
+        timeout_rcv(&mut rx).await,
+        LifecycleEvent::Disconnect { .. }
+    ));
+}
+
+#[tokio::test]
+pub async fn dropped_packets() {
+    const BUFFER_SIZE: usize = 2;
+    let (_svc, io) = SocketIo::builder().max_buffer_size(BUFFER_SIZE).build_svc();
+    let (tx, mut rx) = mpsc::channel::<DropEvent>(100);
+    io.on_drop(move |event| tx.try_send(event).unwrap());
+    io.ns("/", || {});
+
+    let (stx, _srx) = io.new_dummy_sock("/", ()).await;
+    let socket = io.sockets().unwrap().pop().unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+
+    // The client is not reading, the packet buffer is filled until packets are dropped
+    // (the dummy socket pipes the packet buffer to another channel of the same size)
+    let dropped = (0..10)
+        .filter(|_| io.to_sockets([socket.id]).emit("msg", ()).is_err())
+        .count();
+    assert!(dropped > 0);
+    for _ in 0..dropped {
+        let event = timeout_rcv(&mut rx).await;
+        assert_eq!(
+            event,
+            DropEvent {
+                ns: Str::from("/"),
+                sid: socket.id,
+                reason: DropReason::BufferFull,
+                event: Some("msg".into()),
+            }
+        );
+    }
+
+    assert_ok!(stx.try_send(Close));
+    tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    assert!(socket.emit("closed", ()).is_err());
+    let event = timeout_rcv(&mut rx).await;
+    assert_eq!(event.reason, DropReason::SocketClosed);
+    assert_eq!(event.event.as_deref(), Some("closed"));
+    assert!(rx.try_recv().is_err());
+}
@@ -0,0 +1,49 @@
+//! Tests for the socket liveness checks
+mod utils;
+
+use std::time::Duration;
+
+use engineioxide::Packet::*;
+use socketioxide::{extract::SocketRef, SocketIo};
+
+async fn wait_disconnected(socket: &SocketRef) {
+    let res = tokio::time::timeout(Duration::from_millis(100), async {
+        while socket.is_connected() {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+    })
+    .await;
+    assert_ok!(res);
+}
+
+#[tokio::test]
+pub async fn is_connected_after_transport_close() {
+    let (_svc, io) = SocketIo::new_svc();
+    io.ns("/", || {});
+
+    let (stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await); // NS connect packet
+    let socket = io.sockets().unwrap().pop().unwrap();
+    assert!(socket.is_connected());
+    assert!(socket.is_namespace_connected());
+
+    assert_ok!(stx.try_send(Close));
+    wait_disconnected(&socket).await;
+    assert!(!socket.is_namespace_connected());
+}
+
+#[tokio::test]
+pub async fn is_connected_after_ns_disconnect() {
+    let (_svc, io) = SocketIo::new_svc();
+    io.ns("/", || {});
+
+    let (stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await); // NS connect packet
+    let socket = io.sockets().unwrap().pop().unwrap();
+    assert!(socket.is_connected());
+
+    // Client namespace disconnect packet
+    assert_ok!(stx.try_send(Message("1".into())));
+    wait_disconnected(&socket).await;
+    assert!(!socket.is_namespace_connected());
+}
@@ -0,0 +1,42 @@
+//! Tests for the binary attachments limit
+mod utils;
+
+use bytes::Bytes;
+use engineioxide::Packet::*;
+use socketioxide::{extract::Bin, extract::SocketRef, SocketIo};
+use tokio::sync::mpsc;
+
+use utils::try_timeout_rcv;
+
+#[tokio::test]
+pub async fn over_declared_attachments_closes_connection() {
+    let (_svc, io) = SocketIo::builder().max_attachments(2).build_svc();
+    io.ns("/", || {});
+
+    let (stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await); // NS connect packet
+
+    let msg = "51000000-[\"test\",{\"_placeholder\":true,\"num\":0}]";
+    assert_ok!(stx.try_send(Message(msg.into())));
+    assert_eq!(assert_some!(try_timeout_rcv(&mut srx, 20).await), Close);
+}
+
+#[tokio::test]
+pub async fn attachments_within_limit() {
+    let (_svc, io) = SocketIo::builder().max_attachments(2).build_svc();
+    let (tx, mut rx) = mpsc::channel::<Vec<Bytes>>(1);
+    io.ns("/", move |socket: SocketRef| {
+        let tx = tx.clone();
+        socket.on("test", move |Bin(bin)| tx.try_send(bin).unwrap());
+    });
+
+    let (stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await); // NS connect packet
+
+    let msg = "52-[\"test\",{\"_placeholder\":true,\"num\":0},{\"_placeholder\":true,\"num\":1}]";
+    assert_ok!(stx.try_send(Message(msg.into())));
+    assert_ok!(stx.try_send(Binary(Bytes::from_static(&[1]))));
+    assert_ok!(stx.try_send(Binary(Bytes::from_static(&[2]))));
+    let bin = assert_some!(try_timeout_rcv(&mut rx, 20).await);
+    assert_eq!(bin, [Bytes::from_static(&[1]), Bytes::from_static(&[2])]);
+}
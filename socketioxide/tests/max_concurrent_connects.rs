@@ -0,0 +1,59 @@
+//! Tests for the per-namespace connect concurrency limit
+mod utils;
+
+use std::time::Duration;
+
+use engineioxide::Packet::*;
+use socketioxide::{handler::ConnectHandler, SocketIo};
+use tokio::time::Instant;
+
+async fn slow_auth() -> Result<(), std::convert::Infallible> {
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    Ok(())
+}
+
+#[tokio::test]
+pub async fn excess_connect_waits() {
+    let (_, io) = SocketIo::new_svc();
+    io.ns("/", { || {} }.with(slow_auth));
+    assert!(io.max_concurrent_connects("/", 1, Duration::from_secs(1)));
+
+    let start = Instant::now();
+    let (_stx1, mut srx1) = io.new_dummy_sock("/", ()).await;
+    let (_stx2, mut srx2) = io.new_dummy_sock("/", ()).await;
+
+    let p = assert_some!(srx1.recv().await);
+    assert!(matches!(p, Message(s) if s.starts_with('0')));
+    assert!(start.elapsed() < Duration::from_millis(190));
+
+    // The second connect only starts its middleware once the first one completed
+    let p = assert_some!(srx2.recv().await);
+    assert!(matches!(p, Message(s) if s.starts_with('0')));
+    assert!(start.elapsed() >= Duration::from_millis(200));
+    assert_eq!(io.sockets().unwrap().len(), 2);
+}
+
+#[tokio::test]
+pub async fn excess_connect_rejected_after_timeout() {
+    let (_, io) = SocketIo::new_svc();
+    io.ns("/", { || {} }.with(slow_auth));
+    assert!(io.max_concurrent_connects("/", 1, Duration::from_millis(20)));
+
+    let (_stx1, mut srx1) = io.new_dummy_sock("/", ()).await;
+    let (_stx2, mut srx2) = io.new_dummy_sock("/", ()).await;
+
+    let p = assert_some!(srx2.recv().await);
+    assert_eq!(
+        p,
+        Message("4{\"message\":\"too many concurrent connections\"}".into())
+    );
+    let p = assert_some!(srx1.recv().await);
+    assert!(matches!(p, Message(s) if s.starts_with('0')));
+}
+
+#[tokio::test]
+pub async fn unknown_namespace() {
+    let (_, io) = SocketIo::new_svc();
+    io.ns("/", || {});
+    assert!(!io.max_concurrent_connects("/admin", 1, Duration::from_secs(1)));
+}
@@ -0,0 +1,35 @@
+//! Tests for the max connections limit
+mod fixture;
+mod utils;
+
+use std::time::Duration;
+
+use fixture::{create_ws_connection, spawn_server};
+use socketioxide::SocketIo;
+use tokio_tungstenite::tungstenite::{http::StatusCode, Error};
+
+#[tokio::test]
+pub async fn handshake_rejected_over_capacity() {
+    const PORT: u16 = 12370;
+    let (svc, io) = SocketIo::builder().max_connections(2).build_svc();
+    io.ns("/", || {});
+    spawn_server(PORT, svc).await;
+
+    let mut ws1 = create_ws_connection(PORT).await;
+    let _ws2 = create_ws_connection(PORT).await;
+
+    let url = format!("ws://127.0.0.1:{PORT}/socket.io/?EIO=4&transport=websocket");
+    let res = tokio_tungstenite::connect_async(&url).await;
+    match res {
+        Err(Error::Http(res)) => {
+            assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+            assert!(res.headers().contains_key("Retry-After"));
+        }
+        res => panic!("expected a 503 response, got {res:?}"),
+    }
+
+    // The connection count decrements on disconnect
+    assert_ok!(ws1.close(None).await);
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_ok!(tokio_tungstenite::connect_async(&url).await);
+}
@@ -0,0 +1,39 @@
+//! Tests for the max websocket frame size
+mod fixture;
+mod utils;
+
+use std::time::Duration;
+
+use fixture::{create_ws_connection, spawn_server};
+use futures_util::{SinkExt, StreamExt};
+use socketioxide::SocketIo;
+use tokio_tungstenite::tungstenite::{protocol::frame::coding::CloseCode, Message};
+
+#[tokio::test]
+pub async fn oversized_frame_closes_connection() {
+    const PORT: u16 = 12371;
+    let (svc, io) = SocketIo::builder().max_frame_size(1024).build_svc();
+    io.ns("/", || {});
+    spawn_server(PORT, svc).await;
+
+    let mut ws = create_ws_connection(PORT).await;
+    // A frame under the limit is accepted
+    let msg = format!("42[\"msg\",\"{}\"]", "a".repeat(512));
+    assert_ok!(ws.send(Message::Text(msg)).await);
+    let msg = format!("42[\"msg\",\"{}\"]", "a".repeat(2048));
+    assert_ok!(ws.send(Message::Text(msg)).await);
+
+    let close = tokio::time::timeout(Duration::from_millis(500), async {
+        while let Some(msg) = ws.next().await {
+            if let Message::Close(frame) = assert_ok!(msg) {
+                return frame;
+            }
+        }
+        None
+    })
+    .await;
+    let frame = assert_some!(assert_ok!(close));
+    assert_eq!(frame.code, CloseCode::Protocol);
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    assert!(io.sockets().unwrap().is_empty());
+}
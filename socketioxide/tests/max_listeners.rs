@@ -0,0 +1,75 @@
+//! Tests for the max listeners policies
+mod utils;
+
+use engineioxide::Packet::*;
+use socketioxide::{
+    extract::{AckSender, SocketRef},
+    lifecycle::LifecycleEvent,
+    MaxListenersPolicy, SocketIo,
+};
+use tokio::sync::mpsc;
+
+use utils::try_timeout_rcv;
+
+async fn create_socket(
+    policy: MaxListenersPolicy,
+) -> (
+    mpsc::Sender<engineioxide::Packet>,
+    mpsc::Receiver<engineioxide::Packet>,
+    mpsc::Receiver<String>,
+) {
+    let (_svc, io) = SocketIo::builder().max_listeners(2, policy).build_svc();
+    let (tx, rx) = mpsc::channel::<String>(10);
+    io.on_event(move |event| {
+        if let LifecycleEvent::Error { message, .. } = event {
+            tx.try_send(message).unwrap();
+        }
+    });
+    io.ns("/", |socket: SocketRef| {
+        for event in ["a", "b", "c"] {
+            socket.on(event, move |ack: AckSender| ack.send(event).unwrap());
+        }
+        // Replacing an existing handler is always allowed
+        socket.on("a", |ack: AckSender| ack.send("a2").unwrap());
+    });
+    let (stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await); // NS connect packet
+    (stx, srx, rx)
+}
+
+#[tokio::test]
+pub async fn max_listeners_log() {
+    let (stx, mut srx, mut rx) = create_socket(MaxListenersPolicy::Log).await;
+    assert!(try_timeout_rcv(&mut rx, 20).await.is_none());
+
+    assert_ok!(stx.try_send(Message("21[\"a\"]".into())));
+    let msg = assert_some!(try_timeout_rcv(&mut srx, 20).await);
+    assert_eq!(msg, Message("31[\"a2\"]".into()));
+
+    assert_ok!(stx.try_send(Message("22[\"c\"]".into())));
+    let msg = assert_some!(try_timeout_rcv(&mut srx, 20).await);
+    assert_eq!(msg, Message("32[\"c\"]".into()));
+}
+
+#[tokio::test]
+pub async fn max_listeners_reject() {
+    let (stx, mut srx, mut rx) = create_socket(MaxListenersPolicy::Reject).await;
+    let err = assert_some!(try_timeout_rcv(&mut rx, 20).await);
+    assert_eq!(
+        err,
+        "max listeners (2) exceeded, the c handler was not registered"
+    );
+    assert!(try_timeout_rcv(&mut rx, 20).await.is_none());
+
+    assert_ok!(stx.try_send(Message("21[\"a\"]".into())));
+    let msg = assert_some!(try_timeout_rcv(&mut srx, 20).await);
+    assert_eq!(msg, Message("31[\"a2\"]".into()));
+
+    assert_ok!(stx.try_send(Message("22[\"b\"]".into())));
+    let msg = assert_some!(try_timeout_rcv(&mut srx, 20).await);
+    assert_eq!(msg, Message("32[\"b\"]".into()));
+
+    // The rejected handler is not called
+    assert_ok!(stx.try_send(Message("23[\"c\"]".into())));
+    assert!(try_timeout_rcv(&mut srx, 20).await.is_none());
+}
@@ -0,0 +1,66 @@
+//! Tests for the max pending acknowledgements per socket limit
+mod utils;
+
+use std::time::Duration;
+
+use engineioxide::Packet::*;
+use serde_json::Value;
+use socketioxide::{AckError, SocketIo};
+
+#[tokio::test]
+pub async fn max_pending_acks() {
+    let (_svc, io) = SocketIo::builder().max_pending_acks(2).build_svc();
+    io.ns("/", || {});
+    let (stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await); // NS connect packet
+    let socket = io.sockets().unwrap().pop().unwrap();
+
+    let ack1 = socket.emit_with_ack::<_, Value>("test", 1).unwrap();
+    let ack2 = socket.emit_with_ack::<_, Value>("test", 2).unwrap();
+    assert_eq!(
+        assert_some!(srx.recv().await),
+        Message("21[\"test\",1]".into())
+    );
+    assert_eq!(
+        assert_some!(srx.recv().await),
+        Message("22[\"test\",2]".into())
+    );
+
+    // The third ack exceeds the limit and is not sent
+    let ack3 = socket.emit_with_ack::<_, Value>("test", 3).unwrap();
+    assert!(matches!(ack3.await, Err(AckError::TooManyPending)));
+    let res = tokio::time::timeout(Duration::from_millis(20), srx.recv()).await;
+    assert!(res.is_err());
+
+    // Answering an ack frees up a slot
+    assert_ok!(stx.try_send(Message("31[\"ok\"]".into())));
+    assert_ok!(ack1.await);
+    let _ack4 = socket.emit_with_ack::<_, Value>("test", 4).unwrap();
+    assert_eq!(
+        assert_some!(srx.recv().await),
+        Message("23[\"test\",4]".into())
+    );
+
+    // And so does dropping a pending ack stream
+    drop(ack2);
+    let _ack5 = socket.emit_with_ack::<_, Value>("test", 5).unwrap();
+    assert_eq!(
+        assert_some!(srx.recv().await),
+        Message("24[\"test\",5]".into())
+    );
+    let ack6 = socket.emit_with_ack::<_, Value>("test", 6).unwrap();
+    assert!(matches!(ack6.await, Err(AckError::TooManyPending)));
+}
+
+#[tokio::test]
+pub async fn max_pending_acks_broadcast() {
+    let (_svc, io) = SocketIo::builder().max_pending_acks(1).build_svc();
+    io.ns("/", || {});
+    let (_stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await); // NS connect packet
+
+    let _ack1 = io.emit_with_ack::<Value>("test", ()).unwrap();
+    assert_some!(srx.recv().await);
+    let ack2 = io.emit_with_ack::<Value>("test", ()).unwrap();
+    assert!(matches!(ack2.await, Err(AckError::TooManyPending)));
+}
@@ -0,0 +1,59 @@
+//! Tests for the max rooms per socket limit
+mod utils;
+
+use std::time::Duration;
+
+use socketioxide::{extract::SocketRef, JoinError, SocketIo};
+use tokio::sync::mpsc;
+
+#[tokio::test]
+pub async fn max_rooms_per_socket() {
+    let (_svc, io) = SocketIo::builder().max_rooms_per_socket(2).build_svc();
+    let (tx, mut rx) = mpsc::channel::<()>(1);
+    io.ns("/", move |socket: SocketRef| {
+        // The room named after the socket id is not counted
+        assert_ok!(socket.join(socket.id.to_string()));
+        assert_ok!(socket.join(["room1", "room2"]));
+        // Joining an already joined room doesn't count twice
+        assert_ok!(socket.join("room1"));
+
+        assert!(matches!(
+            socket.join(["room1", "room3"]),
+            Err(JoinError::TooManyRooms(2))
+        ));
+        assert_eq!(socket.rooms().unwrap().len(), 3);
+
+        // Leaving a room frees up a slot
+        assert_ok!(socket.leave("room2"));
+        assert_ok!(socket.join("room3"));
+        tx.try_send(()).unwrap();
+    });
+
+    let (_stx, _srx) = io.new_dummy_sock("/", ()).await;
+    tokio::time::timeout(Duration::from_millis(20), rx.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(io.within("room3").sockets().unwrap().len(), 1);
+}
+
+#[tokio::test]
+pub async fn max_rooms_per_socket_operator_join() {
+    let (_svc, io) = SocketIo::builder().max_rooms_per_socket(2).build_svc();
+    io.ns("/", |socket: SocketRef| {
+        socket.join("lobby").unwrap();
+    });
+    let (_stx1, _srx1) = io.new_dummy_sock("/", ()).await;
+    let (_stx2, _srx2) = io.new_dummy_sock("/", ()).await;
+
+    assert_ok!(io.within("lobby").join("room1"));
+    let errors = io.within("lobby").join("room2").unwrap_err();
+    assert_eq!(errors.len(), 2);
+    assert!(errors
+        .iter()
+        .all(|e| matches!(e, JoinError::TooManyRooms(2))));
+    assert!(io.within("room2").sockets().unwrap().is_empty());
+    for socket in io.sockets().unwrap() {
+        assert_eq!(socket.rooms().unwrap().len(), 2);
+    }
+}
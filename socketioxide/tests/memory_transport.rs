@@ -0,0 +1,154 @@
+//! Tests for the in-memory transport
+mod utils;
+
+use std::{pin::Pin, time::Duration};
+
+use engineioxide::{memory::MemoryTransport, Packet, Packet::*, Transport};
+use futures_util::{Sink, Stream};
+use socketioxide::{
+    extract::{Data, SocketRef},
+    socket::DisconnectReason,
+    SendError, SocketError, SocketIo,
+};
+use tokio::sync::mpsc;
+
+async fn timeout_rcv(client: &mut engineioxide::memory::MemoryClient) -> engineioxide::Packet {
+    let packet = tokio::time::timeout(Duration::from_millis(20), client.recv()).await;
+    assert_some!(assert_ok!(packet))
+}
+
+#[tokio::test]
+pub async fn memory_transport_exchange_events() {
+    let (svc, io) = SocketIo::new_svc();
+    io.ns("/", |socket: SocketRef| {
+        socket.on("echo", |socket: SocketRef, Data::<String>(data)| {
+            socket.emit("echo", data).unwrap();
+        });
+    });
+
+    let (transport, mut client) = MemoryTransport::pair(16);
+    svc.connect(transport, http::Request::new(()));
+    assert!(matches!(timeout_rcv(&mut client).await, Open(_)));
+
+    assert_ok!(client.send(Message("0".into())).await);
+    let connect = timeout_rcv(&mut client).await;
+    assert!(matches!(connect, Message(msg) if msg.starts_with("0{\"sid\"")));
+    assert_eq!(io.sockets().unwrap().len(), 1);
+
+    assert_ok!(client.send(Message("2[\"echo\",\"foo\"]".into())).await);
+    assert_eq!(
+        timeout_rcv(&mut client).await,
+        Message("2[\"echo\",\"foo\"]".into())
+    );
+
+    // The session is closed when the client sends a close packet
+    assert_ok!(client.send(Close).await);
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    assert!(io.sockets().unwrap().is_empty());
+}
+
+#[tokio::test]
+pub async fn memory_transport_heartbeat() {
+    let (svc, io) = SocketIo::builder()
+        .ping_interval(Duration::from_millis(10))
+        .ping_timeout(Duration::from_millis(10))
+        .build_svc();
+    io.ns("/", || {});
+
+    let (transport, mut client) = MemoryTransport::pair(16);
+    svc.connect(transport, http::Request::new(()));
+    assert!(matches!(timeout_rcv(&mut client).await, Open(_)));
+    assert_ok!(client.send(Message("0".into())).await);
+    timeout_rcv(&mut client).await; // NS connect packet
+
+    // The session stays open while the client answers the pings
+    for _ in 0..3 {
+        assert_eq!(timeout_rcv(&mut client).await, Ping);
+        assert_ok!(client.send(Pong).await);
+    }
+    assert_eq!(io.sockets().unwrap().len(), 1);
+
+    // Otherwise it is closed after the ping timeout
+    assert_eq!(timeout_rcv(&mut client).await, Ping);
+    tokio::time::sleep(Duration::from_millis(30)).await;
+    assert!(io.sockets().unwrap().is_empty());
+}
+
+#[tokio::test]
+pub async fn memory_transport_backpressure() {
+    let (svc, io) = SocketIo::builder().max_buffer_size(4).build_svc();
+    io.ns("/", || {});
+
+    let (transport, mut client) = MemoryTransport::pair(2);
+    svc.connect(transport, http::Request::new(()));
+    assert!(matches!(timeout_rcv(&mut client).await, Open(_)));
+    assert_ok!(client.send(Message("0".into())).await);
+    timeout_rcv(&mut client).await; // NS connect packet
+    let socket = io.sockets().unwrap().remove(0);
+
+    // The client doesn't receive its packets, so the socket buffer fills up
+    let mut sent = 0;
+    let res = loop {
+        let res = socket.emit("msg", sent);
+        if res.is_err() || sent == 100 {
+            break res;
+        }
+        sent += 1;
+        tokio::time::sleep(Duration::from_millis(1)).await;
+    };
+    assert!(matches!(
+        res,
+        Err(SendError::Socket(SocketError::InternalChannelFull(_)))
+    ));
+    assert!(sent < 10, "{sent}");
+
+    // The buffered packets are delivered in order once the client receives them
+    for i in 0..sent {
+        assert_eq!(
+            timeout_rcv(&mut client).await,
+            Message(format!("2[\"msg\",{i}]").into())
+        );
+    }
+    assert_ok!(socket.emit("msg", sent));
+}
+
+/// A transport whose sink fails after the open packet
+struct BrokenSinkTransport(mpsc::Receiver<Packet>);
+
+impl Transport for BrokenSinkTransport {
+    type Error = &'static str;
+    type Sink = Pin<Box<dyn Sink<Packet, Error = &'static str> + Send>>;
+    type Stream = Pin<Box<dyn Stream<Item = Packet> + Send>>;
+
+    fn split(self) -> (Self::Sink, Self::Stream) {
+        let sink = futures_util::sink::unfold(false, |opened, _: Packet| async move {
+            match opened {
+                false => Ok(true),
+                true => Err("broken sink"),
+            }
+        });
+        let stream = futures_util::stream::unfold(self.0, |mut rx| async move {
+            rx.recv().await.map(|packet| (packet, rx))
+        });
+        (Box::pin(sink), Box::pin(stream))
+    }
+}
+
+#[tokio::test]
+pub async fn memory_transport_sink_error_closes_session() {
+    let (svc, io) = SocketIo::new_svc();
+    let (tx, mut rx) = mpsc::channel::<DisconnectReason>(1);
+    io.ns("/", move |socket: SocketRef| {
+        let tx = tx.clone();
+        socket.on_disconnect(move |reason: DisconnectReason| tx.try_send(reason).unwrap());
+    });
+
+    let (client_tx, client_rx) = mpsc::channel(16);
+    assert_some!(svc.connect(BrokenSinkTransport(client_rx), http::Request::new(())));
+    // The connect packet of the namespace cannot be sent
+    assert_ok!(client_tx.send(Message("0".into())).await);
+
+    let reason = tokio::time::timeout(Duration::from_millis(50), rx.recv()).await;
+    assert_eq!(assert_ok!(reason), Some(DisconnectReason::TransportError));
+    assert!(io.sockets().unwrap().is_empty());
+}
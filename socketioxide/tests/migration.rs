@@ -0,0 +1,85 @@
+//! Tests for the handoff of the sockets to another node
+mod utils;
+
+use std::time::Duration;
+
+use engineioxide::Packet::*;
+use serde_json::json;
+use socketioxide::{extract::SocketRef, socket::DisconnectReason, AckError, SocketIo};
+use tokio::sync::mpsc;
+
+use utils::try_timeout_rcv;
+
+type MigrateResult = Result<(), AckError<()>>;
+
+fn setup() -> (
+    SocketIo,
+    mpsc::Receiver<MigrateResult>,
+    mpsc::Receiver<DisconnectReason>,
+) {
+    let (_svc, io) = SocketIo::builder()
+        .ack_timeout(Duration::from_millis(200))
+        .build_svc();
+    let (res_tx, res_rx) = mpsc::channel(1);
+    let (tx, rx) = mpsc::channel(1);
+    io.ns("/", move |socket: SocketRef| {
+        let res_tx = res_tx.clone();
+        let tx = tx.clone();
+        socket.on("drain", move |socket: SocketRef| async move {
+            let res = socket.request_reconnect(Some("node-b".into())).await;
+            res_tx.try_send(res).unwrap();
+        });
+        socket.on_disconnect(move |reason: DisconnectReason| tx.try_send(reason).unwrap());
+    });
+    (io, res_rx, rx)
+}
+
+#[tokio::test]
+pub async fn migrate_ack_then_disconnect() {
+    let (io, mut res_rx, mut rx) = setup();
+    let (stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await); // NS connect packet
+
+    assert_ok!(stx.try_send(Message("2[\"drain\"]".into())));
+    let msg = match assert_some!(try_timeout_rcv(&mut srx, 300).await) {
+        Message(msg) => msg,
+        packet => panic!("unexpected packet {packet:?}"),
+    };
+    let (ack_id, data) = msg.strip_prefix('2').unwrap().split_once('[').unwrap();
+    let data: serde_json::Value = serde_json::from_str(&format!("[{data}")).unwrap();
+    assert_eq!(data, json!(["server:migrate", { "target": "node-b" }]));
+
+    // The socket stays connected until the client acknowledges the migration
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    assert!(rx.try_recv().is_err());
+    assert_eq!(io.sockets().unwrap().len(), 1);
+
+    assert_ok!(stx.try_send(Message(format!("3{ack_id}[]").into())));
+    assert_eq!(
+        assert_some!(try_timeout_rcv(&mut srx, 300).await),
+        Message("1".into())
+    );
+    assert_eq!(
+        assert_some!(try_timeout_rcv(&mut rx, 300).await),
+        DisconnectReason::Migrating
+    );
+    assert_ok!(assert_some!(try_timeout_rcv(&mut res_rx, 300).await));
+    assert!(io.sockets().unwrap().is_empty());
+}
+
+#[tokio::test]
+pub async fn migrate_without_ack_keeps_socket() {
+    let (io, mut res_rx, mut rx) = setup();
+    let (stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await); // NS connect packet
+
+    assert_ok!(stx.try_send(Message("2[\"drain\"]".into())));
+    assert_some!(try_timeout_rcv(&mut srx, 300).await);
+    assert!(matches!(
+        assert_some!(try_timeout_rcv(&mut res_rx, 300).await),
+        Err(AckError::Timeout)
+    ));
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    assert!(rx.try_recv().is_err());
+    assert_eq!(io.sockets().unwrap().len(), 1);
+}
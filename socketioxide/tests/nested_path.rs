@@ -0,0 +1,85 @@
+//! Tests for a socket.io service nested under a sub-path of an axum router
+mod utils;
+
+use std::time::Duration;
+
+use futures_util::{SinkExt, StreamExt};
+use http_body_util::{BodyExt, Empty};
+use hyper_util::{client::legacy::Client, rt::TokioExecutor};
+use socketioxide::{extract::SocketRef, SocketIo};
+use tokio::net::TcpListener;
+use tokio_tungstenite::tungstenite::Message;
+
+async fn spawn_nested_server(port: u16) -> SocketIo {
+    let (svc, io) = SocketIo::new_svc();
+    io.ns("/", |socket: SocketRef| {
+        socket.on("ping", |socket: SocketRef| {
+            socket.emit("pong", "ok").ok();
+        });
+    });
+    let app = axum::Router::new().nest_service("/rt", svc);
+    let listener = TcpListener::bind(("127.0.0.1", port)).await.unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    io
+}
+
+#[tokio::test]
+pub async fn nested_ws_connection() {
+    const PORT: u16 = 12373;
+    let _io = spawn_nested_server(PORT).await;
+
+    let url = format!("ws://127.0.0.1:{PORT}/rt/socket.io/?EIO=4&transport=websocket");
+    let (mut ws, _) = assert_ok!(tokio_tungstenite::connect_async(url).await);
+    assert_ok!(ws.send(Message::Text("40{}".into())).await);
+    assert_ok!(ws.send(Message::Text("42[\"ping\"]".into())).await);
+
+    let msgs = tokio::time::timeout(Duration::from_millis(200), async {
+        let mut msgs = Vec::new();
+        while let Some(Ok(Message::Text(msg))) = ws.next().await {
+            msgs.push(msg);
+            if msgs.len() == 3 {
+                break;
+            }
+        }
+        msgs
+    })
+    .await;
+    let msgs = assert_ok!(msgs);
+    assert!(msgs[0].starts_with("0{"));
+    assert!(msgs[1].starts_with("40{"));
+    assert_eq!(msgs[2], "42[\"pong\",\"ok\"]");
+}
+
+#[tokio::test]
+pub async fn nested_polling_connection() {
+    const PORT: u16 = 12374;
+    let _io = spawn_nested_server(PORT).await;
+
+    let req = http::Request::builder()
+        .uri(format!(
+            "http://127.0.0.1:{PORT}/rt/socket.io/?EIO=4&transport=polling"
+        ))
+        .body(Empty::<bytes::Bytes>::new())
+        .unwrap();
+    let res = Client::builder(TokioExecutor::new())
+        .build_http()
+        .request(req)
+        .await;
+    let res = assert_ok!(res);
+    assert_eq!(res.status(), 200);
+    let body = assert_ok!(res.into_body().collect().await).to_bytes();
+    assert!(body.starts_with(b"0{\"sid\":"));
+
+    // The requests outside of the nested path are not handled by the engine
+    let req = http::Request::builder()
+        .uri(format!(
+            "http://127.0.0.1:{PORT}/socket.io/?EIO=4&transport=polling"
+        ))
+        .body(Empty::<bytes::Bytes>::new())
+        .unwrap();
+    let res = Client::builder(TokioExecutor::new())
+        .build_http()
+        .request(req)
+        .await;
+    assert_eq!(assert_ok!(res).status(), 404);
+}
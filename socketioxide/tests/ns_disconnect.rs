@@ -0,0 +1,67 @@
+//! Tests for the namespace disconnect handler
+mod fixture;
+mod utils;
+
+use std::time::Duration;
+
+use fixture::{create_server, create_ws_connection};
+use futures_util::{SinkExt, StreamExt};
+use socketioxide::{extract::SocketRef, socket::DisconnectReason};
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::Message;
+
+#[tokio::test]
+pub async fn client_leaves_one_namespace() {
+    const PORT: u16 = 12372;
+    let (tx, mut rx) = mpsc::channel::<(&'static str, &'static str, DisconnectReason)>(10);
+    let io = create_server(PORT).await;
+    for ns in ["/", "/admin"] {
+        let tx = tx.clone();
+        io.ns(ns, move |socket: SocketRef| {
+            let tx1 = tx.clone();
+            socket.on_ns_disconnect(move |reason: DisconnectReason| {
+                tx1.try_send((ns, "ns_disconnect", reason)).unwrap();
+            });
+            let tx = tx.clone();
+            socket.on_disconnect(move |reason: DisconnectReason| {
+                tx.try_send((ns, "disconnect", reason)).unwrap();
+            });
+        });
+    }
+
+    let mut ws = create_ws_connection(PORT).await;
+    assert_ok!(ws.send(Message::Text("40/admin,{}".into())).await);
+    // engine.io open packet, then the connect packets of both namespaces
+    for _ in 0..3 {
+        assert_ok!(assert_some!(ws.next().await));
+    }
+
+    assert_ok!(ws.send(Message::Text("41/admin,".into())).await);
+    let mut events = Vec::new();
+    while let Ok(Some(event)) = tokio::time::timeout(Duration::from_millis(20), rx.recv()).await {
+        events.push(event);
+    }
+    events.sort_by_key(|(ns, kind, _)| (*ns, *kind));
+    assert_eq!(
+        events,
+        [
+            ("/admin", "disconnect", DisconnectReason::ClientNSDisconnect),
+            (
+                "/admin",
+                "ns_disconnect",
+                DisconnectReason::ClientNSDisconnect
+            ),
+        ]
+    );
+    assert_eq!(io.of("/").unwrap().sockets().unwrap().len(), 1);
+
+    // Closing the connection only calls the disconnect handler
+    assert_ok!(ws.send(Message::Text("1".into())).await);
+    let event = tokio::time::timeout(Duration::from_millis(200), rx.recv()).await;
+    assert_eq!(
+        assert_some!(assert_ok!(event)),
+        ("/", "disconnect", DisconnectReason::TransportClose)
+    );
+    let event = tokio::time::timeout(Duration::from_millis(20), rx.recv()).await;
+    assert_err!(event);
+}
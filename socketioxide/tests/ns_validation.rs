@@ -0,0 +1,44 @@
+//! Tests for the rejection of malformed namespace paths in connect packets
+mod utils;
+
+use engineioxide::Packet::*;
+use socketioxide::SocketIo;
+
+fn invalid_ns(ns: &str) -> engineioxide::Packet {
+    Message(format!("4{ns},{{\"message\":\"Invalid namespace\"}}").into())
+}
+
+#[tokio::test]
+pub async fn malformed_ns_paths_are_rejected() {
+    let (_, io) = SocketIo::new_svc();
+    io.ns("/", || {});
+    // A catch-all dynamic namespace would accept any path that is not rejected beforehand
+    io.dyn_ns("/{*rest}", || {}).unwrap();
+
+    let (stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await);
+
+    let overlong = format!("/{}", "a".repeat(300));
+    for ns in [
+        overlong.as_str(),
+        "/ad min",
+        "/admin\u{1}",
+        "/admin?x=1",
+        "/a\\b",
+    ] {
+        assert_ok!(stx.try_send(Message(format!("0{ns},").into())));
+        assert_eq!(assert_some!(srx.recv().await), invalid_ns(ns));
+    }
+    assert!(io.of("/admin").is_none());
+
+    // The paths in the limits are still accepted
+    let longest = format!("/{}", "a".repeat(255));
+    for ns in [longest.as_str(), "/admin™", "/client/1"] {
+        assert_ok!(stx.try_send(Message(format!("0{ns},").into())));
+        let msg = match assert_some!(srx.recv().await) {
+            Message(msg) => msg.to_string(),
+            p => panic!("unexpected packet {p:?}"),
+        };
+        assert!(msg.starts_with(&format!("0{ns},{{\"sid\":")), "{msg}");
+    }
+}
@@ -0,0 +1,466 @@
+//! Tests for the broadcast operators
+mod utils;
+
+use std::time::Duration;
+
+use futures_util::StreamExt;
+
+use socketioxide::{
+    extract::SocketRef, socket::Sid, BroadcastError, SendError, SocketError, SocketIo,
+};
+use tokio::sync::mpsc;
+
+use utils::timeout_rcv_msg;
+
+fn is_contradictory<T>(res: Result<T, BroadcastError>, expected: &[&str]) -> bool {
+    matches!(res, Err(BroadcastError::ContradictoryOperators(rooms)) if rooms == expected)
+}
+
+#[tokio::test]
+pub async fn contradictory_socket_operators() {
+    let (_svc, io) = SocketIo::new_svc();
+    let (tx, mut rx) = mpsc::channel::<()>(1);
+
+    io.ns("/", move |socket: SocketRef| {
+        socket.join(["room1", "room2"]).unwrap();
+
+        let res = socket.to("room1").except("room1").emit("test", "foo");
+        assert!(is_contradictory(res, &["room1"]));
+        let res = socket
+            .within(["room1", "room2"])
+            .except(["room2", "room1"])
+            .emit("test", "foo");
+        assert!(is_contradictory(res, &["room1", "room2"]));
+        let res = socket
+            .to("room1")
+            .except("room1")
+            .emit_with_ack::<()>("test", "foo");
+        assert!(is_contradictory(res, &["room1"]));
+
+        // Only partially excluded rooms are valid
+        assert_ok!(socket
+            .within(["room1", "room2"])
+            .except("room1")
+            .emit("test", "foo"));
+        tx.try_send(()).unwrap();
+    });
+
+    let (_stx, _srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(rx.recv().await);
+}
+
+#[tokio::test]
+pub async fn contradictory_io_operators() {
+    let (_svc, io) = SocketIo::new_svc();
+    io.ns("/", || {});
+
+    let res = io.to("room1").except("room1").emit("test", "foo");
+    assert!(is_contradictory(res, &["room1"]));
+    let res = io
+        .within("room1")
+        .except(["room1", "room2"])
+        .bin(vec![vec![1, 2, 3]])
+        .emit("test", serde_json::json!("foo"));
+    assert!(is_contradictory(res, &["room1"]));
+
+    assert_ok!(io.except("room1").emit("test", "foo"));
+    assert_ok!(io.to("room1").except("room2").emit("test", "foo"));
+}
+
+fn sorted_ids(sockets: Vec<SocketRef>) -> Vec<Sid> {
+    let mut ids: Vec<Sid> = sockets.into_iter().map(|s| s.id).collect();
+    ids.sort_by_key(|id| id.to_string());
+    ids
+}
+
+#[tokio::test]
+pub async fn socket_and_io_broadcast_recipients() {
+    let (_svc, io) = SocketIo::new_svc();
+    io.ns("/", |socket: SocketRef| socket.join("room1").unwrap());
+
+    let (_stx1, mut srx1) = io.new_dummy_sock("/", ()).await;
+    assert_some!(timeout_rcv_msg(&mut srx1).await); // NS connect packet
+    let sender = io.sockets().unwrap().pop().unwrap();
+
+    let (_stx2, mut srx2) = io.new_dummy_sock("/", ()).await;
+    assert_some!(timeout_rcv_msg(&mut srx2).await);
+
+    let all = sorted_ids(io.sockets().unwrap());
+    let others: Vec<Sid> = all.iter().copied().filter(|id| *id != sender.id).collect();
+
+    // Socket-level broadcast operators exclude the sender
+    assert_eq!(sorted_ids(sender.to("room1").sockets().unwrap()), others);
+    let sockets = sender.broadcast().to("room1").sockets().unwrap();
+    assert_eq!(sorted_ids(sockets), others);
+    assert_eq!(sorted_ids(sender.broadcast().sockets().unwrap()), others);
+    assert_eq!(sorted_ids(sender.local().sockets().unwrap()), others);
+    assert_eq!(
+        sorted_ids(sender.except("room2").sockets().unwrap()),
+        others
+    );
+    // Except for the within operator
+    assert_eq!(sorted_ids(sender.within("room1").sockets().unwrap()), all);
+
+    // Io-level operators exclude nobody
+    assert_eq!(sorted_ids(io.to("room1").sockets().unwrap()), all);
+    assert_eq!(sorted_ids(io.within("room1").sockets().unwrap()), all);
+    assert_eq!(sorted_ids(io.local().sockets().unwrap()), all);
+    assert_eq!(sorted_ids(io.except("room2").sockets().unwrap()), all);
+
+    // Emitted messages follow the same recipient sets
+    assert_ok!(sender.to("room1").emit("socket", ()));
+    assert_eq!(
+        assert_some!(timeout_rcv_msg(&mut srx2).await),
+        r#"2["socket",null]"#
+    );
+    assert_eq!(timeout_rcv_msg(&mut srx1).await, None);
+
+    assert_ok!(io.to("room1").emit("io", ()));
+    assert_eq!(
+        assert_some!(timeout_rcv_msg(&mut srx1).await),
+        r#"2["io",null]"#
+    );
+    assert_eq!(
+        assert_some!(timeout_rcv_msg(&mut srx2).await),
+        r#"2["io",null]"#
+    );
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Role {
+    Admin,
+    User,
+}
+
+#[tokio::test]
+pub async fn filter_by_extensions() {
+    let (_svc, io) = SocketIo::new_svc();
+    io.ns("/", |socket: SocketRef| socket.join("room1").unwrap());
+    let is_admin = |ext: &socketioxide::extensions::Extensions| ext.get() == Some(Role::Admin);
+
+    let (_stx1, mut srx1) = io.new_dummy_sock("/", ()).await;
+    assert_some!(timeout_rcv_msg(&mut srx1).await); // NS connect packet
+    let admin = io.sockets().unwrap().pop().unwrap();
+    admin.extensions.insert(Role::Admin);
+
+    let (_stx2, mut srx2) = io.new_dummy_sock("/", ()).await;
+    assert_some!(timeout_rcv_msg(&mut srx2).await);
+    let user = io.sockets().unwrap().into_iter().find(|s| s.id != admin.id);
+    let user = assert_some!(user);
+    user.extensions.insert(Role::User);
+
+    let (_stx3, mut srx3) = io.new_dummy_sock("/", ()).await;
+    assert_some!(timeout_rcv_msg(&mut srx3).await);
+    let no_role = io.sockets().unwrap().into_iter();
+    let no_role = assert_some!(no_role
+        .map(|s| s.id)
+        .find(|id| *id != admin.id && *id != user.id));
+
+    assert_eq!(
+        sorted_ids(io.filter(is_admin).sockets().unwrap()),
+        [admin.id]
+    );
+    let sockets = io.to("room1").filter(|ext| ext.get::<Role>().is_some());
+    assert_eq!(
+        sorted_ids(sockets.sockets().unwrap()),
+        sorted_ids(vec![admin.clone(), user.clone()])
+    );
+    // Combined predicates must all match
+    let sockets = io
+        .filter(is_admin)
+        .filter(|ext| ext.get() == Some(Role::User));
+    assert!(sockets.sockets().unwrap().is_empty());
+    // Socket-level filters exclude the sender
+    assert!(admin.filter(is_admin).sockets().unwrap().is_empty());
+    let sockets = user.to("room1").filter(|ext| ext.get::<Role>().is_none());
+    assert_eq!(sorted_ids(sockets.sockets().unwrap()), [no_role]);
+
+    assert_ok!(io.to("room1").filter(is_admin).emit("admin", ()));
+    assert_eq!(
+        assert_some!(timeout_rcv_msg(&mut srx1).await),
+        r#"2["admin",null]"#
+    );
+    assert_eq!(timeout_rcv_msg(&mut srx2).await, None);
+    assert_eq!(timeout_rcv_msg(&mut srx3).await, None);
+}
+
+#[tokio::test]
+pub async fn ack_filter_only_collects_filtered_acks() {
+    let (_svc, io) = SocketIo::new_svc();
+    io.ns("/", |socket: SocketRef| socket.join("room1").unwrap());
+
+    let (stx1, mut srx1) = io.new_dummy_sock("/", ()).await;
+    assert_some!(timeout_rcv_msg(&mut srx1).await); // NS connect packet
+    let admin = io.sockets().unwrap().pop().unwrap();
+    admin.extensions.insert(Role::Admin);
+
+    let (stx2, mut srx2) = io.new_dummy_sock("/", ()).await;
+    assert_some!(timeout_rcv_msg(&mut srx2).await);
+
+    let acks = io
+        .to("room1")
+        .timeout(Duration::from_secs(10))
+        .ack_filter(|ext| ext.get() == Some(Role::Admin))
+        .emit_with_ack::<Vec<String>>("vote", ())
+        .unwrap();
+
+    // Every socket receives the event but only the admin is asked for an ack
+    assert_eq!(
+        assert_some!(timeout_rcv_msg(&mut srx1).await),
+        r#"21["vote",null]"#
+    );
+    assert_eq!(
+        assert_some!(timeout_rcv_msg(&mut srx2).await),
+        r#"2["vote",null]"#
+    );
+    assert_ok!(stx1.try_send(engineioxide::Packet::Message(r#"31["yes"]"#.into())));
+    // An ack for an unknown id from the other socket is ignored
+    assert_ok!(stx2.try_send(engineioxide::Packet::Message(r#"31["no"]"#.into())));
+
+    // The stream completes without waiting for the timeout of the other socket
+    let acks = tokio::time::timeout(Duration::from_millis(200), acks.collect::<Vec<_>>()).await;
+    let acks = assert_ok!(acks);
+    assert_eq!(acks.len(), 1);
+    let (id, ack) = &acks[0];
+    assert_eq!(*id, admin.id);
+    assert_eq!(assert_ok!(ack.as_ref()).data, ["yes"]);
+}
+
+#[tokio::test]
+pub async fn socket_broadcast_excludes_sender() {
+    let (_svc, io) = SocketIo::new_svc();
+    // The sockets are in different rooms to check that the whole namespace is targeted
+    io.ns("/", |socket: SocketRef| {
+        let room = format!("room-{}", socket.id);
+        socket.join(room).unwrap();
+        socket.on("broadcast", |socket: SocketRef| {
+            socket.broadcast().emit("msg", ()).unwrap();
+        });
+    });
+
+    let (stx1, mut srx1) = io.new_dummy_sock("/", ()).await;
+    assert_some!(timeout_rcv_msg(&mut srx1).await); // NS connect packet
+    let sender = io.sockets().unwrap().pop().unwrap();
+    let (_stx2, mut srx2) = io.new_dummy_sock("/", ()).await;
+    assert_some!(timeout_rcv_msg(&mut srx2).await);
+    let (_stx3, mut srx3) = io.new_dummy_sock("/", ()).await;
+    assert_some!(timeout_rcv_msg(&mut srx3).await);
+
+    let all = sorted_ids(io.sockets().unwrap());
+    let others: Vec<Sid> = all.iter().copied().filter(|id| *id != sender.id).collect();
+    assert_eq!(others.len(), 2);
+    assert_eq!(sorted_ids(sender.broadcast().sockets().unwrap()), others);
+
+    assert_ok!(stx1.try_send(engineioxide::Packet::Message("2[\"broadcast\"]".into())));
+    assert_eq!(
+        assert_some!(timeout_rcv_msg(&mut srx2).await),
+        r#"2["msg",null]"#
+    );
+    assert_eq!(
+        assert_some!(timeout_rcv_msg(&mut srx3).await),
+        r#"2["msg",null]"#
+    );
+    assert_eq!(timeout_rcv_msg(&mut srx1).await, None);
+}
+
+#[tokio::test]
+pub async fn union_and_intersection_of_rooms() {
+    let (_svc, io) = SocketIo::new_svc();
+    io.ns("/", || {});
+
+    let (_stx1, mut srx1) = io.new_dummy_sock("/", ()).await;
+    assert_some!(timeout_rcv_msg(&mut srx1).await); // NS connect packet
+    let (_stx2, mut srx2) = io.new_dummy_sock("/", ()).await;
+    assert_some!(timeout_rcv_msg(&mut srx2).await);
+    let (_stx3, mut srx3) = io.new_dummy_sock("/", ()).await;
+    assert_some!(timeout_rcv_msg(&mut srx3).await);
+
+    let mut sockets = io.sockets().unwrap();
+    sockets.sort_by_key(|s| s.id.to_string());
+    let [eu_pro, eu, pro] = [&sockets[0], &sockets[1], &sockets[2]];
+    eu_pro.join(["region:eu", "plan:pro"]).unwrap();
+    eu.join("region:eu").unwrap();
+    pro.join("plan:pro").unwrap();
+
+    let union = sorted_ids(io.to_any_of(["region:eu", "plan:pro"]).sockets().unwrap());
+    assert_eq!(union, sorted_ids(sockets.clone()));
+    assert_eq!(
+        union,
+        sorted_ids(io.to("region:eu").to("plan:pro").sockets().unwrap())
+    );
+
+    let intersection = io.to_all_of(["region:eu", "plan:pro"]).sockets().unwrap();
+    assert_eq!(sorted_ids(intersection), vec![eu_pro.id]);
+    let intersection = io.to_all_of("region:eu").to_all_of("plan:pro");
+    assert_eq!(sorted_ids(intersection.sockets().unwrap()), vec![eu_pro.id]);
+    let narrowed = io.to("region:eu").to_all_of("plan:pro").sockets().unwrap();
+    assert_eq!(sorted_ids(narrowed), vec![eu_pro.id]);
+    assert!(is_contradictory(
+        io.to_all_of(["region:eu", "plan:pro"])
+            .except("plan:pro")
+            .emit("offer", ()),
+        &["plan:pro"]
+    ));
+
+    // Each socket in the union receives the message only once
+    assert_ok!(io.to_any_of(["region:eu", "plan:pro"]).emit("news", ()));
+    for srx in [&mut srx1, &mut srx2, &mut srx3] {
+        assert_eq!(
+            assert_some!(timeout_rcv_msg(srx).await),
+            r#"2["news",null]"#
+        );
+        assert_eq!(timeout_rcv_msg(srx).await, None);
+    }
+
+    assert_ok!(io.to_all_of(["region:eu", "plan:pro"]).emit("offer", ()));
+    let mut received = 0;
+    for srx in [&mut srx1, &mut srx2, &mut srx3] {
+        if let Some(msg) = timeout_rcv_msg(srx).await {
+            assert_eq!(msg, r#"2["offer",null]"#);
+            received += 1;
+        }
+    }
+    assert_eq!(received, 1);
+}
+
+#[tokio::test]
+pub async fn subscribe_membership_changes() {
+    use futures_util::StreamExt;
+    use socketioxide::{
+        adapter::{MembershipEvent, MembershipKind},
+        extract::Data,
+    };
+
+    let (_svc, io) = SocketIo::new_svc();
+    io.ns("/", |socket: SocketRef| {
+        socket.on("join", |socket: SocketRef, Data::<String>(room)| {
+            socket.join(room).unwrap();
+        });
+        socket.on("leave", |socket: SocketRef, Data::<String>(room)| {
+            socket.leave(room).unwrap();
+        });
+    });
+    let mut changes = io.subscribe_changes("/").unwrap().unwrap();
+
+    let (stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await); // NS connect packet
+    let sid = io.sockets().unwrap()[0].id;
+    assert_ok!(stx.try_send(engineioxide::Packet::Message(
+        "2[\"join\",\"room1\"]".into()
+    )));
+    let expected = MembershipEvent {
+        kind: MembershipKind::Join,
+        sid,
+        room: "room1".into(),
+    };
+    let change = tokio::time::timeout(Duration::from_millis(100), changes.next()).await;
+    assert_eq!(assert_some!(assert_ok!(change)), expected);
+
+    assert_ok!(stx.try_send(engineioxide::Packet::Message(
+        "2[\"leave\",\"room1\"]".into()
+    )));
+    let expected = MembershipEvent {
+        kind: MembershipKind::Leave,
+        ..expected
+    };
+    let change = tokio::time::timeout(Duration::from_millis(100), changes.next()).await;
+    assert_eq!(assert_some!(assert_ok!(change)), expected);
+}
+
+#[tokio::test]
+pub async fn to_own_rooms_overlapping_memberships() {
+    let (_svc, io) = SocketIo::new_svc();
+    io.ns("/", || {});
+
+    let mut socks = Vec::new();
+    for _ in 0..5 {
+        let (stx, mut srx) = io.new_dummy_sock("/", ()).await;
+        let connect = assert_some!(timeout_rcv_msg(&mut srx).await);
+        let sid = connect.split('"').nth(3).unwrap().to_string();
+        let socket = io.sockets().unwrap();
+        let socket = socket
+            .into_iter()
+            .find(|s| s.id.to_string() == sid)
+            .unwrap();
+        socks.push((stx, srx, socket));
+    }
+    let [a, b, c, d, e] = [0, 1, 2, 3, 4].map(|i| socks[i].2.clone());
+    let personal = a.id.to_string();
+    a.join(vec![
+        "room1".to_string(),
+        "room2".to_string(),
+        personal.clone(),
+    ])
+    .unwrap();
+    b.join(["room1", "room2"]).unwrap();
+    c.join("room2").unwrap();
+    // d is only in the personal room of a, which is not taken into account
+    d.join(vec!["room3".to_string(), personal]).unwrap();
+
+    assert_ok!(assert_ok!(a.to_own_rooms()).emit("peers", ()));
+    let received: Vec<Option<String>> = {
+        let mut received = Vec::new();
+        for (_, srx, _) in &mut socks {
+            received.push(timeout_rcv_msg(srx).await);
+        }
+        received
+    };
+    let msg = Some(r#"2["peers",null]"#.to_string());
+    assert_eq!(received, [None, msg.clone(), msg, None, None]);
+    // b and c receive the message only once
+    assert_eq!(timeout_rcv_msg(&mut socks[1].1).await, None);
+
+    // A socket without rooms has no peers
+    let ops = assert_ok!(e.to_own_rooms());
+    assert!(ops.sockets().unwrap().is_empty());
+}
+
+struct Unserializable;
+impl serde::Serialize for Unserializable {
+    fn serialize<S: serde::Serializer>(&self, _: S) -> Result<S::Ok, S::Error> {
+        Err(serde::ser::Error::custom("unserializable"))
+    }
+}
+
+#[tokio::test]
+pub async fn serialize_errors() {
+    let (_svc, io) = SocketIo::new_svc();
+    let (tx, mut rx) = mpsc::channel::<()>(1);
+    io.ns("/", move |socket: SocketRef| {
+        let res = socket.emit("test", Unserializable);
+        assert!(matches!(res, Err(SendError::Serialize(_))));
+        let res = socket.emit_with_ack::<_, ()>("test", Unserializable);
+        assert!(matches!(res, Err(SendError::Serialize(_))));
+        let res = socket.broadcast().emit("test", Unserializable);
+        assert!(matches!(res, Err(BroadcastError::Serialize(_))));
+        tx.try_send(()).unwrap();
+    });
+
+    let (_stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(rx.recv().await);
+    let res = io.emit("test", Unserializable);
+    assert!(matches!(res, Err(BroadcastError::Serialize(_))));
+    let res = io.emit_with_ack::<()>("test", Unserializable);
+    assert!(matches!(res, Err(BroadcastError::Serialize(_))));
+
+    assert_some!(timeout_rcv_msg(&mut srx).await); // NS connect packet
+    assert!(timeout_rcv_msg(&mut srx).await.is_none());
+}
+
+#[tokio::test]
+pub async fn socket_errors_on_full_buffer() {
+    let (_svc, io) = SocketIo::builder().max_buffer_size(2).build_svc();
+    io.ns("/", || {});
+
+    let (_stx, _srx) = io.new_dummy_sock("/", ()).await;
+    // The packets are not forwarded to the client while this test is not yielding
+    assert_ok!(io.emit("test", 1));
+    assert_ok!(io.emit("test", 2));
+    match io.emit("test", 3) {
+        Err(BroadcastError::Socket(errors)) => {
+            assert_eq!(errors.len(), 1);
+            assert!(matches!(errors[0], SocketError::InternalChannelFull(())));
+        }
+        res => panic!("expected a socket error, got {res:?}"),
+    }
+}
@@ -0,0 +1,66 @@
+//! Tests for the namespaces handling the socket events in order
+mod utils;
+
+use std::time::Duration;
+
+use engineioxide::Packet::*;
+use socketioxide::{extract::SocketRef, SocketIo};
+use tokio::sync::mpsc;
+
+use utils::try_timeout_rcv;
+
+fn handler(tx: mpsc::Sender<&'static str>) -> impl Fn(SocketRef) + Clone {
+    move |socket: SocketRef| {
+        let tx1 = tx.clone();
+        socket.on("slow", move || async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            tx1.try_send("slow").unwrap();
+        });
+        let tx2 = tx.clone();
+        socket.on("fast", move || tx2.try_send("fast").unwrap());
+    }
+}
+
+#[tokio::test]
+pub async fn ordered_handlers_completion() {
+    let (_svc, io) = SocketIo::new_svc();
+    let (tx, mut rx) = mpsc::channel(4);
+    io.ns("/", handler(tx.clone()));
+    io.ordered_ns("/ordered", handler(tx));
+
+    // The sync handler completes before the delayed async one
+    let (stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await); // NS connect packet
+    assert_ok!(stx.try_send(Message("2[\"slow\"]".into())));
+    assert_ok!(stx.try_send(Message("2[\"fast\"]".into())));
+    assert_eq!(assert_some!(try_timeout_rcv(&mut rx, 200).await), "fast");
+    assert_eq!(assert_some!(try_timeout_rcv(&mut rx, 200).await), "slow");
+
+    // The next handler starts only once the previous one has completed
+    let (stx, mut srx) = io.new_dummy_sock("/ordered", ()).await;
+    assert_some!(srx.recv().await); // NS connect packet
+    assert_ok!(stx.try_send(Message("2/ordered,[\"slow\"]".into())));
+    assert_ok!(stx.try_send(Message("2/ordered,[\"fast\"]".into())));
+    assert_ok!(stx.try_send(Message("2/ordered,[\"slow\"]".into())));
+    assert_eq!(assert_some!(try_timeout_rcv(&mut rx, 200).await), "slow");
+    assert_eq!(assert_some!(try_timeout_rcv(&mut rx, 200).await), "fast");
+    assert_eq!(assert_some!(try_timeout_rcv(&mut rx, 200).await), "slow");
+}
+
+#[tokio::test]
+pub async fn ordered_sockets_run_concurrently() {
+    let (_svc, io) = SocketIo::new_svc();
+    let (tx, mut rx) = mpsc::channel(4);
+    io.ordered_ns("/", handler(tx));
+
+    let (stx1, mut srx1) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx1.recv().await); // NS connect packet
+    let (stx2, mut srx2) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx2.recv().await); // NS connect packet
+
+    // The queue of a socket doesn't hold the events of another one
+    assert_ok!(stx1.try_send(Message("2[\"slow\"]".into())));
+    assert_ok!(stx2.try_send(Message("2[\"fast\"]".into())));
+    assert_eq!(assert_some!(try_timeout_rcv(&mut rx, 200).await), "fast");
+    assert_eq!(assert_some!(try_timeout_rcv(&mut rx, 200).await), "slow");
+}
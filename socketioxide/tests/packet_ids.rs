@@ -0,0 +1,64 @@
+//! Tests for the packet ids tagging the emitted events
+mod utils;
+
+use engineioxide::Packet::*;
+use socketioxide::{extract::SocketRef, packet::Packet, SocketIo};
+
+use utils::try_timeout_rcv;
+
+#[tokio::test]
+pub async fn incrementing_packet_ids() {
+    let (_svc, io) = SocketIo::builder().packet_ids(true).build_svc();
+    io.ns("/", |socket: SocketRef| {
+        assert_ok!(socket.emit("msg", "hello"));
+        assert_ok!(socket.emit("msg", [1, 2]));
+        // Raw packets are sent as is
+        let packet = Packet::event("/", "raw", serde_json::json!(["data"]));
+        assert_ok!(socket.send_raw(packet));
+        assert_ok!(socket.emit("msg", "world"));
+        assert_ok!(socket.join("room"));
+        socket.on("ping", |socket: SocketRef| {
+            assert_ok!(socket.within("room").emit("msg", ()));
+        });
+    });
+
+    let (stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await); // NS connect packet
+    assert_ok!(stx.try_send(Message("2[\"ping\"]".into())));
+    let expected = [
+        "2[\"msg\",\"hello\",{\"_pid\":1}]",
+        "2[\"msg\",1,2,{\"_pid\":2}]",
+        "2[\"raw\",\"data\"]",
+        "2[\"msg\",\"world\",{\"_pid\":3}]",
+        "2[\"msg\",null,{\"_pid\":4}]",
+    ];
+    for msg in expected {
+        assert_eq!(
+            assert_some!(try_timeout_rcv(&mut srx, 50).await),
+            Message(msg.into())
+        );
+    }
+
+    // Each socket has its own counter
+    let (_stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await);
+    assert_eq!(
+        assert_some!(try_timeout_rcv(&mut srx, 50).await),
+        Message("2[\"msg\",\"hello\",{\"_pid\":1}]".into())
+    );
+}
+
+#[tokio::test]
+pub async fn packet_ids_disabled_by_default() {
+    let (_svc, io) = SocketIo::new_svc();
+    io.ns("/", |socket: SocketRef| {
+        assert_ok!(socket.emit("msg", "hello"));
+    });
+
+    let (_stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await); // NS connect packet
+    assert_eq!(
+        assert_some!(try_timeout_rcv(&mut srx, 50).await),
+        Message("2[\"msg\",\"hello\"]".into())
+    );
+}
@@ -0,0 +1,79 @@
+//! Tests for the events prepared once and emitted to several rooms
+mod utils;
+
+use std::time::Duration;
+
+use bytes::Bytes;
+use engineioxide::Packet::*;
+use serde_json::json;
+use socketioxide::{
+    extract::{Data, SocketRef},
+    SocketIo,
+};
+use tokio::sync::mpsc;
+
+async fn recv_all(srx: &mut mpsc::Receiver<engineioxide::Packet>) -> Vec<engineioxide::Packet> {
+    let mut packets = Vec::new();
+    while let Ok(Some(packet)) = tokio::time::timeout(Duration::from_millis(20), srx.recv()).await {
+        packets.push(packet);
+    }
+    packets
+}
+
+#[tokio::test]
+pub async fn emit_prepared_delivers_the_same_packets_as_emit() {
+    let (_, io) = SocketIo::new_svc();
+    io.ns("/", |socket: SocketRef, Data(room): Data<String>| {
+        socket.join(room).unwrap();
+    });
+
+    let (_stx1, mut srx1) = io.new_dummy_sock("/", json!("a")).await;
+    let (_stx2, mut srx2) = io.new_dummy_sock("/", json!("b")).await;
+    assert_some!(srx1.recv().await);
+    assert_some!(srx2.recv().await);
+
+    let data = json!({ "text": "hello", "list": [1, 2, 3] });
+    let bin = Bytes::from_static(&[1, 2, 3, 4]);
+    io.to("a").emit("msg", &data).unwrap();
+    io.to("b").emit("msg", &data).unwrap();
+    io.to("a").emit("bin", (&data, &bin)).unwrap();
+    io.to("b").emit("bin", (&data, &bin)).unwrap();
+    let expected1 = recv_all(&mut srx1).await;
+    let expected2 = recv_all(&mut srx2).await;
+    assert_eq!(expected1.len(), 3);
+    assert_eq!(expected1, expected2);
+
+    let msg = io.prepare("msg", &data).unwrap();
+    let bin = io.prepare("bin", (&data, &bin)).unwrap();
+    for packet in [&msg, &bin] {
+        io.to("a").emit_prepared(packet).unwrap();
+        io.to("b").emit_prepared(packet).unwrap();
+    }
+    assert_eq!(recv_all(&mut srx1).await, expected1);
+    assert_eq!(recv_all(&mut srx2).await, expected2);
+}
+
+#[tokio::test]
+pub async fn emit_prepared_to_several_namespaces() {
+    let (_, io) = SocketIo::new_svc();
+    io.ns("/", || {});
+    io.ns("/admin", || {});
+
+    let (_stx1, mut srx1) = io.new_dummy_sock("/", ()).await;
+    let (_stx2, mut srx2) = io.new_dummy_sock("/admin", ()).await;
+    assert_some!(srx1.recv().await);
+    assert_some!(srx2.recv().await);
+
+    let packet = io.prepare("alert", "restart").unwrap();
+    io.emit_prepared(&packet).unwrap();
+    io.of("/admin").unwrap().emit_prepared(&packet).unwrap();
+
+    assert_eq!(
+        recv_all(&mut srx1).await,
+        [Message("2[\"alert\",\"restart\"]".into())]
+    );
+    assert_eq!(
+        recv_all(&mut srx2).await,
+        [Message("2/admin,[\"alert\",\"restart\"]".into())]
+    );
+}
@@ -0,0 +1,110 @@
+//! Tests for the presence tracking of rooms
+mod utils;
+
+use engineioxide::Packet::*;
+use socketioxide::{extract::SocketRef, socket::Sid, SocketIo};
+
+use utils::timeout_rcv_msg;
+
+fn presence_msg(event: &str, room: &str, sid: Sid) -> String {
+    format!(r#"2["presence:{event}",{{"room":"{room}","sid":"{sid}"}}]"#)
+}
+
+#[tokio::test]
+pub async fn presence_join_leave() {
+    let (_svc, io) = SocketIo::new_svc();
+    io.ns("/", |socket: SocketRef| {
+        socket.join(["lobby", "other"]).unwrap();
+        socket.on("leave", |socket: SocketRef| socket.leave("lobby").unwrap());
+    });
+    let presence = io.presence("/").unwrap();
+    presence.track("lobby");
+    assert!(presence.is_tracked("lobby"));
+    assert!(!presence.is_tracked("other"));
+
+    let (_stx1, mut srx1) = io.new_dummy_sock("/", ()).await;
+    assert_some!(timeout_rcv_msg(&mut srx1).await); // NS connect packet
+    let sid1 = io.sockets().unwrap().pop().unwrap().id;
+
+    // The first member is notified when a socket joins the room
+    let (stx2, mut srx2) = io.new_dummy_sock("/", ()).await;
+    assert_some!(timeout_rcv_msg(&mut srx2).await);
+    let sid2 = io.sockets().unwrap().into_iter().find(|s| s.id != sid1);
+    let sid2 = assert_some!(sid2).id;
+    assert_eq!(
+        assert_some!(timeout_rcv_msg(&mut srx1).await),
+        presence_msg("join", "lobby", sid2)
+    );
+    // The joining socket is not notified and untracked rooms do not emit events
+    assert_eq!(timeout_rcv_msg(&mut srx2).await, None);
+    assert_eq!(timeout_rcv_msg(&mut srx1).await, None);
+
+    // The remaining member is notified when a socket leaves the room
+    assert_ok!(stx2.try_send(Message("2[\"leave\"]".into())));
+    assert_eq!(
+        assert_some!(timeout_rcv_msg(&mut srx1).await),
+        presence_msg("leave", "lobby", sid2)
+    );
+    assert_eq!(timeout_rcv_msg(&mut srx2).await, None);
+
+    // Leaving a room that was already left does not emit anything
+    assert_ok!(stx2.try_send(Message("2[\"leave\"]".into())));
+    assert_eq!(timeout_rcv_msg(&mut srx1).await, None);
+
+    // The remaining members are notified when a socket disconnects
+    let (stx3, mut srx3) = io.new_dummy_sock("/", ()).await;
+    assert_some!(timeout_rcv_msg(&mut srx3).await);
+    let sid3 = io.sockets().unwrap().into_iter();
+    let sid3 = assert_some!(sid3.map(|s| s.id).find(|id| *id != sid1 && *id != sid2));
+    assert_eq!(
+        assert_some!(timeout_rcv_msg(&mut srx1).await),
+        presence_msg("join", "lobby", sid3)
+    );
+    assert_ok!(stx3.try_send(Close));
+    assert_eq!(
+        assert_some!(timeout_rcv_msg(&mut srx1).await),
+        presence_msg("leave", "lobby", sid3)
+    );
+    assert_eq!(timeout_rcv_msg(&mut srx2).await, None);
+
+    // Untracked rooms do not emit presence events anymore
+    presence.untrack("lobby");
+    assert_ok!(stx2.try_send(Close));
+    let (_stx4, mut srx4) = io.new_dummy_sock("/", ()).await;
+    assert_some!(timeout_rcv_msg(&mut srx4).await);
+    assert_eq!(timeout_rcv_msg(&mut srx1).await, None);
+}
+
+#[tokio::test]
+pub async fn presence_operator_join_leave() {
+    let (_svc, io) = SocketIo::new_svc();
+    io.ns("/", |socket: SocketRef| {
+        socket.join("lobby").unwrap();
+    });
+    io.presence("/").unwrap().track("game");
+
+    let (_stx1, mut srx1) = io.new_dummy_sock("/", ()).await;
+    assert_some!(timeout_rcv_msg(&mut srx1).await); // NS connect packet
+    let sid1 = io.sockets().unwrap().pop().unwrap().id;
+    assert_ok!(io.to_sockets([sid1]).join("game"));
+    let (_stx2, mut srx2) = io.new_dummy_sock("/", ()).await;
+    assert_some!(timeout_rcv_msg(&mut srx2).await);
+    let sid2 = io.sockets().unwrap().into_iter().find(|s| s.id != sid1);
+    let sid2 = assert_some!(sid2).id;
+
+    // The sockets joining through the operators are announced to the members
+    assert_ok!(io.to_sockets([sid2]).join("game"));
+    assert_eq!(
+        assert_some!(timeout_rcv_msg(&mut srx1).await),
+        presence_msg("join", "game", sid2)
+    );
+    assert_eq!(timeout_rcv_msg(&mut srx2).await, None);
+
+    // And so are the sockets leaving through the operators
+    assert_ok!(io.to_sockets([sid2]).leave("game"));
+    assert_eq!(
+        assert_some!(timeout_rcv_msg(&mut srx1).await),
+        presence_msg("leave", "game", sid2)
+    );
+    assert_eq!(io.within("game").sockets().unwrap().len(), 1);
+}
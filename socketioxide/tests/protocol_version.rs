@@ -0,0 +1,75 @@
+//! Tests for the engine.io protocol version negotiated by each socket.
+//! A basic emit is checked against both an engine.io v3 and v4 websocket client.
+use std::time::Duration;
+
+use futures_util::{SinkExt, StreamExt};
+use socketioxide::{extract::SocketRef, EIoProtocolVersion};
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::Message;
+
+mod fixture;
+mod utils;
+
+use fixture::create_server;
+
+async fn next_text<S>(ws: &mut S) -> String
+where
+    S: StreamExt<Item = Result<Message, tokio_tungstenite::tungstenite::Error>> + Unpin,
+{
+    let msg = tokio::time::timeout(Duration::from_millis(200), ws.next())
+        .await
+        .expect("timeout waiting for a ws message");
+    match assert_some!(msg) {
+        Ok(Message::Text(msg)) => msg,
+        msg => panic!("unexpected ws message: {:?}", msg),
+    }
+}
+
+#[tokio::test]
+pub async fn ws_v4_protocol_version() {
+    let io = create_server(12360).await;
+    let (tx, mut rx) = mpsc::channel::<EIoProtocolVersion>(1);
+    io.ns("/", move |socket: SocketRef, v: EIoProtocolVersion| {
+        assert_eq!(socket.protocol_version(), v);
+        tx.try_send(v).unwrap();
+        assert_ok!(socket.emit("hello", "world"));
+    });
+
+    let (mut ws, _) = assert_ok!(
+        tokio_tungstenite::connect_async(
+            "ws://127.0.0.1:12360/socket.io/?EIO=4&transport=websocket"
+        )
+        .await
+    );
+    assert!(next_text(&mut ws).await.starts_with('0')); // open packet
+    assert_ok!(ws.send(Message::Text("40{}".to_string())).await);
+    assert!(next_text(&mut ws).await.starts_with("40")); // connect packet
+
+    assert_eq!(next_text(&mut ws).await, r#"42["hello","world"]"#);
+    assert_eq!(assert_some!(rx.recv().await), EIoProtocolVersion::V4);
+}
+
+#[cfg(feature = "v4")]
+#[tokio::test]
+pub async fn ws_v3_protocol_version() {
+    let io = create_server(12361).await;
+    let (tx, mut rx) = mpsc::channel::<EIoProtocolVersion>(1);
+    io.ns("/", move |socket: SocketRef, v: EIoProtocolVersion| {
+        assert_eq!(socket.protocol_version(), v);
+        tx.try_send(v).unwrap();
+        assert_ok!(socket.emit("hello", "world"));
+    });
+
+    let (mut ws, _) = assert_ok!(
+        tokio_tungstenite::connect_async(
+            "ws://127.0.0.1:12361/socket.io/?EIO=3&transport=websocket"
+        )
+        .await
+    );
+    // Open packet, engine.io v3 clients are then automatically connected to the root namespace
+    assert!(next_text(&mut ws).await.starts_with('0'));
+    assert_eq!(next_text(&mut ws).await, "40");
+
+    assert_eq!(next_text(&mut ws).await, r#"42["hello","world"]"#);
+    assert_eq!(assert_some!(rx.recv().await), EIoProtocolVersion::V3);
+}
@@ -0,0 +1,50 @@
+//! Tests for the handlers receiving the undecoded arguments of the events
+mod utils;
+
+use bytes::Bytes;
+use engineioxide::Packet::*;
+use serde_json::{json, Value};
+use socketioxide::{
+    extract::{Data, RawPacket, SocketRef},
+    SocketIo,
+};
+use tokio::sync::mpsc;
+
+#[tokio::test]
+pub async fn raw_handler_receives_unparsed_args() {
+    let (tx, mut rx) = mpsc::channel::<Bytes>(10);
+    let (dtx, mut drx) = mpsc::channel::<Value>(10);
+    let (_, io) = SocketIo::new_svc();
+    io.ns("/", move |socket: SocketRef| {
+        let tx1 = tx.clone();
+        socket.on_raw("raw", move |RawPacket(args)| {
+            tx1.try_send(args).unwrap();
+        });
+        let tx = tx.clone();
+        socket.on("reencoded", move |RawPacket(args)| {
+            tx.try_send(args).unwrap();
+        });
+        let dtx = dtx.clone();
+        socket.on("decoded", move |Data::<Value>(data)| {
+            dtx.try_send(data).unwrap();
+        });
+    });
+
+    let (stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await);
+
+    // The arguments are given as they were sent
+    assert_ok!(stx.try_send(Message(r#"2["raw",1, {"a" : 2}]"#.into())));
+    assert_eq!(assert_some!(rx.recv().await), r#"[1, {"a" : 2}]"#);
+    assert_ok!(stx.try_send(Message(r#"2["raw"]"#.into())));
+    assert_eq!(assert_some!(rx.recv().await), "[]");
+    // They are not parsed, so an invalid payload is still passed through
+    assert_ok!(stx.try_send(Message(r#"2["raw",{bad]"#.into())));
+    assert_eq!(assert_some!(rx.recv().await), "[{bad]");
+
+    // The other events are still decoded
+    assert_ok!(stx.try_send(Message(r#"2["decoded",{"a":2}]"#.into())));
+    assert_eq!(assert_some!(drx.recv().await), json!({ "a": 2 }));
+    assert_ok!(stx.try_send(Message(r#"2["reencoded",1, {"a" : 2}]"#.into())));
+    assert_eq!(assert_some!(rx.recv().await), r#"[1,{"a":2}]"#);
+}
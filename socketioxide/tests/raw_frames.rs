@@ -0,0 +1,66 @@
+//! Tests for the raw engine.io frames inspection hook
+#![cfg(feature = "debug-frames")]
+mod fixture;
+mod utils;
+
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use fixture::{create_polling_connection, create_ws_connection, send_req, spawn_server};
+use futures_util::StreamExt;
+use socketioxide::{Direction, SocketIo};
+
+type Frames = Arc<Mutex<Vec<(Direction, String)>>>;
+
+fn capture_frames(io: &SocketIo) -> Frames {
+    let frames = Frames::default();
+    let captured = frames.clone();
+    io.on_raw_packet(move |_, direction, data| {
+        let data = String::from_utf8(data.to_vec()).unwrap();
+        captured.lock().unwrap().push((direction, data));
+    });
+    frames
+}
+
+#[tokio::test]
+pub async fn ws_connect_handshake_frames() {
+    let (svc, io) = SocketIo::new_svc();
+    io.ns("/", || {});
+    let frames = capture_frames(&io);
+    spawn_server(12368, svc).await;
+
+    let mut ws = create_ws_connection(12368).await;
+    assert_ok!(assert_some!(ws.next().await)); // Open packet
+    assert_ok!(assert_some!(ws.next().await)); // NS connect packet
+
+    let frames = frames.lock().unwrap().clone();
+    assert_eq!(frames.len(), 3);
+    assert_eq!(frames[0].0, Direction::Outgoing);
+    assert!(frames[0].1.starts_with("0{\"sid\":"));
+    assert_eq!(frames[1], (Direction::Incoming, "40{}".to_string()));
+    assert_eq!(frames[2].0, Direction::Outgoing);
+    assert!(frames[2].1.starts_with("40{\"sid\":"));
+}
+
+#[tokio::test]
+pub async fn polling_connect_handshake_frames() {
+    let (svc, io) = SocketIo::new_svc();
+    io.ns("/", || {});
+    let frames = capture_frames(&io);
+    spawn_server(12369, svc).await;
+
+    let sid = create_polling_connection(12369).await;
+    let params = format!("transport=polling&sid={sid}");
+    let body = send_req(12369, params, http::Method::GET, None).await;
+    tokio::time::sleep(Duration::from_millis(10)).await;
+
+    let frames = frames.lock().unwrap().clone();
+    assert_eq!(frames.len(), 3);
+    assert_eq!(frames[0].0, Direction::Outgoing);
+    assert!(frames[0].1.starts_with("0{\"sid\":"));
+    assert_eq!(frames[1], (Direction::Incoming, "40{}".to_string()));
+    // The client helper strips the first character of the payload
+    assert_eq!(frames[2], (Direction::Outgoing, format!("4{body}")));
+}
@@ -0,0 +1,109 @@
+//! Tests for sending raw packets
+mod fixture;
+mod utils;
+
+use std::time::Duration;
+
+use bytes::Bytes;
+use engineioxide::Packet::*;
+use fixture::{create_ws_connection, spawn_server};
+use futures_util::{SinkExt, StreamExt};
+use serde_json::json;
+use socketioxide::{
+    extract::{AckSender, SocketRef},
+    packet::Packet,
+    RawSendError, SocketIo,
+};
+use tokio_tungstenite::tungstenite::Message as WsMessage;
+
+#[tokio::test]
+pub async fn send_raw_packets() {
+    let (_svc, io) = SocketIo::new_svc();
+    io.ns("/", || {});
+
+    let (_stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await); // NS connect packet
+    let socket: SocketRef = io.sockets().unwrap().pop().unwrap();
+
+    // Hand-constructed event packet
+    assert_ok!(socket.send_raw_bytes(br#"2["test","foo",{"bar":1}]"#));
+    let msg = assert_some!(srx.recv().await);
+    assert_eq!(msg, Message(r#"2["test","foo",{"bar":1}]"#.into()));
+    // It is received as a normal event by a client handler
+    let Message(msg) = msg else { unreachable!() };
+    assert_eq!(
+        assert_ok!(Packet::try_from(msg)),
+        Packet::event("/", "test", json!(["foo", { "bar": 1 }]))
+    );
+
+    // Binary attachments are sent right after the packet
+    let bin = vec![Bytes::from_static(&[1, 2, 3])];
+    let packet = Packet::bin_event("/", "bin", json!("foo"), bin.clone());
+    assert_ok!(socket.send_raw(packet));
+    assert_eq!(
+        assert_some!(srx.recv().await),
+        Message(r#"51-["bin","foo",{"_placeholder":true,"num":0}]"#.into())
+    );
+    assert_eq!(assert_some!(srx.recv().await), Binary(bin[0].clone()));
+
+    // Invalid framing is rejected
+    assert!(matches!(
+        socket.send_raw_bytes(&[0xff, 0xfe]),
+        Err(RawSendError::InvalidUtf8(_))
+    ));
+    assert!(matches!(
+        socket.send_raw_bytes(b"9foo"),
+        Err(RawSendError::InvalidPacket(_))
+    ));
+    assert!(matches!(
+        socket.send_raw_bytes(br#"51-["bin",{"_placeholder":true,"num":0}]"#),
+        Err(RawSendError::MissingAttachments {
+            expected: 1,
+            got: 0
+        })
+    ));
+    assert!(srx.try_recv().is_err());
+}
+
+async fn next_msg(
+    ws: &mut tokio_tungstenite::WebSocketStream<
+        tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>,
+    >,
+) -> String {
+    let msg = tokio::time::timeout(Duration::from_millis(500), ws.next()).await;
+    match assert_some!(assert_ok!(msg)) {
+        Ok(WsMessage::Text(msg)) => msg,
+        msg => panic!("unexpected message: {msg:?}"),
+    }
+}
+
+#[tokio::test]
+pub async fn emit_to_other_namespace() {
+    const PORT: u16 = 12367;
+    let (svc, io) = SocketIo::new_svc();
+    io.ns("/admin", || {});
+    io.ns("/", |socket: SocketRef| {
+        socket.on("forward", |socket: SocketRef, ack: AckSender| {
+            assert_ok!(socket.emit_to_ns("/admin", "hello", "world"));
+            let err = socket.emit_to_ns("/unknown", "hello", "world").unwrap_err();
+            assert!(matches!(err, RawSendError::UnknownNamespace(ns) if ns == "/unknown"));
+            ack.send(()).ok();
+        });
+    });
+    spawn_server(PORT, svc).await;
+
+    let mut ws = create_ws_connection(PORT).await;
+    assert_ok!(ws.send(WsMessage::Text("40/admin,{}".into())).await);
+    assert!(next_msg(&mut ws).await.starts_with('0')); // engine.io open packet
+    assert!(next_msg(&mut ws).await.starts_with("40{")); // socket.io connect packets
+    assert!(next_msg(&mut ws).await.starts_with("40/admin,"));
+
+    assert_ok!(ws.send(WsMessage::Text("421[\"forward\"]".into())).await);
+    // The packet carries the target namespace so it is received by the client socket on this namespace
+    let msg = next_msg(&mut ws).await;
+    assert_eq!(msg, "42/admin,[\"hello\",\"world\"]");
+    let packet = assert_ok!(Packet::try_from(msg[1..].to_string()));
+    assert_eq!(packet.ns, "/admin");
+    // Then the ack of the forward event is received on the root namespace
+    assert_eq!(next_msg(&mut ws).await, "431[]");
+}
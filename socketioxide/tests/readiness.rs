@@ -0,0 +1,64 @@
+//! Tests for the readiness gate holding the namespace connections
+mod utils;
+
+use std::time::Duration;
+
+use engineioxide::Packet::*;
+use socketioxide::{extract::SocketRef, readiness::ReadinessGate, SocketIo};
+use tokio::sync::mpsc;
+
+use utils::try_timeout_rcv;
+
+#[tokio::test]
+pub async fn connects_held_until_gate_open() {
+    let gate = ReadinessGate::new();
+    let (_svc, io) = SocketIo::builder()
+        .readiness_gate(gate.clone(), Duration::from_secs(5))
+        .build_svc();
+    let (tx, mut rx) = mpsc::channel::<()>(2);
+    io.ns("/", move |_: SocketRef| tx.try_send(()).unwrap());
+
+    let (_stx1, mut srx1) = io.new_dummy_sock("/", ()).await;
+    let (_stx2, mut srx2) = io.new_dummy_sock("/", ()).await;
+
+    // The connections are held
+    assert!(try_timeout_rcv(&mut srx1, 50).await.is_none());
+    assert!(try_timeout_rcv(&mut srx2, 50).await.is_none());
+    assert!(rx.try_recv().is_err());
+    assert!(!gate.is_open());
+
+    gate.open();
+    for srx in [&mut srx1, &mut srx2] {
+        let msg = assert_some!(try_timeout_rcv(srx, 50).await);
+        assert!(matches!(msg, Message(msg) if msg.starts_with("0{\"sid\":")));
+    }
+    assert_some!(try_timeout_rcv(&mut rx, 50).await);
+    assert_some!(try_timeout_rcv(&mut rx, 50).await);
+
+    // Once open, the next connections are not held
+    let (_stx3, mut srx3) = io.new_dummy_sock("/", ()).await;
+    assert_some!(try_timeout_rcv(&mut srx3, 50).await);
+    assert_some!(try_timeout_rcv(&mut rx, 50).await);
+}
+
+#[tokio::test]
+pub async fn connects_rejected_after_max_hold() {
+    let gate = ReadinessGate::new();
+    let (_svc, io) = SocketIo::builder()
+        .readiness_gate(gate.clone(), Duration::from_millis(20))
+        .build_svc();
+    let (tx, mut rx) = mpsc::channel::<()>(1);
+    io.ns("/", move |_: SocketRef| tx.try_send(()).unwrap());
+
+    let (_stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    let msg = assert_some!(try_timeout_rcv(&mut srx, 50).await);
+    assert_eq!(msg, Message("4{\"message\":\"server not ready\"}".into()));
+    assert!(try_timeout_rcv(&mut rx, 50).await.is_none());
+
+    // Closing the gate again holds the next connections
+    gate.open();
+    gate.close();
+    let (_stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    let msg = assert_some!(try_timeout_rcv(&mut srx, 50).await);
+    assert_eq!(msg, Message("4{\"message\":\"server not ready\"}".into()));
+}
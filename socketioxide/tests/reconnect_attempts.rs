@@ -0,0 +1,42 @@
+//! Tests for the reconnection attempt count reported in the handshake auth payload
+mod utils;
+
+use serde_json::json;
+use socketioxide::{extract::SocketRef, SocketIo};
+use tokio::sync::mpsc;
+
+#[tokio::test]
+pub async fn reconnect_attempts_from_auth() {
+    let (_svc, io) = SocketIo::builder()
+        .reconnect_attempts_from_auth(|auth| auth.get("attempts")?.as_u64()?.try_into().ok())
+        .build_svc();
+    let (tx, mut rx) = mpsc::channel::<Option<u32>>(3);
+    io.ns("/", move |socket: SocketRef| {
+        tx.try_send(socket.reconnect_attempts()).unwrap();
+    });
+
+    let (_stx, mut srx) = io.new_dummy_sock("/", json!({ "attempts": 3 })).await;
+    assert_some!(srx.recv().await); // NS connect packet
+    assert_eq!(assert_some!(rx.recv().await), Some(3));
+
+    // Missing or invalid values are ignored
+    let (_stx, mut srx) = io.new_dummy_sock("/", json!({ "attempts": "many" })).await;
+    assert_some!(srx.recv().await);
+    assert_eq!(assert_some!(rx.recv().await), None);
+    let (_stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await);
+    assert_eq!(assert_some!(rx.recv().await), None);
+}
+
+#[tokio::test]
+pub async fn reconnect_attempts_without_extractor() {
+    let (_svc, io) = SocketIo::new_svc();
+    let (tx, mut rx) = mpsc::channel::<Option<u32>>(1);
+    io.ns("/", move |socket: SocketRef| {
+        tx.try_send(socket.reconnect_attempts()).unwrap();
+    });
+
+    let (_stx, mut srx) = io.new_dummy_sock("/", json!({ "attempts": 3 })).await;
+    assert_some!(srx.recv().await); // NS connect packet
+    assert_eq!(assert_some!(rx.recv().await), None);
+}
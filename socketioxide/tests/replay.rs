@@ -0,0 +1,83 @@
+//! Tests for the replay of the missed broadcasts
+mod utils;
+
+use engineioxide::Packet::*;
+use socketioxide::{extract::SocketRef, replay::RESYNC_EVENT, ResyncError, SocketIo};
+
+use utils::try_timeout_rcv;
+
+fn article(i: u32) -> engineioxide::Packet {
+    Message(format!("2[\"article\",{i},\"{i}\"]").into())
+}
+
+#[tokio::test]
+pub async fn resync_replays_missed_broadcasts() {
+    let (_svc, io) = SocketIo::builder().replay_buffer(3).build_svc();
+    io.ns("/", |socket: SocketRef| socket.join("news").unwrap());
+
+    let (stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await); // NS connect packet
+    let socket = assert_some!(io.sockets().unwrap().pop());
+
+    for i in 1..=4 {
+        assert_ok!(io.to("news").emit("article", i));
+        assert_eq!(
+            assert_some!(try_timeout_rcv(&mut srx, 20).await),
+            article(i)
+        );
+    }
+    assert_eq!(socket.replay_offset(), Some(4));
+    // Events emitted to the socket alone are not recorded
+    assert_ok!(socket.emit("direct", ()));
+    assert_eq!(
+        assert_some!(try_timeout_rcv(&mut srx, 20).await),
+        Message("2[\"direct\",null]".into())
+    );
+
+    // Replayed from the server
+    assert_eq!(assert_ok!(socket.resync(2)), 2);
+    assert_eq!(
+        assert_some!(try_timeout_rcv(&mut srx, 20).await),
+        article(3)
+    );
+    assert_eq!(
+        assert_some!(try_timeout_rcv(&mut srx, 20).await),
+        article(4)
+    );
+    assert_eq!(assert_ok!(socket.resync(4)), 0);
+
+    // Replayed from the client with the reserved event
+    let resync = format!("2[\"{RESYNC_EVENT}\",\"3\"]");
+    assert_ok!(stx.try_send(Message(resync.into())));
+    assert_eq!(
+        assert_some!(try_timeout_rcv(&mut srx, 20).await),
+        article(4)
+    );
+    assert!(try_timeout_rcv(&mut srx, 20).await.is_none());
+
+    // The first packet was dropped from the buffer
+    assert!(matches!(
+        socket.resync(0),
+        Err(ResyncError::OffsetExpired(2))
+    ));
+    assert!(try_timeout_rcv(&mut srx, 20).await.is_none());
+}
+
+#[tokio::test]
+pub async fn resync_disabled() {
+    let (_svc, io) = SocketIo::new_svc();
+    io.ns("/", |socket: SocketRef| socket.join("news").unwrap());
+
+    let (_stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await); // NS connect packet
+    let socket = assert_some!(io.sockets().unwrap().pop());
+
+    // No offset is appended to the broadcasts
+    assert_ok!(io.to("news").emit("article", 1));
+    assert_eq!(
+        assert_some!(try_timeout_rcv(&mut srx, 20).await),
+        Message("2[\"article\",1]".into())
+    );
+    assert_eq!(socket.replay_offset(), None);
+    assert!(matches!(socket.resync(0), Err(ResyncError::Disabled)));
+}
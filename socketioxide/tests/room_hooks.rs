@@ -0,0 +1,101 @@
+//! Tests for the room join/leave and creation/emptiness hooks
+mod utils;
+
+use std::sync::{Arc, Mutex};
+
+use engineioxide::Packet::*;
+use socketioxide::{extract::SocketRef, socket::Sid, JoinError, SocketIo};
+use tokio::sync::mpsc;
+
+#[tokio::test]
+pub async fn join_allowed_and_vetoed() {
+    let (_svc, io) = SocketIo::new_svc();
+    let (tx, mut rx) = mpsc::channel::<Vec<String>>(1);
+    io.on_join(|_, room| !room.starts_with("admin:"));
+    io.ns("/", move |socket: SocketRef| {
+        assert_ok!(socket.join("lobby"));
+        let res = socket.join(["game", "admin:logs"]);
+        assert!(matches!(res, Err(JoinError::Vetoed(room)) if room == "admin:logs"));
+        // The join is atomic, none of the rooms are joined when one is vetoed
+        let rooms = socket.rooms().unwrap();
+        tx.try_send(rooms.into_iter().map(|r| r.to_string()).collect())
+            .unwrap();
+    });
+
+    let (_stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await); // NS connect packet
+    assert_eq!(assert_some!(rx.recv().await), vec!["lobby".to_string()]);
+}
+
+#[tokio::test]
+pub async fn leave_hook() {
+    let (_svc, io) = SocketIo::new_svc();
+    let left = Arc::new(Mutex::new(Vec::<(Sid, String)>::new()));
+    let left_clone = left.clone();
+    io.on_leave(move |sid, room| left_clone.lock().unwrap().push((sid, room.to_string())));
+    io.ns("/", |socket: SocketRef| {
+        socket.join(["room1", "room2", "room3"]).unwrap();
+        socket.on("leave", |socket: SocketRef| {
+            // Only the rooms that were joined are reported
+            socket.leave(["room1", "unknown"]).unwrap();
+        });
+    });
+
+    let (stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await); // NS connect packet
+    let sid = io.sockets().unwrap().pop().unwrap().id;
+
+    assert_ok!(stx.try_send(Message("2[\"leave\"]".into())));
+    tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    assert_eq!(*left.lock().unwrap(), vec![(sid, "room1".to_string())]);
+
+    // The remaining rooms are reported on disconnection
+    assert_ok!(stx.try_send(Close));
+    tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    let mut left = left.lock().unwrap().clone();
+    left.sort_by(|a, b| a.1.cmp(&b.1));
+    assert_eq!(
+        left,
+        vec![
+            (sid, "room1".to_string()),
+            (sid, "room2".to_string()),
+            (sid, "room3".to_string())
+        ]
+    );
+}
+
+#[tokio::test]
+pub async fn room_created_and_empty() {
+    let (_svc, io) = SocketIo::new_svc();
+    let events = Arc::new(Mutex::new(Vec::<String>::new()));
+    let created = events.clone();
+    io.on_room_created(move |room| created.lock().unwrap().push(format!("created:{room}")));
+    let empty = events.clone();
+    io.on_room_empty(move |room| empty.lock().unwrap().push(format!("empty:{room}")));
+    io.ns("/", |socket: SocketRef| {
+        socket.on("join", |socket: SocketRef| socket.join("game").unwrap());
+        socket.on("leave", |socket: SocketRef| socket.leave("game").unwrap());
+    });
+
+    let (stx1, mut srx1) = io.new_dummy_sock("/", ()).await;
+    let (stx2, mut srx2) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx1.recv().await); // NS connect packet
+    assert_some!(srx2.recv().await);
+
+    // Only the first member creates the room
+    assert_ok!(stx1.try_send(Message("2[\"join\"]".into())));
+    assert_ok!(stx2.try_send(Message("2[\"join\"]".into())));
+    tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    assert_eq!(*events.lock().unwrap(), vec!["created:game".to_string()]);
+
+    // Only the last member empties the room
+    assert_ok!(stx1.try_send(Message("2[\"leave\"]".into())));
+    tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    assert_eq!(events.lock().unwrap().len(), 1);
+    assert_ok!(stx2.try_send(Close));
+    tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    assert_eq!(
+        *events.lock().unwrap(),
+        vec!["created:game".to_string(), "empty:game".to_string()]
+    );
+}
@@ -0,0 +1,186 @@
+//! Tests for the metadata of the rooms
+mod utils;
+
+use std::sync::Arc;
+
+use serde_json::json;
+use socketioxide::{
+    extract::{Data, SocketRef},
+    room_meta::CAPACITY_KEY,
+    JoinError, RoomMetaError, SocketIo,
+};
+use tokio::sync::{mpsc, Barrier};
+
+#[tokio::test]
+pub async fn room_meta_set_and_get() {
+    let (_svc, io) = SocketIo::new_svc();
+    io.ns("/", || {});
+    let (_stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await); // NS connect packet
+    let socket = io.sockets().unwrap().pop().unwrap();
+
+    let meta = io.room_meta("game");
+    assert_eq!(meta.room(), "game");
+    assert_ok!(meta.set("owner", socket.id));
+    assert_ok!(meta.set("mode", "ranked"));
+    assert_eq!(
+        meta.get("owner").unwrap(),
+        Some(json!(socket.id.to_string()))
+    );
+    assert_eq!(meta.all().unwrap().len(), 2);
+
+    // The metadata are shared by the handles of a same room
+    assert_eq!(
+        socket.room_meta("game").get("mode").unwrap(),
+        Some(json!("ranked"))
+    );
+    assert!(io.room_meta("other").all().unwrap().is_empty());
+
+    // They are kept without members
+    assert!(io.rooms().unwrap().is_empty());
+
+    assert_ok!(meta.remove("mode"));
+    assert_eq!(meta.get("mode").unwrap(), None);
+    assert_ok!(meta.clear());
+    assert!(meta.all().unwrap().is_empty());
+}
+
+#[tokio::test]
+pub async fn room_capacity_enforced_on_join() {
+    let (_svc, io) = SocketIo::new_svc();
+    let (tx, mut rx) = mpsc::channel::<Result<(), String>>(4);
+    io.ns("/", move |socket: SocketRef, Data::<String>(room)| {
+        let res = socket.join(room).map_err(|e| match e {
+            JoinError::RoomFull(room) => room.to_string(),
+            e => panic!("unexpected error: {e}"),
+        });
+        tx.try_send(res).unwrap();
+    });
+    io.room_meta("game").set_capacity(2).unwrap();
+    assert_eq!(io.room_meta("game").capacity().unwrap(), Some(2));
+
+    let (_stx1, _srx1) = io.new_dummy_sock("/", "game").await;
+    assert_ok!(rx.try_recv().unwrap());
+    let (_stx2, _srx2) = io.new_dummy_sock("/", "game").await;
+    assert_ok!(rx.try_recv().unwrap());
+    let (_stx3, _srx3) = io.new_dummy_sock("/", "game").await;
+    assert_eq!(rx.try_recv().unwrap(), Err("game".to_string()));
+    let (_stx4, _srx4) = io.new_dummy_sock("/", "lobby").await;
+    assert_ok!(rx.try_recv().unwrap());
+    assert_eq!(io.within("game").sockets().unwrap().len(), 2);
+
+    // A member joining again doesn't count twice
+    let member = io.within("game").sockets().unwrap().remove(0);
+    assert_ok!(member.join(["game", "lobby"]));
+
+    // Leaving the room frees up a slot
+    member.leave("game").unwrap();
+    let (_stx5, _srx5) = io.new_dummy_sock("/", "game").await;
+    assert_ok!(rx.try_recv().unwrap());
+    assert_eq!(io.within("game").sockets().unwrap().len(), 2);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+pub async fn room_capacity_concurrent_joins() {
+    const SOCKETS: usize = 32;
+    const CAPACITY: usize = 5;
+    let (_svc, io) = SocketIo::new_svc();
+    io.ns("/", || {});
+    io.room_meta("game").set_capacity(CAPACITY).unwrap();
+
+    let mut clients = Vec::new();
+    for _ in 0..SOCKETS {
+        let (stx, mut srx) = io.new_dummy_sock("/", ()).await;
+        assert_some!(srx.recv().await);
+        clients.push((stx, srx));
+    }
+
+    let barrier = Arc::new(Barrier::new(SOCKETS));
+    let tasks: Vec<_> = io
+        .sockets()
+        .unwrap()
+        .into_iter()
+        .map(|socket| {
+            let barrier = barrier.clone();
+            tokio::spawn(async move {
+                barrier.wait().await;
+                socket.join("game")
+            })
+        })
+        .collect();
+
+    let mut joined = 0;
+    for task in tasks {
+        match task.await.unwrap() {
+            Ok(()) => joined += 1,
+            Err(JoinError::RoomFull(room)) => assert_eq!(room, "game"),
+            Err(e) => panic!("unexpected error: {e}"),
+        }
+    }
+    assert_eq!(joined, CAPACITY);
+    assert_eq!(io.within("game").sockets().unwrap().len(), CAPACITY);
+}
+
+#[tokio::test]
+pub async fn room_capacity_join_rollback() {
+    let (_svc, io) = SocketIo::new_svc();
+    io.ns("/", || {});
+    io.room_meta("lobby").set_capacity(10).unwrap();
+    io.room_meta("game").set_capacity(1).unwrap();
+    let (_stx1, mut srx1) = io.new_dummy_sock("/", ()).await;
+    let (_stx2, mut srx2) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx1.recv().await);
+    assert_some!(srx2.recv().await);
+    let sockets = io.sockets().unwrap();
+    assert_ok!(sockets[0].join("game"));
+
+    // None of the rooms are joined when one of them is full
+    let res = sockets[1].join(["lobby", "chat", "game"]);
+    assert!(matches!(res, Err(JoinError::RoomFull(room)) if room == "game"));
+    assert!(sockets[1].rooms().unwrap().is_empty());
+    assert!(io.within("lobby").sockets().unwrap().is_empty());
+}
+
+#[tokio::test]
+pub async fn room_capacity_apart_from_user_meta() {
+    let (_svc, io) = SocketIo::new_svc();
+    io.ns("/", || {});
+    let meta = io.room_meta("game");
+    assert_ok!(meta.set_capacity(2));
+    assert_ok!(meta.set("capacity", "alice"));
+
+    // The capacity is not a user metadata
+    assert_eq!(meta.capacity().unwrap(), Some(2));
+    assert_eq!(meta.get("capacity").unwrap(), Some(json!("alice")));
+    assert_eq!(meta.get(CAPACITY_KEY).unwrap(), None);
+    assert_eq!(meta.all().unwrap().len(), 1);
+
+    let res = meta.set(CAPACITY_KEY, 10);
+    assert!(matches!(res, Err(RoomMetaError::ReservedKey(key)) if key == CAPACITY_KEY));
+    let res = meta.remove(CAPACITY_KEY);
+    assert!(matches!(res, Err(RoomMetaError::ReservedKey(_))));
+    assert_eq!(meta.capacity().unwrap(), Some(2));
+
+    assert_ok!(meta.remove_capacity());
+    assert_eq!(meta.capacity().unwrap(), None);
+    assert_eq!(meta.get("capacity").unwrap(), Some(json!("alice")));
+}
+
+#[tokio::test]
+pub async fn room_capacity_enforced_on_operator_join() {
+    let (_svc, io) = SocketIo::new_svc();
+    io.ns("/", || {});
+    io.room_meta("game").set_capacity(2).unwrap();
+    let mut clients = Vec::new();
+    for _ in 0..3 {
+        let (stx, mut srx) = io.new_dummy_sock("/", ()).await;
+        assert_some!(srx.recv().await);
+        clients.push((stx, srx));
+    }
+
+    // The sockets that don't fit in the room are skipped
+    let errors = io.clone().join("game").unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert!(matches!(&errors[0], JoinError::RoomFull(room) if room == "game"));
+    assert_eq!(io.within("game").sockets().unwrap().len(), 2);
+}
@@ -0,0 +1,80 @@
+//! Tests for the request/response RPCs over the acknowledgements
+mod utils;
+
+use engineioxide::Packet::*;
+use serde::Deserialize;
+use socketioxide::{
+    extract::{Data, SocketRef},
+    rpc::RpcError,
+    SocketIo,
+};
+use tokio::sync::mpsc;
+
+use utils::timeout_rcv;
+
+#[derive(Deserialize)]
+struct Divide {
+    a: i64,
+    b: i64,
+}
+
+async fn setup() -> (
+    mpsc::Sender<engineioxide::Packet>,
+    mpsc::Receiver<engineioxide::Packet>,
+) {
+    let (_, io) = SocketIo::new_svc();
+    io.ns("/", |socket: SocketRef| {
+        socket.on_rpc("divide", |Data(req): Data<Divide>| async move {
+            match req.b {
+                0 => Err(RpcError::new("division_by_zero", "cannot divide by zero")),
+                b => Ok(req.a / b),
+            }
+        });
+        socket.on_rpc(
+            "greet",
+            |socket: SocketRef, Data(name): Data<String>| async move {
+                Ok::<_, RpcError>(format!("hello {name} from {}", socket.ns()))
+            },
+        );
+    });
+    let (stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await); // NS connect packet
+    (stx, srx)
+}
+
+#[tokio::test]
+pub async fn successful_rpc() {
+    let (stx, mut srx) = setup().await;
+
+    assert_ok!(stx.try_send(Message("21[\"divide\",{\"a\":6,\"b\":3}]".into())));
+    assert_eq!(
+        timeout_rcv(&mut srx).await,
+        Message("31[{\"result\":2}]".into())
+    );
+    assert_ok!(stx.try_send(Message("22[\"greet\",\"Bob\"]".into())));
+    assert_eq!(
+        timeout_rcv(&mut srx).await,
+        Message("32[{\"result\":\"hello Bob from /\"}]".into())
+    );
+}
+
+#[tokio::test]
+pub async fn failing_rpc() {
+    let (stx, mut srx) = setup().await;
+
+    assert_ok!(stx.try_send(Message("21[\"divide\",{\"a\":6,\"b\":0}]".into())));
+    assert_eq!(
+        timeout_rcv(&mut srx).await,
+        Message(
+            "31[{\"error\":{\"code\":\"division_by_zero\",\"message\":\"cannot divide by zero\"}}]"
+                .into()
+        )
+    );
+
+    // The request can't be deserialized
+    assert_ok!(stx.try_send(Message("22[\"divide\",\"6/0\"]".into())));
+    assert_eq!(
+        timeout_rcv(&mut srx).await,
+        Message("32[{\"error\":{\"code\":\"bad_request\",\"message\":\"bad request\"}}]".into())
+    );
+}
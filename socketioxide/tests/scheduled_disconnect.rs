@@ -0,0 +1,67 @@
+//! Tests for scheduled disconnections
+mod utils;
+
+use std::time::Duration;
+
+use engineioxide::Packet::*;
+use socketioxide::{extract::SocketRef, socket::DisconnectReason, SocketIo};
+use tokio::sync::mpsc;
+
+use utils::try_timeout_rcv;
+
+fn attach_handler(io: &SocketIo) -> mpsc::Receiver<DisconnectReason> {
+    let (tx, rx) = mpsc::channel::<DisconnectReason>(1);
+    io.ns("/", move |socket: SocketRef| {
+        let tx = tx.clone();
+        socket.on_disconnect(move |reason: DisconnectReason| tx.try_send(reason).unwrap());
+        socket.on("schedule", |socket: SocketRef| {
+            assert_ok!(socket.schedule_disconnect(Duration::from_millis(50), "maintenance"));
+        });
+        socket.on("cancel", |socket: SocketRef| {
+            assert!(socket.cancel_scheduled_disconnect());
+            assert!(!socket.cancel_scheduled_disconnect());
+        });
+    });
+    rx
+}
+
+#[tokio::test]
+pub async fn scheduled_disconnect() {
+    let (_svc, io) = SocketIo::new_svc();
+    let mut rx = attach_handler(&io);
+
+    let (stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await); // NS connect packet
+    assert_ok!(stx.try_send(Message("2[\"schedule\"]".into())));
+
+    let msg = assert_some!(try_timeout_rcv(&mut srx, 20).await);
+    assert_eq!(
+        msg,
+        Message("2[\"disconnect_scheduled\",\"maintenance\",50]".into())
+    );
+    // Not disconnected before the delay
+    assert!(try_timeout_rcv(&mut rx, 20).await.is_none());
+
+    let reason = assert_some!(try_timeout_rcv(&mut rx, 100).await);
+    assert_eq!(reason, DisconnectReason::ServerNSDisconnect);
+    assert_eq!(
+        assert_some!(try_timeout_rcv(&mut srx, 20).await),
+        Message("1".into())
+    );
+}
+
+#[tokio::test]
+pub async fn cancel_scheduled_disconnect() {
+    let (_svc, io) = SocketIo::new_svc();
+    let mut rx = attach_handler(&io);
+
+    let (stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await); // NS connect packet
+    assert_ok!(stx.try_send(Message("2[\"schedule\"]".into())));
+    assert_some!(try_timeout_rcv(&mut srx, 20).await); // disconnect_scheduled event
+    assert_ok!(stx.try_send(Message("2[\"cancel\"]".into())));
+
+    assert!(try_timeout_rcv(&mut rx, 100).await.is_none());
+    assert!(try_timeout_rcv(&mut srx, 20).await.is_none());
+    assert_eq!(io.sockets().unwrap().len(), 1);
+}
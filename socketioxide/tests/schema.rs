@@ -0,0 +1,98 @@
+//! Tests for the validation of the outgoing payloads against the registered schemas
+#![cfg(feature = "schema")]
+mod utils;
+
+use std::time::Duration;
+
+use engineioxide::Packet::*;
+use serde_json::json;
+use socketioxide::{extract::SocketRef, schema::SchemaPolicy, BroadcastError, SendError, SocketIo};
+use tokio::sync::mpsc;
+
+async fn recv_all(srx: &mut mpsc::Receiver<engineioxide::Packet>) -> Vec<engineioxide::Packet> {
+    let mut packets = Vec::new();
+    while let Ok(Some(packet)) = tokio::time::timeout(Duration::from_millis(20), srx.recv()).await {
+        packets.push(packet);
+    }
+    packets
+}
+
+fn score_schema() -> serde_json::Value {
+    json!({
+        "type": "object",
+        "properties": { "points": { "type": "integer", "minimum": 0 } },
+        "required": ["points"],
+    })
+}
+
+#[tokio::test]
+pub async fn mismatched_payload_is_rejected() {
+    let (_, io) = SocketIo::new_svc();
+    assert_ok!(io.register_schema("score", score_schema()));
+    io.ns("/", || {});
+
+    let (_stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await);
+    let socket = io.sockets().unwrap().remove(0);
+
+    let res = socket.emit("score", json!({ "points": "3" }));
+    assert!(matches!(res, Err(SendError::Schema(_))), "{res:?}");
+    let res = socket.emit("score", json!({}));
+    assert!(matches!(res, Err(SendError::Schema(_))), "{res:?}");
+    let err = socket.emit("score", json!({ "points": "3" })).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "payload of event \"score\" does not match its schema: \
+         /points: expected type \"integer\", got \"3\""
+    );
+    let res = io.emit("score", json!({ "points": -1 }));
+    assert!(matches!(res, Err(BroadcastError::Schema(_))), "{res:?}");
+    assert_eq!(recv_all(&mut srx).await, []);
+
+    // The valid payloads and the events without a schema are sent
+    assert_ok!(socket.emit("score", json!({ "points": 3 })));
+    assert_ok!(io.emit("other", "anything"));
+    assert_eq!(
+        recv_all(&mut srx).await,
+        [
+            Message("2[\"score\",{\"points\":3}]".into()),
+            Message("2[\"other\",\"anything\"]".into()),
+        ]
+    );
+}
+
+#[tokio::test]
+pub async fn mismatched_payload_is_sent_in_log_mode() {
+    let (_, io) = SocketIo::builder()
+        .schema_policy(SchemaPolicy::Log)
+        .build_svc();
+    assert_ok!(io.register_schema("score", score_schema()));
+    io.ns("/", |socket: SocketRef| {
+        socket.emit("score", json!({ "points": "3" })).unwrap();
+    });
+
+    let (_stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await);
+    assert_eq!(
+        recv_all(&mut srx).await,
+        [Message("2[\"score\",{\"points\":\"3\"}]".into())]
+    );
+}
+
+#[tokio::test]
+pub async fn unsupported_keyword_is_rejected() {
+    let (_, io) = SocketIo::new_svc();
+    assert_ok!(io.register_schema("name", json!({ "type": "string" })));
+    let err = io
+        .register_schema("name", json!({ "type": "string", "pattern": "^[a-z]+$" }))
+        .unwrap_err();
+    assert_eq!(err.path, "/pattern");
+    io.ns("/", || {});
+
+    // The previous schema is kept
+    let (_stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await);
+    let res = io.emit("name", 1);
+    assert!(matches!(res, Err(BroadcastError::Schema(_))), "{res:?}");
+    assert_ok!(io.emit("name", "ABC"));
+}
@@ -0,0 +1,68 @@
+//! Tests for the detection of the connections made over a secure transport
+mod fixture;
+
+use std::time::Duration;
+
+use fixture::spawn_server;
+use futures_util::{SinkExt, StreamExt};
+use socketioxide::{
+    extract::{Secure, SocketRef},
+    SocketIo,
+};
+use tokio_tungstenite::tungstenite::{client::IntoClientRequest, Message};
+
+/// Connects to the default namespace with the given `X-Forwarded-Proto` header
+/// and returns the `secure` event emitted by the server.
+async fn connect(port: u16, forwarded_proto: Option<&str>) -> Message {
+    let mut req = format!("ws://127.0.0.1:{port}/socket.io/?EIO=4&transport=websocket")
+        .into_client_request()
+        .unwrap();
+    if let Some(proto) = forwarded_proto {
+        req.headers_mut()
+            .insert("X-Forwarded-Proto", proto.parse().unwrap());
+    }
+    let (mut ws, _) = tokio_tungstenite::connect_async(req).await.unwrap();
+    ws.send(Message::Text("40{}".into())).await.unwrap();
+
+    let mut msgs = Vec::new();
+    // Engine.io open packet, socket.io connect packet and the emitted event
+    for _ in 0..3 {
+        let msg = tokio::time::timeout(Duration::from_millis(500), ws.next()).await;
+        msgs.push(msg.unwrap().unwrap().unwrap());
+    }
+    msgs.pop().unwrap()
+}
+
+async fn create_server(port: u16, trust_forwarded_proto: bool) {
+    let (svc, io) = SocketIo::builder()
+        .trust_forwarded_proto(trust_forwarded_proto)
+        .build_svc();
+    io.ns("/", |socket: SocketRef, Secure(secure): Secure| {
+        assert_eq!(socket.is_secure(), secure);
+        socket.emit("secure", secure).unwrap();
+    });
+    spawn_server(port, svc).await;
+}
+
+#[tokio::test]
+pub async fn secure_from_trusted_forwarded_proto() {
+    const PORT: u16 = 12378;
+    create_server(PORT, true).await;
+
+    let secure = Message::Text("42[\"secure\",true]".into());
+    let insecure = Message::Text("42[\"secure\",false]".into());
+    assert_eq!(connect(PORT, Some("https")).await, secure);
+    assert_eq!(connect(PORT, Some("https, http")).await, secure);
+    assert_eq!(connect(PORT, Some("http")).await, insecure);
+    assert_eq!(connect(PORT, None).await, insecure);
+}
+
+#[tokio::test]
+pub async fn forwarded_proto_untrusted_by_default() {
+    const PORT: u16 = 12379;
+    create_server(PORT, false).await;
+
+    let insecure = Message::Text("42[\"secure\",false]".into());
+    assert_eq!(connect(PORT, Some("https")).await, insecure);
+    assert_eq!(connect(PORT, None).await, insecure);
+}
@@ -0,0 +1,94 @@
+//! Tests for the engine.io session id shared by the namespace sockets of a client
+mod fixture;
+mod utils;
+
+use std::time::Duration;
+
+use engineioxide::sid::Sid;
+use fixture::{create_ws_connection, spawn_server};
+use futures_util::{SinkExt, StreamExt};
+use socketioxide::{
+    extract::{EngineSessionId, SocketRef},
+    SocketIo,
+};
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::Message;
+
+async fn recv<T>(rx: &mut mpsc::Receiver<T>) -> T {
+    tokio::time::timeout(Duration::from_millis(500), rx.recv())
+        .await
+        .unwrap()
+        .unwrap()
+}
+
+#[tokio::test]
+pub async fn namespaces_share_session_id() {
+    const PORT: u16 = 12364;
+    let (svc, io) = SocketIo::new_svc();
+    let (tx, mut rx) = mpsc::channel::<(&'static str, Sid, Sid)>(4);
+
+    let tx1 = tx.clone();
+    io.ns(
+        "/",
+        move |socket: SocketRef, EngineSessionId(session): EngineSessionId| {
+            assert_ok!(tx1.try_send(("/", socket.id, session)));
+        },
+    );
+    io.ns(
+        "/admin",
+        move |socket: SocketRef, session: EngineSessionId| {
+            assert_ok!(tx.try_send(("/admin", socket.id, *session)));
+            socket.on(
+                "ping",
+                move |socket: SocketRef, session: EngineSessionId| {
+                    assert_ok!(tx.try_send(("ping", socket.id, *session)));
+                },
+            );
+        },
+    );
+    spawn_server(PORT, svc).await;
+
+    let mut ws = create_ws_connection(PORT).await;
+    assert_ok!(ws.send(Message::Text("40/admin,{}".to_string())).await);
+
+    let (root_ns, root_sid, root_session) = recv(&mut rx).await;
+    let (admin_ns, admin_sid, admin_session) = recv(&mut rx).await;
+    assert_eq!((root_ns, admin_ns), ("/", "/admin"));
+
+    assert_eq!(root_session, admin_session);
+    assert_ne!(root_sid, admin_sid);
+    assert_ne!(root_sid, root_session);
+    assert_eq!(
+        io.of("/admin")
+            .unwrap()
+            .get_socket(admin_sid)
+            .unwrap()
+            .session_id(),
+        admin_session
+    );
+
+    // The events are routed to the socket of their namespace
+    assert_ok!(
+        ws.send(Message::Text("42/admin,[\"ping\"]".to_string()))
+            .await
+    );
+    assert_eq!(recv(&mut rx).await, ("ping", admin_sid, admin_session));
+
+    // The connect packets sent to the client carry the namespace socket ids
+    let mut msgs = Vec::new();
+    while msgs.len() < 3 {
+        let msg = tokio::time::timeout(Duration::from_millis(500), ws.next()).await;
+        match assert_some!(assert_ok!(msg)) {
+            Ok(Message::Text(msg)) => msgs.push(msg),
+            msg => panic!("unexpected message: {msg:?}"),
+        }
+    }
+    assert!(
+        msgs.contains(&format!("40{{\"sid\":\"{root_sid}\"}}")),
+        "{msgs:?}"
+    );
+    assert!(
+        msgs.contains(&format!("40/admin,{{\"sid\":\"{admin_sid}\"}}")),
+        "{msgs:?}"
+    );
+}
@@ -0,0 +1,71 @@
+//! Tests for the per-socket config overrides
+mod utils;
+
+use std::time::Duration;
+
+use serde_json::{json, Value};
+use socketioxide::{
+    extract::{Data, SocketRef},
+    socket::SocketConfig,
+    JoinError, SocketIo,
+};
+use tokio::sync::mpsc;
+
+#[tokio::test]
+pub async fn override_applies_to_the_socket_only() {
+    let (_svc, io) = SocketIo::builder()
+        .ack_timeout(Duration::from_secs(5))
+        .max_rooms_per_socket(1)
+        .build_svc();
+    let (tx, mut rx) = mpsc::channel::<(bool, Duration, bool)>(2);
+    io.ns("/", move |socket: SocketRef, Data::<Value>(auth)| {
+        let premium = auth["premium"] == true;
+        if premium {
+            socket.configure(SocketConfig {
+                ack_timeout: Some(Duration::from_secs(30)),
+                max_rooms: Some(3),
+                ..Default::default()
+            });
+        }
+        let joined = socket.join(["room1", "room2"]);
+        assert!(joined.is_ok() || matches!(joined, Err(JoinError::TooManyRooms(1))));
+        tx.try_send((premium, socket.ack_timeout(), joined.is_ok()))
+            .unwrap();
+    });
+
+    let (_stx1, mut srx1) = io.new_dummy_sock("/", json!({ "premium": true })).await;
+    let (_stx2, mut srx2) = io.new_dummy_sock("/", json!({ "premium": false })).await;
+    assert_some!(srx1.recv().await); // NS connect packet
+    assert_some!(srx2.recv().await);
+
+    let mut res = vec![assert_some!(rx.recv().await), assert_some!(rx.recv().await)];
+    res.sort();
+    assert_eq!(
+        res,
+        [
+            (false, Duration::from_secs(5), false),
+            (true, Duration::from_secs(30), true)
+        ]
+    );
+}
+
+#[tokio::test]
+pub async fn unset_fields_keep_their_value() {
+    let (_svc, io) = SocketIo::new_svc();
+    let (tx, mut rx) = mpsc::channel::<Duration>(1);
+    io.ns("/", move |socket: SocketRef| {
+        socket.configure(SocketConfig {
+            ack_timeout: Some(Duration::from_secs(1)),
+            ..Default::default()
+        });
+        socket.configure(SocketConfig {
+            max_listeners: Some(10),
+            ..Default::default()
+        });
+        tx.try_send(socket.ack_timeout()).unwrap();
+    });
+
+    let (_stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await); // NS connect packet
+    assert_eq!(assert_some!(rx.recv().await), Duration::from_secs(1));
+}
@@ -0,0 +1,60 @@
+//! Tests for the in-memory socket groups
+mod utils;
+
+use std::time::Duration;
+
+use engineioxide::Packet::*;
+use socketioxide::{group::SocketGroup, SocketIo};
+
+use utils::try_timeout_rcv;
+
+#[tokio::test]
+pub async fn emit_skips_disconnected_members() {
+    let (_svc, io) = SocketIo::new_svc();
+    io.ns("/", || {});
+
+    let (_stx1, mut srx1) = io.new_dummy_sock("/", ()).await;
+    let (stx2, mut srx2) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx1.recv().await); // NS connect packet
+    assert_some!(srx2.recv().await);
+
+    let group: SocketGroup = io.sockets().unwrap().into_iter().collect();
+    assert_eq!(group.len(), 2);
+
+    // The second client disconnects from the namespace
+    assert_ok!(stx2.try_send(Message("1".into())));
+    tokio::time::sleep(Duration::from_millis(10)).await;
+
+    assert_ok!(group.emit("msg", "hello"));
+    assert_eq!(
+        assert_some!(try_timeout_rcv(&mut srx1, 20).await),
+        Message("2[\"msg\",\"hello\"]".into())
+    );
+    assert!(try_timeout_rcv(&mut srx2, 20).await.is_none());
+    assert_eq!(group.len(), 1);
+}
+
+#[tokio::test]
+pub async fn disconnect_all_members() {
+    let (_svc, io) = SocketIo::new_svc();
+    io.ns("/", || {});
+
+    let (_stx1, mut srx1) = io.new_dummy_sock("/", ()).await;
+    let (_stx2, mut srx2) = io.new_dummy_sock("/", ()).await;
+    let (_stx3, mut srx3) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx1.recv().await); // NS connect packet
+    assert_some!(srx2.recv().await);
+    assert_some!(srx3.recv().await);
+
+    let group = SocketGroup::new();
+    let sockets = io.sockets().unwrap();
+    assert!(group.insert(sockets[0].clone()));
+    assert!(!group.insert(sockets[0].clone()));
+    assert!(group.insert(sockets[1].clone()));
+    let outsider = sockets[2].id;
+
+    assert_ok!(group.disconnect());
+    assert!(group.is_empty());
+    let remaining: Vec<_> = io.sockets().unwrap().into_iter().map(|s| s.id).collect();
+    assert_eq!(remaining, [outsider]);
+}
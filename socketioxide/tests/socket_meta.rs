@@ -0,0 +1,45 @@
+//! Tests for the routing of the broadcasts by socket metadata
+mod utils;
+
+use engineioxide::Packet::*;
+use socketioxide::{
+    extract::{Data, SocketRef},
+    SocketIo,
+};
+
+use utils::try_timeout_rcv;
+
+#[tokio::test]
+pub async fn where_meta_routing() {
+    let (_svc, io) = SocketIo::new_svc();
+    io.ns("/", |socket: SocketRef, Data::<String>(region)| {
+        socket.set_meta("region", region).unwrap();
+    });
+
+    let (_stx1, mut srx1) = io.new_dummy_sock("/", "eu").await;
+    let (_stx2, mut srx2) = io.new_dummy_sock("/", "us").await;
+    let (_stx3, mut srx3) = io.new_dummy_sock("/", "eu").await;
+    for srx in [&mut srx1, &mut srx2, &mut srx3] {
+        assert_some!(srx.recv().await); // NS connect packet
+    }
+
+    assert_ok!(io.where_meta("region", "eu").emit("test", "eu"));
+    assert_eq!(
+        assert_some!(try_timeout_rcv(&mut srx1, 20).await),
+        Message("2[\"test\",\"eu\"]".into())
+    );
+    assert_eq!(
+        assert_some!(try_timeout_rcv(&mut srx3, 20).await),
+        Message("2[\"test\",\"eu\"]".into())
+    );
+    assert!(try_timeout_rcv(&mut srx2, 20).await.is_none());
+
+    let sockets = io.where_meta("region", "us").sockets().unwrap();
+    assert_eq!(sockets.len(), 1);
+    assert_eq!(sockets[0].meta("region").as_deref(), Some("us"));
+    assert!(io
+        .where_meta("region", "asia")
+        .sockets()
+        .unwrap()
+        .is_empty());
+}
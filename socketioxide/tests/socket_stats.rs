@@ -0,0 +1,81 @@
+//! Tests for the throughput counters of the sockets
+mod utils;
+
+use std::time::Duration;
+
+use bytes::Bytes;
+use engineioxide::Packet::*;
+use socketioxide::{
+    extract::{AckSender, SocketRef},
+    socket::SocketStats,
+    SocketIo,
+};
+use tokio::sync::mpsc;
+
+#[tokio::test]
+pub async fn stats_count_the_traffic() {
+    let (_, io) = SocketIo::new_svc();
+    let (tx, mut rx) = mpsc::channel::<SocketStats>(4);
+    io.ns("/", move |socket: SocketRef, stats: SocketStats| {
+        assert_eq!((stats.events_received, stats.events_sent), (0, 0));
+        socket.on("ping", |ack: AckSender| {
+            ack.send("pong").ok();
+        });
+        socket.on("bin", |socket: SocketRef| {
+            socket
+                .bin([Bytes::from_static(&[1, 2, 3])])
+                .emit("bin", serde_json::Value::Null)
+                .ok();
+        });
+        let tx = tx.clone();
+        socket.on("stats", move |stats: SocketStats| {
+            tx.try_send(stats).unwrap();
+        });
+    });
+
+    let (stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    // The connect packet is counted in the bytes sent
+    let mut bytes_out = packet_len(&assert_some!(srx.recv().await));
+
+    let sent = [
+        Message("21[\"ping\",1]".into()),
+        Message("51-[\"bin\",{\"_placeholder\":true,\"num\":0}]".into()),
+        Binary(Bytes::from_static(&[1, 2])),
+    ];
+    let bytes_in: usize = sent.iter().map(packet_len).sum();
+    for packet in sent {
+        assert_ok!(stx.try_send(packet));
+    }
+    io.emit("hello", "world").unwrap();
+
+    // The ack, the binary event with its attachment and the broadcast
+    for _ in 0..4 {
+        let packet = tokio::time::timeout(Duration::from_millis(100), srx.recv()).await;
+        bytes_out += packet_len(&assert_some!(assert_ok!(packet)));
+    }
+
+    let stats_event = Message("2[\"stats\"]".into());
+    let bytes_in = bytes_in + packet_len(&stats_event);
+    assert_ok!(stx.try_send(stats_event));
+    let stats = tokio::time::timeout(Duration::from_millis(100), rx.recv()).await;
+    let stats = assert_some!(assert_ok!(stats));
+    assert_eq!(
+        stats,
+        SocketStats {
+            events_received: 3,
+            events_sent: 2,
+            bytes_in: bytes_in as u64,
+            bytes_out: bytes_out as u64,
+        }
+    );
+    let socket = io.sockets().unwrap().pop().unwrap();
+    assert_eq!(socket.stats(), stats);
+}
+
+fn packet_len(packet: &engineioxide::Packet) -> usize {
+    match packet {
+        Message(msg) => msg.len(),
+        Binary(bin) => bin.len(),
+        p => panic!("unexpected packet {p:?}"),
+    }
+}
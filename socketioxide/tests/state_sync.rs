@@ -0,0 +1,92 @@
+//! Tests for the room documents synchronized with snapshots and patches
+mod utils;
+
+use std::time::Duration;
+
+use engineioxide::Packet::*;
+use serde_json::{json, Value};
+use socketioxide::{extract::SocketRef, SocketIo};
+use tokio::sync::mpsc;
+
+/// Receives an event packet and parses its arguments
+async fn recv_event(srx: &mut mpsc::Receiver<engineioxide::Packet>) -> Value {
+    match assert_some!(srx.recv().await) {
+        Message(msg) => serde_json::from_str(msg.strip_prefix('2').unwrap()).unwrap(),
+        packet => panic!("unexpected packet {packet:?}"),
+    }
+}
+
+#[tokio::test]
+pub async fn late_joiner_receives_snapshot_and_patches() {
+    let (_svc, io) = SocketIo::new_svc();
+    io.ns("/", |socket: SocketRef| {
+        socket.on("join", |socket: SocketRef| {
+            socket.join("board").unwrap();
+        });
+    });
+
+    let (stx1, mut srx1) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx1.recv().await);
+    assert_ok!(stx1.try_send(Message("2[\"join\"]".into())));
+    tokio::time::sleep(Duration::from_millis(10)).await;
+
+    io.to("board")
+        .patch(json!({ "title": "todo", "items": { "a": 1 } }))
+        .unwrap();
+    io.to("board")
+        .patch(json!({ "items": { "a": null, "b": 2 } }))
+        .unwrap();
+    assert_eq!(
+        recv_event(&mut srx1).await,
+        json!(["state:patch", { "room": "board", "patch": { "title": "todo", "items": { "a": 1 } } }])
+    );
+    assert_eq!(
+        recv_event(&mut srx1).await,
+        json!(["state:patch", { "room": "board", "patch": { "items": { "a": null, "b": 2 } } }])
+    );
+
+    // The late joiner receives the current document, then the next patches
+    let (stx2, mut srx2) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx2.recv().await);
+    assert_ok!(stx2.try_send(Message("2[\"join\"]".into())));
+    assert_eq!(
+        recv_event(&mut srx2).await,
+        json!(["state:snapshot", { "room": "board", "state": { "title": "todo", "items": { "b": 2 } } }])
+    );
+
+    io.to("board").patch(json!({ "title": "done" })).unwrap();
+    let patch = json!(["state:patch", { "room": "board", "patch": { "title": "done" } }]);
+    assert_eq!(recv_event(&mut srx1).await, patch);
+    assert_eq!(recv_event(&mut srx2).await, patch);
+    assert_eq!(
+        io.room_state("board"),
+        Some(json!({ "title": "done", "items": { "b": 2 } }))
+    );
+
+    // Without a document, no snapshot is sent
+    assert!(io.clear_room_state("board").is_some());
+    assert_ok!(stx2.try_send(Message("2[\"join\"]".into())));
+    let res = tokio::time::timeout(Duration::from_millis(20), srx2.recv()).await;
+    assert!(res.is_err());
+}
+
+#[tokio::test]
+pub async fn operator_joiner_receives_snapshot() {
+    let (_svc, io) = SocketIo::new_svc();
+    io.ns("/", || {});
+    io.to("board").patch(json!({ "title": "todo" })).unwrap();
+
+    let (_stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await);
+    assert_ok!(io.clone().join("board"));
+    assert_eq!(
+        recv_event(&mut srx).await,
+        json!(["state:snapshot", { "room": "board", "state": { "title": "todo" } }])
+    );
+
+    io.to("board").patch(json!({ "title": "done" })).unwrap();
+    assert_eq!(
+        recv_event(&mut srx).await,
+        json!(["state:patch", { "room": "board", "patch": { "title": "done" } }])
+    );
+}
@@ -0,0 +1,60 @@
+//! Tests for moving a socket between namespaces
+mod utils;
+
+use engineioxide::Packet::*;
+use socketioxide::{extract::SocketRef, socket::DisconnectReason, SocketIo, SwitchNamespaceError};
+use tokio::sync::mpsc;
+
+use utils::try_timeout_rcv;
+
+#[tokio::test]
+pub async fn switch_namespace() {
+    let (_svc, io) = SocketIo::new_svc();
+    let (tx, mut rx) = mpsc::channel::<String>(4);
+    let lobby_tx = tx.clone();
+    io.ns("/", move |socket: SocketRef| {
+        let tx = lobby_tx.clone();
+        socket.on_disconnect(move |reason: DisconnectReason| {
+            tx.try_send(format!("disconnect: {reason:?}")).unwrap();
+        });
+        socket.on("ready", |socket: SocketRef| {
+            socket.join("lobby").unwrap();
+            assert!(matches!(
+                socket.switch_namespace("/unknown"),
+                Err(SwitchNamespaceError::InvalidNamespace(path)) if path == "/unknown"
+            ));
+            assert!(socket.connected());
+            socket.switch_namespace("/game").unwrap();
+        });
+    });
+    io.ns("/game", move |socket: SocketRef| {
+        tx.try_send(format!("connect: {}", socket.ns())).unwrap();
+    });
+
+    let (stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await); // NS connect packet
+
+    assert_ok!(stx.try_send(Message("2[\"ready\"]".into())));
+    assert_eq!(
+        assert_some!(try_timeout_rcv(&mut srx, 20).await),
+        Message("1".into())
+    );
+    let msg = assert_some!(try_timeout_rcv(&mut srx, 20).await);
+    assert!(matches!(msg, Message(msg) if msg.starts_with("0/game,")));
+
+    assert_eq!(
+        assert_some!(try_timeout_rcv(&mut rx, 20).await),
+        "disconnect: ServerNSDisconnect"
+    );
+    assert_eq!(
+        assert_some!(try_timeout_rcv(&mut rx, 20).await),
+        "connect: /game"
+    );
+
+    // The engine.io session is kept and the socket is only connected to the target namespace
+    assert!(io.sockets().unwrap().is_empty());
+    assert!(io.within("lobby").sockets().unwrap().is_empty());
+    let sockets = io.of("/game").unwrap().sockets().unwrap();
+    assert_eq!(sockets.len(), 1);
+    assert!(sockets[0].connected());
+}
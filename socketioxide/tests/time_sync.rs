@@ -0,0 +1,74 @@
+//! Tests for the server time emission and the round-trip time measurement
+mod utils;
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use engineioxide::Packet::*;
+use serde_json::Value;
+use socketioxide::{clock::ServerTime, extract::SocketRef, SocketIo};
+use tokio::sync::mpsc;
+
+use utils::timeout_rcv;
+
+/// Parses a `server:time` event packet and returns its ack id and payload.
+fn parse_time(msg: engineioxide::Packet) -> (String, ServerTime) {
+    let Message(msg) = msg else {
+        panic!("expected a message, got {msg:?}");
+    };
+    let (id, data) = msg.split_once('[').unwrap();
+    let data: Value = serde_json::from_str(&format!("[{data}")).unwrap();
+    assert_eq!(data[0], "server:time");
+    (
+        id.to_string(),
+        serde_json::from_value(data[1].clone()).unwrap(),
+    )
+}
+
+#[tokio::test]
+pub async fn measure_rtt_round_trip() {
+    let (_svc, io) = SocketIo::new_svc();
+    let (tx, mut rx) = mpsc::channel(1);
+    io.ns("/", move |socket: SocketRef| {
+        let tx = tx.clone();
+        tokio::spawn(async move {
+            tx.send(socket.measure_rtt().await.unwrap()).await.unwrap();
+        });
+    });
+
+    let (stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    timeout_rcv(&mut srx).await; // NS connect packet
+
+    let (id, time) = parse_time(timeout_rcv(&mut srx).await);
+    assert_eq!(id, "21");
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+    assert!(now.as_millis() as u64 - time.unix < 1000);
+
+    tokio::time::sleep(Duration::from_millis(30)).await;
+    assert_ok!(stx.try_send(Message("31[]".into())));
+    let rtt = timeout_rcv(&mut rx).await;
+    assert!(rtt >= Duration::from_millis(30));
+    assert!(rtt < Duration::from_millis(200));
+}
+
+#[tokio::test]
+pub async fn time_sync_emits_periodically() {
+    let (_svc, io) = SocketIo::builder()
+        .time_sync(Duration::from_millis(30))
+        .build_svc();
+    io.ns("/", || {});
+
+    let (_stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    timeout_rcv(&mut srx).await; // NS connect packet
+
+    let (id, first) = parse_time(timeout_rcv(&mut srx).await);
+    assert_eq!(id, "2");
+    let (_, second) = parse_time(timeout_rcv(&mut srx).await);
+    assert!(second.monotonic >= first.monotonic + 20);
+    assert!(second.unix >= first.unix);
+
+    let socket = io.sockets().unwrap().remove(0);
+    assert!(socket.stop_time_sync());
+    assert!(tokio::time::timeout(Duration::from_millis(60), srx.recv())
+        .await
+        .is_err());
+}
@@ -0,0 +1,83 @@
+//! Tests for the atomic join of the rooms with a maximum size
+mod utils;
+
+use std::sync::Arc;
+
+use socketioxide::{lifecycle::LifecycleEvent, SocketIo};
+use tokio::sync::{mpsc, Barrier};
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+pub async fn concurrent_try_join_respects_max_size() {
+    const SOCKETS: usize = 32;
+    const MAX: usize = 5;
+    let (_svc, io) = SocketIo::new_svc();
+    io.ns("/", || {});
+
+    let mut clients = Vec::new();
+    for _ in 0..SOCKETS {
+        let (stx, mut srx) = io.new_dummy_sock("/", ()).await;
+        assert_some!(srx.recv().await);
+        clients.push((stx, srx));
+    }
+
+    let barrier = Arc::new(Barrier::new(SOCKETS));
+    let tasks: Vec<_> = io
+        .sockets()
+        .unwrap()
+        .into_iter()
+        .map(|socket| {
+            let barrier = barrier.clone();
+            tokio::spawn(async move {
+                barrier.wait().await;
+                socket.try_join("room", MAX).unwrap()
+            })
+        })
+        .collect();
+
+    let mut joined = 0;
+    for task in tasks {
+        joined += task.await.unwrap() as usize;
+    }
+    assert_eq!(joined, MAX);
+    assert_eq!(io.within("room").sockets().unwrap().len(), MAX);
+}
+
+#[tokio::test]
+pub async fn try_join_member_and_capacity() {
+    let (_svc, io) = SocketIo::new_svc();
+    io.ns("/", || {});
+    let (_stx1, mut srx1) = io.new_dummy_sock("/", ()).await;
+    let (_stx2, mut srx2) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx1.recv().await);
+    assert_some!(srx2.recv().await);
+    let sockets = io.sockets().unwrap();
+
+    assert!(sockets[0].try_join("room", 1).unwrap());
+    // Already a member
+    assert!(sockets[0].try_join("room", 1).unwrap());
+    assert!(!sockets[1].try_join("room", 1).unwrap());
+    assert_eq!(sockets[1].rooms().unwrap().len(), 0);
+
+    // The room capacity is lower than the max size
+    io.room_meta("other").set_capacity(1).unwrap();
+    assert!(sockets[0].try_join("other", 10).unwrap());
+    assert!(!sockets[1].try_join("other", 10).unwrap());
+}
+
+#[tokio::test]
+pub async fn try_join_member_no_side_effects() {
+    let (_svc, io) = SocketIo::new_svc();
+    let (tx, mut rx) = mpsc::channel::<LifecycleEvent>(100);
+    io.on_event(move |event| tx.try_send(event).unwrap());
+    io.ns("/", || {});
+    let (_stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await); // NS connect packet
+    let socket = io.sockets().unwrap().pop().unwrap();
+
+    assert!(socket.try_join("room", 1).unwrap());
+    assert!(socket.try_join("room", 1).unwrap());
+    let joins = std::iter::from_fn(|| rx.try_recv().ok())
+        .filter(|event| matches!(event, LifecycleEvent::Join { .. }))
+        .count();
+    assert_eq!(joins, 1);
+}
@@ -0,0 +1,51 @@
+//! Tests for the typed events
+
+use engineioxide::Packet as EioPacket;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use socketioxide::{
+    extract::{Data, SocketRef},
+    packet::Packet,
+    typed::TypedEvent,
+    SocketIo,
+};
+
+use utils::timeout_rcv;
+mod utils;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Ping {
+    count: u32,
+    label: String,
+}
+impl TypedEvent for Ping {
+    const NAME: &'static str = "ping";
+}
+
+fn create_msg(event: &str, data: serde_json::Value) -> EioPacket {
+    let packet: String = Packet::event("/", event, data).into();
+    EioPacket::Message(packet.into())
+}
+
+#[tokio::test]
+pub async fn typed_event_round_trip() {
+    let (_, io) = SocketIo::new_svc();
+    io.ns("/", |socket: SocketRef| {
+        socket.on(Ping::NAME, |socket: SocketRef, Data::<Ping>(ping)| {
+            let ping = Ping {
+                count: ping.count + 1,
+                label: ping.label,
+            };
+            assert_ok!(socket.emit_typed(ping));
+        });
+    });
+
+    let (stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    timeout_rcv(&mut srx).await;
+
+    let ping = json!({ "count": 1, "label": "foo" });
+    assert_ok!(stx.try_send(create_msg("ping", ping)));
+
+    let pong = json!({ "count": 2, "label": "foo" });
+    assert_eq!(timeout_rcv(&mut srx).await, create_msg("ping", pong));
+}
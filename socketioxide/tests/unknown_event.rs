@@ -0,0 +1,75 @@
+//! Tests for the unknown event policies
+mod utils;
+
+use engineioxide::Packet::*;
+use socketioxide::{
+    extract::{AckSender, SocketRef},
+    SocketIo, UnknownEventPolicy,
+};
+use tokio::sync::mpsc;
+
+use utils::try_timeout_rcv;
+
+async fn create_socket(
+    policy: UnknownEventPolicy,
+) -> (
+    mpsc::Sender<engineioxide::Packet>,
+    mpsc::Receiver<engineioxide::Packet>,
+) {
+    let (_svc, io) = SocketIo::builder().unknown_event_policy(policy).build_svc();
+    io.ns("/", |socket: SocketRef| {
+        socket.on("known", |ack: AckSender| ack.send("ok").unwrap());
+    });
+    let (stx, mut srx) = io.new_dummy_sock("/", ()).await;
+    assert_some!(srx.recv().await); // NS connect packet
+    (stx, srx)
+}
+
+#[tokio::test]
+pub async fn unknown_event_ignore() {
+    let (stx, mut srx) = create_socket(UnknownEventPolicy::default()).await;
+
+    assert_ok!(stx.try_send(Message("21[\"unknown\"]".into())));
+    assert!(try_timeout_rcv(&mut srx, 20).await.is_none());
+
+    assert_ok!(stx.try_send(Message("22[\"known\"]".into())));
+    let msg = assert_some!(try_timeout_rcv(&mut srx, 20).await);
+    assert_eq!(msg, Message("32[\"ok\"]".into()));
+}
+
+#[tokio::test]
+pub async fn unknown_event_log() {
+    let (stx, mut srx) = create_socket(UnknownEventPolicy::Log).await;
+
+    assert_ok!(stx.try_send(Message("21[\"unknown\"]".into())));
+    assert!(try_timeout_rcv(&mut srx, 20).await.is_none());
+
+    assert_ok!(stx.try_send(Message("22[\"known\"]".into())));
+    let msg = assert_some!(try_timeout_rcv(&mut srx, 20).await);
+    assert_eq!(msg, Message("32[\"ok\"]".into()));
+}
+
+#[tokio::test]
+pub async fn unknown_event_error_ack() {
+    let (stx, mut srx) = create_socket(UnknownEventPolicy::ErrorAck).await;
+
+    assert_ok!(stx.try_send(Message("21[\"unknown\"]".into())));
+    let msg = assert_some!(try_timeout_rcv(&mut srx, 20).await);
+    assert_eq!(msg, Message("31[{\"code\":\"unknown_event\"}]".into()));
+
+    // Binary events are also handled
+    assert_ok!(stx.try_send(Message(
+        "51-2[\"unknown\",{\"_placeholder\":true,\"num\":0}]".into()
+    )));
+    assert_ok!(stx.try_send(Binary(vec![1, 2, 3].into())));
+    let msg = assert_some!(try_timeout_rcv(&mut srx, 20).await);
+    assert_eq!(msg, Message("32[{\"code\":\"unknown_event\"}]".into()));
+
+    // Events without ack are ignored
+    assert_ok!(stx.try_send(Message("2[\"unknown\"]".into())));
+    assert!(try_timeout_rcv(&mut srx, 20).await.is_none());
+
+    assert_ok!(stx.try_send(Message("23[\"known\"]".into())));
+    let msg = assert_some!(try_timeout_rcv(&mut srx, 20).await);
+    assert_eq!(msg, Message("33[\"ok\"]".into()));
+}
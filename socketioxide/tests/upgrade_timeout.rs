@@ -0,0 +1,55 @@
+//! Tests for the websocket upgrade timeout
+mod fixture;
+mod utils;
+
+use std::time::Duration;
+
+use fixture::{create_polling_connection, send_req, spawn_server};
+use futures_util::{SinkExt, StreamExt};
+use socketioxide::{extract::SocketRef, SocketIo, TransportType};
+use tokio_tungstenite::tungstenite::Message;
+
+#[tokio::test]
+pub async fn slow_upgrade_fallbacks_to_polling() {
+    const PORT: u16 = 12362;
+    let (svc, io) = SocketIo::builder()
+        .upgrade_timeout(Duration::from_millis(100))
+        .build_svc();
+    io.ns("/", || {});
+    spawn_server(PORT, svc).await;
+
+    let sid = create_polling_connection(PORT).await;
+    let (mut ws, _) = tokio_tungstenite::connect_async(format!(
+        "ws://127.0.0.1:{PORT}/socket.io/?EIO=4&transport=websocket&sid={sid}"
+    ))
+    .await
+    .unwrap();
+
+    // Start the upgrade without ever sending the final upgrade packet
+    assert_ok!(ws.send(Message::Text("2probe".into())).await);
+    let msg = tokio::time::timeout(Duration::from_millis(50), ws.next()).await;
+    assert_eq!(
+        assert_ok!(assert_some!(assert_ok!(msg))),
+        Message::Text("3probe".into())
+    );
+
+    // The websocket connection is dropped after the upgrade timeout
+    let msg = tokio::time::timeout(Duration::from_millis(200), ws.next()).await;
+    assert!(matches!(
+        assert_ok!(msg),
+        None | Some(Err(_)) | Some(Ok(Message::Close(_)))
+    ));
+
+    // The socket is still connected and uses the polling transport
+    let socket: SocketRef = io.sockets().unwrap().pop().unwrap();
+    assert!(matches!(socket.transport_type(), TransportType::Polling));
+    assert_ok!(socket.emit("test", ()));
+    let body = send_req(
+        PORT,
+        format!("transport=polling&sid={sid}"),
+        http::Method::GET,
+        None,
+    )
+    .await;
+    assert!(body.ends_with(r#"42["test",null]"#), "{body}");
+}
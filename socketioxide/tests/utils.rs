@@ -1,5 +1,9 @@
 #![allow(dead_code)]
 
+use std::time::Duration;
+
+use tokio::sync::mpsc;
+
 #[macro_export]
 macro_rules! assert_ok {
     ($e:expr) => {
@@ -59,3 +63,25 @@ macro_rules! assert_some {
         }
     }};
 }
+
+/// Receives the next message of the channel, panicking if none is received within 200ms.
+pub async fn timeout_rcv<T: std::fmt::Debug>(srx: &mut mpsc::Receiver<T>) -> T {
+    let msg = tokio::time::timeout(Duration::from_millis(200), srx.recv()).await;
+    assert_some!(assert_ok!(msg))
+}
+
+/// Receives the next message of the channel, or `None` if none is received within `ms` milliseconds.
+pub async fn try_timeout_rcv<T>(srx: &mut mpsc::Receiver<T>, ms: u64) -> Option<T> {
+    tokio::time::timeout(Duration::from_millis(ms), srx.recv())
+        .await
+        .ok()
+        .flatten()
+}
+
+/// Receives the next engine.io message packet within 20ms, the other packets are ignored.
+pub async fn timeout_rcv_msg(srx: &mut mpsc::Receiver<engineioxide::Packet>) -> Option<String> {
+    match try_timeout_rcv(srx, 20).await {
+        Some(engineioxide::Packet::Message(msg)) => Some(msg.to_string()),
+        _ => None,
+    }
+}
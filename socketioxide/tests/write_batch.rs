@@ -0,0 +1,85 @@
+//! Tests for the websocket write batching
+mod fixture;
+mod utils;
+
+use std::time::{Duration, Instant};
+
+use fixture::{create_ws_connection, spawn_server};
+use futures_util::{SinkExt, StreamExt};
+use socketioxide::{extract::SocketRef, SocketIo};
+use tokio::net::TcpStream;
+use tokio_tungstenite::{tungstenite::Message, MaybeTlsStream, WebSocketStream};
+
+async fn next_msg(ws: &mut WebSocketStream<MaybeTlsStream<TcpStream>>) -> String {
+    let msg = tokio::time::timeout(Duration::from_secs(1), ws.next()).await;
+    match assert_ok!(msg) {
+        Some(Ok(Message::Text(msg))) => msg,
+        msg => panic!("unexpected message: {msg:?}"),
+    }
+}
+
+#[tokio::test]
+pub async fn write_batch_ordering_and_boundaries() {
+    const PORT: u16 = 12363;
+    const WINDOW: Duration = Duration::from_millis(200);
+    let (svc, io) = SocketIo::builder().write_batch(WINDOW, 3).build_svc();
+    io.ns("/", |socket: SocketRef| {
+        socket.on("burst", |socket: SocketRef| {
+            for i in 1..=5 {
+                socket.emit("msg", i).unwrap();
+            }
+        });
+    });
+    spawn_server(PORT, svc).await;
+
+    let mut ws = create_ws_connection(PORT).await;
+    assert!(next_msg(&mut ws).await.starts_with('0')); // engine.io open packet
+    assert!(next_msg(&mut ws).await.starts_with("40")); // socket.io connect packet
+
+    assert_ok!(ws.send(Message::Text("42[\"burst\"]".into())).await);
+    let start = Instant::now();
+    let mut received = Vec::new();
+    for _ in 0..5 {
+        received.push((next_msg(&mut ws).await, start.elapsed()));
+    }
+
+    // The ordering is preserved
+    let msgs: Vec<&str> = received.iter().map(|(msg, _)| msg.as_str()).collect();
+    assert_eq!(
+        msgs,
+        [
+            "42[\"msg\",1]",
+            "42[\"msg\",2]",
+            "42[\"msg\",3]",
+            "42[\"msg\",4]",
+            "42[\"msg\",5]"
+        ]
+    );
+    // The first batch is flushed as soon as it is full
+    assert!(received[2].1 < WINDOW / 2, "{:?}", received[2].1);
+    // The second batch is flushed as soon as the channel is drained
+    assert!(received[4].1 < WINDOW / 2, "{:?}", received[4].1);
+}
+
+#[tokio::test]
+pub async fn write_batch_idle_flush() {
+    const PORT: u16 = 12384;
+    const WINDOW: Duration = Duration::from_millis(500);
+    let (svc, io) = SocketIo::builder().write_batch(WINDOW, 10).build_svc();
+    io.ns("/", |socket: SocketRef| {
+        socket.on("single", |socket: SocketRef| {
+            socket.emit("msg", 1).unwrap();
+        });
+    });
+    spawn_server(PORT, svc).await;
+
+    let mut ws = create_ws_connection(PORT).await;
+    assert!(next_msg(&mut ws).await.starts_with('0')); // engine.io open packet
+    assert!(next_msg(&mut ws).await.starts_with("40")); // socket.io connect packet
+
+    assert_ok!(ws.send(Message::Text("42[\"single\"]".into())).await);
+    let start = Instant::now();
+    assert_eq!(next_msg(&mut ws).await, "42[\"msg\",1]");
+    // A lone packet on an idle socket is not delayed by the window
+    assert!(start.elapsed() < WINDOW / 5, "{:?}", start.elapsed());
+}